@@ -0,0 +1,337 @@
+mod png;
+mod svg;
+mod tikz;
+
+pub use png::*;
+pub use svg::*;
+pub use tikz::*;
+
+use core_lib::{
+  components::{freehand::*, markers::*, styles::*, symbolics::*, virtual_shapes::*},
+  math::*,
+  resources::{Theme, ToScreen, Viewport},
+  utilities::*,
+};
+use specs::prelude::*;
+
+static TICK_LENGTH: f64 = 10.0; // Pixel
+static TICK_SPACING: f64 = 4.0; // Pixel
+static CURVE_SEGMENTS: usize = 64; // Of the arcs and conics
+static LABEL_FONT_SIZE: f64 = 14.0; // Pixel
+
+/// What the figures are drawn with. The widths are half widths, as with the styles
+pub trait Painter {
+  fn stroke_polyline(&mut self, color: Color, radius: f64, points: &[Vector2]);
+
+  fn stroke_circle(&mut self, color: Color, radius: f64, center: Vector2, circle_radius: f64);
+
+  fn fill_polygon(&mut self, color: Color, vertices: &[Vector2]);
+
+  fn fill_disc(&mut self, color: Color, center: Vector2, radius: f64);
+
+  /// Text starting at the position, which is the bottom left corner of its first line
+  fn text(&mut self, text: &str, position: Vector2, color: Color, font_size: f64);
+}
+
+/// Draws the sketch as the viewport shows it, with the same layering as the apps: the polygons at
+/// the bottom, then the curves, the lines, the ink, the points and the texts on top. Nothing is
+/// shown selected
+pub fn paint<P: Painter>(world: &World, viewport: &Viewport, include_hidden: bool, painter: &mut P) {
+  let theme = *world.fetch::<Theme>();
+  let entities = world.entities();
+  let hiddens = world.read_storage::<Hidden>();
+  let shown = |ent: Entity| include_hidden || !hiddens.contains(ent);
+  let screen = AABB::new(0.0, 0.0, viewport.screen_width(), viewport.screen_height());
+
+  for (ent, virt_polygon, style) in (
+    &entities,
+    &world.read_storage::<VirtualPolygon>(),
+    &world.read_storage::<PolygonStyle>(),
+  )
+    .join()
+  {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let mut vertices = screen_points(&virt_polygon.vertices, viewport);
+      painter.fill_polygon(style.fill, &vertices);
+      if let Some(first) = vertices.first().copied() {
+        vertices.push(first);
+      }
+      painter.stroke_polyline(style.border.color, style.border.width, &vertices);
+    }
+  }
+
+  for (ent, virt_circle, style) in (
+    &entities,
+    &world.read_storage::<VirtualCircle>(),
+    &world.read_storage::<CircleStyle>(),
+  )
+    .join()
+  {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let ScreenCircle { center, radius } = virt_circle.to_screen(viewport);
+      painter.fill_disc(style.fill, center.0, radius.0);
+      painter.stroke_circle(style.border.color, style.border.width, center.0, radius.0);
+    }
+  }
+  for (ent, virt_arc, style) in (
+    &entities,
+    &world.read_storage::<VirtualArc>(),
+    &world.read_storage::<ArcStyle>(),
+  )
+    .join()
+  {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let arc: Arc = virt_arc.to_screen(viewport).into();
+      let points = (0..=CURVE_SEGMENTS)
+        .map(|i| arc.point_at_angle(arc.start + arc.sweep * i as f64 / CURVE_SEGMENTS as f64))
+        .collect::<Vec<_>>();
+      painter.stroke_polyline(style.color, style.width, &points);
+    }
+  }
+  for (ent, virt_conic, style) in (
+    &entities,
+    &world.read_storage::<VirtualConic>(),
+    &world.read_storage::<ConicStyle>(),
+  )
+    .join()
+  {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let ellipse: Ellipse = virt_conic.to_screen(viewport).into();
+      let points = (0..=CURVE_SEGMENTS)
+        .map(|i| ellipse.point_at(2.0 * std::f64::consts::PI * i as f64 / CURVE_SEGMENTS as f64))
+        .collect::<Vec<_>>();
+      painter.stroke_polyline(style.color, style.width, &points);
+    }
+  }
+
+  let line_styles = world.read_storage::<LineStyle>();
+  for (ent, virt_line, style) in (&entities, &world.read_storage::<VirtualLine>(), &line_styles).join() {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let line: Line = virt_line.to_screen(viewport).into();
+      if let Some((from, to)) = line.intersect(screen) {
+        for (dash_from, dash_to) in style.dash.dashes(from, to) {
+          painter.stroke_polyline(style.color, style.width, &[dash_from, dash_to]);
+        }
+        for (tick_from, tick_to) in line.tick_marks(style.marks.0, TICK_LENGTH, TICK_SPACING) {
+          painter.stroke_polyline(style.color, style.width, &[tick_from, tick_to]);
+        }
+      }
+    }
+  }
+  for (ent, virt_vector, style) in (
+    &entities,
+    &world.read_storage::<VirtualVector>(),
+    &world.read_storage::<VectorStyle>(),
+  )
+    .join()
+  {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let vector = virt_vector.to_screen(viewport);
+      painter.stroke_polyline(style.color, style.width, &[vector.from.0, vector.to.0]);
+      if let Some((side1, side2)) = vector.arrowhead(style.head_length) {
+        painter.stroke_polyline(style.color, style.width, &[side1.0, vector.to.0, side2.0]);
+      }
+    }
+  }
+
+  // Each piece of the ink is as wide as the pen was pressed, with a dot on every sample joining
+  // the pieces
+  for (ent, stroke, style) in (&entities, &world.read_storage::<FreehandStroke>(), &line_styles).join() {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let samples = stroke
+        .samples
+        .iter()
+        .map(|(position, pressure)| {
+          (
+            position.to_screen(viewport).0,
+            FreehandStroke::width_at(*pressure, style.width),
+          )
+        })
+        .collect::<Vec<_>>();
+      for (position, radius) in &samples {
+        painter.fill_disc(style.color, *position, *radius);
+      }
+      for pair in samples.windows(2) {
+        let ((from, r1), (to, r2)) = (pair[0], pair[1]);
+        painter.stroke_polyline(style.color, (r1 + r2) / 2.0, &[from, to]);
+      }
+    }
+  }
+
+  // The marker is drawn as a border with a smaller one on top, like the apps do
+  let scrn_points = (
+    &entities,
+    &world.read_storage::<VirtualPoint>(),
+    &world.read_storage::<PointStyle>(),
+    &world.read_storage::<SymbolicPoint>(),
+  )
+    .join()
+    .filter(|(ent, _, _, _)| shown(*ent))
+    .map(|(ent, virt_point, style, sym_point)| (ent, virt_point.to_screen(viewport).0, style.resolve_fill(sym_point)))
+    .collect::<Vec<_>>();
+  for (_, center, style) in &scrn_points {
+    let style = style.flatten_alpha();
+    let center_color = match (style.marker_shape, style.fill) {
+      (MarkerShape::Cross, _) | (MarkerShape::Plus, _) => style.color,
+      (MarkerShape::HollowCircle, _) | (_, PointFill::Hollow) => theme.background,
+      _ => style.color,
+    };
+    for primitive in style.marker_shape.primitives(*center, style.radius) {
+      paint_marker_primitive(&primitive, style.border_color, 1.5, painter);
+    }
+    for primitive in style.marker_shape.primitives(*center, style.radius - 1.5) {
+      paint_marker_primitive(&primitive, center_color, 0.75, painter);
+    }
+  }
+
+  // The labels go over the points they name, texts go along with them
+  let labels = world.read_storage::<Label>();
+  for (ent, center, _) in &scrn_points {
+    if let Some(label) = labels.get(*ent) {
+      painter.text(&label.text, *center + label.offset, theme.line, LABEL_FONT_SIZE);
+    }
+  }
+  for (ent, virt_text, style) in (
+    &entities,
+    &world.read_storage::<VirtualText>(),
+    &world.read_storage::<TextStyle>(),
+  )
+    .join()
+  {
+    if shown(ent) {
+      let style = style.flatten_alpha();
+      let text = virt_text.clone().to_screen(viewport);
+      painter.text(&text.text, text.position.0, style.color, style.font_size);
+    }
+  }
+}
+
+/// The corners of the box around the points, curves, texts and ink of the sketch, in sketch
+/// units. None for an empty sketch
+pub fn sketch_bounds(world: &World, include_hidden: bool) -> Option<(Vector2, Vector2)> {
+  let entities = world.entities();
+  let hiddens = world.read_storage::<Hidden>();
+  let shown = |ent: Entity| include_hidden || !hiddens.contains(ent);
+
+  let mut corners = vec![];
+  for (ent, virt_point) in (&entities, &world.read_storage::<VirtualPoint>()).join() {
+    if shown(ent) {
+      corners.push(virt_point.0);
+    }
+  }
+  for (ent, virt_circle) in (&entities, &world.read_storage::<VirtualCircle>()).join() {
+    if shown(ent) {
+      let r = virt_circle.radius.0;
+      corners.push(virt_circle.center.0 - vec2![r, r]);
+      corners.push(virt_circle.center.0 + vec2![r, r]);
+    }
+  }
+  for (ent, virt_conic) in (&entities, &world.read_storage::<VirtualConic>()).join() {
+    if shown(ent) {
+      let r = virt_conic.rx.0.max(virt_conic.ry.0);
+      corners.push(virt_conic.center.0 - vec2![r, r]);
+      corners.push(virt_conic.center.0 + vec2![r, r]);
+    }
+  }
+  for (ent, virt_text) in (&entities, &world.read_storage::<VirtualText>()).join() {
+    if shown(ent) {
+      corners.push(virt_text.position.0);
+    }
+  }
+  for (ent, stroke) in (&entities, &world.read_storage::<FreehandStroke>()).join() {
+    if shown(ent) {
+      corners.extend(stroke.samples.iter().map(|(position, _)| position.0));
+    }
+  }
+
+  let first = *corners.first()?;
+  Some(corners.iter().fold((first, first), |(min, max), corner| {
+    (
+      vec2![min.x.min(corner.x), min.y.min(corner.y)],
+      vec2![max.x.max(corner.x), max.y.max(corner.y)],
+    )
+  }))
+}
+
+fn screen_points(positions: &[VirtualPosition], viewport: &Viewport) -> Vec<Vector2> {
+  positions
+    .iter()
+    .map(|position| position.to_screen(viewport).0)
+    .collect()
+}
+
+fn paint_marker_primitive<P: Painter>(primitive: &MarkerPrimitive, color: Color, stroke_radius: f64, painter: &mut P) {
+  match primitive {
+    MarkerPrimitive::Disc(center, radius) | MarkerPrimitive::Ring(center, radius) => {
+      painter.fill_disc(color, *center, *radius)
+    }
+    MarkerPrimitive::Polygon(vertices) => painter.fill_polygon(color, vertices),
+    MarkerPrimitive::Segment(from, to) => painter.stroke_polyline(color, stroke_radius, &[*from, *to]),
+  }
+}
+
+/// The color as a hex triplet, its alpha apart
+fn hex(color: Color) -> String {
+  let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+  format!(
+    "#{:02x}{:02x}{:02x}",
+    channel(color.r),
+    channel(color.g),
+    channel(color.b)
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use core_lib::{
+    components::{markers::*, symbolics::*},
+    events::*,
+    math::*,
+    resources::{Theme, Viewport},
+    setup_core_lib,
+    test_utils::*,
+  };
+  use specs::prelude::*;
+
+  /// A segment from (0, 0) to (4, 3) and a hidden point at (8, 8), in the theme
+  pub fn sketch(theme: Theme) -> World {
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+    step(&mut world, &mut dispatcher, Command::SetTheme(theme));
+    let mut point_at = |world: &mut World, x: f64, y: f64| {
+      step(
+        world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![x, y])),
+      );
+      last_inserted::<SymbolicPoint>(world)
+    };
+    let from = point_at(&mut world, 0., 0.);
+    let to = point_at(&mut world, 4., 3.);
+    let hidden = point_at(&mut world, 8., 8.);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(from, to))),
+    );
+    step(&mut world, &mut dispatcher, Command::Hide(HideEvent::Hide(hidden)));
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    assert!(world.read_storage::<Hidden>().contains(hidden));
+    world
+  }
+
+  /// Shows from (-1, -1) to (9, 9) on 200 x 200 pixels
+  pub fn viewport() -> Viewport {
+    Viewport::new(vec2![4., 4.], vec2![10., 10.], vec2![200., 200.])
+  }
+}
@@ -0,0 +1,129 @@
+use super::{paint, Painter};
+use core_lib::{
+  math::*,
+  resources::{Theme, Viewport},
+};
+use specs::prelude::*;
+use tiny_skia::{FillRule, LineJoin, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+/// The sketch as a PNG image the size of the viewport, on the background of the theme. There is
+/// no font to draw the texts with, they are left out
+pub fn export_png(world: &World, viewport: &Viewport, include_hidden: bool) -> Result<Vec<u8>, String> {
+  let (width, height) = (viewport.screen_width().round(), viewport.screen_height().round());
+  let mut pixmap =
+    Pixmap::new(width as u32, height as u32).ok_or(format!("Cannot make an image of {} x {}", width, height))?;
+  pixmap.fill(skia_color(world.fetch::<Theme>().background));
+  let mut painter = PngPainter { pixmap };
+  paint(world, viewport, include_hidden, &mut painter);
+  painter.pixmap.encode_png().map_err(|err| err.to_string())
+}
+
+struct PngPainter {
+  pixmap: Pixmap,
+}
+
+impl PngPainter {
+  fn paint(color: Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(skia_color(color));
+    paint.anti_alias = true;
+    paint
+  }
+}
+
+impl Painter for PngPainter {
+  fn stroke_polyline(&mut self, color: Color, radius: f64, points: &[Vector2]) {
+    if radius <= 0.0 {
+      return;
+    }
+    if let Some(path) = polyline(points, false) {
+      let stroke = Stroke {
+        width: (radius * 2.0) as f32,
+        line_join: LineJoin::Round,
+        ..Stroke::default()
+      };
+      self
+        .pixmap
+        .stroke_path(&path, &Self::paint(color), &stroke, Transform::identity(), None);
+    }
+  }
+
+  fn stroke_circle(&mut self, color: Color, radius: f64, center: Vector2, circle_radius: f64) {
+    if radius <= 0.0 {
+      return;
+    }
+    if let Some(path) = PathBuilder::from_circle(center.x as f32, center.y as f32, circle_radius as f32) {
+      let stroke = Stroke {
+        width: (radius * 2.0) as f32,
+        ..Stroke::default()
+      };
+      self
+        .pixmap
+        .stroke_path(&path, &Self::paint(color), &stroke, Transform::identity(), None);
+    }
+  }
+
+  fn fill_polygon(&mut self, color: Color, vertices: &[Vector2]) {
+    if let Some(path) = polyline(vertices, true) {
+      self.pixmap.fill_path(
+        &path,
+        &Self::paint(color),
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+      );
+    }
+  }
+
+  fn fill_disc(&mut self, color: Color, center: Vector2, radius: f64) {
+    if let Some(path) = PathBuilder::from_circle(center.x as f32, center.y as f32, radius as f32) {
+      self.pixmap.fill_path(
+        &path,
+        &Self::paint(color),
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+      );
+    }
+  }
+
+  fn text(&mut self, _: &str, _: Vector2, _: Color, _: f64) {}
+}
+
+fn polyline(points: &[Vector2], closed: bool) -> Option<tiny_skia::Path> {
+  let (first, rest) = points.split_first()?;
+  let mut builder = PathBuilder::new();
+  builder.move_to(first.x as f32, first.y as f32);
+  for point in rest {
+    builder.line_to(point.x as f32, point.y as f32);
+  }
+  if closed {
+    builder.close();
+  }
+  builder.finish()
+}
+
+fn skia_color(color: Color) -> tiny_skia::Color {
+  let channel = |value: f32| value.clamp(0.0, 1.0);
+  tiny_skia::Color::from_rgba(channel(color.r), channel(color.g), channel(color.b), channel(color.a))
+    .unwrap_or(tiny_skia::Color::BLACK)
+}
+
+#[cfg(test)]
+mod test {
+  use super::{super::test::*, *};
+
+  #[test]
+  fn test_export_png() {
+    let world = sketch(Theme::dark());
+    let bytes = export_png(&world, &viewport(), false).unwrap();
+    let pixmap = Pixmap::decode_png(&bytes).unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (200, 200));
+    let corner = pixmap.pixel(0, 0).unwrap().demultiply();
+    let background = skia_color(Theme::dark().background).to_color_u8();
+    assert_eq!(
+      (corner.red(), corner.green(), corner.blue()),
+      (background.red(), background.green(), background.blue())
+    );
+  }
+}
@@ -0,0 +1,133 @@
+use super::{hex, paint, Painter};
+use core_lib::{
+  math::*,
+  resources::{Theme, Viewport},
+};
+use specs::prelude::*;
+use std::fmt::Write;
+
+/// The sketch as an SVG document the size of the viewport, on the background of the theme
+pub fn export_svg(world: &World, viewport: &Viewport, include_hidden: bool) -> String {
+  let (width, height) = (viewport.screen_width(), viewport.screen_height());
+  let mut painter = SvgPainter { body: String::new() };
+  paint(world, viewport, include_hidden, &mut painter);
+  format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.2} {h:.2}\">\n\
+     <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n{}</svg>\n",
+    hex(world.fetch::<Theme>().background),
+    painter.body,
+    w = width,
+    h = height,
+  )
+}
+
+struct SvgPainter {
+  body: String,
+}
+
+impl Painter for SvgPainter {
+  fn stroke_polyline(&mut self, color: Color, radius: f64, points: &[Vector2]) {
+    if points.len() >= 2 && radius > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-width=\"{:.2}\" stroke-linejoin=\"round\"/>",
+        coordinates(points),
+        hex(color),
+        color.a,
+        radius * 2.0
+      );
+    }
+  }
+
+  fn stroke_circle(&mut self, color: Color, radius: f64, center: Vector2, circle_radius: f64) {
+    if circle_radius > 0.0 && radius > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-width=\"{:.2}\"/>",
+        center.x,
+        center.y,
+        circle_radius,
+        hex(color),
+        color.a,
+        radius * 2.0
+      );
+    }
+  }
+
+  fn fill_polygon(&mut self, color: Color, vertices: &[Vector2]) {
+    if vertices.len() >= 3 && color.a > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+        coordinates(vertices),
+        hex(color),
+        color.a
+      );
+    }
+  }
+
+  fn fill_disc(&mut self, color: Color, center: Vector2, radius: f64) {
+    if radius > 0.0 && color.a > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+        center.x,
+        center.y,
+        radius,
+        hex(color),
+        color.a
+      );
+    }
+  }
+
+  fn text(&mut self, text: &str, position: Vector2, color: Color, font_size: f64) {
+    let _ = writeln!(
+      self.body,
+      "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{:.2}\" fill=\"{}\" fill-opacity=\"{}\">{}</text>",
+      position.x,
+      position.y,
+      font_size,
+      hex(color),
+      color.a,
+      escape(text)
+    );
+  }
+}
+
+fn coordinates(points: &[Vector2]) -> String {
+  points
+    .iter()
+    .map(|point| format!("{:.2},{:.2}", point.x, point.y))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+  use super::{super::test::*, *};
+
+  #[test]
+  fn test_export_svg() {
+    let world = sketch(Theme::dark());
+    let svg = export_svg(&world, &viewport(), false);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"200\""));
+    let background = format!(
+      "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+      hex(Theme::dark().background)
+    );
+    assert!(svg.contains(&background));
+    assert_eq!(svg.matches("<polyline").count(), 1);
+
+    // The hidden point is only drawn when asked for
+    let discs = |svg: &str| svg.matches("<circle").count();
+    assert!(discs(&export_svg(&world, &viewport(), true)) > discs(&svg));
+  }
+}
@@ -0,0 +1,141 @@
+use super::{paint, Painter};
+use core_lib::{math::*, resources::Viewport};
+use specs::prelude::*;
+use std::fmt::Write;
+
+/// The sketch as a TikZ picture, one point of TeX for a pixel of the viewport. The y axis goes
+/// down as on screen, and the picture is clipped to the viewport
+pub fn export_tikz(world: &World, viewport: &Viewport, include_hidden: bool) -> String {
+  let mut painter = TikzPainter { body: String::new() };
+  paint(world, viewport, include_hidden, &mut painter);
+  format!(
+    "\\begin{{tikzpicture}}[x=1pt, y=-1pt]\n\\clip (0, 0) rectangle ({:.2}, {:.2});\n{}\\end{{tikzpicture}}\n",
+    viewport.screen_width(),
+    viewport.screen_height(),
+    painter.body
+  )
+}
+
+struct TikzPainter {
+  body: String,
+}
+
+impl Painter for TikzPainter {
+  fn stroke_polyline(&mut self, color: Color, radius: f64, points: &[Vector2]) {
+    if points.len() >= 2 && radius > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "\\draw[{}, line width={:.2}pt, line join=round] {};",
+        color_option("draw", color),
+        radius * 2.0,
+        path(points, " -- ")
+      );
+    }
+  }
+
+  fn stroke_circle(&mut self, color: Color, radius: f64, center: Vector2, circle_radius: f64) {
+    if circle_radius > 0.0 && radius > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "\\draw[{}, line width={:.2}pt] ({:.2}, {:.2}) circle[radius={:.2}];",
+        color_option("draw", color),
+        radius * 2.0,
+        center.x,
+        center.y,
+        circle_radius
+      );
+    }
+  }
+
+  fn fill_polygon(&mut self, color: Color, vertices: &[Vector2]) {
+    if vertices.len() >= 3 && color.a > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "\\fill[{}] {} -- cycle;",
+        color_option("fill", color),
+        path(vertices, " -- ")
+      );
+    }
+  }
+
+  fn fill_disc(&mut self, color: Color, center: Vector2, radius: f64) {
+    if radius > 0.0 && color.a > 0.0 {
+      let _ = writeln!(
+        self.body,
+        "\\fill[{}] ({:.2}, {:.2}) circle[radius={:.2}];",
+        color_option("fill", color),
+        center.x,
+        center.y,
+        radius
+      );
+    }
+  }
+
+  fn text(&mut self, text: &str, position: Vector2, color: Color, font_size: f64) {
+    let _ = writeln!(
+      self.body,
+      "\\node[anchor=base west, inner sep=0pt, {}, font=\\fontsize{{{:.1}pt}}{{{:.1}pt}}\\selectfont] at ({:.2}, {:.2}) {{{}}};",
+      color_option("text", color),
+      font_size,
+      font_size * 1.2,
+      position.x,
+      position.y,
+      escape(text)
+    );
+  }
+}
+
+/// The color of `key` in the xcolor syntax, along with its opacity when translucent
+fn color_option(key: &str, color: Color) -> String {
+  let rgb = format!(
+    "{}={{rgb,1:red,{:.3};green,{:.3};blue,{:.3}}}",
+    key, color.r, color.g, color.b
+  );
+  let opacity_key = match key {
+    "fill" => "fill opacity",
+    "text" => "text opacity",
+    _ => "draw opacity",
+  };
+  if color.a < 1.0 {
+    format!("{}, {}={:.3}", rgb, opacity_key, color.a)
+  } else {
+    rgb
+  }
+}
+
+fn path(points: &[Vector2], separator: &str) -> String {
+  points
+    .iter()
+    .map(|point| format!("({:.2}, {:.2})", point.x, point.y))
+    .collect::<Vec<_>>()
+    .join(separator)
+}
+
+fn escape(text: &str) -> String {
+  text
+    .chars()
+    .map(|c| match c {
+      '\\' => "\\textbackslash{}".to_string(),
+      '~' => "\\textasciitilde{}".to_string(),
+      '^' => "\\textasciicircum{}".to_string(),
+      '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+      c => c.to_string(),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::{super::test::*, *};
+  use core_lib::resources::Theme;
+
+  #[test]
+  fn test_export_tikz() {
+    let world = sketch(Theme::light());
+    let tikz = export_tikz(&world, &viewport(), false);
+    assert!(tikz.starts_with("\\begin{tikzpicture}[x=1pt, y=-1pt]\n\\clip (0, 0) rectangle (200.00, 200.00);\n"));
+    assert!(tikz.ends_with("\\end{tikzpicture}\n"));
+    // The segment goes from (0, 0) to (4, 3), 20 pixels a unit from (-1, 9) at the top left
+    assert!(tikz.contains("(20.00, 180.00) -- (100.00, 120.00)"));
+  }
+}
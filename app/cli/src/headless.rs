@@ -0,0 +1,65 @@
+use core_lib::{components::virtual_shapes::*, events::*, resources::Theme, setup_core_lib};
+use specs::prelude::*;
+use std::path::Path;
+
+static MAX_FRAMES: usize = 1000; // The solvers are given up on after as many frames
+
+/// Loads the sketch in a world of the core library alone, without a window, then dispatches until
+/// no more commands are sent and the points stay where they are. The errors found solving the
+/// sketch are only reported, the figure is still made. The theme, when given, is set once the
+/// sketch is loaded so that the colors following the theme change with it
+pub fn load_sketch(path: &Path, theme: Option<Theme>) -> Result<World, String> {
+  let mut world = World::new();
+  let mut builder = DispatcherBuilder::new();
+  setup_core_lib(&mut builder);
+  let mut dispatcher = builder.build();
+  dispatcher.setup(&mut world);
+
+  let mut command_reader = world.fetch_mut::<CommandEventChannel>().register_reader();
+  let mut error_reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+  world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+    command: Command::LoadSketch(path.to_path_buf()),
+    event_id: None,
+  });
+
+  let mut last_positions = None;
+  let mut converged = false;
+  for _ in 0..MAX_FRAMES {
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    for event in world.fetch::<ErrorEventChannel>().read(&mut error_reader) {
+      match event {
+        ErrorEvent::SketchFile(err) => return Err(format!("{:?}", err)),
+        err => eprintln!("Warning: {:?}", err),
+      }
+    }
+    let commands_sent = world.fetch::<CommandEventChannel>().read(&mut command_reader).count();
+    let positions = point_positions(&world);
+    if commands_sent == 0 && last_positions.as_ref() == Some(&positions) {
+      converged = true;
+      break;
+    }
+    last_positions = Some(positions);
+  }
+  if !converged {
+    eprintln!("Warning: the sketch did not settle after {} frames", MAX_FRAMES);
+  }
+  if let Some(theme) = theme {
+    world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+      command: Command::SetTheme(theme),
+      event_id: None,
+    });
+    dispatcher.dispatch(&world);
+    world.maintain();
+  }
+  Ok(world)
+}
+
+/// Every solved point, in the order of their entities
+fn point_positions(world: &World) -> Vec<(u32, VirtualPoint)> {
+  (&world.entities(), &world.read_storage::<VirtualPoint>())
+    .join()
+    .map(|(ent, virt_point)| (ent.id(), *virt_point))
+    .collect()
+}
@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate core_lib;
+extern crate specs;
+extern crate tiny_skia;
+
+mod export;
+mod headless;
+mod options;
+
+pub use export::*;
+pub use headless::*;
+pub use options::*;
@@ -0,0 +1,42 @@
+// Core crates
+extern crate core_lib;
+
+// Command line library loading and exporting the sketches
+extern crate geopad_cli;
+
+use geopad_cli::*;
+use std::{fs, process};
+
+fn main() {
+  let options = match Options::parse(std::env::args().skip(1)) {
+    Ok(Some(options)) => options,
+    Ok(None) => {
+      println!("{}", USAGE);
+      return;
+    }
+    Err(err) => {
+      eprintln!("{}\n\n{}", err, USAGE);
+      process::exit(2);
+    }
+  };
+
+  // Load the sketch without a window and let the solvers settle
+  let world = match load_sketch(&options.input, options.theme) {
+    Ok(world) => world,
+    Err(err) => {
+      eprintln!("Cannot load {}: {}", options.input.display(), err);
+      process::exit(1);
+    }
+  };
+
+  let viewport = options.viewport(&world);
+  let bytes = match options.format {
+    Format::Svg => Ok(export_svg(&world, &viewport, options.include_hidden).into_bytes()),
+    Format::Tikz => Ok(export_tikz(&world, &viewport, options.include_hidden).into_bytes()),
+    Format::Png => export_png(&world, &viewport, options.include_hidden),
+  };
+  if let Err(err) = bytes.and_then(|bytes| fs::write(&options.output, bytes).map_err(|err| err.to_string())) {
+    eprintln!("Cannot export to {}: {}", options.output.display(), err);
+    process::exit(1);
+  }
+}
@@ -0,0 +1,198 @@
+use crate::export::sketch_bounds;
+use core_lib::{
+  math::*,
+  resources::{Theme, Viewport},
+};
+use specs::prelude::*;
+use std::path::{Path, PathBuf};
+
+static DEFAULT_WIDTH: f64 = 960.0; // Pixel
+static MARGIN: f64 = 0.05; // Share of the sketch left around it when fitting the viewport
+
+pub static USAGE: &str = "Usage: geopad-cli <sketch> -o <output> [options]
+
+Loads a sketch saved by Geometry Sketchpad and exports it as a figure.
+
+Options:
+  -o, --output <path>        File to write, its extension tells the format when --format is not given
+  --format <svg|png|tikz>    Format of the figure
+  --viewport <x0,y0,x1,y1>   Region of the sketch to export, in sketch units. The whole sketch by default
+  --width <pixels>           Width of the figure, 960 by default. The height follows the region
+  --scale <pixels>           Pixels per sketch unit, instead of the width
+  --include-hidden           Also draw the hidden geometries
+  --theme <light|dark>       Colors of the figure, light by default
+  -h, --help                 Show this message
+
+Texts and labels are left out of the PNG figures.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  Svg,
+  Png,
+  Tikz,
+}
+
+impl Format {
+  fn from_name(name: &str) -> Option<Self> {
+    match name.to_lowercase().as_str() {
+      "svg" => Some(Format::Svg),
+      "png" => Some(Format::Png),
+      "tikz" | "tex" => Some(Format::Tikz),
+      _ => None,
+    }
+  }
+
+  fn from_extension(path: &Path) -> Option<Self> {
+    path.extension().and_then(|ext| ext.to_str()).and_then(Self::from_name)
+  }
+}
+
+/// What to export and how, from the command line arguments
+#[derive(Debug, Clone)]
+pub struct Options {
+  pub input: PathBuf,
+  pub output: PathBuf,
+  pub format: Format,
+  pub region: Option<(Vector2, Vector2)>, // Lower left and upper right corners, in sketch units
+  pub width: f64,                         // Pixel
+  pub scale: Option<f64>,                 // Pixels per sketch unit, overrides the width
+  pub include_hidden: bool,
+  pub theme: Option<Theme>, // The one of the sketch when not given
+}
+
+impl Options {
+  /// The options given by the arguments, the program name left out. None when asking for help
+  pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Self>, String> {
+    let (mut input, mut output, mut format, mut region) = (None, None, None, None);
+    let (mut width, mut scale, mut include_hidden, mut theme) = (DEFAULT_WIDTH, None, false, None);
+    while let Some(arg) = args.next() {
+      let mut value = |flag: &str| args.next().ok_or(format!("Missing the value of {}", flag));
+      match arg.as_str() {
+        "-h" | "--help" => return Ok(None),
+        "-o" | "--output" => output = Some(PathBuf::from(value(&arg)?)),
+        "--format" => {
+          let name = value(&arg)?;
+          format = Some(Format::from_name(&name).ok_or(format!("Unknown format {}", name))?);
+        }
+        "--viewport" => region = Some(parse_region(&value(&arg)?)?),
+        "--width" => width = parse_positive(&arg, &value(&arg)?)?,
+        "--scale" => scale = Some(parse_positive(&arg, &value(&arg)?)?),
+        "--include-hidden" => include_hidden = true,
+        "--theme" => {
+          let name = value(&arg)?;
+          theme = Some(match name.to_lowercase().as_str() {
+            "light" => Theme::light(),
+            "dark" => Theme::dark(),
+            _ => return Err(format!("Unknown theme {}", name)),
+          });
+        }
+        _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+        _ if input.is_none() => input = Some(PathBuf::from(arg)),
+        _ => return Err(format!("Unexpected argument {}", arg)),
+      }
+    }
+
+    let input = input.ok_or("Missing the sketch to export")?;
+    let output: PathBuf = output.ok_or("Missing the output file")?;
+    let format = match format.or_else(|| Format::from_extension(&output)) {
+      Some(format) => format,
+      None => return Err(format!("Cannot tell the format of {}, use --format", output.display())),
+    };
+    Ok(Some(Self {
+      input,
+      output,
+      format,
+      region,
+      width,
+      scale,
+      include_hidden,
+      theme,
+    }))
+  }
+
+  /// The viewport showing the region, or the whole sketch with a margin around it. The sketch
+  /// units keep the same size in both directions
+  pub fn viewport(&self, world: &World) -> Viewport {
+    let (min, max) = match self.region {
+      Some(region) => region,
+      None => match sketch_bounds(world, self.include_hidden) {
+        Some((min, max)) => {
+          // A single point or a flat sketch still gets some room around it
+          let size = (max.x - min.x).max(max.y - min.y).max(1.0);
+          let margin = vec2![size, size] * MARGIN;
+          (min - margin, max + margin)
+        }
+        None => {
+          let viewport = Viewport::default();
+          (
+            vec2![viewport.x_min(), viewport.y_min()],
+            vec2![viewport.x_max(), viewport.y_max()],
+          )
+        }
+      },
+    };
+    let size = max - min;
+    let width = match self.scale {
+      Some(scale) => size.x * scale,
+      None => self.width,
+    };
+    Viewport::new((min + max) / 2.0, size, vec2![width, width * size.y / size.x])
+  }
+}
+
+fn parse_positive(flag: &str, value: &str) -> Result<f64, String> {
+  match value.parse::<f64>() {
+    Ok(number) if number > 0.0 && number.is_finite() => Ok(number),
+    _ => Err(format!("{} takes a positive number, not {}", flag, value)),
+  }
+}
+
+fn parse_region(value: &str) -> Result<(Vector2, Vector2), String> {
+  let numbers = value
+    .split(',')
+    .map(|number| number.trim().parse::<f64>())
+    .collect::<Result<Vec<_>, _>>();
+  match numbers.as_deref() {
+    Ok([x0, y0, x1, y1]) if x0 < x1 && y0 < y1 => Ok((vec2![*x0, *y0], vec2![*x1, *y1])),
+    _ => Err(format!(
+      "--viewport takes x0,y0,x1,y1 with x0 < x1 and y0 < y1, not {}",
+      value
+    )),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn parse(args: &str) -> Result<Option<Options>, String> {
+    Options::parse(args.split_whitespace().map(String::from))
+  }
+
+  #[test]
+  fn test_parse_options() {
+    let options = parse("sketch.json -o figure.svg --viewport 0,0,4,3 --scale 10 --include-hidden --theme dark")
+      .unwrap()
+      .unwrap();
+    assert_eq!(options.input, PathBuf::from("sketch.json"));
+    assert_eq!(options.format, Format::Svg);
+    assert_eq!(options.region, Some((vec2![0., 0.], vec2![4., 3.])));
+    assert!(options.include_hidden);
+    assert_eq!(options.theme, Some(Theme::dark()));
+    let viewport = options.viewport(&World::new());
+    assert_eq!(viewport.screen_size, vec2![40., 30.]);
+
+    let options = parse("sketch.json --output figure.pdf --format tex").unwrap().unwrap();
+    assert_eq!(
+      (options.format, options.width, options.theme),
+      (Format::Tikz, DEFAULT_WIDTH, None)
+    );
+
+    assert!(parse("sketch.json -o figure.svg --help").unwrap().is_none());
+    assert!(parse("sketch.json -o figure.pdf").is_err());
+    assert!(parse("sketch.json -o figure.svg --theme sepia").is_err());
+    assert!(parse("sketch.json -o figure.svg --viewport 4,0,0,3").is_err());
+    assert!(parse("sketch.json -o figure.svg --width -3").is_err());
+    assert!(parse("-o figure.svg").is_err());
+  }
+}
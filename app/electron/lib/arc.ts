@@ -0,0 +1,56 @@
+import { Arc as ArcData, ArcStyle } from "../native";
+import * as PIXI from "pixi.js";
+
+export default class Arc {
+
+  arc: ArcData;
+  style: ArcStyle;
+  selected: boolean;
+  graphics: PIXI.Graphics;
+
+  constructor(arc: ArcData, style: ArcStyle) {
+
+    // Basic information
+    this.arc = arc;
+    this.style = style;
+    this.selected = false;
+
+    // Render information
+    this.graphics = new PIXI.Graphics();
+    this.setupGraphicsStyle();
+  }
+
+  updateArc(arc: ArcData) {
+    this.arc = arc;
+    this.setupGraphicsStyle();
+  }
+
+  updateStyle(style: ArcStyle) {
+    this.style = style;
+    this.setupGraphicsStyle();
+  }
+
+  setSelected(selected: boolean) {
+    this.selected = selected;
+    this.setupGraphicsStyle();
+  }
+
+  drawArc(radius: number) {
+    const { center, start, sweep } = this.arc;
+    this.graphics.moveTo(center.x + Math.cos(start) * radius, center.y + Math.sin(start) * radius);
+    this.graphics.arc(center.x, center.y, radius, start, start + sweep);
+  }
+
+  setupGraphicsStyle() {
+    this.graphics.clear();
+    this.graphics.lineStyle(this.style.width, this.style.color, this.style.alpha);
+    this.drawArc(this.arc.radius);
+
+    if (this.selected) {
+      let offset = this.style.width / 2 + 3;
+      this.graphics.lineStyle(1, 0xff00ff);
+      this.drawArc(this.arc.radius - offset);
+      this.drawArc(this.arc.radius + offset);
+    }
+  }
+}
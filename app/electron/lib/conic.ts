@@ -0,0 +1,55 @@
+import { Conic as ConicData, ConicStyle } from "../native";
+import * as PIXI from "pixi.js";
+
+export default class Conic {
+
+  conic: ConicData;
+  style: ConicStyle;
+  selected: boolean;
+  graphics: PIXI.Graphics;
+
+  constructor(conic: ConicData, style: ConicStyle) {
+
+    // Basic information
+    this.conic = conic;
+    this.style = style;
+    this.selected = false;
+
+    // Render information
+    this.graphics = new PIXI.Graphics();
+    this.setupGraphicsStyle();
+  }
+
+  updateConic(conic: ConicData) {
+    this.conic = conic;
+    this.setupGraphicsStyle();
+  }
+
+  updateStyle(style: ConicStyle) {
+    this.style = style;
+    this.setupGraphicsStyle();
+  }
+
+  setSelected(selected: boolean) {
+    this.selected = selected;
+    this.setupGraphicsStyle();
+  }
+
+  setupGraphicsStyle() {
+    const { center, rx, ry, rotation } = this.conic;
+
+    // The ellipse is drawn around the origin of the graphics, which is moved and turned instead
+    this.graphics.clear();
+    this.graphics.position.set(center.x, center.y);
+    this.graphics.rotation = rotation;
+    this.graphics.lineStyle(this.style.width, this.style.color, this.style.alpha);
+    this.graphics.drawEllipse(0, 0, rx, ry);
+
+    if (this.selected) {
+      let offset = this.style.width / 2 + 3;
+      this.graphics.lineStyle(1, 0xff00ff);
+      this.graphics.drawEllipse(0, 0, Math.max(rx - offset, 0), Math.max(ry - offset, 0));
+      this.graphics.drawEllipse(0, 0, rx + offset, ry + offset);
+    }
+  }
+}
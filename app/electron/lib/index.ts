@@ -9,7 +9,13 @@ import "pixi-layers";
 import Point from "./point";
 import Line from "./line";
 import Circle from "./circle";
+import Arc from "./arc";
+import Conic from "./conic";
 import Rectangle from "./rectangle";
+import Polygon from "./polygon";
+import Vector from "./vector";
+import Label from "./label";
+import Text from "./text";
 
 type RustChannel = Geopad.GeopadWorld;
 const RustChannel = Geopad.GeopadWorld;
@@ -29,11 +35,20 @@ export default class GeopadWorld {
   lineGroup: PIXI.display.Group;
   circleGroup: PIXI.display.Group;
   rectangleGroup: PIXI.display.Group;
+  polygonGroup: PIXI.display.Group;
+  backgroundGroup: PIXI.display.Group;
+  labelGroup: PIXI.display.Group;
 
   points: Storage<Point>;
   lines: Storage<Line>;
   circles: Storage<Circle>;
+  arcs: Storage<Arc>;
+  conics: Storage<Conic>;
   rectangles: Storage<Rectangle>;
+  polygons: Storage<Polygon>;
+  vectors: Storage<Vector>;
+  labels: Storage<Label>;
+  texts: Storage<Text>;
 
   constructor($canvas: JQuery<HTMLElement>) {
     this.$canvas = $canvas;
@@ -49,18 +64,24 @@ export default class GeopadWorld {
     this.app.renderer.autoResize = true;
 
     // Create the groups
+    this.labelGroup = new PIXI.display.Group(5, false);
     this.rectangleGroup = new PIXI.display.Group(4, false);
     this.pointGroup = new PIXI.display.Group(3, false);
     this.lineGroup = new PIXI.display.Group(2, false);
     this.circleGroup = new PIXI.display.Group(1, false);
+    this.polygonGroup = new PIXI.display.Group(0, false);
+    this.backgroundGroup = new PIXI.display.Group(-1, false);
 
     // Setup stages
     this.app.stage = new PIXI.display.Stage();
     this.app.stage.sortableChildren = true;
+    this.app.stage.addChild(new PIXI.display.Layer(this.labelGroup));
     this.app.stage.addChild(new PIXI.display.Layer(this.rectangleGroup));
     this.app.stage.addChild(new PIXI.display.Layer(this.pointGroup));
     this.app.stage.addChild(new PIXI.display.Layer(this.lineGroup));
     this.app.stage.addChild(new PIXI.display.Layer(this.circleGroup));
+    this.app.stage.addChild(new PIXI.display.Layer(this.polygonGroup));
+    this.app.stage.addChild(new PIXI.display.Layer(this.backgroundGroup));
 
     // Setup canvas
     $canvas[0].appendChild(this.app.view);
@@ -73,7 +94,13 @@ export default class GeopadWorld {
     this.points = {};
     this.lines = {};
     this.circles = {};
+    this.arcs = {};
+    this.conics = {};
     this.rectangles = {};
+    this.polygons = {};
+    this.vectors = {};
+    this.labels = {};
+    this.texts = {};
 
     const poll = promisify(this.channel.poll.bind(this.channel));
 
@@ -106,6 +133,16 @@ export default class GeopadWorld {
       this.channel.onMouseMove(x, y, relX, relY);
     });
 
+    // Browsers report a pinch on a trackpad as a wheel event with the control key
+    this.$canvas[0].addEventListener("wheel", (event) => {
+      event.preventDefault();
+      if (event.ctrlKey) {
+        this.channel.onPinch(-event.deltaY / 100);
+      } else {
+        this.channel.onMouseScroll(event.deltaX / 100, -event.deltaY / 100);
+      }
+    }, { passive: false });
+
     $(document).keydown((event) => {
       let key = event.which;
       this.channel.onKeyDown(key);
@@ -115,6 +152,11 @@ export default class GeopadWorld {
       let key = event.which;
       this.channel.onKeyUp(key);
     });
+
+    // Characters typed into texts, keys like Backspace only come through keydown
+    $(document).keypress((event) => {
+      this.channel.onTextInput(event.key);
+    });
   }
 
   update(event: Geopad.RenderUpdateEvent) {
@@ -178,9 +220,24 @@ export default class GeopadWorld {
         } else if (event.entity in this.circles) {
           this.app.stage.removeChild(this.circles[event.entity].graphics);
           delete this.circles[event.entity];
+        } else if (event.entity in this.arcs) {
+          this.app.stage.removeChild(this.arcs[event.entity].graphics);
+          delete this.arcs[event.entity];
+        } else if (event.entity in this.conics) {
+          this.app.stage.removeChild(this.conics[event.entity].graphics);
+          delete this.conics[event.entity];
+        } else if (event.entity in this.texts) {
+          this.app.stage.removeChild(this.texts[event.entity].graphics);
+          delete this.texts[event.entity];
         } else if (event.entity in this.rectangles) {
           this.app.stage.removeChild(this.rectangles[event.entity].graphics);
           delete this.rectangles[event.entity];
+        } else if (event.entity in this.polygons) {
+          this.app.stage.removeChild(this.polygons[event.entity].graphics);
+          delete this.polygons[event.entity];
+        } else if (event.entity in this.vectors) {
+          this.app.stage.removeChild(this.vectors[event.entity].graphics);
+          delete this.vectors[event.entity];
         }
       } break;
       case Geopad.EVENT_TYPE_SELECTED_ENTITY: {
@@ -190,6 +247,16 @@ export default class GeopadWorld {
           this.lines[event.entity].setSelected(true);
         } else if (event.entity in this.circles) {
           this.circles[event.entity].setSelected(true);
+        } else if (event.entity in this.arcs) {
+          this.arcs[event.entity].setSelected(true);
+        } else if (event.entity in this.conics) {
+          this.conics[event.entity].setSelected(true);
+        } else if (event.entity in this.texts) {
+          this.texts[event.entity].setSelected(true);
+        } else if (event.entity in this.polygons) {
+          this.polygons[event.entity].setSelected(true);
+        } else if (event.entity in this.vectors) {
+          this.vectors[event.entity].setSelected(true);
         }
       } break;
       case Geopad.EVENT_TYPE_DESELECTED_ENTITY: {
@@ -199,7 +266,101 @@ export default class GeopadWorld {
           this.lines[event.entity].setSelected(false);
         } else if (event.entity in this.circles) {
           this.circles[event.entity].setSelected(false);
+        } else if (event.entity in this.arcs) {
+          this.arcs[event.entity].setSelected(false);
+        } else if (event.entity in this.conics) {
+          this.conics[event.entity].setSelected(false);
+        } else if (event.entity in this.texts) {
+          this.texts[event.entity].setSelected(false);
+        } else if (event.entity in this.polygons) {
+          this.polygons[event.entity].setSelected(false);
+        } else if (event.entity in this.vectors) {
+          this.vectors[event.entity].setSelected(false);
+        }
+      } break;
+      case Geopad.EVENT_TYPE_INSERTED_LABEL: {
+        if (event.entity in this.labels) {
+          this.app.stage.removeChild(this.labels[event.entity].text);
+        }
+        const label = new Label(event.label);
+        this.labels[event.entity] = label;
+        this.app.stage.addChild(label.text);
+        label.text.parentGroup = this.labelGroup;
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_LABEL: {
+        this.labels[event.entity].updateLabel(event.label);
+      } break;
+      case Geopad.EVENT_TYPE_REMOVED_LABEL: {
+        if (event.entity in this.labels) {
+          this.app.stage.removeChild(this.labels[event.entity].text);
+          delete this.labels[event.entity];
         }
+      } break;
+      case Geopad.EVENT_TYPE_INSERTED_POLYGON: {
+        const polygon = new Polygon(event.polygon, event.style);
+        this.polygons[event.entity] = polygon;
+        this.app.stage.addChild(polygon.graphics);
+        polygon.graphics.parentGroup = this.polygonGroup;
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_POLYGON: {
+        this.polygons[event.entity].updatePolygon(event.polygon);
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_POLYGON_STYLE: {
+        this.polygons[event.entity].updateStyle(event.style);
+      } break;
+      case Geopad.EVENT_TYPE_INSERTED_VECTOR: {
+        const vector = new Vector(event.vector, event.style);
+        this.vectors[event.entity] = vector;
+        this.app.stage.addChild(vector.graphics);
+        vector.graphics.parentGroup = this.lineGroup;
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_VECTOR: {
+        this.vectors[event.entity].updateVector(event.vector);
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_VECTOR_STYLE: {
+        this.vectors[event.entity].updateStyle(event.style);
+      } break;
+      case Geopad.EVENT_TYPE_INSERTED_ARC: {
+        const arc = new Arc(event.arc, event.style);
+        this.arcs[event.entity] = arc;
+        this.app.stage.addChild(arc.graphics);
+        arc.graphics.parentGroup = this.circleGroup;
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_ARC: {
+        this.arcs[event.entity].updateArc(event.arc);
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_ARC_STYLE: {
+        this.arcs[event.entity].updateStyle(event.style);
+      } break;
+      case Geopad.EVENT_TYPE_INSERTED_CONIC: {
+        const conic = new Conic(event.conic, event.style);
+        this.conics[event.entity] = conic;
+        this.app.stage.addChild(conic.graphics);
+        conic.graphics.parentGroup = this.circleGroup;
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_CONIC: {
+        this.conics[event.entity].updateConic(event.conic);
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_CONIC_STYLE: {
+        this.conics[event.entity].updateStyle(event.style);
+      } break;
+      case Geopad.EVENT_TYPE_INSERTED_TEXT: {
+        const text = new Text(event.text, event.style);
+        this.texts[event.entity] = text;
+        this.app.stage.addChild(text.graphics);
+        text.graphics.parentGroup = this.labelGroup;
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_TEXT: {
+        this.texts[event.entity].updateText(event.text);
+      } break;
+      case Geopad.EVENT_TYPE_UPDATED_TEXT_STYLE: {
+        this.texts[event.entity].updateStyle(event.style);
+      } break;
+      case Geopad.EVENT_TYPE_INSERTED_BACKGROUND_LINE: {
+        const line = new Line(event.line, event.style);
+        this.lines[event.entity] = line;
+        this.app.stage.addChild(line.graphics);
+        line.graphics.parentGroup = this.backgroundGroup;
       }
     }
   }
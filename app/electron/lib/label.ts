@@ -0,0 +1,29 @@
+import { Label as LabelData } from "../native";
+import * as PIXI from "pixi.js";
+
+export default class Label {
+
+  label: LabelData;
+  text: PIXI.Text;
+
+  constructor(label: LabelData) {
+
+    // Basic information
+    this.label = label;
+
+    // Render information
+    this.text = new PIXI.Text(label.text, { fontFamily: "Arial", fontSize: 13, fill: 0x333333 });
+    this.setupText();
+  }
+
+  updateLabel(label: LabelData) {
+    this.label = label;
+    this.setupText();
+  }
+
+  setupText() {
+    this.text.text = this.label.text;
+    this.text.x = this.label.position.x;
+    this.text.y = this.label.position.y;
+  }
+}
@@ -1,6 +1,9 @@
 import { Line, LineStyle } from "../native";
 import * as PIXI from "pixi.js";
 
+const TICK_LENGTH = 10;
+const TICK_SPACING = 4;
+
 export default class Point {
 
   line: Line;
@@ -38,8 +41,22 @@ export default class Point {
   setupGraphicsStyle() {
     this.graphics.clear();
     this.graphics.lineStyle(this.style.width, this.style.color, this.style.alpha);
-    this.graphics.moveTo(this.line.from.x, this.line.from.y);
-    this.graphics.lineTo(this.line.to.x, this.line.to.y);
+    this.drawDashes();
+
+    if (this.style.marks > 0) {
+      let dir = { x: this.line.to.x - this.line.from.x, y: this.line.to.y - this.line.from.y };
+      let magnitude = Math.sqrt(dir.x * dir.x + dir.y * dir.y);
+      let unit = { x: dir.x / magnitude, y: dir.y / magnitude };
+      let half = TICK_LENGTH / 2;
+      let spacing = Math.min(TICK_SPACING, magnitude / (this.style.marks + 1));
+      let mid = { x: (this.line.from.x + this.line.to.x) / 2, y: (this.line.from.y + this.line.to.y) / 2 };
+      for (let i = 0; i < this.style.marks; i++) {
+        let offset = (i - (this.style.marks - 1) / 2) * spacing;
+        let center = { x: mid.x + unit.x * offset, y: mid.y + unit.y * offset };
+        this.graphics.moveTo(center.x - unit.y * half, center.y + unit.x * half);
+        this.graphics.lineTo(center.x + unit.y * half, center.y - unit.x * half);
+      }
+    }
 
     if (this.selected) {
       let offset = this.style.width / 2 + 3;
@@ -53,4 +70,25 @@ export default class Point {
       this.graphics.lineTo(this.line.to.x - perpDir.x, this.line.to.y - perpDir.y);
     }
   }
-}
\ No newline at end of file
+
+  drawDashes() {
+    let { from, to } = this.line;
+    let dash = this.style.dash;
+    if (dash.length == 0) {
+      this.graphics.moveTo(from.x, from.y);
+      this.graphics.lineTo(to.x, to.y);
+      return;
+    }
+    let dir = { x: to.x - from.x, y: to.y - from.y };
+    let magnitude = Math.sqrt(dir.x * dir.x + dir.y * dir.y);
+    let unit = { x: dir.x / magnitude, y: dir.y / magnitude };
+    for (let t = 0, i = 0; t < magnitude; i++) {
+      let end = Math.min(t + dash[i % dash.length], magnitude);
+      if (i % 2 == 0) {
+        this.graphics.moveTo(from.x + unit.x * t, from.y + unit.y * t);
+        this.graphics.lineTo(from.x + unit.x * end, from.y + unit.y * end);
+      }
+      t = end;
+    }
+  }
+}
@@ -43,9 +43,30 @@ export default class Point {
 
   setupGraphicsStyle() {
     this.graphics.clear();
-    this.graphics.beginFill(this.style.color, this.style.alpha);
+    this.graphics.beginFill(this.style.color, this.style.hollow ? 0 : this.style.alpha);
     this.graphics.lineStyle(this.style.borderWidth, this.style.borderColor, this.style.borderAlpha);
-    this.graphics.drawEllipse(0, 0, this.style.radius - this.style.borderWidth / 2, this.style.radius - this.style.borderWidth / 2);
+    const radius = this.style.radius - this.style.borderWidth / 2;
+    const halfSide = radius / Math.SQRT2;
+    switch (this.style.markerShape) {
+      case "square":
+        this.graphics.drawRect(-halfSide, -halfSide, halfSide * 2, halfSide * 2);
+        break;
+      case "diamond":
+        this.graphics.drawPolygon([0, -radius, radius, 0, 0, radius, -radius, 0]);
+        break;
+      case "cross":
+        this.graphics.lineStyle(this.style.borderWidth, this.style.color, this.style.alpha);
+        this.graphics.moveTo(-halfSide, -halfSide).lineTo(halfSide, halfSide);
+        this.graphics.moveTo(-halfSide, halfSide).lineTo(halfSide, -halfSide);
+        break;
+      case "plus":
+        this.graphics.lineStyle(this.style.borderWidth, this.style.color, this.style.alpha);
+        this.graphics.moveTo(-radius, 0).lineTo(radius, 0);
+        this.graphics.moveTo(0, -radius).lineTo(0, radius);
+        break;
+      default:
+        this.graphics.drawEllipse(0, 0, radius, radius);
+    }
     this.graphics.endFill();
 
     if (this.selected) {
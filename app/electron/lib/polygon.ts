@@ -0,0 +1,54 @@
+import { Polygon as PolygonData, PolygonStyle } from "../native";
+import * as PIXI from "pixi.js";
+
+export default class Polygon {
+
+  polygon: PolygonData;
+  style: PolygonStyle;
+  selected: boolean;
+  graphics: PIXI.Graphics;
+
+  constructor(polygon: PolygonData, style: PolygonStyle) {
+
+    // Basic information
+    this.polygon = polygon;
+    this.style = style;
+    this.selected = false;
+
+    // Render information
+    this.graphics = new PIXI.Graphics();
+    this.setupGraphicsStyle();
+  }
+
+  updatePolygon(polygon: PolygonData) {
+    this.polygon = polygon;
+    this.setupGraphicsStyle();
+  }
+
+  updateStyle(style: PolygonStyle) {
+    this.style = style;
+    this.setupGraphicsStyle();
+  }
+
+  setSelected(selected: boolean) {
+    this.selected = selected;
+    this.setupGraphicsStyle();
+  }
+
+  setupGraphicsStyle() {
+    this.graphics.clear();
+
+    const path = this.polygon.map((vertex) => new PIXI.Point(vertex.x, vertex.y));
+    this.graphics.beginFill(this.style.fill, this.style.fillAlpha);
+    this.graphics.lineStyle(this.style.border.width, this.style.border.color, this.style.border.alpha);
+    this.graphics.drawPolygon(path);
+    this.graphics.endFill();
+
+    if (this.selected) {
+      this.graphics.beginFill(0x000000, 0);
+      this.graphics.lineStyle(this.style.border.width + 4, 0xff00ff, 0.5);
+      this.graphics.drawPolygon(path);
+      this.graphics.endFill();
+    }
+  }
+}
@@ -0,0 +1,50 @@
+import { Text as TextData, TextStyle } from "../native";
+import * as PIXI from "pixi.js";
+
+export default class Text {
+
+  text: TextData;
+  style: TextStyle;
+  selected: boolean;
+  graphics: PIXI.Text;
+
+  constructor(text: TextData, style: TextStyle) {
+
+    // Basic information
+    this.text = text;
+    this.style = style;
+    this.selected = false;
+
+    // Render information, the position is at the bottom left of the text
+    this.graphics = new PIXI.Text(text.text);
+    this.graphics.anchor.set(0, 1);
+    this.setupGraphicsStyle();
+  }
+
+  updateText(text: TextData) {
+    this.text = text;
+    this.setupGraphicsStyle();
+  }
+
+  updateStyle(style: TextStyle) {
+    this.style = style;
+    this.setupGraphicsStyle();
+  }
+
+  setSelected(selected: boolean) {
+    this.selected = selected;
+    this.setupGraphicsStyle();
+  }
+
+  setupGraphicsStyle() {
+    this.graphics.text = this.text.text;
+    this.graphics.x = this.text.position.x;
+    this.graphics.y = this.text.position.y;
+    this.graphics.alpha = this.style.alpha;
+    this.graphics.style = new PIXI.TextStyle({
+      fontFamily: "Arial",
+      fontSize: this.style.fontSize,
+      fill: this.selected ? 0xff00ff : this.style.color,
+    });
+  }
+}
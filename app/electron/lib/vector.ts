@@ -0,0 +1,69 @@
+import { Vector as VectorData, VectorStyle } from "../native";
+import * as PIXI from "pixi.js";
+
+const HEAD_ANGLE = Math.PI / 6;
+
+export default class Vector {
+
+  vector: VectorData;
+  style: VectorStyle;
+  selected: boolean;
+  graphics: PIXI.Graphics;
+
+  constructor(vector: VectorData, style: VectorStyle) {
+
+    // Basic information
+    this.vector = vector;
+    this.style = style;
+    this.selected = false;
+
+    // Render information
+    this.graphics = new PIXI.Graphics();
+    this.setupGraphicsStyle();
+  }
+
+  updateVector(vector: VectorData) {
+    this.vector = vector;
+    this.setupGraphicsStyle();
+  }
+
+  updateStyle(style: VectorStyle) {
+    this.style = style;
+    this.setupGraphicsStyle();
+  }
+
+  setSelected(selected: boolean) {
+    this.selected = selected;
+    this.setupGraphicsStyle();
+  }
+
+  setupGraphicsStyle() {
+    this.graphics.clear();
+    const { from, to } = this.vector;
+    let dir = { x: to.x - from.x, y: to.y - from.y };
+    let magnitude = Math.sqrt(dir.x * dir.x + dir.y * dir.y);
+    if (magnitude === 0) { return; }
+
+    this.graphics.lineStyle(this.style.width, this.style.color, this.style.alpha);
+    this.graphics.moveTo(from.x, from.y);
+    this.graphics.lineTo(to.x, to.y);
+
+    // The arrowhead is two sides going back from the tip
+    let length = this.style.headLength;
+    let back = Math.atan2(-dir.y, -dir.x);
+    for (let angle of [back - HEAD_ANGLE, back + HEAD_ANGLE]) {
+      this.graphics.moveTo(to.x, to.y);
+      this.graphics.lineTo(to.x + Math.cos(angle) * length, to.y + Math.sin(angle) * length);
+    }
+
+    if (this.selected) {
+      let offset = this.style.width / 2 + 3;
+      let perpDir = { x: -dir.y / magnitude * offset, y: dir.x / magnitude * offset };
+      this.graphics.lineStyle(1, 0xff00ff);
+      this.graphics.moveTo(from.x + perpDir.x, from.y + perpDir.y);
+      this.graphics.lineTo(to.x + perpDir.x, to.y + perpDir.y);
+      this.graphics.moveTo(from.x - perpDir.x, from.y - perpDir.y);
+      this.graphics.lineTo(to.x - perpDir.x, to.y - perpDir.y);
+    }
+  }
+}
@@ -13,6 +13,25 @@ export const EVENT_TYPE_UPDATED_RECTANGLE_STYLE = 12;
 export const EVENT_TYPE_REMOVED_ENTITY = 13;
 export const EVENT_TYPE_SELECTED_ENTITY = 14;
 export const EVENT_TYPE_DESELECTED_ENTITY = 15;
+export const EVENT_TYPE_INSERTED_LABEL = 16;
+export const EVENT_TYPE_UPDATED_LABEL = 17;
+export const EVENT_TYPE_REMOVED_LABEL = 18;
+export const EVENT_TYPE_INSERTED_POLYGON = 19;
+export const EVENT_TYPE_UPDATED_POLYGON = 20;
+export const EVENT_TYPE_UPDATED_POLYGON_STYLE = 21;
+export const EVENT_TYPE_INSERTED_VECTOR = 22;
+export const EVENT_TYPE_UPDATED_VECTOR = 23;
+export const EVENT_TYPE_UPDATED_VECTOR_STYLE = 24;
+export const EVENT_TYPE_INSERTED_ARC = 25;
+export const EVENT_TYPE_UPDATED_ARC = 26;
+export const EVENT_TYPE_UPDATED_ARC_STYLE = 27;
+export const EVENT_TYPE_INSERTED_CONIC = 28;
+export const EVENT_TYPE_UPDATED_CONIC = 29;
+export const EVENT_TYPE_UPDATED_CONIC_STYLE = 30;
+export const EVENT_TYPE_INSERTED_TEXT = 31;
+export const EVENT_TYPE_UPDATED_TEXT = 32;
+export const EVENT_TYPE_UPDATED_TEXT_STYLE = 33;
+export const EVENT_TYPE_INSERTED_BACKGROUND_LINE = 34;
 
 export type Position = {
   x: number,
@@ -26,8 +45,12 @@ export type PointStyle = {
   borderColor: number,
   borderAlpha: number,
   borderWidth: number,
+  hollow: boolean, // Constrained points are drawn as a ring
+  markerShape: MarkerShape,
 };
 
+export type MarkerShape = "filledCircle" | "hollowCircle" | "square" | "cross" | "plus" | "diamond";
+
 export type Line = {
   from: Position,
   to: Position,
@@ -37,6 +60,8 @@ export type LineStyle = {
   color: number,
   alpha: number,
   width: number,
+  marks: number,
+  dash: number[], // Lengths drawn and skipped in turn, empty when solid
 };
 
 export type Circle = {
@@ -63,6 +88,68 @@ export type RectangleStyle = {
   border: LineStyle,
 };
 
+export type Polygon = Position[]; // Closed, the last vertex connects back to the first one
+
+export type PolygonStyle = {
+  fill: number,
+  fillAlpha: number,
+  border: LineStyle,
+};
+
+export type Vector = {
+  from: Position,
+  to: Position, // The tip, where the arrowhead is drawn
+};
+
+export type VectorStyle = {
+  color: number,
+  alpha: number,
+  width: number,
+  headLength: number,
+};
+
+export type Arc = {
+  center: Position,
+  radius: number,
+  start: number, // Radians, clockwise from the x axis on screen
+  sweep: number, // Radians, drawn clockwise from start on screen
+};
+
+export type ArcStyle = {
+  color: number,
+  alpha: number,
+  width: number,
+};
+
+export type Conic = {
+  center: Position,
+  rx: number,
+  ry: number,
+  rotation: number, // Radians, clockwise from the x axis on screen
+};
+
+export type ConicStyle = {
+  color: number,
+  alpha: number,
+  width: number,
+};
+
+export type Text = {
+  position: Position, // Bottom left of the text
+  text: string,
+};
+
+export type TextStyle = {
+  color: number,
+  alpha: number,
+  fontSize: number,
+};
+
+export type Label = {
+  position: Position, // Top left of the text
+  text: string,
+};
+
 export type RenderUpdateEvent =
 | { type: 0 } // None
 | { type: 1, entity: string, point: Position, style: PointStyle }  // insert point event
@@ -79,7 +166,26 @@ export type RenderUpdateEvent =
 | { type: 12, entity: string, style: RectangleStyle }
 | { type: 13, entity: string } // remove point event
 | { type: 14, entity: string } // select point event
-| { type: 15, entity: string }; // deselect point event
+| { type: 15, entity: string } // deselect point event
+| { type: 16, entity: string, label: Label } // insert label event
+| { type: 17, entity: string, label: Label }
+| { type: 18, entity: string } // remove label event, the entity may still have a shape
+| { type: 19, entity: string, polygon: Polygon, style: PolygonStyle } // insert polygon event
+| { type: 20, entity: string, polygon: Polygon }
+| { type: 21, entity: string, style: PolygonStyle }
+| { type: 22, entity: string, vector: Vector, style: VectorStyle } // insert vector event
+| { type: 23, entity: string, vector: Vector }
+| { type: 24, entity: string, style: VectorStyle }
+| { type: 25, entity: string, arc: Arc, style: ArcStyle } // insert arc event
+| { type: 26, entity: string, arc: Arc }
+| { type: 27, entity: string, style: ArcStyle }
+| { type: 28, entity: string, conic: Conic, style: ConicStyle } // insert conic event
+| { type: 29, entity: string, conic: Conic }
+| { type: 30, entity: string, style: ConicStyle }
+| { type: 31, entity: string, text: Text, style: TextStyle } // insert text event
+| { type: 32, entity: string, text: Text }
+| { type: 33, entity: string, style: TextStyle }
+| { type: 34, entity: string, line: Line, style: LineStyle }; // insert grid line event
 
 export class GeopadWorld {
   constructor();
@@ -88,7 +194,10 @@ export class GeopadWorld {
   onMouseMove(x: number, y: number, relX: number, relY: number) : void;
   onMouseDown() : void;
   onMouseUp() : void;
+  onMouseScroll(x: number, y: number) : void;
+  onPinch(relPinch: number) : void;
   onKeyDown(key: number) : void;
   onKeyUp(key: number) : void;
+  onTextInput(text: string) : void;
   shutdown() : void;
 }
\ No newline at end of file
@@ -18,6 +18,25 @@ pub enum RenderUpdateEvent {
   SelectedEntity(Entity),
   DeselectedEntity(Entity),
   RemovedEntity(Entity),
+  InsertedLabel(Entity, ScreenLabel),
+  UpdatedLabel(Entity, ScreenLabel),
+  RemovedLabel(Entity), // Only the label, the entity might still have a shape
+  InsertedPolygon(Entity, ScreenPolygon, PolygonStyle),
+  UpdatedPolygon(Entity, ScreenPolygon),
+  UpdatedPolygonStyle(Entity, PolygonStyle),
+  InsertedVector(Entity, ScreenVector, VectorStyle),
+  UpdatedVector(Entity, ScreenVector),
+  UpdatedVectorStyle(Entity, VectorStyle),
+  InsertedArc(Entity, ScreenArc, ArcStyle),
+  UpdatedArc(Entity, ScreenArc),
+  UpdatedArcStyle(Entity, ArcStyle),
+  InsertedConic(Entity, ScreenConic, ConicStyle),
+  UpdatedConic(Entity, ScreenConic),
+  UpdatedConicStyle(Entity, ConicStyle),
+  InsertedText(Entity, ScreenText, TextStyle),
+  UpdatedText(Entity, ScreenText),
+  UpdatedTextStyle(Entity, TextStyle),
+  InsertedBackgroundLine(Entity, ScreenLine, LineStyle), // Drawn under all the geometry
 }
 
 pub fn render_update_event_to_u32(event: &RenderUpdateEvent) -> u32 {
@@ -38,5 +57,81 @@ pub fn render_update_event_to_u32(event: &RenderUpdateEvent) -> u32 {
     RenderUpdateEvent::RemovedEntity(_) => 13,
     RenderUpdateEvent::SelectedEntity(_) => 14,
     RenderUpdateEvent::DeselectedEntity(_) => 15,
+    RenderUpdateEvent::InsertedLabel(_, _) => 16,
+    RenderUpdateEvent::UpdatedLabel(_, _) => 17,
+    RenderUpdateEvent::RemovedLabel(_) => 18,
+    RenderUpdateEvent::InsertedPolygon(_, _, _) => 19,
+    RenderUpdateEvent::UpdatedPolygon(_, _) => 20,
+    RenderUpdateEvent::UpdatedPolygonStyle(_, _) => 21,
+    RenderUpdateEvent::InsertedVector(_, _, _) => 22,
+    RenderUpdateEvent::UpdatedVector(_, _) => 23,
+    RenderUpdateEvent::UpdatedVectorStyle(_, _) => 24,
+    RenderUpdateEvent::InsertedArc(_, _, _) => 25,
+    RenderUpdateEvent::UpdatedArc(_, _) => 26,
+    RenderUpdateEvent::UpdatedArcStyle(_, _) => 27,
+    RenderUpdateEvent::InsertedConic(_, _, _) => 28,
+    RenderUpdateEvent::UpdatedConic(_, _) => 29,
+    RenderUpdateEvent::UpdatedConicStyle(_, _) => 30,
+    RenderUpdateEvent::InsertedText(_, _, _) => 31,
+    RenderUpdateEvent::UpdatedText(_, _) => 32,
+    RenderUpdateEvent::UpdatedTextStyle(_, _) => 33,
+    RenderUpdateEvent::InsertedBackgroundLine(_, _, _) => 34,
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  #[test]
+  fn test_inserted_line_carries_style() {
+    let mut world = World::new();
+    let ent = world.create_entity().build();
+    let line = ScreenLine {
+      from: vec2![0., 0.].into(),
+      to: vec2![10., 0.].into(),
+      line_type: LineType::Segment,
+    };
+    let style = LineStyle {
+      color: Color::black(),
+      width: 2.,
+      marks: EqualityMarks(2),
+      dash: DashPattern::Solid,
+      alpha: 0.5,
+    };
+    let event = RenderUpdateEvent::InsertedLine(ent, line, style);
+    assert_eq!(render_update_event_to_u32(&event), 2);
+    match event {
+      RenderUpdateEvent::InsertedLine(_, _, style) => {
+        assert_eq!(style.marks, EqualityMarks(2));
+        assert_eq!(style.alpha, 0.5);
+        assert_eq!(style.flatten_alpha().color.a, 0.5);
+      }
+      _ => panic!("Expected an inserted line"),
+    }
+  }
+  #[test]
+  fn test_point_style_carries_marker_shape() {
+    let mut world = World::new();
+    let ent = world.create_entity().build();
+    let style = PointStyle {
+      color: Color::red(),
+      radius: 5.,
+      border_color: Color::black(),
+      border_width: 1.5,
+      alpha: 1.,
+      fill: PointFill::Auto,
+      marker_shape: MarkerShape::Diamond,
+    };
+    let events = vec![
+      RenderUpdateEvent::InsertedPoint(ent, vec2![1., 2.].into(), style),
+      RenderUpdateEvent::UpdatedPointStyle(ent, PointStyle { marker_shape: MarkerShape::Cross, ..style }),
+    ];
+    let shapes = events.iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedPoint(_, _, style) | RenderUpdateEvent::UpdatedPointStyle(_, style) => Some(style.flatten_alpha().marker_shape),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(shapes, vec![MarkerShape::Diamond, MarkerShape::Cross]);
+  }
+}
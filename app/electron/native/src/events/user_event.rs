@@ -9,7 +9,8 @@ pub enum UserEvent {
 
 pub enum InputEvent {
   Button(ButtonState, Button),
-  Motion(MotionEvent)
+  Motion(MotionEvent),
+  Text(String),
 }
 
 #[derive(PartialEq)]
@@ -32,4 +33,5 @@ pub enum MotionEvent {
   MouseCursor(Vector2),
   MouseRelative(Vector2),
   MouseScroll(Vector2),
+  Pinch(f64), // Relative magnification
 }
\ No newline at end of file
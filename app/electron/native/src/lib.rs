@@ -8,7 +8,7 @@ use std::thread;
 
 use neon::context::Context;
 use neon::task::Task;
-use neon::types::{JsFunction, JsUndefined, JsNumber};
+use neon::types::{JsFunction, JsUndefined, JsNumber, JsString};
 use neon::{declare_types, register_module};
 
 use specs::prelude::*;
@@ -96,6 +96,25 @@ declare_types! {
       Ok(JsUndefined::new().upcast())
     }
 
+    method onMouseScroll(mut cx) {
+      let this = cx.this();
+      let x = cx.argument::<JsNumber>(0)?.value() as f64;
+      let y = cx.argument::<JsNumber>(1)?.value() as f64;
+      cx.borrow(&this, |emitter| {
+        emitter.receiver.send(UserEvent::Input(InputEvent::Motion(MotionEvent::MouseScroll(vec2![x, y]))))
+      }).or_else(|err| cx.throw_error(&err.to_string()))?;
+      Ok(JsUndefined::new().upcast())
+    }
+
+    method onPinch(mut cx) {
+      let this = cx.this();
+      let rel_pinch = cx.argument::<JsNumber>(0)?.value() as f64;
+      cx.borrow(&this, |emitter| {
+        emitter.receiver.send(UserEvent::Input(InputEvent::Motion(MotionEvent::Pinch(rel_pinch))))
+      }).or_else(|err| cx.throw_error(&err.to_string()))?;
+      Ok(JsUndefined::new().upcast())
+    }
+
     method onMouseDown(mut cx) {
       let this = cx.this();
       cx.borrow(&this, |emitter| {
@@ -130,6 +149,15 @@ declare_types! {
       Ok(JsUndefined::new().upcast())
     }
 
+    method onTextInput(mut cx) {
+      let this = cx.this();
+      let text = cx.argument::<JsString>(0)?.value();
+      cx.borrow(&this, |emitter| {
+        emitter.receiver.send(UserEvent::Input(InputEvent::Text(text)))
+      }).or_else(|err| cx.throw_error(&err.to_string()))?;
+      Ok(JsUndefined::new().upcast())
+    }
+
     method shutdown(mut cx) {
       let this = cx.this();
       cx.borrow(&this, |emitter| emitter.receiver.send(UserEvent::Shutdown)).or_else(|err| cx.throw_error(&err.to_string()))?;
@@ -138,7 +166,7 @@ declare_types! {
   }
 }
 
-static CONSTANTS : [(&'static str, u32); 16] = [
+static CONSTANTS : [(&'static str, u32); 35] = [
   ("EVENT_TYPE_NONE", 0),
   ("EVENT_TYPE_INSERTED_POINT", 1),
   ("EVENT_TYPE_INSERTED_LINE", 2),
@@ -155,6 +183,25 @@ static CONSTANTS : [(&'static str, u32); 16] = [
   ("EVENT_TYPE_REMOVED_ENTITY", 13),
   ("EVENT_TYPE_SELECTED_ENTITY", 14),
   ("EVENT_TYPE_DESELECTED_ENTITY", 15),
+  ("EVENT_TYPE_INSERTED_LABEL", 16),
+  ("EVENT_TYPE_UPDATED_LABEL", 17),
+  ("EVENT_TYPE_REMOVED_LABEL", 18),
+  ("EVENT_TYPE_INSERTED_POLYGON", 19),
+  ("EVENT_TYPE_UPDATED_POLYGON", 20),
+  ("EVENT_TYPE_UPDATED_POLYGON_STYLE", 21),
+  ("EVENT_TYPE_INSERTED_VECTOR", 22),
+  ("EVENT_TYPE_UPDATED_VECTOR", 23),
+  ("EVENT_TYPE_UPDATED_VECTOR_STYLE", 24),
+  ("EVENT_TYPE_INSERTED_ARC", 25),
+  ("EVENT_TYPE_UPDATED_ARC", 26),
+  ("EVENT_TYPE_UPDATED_ARC_STYLE", 27),
+  ("EVENT_TYPE_INSERTED_CONIC", 28),
+  ("EVENT_TYPE_UPDATED_CONIC", 29),
+  ("EVENT_TYPE_UPDATED_CONIC_STYLE", 30),
+  ("EVENT_TYPE_INSERTED_TEXT", 31),
+  ("EVENT_TYPE_UPDATED_TEXT", 32),
+  ("EVENT_TYPE_UPDATED_TEXT_STYLE", 33),
+  ("EVENT_TYPE_INSERTED_BACKGROUND_LINE", 34),
 ];
 
 register_module!(mut cx, {
@@ -47,12 +47,15 @@ impl<'a> System<'a> for ReceiverSystem {
               MotionEvent::MouseScroll(rel_scroll) => {
                 input_state.rel_scroll = input_state.rel_scroll + rel_scroll;
               },
+              MotionEvent::Pinch(rel_pinch) => {
+                input_state.rel_pinch += rel_pinch;
+              },
             },
             InputEvent::Button(button_state, button) => {
               let is_pressed = button_state == ButtonState::Press;
               match button {
                 Button::Keyboard(key) => {
-                  input_state.keyboard.set(key, is_pressed);
+                  input_state.set_key(key, is_pressed);
                 },
                 Button::Mouse(mouse_button) => match mouse_button {
                   MouseButton::Left => {
@@ -80,6 +83,9 @@ impl<'a> System<'a> for ReceiverSystem {
                 },
               }
             },
+            InputEvent::Text(text) => {
+              input_state.type_text(&text);
+            },
           },
           UserEvent::Shutdown => {
             exit_event_channel.single_write(ExitEvent);
@@ -91,5 +97,6 @@ impl<'a> System<'a> for ReceiverSystem {
         Err(std::sync::mpsc::TryRecvError::Empty) => (),
       }
     }
+    input_state.record_mouse_position(SystemTime::now());
   }
 }
\ No newline at end of file
@@ -1,9 +1,10 @@
 use specs::prelude::*;
 use core_lib::{
-  math::*,
-  components::{screen_shapes::*, styles::*},
+  components::{markers::*, screen_shapes::*, styles::*, symbolics::*},
+  math::{LineType, AABB},
   resources::*,
   events::*,
+  utilities::*,
 };
 use crate::events::*;
 
@@ -17,7 +18,19 @@ pub struct SenderSystem {
   circle_style_update_reader: Option<ReaderId<ComponentEvent>>,
   scrn_rect_update_reader: Option<ReaderId<ComponentEvent>>,
   rect_style_update_reader: Option<ReaderId<ComponentEvent>>,
+  scrn_label_update_reader: Option<ReaderId<ComponentEvent>>,
+  scrn_polygon_update_reader: Option<ReaderId<ComponentEvent>>,
+  polygon_style_update_reader: Option<ReaderId<ComponentEvent>>,
+  scrn_vector_update_reader: Option<ReaderId<ComponentEvent>>,
+  vector_style_update_reader: Option<ReaderId<ComponentEvent>>,
+  scrn_arc_update_reader: Option<ReaderId<ComponentEvent>>,
+  arc_style_update_reader: Option<ReaderId<ComponentEvent>>,
+  scrn_conic_update_reader: Option<ReaderId<ComponentEvent>>,
+  conic_style_update_reader: Option<ReaderId<ComponentEvent>>,
+  scrn_text_update_reader: Option<ReaderId<ComponentEvent>>,
+  text_style_update_reader: Option<ReaderId<ComponentEvent>>,
   marker_event_reader: Option<MarkerEventReader>,
+  line_clip_cache: LineClipCache<Entity>,
 }
 
 impl SenderSystem {
@@ -32,7 +45,19 @@ impl SenderSystem {
       circle_style_update_reader: None,
       scrn_rect_update_reader: None,
       rect_style_update_reader: None,
+      scrn_label_update_reader: None,
+      scrn_polygon_update_reader: None,
+      polygon_style_update_reader: None,
+      scrn_vector_update_reader: None,
+      vector_style_update_reader: None,
+      scrn_arc_update_reader: None,
+      arc_style_update_reader: None,
+      scrn_conic_update_reader: None,
+      conic_style_update_reader: None,
+      scrn_text_update_reader: None,
+      text_style_update_reader: None,
       marker_event_reader: None,
+      line_clip_cache: LineClipCache::default(),
     }
   }
 }
@@ -41,15 +66,29 @@ impl<'a> System<'a> for SenderSystem {
   type SystemData = (
     Entities<'a>,
     Read<'a, Viewport>,
+    Read<'a, LineClipMargin>,
     Read<'a, MarkerEventChannel>,
     ReadStorage<'a, ScreenPoint>,
     ReadStorage<'a, PointStyle>,
+    ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, ScreenLine>,
     ReadStorage<'a, LineStyle>,
     ReadStorage<'a, ScreenCircle>,
     ReadStorage<'a, CircleStyle>,
     ReadStorage<'a, ScreenRectangle>,
     ReadStorage<'a, RectangleStyle>,
+    ReadStorage<'a, ScreenLabel>,
+    ReadStorage<'a, ScreenPolygon>,
+    ReadStorage<'a, PolygonStyle>,
+    ReadStorage<'a, ScreenVector>,
+    ReadStorage<'a, VectorStyle>,
+    ReadStorage<'a, ScreenArc>,
+    ReadStorage<'a, ArcStyle>,
+    ReadStorage<'a, ScreenConic>,
+    ReadStorage<'a, ConicStyle>,
+    ReadStorage<'a, ScreenText>,
+    ReadStorage<'a, TextStyle>,
+    ReadStorage<'a, Background>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -62,21 +101,46 @@ impl<'a> System<'a> for SenderSystem {
     self.circle_style_update_reader = Some(WriteStorage::<CircleStyle>::fetch(&world).register_reader());
     self.scrn_rect_update_reader = Some(WriteStorage::<ScreenRectangle>::fetch(&world).register_reader());
     self.rect_style_update_reader = Some(WriteStorage::<RectangleStyle>::fetch(&world).register_reader());
+    self.scrn_label_update_reader = Some(WriteStorage::<ScreenLabel>::fetch(&world).register_reader());
+    self.scrn_polygon_update_reader = Some(WriteStorage::<ScreenPolygon>::fetch(&world).register_reader());
+    self.polygon_style_update_reader = Some(WriteStorage::<PolygonStyle>::fetch(&world).register_reader());
+    self.scrn_vector_update_reader = Some(WriteStorage::<ScreenVector>::fetch(&world).register_reader());
+    self.vector_style_update_reader = Some(WriteStorage::<VectorStyle>::fetch(&world).register_reader());
+    self.scrn_arc_update_reader = Some(WriteStorage::<ScreenArc>::fetch(&world).register_reader());
+    self.arc_style_update_reader = Some(WriteStorage::<ArcStyle>::fetch(&world).register_reader());
+    self.scrn_conic_update_reader = Some(WriteStorage::<ScreenConic>::fetch(&world).register_reader());
+    self.conic_style_update_reader = Some(WriteStorage::<ConicStyle>::fetch(&world).register_reader());
+    self.scrn_text_update_reader = Some(WriteStorage::<ScreenText>::fetch(&world).register_reader());
+    self.text_style_update_reader = Some(WriteStorage::<TextStyle>::fetch(&world).register_reader());
     self.marker_event_reader = Some(world.fetch_mut::<MarkerEventChannel>().register_reader());
   }
 
   fn run(&mut self, (
     entities,
     viewport,
+    line_clip_margin,
     marker_event_channel,
     scrn_points,
     point_styles,
+    sym_points,
     scrn_lines,
     line_styles,
     scrn_circles,
     circle_styles,
     scrn_rects,
     rect_styles,
+    scrn_labels,
+    scrn_polygons,
+    polygon_styles,
+    scrn_vectors,
+    vector_styles,
+    scrn_arcs,
+    arc_styles,
+    scrn_conics,
+    conic_styles,
+    scrn_texts,
+    text_styles,
+    backgrounds,
   ): Self::SystemData) {
 
     // First deal with geometry update
@@ -92,7 +156,25 @@ impl<'a> System<'a> for SenderSystem {
     let mut inserted_rects = BitSet::new();
     let mut modified_rects = BitSet::new();
     let mut modified_rect_styles = BitSet::new();
+    let mut inserted_polygons = BitSet::new();
+    let mut modified_polygons = BitSet::new();
+    let mut modified_polygon_styles = BitSet::new();
+    let mut inserted_vectors = BitSet::new();
+    let mut modified_vectors = BitSet::new();
+    let mut modified_vector_styles = BitSet::new();
+    let mut inserted_arcs = BitSet::new();
+    let mut modified_arcs = BitSet::new();
+    let mut modified_arc_styles = BitSet::new();
+    let mut inserted_conics = BitSet::new();
+    let mut modified_conics = BitSet::new();
+    let mut modified_conic_styles = BitSet::new();
+    let mut inserted_texts = BitSet::new();
+    let mut modified_texts = BitSet::new();
+    let mut modified_text_styles = BitSet::new();
     let mut removed : BitSet = BitSet::new();
+    let mut inserted_labels = BitSet::new();
+    let mut modified_labels = BitSet::new();
+    let mut removed_labels = BitSet::new();
 
     // Screen point updates
     if let Some(reader) = &mut self.scrn_point_update_reader {
@@ -171,16 +253,126 @@ impl<'a> System<'a> for SenderSystem {
       }
     }
 
-    // Do all the insert
-    for (ent, scrn_point, point_style, _) in (&entities, &scrn_points, &point_styles, &inserted_points).join() {
-      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedPoint(ent, *scrn_point, *point_style)) { panic!(err) }
+    if let Some(reader) = &mut self.scrn_polygon_update_reader {
+      for event in scrn_polygons.channel().read(reader) {
+        match event {
+          ComponentEvent::Inserted(id) => { inserted_polygons.add(*id); },
+          ComponentEvent::Modified(id) => { modified_polygons.add(*id); },
+          ComponentEvent::Removed(id) => { removed.add(*id); },
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.polygon_style_update_reader {
+      for event in polygon_styles.channel().read(reader) {
+        match event {
+          ComponentEvent::Modified(id) => { modified_polygon_styles.add(*id); },
+          _ => (),
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.scrn_vector_update_reader {
+      for event in scrn_vectors.channel().read(reader) {
+        match event {
+          ComponentEvent::Inserted(id) => { inserted_vectors.add(*id); },
+          ComponentEvent::Modified(id) => { modified_vectors.add(*id); },
+          ComponentEvent::Removed(id) => { removed.add(*id); },
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.vector_style_update_reader {
+      for event in vector_styles.channel().read(reader) {
+        match event {
+          ComponentEvent::Modified(id) => { modified_vector_styles.add(*id); },
+          _ => (),
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.scrn_arc_update_reader {
+      for event in scrn_arcs.channel().read(reader) {
+        match event {
+          ComponentEvent::Inserted(id) => { inserted_arcs.add(*id); },
+          ComponentEvent::Modified(id) => { modified_arcs.add(*id); },
+          ComponentEvent::Removed(id) => { removed.add(*id); },
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.arc_style_update_reader {
+      for event in arc_styles.channel().read(reader) {
+        match event {
+          ComponentEvent::Modified(id) => { modified_arc_styles.add(*id); },
+          _ => (),
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.scrn_conic_update_reader {
+      for event in scrn_conics.channel().read(reader) {
+        match event {
+          ComponentEvent::Inserted(id) => { inserted_conics.add(*id); },
+          ComponentEvent::Modified(id) => { modified_conics.add(*id); },
+          ComponentEvent::Removed(id) => { removed.add(*id); },
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.conic_style_update_reader {
+      for event in conic_styles.channel().read(reader) {
+        match event {
+          ComponentEvent::Modified(id) => { modified_conic_styles.add(*id); },
+          _ => (),
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.scrn_text_update_reader {
+      for event in scrn_texts.channel().read(reader) {
+        match event {
+          ComponentEvent::Inserted(id) => { inserted_texts.add(*id); },
+          ComponentEvent::Modified(id) => { modified_texts.add(*id); },
+          ComponentEvent::Removed(id) => { removed.add(*id); },
+        }
+      }
+    }
+
+    if let Some(reader) = &mut self.text_style_update_reader {
+      for event in text_styles.channel().read(reader) {
+        match event {
+          ComponentEvent::Modified(id) => { modified_text_styles.add(*id); },
+          _ => (),
+        }
+      }
+    }
+
+    // Labels are removed on their own, their entity may still have a shape
+    if let Some(reader) = &mut self.scrn_label_update_reader {
+      for event in scrn_labels.channel().read(reader) {
+        match event {
+          ComponentEvent::Inserted(id) => { inserted_labels.add(*id); },
+          ComponentEvent::Modified(id) => { modified_labels.add(*id); },
+          ComponentEvent::Removed(id) => { removed_labels.add(*id); },
+        }
+      }
+    }
+
+    // Do all the insert. Points are sent with their fill decided, so that constrained points look
+    // different from the ones that can be dragged
+    for (ent, scrn_point, point_style, sym_point, _) in (&entities, &scrn_points, &point_styles, &sym_points, &inserted_points).join() {
+      let point_style = point_style.resolve_fill(sym_point);
+      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedPoint(ent, *scrn_point, point_style)) { panic!(err) }
     }
     for (ent, scrn_line, line_style, _) in (&entities, &scrn_lines, &line_styles, &inserted_lines).join() {
-      if let Some((from, to)) = scrn_line.intersect(viewport.screen_aabb()) {
-        if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedLine(ent, ScreenLine { from, to, line_type: LineType::Segment }, *line_style)) { panic!(err) }
+      let clipped = clip_line(&mut self.line_clip_cache, ent, *scrn_line, line_clip_margin.clip_aabb(&viewport));
+      let event = if backgrounds.contains(ent) {
+        RenderUpdateEvent::InsertedBackgroundLine(ent, clipped, *line_style)
       } else {
-        if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedLine(ent, *scrn_line, *line_style)) { panic!(err) }
-      }
+        RenderUpdateEvent::InsertedLine(ent, clipped, *line_style)
+      };
+      if let Err(err) = self.sender.send(event) { panic!(err) }
     }
     for (ent, scrn_circle, circle_style, _) in (&entities, &scrn_circles, &circle_styles, &inserted_circles).join() {
       if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedCircle(ent, *scrn_circle, *circle_style)) { panic!(err) }
@@ -188,20 +380,36 @@ impl<'a> System<'a> for SenderSystem {
     for (ent, scrn_rect, rect_style, _) in (&entities, &scrn_rects, &rect_styles, &inserted_rects).join() {
       if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedRectangle(ent, *scrn_rect, *rect_style)) { panic!(err) }
     }
+    for (ent, scrn_polygon, polygon_style, _) in (&entities, &scrn_polygons, &polygon_styles, &inserted_polygons).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedPolygon(ent, scrn_polygon.clone(), *polygon_style)) { panic!(err) }
+    }
+    for (ent, scrn_vector, vector_style, _) in (&entities, &scrn_vectors, &vector_styles, &inserted_vectors).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedVector(ent, *scrn_vector, *vector_style)) { panic!(err) }
+    }
+    for (ent, scrn_arc, arc_style, _) in (&entities, &scrn_arcs, &arc_styles, &inserted_arcs).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedArc(ent, *scrn_arc, *arc_style)) { panic!(err) }
+    }
+    for (ent, scrn_conic, conic_style, _) in (&entities, &scrn_conics, &conic_styles, &inserted_conics).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedConic(ent, *scrn_conic, *conic_style)) { panic!(err) }
+    }
+    for (ent, scrn_text, text_style, _) in (&entities, &scrn_texts, &text_styles, &inserted_texts).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedText(ent, scrn_text.clone(), *text_style)) { panic!(err) }
+    }
+    for (ent, scrn_label, _) in (&entities, &scrn_labels, &inserted_labels).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::InsertedLabel(ent, scrn_label.clone())) { panic!(err) }
+    }
 
     // Do all the modify
     for (ent, scrn_point, _) in (&entities, &scrn_points, &modified_points).join() {
       if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedPoint(ent, *scrn_point)) { panic!(err) }
     }
-    for (ent, point_style, _) in (&entities, &point_styles, &modified_point_styles).join() {
-      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedPointStyle(ent, *point_style)) { panic!(err) }
+    for (ent, point_style, sym_point, _) in (&entities, &point_styles, &sym_points, &modified_point_styles).join() {
+      let point_style = point_style.resolve_fill(sym_point);
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedPointStyle(ent, point_style)) { panic!(err) }
     }
     for (ent, scrn_line, _) in (&entities, &scrn_lines, &modified_lines).join() {
-      if let Some((from, to)) = scrn_line.intersect(viewport.screen_aabb()) {
-        if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedLine(ent, ScreenLine { from, to, line_type: LineType::Segment })) { panic!(err) }
-      } else {
-        if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedLine(ent, *scrn_line)) { panic!(err) }
-      }
+      let clipped = clip_line(&mut self.line_clip_cache, ent, *scrn_line, line_clip_margin.clip_aabb(&viewport));
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedLine(ent, clipped)) { panic!(err) }
     }
     for (ent, line_style, _) in (&entities, &line_styles, &modified_line_styles).join() {
       if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedLineStyle(ent, *line_style)) { panic!(err) }
@@ -218,11 +426,48 @@ impl<'a> System<'a> for SenderSystem {
     for (ent, rect_style, _) in (&entities, &rect_styles, &modified_rect_styles).join() {
       if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedRectangleStyle(ent, *rect_style)) { panic!(err) }
     }
+    for (ent, scrn_polygon, _) in (&entities, &scrn_polygons, &modified_polygons).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedPolygon(ent, scrn_polygon.clone())) { panic!(err) }
+    }
+    for (ent, polygon_style, _) in (&entities, &polygon_styles, &modified_polygon_styles).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedPolygonStyle(ent, *polygon_style)) { panic!(err) }
+    }
+    for (ent, scrn_vector, _) in (&entities, &scrn_vectors, &modified_vectors).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedVector(ent, *scrn_vector)) { panic!(err) }
+    }
+    for (ent, vector_style, _) in (&entities, &vector_styles, &modified_vector_styles).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedVectorStyle(ent, *vector_style)) { panic!(err) }
+    }
+    for (ent, scrn_arc, _) in (&entities, &scrn_arcs, &modified_arcs).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedArc(ent, *scrn_arc)) { panic!(err) }
+    }
+    for (ent, arc_style, _) in (&entities, &arc_styles, &modified_arc_styles).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedArcStyle(ent, *arc_style)) { panic!(err) }
+    }
+    for (ent, scrn_conic, _) in (&entities, &scrn_conics, &modified_conics).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedConic(ent, *scrn_conic)) { panic!(err) }
+    }
+    for (ent, conic_style, _) in (&entities, &conic_styles, &modified_conic_styles).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedConicStyle(ent, *conic_style)) { panic!(err) }
+    }
+    for (ent, scrn_text, _) in (&entities, &scrn_texts, &modified_texts).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedText(ent, scrn_text.clone())) { panic!(err) }
+    }
+    for (ent, text_style, _) in (&entities, &text_styles, &modified_text_styles).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedTextStyle(ent, *text_style)) { panic!(err) }
+    }
+    for (ent, scrn_label, _, _) in (&entities, &scrn_labels, &modified_labels, !&inserted_labels).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::UpdatedLabel(ent, scrn_label.clone())) { panic!(err) }
+    }
 
     // Do all the removals
     for (ent, _) in (&entities, &removed).join() {
+      self.line_clip_cache.remove(ent);
       if let Err(err) = self.sender.send(RenderUpdateEvent::RemovedEntity(ent)) { panic!(err) }
     }
+    for (ent, _, _) in (&entities, &removed_labels, !&scrn_labels).join() {
+      if let Err(err) = self.sender.send(RenderUpdateEvent::RemovedLabel(ent)) { panic!(err) }
+    }
 
     // Then deal with select update
     if let Some(reader) = &mut self.marker_event_reader {
@@ -239,4 +484,257 @@ impl<'a> System<'a> for SenderSystem {
       }
     }
   }
-}
\ No newline at end of file
+}
+
+/// The part of the line on the screen as a segment, or the line itself when it is off the screen
+fn clip_line(cache: &mut LineClipCache<Entity>, ent: Entity, scrn_line: ScreenLine, bounds: AABB) -> ScreenLine {
+  match cache.clip(ent, scrn_line, bounds) {
+    Some((from, to)) => ScreenLine { from: from.into(), to: to.into(), line_type: LineType::Segment },
+    None => scrn_line,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::{components::measurements::*, math::*, setup_core_lib};
+  use std::sync::mpsc;
+
+  #[test]
+  fn test_constrained_point_is_sent_hollow() {
+    let (tx, rx) = mpsc::channel();
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    builder.add_thread_local(SenderSystem::new(tx));
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let mut insert_point = |world: &mut World, sym_point: SymbolicPoint| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+        command: Command::PointInsert(InsertPointEvent::InsertPoint(sym_point)),
+        event_id: None,
+      });
+      dispatcher.dispatch(world);
+      world.maintain();
+      (&world.entities(), &world.read_storage::<SymbolicPoint>()).join().map(|(ent, _)| ent).max_by_key(|ent| ent.id()).unwrap()
+    };
+    let p1 = insert_point(&mut world, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let p2 = insert_point(&mut world, SymbolicPoint::Free(vec2![2., 0.].into()));
+    let mid = insert_point(&mut world, SymbolicPoint::MidPoint(p1, p2));
+
+    let fills = rx.try_iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedPoint(ent, _, style) => Some((ent, style.fill)),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(fills, vec![(p1, PointFill::Solid), (p2, PointFill::Solid), (mid, PointFill::Hollow)]);
+  }
+
+  #[test]
+  fn test_measurement_label_is_sent_on_its_own() {
+    let (tx, rx) = mpsc::channel();
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    builder.add_thread_local(SenderSystem::new(tx));
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let mut step = |world: &mut World, command: Command| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent { command, event_id: None });
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+    step(&mut world, Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![0., 0.].into()))));
+    step(&mut world, Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![3., 4.].into()))));
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>()).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    let (p1, p2) = (points[0], points[1]);
+    step(&mut world, Command::MeasurementInsert(InsertMeasurementEvent::InsertMeasurement(Measurement::DistancePP(p1, p2))));
+    let old = SymbolicPoint::Free(vec2![3., 4.].into());
+    step(&mut world, Command::Update(UpdateEvent::UpdatePoint(p2, old, SymbolicPoint::Free(vec2![6., 8.].into()))));
+    step(&mut world, Command::Remove(RemoveEvent::Remove(p2)));
+
+    let labels = rx.try_iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedLabel(_, label) => Some(format!("inserted {}", label.text)),
+      RenderUpdateEvent::UpdatedLabel(_, label) => Some(format!("updated {}", label.text)),
+      RenderUpdateEvent::RemovedLabel(_) => Some("removed".to_string()),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(labels, vec!["inserted 5.00", "updated 10.00", "removed"]);
+  }
+
+  #[test]
+  fn test_polygon_is_sent_and_follows_its_vertices() {
+    let (tx, rx) = mpsc::channel();
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    builder.add_thread_local(SenderSystem::new(tx));
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let mut step = |world: &mut World, command: Command| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent { command, event_id: None });
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+    for (x, y) in &[(0., 0.), (4., 0.), (0., 3.)] {
+      step(&mut world, Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![*x, *y].into()))));
+    }
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>()).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    step(&mut world, Command::PolygonInsert(InsertPolygonEvent::InsertPolygon(SymbolicPolygon(points.clone()))));
+    let old = SymbolicPoint::Free(vec2![4., 0.].into());
+    step(&mut world, Command::Update(UpdateEvent::UpdatePoint(points[1], old, SymbolicPoint::Free(vec2![8., 0.].into()))));
+
+    let polygons = rx.try_iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedPolygon(_, polygon, _) => Some(("inserted", polygon.vertices.len())),
+      RenderUpdateEvent::UpdatedPolygon(_, polygon) => Some(("updated", polygon.vertices.len())),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(polygons, vec![("inserted", 3), ("updated", 3)]);
+  }
+
+  #[test]
+  fn test_vector_is_sent_and_follows_its_tip() {
+    let (tx, rx) = mpsc::channel();
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    builder.add_thread_local(SenderSystem::new(tx));
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let mut step = |world: &mut World, command: Command| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent { command, event_id: None });
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+    for (x, y) in &[(0., 0.), (4., 0.)] {
+      step(&mut world, Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![*x, *y].into()))));
+    }
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>()).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    step(&mut world, Command::VectorInsert(InsertVectorEvent::InsertVector(SymbolicVector(points[0], points[1]))));
+    let old = SymbolicPoint::Free(vec2![4., 0.].into());
+    step(&mut world, Command::Update(UpdateEvent::UpdatePoint(points[1], old, SymbolicPoint::Free(vec2![8., 0.].into()))));
+
+    let vectors = rx.try_iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedVector(_, vector, _) => Some(("inserted", vector)),
+      RenderUpdateEvent::UpdatedVector(_, vector) => Some(("updated", vector)),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(vectors.len(), 2);
+    assert_eq!(vectors[0].0, "inserted");
+    assert_eq!(vectors[1].0, "updated");
+    assert_ne!(vectors[0].1.to, vectors[1].1.to);
+    assert_eq!(vectors[0].1.from, vectors[1].1.from);
+  }
+
+  #[test]
+  fn test_arc_is_sent_and_follows_its_points() {
+    let (tx, rx) = mpsc::channel();
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    builder.add_thread_local(SenderSystem::new(tx));
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let mut step = |world: &mut World, command: Command| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent { command, event_id: None });
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+    for (x, y) in &[(0., 0.), (2., 0.), (0., 2.)] {
+      step(&mut world, Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![*x, *y].into()))));
+    }
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>()).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    step(&mut world, Command::ArcInsert(InsertArcEvent::InsertArc(SymbolicArc::CenterTwoPoint(points[0], points[1], points[2]))));
+    let old = SymbolicPoint::Free(vec2![2., 0.].into());
+    step(&mut world, Command::Update(UpdateEvent::UpdatePoint(points[1], old, SymbolicPoint::Free(vec2![4., 0.].into()))));
+
+    let arcs = rx.try_iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedArc(_, arc, _) => Some(("inserted", arc)),
+      RenderUpdateEvent::UpdatedArc(_, arc) => Some(("updated", arc)),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(arcs.len(), 2);
+    assert_eq!(arcs[0].0, "inserted");
+    assert_eq!(arcs[1].0, "updated");
+    assert_eq!(arcs[0].1.center, arcs[1].1.center);
+    assert!((arcs[1].1.radius.0 - arcs[0].1.radius.0 * 2.).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_conic_is_sent_and_follows_its_points() {
+    let (tx, rx) = mpsc::channel();
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    builder.add_thread_local(SenderSystem::new(tx));
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let mut step = |world: &mut World, command: Command| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent { command, event_id: None });
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+    for (x, y) in &[(-3., 0.), (3., 0.), (0., 4.)] {
+      step(&mut world, Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![*x, *y].into()))));
+    }
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>()).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    step(&mut world, Command::ConicInsert(InsertConicEvent::InsertConic(SymbolicConic::Ellipse(points[0], points[1], points[2]))));
+    let old = SymbolicPoint::Free(vec2![0., 4.].into());
+    step(&mut world, Command::Update(UpdateEvent::UpdatePoint(points[2], old, SymbolicPoint::Free(vec2![0., 8.].into()))));
+
+    let conics = rx.try_iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedConic(_, conic, _) => Some(("inserted", conic)),
+      RenderUpdateEvent::UpdatedConic(_, conic) => Some(("updated", conic)),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(conics.len(), 2);
+    assert_eq!(conics[0].0, "inserted");
+    assert_eq!(conics[1].0, "updated");
+    assert_eq!(conics[0].1.center, conics[1].1.center);
+    assert!(conics[1].1.ry.0 > conics[0].1.ry.0);
+  }
+
+  #[test]
+  fn test_text_is_sent_and_follows_its_anchor() {
+    let (tx, rx) = mpsc::channel();
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    builder.add_thread_local(SenderSystem::new(tx));
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let mut step = |world: &mut World, command: Command| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent { command, event_id: None });
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+    step(&mut world, Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![0., 0.].into()))));
+    let point = (&world.entities(), &world.read_storage::<SymbolicPoint>()).join().map(|(ent, _)| ent).next().unwrap();
+    let anchor = TextAnchor::Entity(point, vec2![1., 1.].into());
+    step(&mut world, Command::TextInsert(InsertTextEvent::InsertText(SymbolicText { text: "P".to_string(), anchor })));
+    let old = SymbolicPoint::Free(vec2![0., 0.].into());
+    step(&mut world, Command::Update(UpdateEvent::UpdatePoint(point, old, SymbolicPoint::Free(vec2![2., 0.].into()))));
+
+    let texts = rx.try_iter().filter_map(|event| match event {
+      RenderUpdateEvent::InsertedText(_, text, _) => Some(("inserted", text)),
+      RenderUpdateEvent::UpdatedText(_, text) => Some(("updated", text)),
+      _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(texts.len(), 2);
+    assert_eq!(texts[0].0, "inserted");
+    assert_eq!(texts[1].0, "updated");
+    assert_eq!(texts[1].1.text, "P");
+    assert!(texts[1].1.position.0.x > texts[0].1.position.0.x);
+  }
+}
@@ -8,7 +8,7 @@ use neon::result::JsResult;
 use neon::task::Task;
 use neon::types::{JsUndefined, JsValue};
 
-use core_lib::{math::*, utilities::*, components::styles::*};
+use core_lib::{math::*, utilities::*, components::{screen_shapes::*, styles::*}};
 use crate::events::*;
 use super::*;
 
@@ -71,7 +71,7 @@ impl Task for EventEmitterTask {
 
     macro_rules! point_style {
       ($point_style: expr) => {{
-        let PointStyle { color, radius, border_color, border_width } = $point_style;
+        let PointStyle { color, radius, border_color, border_width, fill, marker_shape, .. } = $point_style.flatten_alpha();
         let style = cx.empty_object();
         let event_style_color = cx.number(color_to_hex(color));
         let event_style_alpha = cx.number(color.a);
@@ -79,12 +79,16 @@ impl Task for EventEmitterTask {
         let event_style_border_alpha = cx.number(border_color.a);
         let event_style_radius = cx.number(radius);
         let event_style_border_width = cx.number(border_width);
+        let event_style_hollow = cx.boolean(fill == PointFill::Hollow || marker_shape == MarkerShape::HollowCircle);
+        let event_style_marker_shape = cx.string(marker_shape_to_str(marker_shape));
         style.set(&mut cx, "color", event_style_color)?;
         style.set(&mut cx, "alpha", event_style_alpha)?;
         style.set(&mut cx, "borderColor", event_style_border_color)?;
         style.set(&mut cx, "borderAlpha", event_style_border_alpha)?;
         style.set(&mut cx, "radius", event_style_radius)?;
         style.set(&mut cx, "borderWidth", event_style_border_width)?;
+        style.set(&mut cx, "hollow", event_style_hollow)?;
+        style.set(&mut cx, "markerShape", event_style_marker_shape)?;
         style
       }};
     }
@@ -103,14 +107,22 @@ impl Task for EventEmitterTask {
 
     macro_rules! line_style {
       ($line_style: expr) => {{
-        let LineStyle { color, width } = $line_style;
+        let LineStyle { color, width, marks, dash, .. } = $line_style.flatten_alpha();
         let rgb = cx.number(color_to_hex(color));
         let alpha = cx.number(color.a);
         let width = cx.number(width);
+        let marks = cx.number(marks.0);
+        let lengths = cx.empty_array();
+        for (i, length) in dash.lengths().iter().enumerate() {
+          let length = cx.number(*length);
+          lengths.set(&mut cx, i as u32, length)?;
+        }
         let style = cx.empty_object();
         style.set(&mut cx, "color", rgb)?;
         style.set(&mut cx, "alpha", alpha)?;
         style.set(&mut cx, "width", width)?;
+        style.set(&mut cx, "marks", marks)?;
+        style.set(&mut cx, "dash", lengths)?;
         style
       }};
     }
@@ -129,7 +141,7 @@ impl Task for EventEmitterTask {
 
     macro_rules! circle_style {
       ($circle_style: expr) => {{
-        let CircleStyle { fill, border } = $circle_style;
+        let CircleStyle { fill, border, .. } = $circle_style.flatten_alpha();
         let circle_style = cx.empty_object();
         let fill_rgb = cx.number(color_to_hex(fill));
         let fill_alpha = cx.number(fill.a);
@@ -159,7 +171,7 @@ impl Task for EventEmitterTask {
 
     macro_rules! rect_style {
       ($rect_style: expr) => {{
-        let RectangleStyle { fill, border } = $rect_style;
+        let RectangleStyle { fill, border, .. } = $rect_style.flatten_alpha();
         let rect_style = cx.empty_object();
         let fill_rgb = cx.number(color_to_hex(fill));
         let fill_alpha = cx.number(fill.a);
@@ -171,6 +183,158 @@ impl Task for EventEmitterTask {
       }};
     }
 
+    macro_rules! polygon {
+      ($polygon: expr) => {{
+        let ScreenPolygon { vertices } = $polygon;
+        let polygon = cx.empty_array();
+        for (i, vertex) in vertices.into_iter().enumerate() {
+          let vertex = position!(vertex);
+          polygon.set(&mut cx, i as u32, vertex)?;
+        }
+        polygon
+      }};
+    }
+
+    macro_rules! polygon_style {
+      ($polygon_style: expr) => {{
+        let PolygonStyle { fill, border, .. } = $polygon_style.flatten_alpha();
+        let polygon_style = cx.empty_object();
+        let fill_rgb = cx.number(color_to_hex(fill));
+        let fill_alpha = cx.number(fill.a);
+        let border = line_style!(border);
+        polygon_style.set(&mut cx, "fill", fill_rgb)?;
+        polygon_style.set(&mut cx, "fillAlpha", fill_alpha)?;
+        polygon_style.set(&mut cx, "border", border)?;
+        polygon_style
+      }};
+    }
+
+    macro_rules! vector {
+      ($vector: expr) => {{
+        let ScreenVector { from, to } = $vector;
+        let vector = cx.empty_object();
+        let from = position!(from);
+        let to = position!(to);
+        vector.set(&mut cx, "from", from)?;
+        vector.set(&mut cx, "to", to)?;
+        vector
+      }};
+    }
+
+    macro_rules! vector_style {
+      ($vector_style: expr) => {{
+        let VectorStyle { color, width, head_length, .. } = $vector_style.flatten_alpha();
+        let rgb = cx.number(color_to_hex(color));
+        let alpha = cx.number(color.a);
+        let width = cx.number(width);
+        let head_length = cx.number(head_length);
+        let style = cx.empty_object();
+        style.set(&mut cx, "color", rgb)?;
+        style.set(&mut cx, "alpha", alpha)?;
+        style.set(&mut cx, "width", width)?;
+        style.set(&mut cx, "headLength", head_length)?;
+        style
+      }};
+    }
+
+    macro_rules! arc {
+      ($arc: expr) => {{
+        let ScreenArc { center, radius, start, sweep } = $arc;
+        let arc = cx.empty_object();
+        let center = position!(center);
+        let radius = cx.number(radius);
+        let start = cx.number(start);
+        let sweep = cx.number(sweep);
+        arc.set(&mut cx, "center", center)?;
+        arc.set(&mut cx, "radius", radius)?;
+        arc.set(&mut cx, "start", start)?;
+        arc.set(&mut cx, "sweep", sweep)?;
+        arc
+      }};
+    }
+
+    macro_rules! arc_style {
+      ($arc_style: expr) => {{
+        let ArcStyle { color, width, .. } = $arc_style.flatten_alpha();
+        let rgb = cx.number(color_to_hex(color));
+        let alpha = cx.number(color.a);
+        let width = cx.number(width);
+        let style = cx.empty_object();
+        style.set(&mut cx, "color", rgb)?;
+        style.set(&mut cx, "alpha", alpha)?;
+        style.set(&mut cx, "width", width)?;
+        style
+      }};
+    }
+
+    macro_rules! conic {
+      ($conic: expr) => {{
+        let ScreenConic { center, rx, ry, rotation } = $conic;
+        let conic = cx.empty_object();
+        let center = position!(center);
+        let rx = cx.number(rx);
+        let ry = cx.number(ry);
+        let rotation = cx.number(rotation);
+        conic.set(&mut cx, "center", center)?;
+        conic.set(&mut cx, "rx", rx)?;
+        conic.set(&mut cx, "ry", ry)?;
+        conic.set(&mut cx, "rotation", rotation)?;
+        conic
+      }};
+    }
+
+    macro_rules! conic_style {
+      ($conic_style: expr) => {{
+        let ConicStyle { color, width, .. } = $conic_style.flatten_alpha();
+        let rgb = cx.number(color_to_hex(color));
+        let alpha = cx.number(color.a);
+        let width = cx.number(width);
+        let style = cx.empty_object();
+        style.set(&mut cx, "color", rgb)?;
+        style.set(&mut cx, "alpha", alpha)?;
+        style.set(&mut cx, "width", width)?;
+        style
+      }};
+    }
+
+    macro_rules! text {
+      ($text: expr) => {{
+        let ScreenText { position, text: content } = $text;
+        let text = cx.empty_object();
+        let position = position!(position);
+        let content = cx.string(content);
+        text.set(&mut cx, "position", position)?;
+        text.set(&mut cx, "text", content)?;
+        text
+      }};
+    }
+
+    macro_rules! text_style {
+      ($text_style: expr) => {{
+        let TextStyle { color, font_size, .. } = $text_style.flatten_alpha();
+        let rgb = cx.number(color_to_hex(color));
+        let alpha = cx.number(color.a);
+        let font_size = cx.number(font_size);
+        let style = cx.empty_object();
+        style.set(&mut cx, "color", rgb)?;
+        style.set(&mut cx, "alpha", alpha)?;
+        style.set(&mut cx, "fontSize", font_size)?;
+        style
+      }};
+    }
+
+    macro_rules! label {
+      ($label: expr) => {{
+        let ScreenLabel { position, text } = $label;
+        let label = cx.empty_object();
+        let position = position!(position);
+        let text = cx.string(text);
+        label.set(&mut cx, "position", position)?;
+        label.set(&mut cx, "text", text)?;
+        label
+      }};
+    }
+
     match event {
       RenderUpdateEvent::None => (),
       RenderUpdateEvent::InsertedPoint(ent, scrn_point, point_style) => {
@@ -181,7 +345,8 @@ impl Task for EventEmitterTask {
         let style = point_style!(point_style);
         o.set(&mut cx, "style", style)?;
       },
-      RenderUpdateEvent::InsertedLine(ent, scrn_line, line_style) => {
+      RenderUpdateEvent::InsertedLine(ent, scrn_line, line_style)
+      | RenderUpdateEvent::InsertedBackgroundLine(ent, scrn_line, line_style) => {
         let entity = entity!(ent);
         o.set(&mut cx, "entity", entity)?;
         let line = line!(scrn_line);
@@ -265,6 +430,122 @@ impl Task for EventEmitterTask {
         let entity = entity!(ent);
         o.set(&mut cx, "entity", entity)?;
       },
+      RenderUpdateEvent::InsertedLabel(ent, scrn_label) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let label = label!(scrn_label);
+        o.set(&mut cx, "label", label)?;
+      },
+      RenderUpdateEvent::UpdatedLabel(ent, scrn_label) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let label = label!(scrn_label);
+        o.set(&mut cx, "label", label)?;
+      },
+      RenderUpdateEvent::RemovedLabel(ent) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+      },
+      RenderUpdateEvent::InsertedPolygon(ent, scrn_polygon, polygon_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let polygon = polygon!(scrn_polygon);
+        o.set(&mut cx, "polygon", polygon)?;
+        let style = polygon_style!(polygon_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::UpdatedPolygon(ent, scrn_polygon) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let polygon = polygon!(scrn_polygon);
+        o.set(&mut cx, "polygon", polygon)?;
+      },
+      RenderUpdateEvent::UpdatedPolygonStyle(ent, polygon_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let style = polygon_style!(polygon_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::InsertedVector(ent, scrn_vector, vector_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let vector = vector!(scrn_vector);
+        o.set(&mut cx, "vector", vector)?;
+        let style = vector_style!(vector_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::UpdatedVector(ent, scrn_vector) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let vector = vector!(scrn_vector);
+        o.set(&mut cx, "vector", vector)?;
+      },
+      RenderUpdateEvent::UpdatedVectorStyle(ent, vector_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let style = vector_style!(vector_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::InsertedArc(ent, scrn_arc, arc_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let arc = arc!(scrn_arc);
+        o.set(&mut cx, "arc", arc)?;
+        let style = arc_style!(arc_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::UpdatedArc(ent, scrn_arc) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let arc = arc!(scrn_arc);
+        o.set(&mut cx, "arc", arc)?;
+      },
+      RenderUpdateEvent::UpdatedArcStyle(ent, arc_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let style = arc_style!(arc_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::InsertedConic(ent, scrn_conic, conic_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let conic = conic!(scrn_conic);
+        o.set(&mut cx, "conic", conic)?;
+        let style = conic_style!(conic_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::UpdatedConic(ent, scrn_conic) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let conic = conic!(scrn_conic);
+        o.set(&mut cx, "conic", conic)?;
+      },
+      RenderUpdateEvent::UpdatedConicStyle(ent, conic_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let style = conic_style!(conic_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::InsertedText(ent, scrn_text, text_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let text = text!(scrn_text);
+        o.set(&mut cx, "text", text)?;
+        let style = text_style!(text_style);
+        o.set(&mut cx, "style", style)?;
+      },
+      RenderUpdateEvent::UpdatedText(ent, scrn_text) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let text = text!(scrn_text);
+        o.set(&mut cx, "text", text)?;
+      },
+      RenderUpdateEvent::UpdatedTextStyle(ent, text_style) => {
+        let entity = entity!(ent);
+        o.set(&mut cx, "entity", entity)?;
+        let style = text_style!(text_style);
+        o.set(&mut cx, "style", style)?;
+      },
     }
     Ok(o.upcast())
   }
@@ -46,9 +46,11 @@ pub fn u32_to_key(k: u32) -> Key {
     90 => Key::Z,
     91 => Key::LCommand,
     93 => Key::RCommand,
+    186 => Key::Semicolon,
     187 => Key::Equals,
     189 => Key::Minus,
     220 => Key::Backslash,
+    222 => Key::Quote,
     _ => Key::Unknown,
   }
 }
\ No newline at end of file
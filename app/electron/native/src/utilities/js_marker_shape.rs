@@ -0,0 +1,12 @@
+use core_lib::components::styles::MarkerShape;
+
+pub fn marker_shape_to_str(marker_shape: MarkerShape) -> &'static str {
+  match marker_shape {
+    MarkerShape::FilledCircle => "filledCircle",
+    MarkerShape::HollowCircle => "hollowCircle",
+    MarkerShape::Square => "square",
+    MarkerShape::Cross => "cross",
+    MarkerShape::Plus => "plus",
+    MarkerShape::Diamond => "diamond",
+  }
+}
\ No newline at end of file
@@ -1,7 +1,9 @@
 mod event_emitter_task;
 mod js_color;
 mod js_key;
+mod js_marker_shape;
 
 pub use event_emitter_task::*;
 pub use js_color::color_to_hex;
-pub use js_key::u32_to_key;
\ No newline at end of file
+pub use js_key::u32_to_key;
+pub use js_marker_shape::marker_shape_to_str;
\ No newline at end of file
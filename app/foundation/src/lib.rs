@@ -7,12 +7,33 @@ extern crate specs;
 mod utilities;
 mod window_system;
 
+use core_lib::utilities::LineClipCache;
 use piston_window::*;
+use std::collections::HashMap;
 pub use window_system::WindowSystem as PistonWindowSystem;
 
+/// There's no font shipped with the app, the first of these found is used for the labels
+static FONT_PATHS: [&str; 4] = [
+  "/Library/Fonts/Arial.ttf",
+  "/System/Library/Fonts/Supplemental/Arial.ttf",
+  "C:\\Windows\\Fonts\\arial.ttf",
+  "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+];
+
 pub fn new_piston_window() -> PistonWindowSystem {
-  let window: PistonWindow = WindowSettings::new("Geometry Sketchpad", core_lib::resources::WINDOW_SIZE)
+  let mut window: PistonWindow = WindowSettings::new(window_system::WINDOW_TITLE, core_lib::resources::WINDOW_SIZE)
+    .samples(4) // Multisampling, so that the shapes are anti-aliased
     .build()
     .unwrap();
-  window_system::WindowSystem { window }
+  let glyphs = FONT_PATHS.iter().find_map(|path| window.load_font(path).ok());
+  let texture_context = window.create_texture_context();
+  window_system::WindowSystem {
+    window,
+    glyphs,
+    texture_context,
+    underlay_textures: HashMap::new(),
+    line_clip_cache: LineClipCache::default(),
+    status_event_reader: None,
+    pressed_on_overlay: false,
+  }
 }
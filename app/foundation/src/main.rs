@@ -6,9 +6,22 @@ extern crate specs;
 // Foundation library providing "new_piston_window"
 extern crate geopad_foundation;
 
-use core_ui::{resources::*, setup_core_ui};
+#[cfg(feature = "scripting")]
+use core_lib::systems::scripting::ScriptSystem;
+use core_lib::{
+  components::underlays::UnderlayPlacement,
+  events::*,
+  io::read_session_log,
+  resources::Viewport,
+  systems::data_managers::SessionRecorder,
+  utilities::{replay, ReplaySpeed, VirtualPosition},
+};
+use core_ui::{resources::*, setup_core_ui, utilities::FixedTimestep};
 use geopad_foundation::new_piston_window;
 use specs::prelude::*;
+use std::{fs, path::PathBuf, thread, time::Instant};
+
+static FIXED_TIMESTEP_RATE: u32 = 60; // Hz, used when running with `--fixed-timestep`
 
 fn main() {
   let mut world = World::new();
@@ -17,13 +30,111 @@ fn main() {
   // Setup the core ui
   setup_core_ui(&mut builder);
 
+  // With `--record <path>`, the session is logged once every other system is done with the frame
+  if let Some(path) = path_argument("--record") {
+    builder.add_barrier();
+    builder.add(SessionRecorder::new(path), "session_recorder", &[]);
+  }
+
+  // With `--script <path>`, the Lua script runs on the sketch before every frame gets rendered
+  #[cfg(feature = "scripting")]
+  {
+    if let Some(path) = path_argument("--script") {
+      match fs::read_to_string(&path) {
+        Ok(source) => builder.add_thread_local(ScriptSystem::new(path.display().to_string(), source)),
+        Err(err) => eprintln!("Cannot read {}: {}", path.display(), err),
+      }
+    }
+  }
+
+  // With `--underlay <path>`, the image is shown beneath the sketch, to trace over it
+  let mut window_system = new_piston_window();
+  let underlay = path_argument("--underlay").map(|path| {
+    window_system
+      .load_underlay(&path)
+      .map_err(|err| format!("Cannot load {}: {}", path.display(), err))
+  });
+
   // Add the window system and build the dispatcher
-  builder.add_thread_local(new_piston_window());
+  builder.add_thread_local(window_system);
 
   // Build the dispatcher
   let mut dispatcher = builder.build();
   dispatcher.setup(&mut world);
+
+  // With `--keymap <path>`, the shortcuts listed in the TOML file replace the default ones
+  if let Some(path) = path_argument("--keymap") {
+    let key_map = fs::read_to_string(&path)
+      .map_err(|err| err.to_string())
+      .and_then(|source| KeyMap::from_toml(&source));
+    match key_map {
+      Ok(key_map) => world.insert(key_map),
+      Err(err) => eprintln!("Cannot load the key map {}: {}", path.display(), err),
+    }
+  }
+
+  // The underlay starts at the center of the window, a pixel of the image on a pixel of the screen
+  match underlay {
+    Some(Ok(underlay)) => {
+      let placement = {
+        let viewport = world.fetch::<Viewport>();
+        UnderlayPlacement::centered_at(
+          &underlay,
+          VirtualPosition(viewport.virtual_center),
+          1. / viewport.virtual_to_screen_scale(),
+        )
+      };
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+        command: Command::UnderlayInsert(InsertUnderlayEvent::InsertUnderlay(underlay, placement)),
+        event_id: None,
+      });
+    }
+    Some(Err(err)) => eprintln!("{}", err),
+    None => (),
+  }
+
+  // With `--replay <path>`, the logged session is played again in real time before handing over
+  if let Some(path) = path_argument("--replay") {
+    let log = fs::read_to_string(&path)
+      .map_err(|err| err.to_string())
+      .and_then(|text| read_session_log(&text, &world.entities()).map_err(|err| format!("{:?}", err)));
+    match log {
+      Ok(log) => replay(&mut world, &mut dispatcher, &log, ReplaySpeed::RealTime),
+      Err(err) => eprintln!("Cannot replay {}: {}", path.display(), err),
+    }
+  }
+
+  if std::env::args().any(|arg| arg == "--fixed-timestep") {
+    run_fixed_timestep(&mut world, &mut dispatcher);
+  } else {
+    while !world.fetch::<ExitState>().is_exiting() {
+      dispatcher.dispatch(&mut world);
+    }
+  }
+}
+
+/// The path following the flag among the arguments
+fn path_argument(flag: &str) -> Option<PathBuf> {
+  let mut args = std::env::args().skip_while(|arg| arg != flag);
+  args.next()?;
+  args.next().map(PathBuf::from)
+}
+
+/// Dispatches at a fixed rate, sleeping between the frames. The delta time is always the fixed
+/// step so that the animations don't depend on the frame rate
+fn run_fixed_timestep(world: &mut World, dispatcher: &mut Dispatcher) {
+  let mut timestep = FixedTimestep::new(FIXED_TIMESTEP_RATE);
+  world.insert(DeltaTime::fixed(timestep.step().as_secs_f64()));
+  let mut last = Instant::now();
   while !world.fetch::<ExitState>().is_exiting() {
-    dispatcher.dispatch(&mut world);
+    let now = Instant::now();
+    for _ in 0..timestep.advance(now - last) {
+      dispatcher.dispatch(world);
+      if world.fetch::<ExitState>().is_exiting() {
+        return;
+      }
+    }
+    last = now;
+    thread::sleep(timestep.until_next_step());
   }
 }
@@ -1,6 +1,6 @@
 use core_lib::{events::*, math::*};
-use core_ui::{events::*, resources::*};
-use piston_window::{Button, ButtonArgs, ButtonState, Input, Motion, MouseButton, ResizeArgs};
+use core_ui::{events::*, resources::*, utilities::*};
+use piston_window::{Button, ButtonArgs, ButtonState, Input, Motion, MouseButton, ResizeArgs, Touch};
 use specs::prelude::*;
 use std::time::SystemTime;
 
@@ -46,7 +46,7 @@ pub fn handle_input<'a>(
         }
         Button::Keyboard(piston_key) => {
           let key = piston_key_to_key(piston_key, scancode);
-          input_state.keyboard.set(key, is_pressed)
+          input_state.set_key(key, is_pressed)
         }
         _ => (),
       }
@@ -66,15 +66,71 @@ pub fn handle_input<'a>(
           }
         }
       }
+      // Only for the gestures and the pressure, the desktop already turns a single finger into the
+      // mouse
+      Motion::Touch(args) => {
+        let phase = match args.touch {
+          Touch::Start => TouchPhase::Start,
+          Touch::Move => TouchPhase::Move,
+          Touch::End | Touch::Cancel => TouchPhase::End,
+        };
+        input_state.touch(args.id, phase, From::<Vector2>::from(args.position().into()));
+        // Pens and styluses come as touches telling how hard they press, none without a sensor
+        input_state.pen_pressure = match phase {
+          TouchPhase::End => None,
+          _ if args.pressure() > 0. => Some(args.pressure()),
+          _ => input_state.pen_pressure,
+        };
+      }
       _ => (),
     },
     Input::Resize(ResizeArgs { window_size, .. }) => {
       viewport_event_channel.single_write(ViewportEvent::Resize(Vector2::from(window_size)));
     }
+    Input::Text(text) => input_state.type_text(&text),
+    Input::Focus(focus) => input_state.set_focus(focus, mouse_event_channel),
     _ => (),
   }
 }
 
+/// A click on the overlay goes to its widget instead of the sketch, and so does the release of the
+/// button pressed there. Returns whether the overlay took the input
+pub fn handle_overlay_input<'a>(
+  input: &Input,
+  widgets: &[OverlayWidget],
+  pressed_on_overlay: &mut bool,
+  input_state: &Write<'a, InputState>,
+  tool_change_event_channel: &mut Write<'a, ToolChangeEventChannel>,
+  command_event_channel: &mut Write<'a, CommandEventChannel>,
+) -> bool {
+  match input {
+    Input::Button(ButtonArgs {
+      state,
+      button: Button::Mouse(MouseButton::Left),
+      ..
+    }) => match state {
+      ButtonState::Press if is_over_overlay(widgets, input_state.mouse_abs_pos) => {
+        match overlay_action_at(widgets, input_state.mouse_abs_pos) {
+          Some(OverlayAction::SetTool(tool)) => tool_change_event_channel.single_write(ToolChangeEvent(*tool)),
+          Some(OverlayAction::Command(command)) => command_event_channel.single_write(CommandEvent {
+            command: command.clone(),
+            event_id: None,
+          }),
+          None => (),
+        }
+        *pressed_on_overlay = true;
+        true
+      }
+      ButtonState::Release if *pressed_on_overlay => {
+        *pressed_on_overlay = false;
+        true
+      }
+      _ => false,
+    },
+    _ => false,
+  }
+}
+
 pub fn handle_dt_update<'a>(dt: f64, delta_time: &mut Write<'a, DeltaTime>) {
   delta_time.set(dt);
 }
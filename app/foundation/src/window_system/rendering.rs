@@ -1,32 +1,84 @@
 use core_lib::{
-  components::{markers::*, screen_shapes::*, styles::*},
+  components::{freehand::*, markers::*, screen_shapes::*, styles::*, symbolics::*, underlays::*},
   math::*,
-  resources::Viewport,
+  resources::{LineClipMargin, Theme, ToScreen, Viewport},
   utilities::*,
 };
+use core_ui::utilities::OverlayWidget;
 use piston_window::{
-  circle_arc, clear, ellipse, line_from_to, rectangle, Context, Event as PistonEvent, G2d, PistonWindow,
+  circle_arc, clear, ellipse, line_from_to, polygon, rectangle, text::Text, Context, Event as PistonEvent, G2d,
+  G2dTexture, Glyphs, Image, ImageSize, PistonWindow, Transformed,
 };
 use specs::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+static TICK_LENGTH: f64 = 10.0; // Pixel
+static TICK_SPACING: f64 = 4.0; // Pixel
+static CONIC_SEGMENTS: usize = 64;
+static LABEL_FONT_SIZE: u32 = 14; // Pixel
+static HALO_WIDTH: f64 = 3.0; // Pixel, added to the half width of the hovered curves
+static HALO_ALPHA: f32 = 0.4;
+static OVERLAY_FONT_SIZE: u32 = 13; // Pixel
+static UNSOLVABLE_ALPHA: f64 = 0.25; // Of the geometries drawn where they were before becoming undefined
+
+/// The style flattened, faded out when the geometry is unsolvable to gray it out
+macro_rules! faded {
+  ($style:expr, $unsolvable:expr) => {{
+    let mut style = $style;
+    if $unsolvable.is_some() {
+      style.alpha *= UNSOLVABLE_ALPHA;
+    }
+    style.flatten_alpha()
+  }};
+}
 
 pub fn render<'a>(
   window: &mut PistonWindow,
+  mut glyphs: Option<&mut Glyphs>,
+  underlay_textures: &HashMap<PathBuf, Option<G2dTexture>>,
   event: &PistonEvent,
   viewport: &Viewport,
+  theme: &Theme,
+  line_clip_margin: &LineClipMargin,
+  line_clip_cache: &mut LineClipCache<Entity>,
+  entities: &Entities<'a>,
   scrn_points: &ReadStorage<'a, ScreenPoint>,
   scrn_lines: &ReadStorage<'a, ScreenLine>,
   scrn_circles: &ReadStorage<'a, ScreenCircle>,
+  scrn_arcs: &ReadStorage<'a, ScreenArc>,
+  scrn_conics: &ReadStorage<'a, ScreenConic>,
   scrn_rects: &ReadStorage<'a, ScreenRectangle>,
+  scrn_polygons: &ReadStorage<'a, ScreenPolygon>,
+  scrn_vectors: &ReadStorage<'a, ScreenVector>,
+  scrn_strokes: &ReadStorage<'a, ScreenFreehandStroke>,
   point_styles: &ReadStorage<'a, PointStyle>,
   line_styles: &ReadStorage<'a, LineStyle>,
   circle_styles: &ReadStorage<'a, CircleStyle>,
+  arc_styles: &ReadStorage<'a, ArcStyle>,
+  conic_styles: &ReadStorage<'a, ConicStyle>,
   rect_styles: &ReadStorage<'a, RectangleStyle>,
+  polygon_styles: &ReadStorage<'a, PolygonStyle>,
+  vector_styles: &ReadStorage<'a, VectorStyle>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  labels: &ReadStorage<'a, Label>,
+  underlays: &ReadStorage<'a, ImageUnderlay>,
+  underlay_placements: &ReadStorage<'a, UnderlayPlacement>,
+  scrn_texts: &ReadStorage<'a, ScreenText>,
+  text_styles: &ReadStorage<'a, TextStyle>,
   selecteds: &ReadStorage<'a, Selected>,
+  hovereds: &ReadStorage<'a, Hovered>,
   hiddens: &ReadStorage<'a, Hidden>,
+  backgrounds: &ReadStorage<'a, Background>,
+  unsolvables: &ReadStorage<'a, Unsolvable>,
+  overlay: &[OverlayWidget],
 ) {
-  window.draw_2d(event, |context, graphics, _device| {
+  // The lines are clipped only when they or the viewport changed since the last frame
+  let clip_bounds = line_clip_margin.clip_aabb(viewport);
+
+  window.draw_2d(event, |context, graphics, device| {
     // Clean the screen first
-    clear(Color::white().into(), graphics);
+    clear(theme.background.into(), graphics);
 
     // NOTE: The later we draw, the higher the shape will be in the layers
     // i.e. The later we draw, the shape will be more on top of other shapes
@@ -35,48 +87,308 @@ pub fn render<'a>(
     // Note that currently we only have select rectangles so we draw rectangles on the most
     // top.
 
+    // The underlays go under everything else, the grid included
+    for (underlay, placement, _) in (underlays, underlay_placements, !hiddens).join() {
+      if let Some(Some(texture)) = underlay_textures.get(&underlay.path) {
+        render_underlay(underlay, placement, texture, viewport, context, graphics);
+      }
+    }
+
+    // The grid goes under all the geometry
+    for (ent, line, style, _) in (entities, scrn_lines, line_styles, backgrounds).join() {
+      render_line(
+        line,
+        line_clip_cache.clip(ent, *line, clip_bounds),
+        &style.flatten_alpha(),
+        false,
+        theme,
+        context,
+        graphics,
+      );
+    }
+
+    // Polygons are filled areas, they go below the rest
+    for (scrn_polygon, style, unsolvable, _) in (scrn_polygons, polygon_styles, unsolvables.maybe(), !hiddens).join() {
+      render_polygon(scrn_polygon, &faded!(*style, unsolvable), context, graphics);
+    }
+
+    // The hovered curves get a halo under all of them
+    for (circle, style, _, _) in (scrn_circles, circle_styles, hovereds, !hiddens).join() {
+      let halo = CircleStyle {
+        fill: Color::transparent(),
+        border: halo_style(style.border.width, theme),
+        alpha: 1.0,
+      };
+      render_circle(circle, &halo, false, theme, context, graphics);
+    }
+    for (arc, style, _, _) in (scrn_arcs, arc_styles, hovereds, !hiddens).join() {
+      let LineStyle { color, width, .. } = halo_style(style.width, theme);
+      render_arc(
+        arc,
+        &ArcStyle {
+          color,
+          width,
+          alpha: 1.0,
+        },
+        false,
+        theme,
+        context,
+        graphics,
+      );
+    }
+    for (conic, style, _, _) in (scrn_conics, conic_styles, hovereds, !hiddens).join() {
+      let LineStyle { color, width, .. } = halo_style(style.width, theme);
+      render_conic(
+        conic,
+        &ConicStyle {
+          color,
+          width,
+          alpha: 1.0,
+        },
+        false,
+        theme,
+        context,
+        graphics,
+      );
+    }
+    for (ent, line, style, _, _) in (entities, scrn_lines, line_styles, hovereds, !hiddens).join() {
+      render_line(
+        line,
+        line_clip_cache.clip(ent, *line, clip_bounds),
+        &halo_style(style.width, theme),
+        false,
+        theme,
+        context,
+        graphics,
+      );
+    }
+
     // First draw the circles
-    for (circle, style, _, _) in (scrn_circles, circle_styles, !selecteds, !hiddens).join() {
-      render_circle(circle, style, false, context, graphics);
+    for (circle, style, unsolvable, _, _) in
+      (scrn_circles, circle_styles, unsolvables.maybe(), !selecteds, !hiddens).join()
+    {
+      render_circle(circle, &faded!(*style, unsolvable), false, theme, context, graphics);
     }
-    for (circle, style, _, _) in (scrn_circles, circle_styles, selecteds, !hiddens).join() {
-      render_circle(circle, style, true, context, graphics);
+    for (circle, style, unsolvable, _, _) in
+      (scrn_circles, circle_styles, unsolvables.maybe(), selecteds, !hiddens).join()
+    {
+      render_circle(circle, &faded!(*style, unsolvable), true, theme, context, graphics);
+    }
+    for (arc, style, selected, unsolvable, _) in
+      (scrn_arcs, arc_styles, selecteds.maybe(), unsolvables.maybe(), !hiddens).join()
+    {
+      render_arc(
+        arc,
+        &faded!(*style, unsolvable),
+        selected.is_some(),
+        theme,
+        context,
+        graphics,
+      );
+    }
+    for (conic, style, selected, unsolvable, _) in (
+      scrn_conics,
+      conic_styles,
+      selecteds.maybe(),
+      unsolvables.maybe(),
+      !hiddens,
+    )
+      .join()
+    {
+      render_conic(
+        conic,
+        &faded!(*style, unsolvable),
+        selected.is_some(),
+        theme,
+        context,
+        graphics,
+      );
     }
 
     // Then, draw the lines
-    for (line, style, _, _) in (scrn_lines, line_styles, !selecteds, !hiddens).join() {
-      render_line(line, style, false, viewport, context, graphics);
+    for (ent, line, style, unsolvable, _, _, _) in (
+      entities,
+      scrn_lines,
+      line_styles,
+      unsolvables.maybe(),
+      !selecteds,
+      !hiddens,
+      !backgrounds,
+    )
+      .join()
+    {
+      render_line(
+        line,
+        line_clip_cache.clip(ent, *line, clip_bounds),
+        &faded!(*style, unsolvable),
+        false,
+        theme,
+        context,
+        graphics,
+      );
+    }
+    for (ent, line, style, unsolvable, _, _) in (
+      entities,
+      scrn_lines,
+      line_styles,
+      unsolvables.maybe(),
+      selecteds,
+      !hiddens,
+    )
+      .join()
+    {
+      render_line(
+        line,
+        line_clip_cache.clip(ent, *line, clip_bounds),
+        &faded!(*style, unsolvable),
+        true,
+        theme,
+        context,
+        graphics,
+      );
+    }
+
+    // Vectors go with the lines, their arrowheads are drawn over the lines they end on
+    for (vector, style, selected, unsolvable, _) in (
+      scrn_vectors,
+      vector_styles,
+      selecteds.maybe(),
+      unsolvables.maybe(),
+      !hiddens,
+    )
+      .join()
+    {
+      render_vector(
+        vector,
+        &faded!(*style, unsolvable),
+        selected.is_some(),
+        theme,
+        context,
+        graphics,
+      );
     }
-    for (line, style, _, _) in (scrn_lines, line_styles, selecteds, !hiddens).join() {
-      render_line(line, style, true, viewport, context, graphics);
+
+    // The ink goes over the constructions it annotates
+    for (stroke, style, selected, _) in (scrn_strokes, line_styles, selecteds.maybe(), !hiddens).join() {
+      render_freehand(
+        stroke,
+        &style.flatten_alpha(),
+        selected.is_some(),
+        theme,
+        context,
+        graphics,
+      );
     }
 
     // Lastly, draw the points
-    for (point, style, _, _) in (scrn_points, point_styles, !selecteds, !hiddens).join() {
-      render_point(point, style, false, context, graphics);
+    for (point, style, sym_point, unsolvable, _, _) in (
+      scrn_points,
+      point_styles,
+      sym_points,
+      unsolvables.maybe(),
+      !selecteds,
+      !hiddens,
+    )
+      .join()
+    {
+      let style = faded!(style.resolve_fill(sym_point), unsolvable);
+      render_point(point, &style, false, theme, context, graphics);
+    }
+    for (point, style, sym_point, unsolvable, _, _) in (
+      scrn_points,
+      point_styles,
+      sym_points,
+      unsolvables.maybe(),
+      selecteds,
+      !hiddens,
+    )
+      .join()
+    {
+      let style = faded!(style.resolve_fill(sym_point), unsolvable);
+      render_point(point, &style, true, theme, context, graphics);
     }
-    for (point, style, _, _) in (scrn_points, point_styles, selecteds, !hiddens).join() {
-      render_point(point, style, true, context, graphics);
+
+    // The labels go over the points they name, texts go along with them
+    if let Some(glyphs) = glyphs.as_deref_mut() {
+      for (point, label, _) in (scrn_points, labels, !hiddens).join() {
+        render_label(point, label, theme, glyphs, context, graphics);
+      }
+      for (text, style, selected, unsolvable, _) in (
+        scrn_texts,
+        text_styles,
+        selecteds.maybe(),
+        unsolvables.maybe(),
+        !hiddens,
+      )
+        .join()
+      {
+        render_text(
+          text,
+          &faded!(*style, unsolvable),
+          selected.is_some(),
+          theme,
+          glyphs,
+          context,
+          graphics,
+        );
+      }
+      glyphs.factory.encoder.flush(device);
     }
 
     // Additionally, draw rectangles
     for (rect, style) in (scrn_rects, rect_styles).join() {
-      render_rectangle(rect, style, context, graphics);
+      render_rectangle(rect, &style.flatten_alpha(), context, graphics);
+    }
+
+    // The toolbar, the style pickers and the inspector go over the sketch
+    render_overlay(overlay, theme, glyphs.as_deref_mut(), context, graphics);
+    if let Some(glyphs) = glyphs {
+      glyphs.factory.encoder.flush(device);
     }
   });
 }
 
+fn render_underlay(
+  underlay: &ImageUnderlay,
+  placement: &UnderlayPlacement,
+  texture: &G2dTexture,
+  viewport: &Viewport,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let top_left = placement.position.to_screen(viewport).0;
+  let bottom_right = placement.bottom_right(underlay).to_screen(viewport).0;
+  let (width, height) = texture.get_size();
+  let transform = context.transform.trans(top_left.x, top_left.y).scale(
+    (bottom_right.x - top_left.x) / width as f64,
+    (bottom_right.y - top_left.y) / height as f64,
+  );
+  Image::new_color([1.0, 1.0, 1.0, placement.opacity as f32]).draw(texture, &context.draw_state, transform, graphics);
+}
+
+/// A wide translucent stroke in the hover color, drawn under a curve of the given width
+fn halo_style(width: f64, theme: &Theme) -> LineStyle {
+  LineStyle {
+    color: theme.hover.apply_alpha(HALO_ALPHA),
+    width: width + HALO_WIDTH,
+    marks: EqualityMarks::default(),
+    dash: DashPattern::Solid,
+    alpha: 1.0,
+  }
+}
+
 fn render_point(
   ScreenPosition(Vector2 { x, y }): &ScreenPoint,
   style: &PointStyle,
   selected: bool,
+  theme: &Theme,
   context: Context,
   graphics: &mut G2d,
 ) {
   if selected {
     let radius = style.radius + 3.0;
     circle_arc(
-      Color::magenta().into(),
+      theme.selection.into(),
       0.5,
       0.0,
       std::f64::consts::PI * 1.9999,
@@ -85,41 +397,81 @@ fn render_point(
       graphics,
     );
   }
-  ellipse(
-    rgba!(0.0, 0.0, 0.0, style.color.a).into(),
-    [x - style.radius, y - style.radius, style.radius * 2., style.radius * 2.],
-    context.transform,
-    graphics,
-  );
-  let center_radius = style.radius - 1.5;
-  ellipse(
-    style.color.into(),
-    [
-      x - center_radius,
-      y - center_radius,
-      center_radius * 2.,
-      center_radius * 2.,
-    ],
-    context.transform,
-    graphics,
-  );
+  // The marker is drawn as a border with a smaller one on top. Hollow points only keep their
+  // border, showing the background through, while crosses and pluses are outlined strokes
+  let center_color = match (style.marker_shape, style.fill) {
+    (MarkerShape::Cross, _) | (MarkerShape::Plus, _) => style.color,
+    (MarkerShape::HollowCircle, _) | (_, PointFill::Hollow) => theme.background,
+    _ => style.color,
+  };
+  let center = vec2![*x, *y];
+  for primitive in style.marker_shape.primitives(center, style.radius) {
+    render_marker_primitive(&primitive, style.border_color, 1.5, context, graphics);
+  }
+  for primitive in style.marker_shape.primitives(center, style.radius - 1.5) {
+    render_marker_primitive(&primitive, center_color, 0.75, context, graphics);
+  }
+}
+
+fn render_marker_primitive(
+  primitive: &MarkerPrimitive,
+  color: Color,
+  stroke_radius: f64,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  match primitive {
+    MarkerPrimitive::Disc(Vector2 { x, y }, radius) | MarkerPrimitive::Ring(Vector2 { x, y }, radius) => ellipse(
+      color.into(),
+      [x - radius, y - radius, radius * 2., radius * 2.],
+      context.transform,
+      graphics,
+    ),
+    MarkerPrimitive::Polygon(vertices) => {
+      let vertices = vertices.iter().map(|v| [v.x, v.y]).collect::<Vec<_>>();
+      polygon(color.into(), &vertices, context.transform, graphics);
+    }
+    MarkerPrimitive::Segment(from, to) => {
+      line_from_to(color.into(), stroke_radius, *from, *to, context.transform, graphics)
+    }
+  }
 }
 
 fn render_line(
   l: &ScreenLine,
+  clipped: Option<(Vector2, Vector2)>, // The part of the line on the screen
   style: &LineStyle,
   selected: bool,
-  viewport: &Viewport,
+  theme: &Theme,
   context: Context,
   graphics: &mut G2d,
 ) {
-  if let Some((from, to)) = Into::<Line>::into(*l).intersect(viewport.screen_aabb()) {
-    line_from_to(style.color.into(), style.width, from, to, context.transform, graphics);
+  if let Some((from, to)) = clipped {
+    for (dash_from, dash_to) in style.dash.dashes(from, to) {
+      line_from_to(
+        style.color.into(),
+        style.width,
+        dash_from,
+        dash_to,
+        context.transform,
+        graphics,
+      );
+    }
+    for (tick_from, tick_to) in Into::<Line>::into(*l).tick_marks(style.marks.0, TICK_LENGTH, TICK_SPACING) {
+      line_from_to(
+        style.color.into(),
+        style.width,
+        tick_from,
+        tick_to,
+        context.transform,
+        graphics,
+      );
+    }
     if selected {
       let Vector2 { x: dx, y: dy } = (to - from).normalized();
       let perp_dir = vec2![-dy, dx] * (style.width / 2.0 + 3.0);
       line_from_to(
-        Color::magenta().into(),
+        theme.selection.into(),
         0.5,
         from - perp_dir,
         to - perp_dir,
@@ -127,7 +479,7 @@ fn render_line(
         graphics,
       );
       line_from_to(
-        Color::magenta().into(),
+        theme.selection.into(),
         0.5,
         from + perp_dir,
         to + perp_dir,
@@ -142,6 +494,7 @@ fn render_circle(
   ScreenCircle { center, radius }: &ScreenCircle,
   style: &CircleStyle,
   selected: bool,
+  theme: &Theme,
   context: Context,
   graphics: &mut G2d,
 ) {
@@ -162,7 +515,7 @@ fn render_circle(
     let inner_radius = radius - style.border.width / 2.0 - 3.0;
     let outer_radius = radius + style.border.width / 2.0 + 3.0;
     circle_arc(
-      Color::magenta().into(),
+      theme.selection.into(),
       0.5,
       0.0,
       std::f64::consts::PI * 1.999999999,
@@ -176,7 +529,7 @@ fn render_circle(
       graphics,
     );
     circle_arc(
-      Color::magenta().into(),
+      theme.selection.into(),
       0.5,
       0.0,
       std::f64::consts::PI * 1.999999999,
@@ -192,6 +545,156 @@ fn render_circle(
   }
 }
 
+fn render_arc(
+  ScreenArc {
+    center,
+    radius,
+    start,
+    sweep,
+  }: &ScreenArc,
+  style: &ArcStyle,
+  selected: bool,
+  theme: &Theme,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let center: Vector2 = Into::<Vector2>::into(*center);
+  let radius: f64 = Into::<f64>::into(*radius);
+  let rect_of = |r: f64| [center.x - r, center.y - r, r * 2.0, r * 2.0];
+  circle_arc(
+    style.color.into(),
+    style.width,
+    *start,
+    start + sweep,
+    rect_of(radius),
+    context.transform,
+    graphics,
+  );
+  if selected {
+    for r in &[radius - style.width / 2.0 - 3.0, radius + style.width / 2.0 + 3.0] {
+      circle_arc(
+        theme.selection.into(),
+        0.5,
+        *start,
+        start + sweep,
+        rect_of(*r),
+        context.transform,
+        graphics,
+      );
+    }
+  }
+}
+
+fn render_conic(
+  conic: &ScreenConic,
+  style: &ConicStyle,
+  selected: bool,
+  theme: &Theme,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let ellipse: Ellipse = (*conic).into();
+  let render_outline = |ellipse: Ellipse, color: Color, width: f64, graphics: &mut G2d| {
+    let step = 2.0 * std::f64::consts::PI / CONIC_SEGMENTS as f64;
+    for i in 0..CONIC_SEGMENTS {
+      let from = ellipse.point_at(step * i as f64);
+      let to = ellipse.point_at(step * (i + 1) as f64);
+      line_from_to(
+        color.into(),
+        width,
+        [from.x, from.y],
+        [to.x, to.y],
+        context.transform,
+        graphics,
+      );
+    }
+  };
+  render_outline(ellipse, style.color, style.width, graphics);
+  if selected {
+    for offset in &[-style.width / 2.0 - 3.0, style.width / 2.0 + 3.0] {
+      let outline = Ellipse {
+        rx: (ellipse.rx + offset).max(0.0),
+        ry: (ellipse.ry + offset).max(0.0),
+        ..ellipse
+      };
+      render_outline(outline, theme.selection, 0.5, graphics);
+    }
+  }
+}
+
+/// Each piece of the stroke is as wide as the pen was pressed at its ends, the round ends of the
+/// lines join the pieces
+fn render_freehand(
+  stroke: &ScreenFreehandStroke,
+  style: &LineStyle,
+  selected: bool,
+  theme: &Theme,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let render_samples = |color: Color, extra: f64, graphics: &mut G2d| {
+    if let [(ScreenPosition(Vector2 { x, y }), pressure)] = stroke.samples.as_slice() {
+      let radius = FreehandStroke::width_at(*pressure, style.width) + extra;
+      let rect = [x - radius, y - radius, radius * 2.0, radius * 2.0];
+      ellipse(color.into(), rect, context.transform, graphics);
+    }
+    for pair in stroke.samples.windows(2) {
+      let ((from, p1), (to, p2)) = (pair[0], pair[1]);
+      line_from_to(
+        color.into(),
+        FreehandStroke::width_at((p1 + p2) / 2.0, style.width) + extra,
+        [from.0.x, from.0.y],
+        [to.0.x, to.0.y],
+        context.transform,
+        graphics,
+      );
+    }
+  };
+  if selected {
+    render_samples(theme.selection, 3.0, graphics);
+  }
+  render_samples(style.color, 0.0, graphics);
+}
+
+fn render_label(
+  ScreenPosition(position): &ScreenPoint,
+  label: &Label,
+  theme: &Theme,
+  glyphs: &mut Glyphs,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let Vector2 { x, y } = *position + label.offset;
+  // A glyph missing from the font only leaves the label unfinished
+  let _ = Text::new_color(theme.line.into(), LABEL_FONT_SIZE).draw(
+    &label.text,
+    glyphs,
+    &context.draw_state,
+    context.transform.trans(x, y),
+    graphics,
+  );
+}
+
+fn render_text(
+  text: &ScreenText,
+  style: &TextStyle,
+  selected: bool,
+  theme: &Theme,
+  glyphs: &mut Glyphs,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let Vector2 { x, y } = text.position.0;
+  let color = if selected { theme.selection } else { style.color };
+  let _ = Text::new_color(color.into(), style.font_size as u32).draw(
+    &text.text,
+    glyphs,
+    &context.draw_state,
+    context.transform.trans(x, y),
+    graphics,
+  );
+}
+
 fn render_rectangle(rect: &AABB, style: &RectangleStyle, context: Context, graphics: &mut G2d) {
   line_from_to(
     style.border.color.into(),
@@ -232,3 +735,96 @@ fn render_rectangle(rect: &AABB, style: &RectangleStyle, context: Context, graph
     graphics,
   );
 }
+
+fn render_overlay(
+  widgets: &[OverlayWidget],
+  theme: &Theme,
+  mut glyphs: Option<&mut Glyphs>,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  for widget in widgets {
+    // The rows of the inspector are drawn over its panel without a frame of their own
+    if widget.action.is_some() || widget.text.is_empty() {
+      let fill = match widget.swatch {
+        Some(color) => color,
+        None if widget.active => theme.selection.apply_alpha(0.3),
+        None => theme.background.apply_alpha(0.9),
+      };
+      let style = RectangleStyle {
+        fill,
+        border: LineStyle {
+          color: theme.line,
+          width: 1.0,
+          marks: EqualityMarks::default(),
+          dash: DashPattern::Solid,
+          alpha: 1.0,
+        },
+        alpha: 1.0,
+      };
+      render_rectangle(&widget.rect, &style, context, graphics);
+    }
+    if let Some(glyphs) = glyphs.as_deref_mut() {
+      let AABB { x, y, height, .. } = widget.rect;
+      let _ = Text::new_color(theme.line.into(), OVERLAY_FONT_SIZE).draw(
+        &widget.text,
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(x + 6.0, y + height * 0.7),
+        graphics,
+      );
+    }
+  }
+}
+
+fn render_polygon(
+  ScreenPolygon { vertices }: &ScreenPolygon,
+  style: &PolygonStyle,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let path = vertices.iter().map(|v| [v.0.x, v.0.y]).collect::<Vec<_>>();
+  polygon(style.fill.into(), &path, context.transform, graphics);
+  for (i, from) in path.iter().enumerate() {
+    let to = path[(i + 1) % path.len()];
+    line_from_to(
+      style.border.color.into(),
+      style.border.width,
+      *from,
+      to,
+      context.transform,
+      graphics,
+    );
+  }
+}
+
+fn render_vector(
+  vector: &ScreenVector,
+  style: &VectorStyle,
+  selected: bool,
+  theme: &Theme,
+  context: Context,
+  graphics: &mut G2d,
+) {
+  let (from, to) = (vector.from.0, vector.to.0);
+  if selected && from != to {
+    let Vector2 { x: dx, y: dy } = (to - from).normalized();
+    let perp_dir = vec2![-dy, dx] * (style.width / 2.0 + 3.0);
+    for offset in &[perp_dir, -perp_dir] {
+      line_from_to(
+        theme.selection.into(),
+        0.5,
+        from + *offset,
+        to + *offset,
+        context.transform,
+        graphics,
+      );
+    }
+  }
+  line_from_to(style.color.into(), style.width, from, to, context.transform, graphics);
+  if let Some((side1, side2)) = vector.arrowhead(style.head_length) {
+    for side in &[side1, side2] {
+      line_from_to(style.color.into(), style.width, to, side.0, context.transform, graphics);
+    }
+  }
+}
@@ -1,88 +1,207 @@
 use core_lib::{
-  components::{markers::*, screen_shapes::*, styles::*},
+  components::{freehand::*, markers::*, screen_shapes::*, styles::*, symbolics::*, underlays::*},
   events::*,
-  resources::Viewport,
+  math::Vector2,
+  resources::{Inspection, LineClipMargin, Theme, Viewport},
+  utilities::LineClipCache,
 };
-use core_ui::{events::*, resources::*};
+use core_ui::{events::*, resources::*, utilities::*};
 use piston_window::{Event as PistonEvent, *};
 use specs::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use super::{event_handling::*, rendering::*};
 
+pub static WINDOW_TITLE: &str = "Geometry Sketchpad";
+
 pub struct WindowSystem {
   pub window: PistonWindow,
+  pub glyphs: Option<Glyphs>, // No labels or texts are drawn without a font
+  pub texture_context: G2dTextureContext,
+  pub underlay_textures: HashMap<PathBuf, Option<G2dTexture>>, // None when the image could not be loaded
+  pub line_clip_cache: LineClipCache<Entity>,
+  pub status_event_reader: Option<StatusEventReader>,
+  pub pressed_on_overlay: bool, // The left button went down on the overlay, its release goes there too
+}
+
+impl WindowSystem {
+  /// Loads the image at the path to be used as an underlay, the underlay to insert has its size
+  pub fn load_underlay(&mut self, path: &Path) -> Result<ImageUnderlay, String> {
+    let texture = self.load_texture(path)?;
+    let (width, height) = texture.get_size();
+    self.underlay_textures.insert(path.to_path_buf(), Some(texture));
+    Ok(ImageUnderlay {
+      path: path.to_path_buf(),
+      size: vec2![width as f64, height as f64],
+    })
+  }
+
+  fn load_texture(&mut self, path: &Path) -> Result<G2dTexture, String> {
+    Texture::from_path(&mut self.texture_context, path, Flip::None, &TextureSettings::new())
+  }
 }
 
 impl<'a> System<'a> for WindowSystem {
   type SystemData = (
+    Entities<'a>,
     // Resources
     Read<'a, Viewport>,
-    Write<'a, ExitEventChannel>,
-    Write<'a, MouseEventChannel>,
-    Write<'a, ViewportEventChannel>,
-    Write<'a, InputState>,
-    Write<'a, DeltaTime>,
+    Read<'a, Theme>,
+    Read<'a, LineClipMargin>,
+    (
+      Write<'a, ExitEventChannel>,
+      Write<'a, MouseEventChannel>,
+      Write<'a, ViewportEventChannel>,
+    ),
+    (Write<'a, InputState>, Write<'a, DeltaTime>),
+    Read<'a, StatusEventChannel>,
+    (
+      Read<'a, ToolState>,
+      Read<'a, Inspection>,
+      Write<'a, ToolChangeEventChannel>,
+      Write<'a, CommandEventChannel>,
+    ),
     // Data
     ReadStorage<'a, ScreenPoint>,
     ReadStorage<'a, ScreenLine>,
     ReadStorage<'a, ScreenCircle>,
+    (ReadStorage<'a, ScreenArc>, ReadStorage<'a, ArcStyle>),
+    (ReadStorage<'a, ScreenConic>, ReadStorage<'a, ConicStyle>),
     ReadStorage<'a, ScreenRectangle>,
+    ReadStorage<'a, ScreenPolygon>,
+    (ReadStorage<'a, ScreenVector>, ReadStorage<'a, ScreenFreehandStroke>),
     ReadStorage<'a, PointStyle>,
     ReadStorage<'a, LineStyle>,
     ReadStorage<'a, CircleStyle>,
     ReadStorage<'a, RectangleStyle>,
-    ReadStorage<'a, Selected>,
-    ReadStorage<'a, Hidden>,
+    ReadStorage<'a, PolygonStyle>,
+    ReadStorage<'a, VectorStyle>,
+    (ReadStorage<'a, SymbolicPoint>, ReadStorage<'a, Label>),
+    (ReadStorage<'a, ImageUnderlay>, ReadStorage<'a, UnderlayPlacement>),
+    (ReadStorage<'a, ScreenText>, ReadStorage<'a, TextStyle>),
+    (
+      ReadStorage<'a, Selected>,
+      ReadStorage<'a, Hovered>,
+      ReadStorage<'a, Hidden>,
+      ReadStorage<'a, Background>,
+      ReadStorage<'a, Unsolvable>,
+    ),
   );
 
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.status_event_reader = Some(world.fetch_mut::<StatusEventChannel>().register_reader());
+  }
+
   fn run(
     &mut self,
     (
+      entities,
       viewport,
-      mut exit_event_channel,
-      mut mouse_event_channel,
-      mut viewport_event_channel,
-      mut input_state,
-      mut delta_time,
+      theme,
+      line_clip_margin,
+      (mut exit_event_channel, mut mouse_event_channel, mut viewport_event_channel),
+      (mut input_state, mut delta_time),
+      status_event_channel,
+      (tool_state, inspection, mut tool_change_event_channel, mut command_event_channel),
       scrn_points,
       scrn_lines,
       scrn_circles,
+      (scrn_arcs, arc_styles),
+      (scrn_conics, conic_styles),
       scrn_rects,
+      scrn_polygons,
+      (scrn_vectors, scrn_strokes),
       point_styles,
       line_styles,
       circle_styles,
       rect_styles,
-      selecteds,
-      hiddens,
+      polygon_styles,
+      vector_styles,
+      (sym_points, labels),
+      (underlays, underlay_placements),
+      (scrn_texts, text_styles),
+      (selecteds, hovereds, hiddens, backgrounds, unsolvables),
     ): Self::SystemData,
   ) {
     input_state.reset_relative_data();
+
+    // The images of the underlays inserted by now, e.g. by the history, are loaded once
+    for underlay in underlays.join() {
+      if !self.underlay_textures.contains_key(&underlay.path) {
+        let texture = self.load_texture(&underlay.path).ok();
+        self.underlay_textures.insert(underlay.path.clone(), texture);
+      }
+    }
+
+    // The overlay is driven by the same channels as the keyboard shortcuts, and laid out once a frame
+    let overlay = overlay_widgets(tool_state.get(), &inspection, &viewport);
+
     loop {
       if let Some(event) = self.window.next() {
         match event {
-          PistonEvent::Input(input, _) => handle_input(
-            input,
-            &mut input_state,
-            &mut mouse_event_channel,
-            &mut viewport_event_channel,
-          ),
+          PistonEvent::Input(input, _) => {
+            let taken = handle_overlay_input(
+              &input,
+              &overlay,
+              &mut self.pressed_on_overlay,
+              &input_state,
+              &mut tool_change_event_channel,
+              &mut command_event_channel,
+            );
+            if !taken {
+              handle_input(
+                input,
+                &mut input_state,
+                &mut mouse_event_channel,
+                &mut viewport_event_channel,
+              );
+            }
+          }
           PistonEvent::Loop(lp) => match lp {
             Loop::Update(UpdateArgs { dt }) => handle_dt_update(dt, &mut delta_time),
             Loop::Render(_) => {
               render(
                 &mut self.window,
+                self.glyphs.as_mut(),
+                &self.underlay_textures,
                 &event,
                 &*viewport,
+                &*theme,
+                &*line_clip_margin,
+                &mut self.line_clip_cache,
+                &entities,
                 &scrn_points,
                 &scrn_lines,
                 &scrn_circles,
+                &scrn_arcs,
+                &scrn_conics,
                 &scrn_rects,
+                &scrn_polygons,
+                &scrn_vectors,
+                &scrn_strokes,
                 &point_styles,
                 &line_styles,
                 &circle_styles,
+                &arc_styles,
+                &conic_styles,
                 &rect_styles,
+                &polygon_styles,
+                &vector_styles,
+                &sym_points,
+                &labels,
+                &underlays,
+                &underlay_placements,
+                &scrn_texts,
+                &text_styles,
                 &selecteds,
+                &hovereds,
                 &hiddens,
+                &backgrounds,
+                &unsolvables,
+                &overlay,
               );
               break;
             }
@@ -95,5 +214,14 @@ impl<'a> System<'a> for WindowSystem {
         break;
       }
     }
+    self.line_clip_cache.retain(|ent| scrn_lines.contains(ent));
+    input_state.record_mouse_position(SystemTime::now());
+
+    // There's no status bar, the cursor position is shown in the title
+    if let Some(reader) = &mut self.status_event_reader {
+      if let Some(StatusEvent::CursorPosition(text)) = status_event_channel.read(reader).last() {
+        self.window.set_title(format!("{} {}", WINDOW_TITLE, text));
+      }
+    }
   }
 }
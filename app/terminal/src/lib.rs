@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate core_lib;
+extern crate core_ui;
+extern crate ratatui;
+extern crate specs;
+
+mod terminal_system;
+mod utilities;
+
+pub use terminal_system::TerminalSystem;
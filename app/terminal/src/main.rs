@@ -0,0 +1,35 @@
+// Core crates
+extern crate core_lib;
+extern crate core_ui;
+extern crate specs;
+
+// Terminal library providing the "TerminalSystem"
+extern crate geopad_terminal;
+
+use core_ui::{resources::*, setup_core_ui};
+use geopad_terminal::TerminalSystem;
+use specs::prelude::*;
+
+fn main() {
+  let mut world = World::new();
+  let mut builder = DispatcherBuilder::new();
+
+  // Setup the core ui
+  setup_core_ui(&mut builder);
+
+  // Add the terminal system and build the dispatcher
+  let terminal_system = match TerminalSystem::new() {
+    Ok(terminal_system) => terminal_system,
+    Err(err) => {
+      eprintln!("Cannot take over the terminal: {}", err);
+      return;
+    }
+  };
+  builder.add_thread_local(terminal_system);
+  let mut dispatcher = builder.build();
+  dispatcher.setup(&mut world);
+
+  while !world.fetch::<ExitState>().is_exiting() {
+    dispatcher.dispatch(&world);
+  }
+}
@@ -0,0 +1,147 @@
+use crate::utilities::key_code_to_key;
+use core_lib::{events::*, math::*};
+use core_ui::{events::*, resources::*};
+use ratatui::crossterm::event::{
+  Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent as TerminalMouseEvent, MouseEventKind,
+};
+use specs::prelude::*;
+use std::time::SystemTime;
+
+static CLICK_TIME_THRESHOLD: u128 = 100; // 0.1 second
+
+/// Size of a character cell in screen pixels, the braille dots being a 2 x 4 grid in every cell
+pub static CELL_WIDTH: f64 = 8.0; // Pixel
+pub static CELL_HEIGHT: f64 = 16.0; // Pixel
+
+/// Position of the center of the cell in screen pixels
+pub fn cell_center(column: u16, row: u16) -> Vector2 {
+  vec2![(column as f64 + 0.5) * CELL_WIDTH, (row as f64 + 0.5) * CELL_HEIGHT]
+}
+
+/// Terminals only tell when a key gets pressed, so every key is released on the next frame, see
+/// `released_keys`. The modifiers come along with the key, they are pressed with it
+pub fn handle_event<'a>(
+  event: Event,
+  released_keys: &mut Vec<Key>,
+  input_state: &mut Write<'a, InputState>,
+  mouse_event_channel: &mut Write<'a, MouseEventChannel>,
+  viewport_event_channel: &mut Write<'a, ViewportEventChannel>,
+) {
+  match event {
+    Event::Key(KeyEvent {
+      code, modifiers, kind, ..
+    }) => {
+      if kind == KeyEventKind::Release {
+        return;
+      }
+      let modifier_keys = [
+        (KeyModifiers::CONTROL, Key::LCtrl),
+        (KeyModifiers::SHIFT, Key::LShift),
+        (KeyModifiers::ALT, Key::LAlt),
+      ];
+      let shifted = matches!(code, KeyCode::Char(c) if c.is_ascii_uppercase() || "~!@#$%^&*()_+{}|:\"<>?".contains(c));
+      for (modifier, key) in &modifier_keys {
+        if modifiers.contains(*modifier) || (*key == Key::LShift && shifted) {
+          input_state.set_key(*key, true);
+          released_keys.push(*key);
+        }
+      }
+      let key = key_code_to_key(code);
+      input_state.set_key(key, true);
+      released_keys.push(key);
+      if let KeyCode::Char(c) = code {
+        if !modifiers.contains(KeyModifiers::CONTROL) {
+          input_state.type_text(&c.to_string());
+        }
+      }
+    }
+    Event::Mouse(TerminalMouseEvent { kind, column, row, .. }) => {
+      let position = cell_center(column, row);
+      let scrn_rel_mov = From::<Vector2>::from(position - input_state.mouse_abs_pos.0);
+      input_state.mouse_abs_pos = position.into();
+      match kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+          input_state.mouse_left_button.set(true);
+          input_state.mouse_left_button_last_pressed = Some(SystemTime::now());
+          mouse_event_channel.single_write(MouseEvent::MouseDown(input_state.mouse_abs_pos));
+        }
+        MouseEventKind::Up(MouseButton::Left) => {
+          input_state.mouse_left_button.set(false);
+          mouse_event_channel.single_write(MouseEvent::MouseUp(input_state.mouse_abs_pos));
+          if input_state.is_mouse_left_button_dragging {
+            input_state.is_mouse_left_button_dragging = false;
+            mouse_event_channel.single_write(MouseEvent::DragEnd(input_state.mouse_abs_pos));
+          } else if let Some(last_pressed) = input_state.mouse_left_button_last_pressed {
+            if last_pressed.elapsed().unwrap().as_millis() < CLICK_TIME_THRESHOLD {
+              mouse_event_channel.single_write(MouseEvent::Click(input_state.mouse_abs_pos));
+            }
+          }
+        }
+        MouseEventKind::Down(MouseButton::Right) => input_state.mouse_right_button.set(true),
+        MouseEventKind::Up(MouseButton::Right) => input_state.mouse_right_button.set(false),
+        MouseEventKind::Drag(MouseButton::Left) | MouseEventKind::Moved => {
+          input_state.mouse_rel_movement = input_state.mouse_rel_movement + scrn_rel_mov;
+          if input_state.is_mouse_left_button_dragging {
+            mouse_event_channel.single_write(MouseEvent::DragMove(scrn_rel_mov, input_state.mouse_abs_pos));
+          } else if input_state.mouse_left_button.is_activated() {
+            input_state.is_mouse_left_button_dragging = true;
+            mouse_event_channel.single_write(MouseEvent::DragBegin(input_state.mouse_abs_pos));
+          }
+        }
+        MouseEventKind::ScrollUp => input_state.rel_scroll = input_state.rel_scroll + vec2![0., 1.],
+        MouseEventKind::ScrollDown => input_state.rel_scroll = input_state.rel_scroll + vec2![0., -1.],
+        _ => (),
+      }
+    }
+    Event::Resize(columns, rows) => {
+      viewport_event_channel.single_write(ViewportEvent::Resize(screen_size(columns, rows)));
+    }
+    Event::FocusGained => input_state.set_focus(true, mouse_event_channel),
+    Event::FocusLost => input_state.set_focus(false, mouse_event_channel),
+    _ => (),
+  }
+}
+
+/// The last row is the status line, the sketch takes the rest
+pub fn screen_size(columns: u16, rows: u16) -> Vector2 {
+  vec2![columns as f64 * CELL_WIDTH, rows.saturating_sub(1) as f64 * CELL_HEIGHT]
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_keys_are_released_on_the_next_frame() {
+    let mut world = World::new();
+    world.insert(InputState::default());
+    world.insert(MouseEventChannel::new());
+    world.insert(ViewportEventChannel::new());
+    let key = |world: &World, code: KeyCode, modifiers: KeyModifiers| {
+      let mut released_keys = vec![];
+      let (mut input_state, mut mouse_event_channel, mut viewport_event_channel) =
+        world.system_data::<(Write<InputState>, Write<MouseEventChannel>, Write<ViewportEventChannel>)>();
+      handle_event(
+        Event::Key(KeyEvent::new(code, modifiers)),
+        &mut released_keys,
+        &mut input_state,
+        &mut mouse_event_channel,
+        &mut viewport_event_channel,
+      );
+      released_keys
+    };
+
+    // Ctrl+Z comes with its modifier
+    let released_keys = key(&world, KeyCode::Char('z'), KeyModifiers::CONTROL);
+    let pressed = |key: Key| world.fetch::<InputState>().keyboard.is_activated(key);
+    assert!(pressed(Key::LCtrl) && pressed(Key::Z));
+    assert!(!pressed(Key::LShift));
+    assert_eq!(released_keys, vec![Key::LCtrl, Key::Z]);
+
+    // A shifted character presses shift even when the terminal does not tell, and is typed
+    world.fetch_mut::<InputState>().set_text_mode(true);
+    let released_keys = key(&world, KeyCode::Char('_'), KeyModifiers::NONE);
+    assert_eq!(released_keys, vec![Key::LShift, Key::Minus]);
+    assert_eq!(world.fetch::<InputState>().typed_text, "_");
+  }
+}
@@ -0,0 +1,5 @@
+mod event_handling;
+mod rendering;
+mod terminal_system;
+
+pub use terminal_system::*;
@@ -0,0 +1,336 @@
+use core_lib::{
+  components::{freehand::*, markers::*, screen_shapes::*, styles::*},
+  math::*,
+  resources::{LineClipMargin, Theme, Viewport},
+};
+use ratatui::{
+  layout::Rect,
+  style::{Color as TerminalColor, Style},
+  symbols::Marker,
+  text::Line as TextLine,
+  widgets::{
+    canvas::{Canvas, Line as CanvasLine, Points},
+    Paragraph,
+  },
+  Frame,
+};
+use specs::prelude::*;
+use std::f64::consts::PI;
+
+static CURVE_SEGMENTS: usize = 64; // Of the arcs and conics
+
+/// Draws the screen shapes in braille dots, from the grid at the bottom to the select rectangle
+/// on top. A braille cell has a single color, so there are no fills and the selection is shown by
+/// the color of the selected shapes rather than by outlines around them. The hovered curves get
+/// the hover color the same way, instead of a halo
+pub fn render<'a>(
+  frame: &mut Frame,
+  status: &str,
+  viewport: &Viewport,
+  theme: &Theme,
+  line_clip_margin: &LineClipMargin,
+  scrn_points: &ReadStorage<'a, ScreenPoint>,
+  scrn_lines: &ReadStorage<'a, ScreenLine>,
+  scrn_circles: &ReadStorage<'a, ScreenCircle>,
+  scrn_arcs: &ReadStorage<'a, ScreenArc>,
+  scrn_conics: &ReadStorage<'a, ScreenConic>,
+  scrn_rects: &ReadStorage<'a, ScreenRectangle>,
+  scrn_polygons: &ReadStorage<'a, ScreenPolygon>,
+  scrn_vectors: &ReadStorage<'a, ScreenVector>,
+  scrn_strokes: &ReadStorage<'a, ScreenFreehandStroke>,
+  point_styles: &ReadStorage<'a, PointStyle>,
+  line_styles: &ReadStorage<'a, LineStyle>,
+  circle_styles: &ReadStorage<'a, CircleStyle>,
+  arc_styles: &ReadStorage<'a, ArcStyle>,
+  conic_styles: &ReadStorage<'a, ConicStyle>,
+  rect_styles: &ReadStorage<'a, RectangleStyle>,
+  polygon_styles: &ReadStorage<'a, PolygonStyle>,
+  vector_styles: &ReadStorage<'a, VectorStyle>,
+  labels: &ReadStorage<'a, Label>,
+  scrn_texts: &ReadStorage<'a, ScreenText>,
+  text_styles: &ReadStorage<'a, TextStyle>,
+  selecteds: &ReadStorage<'a, Selected>,
+  hovereds: &ReadStorage<'a, Hovered>,
+  hiddens: &ReadStorage<'a, Hidden>,
+  backgrounds: &ReadStorage<'a, Background>,
+) {
+  let area = frame.area();
+  let sketch_area = Rect {
+    height: area.height.saturating_sub(1),
+    ..area
+  };
+  let status_area = Rect {
+    y: area.y + sketch_area.height,
+    height: area.height - sketch_area.height,
+    ..area
+  };
+  let Vector2 { x: width, y: height } = viewport.screen_size;
+  let color_of = |color: Color, selected: bool| terminal_color(if selected { theme.selection } else { color });
+  let curve_color_of = |color: Color, selected: bool, hovered: bool| match (selected, hovered) {
+    (false, true) => terminal_color(theme.hover),
+    _ => color_of(color, selected),
+  };
+
+  let canvas = Canvas::default()
+    .marker(Marker::Braille)
+    .background_color(terminal_color(theme.background))
+    .x_bounds([0.0, width])
+    .y_bounds([0.0, height])
+    .paint(|context| {
+      // The canvas goes upwards, the screen downwards
+      let mut segment = |from: Vector2, to: Vector2, color: TerminalColor| {
+        context.draw(&CanvasLine {
+          x1: from.x,
+          y1: height - from.y,
+          x2: to.x,
+          y2: height - to.y,
+          color,
+        })
+      };
+
+      // The grid goes under all the geometry
+      for (line, style, _) in (scrn_lines, line_styles, backgrounds).join() {
+        if let Some((from, to)) = Into::<Line>::into(*line).intersect(line_clip_margin.clip_aabb(viewport)) {
+          segment(from, to, terminal_color(style.flatten_alpha().color));
+        }
+      }
+
+      // Then the outlines of the polygons, circles, arcs and conics
+      for (polygon, style, selected, _) in (scrn_polygons, polygon_styles, selecteds.maybe(), !hiddens).join() {
+        let color = color_of(style.flatten_alpha().border.color, selected.is_some());
+        for (i, from) in polygon.vertices.iter().enumerate() {
+          segment(from.0, polygon.vertices[(i + 1) % polygon.vertices.len()].0, color);
+        }
+      }
+      for (circle, style, selected, hovered, _) in (
+        scrn_circles,
+        circle_styles,
+        selecteds.maybe(),
+        hovereds.maybe(),
+        !hiddens,
+      )
+        .join()
+      {
+        let color = curve_color_of(
+          style.flatten_alpha().border.color,
+          selected.is_some(),
+          hovered.is_some(),
+        );
+        let center: Vector2 = circle.center.into();
+        let radius: f64 = circle.radius.into();
+        curve(&mut segment, color, |t| center + vec2![t.cos(), t.sin()] * radius);
+      }
+      for (arc, style, selected, hovered, _) in
+        (scrn_arcs, arc_styles, selecteds.maybe(), hovereds.maybe(), !hiddens).join()
+      {
+        let color = curve_color_of(style.flatten_alpha().color, selected.is_some(), hovered.is_some());
+        let center: Vector2 = arc.center.into();
+        let radius: f64 = arc.radius.into();
+        curve(&mut segment, color, |t| {
+          let angle = arc.start + arc.sweep * t / (2.0 * PI);
+          center + vec2![angle.cos(), angle.sin()] * radius
+        });
+      }
+      for (conic, style, selected, hovered, _) in
+        (scrn_conics, conic_styles, selecteds.maybe(), hovereds.maybe(), !hiddens).join()
+      {
+        let color = curve_color_of(style.flatten_alpha().color, selected.is_some(), hovered.is_some());
+        let ellipse: Ellipse = (*conic).into();
+        curve(&mut segment, color, |t| ellipse.point_at(t));
+      }
+
+      // Then the lines and the vectors
+      for (line, style, selected, hovered, _, _) in (
+        scrn_lines,
+        line_styles,
+        selecteds.maybe(),
+        hovereds.maybe(),
+        !hiddens,
+        !backgrounds,
+      )
+        .join()
+      {
+        let style = style.flatten_alpha();
+        let color = curve_color_of(style.color, selected.is_some(), hovered.is_some());
+        if let Some((from, to)) = Into::<Line>::into(*line).intersect(line_clip_margin.clip_aabb(viewport)) {
+          for (dash_from, dash_to) in style.dash.dashes(from, to) {
+            segment(dash_from, dash_to, color);
+          }
+        }
+      }
+      for (vector, style, selected, _) in (scrn_vectors, vector_styles, selecteds.maybe(), !hiddens).join() {
+        let style = style.flatten_alpha();
+        let color = color_of(style.color, selected.is_some());
+        segment(vector.from.0, vector.to.0, color);
+        if let Some((side1, side2)) = vector.arrowhead(style.head_length) {
+          segment(vector.to.0, side1.0, color);
+          segment(vector.to.0, side2.0, color);
+        }
+      }
+      // The ink is as thin as the lines, however hard it was pressed
+      for (stroke, style, selected, _) in (scrn_strokes, line_styles, selecteds.maybe(), !hiddens).join() {
+        let color = color_of(style.flatten_alpha().color, selected.is_some());
+        for pair in stroke.samples.windows(2) {
+          segment((pair[0].0).0, (pair[1].0).0, color);
+        }
+      }
+
+      // The points go over the rest, as a single dot whatever their radius
+      for (point, style, selected, _) in (scrn_points, point_styles, selecteds.maybe(), !hiddens).join() {
+        context.draw(&Points {
+          coords: &[(point.0.x, height - point.0.y)],
+          color: color_of(style.flatten_alpha().color, selected.is_some()),
+        });
+      }
+
+      // Lastly the select rectangle
+      for (rect, style) in (scrn_rects, rect_styles).join() {
+        let color = terminal_color(style.flatten_alpha().border.color);
+        let corners = [
+          vec2![rect.x, rect.y],
+          vec2![rect.x + rect.width, rect.y],
+          vec2![rect.x + rect.width, rect.y + rect.height],
+          vec2![rect.x, rect.y + rect.height],
+        ];
+        for (i, from) in corners.iter().enumerate() {
+          context.draw(&CanvasLine {
+            x1: from.x,
+            y1: height - from.y,
+            x2: corners[(i + 1) % 4].x,
+            y2: height - corners[(i + 1) % 4].y,
+            color,
+          });
+        }
+      }
+
+      // The texts are printed over the dots, in the cell they start in
+      context.layer();
+      for (point, label, _) in (scrn_points, labels, !hiddens).join() {
+        let Vector2 { x, y } = point.0 + label.offset;
+        let style = Style::default().fg(terminal_color(theme.line));
+        context.print(x, height - y, TextLine::styled(label.text.clone(), style));
+      }
+      for (text, style, selected, _) in (scrn_texts, text_styles, selecteds.maybe(), !hiddens).join() {
+        let Vector2 { x, y } = text.position.0;
+        let style = Style::default().fg(color_of(style.flatten_alpha().color, selected.is_some()));
+        context.print(x, height - y, TextLine::styled(text.text.clone(), style));
+      }
+    });
+  frame.render_widget(canvas, sketch_area);
+  frame.render_widget(Paragraph::new(status.to_string()), status_area);
+}
+
+/// Draws the curve going through `point_at(t)` for `t` from 0 to 2π
+fn curve<S: FnMut(Vector2, Vector2, TerminalColor), P: Fn(f64) -> Vector2>(
+  segment: &mut S,
+  color: TerminalColor,
+  point_at: P,
+) {
+  let step = 2.0 * PI / CURVE_SEGMENTS as f64;
+  for i in 0..CURVE_SEGMENTS {
+    segment(point_at(step * i as f64), point_at(step * (i + 1) as f64), color);
+  }
+}
+
+fn terminal_color(color: Color) -> TerminalColor {
+  let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+  TerminalColor::Rgb(channel(color.r), channel(color.g), channel(color.b))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::terminal_system::event_handling::*;
+  use core_lib::resources::DefaultPointStyle;
+  use ratatui::{backend::TestBackend, Terminal};
+
+  #[test]
+  fn test_render_point_and_status() {
+    let mut world = World::new();
+    world.register::<ScreenPoint>();
+    world.register::<ScreenLine>();
+    world.register::<ScreenCircle>();
+    world.register::<ScreenArc>();
+    world.register::<ScreenConic>();
+    world.register::<ScreenRectangle>();
+    world.register::<ScreenPolygon>();
+    world.register::<ScreenVector>();
+    world.register::<ScreenFreehandStroke>();
+    world.register::<PointStyle>();
+    world.register::<LineStyle>();
+    world.register::<CircleStyle>();
+    world.register::<ArcStyle>();
+    world.register::<ConicStyle>();
+    world.register::<RectangleStyle>();
+    world.register::<PolygonStyle>();
+    world.register::<VectorStyle>();
+    world.register::<Label>();
+    world.register::<ScreenText>();
+    world.register::<TextStyle>();
+    world.register::<Selected>();
+    world.register::<Hovered>();
+    world.register::<Hidden>();
+    world.register::<Background>();
+
+    // A point in the middle of the third column of the second row, and a hidden one
+    world
+      .create_entity()
+      .with(ScreenPoint::from(cell_center(2, 1)))
+      .with(DefaultPointStyle::default().get())
+      .build();
+    world
+      .create_entity()
+      .with(ScreenPoint::from(cell_center(5, 1)))
+      .with(DefaultPointStyle::default().get())
+      .with(Hidden)
+      .build();
+
+    let (columns, rows) = (8, 3);
+    let mut viewport = Viewport::default();
+    viewport.set_screen_size(screen_size(columns, rows));
+    let mut terminal = Terminal::new(TestBackend::new(columns, rows)).unwrap();
+    terminal
+      .draw(|frame| {
+        render(
+          frame,
+          "x: 1, y: 2",
+          &viewport,
+          &Theme::default(),
+          &LineClipMargin::default(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+          &world.read_storage(),
+        )
+      })
+      .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let symbol = |column: u16, row: u16| buffer[(column, row)].symbol().to_string();
+    assert_ne!(symbol(2, 1), " ");
+    assert_eq!(symbol(5, 1), " ");
+    assert_eq!(symbol(0, 0), " ");
+    let status = (0..columns).map(|column| symbol(column, 2)).collect::<String>();
+    assert_eq!(status, "x: 1, y:");
+  }
+}
@@ -0,0 +1,212 @@
+use core_lib::{
+  components::{freehand::*, markers::*, screen_shapes::*, styles::*},
+  events::*,
+  resources::{LineClipMargin, Theme, Viewport},
+};
+use core_ui::{events::*, resources::*};
+use ratatui::{
+  backend::CrosstermBackend,
+  crossterm::{
+    event::{self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    execute, terminal,
+  },
+  Terminal,
+};
+use specs::prelude::*;
+use std::{
+  io::{self, Stdout},
+  time::{Duration, Instant, SystemTime},
+};
+
+use super::{event_handling::*, rendering::*};
+
+static FRAME_DURATION: Duration = Duration::from_millis(16); // Time waited for events, about 60 Hz
+
+pub struct TerminalSystem {
+  pub terminal: Terminal<CrosstermBackend<Stdout>>,
+  pub status: String, // Shown in the last row
+  pub released_keys: Vec<Key>,
+  pub last_frame: Instant,
+  pub status_event_reader: Option<StatusEventReader>,
+}
+
+impl TerminalSystem {
+  /// Takes over the terminal, which is given back when the system is dropped
+  pub fn new() -> io::Result<Self> {
+    terminal::enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(
+      stdout,
+      terminal::EnterAlternateScreen,
+      EnableMouseCapture,
+      EnableFocusChange
+    )?;
+    Ok(Self {
+      terminal: Terminal::new(CrosstermBackend::new(stdout))?,
+      status: String::new(),
+      released_keys: vec![],
+      last_frame: Instant::now(),
+      status_event_reader: None,
+    })
+  }
+}
+
+impl Drop for TerminalSystem {
+  fn drop(&mut self) {
+    let _ = execute!(
+      self.terminal.backend_mut(),
+      DisableFocusChange,
+      DisableMouseCapture,
+      terminal::LeaveAlternateScreen
+    );
+    let _ = terminal::disable_raw_mode();
+    let _ = self.terminal.show_cursor();
+  }
+}
+
+impl<'a> System<'a> for TerminalSystem {
+  type SystemData = (
+    // Resources
+    Read<'a, Viewport>,
+    Read<'a, Theme>,
+    Read<'a, LineClipMargin>,
+    Write<'a, MouseEventChannel>,
+    Write<'a, ViewportEventChannel>,
+    Write<'a, InputState>,
+    Write<'a, DeltaTime>,
+    Read<'a, StatusEventChannel>,
+    // Data
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+    (ReadStorage<'a, ScreenArc>, ReadStorage<'a, ArcStyle>),
+    (ReadStorage<'a, ScreenConic>, ReadStorage<'a, ConicStyle>),
+    ReadStorage<'a, ScreenRectangle>,
+    ReadStorage<'a, ScreenPolygon>,
+    (ReadStorage<'a, ScreenVector>, ReadStorage<'a, ScreenFreehandStroke>),
+    ReadStorage<'a, PointStyle>,
+    ReadStorage<'a, LineStyle>,
+    ReadStorage<'a, CircleStyle>,
+    ReadStorage<'a, RectangleStyle>,
+    ReadStorage<'a, PolygonStyle>,
+    ReadStorage<'a, VectorStyle>,
+    ReadStorage<'a, Label>,
+    (ReadStorage<'a, ScreenText>, ReadStorage<'a, TextStyle>),
+    (
+      ReadStorage<'a, Selected>,
+      ReadStorage<'a, Hovered>,
+      ReadStorage<'a, Hidden>,
+      ReadStorage<'a, Background>,
+    ),
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.status_event_reader = Some(world.fetch_mut::<StatusEventChannel>().register_reader());
+
+    // The sketch starts at the size of the terminal rather than of a window
+    if let Ok((columns, rows)) = terminal::size() {
+      world
+        .fetch_mut::<ViewportEventChannel>()
+        .single_write(ViewportEvent::Resize(screen_size(columns, rows)));
+    }
+  }
+
+  fn run(
+    &mut self,
+    (
+      viewport,
+      theme,
+      line_clip_margin,
+      mut mouse_event_channel,
+      mut viewport_event_channel,
+      mut input_state,
+      mut delta_time,
+      status_event_channel,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+      (scrn_arcs, arc_styles),
+      (scrn_conics, conic_styles),
+      scrn_rects,
+      scrn_polygons,
+      (scrn_vectors, scrn_strokes),
+      point_styles,
+      line_styles,
+      circle_styles,
+      rect_styles,
+      polygon_styles,
+      vector_styles,
+      labels,
+      (scrn_texts, text_styles),
+      (selecteds, hovereds, hiddens, backgrounds),
+    ): Self::SystemData,
+  ) {
+    input_state.reset_relative_data();
+    for key in self.released_keys.drain(..) {
+      input_state.set_key(key, false);
+    }
+
+    // Wait for the first event of the frame, then take the ones already there
+    let mut timeout = FRAME_DURATION;
+    while let Ok(true) = event::poll(timeout) {
+      match event::read() {
+        Ok(event) => handle_event(
+          event,
+          &mut self.released_keys,
+          &mut input_state,
+          &mut mouse_event_channel,
+          &mut viewport_event_channel,
+        ),
+        Err(_) => break,
+      }
+      timeout = Duration::from_secs(0);
+    }
+
+    let now = Instant::now();
+    delta_time.set((now - self.last_frame).as_secs_f64());
+    self.last_frame = now;
+
+    if let Some(reader) = &mut self.status_event_reader {
+      if let Some(StatusEvent::CursorPosition(text)) = status_event_channel.read(reader).last() {
+        self.status = text.clone();
+      }
+    }
+
+    let status = &self.status;
+    let _ = self.terminal.draw(|frame| {
+      render(
+        frame,
+        status,
+        &*viewport,
+        &*theme,
+        &*line_clip_margin,
+        &scrn_points,
+        &scrn_lines,
+        &scrn_circles,
+        &scrn_arcs,
+        &scrn_conics,
+        &scrn_rects,
+        &scrn_polygons,
+        &scrn_vectors,
+        &scrn_strokes,
+        &point_styles,
+        &line_styles,
+        &circle_styles,
+        &arc_styles,
+        &conic_styles,
+        &rect_styles,
+        &polygon_styles,
+        &vector_styles,
+        &labels,
+        &scrn_texts,
+        &text_styles,
+        &selecteds,
+        &hovereds,
+        &hiddens,
+        &backgrounds,
+      )
+    });
+    input_state.record_mouse_position(SystemTime::now());
+  }
+}
@@ -0,0 +1,90 @@
+use core_ui::resources::Key;
+use ratatui::crossterm::event::KeyCode;
+
+/// Terminals send characters rather than keys, a shifted character is taken as the key it is on
+/// for a US layout, e.g. `_` is the minus key
+pub fn key_code_to_key(code: KeyCode) -> Key {
+  match code {
+    KeyCode::Backspace => Key::Backspace,
+    KeyCode::Enter => Key::Return,
+    KeyCode::Left => Key::Left,
+    KeyCode::Right => Key::Right,
+    KeyCode::Up => Key::Up,
+    KeyCode::Down => Key::Down,
+    KeyCode::Home => Key::Home,
+    KeyCode::End => Key::End,
+    KeyCode::PageUp => Key::PageUp,
+    KeyCode::PageDown => Key::PageDown,
+    KeyCode::Tab | KeyCode::BackTab => Key::Tab,
+    KeyCode::Delete => Key::Delete,
+    KeyCode::Insert => Key::Insert,
+    KeyCode::Esc => Key::Escape,
+    KeyCode::F(n) => match n {
+      1 => Key::F1,
+      2 => Key::F2,
+      3 => Key::F3,
+      4 => Key::F4,
+      5 => Key::F5,
+      6 => Key::F6,
+      7 => Key::F7,
+      8 => Key::F8,
+      9 => Key::F9,
+      10 => Key::F10,
+      11 => Key::F11,
+      12 => Key::F12,
+      _ => Key::Unknown,
+    },
+    KeyCode::Char(c) => match c.to_ascii_lowercase() {
+      ' ' => Key::Space,
+      '\'' | '"' => Key::Quote,
+      ',' | '<' => Key::Comma,
+      '-' | '_' => Key::Minus,
+      '.' | '>' => Key::Period,
+      '/' | '?' => Key::Slash,
+      '0' | ')' => Key::D0,
+      '1' | '!' => Key::D1,
+      '2' | '@' => Key::D2,
+      '3' | '#' => Key::D3,
+      '4' | '$' => Key::D4,
+      '5' | '%' => Key::D5,
+      '6' | '^' => Key::D6,
+      '7' | '&' => Key::D7,
+      '8' | '*' => Key::D8,
+      '9' | '(' => Key::D9,
+      ';' | ':' => Key::Semicolon,
+      '=' | '+' => Key::Equals,
+      '[' | '{' => Key::LeftBracket,
+      '\\' | '|' => Key::Backslash,
+      ']' | '}' => Key::RightBracket,
+      '`' | '~' => Key::Backquote,
+      'a' => Key::A,
+      'b' => Key::B,
+      'c' => Key::C,
+      'd' => Key::D,
+      'e' => Key::E,
+      'f' => Key::F,
+      'g' => Key::G,
+      'h' => Key::H,
+      'i' => Key::I,
+      'j' => Key::J,
+      'k' => Key::K,
+      'l' => Key::L,
+      'm' => Key::M,
+      'n' => Key::N,
+      'o' => Key::O,
+      'p' => Key::P,
+      'q' => Key::Q,
+      'r' => Key::R,
+      's' => Key::S,
+      't' => Key::T,
+      'u' => Key::U,
+      'v' => Key::V,
+      'w' => Key::W,
+      'x' => Key::X,
+      'y' => Key::Y,
+      'z' => Key::Z,
+      _ => Key::Unknown,
+    },
+    _ => Key::Unknown,
+  }
+}
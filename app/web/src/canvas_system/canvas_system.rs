@@ -0,0 +1,162 @@
+use core_lib::{
+  components::{freehand::*, markers::*, screen_shapes::*, styles::*, symbolics::*},
+  events::*,
+  math::*,
+  resources::{LineClipMargin, Theme, Viewport},
+  utilities::LineClipCache,
+};
+use core_ui::{events::*, resources::*};
+use specs::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+use web_sys::{CanvasRenderingContext2d, Document, HtmlCanvasElement};
+
+use super::{event_handling::*, rendering::*};
+
+pub static PAGE_TITLE: &str = "Geometry Sketchpad";
+
+/// The counterpart of the window system of the piston app, on a canvas of the page. It handles
+/// the events queued since the last frame, then renders the frame
+pub struct CanvasSystem {
+  pub document: Document,
+  pub canvas: HtmlCanvasElement,
+  pub context: CanvasRenderingContext2d,
+  pub events: Rc<RefCell<Vec<DomEvent>>>, // Filled by the listeners of the page
+  pub last_frame: Option<f64>,            // Milliseconds since the epoch
+  pub line_clip_cache: LineClipCache<Entity>,
+  pub status_event_reader: Option<StatusEventReader>,
+}
+
+impl<'a> System<'a> for CanvasSystem {
+  type SystemData = (
+    Entities<'a>,
+    // Resources
+    Read<'a, Viewport>,
+    Read<'a, Theme>,
+    Read<'a, LineClipMargin>,
+    Write<'a, MouseEventChannel>,
+    Write<'a, ViewportEventChannel>,
+    Write<'a, InputState>,
+    Write<'a, DeltaTime>,
+    Read<'a, StatusEventChannel>,
+    // Data
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+    (ReadStorage<'a, ScreenArc>, ReadStorage<'a, ArcStyle>),
+    (ReadStorage<'a, ScreenConic>, ReadStorage<'a, ConicStyle>),
+    ReadStorage<'a, ScreenRectangle>,
+    ReadStorage<'a, ScreenPolygon>,
+    (ReadStorage<'a, ScreenVector>, ReadStorage<'a, ScreenFreehandStroke>),
+    ReadStorage<'a, PointStyle>,
+    ReadStorage<'a, LineStyle>,
+    ReadStorage<'a, CircleStyle>,
+    ReadStorage<'a, RectangleStyle>,
+    ReadStorage<'a, PolygonStyle>,
+    ReadStorage<'a, VectorStyle>,
+    (ReadStorage<'a, SymbolicPoint>, ReadStorage<'a, Label>),
+    (ReadStorage<'a, ScreenText>, ReadStorage<'a, TextStyle>),
+    (
+      ReadStorage<'a, Selected>,
+      ReadStorage<'a, Hovered>,
+      ReadStorage<'a, Hidden>,
+      ReadStorage<'a, Background>,
+    ),
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.status_event_reader = Some(world.fetch_mut::<StatusEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      viewport,
+      theme,
+      line_clip_margin,
+      mut mouse_event_channel,
+      mut viewport_event_channel,
+      mut input_state,
+      mut delta_time,
+      status_event_channel,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+      (scrn_arcs, arc_styles),
+      (scrn_conics, conic_styles),
+      scrn_rects,
+      scrn_polygons,
+      (scrn_vectors, scrn_strokes),
+      point_styles,
+      line_styles,
+      circle_styles,
+      rect_styles,
+      polygon_styles,
+      vector_styles,
+      (sym_points, labels),
+      (scrn_texts, text_styles),
+      (selecteds, hovereds, hiddens, backgrounds),
+    ): Self::SystemData,
+  ) {
+    input_state.reset_relative_data();
+    for event in self.events.borrow_mut().drain(..) {
+      handle_event(
+        event,
+        &mut input_state,
+        &mut mouse_event_channel,
+        &mut viewport_event_channel,
+      );
+    }
+
+    let now = js_sys::Date::now();
+    if let Some(last_frame) = self.last_frame {
+      delta_time.set((now - last_frame) / 1000.0);
+    }
+    self.last_frame = Some(now);
+
+    render(
+      &self.context,
+      vec2![self.canvas.width() as f64, self.canvas.height() as f64],
+      &*viewport,
+      &*theme,
+      &*line_clip_margin,
+      &mut self.line_clip_cache,
+      &entities,
+      &scrn_points,
+      &scrn_lines,
+      &scrn_circles,
+      &scrn_arcs,
+      &scrn_conics,
+      &scrn_rects,
+      &scrn_polygons,
+      &scrn_vectors,
+      &scrn_strokes,
+      &point_styles,
+      &line_styles,
+      &circle_styles,
+      &arc_styles,
+      &conic_styles,
+      &rect_styles,
+      &polygon_styles,
+      &vector_styles,
+      &sym_points,
+      &labels,
+      &scrn_texts,
+      &text_styles,
+      &selecteds,
+      &hovereds,
+      &hiddens,
+      &backgrounds,
+    );
+    self.line_clip_cache.retain(|ent| scrn_lines.contains(ent));
+    input_state.record_mouse_position(SystemTime::now());
+
+    // There's no status bar, the cursor position is shown in the title of the page
+    if let Some(reader) = &mut self.status_event_reader {
+      if let Some(StatusEvent::CursorPosition(text)) = status_event_channel.read(reader).last() {
+        self.document.set_title(&format!("{} {}", PAGE_TITLE, text));
+      }
+    }
+  }
+}
@@ -0,0 +1,138 @@
+use core_lib::{events::*, math::*, utilities::*};
+use core_ui::{events::*, resources::*};
+use specs::prelude::*;
+
+static CLICK_TIME_THRESHOLD: u128 = 100; // 0.1 second
+static LEFT_BUTTON: i16 = 0;
+static RIGHT_BUTTON: i16 = 2;
+
+/// The events of the page, queued by the listeners until the next frame
+#[derive(Debug, Clone)]
+pub enum DomEvent {
+  MouseButton(i16, bool),          // Button, pressed
+  MouseMove(Vector2, Vector2),     // Position on the canvas, movement since the last one
+  Wheel(Vector2),                  // In notches of the wheel, positive when scrolling up
+  Touch(i64, TouchPhase, Vector2), // Finger, position on the canvas
+  PenPressure(Option<f64>),        // From 0 to 1, none once the pen is lifted
+  Key(Key, bool),                  // Key, pressed
+  Text(String),
+  Focus(bool),
+  Resize(Vector2), // Size of the canvas
+}
+
+pub fn handle_event<'a>(
+  event: DomEvent,
+  input_state: &mut Write<'a, InputState>,
+  mouse_event_channel: &mut Write<'a, MouseEventChannel>,
+  viewport_event_channel: &mut Write<'a, ViewportEventChannel>,
+) {
+  match event {
+    DomEvent::MouseButton(button, is_pressed) if button == LEFT_BUTTON => {
+      input_state.mouse_left_button.set(is_pressed);
+      if is_pressed {
+        input_state.mouse_left_button_last_pressed = Some(SystemTime::now());
+        mouse_event_channel.single_write(MouseEvent::MouseDown(input_state.mouse_abs_pos));
+      } else {
+        mouse_event_channel.single_write(MouseEvent::MouseUp(input_state.mouse_abs_pos));
+        if input_state.is_mouse_left_button_dragging {
+          input_state.is_mouse_left_button_dragging = false;
+          mouse_event_channel.single_write(MouseEvent::DragEnd(input_state.mouse_abs_pos));
+        } else if let Some(last_pressed) = input_state.mouse_left_button_last_pressed {
+          if last_pressed.elapsed().unwrap().as_millis() < CLICK_TIME_THRESHOLD {
+            mouse_event_channel.single_write(MouseEvent::Click(input_state.mouse_abs_pos));
+          }
+        }
+      }
+    }
+    DomEvent::MouseButton(button, is_pressed) if button == RIGHT_BUTTON => {
+      input_state.mouse_right_button.set(is_pressed);
+    }
+    DomEvent::MouseMove(position, movement) => {
+      input_state.mouse_abs_pos = position.into();
+      let scrn_rel_mov = From::<Vector2>::from(movement);
+      input_state.mouse_rel_movement = input_state.mouse_rel_movement + scrn_rel_mov;
+      if input_state.is_mouse_left_button_dragging {
+        mouse_event_channel.single_write(MouseEvent::DragMove(scrn_rel_mov, input_state.mouse_abs_pos));
+      } else if input_state.mouse_left_button.is_activated() {
+        input_state.is_mouse_left_button_dragging = true;
+        mouse_event_channel.single_write(MouseEvent::DragBegin(input_state.mouse_abs_pos));
+      }
+    }
+    DomEvent::PenPressure(pressure) => input_state.pen_pressure = pressure,
+    DomEvent::Touch(id, phase, position) => {
+      let (fingers_before, previous) = (input_state.touches.len(), input_state.touches.get(&id).copied());
+      input_state.touch(id, phase, position.into());
+      // A single finger acts as the left button, a second one turns it into a gesture instead
+      let mouse_events = match phase {
+        TouchPhase::Start if fingers_before == 0 => vec![
+          DomEvent::MouseMove(position, vec2![0., 0.]),
+          DomEvent::MouseButton(LEFT_BUTTON, true),
+        ],
+        TouchPhase::Move if input_state.touches.len() == 1 => match previous {
+          Some(ScreenPosition(previous)) => vec![DomEvent::MouseMove(position, position - previous)],
+          None => vec![],
+        },
+        _ if input_state.touches.len() != 1 && input_state.mouse_left_button.is_activated() => {
+          vec![DomEvent::MouseButton(LEFT_BUTTON, false)]
+        }
+        _ => vec![],
+      };
+      for event in mouse_events {
+        handle_event(event, input_state, mouse_event_channel, viewport_event_channel);
+      }
+    }
+    DomEvent::Wheel(rel_scroll) => input_state.rel_scroll = input_state.rel_scroll + rel_scroll,
+    DomEvent::Key(key, is_pressed) => input_state.set_key(key, is_pressed),
+    DomEvent::Text(text) => input_state.type_text(&text),
+    DomEvent::Focus(focus) => input_state.set_focus(focus, mouse_event_channel),
+    DomEvent::Resize(size) => viewport_event_channel.single_write(ViewportEvent::Resize(size)),
+    _ => (),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_single_finger_drags_and_second_finger_lets_go() {
+    let mut world = World::new();
+    world.insert(InputState::default());
+    world.insert(MouseEventChannel::new());
+    world.insert(ViewportEventChannel::new());
+    let mut reader = world.fetch_mut::<MouseEventChannel>().register_reader();
+    let mut touch = |world: &World, id: i64, phase: TouchPhase, position: Vector2| {
+      let (mut input_state, mut mouse_event_channel, mut viewport_event_channel) =
+        world.system_data::<(Write<InputState>, Write<MouseEventChannel>, Write<ViewportEventChannel>)>();
+      handle_event(
+        DomEvent::Touch(id, phase, position),
+        &mut input_state,
+        &mut mouse_event_channel,
+        &mut viewport_event_channel,
+      );
+      mouse_event_channel
+        .read(&mut reader)
+        .map(|event| match event {
+          MouseEvent::DragBegin(_) => "drag_begin",
+          MouseEvent::DragMove(_, _) => "drag_move",
+          MouseEvent::DragEnd(_) => "drag_end",
+          MouseEvent::MouseDown(_) => "mouse_down",
+          MouseEvent::MouseUp(_) => "mouse_up",
+          MouseEvent::Click(_) => "click",
+        })
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(touch(&world, 1, TouchPhase::Start, vec2![10., 10.]), vec!["mouse_down"]);
+    assert_eq!(touch(&world, 1, TouchPhase::Move, vec2![20., 10.]), vec!["drag_begin"]);
+    assert_eq!(touch(&world, 1, TouchPhase::Move, vec2![30., 10.]), vec!["drag_move"]);
+    assert_eq!(world.fetch::<InputState>().mouse_abs_pos.0, vec2![30., 10.]);
+
+    // A second finger turns the drag into a gesture
+    assert_eq!(
+      touch(&world, 2, TouchPhase::Start, vec2![50., 50.]),
+      vec!["mouse_up", "drag_end"]
+    );
+    assert!(!world.fetch::<InputState>().mouse_left_button.is_activated());
+  }
+}
@@ -0,0 +1,143 @@
+use super::event_handling::DomEvent;
+use crate::utilities::dom_code_to_key;
+use core_lib::math::*;
+use core_ui::resources::TouchPhase;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{
+  EventTarget, FocusEvent, HtmlCanvasElement, KeyboardEvent, MouseEvent, PointerEvent, TouchEvent, WheelEvent, Window,
+};
+
+static PIXELS_PER_NOTCH: f64 = 100.0; // Of the wheel, when the page scrolls by pixels
+
+/// Queues the events of the canvas and of the window for the canvas system. The listeners stay for
+/// as long as the page, so their closures are leaked on purpose
+pub fn listen(window: &Window, canvas: &HtmlCanvasElement, events: &Rc<RefCell<Vec<DomEvent>>>) -> Result<(), JsValue> {
+  // The canvas takes the keyboard focus, so that the shortcuts don't reach the browser
+  canvas.set_tab_index(0);
+  canvas.focus()?;
+
+  let queue = events.clone();
+  on(canvas, "mousedown", move |event: MouseEvent| {
+    queue.borrow_mut().push(DomEvent::MouseButton(event.button(), true))
+  })?;
+  let queue = events.clone();
+  on(canvas, "mouseup", move |event: MouseEvent| {
+    queue.borrow_mut().push(DomEvent::MouseButton(event.button(), false))
+  })?;
+  let queue = events.clone();
+  on(canvas, "mousemove", move |event: MouseEvent| {
+    let position = vec2![event.offset_x() as f64, event.offset_y() as f64];
+    let movement = vec2![event.movement_x() as f64, event.movement_y() as f64];
+    queue.borrow_mut().push(DomEvent::MouseMove(position, movement))
+  })?;
+  // A pen also sends the mouse events, right after these ones telling how hard it presses
+  for name in &["pointerdown", "pointermove"] {
+    let queue = events.clone();
+    on(canvas, name, move |event: PointerEvent| {
+      if event.pointer_type() == "pen" && event.buttons() != 0 {
+        queue
+          .borrow_mut()
+          .push(DomEvent::PenPressure(Some(event.pressure() as f64)))
+      }
+    })?;
+  }
+  for name in &["pointerup", "pointercancel"] {
+    let queue = events.clone();
+    on(canvas, name, move |event: PointerEvent| {
+      if event.pointer_type() == "pen" {
+        queue.borrow_mut().push(DomEvent::PenPressure(None))
+      }
+    })?;
+  }
+  on(canvas, "contextmenu", |event: MouseEvent| event.prevent_default())?;
+  let queue = events.clone();
+  on(canvas, "wheel", move |event: WheelEvent| {
+    event.prevent_default();
+    let scale = if event.delta_mode() == WheelEvent::DOM_DELTA_PIXEL {
+      PIXELS_PER_NOTCH
+    } else {
+      1.0
+    };
+    let rel_scroll = vec2![-event.delta_x(), -event.delta_y()] / scale;
+    queue.borrow_mut().push(DomEvent::Wheel(rel_scroll))
+  })?;
+  // The page would scroll and zoom itself otherwise. The canvas fills the window, so the position
+  // in the window is the one on the canvas
+  for (name, phase) in &[
+    ("touchstart", TouchPhase::Start),
+    ("touchmove", TouchPhase::Move),
+    ("touchend", TouchPhase::End),
+    ("touchcancel", TouchPhase::End),
+  ] {
+    let (queue, phase) = (events.clone(), *phase);
+    on(canvas, name, move |event: TouchEvent| {
+      event.prevent_default();
+      let touches = event.changed_touches();
+      let mut queue = queue.borrow_mut();
+      for touch in (0..touches.length()).filter_map(|i| touches.get(i)) {
+        let position = vec2![touch.client_x() as f64, touch.client_y() as f64];
+        queue.push(DomEvent::Touch(touch.identifier() as i64, phase, position));
+      }
+    })?;
+  }
+  let queue = events.clone();
+  on(canvas, "keydown", move |event: KeyboardEvent| {
+    event.prevent_default();
+    let mut queue = queue.borrow_mut();
+    queue.push(DomEvent::Key(dom_code_to_key(&event.code()), true));
+    // The keys typing a character have it as their name, e.g. `a` or `A` with shift
+    let text = event.key();
+    if text.chars().count() == 1 && !event.ctrl_key() && !event.meta_key() {
+      queue.push(DomEvent::Text(text));
+    }
+  })?;
+  let queue = events.clone();
+  on(canvas, "keyup", move |event: KeyboardEvent| {
+    queue
+      .borrow_mut()
+      .push(DomEvent::Key(dom_code_to_key(&event.code()), false))
+  })?;
+  let queue = events.clone();
+  on(canvas, "focus", move |_: FocusEvent| {
+    queue.borrow_mut().push(DomEvent::Focus(true))
+  })?;
+  let queue = events.clone();
+  on(canvas, "blur", move |_: FocusEvent| {
+    queue.borrow_mut().push(DomEvent::Focus(false))
+  })?;
+
+  // The canvas fills the window, it is resized along with it
+  let resize = {
+    let (window, canvas, queue) = (window.clone(), canvas.clone(), events.clone());
+    move || {
+      let width = window
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .unwrap_or(0.0);
+      let height = window
+        .inner_height()
+        .ok()
+        .and_then(|height| height.as_f64())
+        .unwrap_or(0.0);
+      canvas.set_width(width as u32);
+      canvas.set_height(height as u32);
+      queue.borrow_mut().push(DomEvent::Resize(vec2![width, height]))
+    }
+  };
+  resize();
+  on(window, "resize", move |_: web_sys::Event| resize())
+}
+
+fn on<E: JsCast + 'static, F: FnMut(E) + 'static>(
+  target: &EventTarget,
+  name: &str,
+  mut listener: F,
+) -> Result<(), JsValue> {
+  let closure =
+    Closure::wrap(Box::new(move |event: web_sys::Event| listener(event.unchecked_into())) as Box<dyn FnMut(_)>);
+  target.add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())?;
+  closure.forget();
+  Ok(())
+}
@@ -0,0 +1,7 @@
+mod canvas_system;
+mod event_handling;
+mod listening;
+mod rendering;
+
+pub use canvas_system::*;
+pub use listening::listen;
@@ -0,0 +1,467 @@
+use core_lib::{
+  components::{freehand::*, markers::*, screen_shapes::*, styles::*, symbolics::*},
+  math::*,
+  resources::{LineClipMargin, Theme, Viewport},
+  utilities::*,
+};
+use specs::prelude::*;
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d as Context;
+
+static TICK_LENGTH: f64 = 10.0; // Pixel
+static TICK_SPACING: f64 = 4.0; // Pixel
+static CONIC_SEGMENTS: usize = 64;
+static LABEL_FONT_SIZE: u32 = 14; // Pixel
+static HALO_WIDTH: f64 = 3.0; // Pixel, added to the half width of the hovered curves
+static HALO_ALPHA: f32 = 0.4;
+
+/// Same layering as the piston app, from the grid at the bottom to the select rectangle on top
+pub fn render<'a>(
+  context: &Context,
+  size: Vector2,
+  viewport: &Viewport,
+  theme: &Theme,
+  line_clip_margin: &LineClipMargin,
+  line_clip_cache: &mut LineClipCache<Entity>,
+  entities: &Entities<'a>,
+  scrn_points: &ReadStorage<'a, ScreenPoint>,
+  scrn_lines: &ReadStorage<'a, ScreenLine>,
+  scrn_circles: &ReadStorage<'a, ScreenCircle>,
+  scrn_arcs: &ReadStorage<'a, ScreenArc>,
+  scrn_conics: &ReadStorage<'a, ScreenConic>,
+  scrn_rects: &ReadStorage<'a, ScreenRectangle>,
+  scrn_polygons: &ReadStorage<'a, ScreenPolygon>,
+  scrn_vectors: &ReadStorage<'a, ScreenVector>,
+  scrn_strokes: &ReadStorage<'a, ScreenFreehandStroke>,
+  point_styles: &ReadStorage<'a, PointStyle>,
+  line_styles: &ReadStorage<'a, LineStyle>,
+  circle_styles: &ReadStorage<'a, CircleStyle>,
+  arc_styles: &ReadStorage<'a, ArcStyle>,
+  conic_styles: &ReadStorage<'a, ConicStyle>,
+  rect_styles: &ReadStorage<'a, RectangleStyle>,
+  polygon_styles: &ReadStorage<'a, PolygonStyle>,
+  vector_styles: &ReadStorage<'a, VectorStyle>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  labels: &ReadStorage<'a, Label>,
+  scrn_texts: &ReadStorage<'a, ScreenText>,
+  text_styles: &ReadStorage<'a, TextStyle>,
+  selecteds: &ReadStorage<'a, Selected>,
+  hovereds: &ReadStorage<'a, Hovered>,
+  hiddens: &ReadStorage<'a, Hidden>,
+  backgrounds: &ReadStorage<'a, Background>,
+) {
+  // The lines are clipped only when they or the viewport changed since the last frame
+  let clip_bounds = line_clip_margin.clip_aabb(viewport);
+
+  // Clean the screen first
+  context.set_fill_style_str(&css(theme.background));
+  context.fill_rect(0.0, 0.0, size.x, size.y);
+
+  // The grid goes under all the geometry
+  for (ent, line, style, _) in (entities, scrn_lines, line_styles, backgrounds).join() {
+    render_line(
+      line,
+      line_clip_cache.clip(ent, *line, clip_bounds),
+      &style.flatten_alpha(),
+      false,
+      theme,
+      context,
+    );
+  }
+
+  // Polygons are filled areas, they go below the rest
+  for (scrn_polygon, style, _) in (scrn_polygons, polygon_styles, !hiddens).join() {
+    render_polygon(scrn_polygon, &style.flatten_alpha(), context);
+  }
+
+  // The hovered curves get a halo under all of them
+  for (circle, style, _, _) in (scrn_circles, circle_styles, hovereds, !hiddens).join() {
+    let halo = CircleStyle {
+      fill: Color::transparent(),
+      border: halo_style(style.border.width, theme),
+      alpha: 1.0,
+    };
+    render_circle(circle, &halo, false, theme, context);
+  }
+  for (arc, style, _, _) in (scrn_arcs, arc_styles, hovereds, !hiddens).join() {
+    let LineStyle { color, width, .. } = halo_style(style.width, theme);
+    render_arc(
+      arc,
+      &ArcStyle {
+        color,
+        width,
+        alpha: 1.0,
+      },
+      false,
+      theme,
+      context,
+    );
+  }
+  for (conic, style, _, _) in (scrn_conics, conic_styles, hovereds, !hiddens).join() {
+    let LineStyle { color, width, .. } = halo_style(style.width, theme);
+    render_conic(
+      conic,
+      &ConicStyle {
+        color,
+        width,
+        alpha: 1.0,
+      },
+      false,
+      theme,
+      context,
+    );
+  }
+  for (ent, line, style, _, _) in (entities, scrn_lines, line_styles, hovereds, !hiddens).join() {
+    render_line(
+      line,
+      line_clip_cache.clip(ent, *line, clip_bounds),
+      &halo_style(style.width, theme),
+      false,
+      theme,
+      context,
+    );
+  }
+
+  // Then the circles, arcs and conics
+  for (circle, style, selected, _) in (scrn_circles, circle_styles, selecteds.maybe(), !hiddens).join() {
+    render_circle(circle, &style.flatten_alpha(), selected.is_some(), theme, context);
+  }
+  for (arc, style, selected, _) in (scrn_arcs, arc_styles, selecteds.maybe(), !hiddens).join() {
+    render_arc(arc, &style.flatten_alpha(), selected.is_some(), theme, context);
+  }
+  for (conic, style, selected, _) in (scrn_conics, conic_styles, selecteds.maybe(), !hiddens).join() {
+    render_conic(conic, &style.flatten_alpha(), selected.is_some(), theme, context);
+  }
+
+  // Then the lines, the selected ones over the others
+  for (ent, line, style, _, _, _) in (entities, scrn_lines, line_styles, !selecteds, !hiddens, !backgrounds).join() {
+    render_line(
+      line,
+      line_clip_cache.clip(ent, *line, clip_bounds),
+      &style.flatten_alpha(),
+      false,
+      theme,
+      context,
+    );
+  }
+  for (ent, line, style, _, _) in (entities, scrn_lines, line_styles, selecteds, !hiddens).join() {
+    render_line(
+      line,
+      line_clip_cache.clip(ent, *line, clip_bounds),
+      &style.flatten_alpha(),
+      true,
+      theme,
+      context,
+    );
+  }
+
+  // Vectors go with the lines, their arrowheads are drawn over the lines they end on
+  for (vector, style, selected, _) in (scrn_vectors, vector_styles, selecteds.maybe(), !hiddens).join() {
+    render_vector(vector, &style.flatten_alpha(), selected.is_some(), theme, context);
+  }
+
+  // The ink goes over the constructions it annotates
+  for (stroke, style, selected, _) in (scrn_strokes, line_styles, selecteds.maybe(), !hiddens).join() {
+    render_freehand(stroke, &style.flatten_alpha(), selected.is_some(), theme, context);
+  }
+
+  // Then the points, the selected ones over the others
+  for (point, style, sym_point, _, _) in (scrn_points, point_styles, sym_points, !selecteds, !hiddens).join() {
+    let style = style.resolve_fill(sym_point).flatten_alpha();
+    render_point(point, &style, false, theme, context);
+  }
+  for (point, style, sym_point, _, _) in (scrn_points, point_styles, sym_points, selecteds, !hiddens).join() {
+    let style = style.resolve_fill(sym_point).flatten_alpha();
+    render_point(point, &style, true, theme, context);
+  }
+
+  // The labels go over the points they name, texts go along with them
+  for (point, label, _) in (scrn_points, labels, !hiddens).join() {
+    let Vector2 { x, y } = point.0 + label.offset;
+    render_text_at(&label.text, x, y, theme.line, LABEL_FONT_SIZE as f64, context);
+  }
+  for (text, style, selected, _) in (scrn_texts, text_styles, selecteds.maybe(), !hiddens).join() {
+    let style = style.flatten_alpha();
+    let color = if selected.is_some() {
+      theme.selection
+    } else {
+      style.color
+    };
+    let Vector2 { x, y } = text.position.0;
+    render_text_at(&text.text, x, y, color, style.font_size, context);
+  }
+
+  // Lastly the select rectangle
+  for (rect, style) in (scrn_rects, rect_styles).join() {
+    render_rectangle(rect, &style.flatten_alpha(), context);
+  }
+}
+
+/// A wide translucent stroke in the hover color, drawn under a curve of the given width
+fn halo_style(width: f64, theme: &Theme) -> LineStyle {
+  LineStyle {
+    color: theme.hover.apply_alpha(HALO_ALPHA),
+    width: width + HALO_WIDTH,
+    marks: EqualityMarks::default(),
+    dash: DashPattern::Solid,
+    alpha: 1.0,
+  }
+}
+
+fn css(color: Color) -> String {
+  let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+  format!(
+    "rgba({}, {}, {}, {})",
+    channel(color.r),
+    channel(color.g),
+    channel(color.b),
+    color.a
+  )
+}
+
+/// Piston takes the half width of the strokes, the canvas the whole one. The widths are doubled
+/// so that both apps look the same
+fn begin_stroke(color: Color, radius: f64, context: &Context) {
+  context.set_stroke_style_str(&css(color));
+  context.set_line_width(radius * 2.0);
+  context.begin_path();
+}
+
+fn stroke_segment(color: Color, radius: f64, from: Vector2, to: Vector2, context: &Context) {
+  begin_stroke(color, radius, context);
+  context.move_to(from.x, from.y);
+  context.line_to(to.x, to.y);
+  context.stroke();
+}
+
+fn stroke_circle(color: Color, radius: f64, center: Vector2, circle_radius: f64, context: &Context) {
+  if circle_radius > 0.0 {
+    begin_stroke(color, radius, context);
+    let _ = context.arc(center.x, center.y, circle_radius, 0.0, 2.0 * PI);
+    context.stroke();
+  }
+}
+
+fn fill_path(color: Color, vertices: &[Vector2], context: &Context) {
+  if let Some((first, rest)) = vertices.split_first() {
+    context.set_fill_style_str(&css(color));
+    context.begin_path();
+    context.move_to(first.x, first.y);
+    for vertex in rest {
+      context.line_to(vertex.x, vertex.y);
+    }
+    context.close_path();
+    context.fill();
+  }
+}
+
+fn fill_disc(color: Color, center: Vector2, radius: f64, context: &Context) {
+  if radius > 0.0 {
+    context.set_fill_style_str(&css(color));
+    context.begin_path();
+    let _ = context.arc(center.x, center.y, radius, 0.0, 2.0 * PI);
+    context.fill();
+  }
+}
+
+fn render_point(
+  ScreenPosition(center): &ScreenPoint,
+  style: &PointStyle,
+  selected: bool,
+  theme: &Theme,
+  context: &Context,
+) {
+  if selected {
+    stroke_circle(theme.selection, 0.5, *center, style.radius + 3.0, context);
+  }
+  // The marker is drawn as a border with a smaller one on top. Hollow points only keep their
+  // border, showing the background through, while crosses and pluses are outlined strokes
+  let center_color = match (style.marker_shape, style.fill) {
+    (MarkerShape::Cross, _) | (MarkerShape::Plus, _) => style.color,
+    (MarkerShape::HollowCircle, _) | (_, PointFill::Hollow) => theme.background,
+    _ => style.color,
+  };
+  for primitive in style.marker_shape.primitives(*center, style.radius) {
+    render_marker_primitive(&primitive, style.border_color, 1.5, context);
+  }
+  for primitive in style.marker_shape.primitives(*center, style.radius - 1.5) {
+    render_marker_primitive(&primitive, center_color, 0.75, context);
+  }
+}
+
+fn render_marker_primitive(primitive: &MarkerPrimitive, color: Color, stroke_radius: f64, context: &Context) {
+  match primitive {
+    MarkerPrimitive::Disc(center, radius) | MarkerPrimitive::Ring(center, radius) => {
+      fill_disc(color, *center, *radius, context)
+    }
+    MarkerPrimitive::Polygon(vertices) => fill_path(color, vertices, context),
+    MarkerPrimitive::Segment(from, to) => stroke_segment(color, stroke_radius, *from, *to, context),
+  }
+}
+
+fn render_line(
+  l: &ScreenLine,
+  clipped: Option<(Vector2, Vector2)>, // The part of the line on the screen
+  style: &LineStyle,
+  selected: bool,
+  theme: &Theme,
+  context: &Context,
+) {
+  if let Some((from, to)) = clipped {
+    for (dash_from, dash_to) in style.dash.dashes(from, to) {
+      stroke_segment(style.color, style.width, dash_from, dash_to, context);
+    }
+    for (tick_from, tick_to) in Into::<Line>::into(*l).tick_marks(style.marks.0, TICK_LENGTH, TICK_SPACING) {
+      stroke_segment(style.color, style.width, tick_from, tick_to, context);
+    }
+    if selected {
+      let Vector2 { x: dx, y: dy } = (to - from).normalized();
+      let perp_dir = vec2![-dy, dx] * (style.width / 2.0 + 3.0);
+      stroke_segment(theme.selection, 0.5, from - perp_dir, to - perp_dir, context);
+      stroke_segment(theme.selection, 0.5, from + perp_dir, to + perp_dir, context);
+    }
+  }
+}
+
+fn render_circle(
+  ScreenCircle { center, radius }: &ScreenCircle,
+  style: &CircleStyle,
+  selected: bool,
+  theme: &Theme,
+  context: &Context,
+) {
+  let center: Vector2 = Into::<Vector2>::into(*center);
+  let radius: f64 = Into::<f64>::into(*radius);
+  fill_disc(style.fill, center, radius, context);
+  stroke_circle(style.border.color, style.border.width, center, radius, context);
+  if selected {
+    for r in &[
+      radius - style.border.width / 2.0 - 3.0,
+      radius + style.border.width / 2.0 + 3.0,
+    ] {
+      stroke_circle(theme.selection, 0.5, center, *r, context);
+    }
+  }
+}
+
+fn render_arc(
+  ScreenArc {
+    center,
+    radius,
+    start,
+    sweep,
+  }: &ScreenArc,
+  style: &ArcStyle,
+  selected: bool,
+  theme: &Theme,
+  context: &Context,
+) {
+  let center: Vector2 = Into::<Vector2>::into(*center);
+  let radius: f64 = Into::<f64>::into(*radius);
+  let stroke_arc = |color: Color, stroke_radius: f64, r: f64| {
+    if r > 0.0 {
+      begin_stroke(color, stroke_radius, context);
+      let _ = context.arc_with_anticlockwise(center.x, center.y, r, *start, start + sweep, *sweep < 0.0);
+      context.stroke();
+    }
+  };
+  stroke_arc(style.color, style.width, radius);
+  if selected {
+    for r in &[radius - style.width / 2.0 - 3.0, radius + style.width / 2.0 + 3.0] {
+      stroke_arc(theme.selection, 0.5, *r);
+    }
+  }
+}
+
+fn render_conic(conic: &ScreenConic, style: &ConicStyle, selected: bool, theme: &Theme, context: &Context) {
+  let ellipse: Ellipse = (*conic).into();
+  let render_outline = |ellipse: Ellipse, color: Color, width: f64| {
+    let step = 2.0 * PI / CONIC_SEGMENTS as f64;
+    begin_stroke(color, width, context);
+    let start = ellipse.point_at(0.0);
+    context.move_to(start.x, start.y);
+    for i in 1..=CONIC_SEGMENTS {
+      let to = ellipse.point_at(step * i as f64);
+      context.line_to(to.x, to.y);
+    }
+    context.stroke();
+  };
+  render_outline(ellipse, style.color, style.width);
+  if selected {
+    for offset in &[-style.width / 2.0 - 3.0, style.width / 2.0 + 3.0] {
+      let outline = Ellipse {
+        rx: (ellipse.rx + offset).max(0.0),
+        ry: (ellipse.ry + offset).max(0.0),
+        ..ellipse
+      };
+      render_outline(outline, theme.selection, 0.5);
+    }
+  }
+}
+
+/// Each piece of the stroke is as wide as the pen was pressed at its ends, the round caps join
+/// the pieces
+fn render_freehand(stroke: &ScreenFreehandStroke, style: &LineStyle, selected: bool, theme: &Theme, context: &Context) {
+  context.set_line_cap("round");
+  let render_samples = |color: Color, extra: f64| {
+    if let [(only, pressure)] = stroke.samples.as_slice() {
+      let radius = FreehandStroke::width_at(*pressure, style.width) + extra;
+      stroke_segment(color, radius, only.0, only.0, context);
+    }
+    for pair in stroke.samples.windows(2) {
+      let ((from, p1), (to, p2)) = (pair[0], pair[1]);
+      let radius = FreehandStroke::width_at((p1 + p2) / 2.0, style.width) + extra;
+      stroke_segment(color, radius, from.0, to.0, context);
+    }
+  };
+  if selected {
+    render_samples(theme.selection, 3.0);
+  }
+  render_samples(style.color, 0.0);
+  context.set_line_cap("butt");
+}
+
+fn render_text_at(text: &str, x: f64, y: f64, color: Color, font_size: f64, context: &Context) {
+  context.set_fill_style_str(&css(color));
+  context.set_font(&format!("{}px sans-serif", font_size));
+  let _ = context.fill_text(text, x, y);
+}
+
+fn render_rectangle(rect: &AABB, style: &RectangleStyle, context: &Context) {
+  context.set_fill_style_str(&css(style.fill));
+  context.fill_rect(rect.x, rect.y, rect.width, rect.height);
+  begin_stroke(style.border.color, style.border.width, context);
+  context.rect(rect.x, rect.y, rect.width, rect.height);
+  context.stroke();
+}
+
+fn render_polygon(ScreenPolygon { vertices }: &ScreenPolygon, style: &PolygonStyle, context: &Context) {
+  let path = vertices.iter().map(|v| v.0).collect::<Vec<_>>();
+  fill_path(style.fill, &path, context);
+  for (i, from) in path.iter().enumerate() {
+    stroke_segment(
+      style.border.color,
+      style.border.width,
+      *from,
+      path[(i + 1) % path.len()],
+      context,
+    );
+  }
+}
+
+fn render_vector(vector: &ScreenVector, style: &VectorStyle, selected: bool, theme: &Theme, context: &Context) {
+  let (from, to) = (vector.from.0, vector.to.0);
+  if selected && from != to {
+    let Vector2 { x: dx, y: dy } = (to - from).normalized();
+    let perp_dir = vec2![-dy, dx] * (style.width / 2.0 + 3.0);
+    for offset in &[perp_dir, -perp_dir] {
+      stroke_segment(theme.selection, 0.5, from + *offset, to + *offset, context);
+    }
+  }
+  stroke_segment(style.color, style.width, from, to, context);
+  if let Some((side1, side2)) = vector.arrowhead(style.head_length) {
+    for side in &[side1, side2] {
+      stroke_segment(style.color, style.width, to, side.0, context);
+    }
+  }
+}
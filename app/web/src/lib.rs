@@ -0,0 +1,84 @@
+#[macro_use]
+extern crate core_lib;
+extern crate core_ui;
+extern crate rayon;
+extern crate specs;
+
+mod canvas_system;
+mod utilities;
+
+use canvas_system::{listen, CanvasSystem};
+use core_lib::utilities::LineClipCache;
+use core_ui::{resources::ExitState, setup_core_ui};
+use rayon::ThreadPoolBuilder;
+use specs::prelude::*;
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+/// Runs the sketchpad on the canvas of the given id, which is resized to fill the window. Every
+/// frame is dispatched from `requestAnimationFrame`
+#[wasm_bindgen]
+pub fn start(canvas_id: &str) -> Result<(), JsValue> {
+  let window = web_sys::window().ok_or("No window")?;
+  let document = window.document().ok_or("No document")?;
+  let canvas = document
+    .get_element_by_id(canvas_id)
+    .ok_or_else(|| format!("No element of id {}", canvas_id))?
+    .dyn_into::<HtmlCanvasElement>()?;
+  let context = canvas
+    .get_context("2d")?
+    .ok_or("No 2d context")?
+    .dyn_into::<CanvasRenderingContext2d>()?;
+
+  let events = Rc::new(RefCell::new(vec![]));
+  listen(&window, &canvas, &events)?;
+
+  // The page cannot spawn threads, the systems run one after another on its own thread
+  let thread_pool = ThreadPoolBuilder::new()
+    .num_threads(1)
+    .use_current_thread()
+    .build()
+    .map_err(|err| err.to_string())?;
+
+  let mut world = World::new();
+  let mut builder = DispatcherBuilder::new().with_pool(Arc::new(thread_pool));
+
+  // Setup the core ui
+  setup_core_ui(&mut builder);
+
+  // Add the canvas system and build the dispatcher
+  builder.add_thread_local(CanvasSystem {
+    document,
+    canvas,
+    context,
+    events,
+    last_frame: None,
+    line_clip_cache: LineClipCache::default(),
+    status_event_reader: None,
+  });
+  let mut dispatcher = builder.build();
+  dispatcher.setup(&mut world);
+
+  // The frame callback requests the next frame, so it holds on to itself until exiting
+  let frame = Rc::new(RefCell::new(None::<Closure<dyn FnMut()>>));
+  let next_frame = frame.clone();
+  *frame.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+    dispatcher.dispatch(&world);
+    if world.fetch::<ExitState>().is_exiting() {
+      next_frame.borrow_mut().take();
+    } else if let Some(callback) = &*next_frame.borrow() {
+      request_animation_frame(callback);
+    }
+  }) as Box<dyn FnMut()>));
+  if let Some(callback) = &*frame.borrow() {
+    request_animation_frame(callback);
+  }
+  Ok(())
+}
+
+fn request_animation_frame(callback: &Closure<dyn FnMut()>) {
+  if let Some(window) = web_sys::window() {
+    let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+  }
+}
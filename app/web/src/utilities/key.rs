@@ -0,0 +1,105 @@
+use core_ui::resources::Key;
+
+/// The key from the `code` of a keyboard event, which is the physical key whatever the layout.
+/// The core takes Ctrl as the command key outside of macOS, which the browser cannot tell apart,
+/// so Cmd is taken as Ctrl too for the shortcuts to work on a mac
+pub fn dom_code_to_key(code: &str) -> Key {
+  match code {
+    "Backspace" => Key::Backspace,
+    "Tab" => Key::Tab,
+    "Enter" => Key::Return,
+    "NumpadEnter" => Key::NumPadEnter,
+    "Escape" => Key::Escape,
+    "Space" => Key::Space,
+    "Quote" => Key::Quote,
+    "Comma" => Key::Comma,
+    "Minus" => Key::Minus,
+    "Period" => Key::Period,
+    "Slash" => Key::Slash,
+    "Digit0" => Key::D0,
+    "Digit1" => Key::D1,
+    "Digit2" => Key::D2,
+    "Digit3" => Key::D3,
+    "Digit4" => Key::D4,
+    "Digit5" => Key::D5,
+    "Digit6" => Key::D6,
+    "Digit7" => Key::D7,
+    "Digit8" => Key::D8,
+    "Digit9" => Key::D9,
+    "Semicolon" => Key::Semicolon,
+    "Equal" => Key::Equals,
+    "BracketLeft" => Key::LeftBracket,
+    "Backslash" => Key::Backslash,
+    "BracketRight" => Key::RightBracket,
+    "Backquote" => Key::Backquote,
+    "KeyA" => Key::A,
+    "KeyB" => Key::B,
+    "KeyC" => Key::C,
+    "KeyD" => Key::D,
+    "KeyE" => Key::E,
+    "KeyF" => Key::F,
+    "KeyG" => Key::G,
+    "KeyH" => Key::H,
+    "KeyI" => Key::I,
+    "KeyJ" => Key::J,
+    "KeyK" => Key::K,
+    "KeyL" => Key::L,
+    "KeyM" => Key::M,
+    "KeyN" => Key::N,
+    "KeyO" => Key::O,
+    "KeyP" => Key::P,
+    "KeyQ" => Key::Q,
+    "KeyR" => Key::R,
+    "KeyS" => Key::S,
+    "KeyT" => Key::T,
+    "KeyU" => Key::U,
+    "KeyV" => Key::V,
+    "KeyW" => Key::W,
+    "KeyX" => Key::X,
+    "KeyY" => Key::Y,
+    "KeyZ" => Key::Z,
+    "Delete" => Key::Delete,
+    "CapsLock" => Key::CapsLock,
+    "F1" => Key::F1,
+    "F2" => Key::F2,
+    "F3" => Key::F3,
+    "F4" => Key::F4,
+    "F5" => Key::F5,
+    "F6" => Key::F6,
+    "F7" => Key::F7,
+    "F8" => Key::F8,
+    "F9" => Key::F9,
+    "F10" => Key::F10,
+    "F11" => Key::F11,
+    "F12" => Key::F12,
+    "Insert" => Key::Insert,
+    "Home" => Key::Home,
+    "PageUp" => Key::PageUp,
+    "End" => Key::End,
+    "PageDown" => Key::PageDown,
+    "ArrowRight" => Key::Right,
+    "ArrowLeft" => Key::Left,
+    "ArrowDown" => Key::Down,
+    "ArrowUp" => Key::Up,
+    "ControlLeft" | "MetaLeft" => Key::LCtrl,
+    "ControlRight" | "MetaRight" => Key::RCtrl,
+    "ShiftLeft" => Key::LShift,
+    "ShiftRight" => Key::RShift,
+    "AltLeft" => Key::LAlt,
+    "AltRight" => Key::RAlt,
+    _ => Key::Unknown,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_dom_code_to_key() {
+    assert_eq!(dom_code_to_key("KeyZ"), Key::Z);
+    assert_eq!(dom_code_to_key("Digit3"), Key::D3);
+    assert_eq!(dom_code_to_key("MetaLeft"), dom_code_to_key("ControlLeft"));
+    assert_eq!(dom_code_to_key("IntlBackslash"), Key::Unknown);
+  }
+}
@@ -0,0 +1,130 @@
+//! How long the virtual shape solver takes on a sketch of about 10k entities: solving all of it,
+//! and following a single free point being dragged. With the `parallel` feature, the whole sketch
+//! is also solved on a single thread to compare
+
+#[macro_use]
+extern crate core_lib;
+
+use core_lib::{
+  components::symbolics::*,
+  events::*,
+  math::*,
+  resources::{DefaultLineStyle, DefaultPointStyle, DependencyGraph},
+  systems::solvers::VirtualShapeSolver,
+  utilities::Geometry,
+};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use specs::prelude::*;
+
+static ROWS: usize = 50; // Independent of each other
+static ROW_LENGTH: usize = 50; // Free points, each one but the last adds three more entities
+
+/// Rows of free points, with the midpoint of each two neighbours, a line through the first of
+/// them and that midpoint, and the midpoint rotated around the first one. Nothing is solved yet
+fn build_sketch(mut solver: VirtualShapeSolver) -> (World, VirtualShapeSolver, Vec<Entity>) {
+  let mut world = World::new();
+  System::setup(&mut solver, &mut world);
+
+  let point_style = DefaultPointStyle::default().get();
+  let line_style = DefaultLineStyle::default().get();
+  let free_points = (0..ROWS * ROW_LENGTH)
+    .map(|i| {
+      let position = vec2![(i % ROW_LENGTH) as f64, (i / ROW_LENGTH) as f64];
+      insert(
+        &mut world,
+        Geometry::Point(SymbolicPoint::Free(position.into()), point_style),
+        &[],
+      )
+    })
+    .collect::<Vec<_>>();
+  for pair in free_points.chunks(ROW_LENGTH).flat_map(|row| row.windows(2)) {
+    let (from, to) = (pair[0], pair[1]);
+    let mid = insert(
+      &mut world,
+      Geometry::Point(SymbolicPoint::MidPoint(from, to), point_style),
+      &[from, to],
+    );
+    insert(
+      &mut world,
+      Geometry::Line(SymbolicLine::Straight(from, mid), line_style),
+      &[from, mid],
+    );
+    insert(
+      &mut world,
+      Geometry::Point(SymbolicPoint::Rotate(mid, from, 0.5), point_style),
+      &[mid, from],
+    );
+  }
+  (world, solver, free_points)
+}
+
+/// Inserts the geometry the way the insert handlers do, the solver is told through its event
+fn insert(world: &mut World, geometry: Geometry, parents: &[Entity]) -> Entity {
+  let ent = match &geometry {
+    Geometry::Point(sym_point, _) => world.create_entity().with(*sym_point).build(),
+    Geometry::Line(sym_line, _) => world.create_entity().with(*sym_line).build(),
+    _ => unreachable!("The sketch has only points and lines"),
+  };
+  for parent in parents {
+    world.fetch_mut::<DependencyGraph>().add(parent, &ent);
+  }
+  world
+    .fetch_mut::<GeometryEventChannel>()
+    .single_write(GeometryEvent::inserted(ent, geometry));
+  ent
+}
+
+fn solve_whole_sketch(c: &mut Criterion) {
+  c.bench_function("solve 10k entities", |b| {
+    b.iter_batched(
+      || build_sketch(VirtualShapeSolver::default()),
+      |(world, mut solver, _)| solver.run_now(&world),
+      BatchSize::LargeInput,
+    )
+  });
+}
+
+#[cfg(feature = "parallel")]
+fn solve_whole_sketch_serially(c: &mut Criterion) {
+  c.bench_function("solve 10k entities on one thread", |b| {
+    b.iter_batched(
+      || build_sketch(VirtualShapeSolver::serial()),
+      |(world, mut solver, _)| solver.run_now(&world),
+      BatchSize::LargeInput,
+    )
+  });
+}
+
+fn move_leaf_point(c: &mut Criterion) {
+  let (world, mut solver, free_points) = build_sketch(VirtualShapeSolver::default());
+  solver.run_now(&world);
+
+  // A point in the middle of a row, eight entities follow it
+  let leaf = free_points[ROW_LENGTH / 2];
+  let mut y = 0.0;
+  c.bench_function("move a free point among 10k entities", |b| {
+    b.iter(|| {
+      y += 0.01;
+      let new_sym_point = SymbolicPoint::Free(vec2![(ROW_LENGTH / 2) as f64, y].into());
+      let mut sym_points = world.write_storage::<SymbolicPoint>();
+      let old_sym_point = *sym_points.get(leaf).unwrap();
+      sym_points.insert(leaf, new_sym_point).unwrap();
+      drop(sym_points);
+      world
+        .fetch_mut::<GeometryEventChannel>()
+        .single_write(GeometryEvent::point_updated(leaf, old_sym_point, new_sym_point));
+      solver.run_now(&world);
+    })
+  });
+}
+
+#[cfg(feature = "parallel")]
+criterion_group!(
+  benches,
+  solve_whole_sketch,
+  solve_whole_sketch_serially,
+  move_leaf_point
+);
+#[cfg(not(feature = "parallel"))]
+criterion_group!(benches, solve_whole_sketch, move_leaf_point);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+use crate::utilities::VirtualPosition;
+use specs::prelude::*;
+
+/// Share of the width of its style a stroke keeps where the pen barely touches, so that the
+/// lightest strokes still show
+pub static MIN_PRESSURE_WIDTH: f64 = 0.2;
+
+/// Ink drawn by hand with a pen or the mouse. It depends on nothing and is kept as it was drawn,
+/// the positions the pen went through along with how hard it was pressed there
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreehandStroke {
+  pub samples: Vec<(VirtualPosition, f64)>, // Position, pressure from 0 to 1
+}
+
+impl FreehandStroke {
+  /// The width of the stroke under a pressure, the full width of its style being for the
+  /// hardest press
+  pub fn width_at(pressure: f64, width: f64) -> f64 {
+    width * (MIN_PRESSURE_WIDTH + (1. - MIN_PRESSURE_WIDTH) * pressure.clamp(0., 1.))
+  }
+}
+
+impl Component for FreehandStroke {
+  type Storage = VecStorage<Self>;
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_width_follows_the_pressure() {
+    assert_eq!(FreehandStroke::width_at(1., 5.), 5.);
+    assert_eq!(FreehandStroke::width_at(0., 5.), 1.);
+    assert!((FreehandStroke::width_at(0.5, 5.) - 3.).abs() < 1e-12);
+    assert_eq!(FreehandStroke::width_at(2., 5.), 5.);
+  }
+}
@@ -0,0 +1,5 @@
+mod freehand_stroke;
+mod screen_freehand_stroke;
+
+pub use freehand_stroke::*;
+pub use screen_freehand_stroke::*;
@@ -0,0 +1,12 @@
+use crate::utilities::ScreenPosition;
+use specs::prelude::*;
+
+/// A freehand stroke as it is drawn on the screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenFreehandStroke {
+  pub samples: Vec<(ScreenPosition, f64)>, // Position, pressure from 0 to 1
+}
+
+impl Component for ScreenFreehandStroke {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
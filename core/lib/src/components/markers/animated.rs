@@ -0,0 +1,77 @@
+use specs::prelude::*;
+
+/// A point on a line or on a circle moving along it by itself. The speed is the fraction of the
+/// path covered every second, the segment between the two points of a line or a full turn of a
+/// circle
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Animated {
+  pub speed: f64,
+  pub mode: AnimationMode,
+  pub playing: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnimationMode {
+  Loop,     // Jump back to the start once at the end
+  PingPong, // Turn around at both ends
+}
+
+impl Default for Animated {
+  fn default() -> Self {
+    Self {
+      speed: 0.25,
+      mode: AnimationMode::Loop,
+      playing: false,
+    }
+  }
+}
+
+impl Animated {
+  /// Move the parameter `u`, going from 0 to 1 along the path, by `dt` seconds. Turning around
+  /// flips the sign of the speed
+  pub fn advance(&mut self, u: f64, dt: f64) -> f64 {
+    let u = u + self.speed * dt;
+    match self.mode {
+      AnimationMode::Loop => u.rem_euclid(1.0),
+      AnimationMode::PingPong => {
+        if u > 1.0 {
+          self.speed = -self.speed;
+          2.0 - u
+        } else if u < 0.0 {
+          self.speed = -self.speed;
+          -u
+        } else {
+          u
+        }
+      }
+    }
+  }
+}
+
+impl Component for Animated {
+  type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_advance_loop_and_ping_pong() {
+    let mut animated = Animated {
+      speed: 0.5,
+      mode: AnimationMode::Loop,
+      playing: true,
+    };
+    assert_eq!(animated.advance(0.25, 1.0), 0.75);
+    assert_eq!(animated.advance(0.75, 1.0), 0.25);
+    assert_eq!(animated.speed, 0.5);
+
+    animated.mode = AnimationMode::PingPong;
+    assert_eq!(animated.advance(0.75, 1.0), 0.75);
+    assert_eq!(animated.speed, -0.5);
+    assert_eq!(animated.advance(0.75, 1.0), 0.25);
+    assert_eq!(animated.advance(0.25, 1.0), 0.25);
+    assert_eq!(animated.speed, 0.5);
+  }
+}
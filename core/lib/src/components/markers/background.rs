@@ -0,0 +1,9 @@
+use specs::prelude::*;
+
+/// The shape is drawn under all the geometry, as the grid is
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Background;
+
+impl Component for Background {
+  type Storage = NullStorage<Self>;
+}
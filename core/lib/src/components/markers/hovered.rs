@@ -0,0 +1,10 @@
+use specs::prelude::*;
+
+/// Marks the curves under the cursor that snapping could use, renderers draw a halo around them.
+/// Set again every frame
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Hovered;
+
+impl Component for Hovered {
+  type Storage = NullStorage<Self>;
+}
@@ -0,0 +1,25 @@
+use crate::math::Vector2;
+use specs::prelude::*;
+
+static LABEL_OFFSET: Vector2 = Vector2 { x: 8.0, y: -8.0 }; // Pixel, to the top right of the point
+
+/// The name of a point shown next to it. The offset goes from the point on screen to the start of
+/// the baseline of the text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+  pub text: String,
+  pub offset: Vector2,
+}
+
+impl Label {
+  pub fn new(text: String) -> Self {
+    Self {
+      text,
+      offset: LABEL_OFFSET,
+    }
+  }
+}
+
+impl Component for Label {
+  type Storage = DenseVecStorage<Self>;
+}
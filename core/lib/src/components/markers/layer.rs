@@ -0,0 +1,10 @@
+use specs::prelude::*;
+
+/// The layer the entity is on, see `LayerManager`. Entities without a layer are on the default
+/// layer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Layer(pub u32);
+
+impl Component for Layer {
+  type Storage = DenseVecStorage<Self>;
+}
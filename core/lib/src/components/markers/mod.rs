@@ -1,7 +1,23 @@
+mod animated;
+mod background;
 mod element;
 mod hidden;
+mod hovered;
+mod label;
+mod layer;
 mod selected;
+mod show_coordinates;
+mod traced;
+mod unsolvable;
 
+pub use animated::*;
+pub use background::*;
 pub use element::*;
 pub use hidden::*;
+pub use hovered::*;
+pub use label::*;
+pub use layer::*;
 pub use selected::*;
+pub use show_coordinates::*;
+pub use traced::*;
+pub use unsolvable::*;
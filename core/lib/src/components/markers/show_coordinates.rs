@@ -0,0 +1,8 @@
+use specs::prelude::*;
+
+#[derive(Default, Debug, Copy, Clone)]
+pub struct ShowCoordinates;
+
+impl Component for ShowCoordinates {
+  type Storage = NullStorage<Self>;
+}
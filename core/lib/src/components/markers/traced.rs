@@ -0,0 +1,9 @@
+use specs::prelude::*;
+
+/// The point leaves a trace of its positions while another point is dragged
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Traced;
+
+impl Component for Traced {
+  type Storage = NullStorage<Self>;
+}
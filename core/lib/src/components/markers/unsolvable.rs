@@ -0,0 +1,17 @@
+use specs::prelude::*;
+
+/// The geometry could not be solved, it has no virtual shape until its parents move so that it
+/// exists again. Its last screen shape is kept for the frontends to gray out. Inserted and removed
+/// by `VirtualShapeSolver`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Unsolvable(pub UnsolvableReason);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnsolvableReason {
+  Degenerate,              // Its parents are placed so that it does not exist, e.g. parallel lines intersecting
+  UndefinedParent(Entity), // It is defined by a geometry that could not be solved either
+}
+
+impl Component for Unsolvable {
+  type Storage = DenseVecStorage<Self>;
+}
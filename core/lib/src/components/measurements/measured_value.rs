@@ -0,0 +1,10 @@
+use specs::prelude::*;
+
+/// The current value of a measurement, in virtual units or radians. Missing when the measured
+/// geometries are undefined
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MeasuredValue(pub f64);
+
+impl Component for MeasuredValue {
+  type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,47 @@
+use specs::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Measurement {
+  DistancePP(Entity, Entity),    // (Point entity, Point entity)
+  Angle(Entity, Entity, Entity), // (Point entity, Vertex point entity, Point entity)
+  Radius(Entity),                // (Circle entity)
+  SegmentLength(Entity),         // (Segment entity)
+  PolygonArea(Entity),           // (Polygon entity)
+}
+
+impl Measurement {
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Measurement::DistancePP(_, _) => "DistancePP",
+      Measurement::Angle(_, _, _) => "Angle",
+      Measurement::Radius(_) => "Radius",
+      Measurement::SegmentLength(_) => "SegmentLength",
+      Measurement::PolygonArea(_) => "PolygonArea",
+    }
+  }
+
+  /// The geometries being measured
+  pub fn targets(&self) -> Vec<Entity> {
+    match *self {
+      Measurement::DistancePP(p1, p2) => vec![p1, p2],
+      Measurement::Angle(p1, vertex, p2) => vec![p1, vertex, p2],
+      Measurement::Radius(c) => vec![c],
+      Measurement::SegmentLength(l) => vec![l],
+      Measurement::PolygonArea(p) => vec![p],
+    }
+  }
+
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match *self {
+      Measurement::DistancePP(p1, p2) => Measurement::DistancePP(f(p1), f(p2)),
+      Measurement::Angle(p1, vertex, p2) => Measurement::Angle(f(p1), f(vertex), f(p2)),
+      Measurement::Radius(c) => Measurement::Radius(f(c)),
+      Measurement::SegmentLength(l) => Measurement::SegmentLength(f(l)),
+      Measurement::PolygonArea(p) => Measurement::PolygonArea(f(p)),
+    }
+  }
+}
+
+impl Component for Measurement {
+  type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,5 @@
+mod measured_value;
+mod measurement;
+
+pub use measured_value::*;
+pub use measurement::*;
@@ -1,5 +1,9 @@
+pub mod freehand;
 pub mod markers;
+pub mod measurements;
 pub mod screen_shapes;
+pub mod sliders;
 pub mod styles;
 pub mod symbolics;
+pub mod underlays;
 pub mod virtual_shapes;
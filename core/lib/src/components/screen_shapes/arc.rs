@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::ScreenArc;
+
+impl Component for ScreenArc {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::ScreenConic;
+
+impl Component for ScreenConic {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
@@ -0,0 +1,12 @@
+use crate::utilities::*;
+use specs::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenLabel {
+  pub position: ScreenPosition, // Top left of the text
+  pub text: String,
+}
+
+impl Component for ScreenLabel {
+  type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
@@ -1,9 +1,21 @@
+mod arc;
 mod circle;
+mod conic;
+mod label;
 mod line;
 mod point;
+mod polygon;
 mod rectangle;
+mod text;
+mod vector;
 
+pub use arc::*;
 pub use circle::*;
+pub use conic::*;
+pub use label::*;
 pub use line::*;
 pub use point::*;
+pub use polygon::*;
 pub use rectangle::*;
+pub use text::*;
+pub use vector::*;
@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::ScreenText;
+
+impl Component for ScreenText {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
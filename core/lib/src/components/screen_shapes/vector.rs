@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::ScreenVector;
+
+impl Component for ScreenVector {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
@@ -0,0 +1,5 @@
+mod slider;
+mod slider_value;
+
+pub use slider::*;
+pub use slider_value::*;
@@ -0,0 +1,57 @@
+use crate::utilities::{ScreenScalar, VirtualPosition};
+use specs::prelude::*;
+
+/// Length of the track of every slider, whatever the zoom
+pub static SLIDER_TRACK_LENGTH: ScreenScalar = ScreenScalar(160.0); // Pixel
+
+/// A control placed on the canvas whose value is dragged between `min` and `max`. Named, it can be
+/// used in the scalar expressions like a scalar, e.g. `angle = t * pi`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Slider {
+  pub position: VirtualPosition, // Left end of the track
+  pub min: f64,
+  pub max: f64,
+}
+
+impl Slider {
+  pub fn clamp(&self, value: f64) -> f64 {
+    value.max(self.min).min(self.max)
+  }
+
+  /// Where the value is along the track, from 0 at the left end to 1 at the right end
+  pub fn ratio_of(&self, value: f64) -> f64 {
+    if self.max > self.min {
+      (self.clamp(value) - self.min) / (self.max - self.min)
+    } else {
+      0.
+    }
+  }
+
+  /// The value at a ratio along the track, the ratios out of the track give the closest end
+  pub fn value_at(&self, ratio: f64) -> f64 {
+    self.clamp(self.min + (self.max - self.min) * ratio)
+  }
+}
+
+impl Component for Slider {
+  type Storage = VecStorage<Self>;
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::math::*;
+
+  #[test]
+  fn test_value_along_the_track() {
+    let slider = Slider {
+      position: vec2![0., 0.].into(),
+      min: -1.,
+      max: 3.,
+    };
+    assert_eq!(slider.ratio_of(0.), 0.25);
+    assert_eq!(slider.ratio_of(5.), 1.);
+    assert_eq!(slider.value_at(0.5), 1.);
+    assert_eq!(slider.value_at(-0.2), -1.);
+  }
+}
@@ -0,0 +1,9 @@
+use specs::prelude::*;
+
+/// The current value of a slider, always between its minimum and its maximum
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SliderValue(pub f64);
+
+impl Component for SliderValue {
+  type Storage = VecStorage<Self>;
+}
@@ -2,10 +2,11 @@ use super::LineStyle;
 use crate::math::*;
 use specs::prelude::*;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct CircleStyle {
   pub fill: Color,
   pub border: LineStyle,
+  pub alpha: f64, // Opacity of the whole circle, from 0 to 1
 }
 
 impl Component for CircleStyle {
@@ -17,6 +18,45 @@ impl CircleStyle {
     Self {
       fill: self.fill.apply_alpha(a),
       border: self.border.apply_alpha(a),
+      alpha: self.alpha,
     }
   }
+
+  /// The same style with its opacity, and the one of its border, multiplied into the alpha of
+  /// its colors
+  pub fn flatten_alpha(self) -> Self {
+    let style = self.apply_alpha(self.alpha as f32);
+    Self {
+      fill: style.fill,
+      border: style.border.flatten_alpha(),
+      alpha: 1.0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::components::styles::{DashPattern, EqualityMarks};
+
+  #[test]
+  fn test_circle_style_flatten_alpha() {
+    let style = CircleStyle {
+      fill: rgba!(0.0, 0.6, 0.0, 0.5),
+      border: LineStyle {
+        color: Color::black(),
+        width: 2.0,
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: 0.5,
+      },
+      alpha: 0.4,
+    };
+    let flat = style.flatten_alpha();
+    assert_eq!(flat.alpha, 1.0);
+    assert_eq!(flat.border.alpha, 1.0);
+    assert!((flat.fill.a - 0.2).abs() < 1e-6);
+    assert!((flat.border.color.a - 0.2).abs() < 1e-6);
+    assert_eq!(flat.border.width, 2.0);
+  }
 }
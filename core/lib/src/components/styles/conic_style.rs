@@ -0,0 +1,30 @@
+use crate::math::*;
+use specs::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ConicStyle {
+  pub color: Color,
+  pub width: f64,
+  pub alpha: f64, // Opacity of the whole conic, from 0 to 1
+}
+
+impl Component for ConicStyle {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
+impl ConicStyle {
+  pub fn apply_alpha(self, a: f32) -> Self {
+    Self {
+      color: self.color.apply_alpha(a),
+      ..self
+    }
+  }
+
+  /// The same style with its opacity multiplied into the alpha of its color
+  pub fn flatten_alpha(self) -> Self {
+    Self {
+      alpha: 1.0,
+      ..self.apply_alpha(self.alpha as f32)
+    }
+  }
+}
@@ -1,10 +1,69 @@
 use crate::math::*;
 use specs::prelude::*;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct LineStyle {
   pub color: Color,
   pub width: f64,
+  pub marks: EqualityMarks,
+  pub dash: DashPattern,
+  pub alpha: f64, // Opacity of the whole line, from 0 to 1
+}
+
+/// Number of tick marks drawn across a segment to mark it equal to the other segments having
+/// the same number of marks. Lines other than segments ignore it
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct EqualityMarks(pub u8);
+
+/// How a line is stroked. The borders of circles and polygons are always drawn solid
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DashPattern {
+  Solid,
+  Dashed,
+  Dotted,
+  DashDot,
+}
+
+impl Default for DashPattern {
+  fn default() -> Self {
+    DashPattern::Solid
+  }
+}
+
+impl DashPattern {
+  /// Lengths of the drawn and skipped parts of the pattern in turn, in pixels. Empty when solid
+  pub fn lengths(self) -> &'static [f64] {
+    match self {
+      DashPattern::Solid => &[],
+      DashPattern::Dashed => &[8.0, 6.0],
+      DashPattern::Dotted => &[2.0, 4.0],
+      DashPattern::DashDot => &[8.0, 4.0, 2.0, 4.0],
+    }
+  }
+
+  /// The drawn parts of the segment from `from` to `to`, in screen space. The pattern starts over
+  /// at `from`
+  pub fn dashes(self, from: Vector2, to: Vector2) -> Vec<(Vector2, Vector2)> {
+    let lengths = self.lengths();
+    let magnitude = (to - from).magnitude();
+    if lengths.is_empty() || magnitude == 0. {
+      return vec![(from, to)];
+    }
+    let unit = (to - from) / magnitude;
+    let mut dashes = vec![];
+    let mut t = 0.;
+    for (i, length) in lengths.iter().cycle().enumerate() {
+      if t >= magnitude {
+        break;
+      }
+      let end = (t + length).min(magnitude);
+      if i % 2 == 0 {
+        dashes.push((from + unit * t, from + unit * end));
+      }
+      t = end;
+    }
+    dashes
+  }
 }
 
 impl Component for LineStyle {
@@ -16,6 +75,35 @@ impl LineStyle {
     Self {
       color: self.color.apply_alpha(a),
       width: self.width,
+      marks: self.marks,
+      dash: self.dash,
+      alpha: self.alpha,
+    }
+  }
+
+  /// The same style with its opacity multiplied into the alpha of its color
+  pub fn flatten_alpha(self) -> Self {
+    Self {
+      alpha: 1.0,
+      ..self.apply_alpha(self.alpha as f32)
     }
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_dashes_along_a_segment() {
+    let (from, to) = (vec2![0., 0.], vec2![20., 0.]);
+    assert_eq!(DashPattern::Solid.dashes(from, to), vec![(from, to)]);
+
+    // 8 drawn, 6 skipped, then the last 6 pixels are drawn
+    assert_eq!(
+      DashPattern::Dashed.dashes(from, to),
+      vec![(vec2![0., 0.], vec2![8., 0.]), (vec2![14., 0.], vec2![20., 0.])]
+    );
+    assert_eq!(DashPattern::DashDot.dashes(from, to).len(), 3);
+  }
+}
@@ -1,9 +1,19 @@
+mod arc_style;
 mod circle_style;
+mod conic_style;
 mod line_style;
 mod point_style;
+mod polygon_style;
 mod rectangle_style;
+mod text_style;
+mod vector_style;
 
+pub use arc_style::*;
 pub use circle_style::*;
+pub use conic_style::*;
 pub use line_style::*;
 pub use point_style::*;
+pub use polygon_style::*;
 pub use rectangle_style::*;
+pub use text_style::*;
+pub use vector_style::*;
@@ -1,12 +1,83 @@
-use crate::math::*;
+use crate::{components::symbolics::SymbolicPoint, math::*};
 use specs::prelude::*;
 
-#[derive(Debug, Copy, Clone)]
+/// How the inside of a point is drawn. `Auto` follows the kind of the point so that it's clear
+/// what can be dragged: free and fixed points are solid while constrained points are hollow
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PointFill {
+  Auto,
+  Solid,
+  Hollow,
+}
+
+/// The symbol drawn for a point. Circles, squares and diamonds are drawn with the fill of the
+/// point, a hollow circle is always hollow and crosses and pluses are only strokes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MarkerShape {
+  FilledCircle,
+  HollowCircle,
+  Square,
+  Cross,
+  Plus,
+  Diamond,
+}
+
+/// What a renderer has to draw for a marker, in screen space
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkerPrimitive {
+  Disc(Vector2, f64),    // Center, radius
+  Ring(Vector2, f64),    // Center, radius
+  Polygon(Vec<Vector2>), // Vertices in counter clockwise order
+  Segment(Vector2, Vector2),
+}
+
+impl MarkerShape {
+  /// The primitives of the marker centered at `center` and fitting in a circle of `radius`
+  pub fn primitives(self, center: Vector2, radius: f64) -> Vec<MarkerPrimitive> {
+    // The diagonal of a square in the circle is as long as its diameter
+    let half_side = radius / 2f64.sqrt();
+    match self {
+      MarkerShape::FilledCircle => vec![MarkerPrimitive::Disc(center, radius)],
+      MarkerShape::HollowCircle => vec![MarkerPrimitive::Ring(center, radius)],
+      MarkerShape::Square => vec![MarkerPrimitive::Polygon(vec![
+        center + vec2![-half_side, -half_side],
+        center + vec2![half_side, -half_side],
+        center + vec2![half_side, half_side],
+        center + vec2![-half_side, half_side],
+      ])],
+      MarkerShape::Diamond => vec![MarkerPrimitive::Polygon(vec![
+        center + vec2![0., -radius],
+        center + vec2![radius, 0.],
+        center + vec2![0., radius],
+        center + vec2![-radius, 0.],
+      ])],
+      MarkerShape::Cross => vec![
+        MarkerPrimitive::Segment(
+          center + vec2![-half_side, -half_side],
+          center + vec2![half_side, half_side],
+        ),
+        MarkerPrimitive::Segment(
+          center + vec2![-half_side, half_side],
+          center + vec2![half_side, -half_side],
+        ),
+      ],
+      MarkerShape::Plus => vec![
+        MarkerPrimitive::Segment(center + vec2![-radius, 0.], center + vec2![radius, 0.]),
+        MarkerPrimitive::Segment(center + vec2![0., -radius], center + vec2![0., radius]),
+      ],
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct PointStyle {
   pub color: Color,
   pub radius: f64,
   pub border_color: Color,
   pub border_width: f64,
+  pub alpha: f64, // Opacity of the whole point, from 0 to 1
+  pub fill: PointFill,
+  pub marker_shape: MarkerShape,
 }
 
 impl Component for PointStyle {
@@ -20,6 +91,28 @@ impl PointStyle {
       radius: self.radius,
       border_color: self.border_color.apply_alpha(a),
       border_width: self.border_width,
+      alpha: self.alpha,
+      fill: self.fill,
+      marker_shape: self.marker_shape,
+    }
+  }
+
+  /// The same style with an `Auto` fill decided by the kind of `sym_point`. An explicit fill is
+  /// kept as it is
+  pub fn resolve_fill(self, sym_point: &SymbolicPoint) -> Self {
+    let fill = match self.fill {
+      PointFill::Auto if sym_point.is_constrained() => PointFill::Hollow,
+      PointFill::Auto => PointFill::Solid,
+      fill => fill,
+    };
+    Self { fill, ..self }
+  }
+
+  /// The same style with its opacity multiplied into the alpha of its colors
+  pub fn flatten_alpha(self) -> Self {
+    Self {
+      alpha: 1.0,
+      ..self.apply_alpha(self.alpha as f32)
     }
   }
 
@@ -29,6 +122,62 @@ impl PointStyle {
       radius: self.radius + dr,
       border_color: self.border_color,
       border_width: self.border_width,
+      alpha: self.alpha,
+      fill: self.fill,
+      marker_shape: self.marker_shape,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_marker_primitives() {
+    let center = vec2![10., 20.];
+    assert_eq!(
+      MarkerShape::FilledCircle.primitives(center, 4.),
+      vec![MarkerPrimitive::Disc(center, 4.)]
+    );
+    assert_eq!(
+      MarkerShape::HollowCircle.primitives(center, 4.),
+      vec![MarkerPrimitive::Ring(center, 4.)]
+    );
+    assert_eq!(
+      MarkerShape::Diamond.primitives(center, 4.),
+      vec![MarkerPrimitive::Polygon(vec![
+        vec2![10., 16.],
+        vec2![14., 20.],
+        vec2![10., 24.],
+        vec2![6., 20.],
+      ])]
+    );
+    assert_eq!(
+      MarkerShape::Plus.primitives(center, 4.),
+      vec![
+        MarkerPrimitive::Segment(vec2![6., 20.], vec2![14., 20.]),
+        MarkerPrimitive::Segment(vec2![10., 16.], vec2![10., 24.]),
+      ]
+    );
+
+    // The corners of squares and crosses touch the circle of the point
+    let vertices = match MarkerShape::Square.primitives(center, 4.).as_slice() {
+      [MarkerPrimitive::Polygon(vertices)] => vertices.clone(),
+      primitives => panic!("Expected a single polygon, got {:?}", primitives),
+    };
+    assert_eq!(vertices.len(), 4);
+    for primitive in MarkerShape::Cross.primitives(center, 4.) {
+      match primitive {
+        MarkerPrimitive::Segment(from, to) => {
+          assert!(vertices.iter().any(|vertex| (*vertex - from).magnitude() < 1e-12));
+          assert!(vertices.iter().any(|vertex| (*vertex - to).magnitude() < 1e-12));
+        }
+        _ => panic!("Expected the segments of a cross"),
+      }
+    }
+    for vertex in vertices {
+      assert!(((vertex - center).magnitude() - 4.).abs() < 1e-12);
     }
   }
 }
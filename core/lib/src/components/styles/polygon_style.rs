@@ -0,0 +1,35 @@
+use super::LineStyle;
+use crate::math::*;
+use specs::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PolygonStyle {
+  pub fill: Color,
+  pub border: LineStyle,
+  pub alpha: f64, // Opacity of the whole polygon, from 0 to 1
+}
+
+impl Component for PolygonStyle {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
+impl PolygonStyle {
+  pub fn apply_alpha(self, a: f32) -> Self {
+    Self {
+      fill: self.fill.apply_alpha(a),
+      border: self.border.apply_alpha(a),
+      alpha: self.alpha,
+    }
+  }
+
+  /// The same style with its opacity, and the one of its border, multiplied into the alpha of
+  /// its colors
+  pub fn flatten_alpha(self) -> Self {
+    let style = self.apply_alpha(self.alpha as f32);
+    Self {
+      fill: style.fill,
+      border: style.border.flatten_alpha(),
+      alpha: 1.0,
+    }
+  }
+}
@@ -6,8 +6,30 @@ use specs::prelude::*;
 pub struct RectangleStyle {
   pub fill: Color,
   pub border: LineStyle,
+  pub alpha: f64, // Opacity of the whole rectangle, from 0 to 1
 }
 
 impl Component for RectangleStyle {
   type Storage = FlaggedStorage<Self, VecStorage<Self>>;
 }
+
+impl RectangleStyle {
+  pub fn apply_alpha(self, a: f32) -> Self {
+    Self {
+      fill: self.fill.apply_alpha(a),
+      border: self.border.apply_alpha(a),
+      alpha: self.alpha,
+    }
+  }
+
+  /// The same style with its opacity, and the one of its border, multiplied into the alpha of
+  /// its colors
+  pub fn flatten_alpha(self) -> Self {
+    let style = self.apply_alpha(self.alpha as f32);
+    Self {
+      fill: style.fill,
+      border: style.border.flatten_alpha(),
+      alpha: 1.0,
+    }
+  }
+}
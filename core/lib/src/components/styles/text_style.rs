@@ -0,0 +1,30 @@
+use crate::math::*;
+use specs::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextStyle {
+  pub color: Color,
+  pub font_size: f64, // In pixels
+  pub alpha: f64,     // Opacity of the text, from 0 to 1
+}
+
+impl Component for TextStyle {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
+impl TextStyle {
+  pub fn apply_alpha(self, a: f32) -> Self {
+    Self {
+      color: self.color.apply_alpha(a),
+      ..self
+    }
+  }
+
+  /// The same style with its opacity multiplied into the alpha of its color
+  pub fn flatten_alpha(self) -> Self {
+    Self {
+      alpha: 1.0,
+      ..self.apply_alpha(self.alpha as f32)
+    }
+  }
+}
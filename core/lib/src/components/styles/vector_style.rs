@@ -0,0 +1,31 @@
+use crate::math::*;
+use specs::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VectorStyle {
+  pub color: Color,
+  pub width: f64,
+  pub head_length: f64, // Length of the sides of the arrowhead, in pixels
+  pub alpha: f64,       // Opacity of the whole vector, from 0 to 1
+}
+
+impl Component for VectorStyle {
+  type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
+impl VectorStyle {
+  pub fn apply_alpha(self, a: f32) -> Self {
+    Self {
+      color: self.color.apply_alpha(a),
+      ..self
+    }
+  }
+
+  /// The same style with its opacity multiplied into the alpha of its color
+  pub fn flatten_alpha(self) -> Self {
+    Self {
+      alpha: 1.0,
+      ..self.apply_alpha(self.alpha as f32)
+    }
+  }
+}
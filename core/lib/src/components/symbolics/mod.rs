@@ -1,7 +1,17 @@
+mod symbolic_arc;
 mod symbolic_circle;
+mod symbolic_conic;
 mod symbolic_line;
 mod symbolic_point;
+mod symbolic_polygon;
+mod symbolic_text;
+mod symbolic_vector;
 
+pub use symbolic_arc::*;
 pub use symbolic_circle::*;
+pub use symbolic_conic::*;
 pub use symbolic_line::*;
 pub use symbolic_point::*;
+pub use symbolic_polygon::*;
+pub use symbolic_text::*;
+pub use symbolic_vector::*;
@@ -0,0 +1,36 @@
+use specs::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SymbolicArc {
+  ThreePoint(Entity, Entity, Entity),     // (Start point, Point on arc, End point)
+  CenterTwoPoint(Entity, Entity, Entity), // (Center point, Start point, End point), counter clockwise
+}
+
+impl SymbolicArc {
+  /// Name of the kind of the symbolic arc, e.g. `ThreePoint`
+  pub fn kind(&self) -> &'static str {
+    match self {
+      SymbolicArc::ThreePoint(_, _, _) => "ThreePoint",
+      SymbolicArc::CenterTwoPoint(_, _, _) => "CenterTwoPoint",
+    }
+  }
+
+  /// The three point entities the arc is defined by
+  pub fn points(&self) -> [Entity; 3] {
+    match *self {
+      SymbolicArc::ThreePoint(p1, p2, p3) | SymbolicArc::CenterTwoPoint(p1, p2, p3) => [p1, p2, p3],
+    }
+  }
+
+  /// The same symbolic arc with all the point entities mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match *self {
+      SymbolicArc::ThreePoint(p1, p2, p3) => SymbolicArc::ThreePoint(f(p1), f(p2), f(p3)),
+      SymbolicArc::CenterTwoPoint(center, from, to) => SymbolicArc::CenterTwoPoint(f(center), f(from), f(to)),
+    }
+  }
+}
+
+impl Component for SymbolicArc {
+  type Storage = VecStorage<Self>;
+}
@@ -1,8 +1,40 @@
+use crate::utilities::ScalarId;
 use specs::prelude::*;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum SymbolicCircle {
-  CenterRadius(Entity, Entity),
+  CenterRadius(Entity, Entity),          // (Center point, Point on circle)
+  EqualRadius(Entity, Entity),           // (Center point, Circle whose radius is kept equal)
+  CompassRadius(Entity, Entity, Entity), // (Center point, Segment end point, Segment end point)
+  ScalarRadius(Entity, ScalarId),        // (Center point, Scalar of the radius)
+  ThreePoints(Entity, Entity, Entity),   // (Point on circle, Point on circle, Point on circle)
+  Reflect(Entity, Entity),               // (Circle, Mirror line)
+}
+
+impl SymbolicCircle {
+  /// Name of the kind of the symbolic circle, e.g. `CenterRadius`
+  pub fn kind(&self) -> &'static str {
+    match self {
+      SymbolicCircle::CenterRadius(_, _) => "CenterRadius",
+      SymbolicCircle::EqualRadius(_, _) => "EqualRadius",
+      SymbolicCircle::CompassRadius(_, _, _) => "CompassRadius",
+      SymbolicCircle::ScalarRadius(_, _) => "ScalarRadius",
+      SymbolicCircle::ThreePoints(_, _, _) => "ThreePoints",
+      SymbolicCircle::Reflect(_, _) => "Reflect",
+    }
+  }
+
+  /// The same symbolic circle with all the dependency entities mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match *self {
+      SymbolicCircle::CenterRadius(p1, p2) => SymbolicCircle::CenterRadius(f(p1), f(p2)),
+      SymbolicCircle::EqualRadius(p, c) => SymbolicCircle::EqualRadius(f(p), f(c)),
+      SymbolicCircle::CompassRadius(p, p1, p2) => SymbolicCircle::CompassRadius(f(p), f(p1), f(p2)),
+      SymbolicCircle::ScalarRadius(p, scalar) => SymbolicCircle::ScalarRadius(f(p), scalar),
+      SymbolicCircle::ThreePoints(p1, p2, p3) => SymbolicCircle::ThreePoints(f(p1), f(p2), f(p3)),
+      SymbolicCircle::Reflect(c, mirror) => SymbolicCircle::Reflect(f(c), f(mirror)),
+    }
+  }
 }
 
 impl Component for SymbolicCircle {
@@ -0,0 +1,37 @@
+use specs::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SymbolicConic {
+  Ellipse(Entity, Entity, Entity), // (Focus point, Focus point, Point on ellipse)
+  FivePoints(Entity, Entity, Entity, Entity, Entity), // Points on the conic, only ellipses are solved
+}
+
+impl SymbolicConic {
+  /// Name of the kind of the symbolic conic, e.g. `FivePoints`
+  pub fn kind(&self) -> &'static str {
+    match self {
+      SymbolicConic::Ellipse(_, _, _) => "Ellipse",
+      SymbolicConic::FivePoints(_, _, _, _, _) => "FivePoints",
+    }
+  }
+
+  /// The point entities the conic is defined by
+  pub fn points(&self) -> Vec<Entity> {
+    match *self {
+      SymbolicConic::Ellipse(f1, f2, p) => vec![f1, f2, p],
+      SymbolicConic::FivePoints(p1, p2, p3, p4, p5) => vec![p1, p2, p3, p4, p5],
+    }
+  }
+
+  /// The same symbolic conic with all the point entities mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match *self {
+      SymbolicConic::Ellipse(f1, f2, p) => SymbolicConic::Ellipse(f(f1), f(f2), f(p)),
+      SymbolicConic::FivePoints(p1, p2, p3, p4, p5) => SymbolicConic::FivePoints(f(p1), f(p2), f(p3), f(p4), f(p5)),
+    }
+  }
+}
+
+impl Component for SymbolicConic {
+  type Storage = VecStorage<Self>;
+}
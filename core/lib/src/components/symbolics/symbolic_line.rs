@@ -1,12 +1,63 @@
+use crate::math::{LineType, TangentKind};
 use specs::prelude::*;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum SymbolicLine {
-  Straight(Entity, Entity),      // (Point Entity, Point Entity)
-  Ray(Entity, Entity),           // (Point Entity, Point Entity)
-  Segment(Entity, Entity),       // (Point Entity, Point Entity)
-  Parallel(Entity, Entity),      // (Line Entity, Point Entity)
-  Perpendicular(Entity, Entity), // (Line Entity, Point Entity)
+  Straight(Entity, Entity),                   // (Point Entity, Point Entity)
+  Ray(Entity, Entity),                        // (Point Entity, Point Entity)
+  Segment(Entity, Entity),                    // (Point Entity, Point Entity)
+  Parallel(Entity, Entity),                   // (Line Entity, Point Entity)
+  Perpendicular(Entity, Entity),              // (Line Entity, Point Entity)
+  CommonTangent(Entity, Entity, TangentKind), // (Circle Entity, Circle Entity, Which tangent)
+  Tangent(Entity, Entity, u8),                // (Circle Entity, Point Entity, Which of the two tangents)
+  PerpendicularBisector(Entity, Entity),      // (Point Entity, Point Entity)
+  Reflect(Entity, Entity),                    // (Line Entity, Mirror line Entity)
+}
+
+impl SymbolicLine {
+  /// Name of the kind of the symbolic line, e.g. `Segment`
+  pub fn kind(&self) -> &'static str {
+    match self {
+      SymbolicLine::Straight(_, _) => "Straight",
+      SymbolicLine::Ray(_, _) => "Ray",
+      SymbolicLine::Segment(_, _) => "Segment",
+      SymbolicLine::Parallel(_, _) => "Parallel",
+      SymbolicLine::Perpendicular(_, _) => "Perpendicular",
+      SymbolicLine::CommonTangent(_, _, _) => "CommonTangent",
+      SymbolicLine::Tangent(_, _, _) => "Tangent",
+      SymbolicLine::PerpendicularBisector(_, _) => "PerpendicularBisector",
+      SymbolicLine::Reflect(_, _) => "Reflect",
+    }
+  }
+
+  /// The same line through the same two points but of another type. Lines that are not defined
+  /// by two points have no type to change
+  pub fn with_line_type(&self, line_type: LineType) -> Option<Self> {
+    let (p1, p2) = match *self {
+      SymbolicLine::Straight(p1, p2) | SymbolicLine::Ray(p1, p2) | SymbolicLine::Segment(p1, p2) => (p1, p2),
+      _ => return None,
+    };
+    Some(match line_type {
+      LineType::Straight => SymbolicLine::Straight(p1, p2),
+      LineType::Ray => SymbolicLine::Ray(p1, p2),
+      LineType::Segment => SymbolicLine::Segment(p1, p2),
+    })
+  }
+
+  /// The same symbolic line with all the dependency entities mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match *self {
+      SymbolicLine::Straight(p1, p2) => SymbolicLine::Straight(f(p1), f(p2)),
+      SymbolicLine::Ray(p1, p2) => SymbolicLine::Ray(f(p1), f(p2)),
+      SymbolicLine::Segment(p1, p2) => SymbolicLine::Segment(f(p1), f(p2)),
+      SymbolicLine::Parallel(l, p) => SymbolicLine::Parallel(f(l), f(p)),
+      SymbolicLine::Perpendicular(l, p) => SymbolicLine::Perpendicular(f(l), f(p)),
+      SymbolicLine::CommonTangent(c1, c2, kind) => SymbolicLine::CommonTangent(f(c1), f(c2), kind),
+      SymbolicLine::Tangent(c, p, index) => SymbolicLine::Tangent(f(c), f(p), index),
+      SymbolicLine::PerpendicularBisector(p1, p2) => SymbolicLine::PerpendicularBisector(f(p1), f(p2)),
+      SymbolicLine::Reflect(l, mirror) => SymbolicLine::Reflect(f(l), f(mirror)),
+    }
+  }
 }
 
 impl Component for SymbolicLine {
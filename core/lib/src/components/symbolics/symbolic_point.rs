@@ -1,24 +1,87 @@
 use crate::utilities::*;
 use specs::prelude::*;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum SymbolicPoint {
   Fixed(VirtualPosition),
   Free(VirtualPosition),
   MidPoint(Entity, Entity),                                 // (Point entity, Point entity)
-  OnLine(Entity, VirtualScalar),                            // (Line entity, frac{p_to_from}{to_to_from})
-  LineLineIntersect(Entity, Entity),                        // (Line entity, Line entity)
-  OnCircle(Entity, f64),                                    // (Circle entity, theta)
-  CircleLineIntersect(Entity, Entity, CircleIntersectId),   // (Circle entity, Line entity, Id)
+  Ratio(Entity, Entity, f64), // (Point entity, Point entity, Fraction of the way from the first to the second)
+  OnLine(Entity, VirtualScalar), // (Line entity, frac{p_to_from}{to_to_from})
+  LineLineIntersect(Entity, Entity), // (Line entity, Line entity)
+  OnCircle(Entity, f64),      // (Circle entity, theta)
+  OnConic(Entity, f64),       // (Conic entity, parameter of the ellipse)
+  CircleLineIntersect(Entity, Entity, CircleIntersectId), // (Circle entity, Line entity, Id)
   CircleCircleIntersect(Entity, Entity, CircleIntersectId), // (Circle entity, Circle entity, Id)
+  ArcLineIntersect(Entity, Entity, CircleIntersectId), // (Arc entity, Line entity, Id)
+  ArcCircleIntersect(Entity, Entity, CircleIntersectId), // (Arc entity, Circle entity, Id)
+  PointReflection(Entity, Entity), // (Source point entity, Center point entity)
+  Reflect(Entity, Entity),    // (Point entity, Mirror line entity)
+  Rotate(Entity, Entity, f64), // (Point entity, Center point entity, Counter clockwise radians)
+  RotateByScalar(Entity, Entity, ScalarId), // (Point entity, Center point entity, Scalar of the counter clockwise radians)
+  Translate(Entity, VirtualPosition),       // (Point entity, Translation vector)
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum CircleIntersectId {
   First,
   Second,
 }
 
+impl SymbolicPoint {
+  /// Name of the kind of the symbolic point, e.g. `MidPoint`
+  pub fn kind(&self) -> &'static str {
+    match self {
+      SymbolicPoint::Fixed(_) => "Fixed",
+      SymbolicPoint::Free(_) => "Free",
+      SymbolicPoint::MidPoint(_, _) => "MidPoint",
+      SymbolicPoint::Ratio(_, _, _) => "Ratio",
+      SymbolicPoint::OnLine(_, _) => "OnLine",
+      SymbolicPoint::LineLineIntersect(_, _) => "LineLineIntersect",
+      SymbolicPoint::OnCircle(_, _) => "OnCircle",
+      SymbolicPoint::OnConic(_, _) => "OnConic",
+      SymbolicPoint::CircleLineIntersect(_, _, _) => "CircleLineIntersect",
+      SymbolicPoint::CircleCircleIntersect(_, _, _) => "CircleCircleIntersect",
+      SymbolicPoint::ArcLineIntersect(_, _, _) => "ArcLineIntersect",
+      SymbolicPoint::ArcCircleIntersect(_, _, _) => "ArcCircleIntersect",
+      SymbolicPoint::PointReflection(_, _) => "PointReflection",
+      SymbolicPoint::Reflect(_, _) => "Reflect",
+      SymbolicPoint::Rotate(_, _, _) => "Rotate",
+      SymbolicPoint::RotateByScalar(_, _, _) => "RotateByScalar",
+      SymbolicPoint::Translate(_, _) => "Translate",
+    }
+  }
+
+  /// Whether the point is derived from other geometries, as opposed to a free or fixed point
+  /// standing on its own
+  pub fn is_constrained(&self) -> bool {
+    !matches!(self, SymbolicPoint::Fixed(_) | SymbolicPoint::Free(_))
+  }
+
+  /// The same symbolic point with all the dependency entities mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match *self {
+      SymbolicPoint::Fixed(pos) => SymbolicPoint::Fixed(pos),
+      SymbolicPoint::Free(pos) => SymbolicPoint::Free(pos),
+      SymbolicPoint::MidPoint(p1, p2) => SymbolicPoint::MidPoint(f(p1), f(p2)),
+      SymbolicPoint::Ratio(p1, p2, ratio) => SymbolicPoint::Ratio(f(p1), f(p2), ratio),
+      SymbolicPoint::OnLine(l, t) => SymbolicPoint::OnLine(f(l), t),
+      SymbolicPoint::LineLineIntersect(l1, l2) => SymbolicPoint::LineLineIntersect(f(l1), f(l2)),
+      SymbolicPoint::OnCircle(c, theta) => SymbolicPoint::OnCircle(f(c), theta),
+      SymbolicPoint::OnConic(c, t) => SymbolicPoint::OnConic(f(c), t),
+      SymbolicPoint::CircleLineIntersect(c, l, id) => SymbolicPoint::CircleLineIntersect(f(c), f(l), id),
+      SymbolicPoint::CircleCircleIntersect(c1, c2, id) => SymbolicPoint::CircleCircleIntersect(f(c1), f(c2), id),
+      SymbolicPoint::ArcLineIntersect(a, l, id) => SymbolicPoint::ArcLineIntersect(f(a), f(l), id),
+      SymbolicPoint::ArcCircleIntersect(a, c, id) => SymbolicPoint::ArcCircleIntersect(f(a), f(c), id),
+      SymbolicPoint::PointReflection(source, center) => SymbolicPoint::PointReflection(f(source), f(center)),
+      SymbolicPoint::Reflect(p, mirror) => SymbolicPoint::Reflect(f(p), f(mirror)),
+      SymbolicPoint::Rotate(p, center, radians) => SymbolicPoint::Rotate(f(p), f(center), radians),
+      SymbolicPoint::RotateByScalar(p, center, scalar) => SymbolicPoint::RotateByScalar(f(p), f(center), scalar),
+      SymbolicPoint::Translate(p, vector) => SymbolicPoint::Translate(f(p), vector),
+    }
+  }
+}
+
 impl Component for SymbolicPoint {
   type Storage = VecStorage<Self>;
 }
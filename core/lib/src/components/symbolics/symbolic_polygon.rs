@@ -0,0 +1,16 @@
+use specs::prelude::*;
+
+/// Closed polygon through the points, in order. The last point connects back to the first one
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolicPolygon(pub Vec<Entity>);
+
+impl SymbolicPolygon {
+  /// The same symbolic polygon with all the vertex entities mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    SymbolicPolygon(self.0.iter().map(|ent| f(*ent)).collect())
+  }
+}
+
+impl Component for SymbolicPolygon {
+  type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,34 @@
+use crate::utilities::VirtualPosition;
+use specs::prelude::*;
+
+/// Where a text is placed. A text anchored to a geometry keeps its offset from the anchor of the
+/// geometry when the geometry moves
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TextAnchor {
+  Position(VirtualPosition),
+  Entity(Entity, VirtualPosition), // Point, line or circle entity, offset from its anchor
+}
+
+/// Free floating text annotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolicText {
+  pub text: String,
+  pub anchor: TextAnchor,
+}
+
+impl SymbolicText {
+  /// The same symbolic text with the anchor entity mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    SymbolicText {
+      text: self.text.clone(),
+      anchor: match self.anchor {
+        TextAnchor::Position(position) => TextAnchor::Position(position),
+        TextAnchor::Entity(ent, offset) => TextAnchor::Entity(f(ent), offset),
+      },
+    }
+  }
+}
+
+impl Component for SymbolicText {
+  type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,16 @@
+use specs::prelude::*;
+
+/// Vector pointing from the first point to the second one, drawn as an arrow
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SymbolicVector(pub Entity, pub Entity); // (From point entity, To point entity)
+
+impl SymbolicVector {
+  /// The same symbolic vector with both point entities mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    SymbolicVector(f(self.0), f(self.1))
+  }
+}
+
+impl Component for SymbolicVector {
+  type Storage = VecStorage<Self>;
+}
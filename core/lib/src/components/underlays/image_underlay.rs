@@ -0,0 +1,15 @@
+use crate::math::*;
+use specs::prelude::*;
+use std::path::PathBuf;
+
+/// An image shown beneath all the geometries, e.g. a diagram or a photo to trace over. Only the
+/// path is kept, the apps load the image themselves and tell its size in pixels when inserting it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageUnderlay {
+  pub path: PathBuf,
+  pub size: Vector2, // Pixel of the image, width and height
+}
+
+impl Component for ImageUnderlay {
+  type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,5 @@
+mod image_underlay;
+mod underlay_placement;
+
+pub use image_underlay::*;
+pub use underlay_placement::*;
@@ -0,0 +1,95 @@
+use super::ImageUnderlay;
+use crate::{math::*, utilities::VirtualPosition};
+use specs::prelude::*;
+
+/// The smallest scale an underlay is shrunk to, so that it never vanishes nor flips
+pub static MIN_UNDERLAY_SCALE: f64 = 0.0001; // Virtual length of a pixel of the image
+
+/// Where an underlay lies in the world and how opaque it is, everything that is dragged or changed
+/// once the image is there
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UnderlayPlacement {
+  pub position: VirtualPosition, // Top left corner of the image
+  pub scale: f64,                // Virtual length of a pixel of the image
+  pub opacity: f64,              // From 0 to 1
+}
+
+impl UnderlayPlacement {
+  /// The image centered on `center`, keeping the opacity at 1
+  pub fn centered_at(underlay: &ImageUnderlay, center: VirtualPosition, scale: f64) -> Self {
+    let half_size = vec2![underlay.size.x, -underlay.size.y] * (scale / 2.);
+    Self {
+      position: VirtualPosition(center.0 - half_size),
+      scale,
+      opacity: 1.,
+    }
+  }
+
+  /// The bottom right corner of the image, y going up in the world
+  pub fn bottom_right(&self, underlay: &ImageUnderlay) -> VirtualPosition {
+    self.position + VirtualPosition(vec2![underlay.size.x, -underlay.size.y] * self.scale)
+  }
+
+  pub fn moved_by(self, offset: VirtualPosition) -> Self {
+    Self {
+      position: self.position + offset,
+      ..self
+    }
+  }
+
+  /// Scaled so that the bottom right corner comes as close as it can to `corner`, the top left
+  /// corner staying where it is and the image keeping its aspect ratio
+  pub fn scaled_to(self, underlay: &ImageUnderlay, corner: VirtualPosition) -> Self {
+    let diagonal = vec2![underlay.size.x, -underlay.size.y];
+    let scale = (corner - self.position).0.dot(diagonal) / diagonal.dot(diagonal);
+    Self {
+      scale: if scale.is_finite() {
+        scale.max(MIN_UNDERLAY_SCALE)
+      } else {
+        self.scale
+      },
+      ..self
+    }
+  }
+
+  pub fn with_opacity(self, opacity: f64) -> Self {
+    Self {
+      opacity: opacity.clamp(0., 1.),
+      ..self
+    }
+  }
+}
+
+impl Component for UnderlayPlacement {
+  type Storage = VecStorage<Self>;
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_move_and_scale_by_the_corners() {
+    let underlay = ImageUnderlay {
+      path: PathBuf::from("diagram.png"),
+      size: vec2![200., 100.],
+    };
+    let placement = UnderlayPlacement::centered_at(&underlay, vec2![0., 0.].into(), 0.1);
+    assert_eq!(placement.position.0, vec2![-10., 5.]);
+    assert_eq!(placement.bottom_right(&underlay).0, vec2![10., -5.]);
+
+    let moved = placement.moved_by(vec2![1., 2.].into());
+    assert_eq!(moved.bottom_right(&underlay).0, vec2![11., -3.]);
+
+    // The aspect ratio is kept, the corner is projected on the diagonal
+    let scaled = placement.scaled_to(&underlay, vec2![30., -5.].into());
+    assert!((scaled.scale - 0.18).abs() < 1e-9);
+    assert_eq!(scaled.position.0, placement.position.0);
+    assert_eq!(
+      placement.scaled_to(&underlay, vec2![-20., 20.].into()).scale,
+      MIN_UNDERLAY_SCALE
+    );
+    assert_eq!(placement.with_opacity(1.5).opacity, 1.);
+  }
+}
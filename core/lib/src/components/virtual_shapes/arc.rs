@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::VirtualArc;
+
+impl Component for VirtualArc {
+  type Storage = VecStorage<Self>;
+}
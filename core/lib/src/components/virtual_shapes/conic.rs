@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::VirtualConic;
+
+impl Component for VirtualConic {
+  type Storage = VecStorage<Self>;
+}
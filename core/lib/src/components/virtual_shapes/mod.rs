@@ -1,7 +1,17 @@
+mod arc;
 mod circle;
+mod conic;
 mod line;
 mod point;
+mod polygon;
+mod text;
+mod vector;
 
+pub use arc::*;
 pub use circle::*;
+pub use conic::*;
 pub use line::*;
 pub use point::*;
+pub use polygon::*;
+pub use text::*;
+pub use vector::*;
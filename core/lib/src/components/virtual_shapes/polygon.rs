@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::VirtualPolygon;
+
+impl Component for VirtualPolygon {
+  type Storage = VecStorage<Self>;
+}
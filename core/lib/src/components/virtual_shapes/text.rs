@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::VirtualText;
+
+impl Component for VirtualText {
+  type Storage = VecStorage<Self>;
+}
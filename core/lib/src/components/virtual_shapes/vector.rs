@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+pub use crate::utilities::VirtualVector;
+
+impl Component for VirtualVector {
+  type Storage = VecStorage<Self>;
+}
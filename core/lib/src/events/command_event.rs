@@ -1,28 +1,104 @@
-use crate::components::{styles::*, symbolics::*};
+use crate::{
+  components::{
+    freehand::*,
+    markers::{AnimationMode, Layer},
+    measurements::*,
+    sliders::*,
+    styles::*,
+    symbolics::*,
+    underlays::*,
+  },
+  math::*,
+  resources::{AngleConstraint, Constraint, MacroId, Theme},
+  utilities::{Geometry, Style, VirtualPosition},
+};
 use shrev::*;
 use specs::prelude::*;
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct CommandEvent {
   pub command: Command,
   pub event_id: Option<usize>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Command {
   PointInsert(InsertPointEvent),
   LineInsert(InsertLineEvent),
   CircleInsert(InsertCircleEvent),
+  ArcInsert(InsertArcEvent),
+  ConicInsert(InsertConicEvent),
+  PolygonInsert(InsertPolygonEvent),
+  VectorInsert(InsertVectorEvent),
+  TextInsert(InsertTextEvent),
+  MeasurementInsert(InsertMeasurementEvent),
+  SliderInsert(InsertSliderEvent),
+  UnderlayInsert(InsertUnderlayEvent),
+  FreehandInsert(InsertFreehandEvent),
   Remove(RemoveEvent),
   Update(UpdateEvent),
   Select(SelectEvent),
   Hide(HideEvent),
+  Rename(RenameEvent),
+  Layer(LayerEvent),
+  Restyle(RestyleEvent),
+  Style(StyleCommand), // Applied to the selection
+  SetTheme(Theme),
+  ToggleTheme, // Between the light and the dark themes
+  DumpDependencyGraph,
+  Coordinates(CoordinatesEvent),
+  Trace(TraceEvent),
+  Animation(AnimationEvent),
+  AlignSelected(Alignment, Reference),
+  DistributeSelected(Axis),
+  RotateSelected {
+    pivot: Entity,
+    radians: f64,
+  },
+  ScaleSelected {
+    pivot: Entity,
+    factor: f64,
+  },
+  ReflectSelected {
+    mirror: Entity,
+  },
+  CopySelected, // Into the clipboard, with the geometries they depend on
+  Paste,
+  DuplicateSelection, // With all the geometries it depends on
+  ConstrainAngle(AngleConstraint),
+  RemoveAngleConstraint(Entity, Entity),
+  Constrain(Constraint),
+  RemoveConstraint(Constraint),
+  DefineScalar(String), // A definition such as `r = dist(A, B) * 2`
+  RemoveScalar(String), // Name of the scalar
+  DefineMacro {
+    name: String,
+    inputs: Vec<Entity>, // In the order the macro is to be given them
+    outputs: Vec<Entity>,
+  },
+  DefineMacroFromSelection(String), // Name, see `MacroHandler` for which selected are the inputs
+  ApplyMacro(MacroId, Vec<Entity>), // Inputs
+  SuspendSolve,
+  ResumeSolve,
+  ClearAll,
+  ChangeLineType(Entity, LineType),
+  SetRotationAngle(Entity, f64), // Of a rotated point, in counter clockwise radians
+  SetTranslation(Entity, VirtualPosition), // Of a translated point
+  RedefinePoint(Entity, Option<Entity>), // Point, the line, circle or conic to put it on, or none to free it
+  RedefineSelectedPoint,         // Onto the selected line, circle or conic, freed if there is none
+  BeginTransaction(String),      // Label of the undo step
+  EndTransaction,
+  SaveSketch(PathBuf),
+  LoadSketch(PathBuf),
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum InsertPointEvent {
   InsertPoint(SymbolicPoint),
+  InsertPointAt(Vector2), // Fixed point at exact virtual coordinates, no snapping
   InsertMidPointFromSelection,
+  InsertIntersectionsFromSelection, // Of the two selected lines, circles or arcs
   InsertPointWithStyle(SymbolicPoint, PointStyle),
   InsertPointByHistory(Entity, SymbolicPoint, PointStyle),
 }
@@ -32,6 +108,7 @@ pub enum InsertLineEvent {
   InsertLine(SymbolicLine),
   InsertParallelFromSelection,
   InsertPerpendicularFromSelection,
+  InsertPerpendicularBisectorFromSelection, // Of the two selected points, or of the selected segment
   InsertLineWithStyle(SymbolicLine, LineStyle),
   InsertLineByHistory(Entity, SymbolicLine, LineStyle),
 }
@@ -39,10 +116,66 @@ pub enum InsertLineEvent {
 #[derive(Debug, Clone, Copy)]
 pub enum InsertCircleEvent {
   InsertCircle(SymbolicCircle),
+  InsertCircumcircleFromSelection, // Circle through the three selected points
+  InsertCompassFromSelection,      // Around the selected point, as wide as the selected segment is long
   InsertCircleWithStyle(SymbolicCircle, CircleStyle),
   InsertCircleByHistory(Entity, SymbolicCircle, CircleStyle),
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum InsertArcEvent {
+  InsertArc(SymbolicArc),
+  InsertArcByHistory(Entity, SymbolicArc, ArcStyle),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum InsertConicEvent {
+  InsertConic(SymbolicConic),
+  InsertConicByHistory(Entity, SymbolicConic, ConicStyle),
+}
+
+#[derive(Debug, Clone)]
+pub enum InsertPolygonEvent {
+  InsertPolygon(SymbolicPolygon),
+  InsertPolygonByHistory(Entity, SymbolicPolygon, PolygonStyle),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum InsertVectorEvent {
+  InsertVector(SymbolicVector),
+  InsertVectorByHistory(Entity, SymbolicVector, VectorStyle),
+}
+
+#[derive(Debug, Clone)]
+pub enum InsertTextEvent {
+  InsertText(SymbolicText),
+  InsertTextByHistory(Entity, SymbolicText, TextStyle),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum InsertMeasurementEvent {
+  InsertMeasurement(Measurement),
+  InsertMeasurementByHistory(Entity, Measurement),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum InsertSliderEvent {
+  InsertSlider(Slider, SliderValue),
+  InsertSliderByHistory(Entity, Slider, SliderValue),
+}
+
+#[derive(Debug, Clone)]
+pub enum InsertUnderlayEvent {
+  InsertUnderlay(ImageUnderlay, UnderlayPlacement),
+  InsertUnderlayByHistory(Entity, ImageUnderlay, UnderlayPlacement),
+}
+
+#[derive(Debug, Clone)]
+pub enum InsertFreehandEvent {
+  InsertFreehand(FreehandStroke),
+  InsertFreehandByHistory(Entity, FreehandStroke, LineStyle),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum RemoveEvent {
   Remove(Entity),
@@ -56,6 +189,13 @@ pub enum UpdateEvent {
   UpdatePoint(Entity, SymbolicPoint, SymbolicPoint), // Entity, before, after
   UpdatePointEnd(Entity, SymbolicPoint, SymbolicPoint), // Entity, before, after
   UpdatePointByHistory(Entity, SymbolicPoint, SymbolicPoint), // Entity, before, after
+  UpdateLineByHistory(Entity, SymbolicLine, SymbolicLine), // Entity, before, after
+  UpdateSlider(Entity, f64, f64),                    // Entity, value before, value after
+  UpdateSliderEnd(Entity, f64, f64),                 // Entity, value before, value after
+  UpdateSliderByHistory(Entity, f64, f64),           // Entity, value before, value after
+  UpdateUnderlay(Entity, UnderlayPlacement, UnderlayPlacement), // Entity, before, after
+  UpdateUnderlayEnd(Entity, UnderlayPlacement, UnderlayPlacement), // Entity, before, after
+  UpdateUnderlayByHistory(Entity, UnderlayPlacement, UnderlayPlacement), // Entity, before, after
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -73,9 +213,355 @@ pub enum HideEvent {
   Unhide(Entity),
   UnhideByHistory(Entity),
   HideSelected,
+  HideAncestorsOfSelected, // The lines, circles, arcs and conics the selection is constructed from
   UnhideAll,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum RestyleEvent {
+  Restyle(Entity, Style),
+  RestyleByHistory(Entity, Style),
+}
+
+/// Changes one property of the style of every selected geometry, the geometries without such a
+/// property are left as they are
+#[derive(Debug, Clone, Copy)]
+pub enum StyleCommand {
+  SetColor(Color),
+  SetThickness(f64), // Width of lines, arcs, conics, vectors and of the borders of circles and polygons
+  SetDashPattern(DashPattern), // Of lines
+  SetPointRadius(f64),
+}
+
+impl Command {
+  /// The command inserting the geometry back under its own entity, without being recorded
+  pub fn insert_by_history(ent: Entity, geometry: &Geometry) -> Self {
+    match geometry {
+      Geometry::Point(sym_point, point_style) => {
+        Command::PointInsert(InsertPointEvent::InsertPointByHistory(ent, *sym_point, *point_style))
+      }
+      Geometry::Line(sym_line, line_style) => {
+        Command::LineInsert(InsertLineEvent::InsertLineByHistory(ent, *sym_line, *line_style))
+      }
+      Geometry::Circle(sym_circle, circle_style) => Command::CircleInsert(InsertCircleEvent::InsertCircleByHistory(
+        ent,
+        *sym_circle,
+        *circle_style,
+      )),
+      Geometry::Arc(sym_arc, arc_style) => {
+        Command::ArcInsert(InsertArcEvent::InsertArcByHistory(ent, *sym_arc, *arc_style))
+      }
+      Geometry::Conic(sym_conic, conic_style) => {
+        Command::ConicInsert(InsertConicEvent::InsertConicByHistory(ent, *sym_conic, *conic_style))
+      }
+      Geometry::Polygon(sym_polygon, polygon_style) => Command::PolygonInsert(
+        InsertPolygonEvent::InsertPolygonByHistory(ent, sym_polygon.clone(), *polygon_style),
+      ),
+      Geometry::Vector(sym_vector, vector_style) => Command::VectorInsert(InsertVectorEvent::InsertVectorByHistory(
+        ent,
+        *sym_vector,
+        *vector_style,
+      )),
+      Geometry::Text(sym_text, text_style) => {
+        Command::TextInsert(InsertTextEvent::InsertTextByHistory(ent, sym_text.clone(), *text_style))
+      }
+      Geometry::Measurement(measurement) => {
+        Command::MeasurementInsert(InsertMeasurementEvent::InsertMeasurementByHistory(ent, *measurement))
+      }
+      Geometry::Slider(slider, slider_value) => {
+        Command::SliderInsert(InsertSliderEvent::InsertSliderByHistory(ent, *slider, *slider_value))
+      }
+      Geometry::Underlay(underlay, placement) => Command::UnderlayInsert(InsertUnderlayEvent::InsertUnderlayByHistory(
+        ent,
+        underlay.clone(),
+        *placement,
+      )),
+      Geometry::Freehand(stroke, line_style) => Command::FreehandInsert(InsertFreehandEvent::InsertFreehandByHistory(
+        ent,
+        stroke.clone(),
+        *line_style,
+      )),
+    }
+  }
+
+  /// The same command with all the entities it refers to mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match self {
+      Command::PointInsert(event) => Command::PointInsert(match *event {
+        InsertPointEvent::InsertPoint(sym_point) => InsertPointEvent::InsertPoint(sym_point.remap(f)),
+        InsertPointEvent::InsertPointAt(position) => InsertPointEvent::InsertPointAt(position),
+        InsertPointEvent::InsertMidPointFromSelection => InsertPointEvent::InsertMidPointFromSelection,
+        InsertPointEvent::InsertIntersectionsFromSelection => InsertPointEvent::InsertIntersectionsFromSelection,
+        InsertPointEvent::InsertPointWithStyle(sym_point, style) => {
+          InsertPointEvent::InsertPointWithStyle(sym_point.remap(f), style)
+        }
+        InsertPointEvent::InsertPointByHistory(ent, sym_point, style) => {
+          InsertPointEvent::InsertPointByHistory(f(ent), sym_point.remap(f), style)
+        }
+      }),
+      Command::LineInsert(event) => Command::LineInsert(match *event {
+        InsertLineEvent::InsertLine(sym_line) => InsertLineEvent::InsertLine(sym_line.remap(f)),
+        InsertLineEvent::InsertParallelFromSelection => InsertLineEvent::InsertParallelFromSelection,
+        InsertLineEvent::InsertPerpendicularFromSelection => InsertLineEvent::InsertPerpendicularFromSelection,
+        InsertLineEvent::InsertPerpendicularBisectorFromSelection => {
+          InsertLineEvent::InsertPerpendicularBisectorFromSelection
+        }
+        InsertLineEvent::InsertLineWithStyle(sym_line, style) => {
+          InsertLineEvent::InsertLineWithStyle(sym_line.remap(f), style)
+        }
+        InsertLineEvent::InsertLineByHistory(ent, sym_line, style) => {
+          InsertLineEvent::InsertLineByHistory(f(ent), sym_line.remap(f), style)
+        }
+      }),
+      Command::CircleInsert(event) => Command::CircleInsert(match *event {
+        InsertCircleEvent::InsertCircle(sym_circle) => InsertCircleEvent::InsertCircle(sym_circle.remap(f)),
+        InsertCircleEvent::InsertCircumcircleFromSelection => InsertCircleEvent::InsertCircumcircleFromSelection,
+        InsertCircleEvent::InsertCompassFromSelection => InsertCircleEvent::InsertCompassFromSelection,
+        InsertCircleEvent::InsertCircleWithStyle(sym_circle, style) => {
+          InsertCircleEvent::InsertCircleWithStyle(sym_circle.remap(f), style)
+        }
+        InsertCircleEvent::InsertCircleByHistory(ent, sym_circle, style) => {
+          InsertCircleEvent::InsertCircleByHistory(f(ent), sym_circle.remap(f), style)
+        }
+      }),
+      Command::ArcInsert(event) => Command::ArcInsert(match *event {
+        InsertArcEvent::InsertArc(sym_arc) => InsertArcEvent::InsertArc(sym_arc.remap(f)),
+        InsertArcEvent::InsertArcByHistory(ent, sym_arc, style) => {
+          InsertArcEvent::InsertArcByHistory(f(ent), sym_arc.remap(f), style)
+        }
+      }),
+      Command::ConicInsert(event) => Command::ConicInsert(match *event {
+        InsertConicEvent::InsertConic(sym_conic) => InsertConicEvent::InsertConic(sym_conic.remap(f)),
+        InsertConicEvent::InsertConicByHistory(ent, sym_conic, style) => {
+          InsertConicEvent::InsertConicByHistory(f(ent), sym_conic.remap(f), style)
+        }
+      }),
+      Command::PolygonInsert(event) => Command::PolygonInsert(match event {
+        InsertPolygonEvent::InsertPolygon(sym_polygon) => InsertPolygonEvent::InsertPolygon(sym_polygon.remap(f)),
+        InsertPolygonEvent::InsertPolygonByHistory(ent, sym_polygon, style) => {
+          InsertPolygonEvent::InsertPolygonByHistory(f(*ent), sym_polygon.remap(f), *style)
+        }
+      }),
+      Command::VectorInsert(event) => Command::VectorInsert(match *event {
+        InsertVectorEvent::InsertVector(sym_vector) => InsertVectorEvent::InsertVector(sym_vector.remap(f)),
+        InsertVectorEvent::InsertVectorByHistory(ent, sym_vector, style) => {
+          InsertVectorEvent::InsertVectorByHistory(f(ent), sym_vector.remap(f), style)
+        }
+      }),
+      Command::TextInsert(event) => Command::TextInsert(match event {
+        InsertTextEvent::InsertText(sym_text) => InsertTextEvent::InsertText(sym_text.remap(f)),
+        InsertTextEvent::InsertTextByHistory(ent, sym_text, style) => {
+          InsertTextEvent::InsertTextByHistory(f(*ent), sym_text.remap(f), *style)
+        }
+      }),
+      Command::MeasurementInsert(event) => Command::MeasurementInsert(match *event {
+        InsertMeasurementEvent::InsertMeasurement(measurement) => {
+          InsertMeasurementEvent::InsertMeasurement(measurement.remap(f))
+        }
+        InsertMeasurementEvent::InsertMeasurementByHistory(ent, measurement) => {
+          InsertMeasurementEvent::InsertMeasurementByHistory(f(ent), measurement.remap(f))
+        }
+      }),
+      Command::SliderInsert(event) => Command::SliderInsert(match *event {
+        InsertSliderEvent::InsertSlider(slider, slider_value) => InsertSliderEvent::InsertSlider(slider, slider_value),
+        InsertSliderEvent::InsertSliderByHistory(ent, slider, slider_value) => {
+          InsertSliderEvent::InsertSliderByHistory(f(ent), slider, slider_value)
+        }
+      }),
+      Command::UnderlayInsert(event) => Command::UnderlayInsert(match event {
+        InsertUnderlayEvent::InsertUnderlay(underlay, placement) => {
+          InsertUnderlayEvent::InsertUnderlay(underlay.clone(), *placement)
+        }
+        InsertUnderlayEvent::InsertUnderlayByHistory(ent, underlay, placement) => {
+          InsertUnderlayEvent::InsertUnderlayByHistory(f(*ent), underlay.clone(), *placement)
+        }
+      }),
+      Command::FreehandInsert(event) => Command::FreehandInsert(match event {
+        InsertFreehandEvent::InsertFreehand(stroke) => InsertFreehandEvent::InsertFreehand(stroke.clone()),
+        InsertFreehandEvent::InsertFreehandByHistory(ent, stroke, line_style) => {
+          InsertFreehandEvent::InsertFreehandByHistory(f(*ent), stroke.clone(), *line_style)
+        }
+      }),
+      Command::Remove(event) => Command::Remove(match *event {
+        RemoveEvent::Remove(ent) => RemoveEvent::Remove(f(ent)),
+        RemoveEvent::RemoveByHistory(ent) => RemoveEvent::RemoveByHistory(f(ent)),
+        RemoveEvent::RemoveSelected => RemoveEvent::RemoveSelected,
+        RemoveEvent::RemoveAll => RemoveEvent::RemoveAll,
+      }),
+      Command::Update(event) => Command::Update(match *event {
+        UpdateEvent::UpdatePoint(ent, before, after) => {
+          UpdateEvent::UpdatePoint(f(ent), before.remap(f), after.remap(f))
+        }
+        UpdateEvent::UpdatePointEnd(ent, before, after) => {
+          UpdateEvent::UpdatePointEnd(f(ent), before.remap(f), after.remap(f))
+        }
+        UpdateEvent::UpdatePointByHistory(ent, before, after) => {
+          UpdateEvent::UpdatePointByHistory(f(ent), before.remap(f), after.remap(f))
+        }
+        UpdateEvent::UpdateLineByHistory(ent, before, after) => {
+          UpdateEvent::UpdateLineByHistory(f(ent), before.remap(f), after.remap(f))
+        }
+        UpdateEvent::UpdateSlider(ent, before, after) => UpdateEvent::UpdateSlider(f(ent), before, after),
+        UpdateEvent::UpdateSliderEnd(ent, before, after) => UpdateEvent::UpdateSliderEnd(f(ent), before, after),
+        UpdateEvent::UpdateSliderByHistory(ent, before, after) => {
+          UpdateEvent::UpdateSliderByHistory(f(ent), before, after)
+        }
+        UpdateEvent::UpdateUnderlay(ent, before, after) => UpdateEvent::UpdateUnderlay(f(ent), before, after),
+        UpdateEvent::UpdateUnderlayEnd(ent, before, after) => UpdateEvent::UpdateUnderlayEnd(f(ent), before, after),
+        UpdateEvent::UpdateUnderlayByHistory(ent, before, after) => {
+          UpdateEvent::UpdateUnderlayByHistory(f(ent), before, after)
+        }
+      }),
+      Command::Select(event) => Command::Select(match *event {
+        SelectEvent::Select(ent) => SelectEvent::Select(f(ent)),
+        SelectEvent::Deselect(ent) => SelectEvent::Deselect(f(ent)),
+        SelectEvent::SelectAll => SelectEvent::SelectAll,
+        SelectEvent::DeselectAll => SelectEvent::DeselectAll,
+      }),
+      Command::Hide(event) => Command::Hide(match *event {
+        HideEvent::Hide(ent) => HideEvent::Hide(f(ent)),
+        HideEvent::HideByHistory(ent) => HideEvent::HideByHistory(f(ent)),
+        HideEvent::Unhide(ent) => HideEvent::Unhide(f(ent)),
+        HideEvent::UnhideByHistory(ent) => HideEvent::UnhideByHistory(f(ent)),
+        HideEvent::HideSelected => HideEvent::HideSelected,
+        HideEvent::HideAncestorsOfSelected => HideEvent::HideAncestorsOfSelected,
+        HideEvent::UnhideAll => HideEvent::UnhideAll,
+      }),
+      Command::Rename(event) => Command::Rename(match event {
+        RenameEvent::Rename(ent, name) => RenameEvent::Rename(f(*ent), name.clone()),
+        RenameEvent::RenameByHistory(ent, name) => RenameEvent::RenameByHistory(f(*ent), name.clone()),
+        RenameEvent::RenameSelected(name) => RenameEvent::RenameSelected(name.clone()),
+        RenameEvent::Unname(ent) => RenameEvent::Unname(f(*ent)),
+      }),
+      Command::Layer(event) => Command::Layer(event.clone()),
+      Command::Restyle(event) => Command::Restyle(match *event {
+        RestyleEvent::Restyle(ent, style) => RestyleEvent::Restyle(f(ent), style),
+        RestyleEvent::RestyleByHistory(ent, style) => RestyleEvent::RestyleByHistory(f(ent), style),
+      }),
+      Command::Style(style_command) => Command::Style(*style_command),
+      Command::SetTheme(theme) => Command::SetTheme(*theme),
+      Command::ToggleTheme => Command::ToggleTheme,
+      Command::DumpDependencyGraph => Command::DumpDependencyGraph,
+      Command::Coordinates(event) => Command::Coordinates(match *event {
+        CoordinatesEvent::Toggle(ent) => CoordinatesEvent::Toggle(f(ent)),
+        CoordinatesEvent::ToggleSelected => CoordinatesEvent::ToggleSelected,
+      }),
+      Command::Trace(event) => Command::Trace(match *event {
+        TraceEvent::Toggle(ent) => TraceEvent::Toggle(f(ent)),
+        TraceEvent::ToggleSelected => TraceEvent::ToggleSelected,
+        TraceEvent::Clear => TraceEvent::Clear,
+      }),
+      Command::Animation(event) => Command::Animation(match *event {
+        AnimationEvent::Play(ent) => AnimationEvent::Play(f(ent)),
+        AnimationEvent::Pause(ent) => AnimationEvent::Pause(f(ent)),
+        AnimationEvent::TogglePlaySelected => AnimationEvent::TogglePlaySelected,
+        AnimationEvent::SetSpeed(ent, speed) => AnimationEvent::SetSpeed(f(ent), speed),
+        AnimationEvent::SetMode(ent, mode) => AnimationEvent::SetMode(f(ent), mode),
+      }),
+      Command::AlignSelected(alignment, reference) => Command::AlignSelected(*alignment, *reference),
+      Command::DistributeSelected(axis) => Command::DistributeSelected(*axis),
+      Command::RotateSelected { pivot, radians } => Command::RotateSelected {
+        pivot: f(*pivot),
+        radians: *radians,
+      },
+      Command::ScaleSelected { pivot, factor } => Command::ScaleSelected {
+        pivot: f(*pivot),
+        factor: *factor,
+      },
+      Command::ReflectSelected { mirror } => Command::ReflectSelected { mirror: f(*mirror) },
+      Command::CopySelected => Command::CopySelected,
+      Command::Paste => Command::Paste,
+      Command::DuplicateSelection => Command::DuplicateSelection,
+      Command::ConstrainAngle(constraint) => Command::ConstrainAngle(AngleConstraint {
+        line_a: f(constraint.line_a),
+        line_b: f(constraint.line_b),
+        radians: constraint.radians,
+      }),
+      Command::RemoveAngleConstraint(line_a, line_b) => Command::RemoveAngleConstraint(f(*line_a), f(*line_b)),
+      Command::Constrain(constraint) => Command::Constrain(constraint.remap(f)),
+      Command::RemoveConstraint(constraint) => Command::RemoveConstraint(constraint.remap(f)),
+      Command::DefineScalar(definition) => Command::DefineScalar(definition.clone()),
+      Command::RemoveScalar(name) => Command::RemoveScalar(name.clone()),
+      Command::DefineMacro { name, inputs, outputs } => Command::DefineMacro {
+        name: name.clone(),
+        inputs: inputs.iter().map(|ent| f(*ent)).collect(),
+        outputs: outputs.iter().map(|ent| f(*ent)).collect(),
+      },
+      Command::DefineMacroFromSelection(name) => Command::DefineMacroFromSelection(name.clone()),
+      Command::ApplyMacro(id, inputs) => Command::ApplyMacro(*id, inputs.iter().map(|ent| f(*ent)).collect()),
+      Command::SuspendSolve => Command::SuspendSolve,
+      Command::ResumeSolve => Command::ResumeSolve,
+      Command::ClearAll => Command::ClearAll,
+      Command::ChangeLineType(ent, line_type) => Command::ChangeLineType(f(*ent), *line_type),
+      Command::SetRotationAngle(ent, radians) => Command::SetRotationAngle(f(*ent), *radians),
+      Command::SetTranslation(ent, vector) => Command::SetTranslation(f(*ent), *vector),
+      Command::RedefinePoint(ent, target) => Command::RedefinePoint(f(*ent), target.map(&mut *f)),
+      Command::RedefineSelectedPoint => Command::RedefineSelectedPoint,
+      Command::BeginTransaction(label) => Command::BeginTransaction(label.clone()),
+      Command::EndTransaction => Command::EndTransaction,
+      Command::SaveSketch(path) => Command::SaveSketch(path.clone()),
+      Command::LoadSketch(path) => Command::LoadSketch(path.clone()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CoordinatesEvent {
+  Toggle(Entity),
+  ToggleSelected,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TraceEvent {
+  Toggle(Entity), // Untracing a point also removes its trace
+  ToggleSelected,
+  Clear, // Remove all the traces, the points keep being traced
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum AnimationEvent {
+  Play(Entity), // Only points on a line or on a circle can be animated
+  Pause(Entity),
+  TogglePlaySelected,
+  SetSpeed(Entity, f64), // Fraction of the path per second, negative to go backwards
+  SetMode(Entity, AnimationMode),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alignment {
+  Horizontal, // Common y
+  Vertical,   // Common x
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reference {
+  Average,
+  FirstSelected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+  X,
+  Y,
+}
+
+#[derive(Debug, Clone)]
+pub enum RenameEvent {
+  Rename(Entity, String),
+  RenameByHistory(Entity, Option<String>), // No name takes the name away
+  RenameSelected(String),
+  Unname(Entity),
+}
+
+#[derive(Debug, Clone)]
+pub enum LayerEvent {
+  Create(String), // Name of the new layer
+  Rename(Layer, String),
+  SetHidden(Layer, bool),
+  SetLocked(Layer, bool),
+  MoveSelectedTo(Layer),
+}
+
 pub type CommandEventChannel = EventChannel<CommandEvent>;
 
 pub type CommandEventReader = ReaderId<CommandEvent>;
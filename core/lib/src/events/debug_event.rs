@@ -0,0 +1,10 @@
+use shrev::*;
+
+#[derive(Debug, Clone)]
+pub enum DebugEvent {
+  DependencyGraph(String), // In DOT format
+}
+
+pub type DebugEventChannel = EventChannel<DebugEvent>;
+
+pub type DebugEventReader = ReaderId<DebugEvent>;
@@ -0,0 +1,15 @@
+use crate::components::markers::UnsolvableReason;
+use shrev::*;
+use specs::prelude::*;
+
+/// Written by `VirtualShapeSolver` when a geometry stops or starts being solvable, for the
+/// frontends to gray it out and show why
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticEvent {
+  Unsolvable(Entity, UnsolvableReason), // Entity that could not be solved, or the reason changed
+  Solvable(Entity),                     // Entity that was unsolvable and got solved again
+}
+
+pub type DiagnosticEventChannel = EventChannel<DiagnosticEvent>;
+
+pub type DiagnosticEventReader = ReaderId<DiagnosticEvent>;
@@ -0,0 +1,30 @@
+use crate::{
+  components::markers::Layer,
+  io::SketchFileError,
+  resources::{Constraint, MacroError},
+  utilities::ExpressionError,
+};
+use shrev::*;
+use specs::prelude::*;
+
+#[derive(Debug, Clone)]
+pub enum ErrorEvent {
+  DuplicateName(Entity, String),      // Entity being renamed, the name already taken
+  NotOneSelected(usize),              // Amount of selected elements, when the command needs exactly one
+  LineNotDraggable(Entity),           // Line with a defining point that is not free
+  PointNotMovable(Entity),            // Point constrained by other elements, it is not free
+  InvalidScaleFactor(f64),            // Scale factor that is not positive
+  TooManyEntities(usize),             // The maximum amount of geometries, already reached
+  OverConstrained(Entity, Entity),    // Lines of an angle constraint, neither has a free point to move
+  Unsatisfiable(Constraint),          // Constraint the free points could not be moved to satisfy
+  InvalidExpression(ExpressionError), // Scalar definition that could not be parsed
+  SketchFile(SketchFileError),        // Sketch that could not be saved or loaded
+  LayerLocked(Entity),                // Entity that cannot be dragged nor removed as its layer is locked
+  UnknownLayer(Layer),                // Layer that does not exist
+  InvalidMacro(MacroError),           // Macro that could not be defined or applied
+  Script(String),                     // Error raised by a script, with its message
+}
+
+pub type ErrorEventChannel = EventChannel<ErrorEvent>;
+
+pub type ErrorEventReader = ReaderId<ErrorEvent>;
@@ -1,12 +1,24 @@
-use crate::{components::symbolics::SymbolicPoint, utilities::Geometry};
+use crate::{
+  components::{
+    symbolics::{SymbolicLine, SymbolicPoint},
+    underlays::UnderlayPlacement,
+  },
+  resources::DependencyGraph,
+  utilities::{Geometry, ScalarId},
+};
 use shrev::{EventChannel, ReaderId};
 use specs::prelude::*;
+use std::collections::HashSet;
 
 pub enum GeometryEvent {
   Inserted(Entity, Geometry, bool),
   Removed(Entity, Geometry, bool),
   PointUpdated(Entity, SymbolicPoint, SymbolicPoint, bool),
   PointUpdateFinished(Entity, SymbolicPoint, SymbolicPoint, bool),
+  LineUpdated(Entity, SymbolicLine, SymbolicLine, bool),
+  ScalarUpdated(ScalarId),                      // Defined, removed or evaluated to another value
+  SliderUpdateFinished(Entity, f64, f64, bool), // Entity, value before the drag, value after it
+  UnderlayUpdateFinished(Entity, UnderlayPlacement, UnderlayPlacement, bool), // Entity, before the drag, after it
 }
 
 pub type GeometryEventChannel = EventChannel<GeometryEvent>;
@@ -49,4 +61,47 @@ impl GeometryEvent {
   ) -> Self {
     GeometryEvent::PointUpdateFinished(entity, old_sym_point, new_sym_point, true)
   }
+
+  pub fn slider_update_finished(entity: Entity, old_value: f64, new_value: f64) -> Self {
+    GeometryEvent::SliderUpdateFinished(entity, old_value, new_value, false)
+  }
+
+  pub fn slider_update_finished_by_history(entity: Entity, old_value: f64, new_value: f64) -> Self {
+    GeometryEvent::SliderUpdateFinished(entity, old_value, new_value, true)
+  }
+
+  pub fn underlay_update_finished(
+    entity: Entity,
+    old_placement: UnderlayPlacement,
+    new_placement: UnderlayPlacement,
+  ) -> Self {
+    GeometryEvent::UnderlayUpdateFinished(entity, old_placement, new_placement, false)
+  }
+
+  pub fn underlay_update_finished_by_history(
+    entity: Entity,
+    old_placement: UnderlayPlacement,
+    new_placement: UnderlayPlacement,
+  ) -> Self {
+    GeometryEvent::UnderlayUpdateFinished(entity, old_placement, new_placement, true)
+  }
+
+  pub fn line_updated(entity: Entity, old_sym_line: SymbolicLine, new_sym_line: SymbolicLine) -> Self {
+    GeometryEvent::LineUpdated(entity, old_sym_line, new_sym_line, false)
+  }
+
+  pub fn line_updated_by_history(entity: Entity, old_sym_line: SymbolicLine, new_sym_line: SymbolicLine) -> Self {
+    GeometryEvent::LineUpdated(entity, old_sym_line, new_sym_line, true)
+  }
+
+  /// The geometries to solve again after an update, the updated one included
+  pub fn updated_dependents(&self, dependency_graph: &DependencyGraph) -> HashSet<Entity> {
+    match self {
+      GeometryEvent::PointUpdated(ent, _, _, _) | GeometryEvent::LineUpdated(ent, _, _, _) => {
+        dependency_graph.get_all_dependents(ent)
+      }
+      GeometryEvent::ScalarUpdated(scalar) => dependency_graph.get_scalar_dependents(scalar),
+      _ => HashSet::new(),
+    }
+  }
 }
@@ -1,10 +1,13 @@
+use crate::resources::NodeId;
 use shrev::*;
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub enum HistoryEvent {
   Redo,
   Undo,
   Clear,
+  JumpTo(NodeId),        // Undoes and redoes along the history tree until the node is the current state
+  ToggleViewportHistory, // Whether panning and zooming can be undone too
 }
 
 pub type HistoryEventChannel = EventChannel<HistoryEvent>;
@@ -0,0 +1,13 @@
+use crate::resources::InspectedGeometry;
+use shrev::*;
+
+/// Emitted whenever the description of the selection changes, with nothing in it once the
+/// selection is empty
+#[derive(Debug, Clone)]
+pub enum InspectionEvent {
+  Inspected(Vec<InspectedGeometry>),
+}
+
+pub type InspectionEventChannel = EventChannel<InspectionEvent>;
+
+pub type InspectionEventReader = ReaderId<InspectionEvent>;
@@ -1,11 +1,14 @@
+use crate::utilities::Style;
 use shrev::*;
 use specs::prelude::*;
 
 pub enum MarkerEvent {
   Select(Entity),
   Deselect(Entity),
-  Hide(Entity, bool),   // bool: Is done by history
-  Unhide(Entity, bool), // bool: Is done by history
+  Hide(Entity, bool),                                   // bool: Is done by history
+  Unhide(Entity, bool),                                 // bool: Is done by history
+  Restyle(Entity, Style, Style, bool),                  // Entity, old, new, is done by history
+  Rename(Entity, Option<String>, Option<String>, bool), // Entity, old, new, is done by history
 }
 
 pub type MarkerEventChannel = EventChannel<MarkerEvent>;
@@ -28,4 +31,20 @@ impl MarkerEvent {
   pub fn unhide_by_history(ent: Entity) -> Self {
     MarkerEvent::Unhide(ent, true)
   }
+
+  pub fn restyle(ent: Entity, old_style: Style, new_style: Style) -> Self {
+    MarkerEvent::Restyle(ent, old_style, new_style, false)
+  }
+
+  pub fn restyle_by_history(ent: Entity, old_style: Style, new_style: Style) -> Self {
+    MarkerEvent::Restyle(ent, old_style, new_style, true)
+  }
+
+  pub fn rename(ent: Entity, old_name: Option<String>, new_name: Option<String>) -> Self {
+    MarkerEvent::Rename(ent, old_name, new_name, false)
+  }
+
+  pub fn rename_by_history(ent: Entity, old_name: Option<String>, new_name: Option<String>) -> Self {
+    MarkerEvent::Rename(ent, old_name, new_name, true)
+  }
 }
@@ -1,11 +1,19 @@
 mod command_event;
+mod debug_event;
+mod diagnostic_event;
+mod error_event;
 mod geometry_event;
 mod history_event;
+mod inspection_event;
 mod marker_event;
 mod viewport_event;
 
 pub use command_event::*;
+pub use debug_event::*;
+pub use diagnostic_event::*;
+pub use error_event::*;
 pub use geometry_event::*;
 pub use history_event::*;
+pub use inspection_event::*;
 pub use marker_event::*;
 pub use viewport_event::*;
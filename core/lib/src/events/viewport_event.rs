@@ -1,10 +1,12 @@
-use crate::math::*;
+use crate::{math::*, resources::Viewport};
 use shrev::{EventChannel, ReaderId};
 
 pub enum ViewportEvent {
-  Move(Vector2),   // Virtual Center
-  Scale(f64),      // Change in pixel
-  Resize(Vector2), // Screen Size
+  Move(Vector2),      // Virtual Center
+  Scale(f64),         // Change in pixel
+  Zoom(f64, Vector2), // Factor, virtual position staying under the cursor
+  Resize(Vector2),    // Screen Size
+  Restore(Viewport),  // Viewport from history, keeping the current screen size
 }
 
 pub type ViewportEventChannel = EventChannel<ViewportEvent>;
@@ -0,0 +1,5 @@
+mod session_log;
+mod sketch_file;
+
+pub use session_log::*;
+pub use sketch_file::*;
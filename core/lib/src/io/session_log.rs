@@ -0,0 +1,443 @@
+use super::sketch_file::*;
+use crate::{
+  components::symbolics::SymbolicText,
+  events::*,
+  math::*,
+  resources::{CommandLog, MacroId, NodeId, RecordedCommand, RecordedHistoryEvent, RecordedInsertion},
+  utilities::GeometryKind,
+};
+use serde_json::{json, Value};
+use specs::{prelude::*, world::EntitiesRes};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Version written in the first line of every session log. Logs of a newer version are refused
+pub const SESSION_LOG_VERSION: u64 = 1;
+
+/// The first line of a session log
+pub fn write_session_header() -> String {
+  json!({ "version": SESSION_LOG_VERSION }).to_string()
+}
+
+/// The line of a command emitted during the session. The commands emitted by undo and redo are not
+/// written, replaying the history events emits them again. The commands that cannot be written are
+/// kept as their debug output, and skipped when the log is read
+pub fn write_session_command(frame: usize, time: Duration, event: &CommandEvent) -> Option<String> {
+  if is_by_history(&event.command) {
+    return None;
+  }
+  let line = match command_to_json(&event.command) {
+    Some(command) => json!({
+      "frame": frame,
+      "time": time.as_secs_f64(),
+      "event_id": event.event_id,
+      "command": command,
+    }),
+    None => json!({
+      "frame": frame,
+      "time": time.as_secs_f64(),
+      "unsupported": format!("{:?}", event.command),
+    }),
+  };
+  Some(line.to_string())
+}
+
+/// The line of a geometry inserted by a command, the replay pairs it with the one it inserts
+pub fn write_session_insertion(frame: usize, entity: Entity, kind: GeometryKind) -> String {
+  json!({ "frame": frame, "inserted": entity.id(), "kind": kind_to_str(kind) }).to_string()
+}
+
+pub fn write_session_history_event(frame: usize, time: Duration, event: HistoryEvent) -> String {
+  let history = match event {
+    HistoryEvent::Undo => json!({ "type": "undo" }),
+    HistoryEvent::Redo => json!({ "type": "redo" }),
+    HistoryEvent::Clear => json!({ "type": "clear" }),
+    HistoryEvent::JumpTo(node) => json!({ "type": "jump_to", "node": node.0 }),
+    HistoryEvent::ToggleViewportHistory => json!({ "type": "toggle_viewport_history" }),
+  };
+  json!({ "frame": frame, "time": time.as_secs_f64(), "history": history }).to_string()
+}
+
+/// Reads a session log back as a command log to replay. Every inserted geometry gets a new entity
+/// created in `entities`, standing for it until the replay inserts the actual one. The entities
+/// are deleted again when the log turns out to be invalid
+pub fn read_session_log(text: &str, entities: &EntitiesRes) -> Result<CommandLog, SketchFileError> {
+  let mut lines = vec![];
+  for line in text.lines().filter(|line| !line.trim().is_empty()) {
+    lines.push(serde_json::from_str::<Value>(line).map_err(|err| SketchFileError::Syntax(err.to_string()))?);
+  }
+  let version = lines
+    .first()
+    .and_then(|header| header["version"].as_u64())
+    .ok_or_else(|| SketchFileError::Invalid("missing version".to_string()))?;
+  if version > SESSION_LOG_VERSION {
+    return Err(SketchFileError::UnsupportedVersion(version));
+  }
+
+  let mut refs = HashMap::new();
+  for line in &lines[1..] {
+    if let Some(id) = line["inserted"].as_u64() {
+      refs.entry(id).or_insert_with(|| entities.create());
+    }
+  }
+  let log = read_records(&lines[1..], &refs);
+  if log.is_err() {
+    for ent in refs.values() {
+      let _ = entities.delete(*ent);
+    }
+  }
+  log
+}
+
+fn read_records(lines: &[Value], refs: &HashMap<u64, Entity>) -> Result<CommandLog, SketchFileError> {
+  let mut commands = vec![];
+  let mut insertions = vec![];
+  let mut history_events = vec![];
+  for line in lines {
+    let frame = number(&line["frame"])? as usize;
+    let time = || number(&line["time"]).map(Duration::from_secs_f64);
+    if !line["command"].is_null() {
+      commands.push(RecordedCommand {
+        frame,
+        time: time()?,
+        event: CommandEvent {
+          command: command_from_json(&line["command"], refs)?,
+          event_id: line["event_id"].as_u64().map(|id| id as usize),
+        },
+      });
+    } else if !line["inserted"].is_null() {
+      insertions.push(RecordedInsertion {
+        frame,
+        entity: reference(&line["inserted"], refs)?,
+        kind: kind_from_json(&line["kind"])?,
+      });
+    } else if !line["history"].is_null() {
+      history_events.push(RecordedHistoryEvent {
+        frame,
+        time: time()?,
+        event: history_event_from_json(&line["history"])?,
+      });
+    } else if line["unsupported"].is_null() {
+      return Err(SketchFileError::Invalid(format!("unknown line {}", line)));
+    }
+  }
+  Ok(CommandLog::from_records(commands, insertions, history_events))
+}
+
+fn is_by_history(command: &Command) -> bool {
+  matches!(
+    command,
+    Command::PointInsert(InsertPointEvent::InsertPointByHistory(_, _, _))
+      | Command::LineInsert(InsertLineEvent::InsertLineByHistory(_, _, _))
+      | Command::CircleInsert(InsertCircleEvent::InsertCircleByHistory(_, _, _))
+      | Command::ArcInsert(InsertArcEvent::InsertArcByHistory(_, _, _))
+      | Command::ConicInsert(InsertConicEvent::InsertConicByHistory(_, _, _))
+      | Command::PolygonInsert(InsertPolygonEvent::InsertPolygonByHistory(_, _, _))
+      | Command::VectorInsert(InsertVectorEvent::InsertVectorByHistory(_, _, _))
+      | Command::TextInsert(InsertTextEvent::InsertTextByHistory(_, _, _))
+      | Command::MeasurementInsert(InsertMeasurementEvent::InsertMeasurementByHistory(_, _))
+      | Command::SliderInsert(InsertSliderEvent::InsertSliderByHistory(_, _, _))
+      | Command::UnderlayInsert(InsertUnderlayEvent::InsertUnderlayByHistory(_, _, _))
+      | Command::FreehandInsert(InsertFreehandEvent::InsertFreehandByHistory(_, _, _))
+      | Command::Remove(RemoveEvent::RemoveByHistory(_))
+      | Command::Update(UpdateEvent::UpdatePointByHistory(_, _, _))
+      | Command::Update(UpdateEvent::UpdateLineByHistory(_, _, _))
+      | Command::Update(UpdateEvent::UpdateSliderByHistory(_, _, _))
+      | Command::Update(UpdateEvent::UpdateUnderlayByHistory(_, _, _))
+      | Command::Hide(HideEvent::HideByHistory(_))
+      | Command::Hide(HideEvent::UnhideByHistory(_))
+      | Command::Rename(RenameEvent::RenameByHistory(_, _))
+      | Command::Restyle(RestyleEvent::RestyleByHistory(_, _))
+  )
+}
+
+fn kind_to_str(kind: GeometryKind) -> &'static str {
+  match kind {
+    GeometryKind::Point => "point",
+    GeometryKind::Line => "line",
+    GeometryKind::Circle => "circle",
+    GeometryKind::Arc => "arc",
+    GeometryKind::Conic => "conic",
+    GeometryKind::Polygon => "polygon",
+    GeometryKind::Vector => "vector",
+    GeometryKind::Text => "text",
+    GeometryKind::Measurement => "measurement",
+    GeometryKind::Slider => "slider",
+    GeometryKind::Underlay => "underlay",
+    GeometryKind::Freehand => "freehand",
+  }
+}
+
+fn kind_from_json(value: &Value) -> Result<GeometryKind, SketchFileError> {
+  Ok(match value.as_str() {
+    Some("point") => GeometryKind::Point,
+    Some("line") => GeometryKind::Line,
+    Some("circle") => GeometryKind::Circle,
+    Some("arc") => GeometryKind::Arc,
+    Some("conic") => GeometryKind::Conic,
+    Some("polygon") => GeometryKind::Polygon,
+    Some("vector") => GeometryKind::Vector,
+    Some("text") => GeometryKind::Text,
+    Some("measurement") => GeometryKind::Measurement,
+    Some("slider") => GeometryKind::Slider,
+    Some("underlay") => GeometryKind::Underlay,
+    Some("freehand") => GeometryKind::Freehand,
+    _ => return Err(SketchFileError::Invalid(format!("unknown kind {}", value))),
+  })
+}
+
+fn history_event_from_json(value: &Value) -> Result<HistoryEvent, SketchFileError> {
+  Ok(match value["type"].as_str() {
+    Some("undo") => HistoryEvent::Undo,
+    Some("redo") => HistoryEvent::Redo,
+    Some("clear") => HistoryEvent::Clear,
+    Some("jump_to") => HistoryEvent::JumpTo(NodeId(number(&value["node"])? as usize)),
+    Some("toggle_viewport_history") => HistoryEvent::ToggleViewportHistory,
+    _ => return Err(SketchFileError::Invalid(format!("unknown history event {}", value))),
+  })
+}
+
+/// The commands a user can emit through the interface of the apps, `None` for the others
+fn command_to_json(command: &Command) -> Option<Value> {
+  let id = |ent: &Entity| json!(ent.id());
+  let unit = |kind: &str| json!({ "type": kind });
+  Some(match command {
+    Command::PointInsert(event) => match event {
+      InsertPointEvent::InsertPoint(sym_point) => {
+        json!({ "type": "insert_point", "symbolic": point_to_json(sym_point) })
+      }
+      InsertPointEvent::InsertPointAt(position) => json!({ "type": "insert_point_at", "at": [position.x, position.y] }),
+      InsertPointEvent::InsertMidPointFromSelection => unit("insert_mid_point_from_selection"),
+      InsertPointEvent::InsertIntersectionsFromSelection => unit("insert_intersections_from_selection"),
+      InsertPointEvent::InsertPointWithStyle(sym_point, point_style) => json!({
+        "type": "insert_point",
+        "symbolic": point_to_json(sym_point),
+        "style": point_style_to_json(point_style),
+      }),
+      InsertPointEvent::InsertPointByHistory(_, _, _) => return None,
+    },
+    Command::LineInsert(event) => match event {
+      InsertLineEvent::InsertLine(sym_line) => json!({ "type": "insert_line", "symbolic": line_to_json(sym_line) }),
+      InsertLineEvent::InsertParallelFromSelection => unit("insert_parallel_from_selection"),
+      InsertLineEvent::InsertPerpendicularFromSelection => unit("insert_perpendicular_from_selection"),
+      InsertLineEvent::InsertPerpendicularBisectorFromSelection => unit("insert_perpendicular_bisector_from_selection"),
+      InsertLineEvent::InsertLineWithStyle(sym_line, line_style) => json!({
+        "type": "insert_line",
+        "symbolic": line_to_json(sym_line),
+        "style": line_style_to_json(line_style),
+      }),
+      InsertLineEvent::InsertLineByHistory(_, _, _) => return None,
+    },
+    Command::CircleInsert(event) => match event {
+      InsertCircleEvent::InsertCircle(sym_circle) => {
+        json!({ "type": "insert_circle", "symbolic": circle_to_json(sym_circle) })
+      }
+      InsertCircleEvent::InsertCircumcircleFromSelection => unit("insert_circumcircle_from_selection"),
+      InsertCircleEvent::InsertCompassFromSelection => unit("insert_compass_from_selection"),
+      InsertCircleEvent::InsertCircleWithStyle(sym_circle, circle_style) => json!({
+        "type": "insert_circle",
+        "symbolic": circle_to_json(sym_circle),
+        "style": circle_style_to_json(circle_style),
+      }),
+      InsertCircleEvent::InsertCircleByHistory(_, _, _) => return None,
+    },
+    Command::TextInsert(InsertTextEvent::InsertText(sym_text)) => json!({
+      "type": "insert_text",
+      "text": sym_text.text,
+      "anchor": text_anchor_to_json(&sym_text.anchor),
+    }),
+    Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(stroke)) => {
+      json!({ "type": "insert_freehand", "samples": freehand_to_json(stroke) })
+    }
+    Command::Remove(event) => match event {
+      RemoveEvent::Remove(ent) => json!({ "type": "remove", "entity": id(ent) }),
+      RemoveEvent::RemoveSelected => unit("remove_selected"),
+      RemoveEvent::RemoveAll => unit("remove_all"),
+      RemoveEvent::RemoveByHistory(_) => return None,
+    },
+    Command::Update(event) => match event {
+      UpdateEvent::UpdatePoint(ent, before, after) => json!({
+        "type": "update_point",
+        "entity": id(ent),
+        "before": point_to_json(before),
+        "after": point_to_json(after),
+      }),
+      UpdateEvent::UpdatePointEnd(ent, before, after) => json!({
+        "type": "update_point_end",
+        "entity": id(ent),
+        "before": point_to_json(before),
+        "after": point_to_json(after),
+      }),
+      _ => return None,
+    },
+    Command::Select(event) => match event {
+      SelectEvent::Select(ent) => json!({ "type": "select", "entity": id(ent) }),
+      SelectEvent::Deselect(ent) => json!({ "type": "deselect", "entity": id(ent) }),
+      SelectEvent::SelectAll => unit("select_all"),
+      SelectEvent::DeselectAll => unit("deselect_all"),
+    },
+    Command::Hide(event) => match event {
+      HideEvent::Hide(ent) => json!({ "type": "hide", "entity": id(ent) }),
+      HideEvent::Unhide(ent) => json!({ "type": "unhide", "entity": id(ent) }),
+      HideEvent::HideSelected => unit("hide_selected"),
+      HideEvent::HideAncestorsOfSelected => unit("hide_ancestors_of_selected"),
+      HideEvent::UnhideAll => unit("unhide_all"),
+      _ => return None,
+    },
+    Command::Rename(event) => match event {
+      RenameEvent::Rename(ent, name) => json!({ "type": "rename", "entity": id(ent), "name": name }),
+      RenameEvent::RenameSelected(name) => json!({ "type": "rename_selected", "name": name }),
+      RenameEvent::Unname(ent) => json!({ "type": "unname", "entity": id(ent) }),
+      RenameEvent::RenameByHistory(_, _) => return None,
+    },
+    Command::ToggleTheme => unit("toggle_theme"),
+    Command::CopySelected => unit("copy_selected"),
+    Command::Paste => unit("paste"),
+    Command::DuplicateSelection => unit("duplicate_selection"),
+    Command::Constrain(constraint) => json!({ "type": "constrain", "constraint": constraint_to_json(constraint) }),
+    Command::RemoveConstraint(constraint) => {
+      json!({ "type": "remove_constraint", "constraint": constraint_to_json(constraint) })
+    }
+    Command::DefineScalar(definition) => json!({ "type": "define_scalar", "definition": definition }),
+    Command::RemoveScalar(name) => json!({ "type": "remove_scalar", "name": name }),
+    Command::DefineMacro { name, inputs, outputs } => json!({
+      "type": "define_macro",
+      "name": name,
+      "inputs": inputs.iter().map(id).collect::<Vec<_>>(),
+      "outputs": outputs.iter().map(id).collect::<Vec<_>>(),
+    }),
+    Command::DefineMacroFromSelection(name) => json!({ "type": "define_macro_from_selection", "name": name }),
+    Command::ApplyMacro(macro_id, inputs) => json!({
+      "type": "apply_macro",
+      "macro": macro_id.0,
+      "inputs": inputs.iter().map(id).collect::<Vec<_>>(),
+    }),
+    Command::SuspendSolve => unit("suspend_solve"),
+    Command::ResumeSolve => unit("resume_solve"),
+    Command::ClearAll => unit("clear_all"),
+    Command::BeginTransaction(label) => json!({ "type": "begin_transaction", "label": label }),
+    Command::EndTransaction => unit("end_transaction"),
+    Command::SaveSketch(path) => json!({ "type": "save_sketch", "path": path.to_string_lossy() }),
+    Command::LoadSketch(path) => json!({ "type": "load_sketch", "path": path.to_string_lossy() }),
+    _ => return None,
+  })
+}
+
+fn command_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<Command, SketchFileError> {
+  let entity = || reference(&value["entity"], refs);
+  let string = |key: &str| {
+    value[key]
+      .as_str()
+      .map(|string| string.to_string())
+      .ok_or_else(|| SketchFileError::Invalid(format!("expected a text, found {}", value[key])))
+  };
+  let entities = |key: &str| {
+    value[key]
+      .as_array()
+      .ok_or_else(|| SketchFileError::Invalid(format!("expected ids, found {}", value[key])))?
+      .iter()
+      .map(|id| reference(id, refs))
+      .collect::<Result<Vec<_>, _>>()
+  };
+  let has_style = !value["style"].is_null();
+  Ok(match value["type"].as_str() {
+    Some("insert_point") => {
+      let sym_point = point_from_json(&value["symbolic"], refs)?;
+      Command::PointInsert(if has_style {
+        InsertPointEvent::InsertPointWithStyle(sym_point, point_style_from_json(&value["style"])?)
+      } else {
+        InsertPointEvent::InsertPoint(sym_point)
+      })
+    }
+    Some("insert_point_at") => Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![
+      number(&value["at"][0])?,
+      number(&value["at"][1])?
+    ])),
+    Some("insert_mid_point_from_selection") => Command::PointInsert(InsertPointEvent::InsertMidPointFromSelection),
+    Some("insert_intersections_from_selection") => {
+      Command::PointInsert(InsertPointEvent::InsertIntersectionsFromSelection)
+    }
+    Some("insert_line") => {
+      let sym_line = line_from_json(&value["symbolic"], refs)?;
+      Command::LineInsert(if has_style {
+        InsertLineEvent::InsertLineWithStyle(sym_line, line_style_from_json(&value["style"])?)
+      } else {
+        InsertLineEvent::InsertLine(sym_line)
+      })
+    }
+    Some("insert_parallel_from_selection") => Command::LineInsert(InsertLineEvent::InsertParallelFromSelection),
+    Some("insert_perpendicular_from_selection") => {
+      Command::LineInsert(InsertLineEvent::InsertPerpendicularFromSelection)
+    }
+    Some("insert_perpendicular_bisector_from_selection") => {
+      Command::LineInsert(InsertLineEvent::InsertPerpendicularBisectorFromSelection)
+    }
+    Some("insert_circle") => {
+      let sym_circle = circle_from_json(&value["symbolic"], refs)?;
+      Command::CircleInsert(if has_style {
+        InsertCircleEvent::InsertCircleWithStyle(sym_circle, circle_style_from_json(&value["style"])?)
+      } else {
+        InsertCircleEvent::InsertCircle(sym_circle)
+      })
+    }
+    Some("insert_circumcircle_from_selection") => {
+      Command::CircleInsert(InsertCircleEvent::InsertCircumcircleFromSelection)
+    }
+    Some("insert_compass_from_selection") => Command::CircleInsert(InsertCircleEvent::InsertCompassFromSelection),
+    Some("insert_text") => Command::TextInsert(InsertTextEvent::InsertText(SymbolicText {
+      text: string("text")?,
+      anchor: text_anchor_from_json(&value["anchor"], refs)?,
+    })),
+    Some("insert_freehand") => Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(freehand_from_json(
+      &value["samples"],
+    )?)),
+    Some("remove") => Command::Remove(RemoveEvent::Remove(entity()?)),
+    Some("remove_selected") => Command::Remove(RemoveEvent::RemoveSelected),
+    Some("remove_all") => Command::Remove(RemoveEvent::RemoveAll),
+    Some("update_point") => Command::Update(UpdateEvent::UpdatePoint(
+      entity()?,
+      point_from_json(&value["before"], refs)?,
+      point_from_json(&value["after"], refs)?,
+    )),
+    Some("update_point_end") => Command::Update(UpdateEvent::UpdatePointEnd(
+      entity()?,
+      point_from_json(&value["before"], refs)?,
+      point_from_json(&value["after"], refs)?,
+    )),
+    Some("select") => Command::Select(SelectEvent::Select(entity()?)),
+    Some("deselect") => Command::Select(SelectEvent::Deselect(entity()?)),
+    Some("select_all") => Command::Select(SelectEvent::SelectAll),
+    Some("deselect_all") => Command::Select(SelectEvent::DeselectAll),
+    Some("hide") => Command::Hide(HideEvent::Hide(entity()?)),
+    Some("unhide") => Command::Hide(HideEvent::Unhide(entity()?)),
+    Some("hide_selected") => Command::Hide(HideEvent::HideSelected),
+    Some("hide_ancestors_of_selected") => Command::Hide(HideEvent::HideAncestorsOfSelected),
+    Some("unhide_all") => Command::Hide(HideEvent::UnhideAll),
+    Some("rename") => Command::Rename(RenameEvent::Rename(entity()?, string("name")?)),
+    Some("rename_selected") => Command::Rename(RenameEvent::RenameSelected(string("name")?)),
+    Some("unname") => Command::Rename(RenameEvent::Unname(entity()?)),
+    Some("toggle_theme") => Command::ToggleTheme,
+    Some("copy_selected") => Command::CopySelected,
+    Some("paste") => Command::Paste,
+    Some("duplicate_selection") => Command::DuplicateSelection,
+    Some("constrain") => Command::Constrain(constraint_from_json(&value["constraint"], refs)?),
+    Some("remove_constraint") => Command::RemoveConstraint(constraint_from_json(&value["constraint"], refs)?),
+    Some("define_scalar") => Command::DefineScalar(string("definition")?),
+    Some("remove_scalar") => Command::RemoveScalar(string("name")?),
+    Some("define_macro") => Command::DefineMacro {
+      name: string("name")?,
+      inputs: entities("inputs")?,
+      outputs: entities("outputs")?,
+    },
+    Some("define_macro_from_selection") => Command::DefineMacroFromSelection(string("name")?),
+    Some("apply_macro") => Command::ApplyMacro(MacroId(number(&value["macro"])? as usize), entities("inputs")?),
+    Some("suspend_solve") => Command::SuspendSolve,
+    Some("resume_solve") => Command::ResumeSolve,
+    Some("clear_all") => Command::ClearAll,
+    Some("begin_transaction") => Command::BeginTransaction(string("label")?),
+    Some("end_transaction") => Command::EndTransaction,
+    Some("save_sketch") => Command::SaveSketch(string("path")?.into()),
+    Some("load_sketch") => Command::LoadSketch(string("path")?.into()),
+    _ => return Err(SketchFileError::Invalid(format!("unknown command {}", value["type"]))),
+  })
+}
@@ -0,0 +1,856 @@
+use crate::{
+  components::{freehand::*, measurements::*, sliders::*, styles::*, symbolics::*, underlays::*},
+  math::*,
+  resources::{AngleConstraint, Constraint},
+  utilities::*,
+};
+use serde_json::{json, Value};
+use specs::{prelude::*, world::EntitiesRes};
+use std::{collections::HashMap, path::PathBuf};
+
+/// Version written in every sketch file. Files of a newer version are refused. Version 2 added
+/// the arcs, conics, polygons, vectors, measurements, sliders and underlays
+pub const SKETCH_FILE_VERSION: u64 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SketchFileError {
+  Io(String),              // Message of the failed read or write
+  Syntax(String),          // Message of the JSON parser
+  UnsupportedVersion(u64), // Version of the file
+  Invalid(String),         // What is wrong with the content
+  DuplicateId(u64),        // Id of more than one element
+  UnknownReference(u64),   // Id of no element
+}
+
+/// The content of a sketch file: the geometries of every kind with their styles, which ones are
+/// hidden, the names, the constraints and the scalars. The geometries are in the order they got
+/// created so that they come after the ones they depend on
+#[derive(Debug, Clone, Default)]
+pub struct SketchDocument {
+  pub geometries: Vec<(Entity, Geometry)>,
+  pub hidden: Vec<Entity>,
+  pub names: Vec<(Entity, String)>,
+  pub angle_constraints: Vec<AngleConstraint>,
+  pub constraints: Vec<Constraint>,
+  pub scalars: Vec<(ScalarId, String, String)>, // Id, name and expression
+}
+
+/// The document as versioned JSON. Entities are written as their id, which the other elements
+/// refer to
+pub fn write_sketch(document: &SketchDocument) -> String {
+  let name = |ent: &Entity| {
+    document
+      .names
+      .iter()
+      .find(|(named, _)| named == ent)
+      .map(|(_, name)| json!(name))
+      .unwrap_or(Value::Null)
+  };
+  let mut elements = document
+    .geometries
+    .iter()
+    .map(|(ent, geometry)| {
+      let mut element = geometry_to_json(geometry);
+      element["id"] = json!(ent.id());
+      element["hidden"] = json!(document.hidden.contains(ent));
+      element["name"] = name(ent);
+      (ent.id(), element)
+    })
+    .collect::<Vec<_>>();
+  elements.sort_by_key(|(id, _)| *id);
+  let angle_constraints = document
+    .angle_constraints
+    .iter()
+    .map(|constraint| {
+      json!({
+        "line_a": constraint.line_a.id(),
+        "line_b": constraint.line_b.id(),
+        "radians": constraint.radians,
+      })
+    })
+    .collect::<Vec<_>>();
+  let constraints = document.constraints.iter().map(constraint_to_json).collect::<Vec<_>>();
+  let scalars = document
+    .scalars
+    .iter()
+    .map(|(id, name, source)| json!({ "id": id.0, "name": name, "expression": source }))
+    .collect::<Vec<_>>();
+  let value = json!({
+    "version": SKETCH_FILE_VERSION,
+    "elements": elements.into_iter().map(|(_, element)| element).collect::<Vec<_>>(),
+    "angle_constraints": angle_constraints,
+    "constraints": constraints,
+    "scalars": scalars,
+  });
+  serde_json::to_string_pretty(&value).unwrap()
+}
+
+/// Reads a document written by `write_sketch`. Every element gets a new entity created in
+/// `entities`, the entities are deleted again when the file turns out to be invalid
+pub fn read_sketch(text: &str, entities: &EntitiesRes) -> Result<SketchDocument, SketchFileError> {
+  let value: Value = serde_json::from_str(text).map_err(|err| SketchFileError::Syntax(err.to_string()))?;
+  let version = value["version"]
+    .as_u64()
+    .ok_or_else(|| SketchFileError::Invalid("missing version".to_string()))?;
+  if version > SKETCH_FILE_VERSION {
+    return Err(SketchFileError::UnsupportedVersion(version));
+  }
+  let elements = value["elements"]
+    .as_array()
+    .ok_or_else(|| SketchFileError::Invalid("missing elements".to_string()))?;
+  let mut ids = vec![];
+  for element in elements {
+    let id = element["id"]
+      .as_u64()
+      .ok_or_else(|| SketchFileError::Invalid("element without an id".to_string()))?;
+    if ids.contains(&id) {
+      return Err(SketchFileError::DuplicateId(id));
+    }
+    ids.push(id);
+  }
+
+  let refs = ids.iter().map(|id| (*id, entities.create())).collect::<HashMap<_, _>>();
+  let document = read_document(&value, elements, &refs);
+  if document.is_err() {
+    for ent in refs.values() {
+      let _ = entities.delete(*ent);
+    }
+  }
+  document
+}
+
+fn read_document(
+  value: &Value,
+  elements: &[Value],
+  refs: &HashMap<u64, Entity>,
+) -> Result<SketchDocument, SketchFileError> {
+  let mut document = SketchDocument::default();
+  for element in elements {
+    let ent = refs[&element["id"].as_u64().unwrap()];
+    document.geometries.push((ent, geometry_from_json(element, refs)?));
+    if element["hidden"].as_bool().unwrap_or(false) {
+      document.hidden.push(ent);
+    }
+    if let Some(name) = element["name"].as_str() {
+      document.names.push((ent, name.to_string()));
+    }
+  }
+  if let Some(angle_constraints) = value["angle_constraints"].as_array() {
+    for constraint in angle_constraints {
+      let line = |key: &str| reference(&constraint[key], refs);
+      document.angle_constraints.push(AngleConstraint {
+        line_a: line("line_a")?,
+        line_b: line("line_b")?,
+        radians: number(&constraint["radians"])?,
+      });
+    }
+  }
+  if let Some(constraints) = value["constraints"].as_array() {
+    for constraint in constraints {
+      document.constraints.push(constraint_from_json(constraint, refs)?);
+    }
+  }
+  if let Some(scalars) = value["scalars"].as_array() {
+    for scalar in scalars {
+      let (id, name, source) = match (
+        scalar["id"].as_u64(),
+        scalar["name"].as_str(),
+        scalar["expression"].as_str(),
+      ) {
+        (Some(id), Some(name), Some(source)) => (ScalarId(id as usize), name, source),
+        _ => return Err(SketchFileError::Invalid(format!("expected a scalar, found {}", scalar))),
+      };
+      parse_expression(source).map_err(|err| SketchFileError::Invalid(format!("{:?} in {}", err, source)))?;
+      document.scalars.push((id, name.to_string(), source.to_string()));
+    }
+  }
+  Ok(document)
+}
+
+/// The element of a geometry, without its id, whether it is hidden and its name
+fn geometry_to_json(geometry: &Geometry) -> Value {
+  match geometry {
+    Geometry::Point(sym_point, point_style) => json!({
+      "kind": "point",
+      "symbolic": point_to_json(sym_point),
+      "style": point_style_to_json(point_style),
+    }),
+    Geometry::Line(sym_line, line_style) => json!({
+      "kind": "line",
+      "symbolic": line_to_json(sym_line),
+      "style": line_style_to_json(line_style),
+    }),
+    Geometry::Circle(sym_circle, circle_style) => json!({
+      "kind": "circle",
+      "symbolic": circle_to_json(sym_circle),
+      "style": circle_style_to_json(circle_style),
+    }),
+    Geometry::Arc(sym_arc, arc_style) => json!({
+      "kind": "arc",
+      "symbolic": arc_to_json(sym_arc),
+      "style": arc_style_to_json(arc_style),
+    }),
+    Geometry::Conic(sym_conic, conic_style) => json!({
+      "kind": "conic",
+      "symbolic": conic_to_json(sym_conic),
+      "style": conic_style_to_json(conic_style),
+    }),
+    Geometry::Polygon(sym_polygon, polygon_style) => json!({
+      "kind": "polygon",
+      "symbolic": sym_polygon.0.iter().map(|ent| ent.id()).collect::<Vec<_>>(),
+      "style": polygon_style_to_json(polygon_style),
+    }),
+    Geometry::Vector(sym_vector, vector_style) => json!({
+      "kind": "vector",
+      "symbolic": [sym_vector.0.id(), sym_vector.1.id()],
+      "style": vector_style_to_json(vector_style),
+    }),
+    Geometry::Text(sym_text, text_style) => json!({
+      "kind": "text",
+      "text": sym_text.text,
+      "symbolic": text_anchor_to_json(&sym_text.anchor),
+      "style": text_style_to_json(text_style),
+    }),
+    Geometry::Measurement(measurement) => json!({
+      "kind": "measurement",
+      "symbolic": symbolic(measurement.kind(), measurement.targets().iter().map(|ent| json!(ent.id())).collect()),
+    }),
+    Geometry::Slider(slider, slider_value) => json!({
+      "kind": "slider",
+      "symbolic": {
+        "position": [slider.position.0.x, slider.position.0.y],
+        "min": slider.min,
+        "max": slider.max,
+      },
+      "value": slider_value.0,
+    }),
+    Geometry::Underlay(underlay, placement) => json!({
+      "kind": "underlay",
+      "symbolic": {
+        "path": underlay.path.to_string_lossy(),
+        "size": [underlay.size.x, underlay.size.y],
+      },
+      "placement": {
+        "position": [placement.position.0.x, placement.position.0.y],
+        "scale": placement.scale,
+        "opacity": placement.opacity,
+      },
+    }),
+    Geometry::Freehand(stroke, line_style) => json!({
+      "kind": "freehand",
+      "symbolic": freehand_to_json(stroke),
+      "style": line_style_to_json(line_style),
+    }),
+  }
+}
+
+fn geometry_from_json(element: &Value, refs: &HashMap<u64, Entity>) -> Result<Geometry, SketchFileError> {
+  let symbolic = &element["symbolic"];
+  let style = &element["style"];
+  let position = |value: &Value| -> Result<VirtualPosition, SketchFileError> {
+    Ok(VirtualPosition(vec2![number(&value[0])?, number(&value[1])?]))
+  };
+  let references = |value: &Value| -> Result<Vec<Entity>, SketchFileError> {
+    value
+      .as_array()
+      .ok_or_else(|| SketchFileError::Invalid(format!("expected ids, found {}", value)))?
+      .iter()
+      .map(|id| reference(id, refs))
+      .collect()
+  };
+  Ok(match element["kind"].as_str() {
+    Some("point") => Geometry::Point(point_from_json(symbolic, refs)?, point_style_from_json(style)?),
+    Some("line") => Geometry::Line(line_from_json(symbolic, refs)?, line_style_from_json(style)?),
+    Some("circle") => Geometry::Circle(circle_from_json(symbolic, refs)?, circle_style_from_json(style)?),
+    Some("arc") => Geometry::Arc(arc_from_json(symbolic, refs)?, arc_style_from_json(style)?),
+    Some("conic") => Geometry::Conic(conic_from_json(symbolic, refs)?, conic_style_from_json(style)?),
+    Some("polygon") => Geometry::Polygon(SymbolicPolygon(references(symbolic)?), polygon_style_from_json(style)?),
+    Some("vector") => match references(symbolic)?.as_slice() {
+      [from, to] => Geometry::Vector(SymbolicVector(*from, *to), vector_style_from_json(style)?),
+      _ => {
+        return Err(SketchFileError::Invalid(format!(
+          "expected a vector, found {}",
+          symbolic
+        )))
+      }
+    },
+    Some("text") => {
+      let text = element["text"]
+        .as_str()
+        .ok_or_else(|| SketchFileError::Invalid(format!("expected a text, found {}", element["text"])))?;
+      let sym_text = SymbolicText {
+        text: text.to_string(),
+        anchor: text_anchor_from_json(symbolic, refs)?,
+      };
+      Geometry::Text(sym_text, text_style_from_json(style)?)
+    }
+    Some("measurement") => Geometry::Measurement(measurement_from_json(symbolic, refs)?),
+    Some("slider") => Geometry::Slider(
+      Slider {
+        position: position(&symbolic["position"])?,
+        min: number(&symbolic["min"])?,
+        max: number(&symbolic["max"])?,
+      },
+      SliderValue(number(&element["value"])?),
+    ),
+    Some("underlay") => {
+      let path = symbolic["path"]
+        .as_str()
+        .ok_or_else(|| SketchFileError::Invalid(format!("expected a path, found {}", symbolic["path"])))?;
+      let placement = &element["placement"];
+      Geometry::Underlay(
+        ImageUnderlay {
+          path: PathBuf::from(path),
+          size: position(&symbolic["size"])?.0,
+        },
+        UnderlayPlacement {
+          position: position(&placement["position"])?,
+          scale: number(&placement["scale"])?,
+          opacity: number(&placement["opacity"])?,
+        },
+      )
+    }
+    Some("freehand") => Geometry::Freehand(freehand_from_json(symbolic)?, line_style_from_json(style)?),
+    _ => return Err(SketchFileError::Invalid(format!("unknown kind {}", element["kind"]))),
+  })
+}
+
+pub(super) fn constraint_to_json(constraint: &Constraint) -> Value {
+  let (a, b) = constraint.entities();
+  match constraint {
+    Constraint::FixedDistance(_, _, distance) => {
+      json!({ "kind": "fixed_distance", "a": a.id(), "b": b.id(), "value": distance })
+    }
+    Constraint::FixedAngle(_, _, radians) => {
+      json!({ "kind": "fixed_angle", "a": a.id(), "b": b.id(), "value": radians })
+    }
+    Constraint::Coincident(_, _) => json!({ "kind": "coincident", "a": a.id(), "b": b.id() }),
+    Constraint::Parallel(_, _) => json!({ "kind": "parallel", "a": a.id(), "b": b.id() }),
+  }
+}
+
+pub(super) fn constraint_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<Constraint, SketchFileError> {
+  let (a, b) = (reference(&value["a"], refs)?, reference(&value["b"], refs)?);
+  match value["kind"].as_str() {
+    Some("fixed_distance") => Ok(Constraint::FixedDistance(a, b, number(&value["value"])?)),
+    Some("fixed_angle") => Ok(Constraint::FixedAngle(a, b, number(&value["value"])?)),
+    Some("coincident") => Ok(Constraint::Coincident(a, b)),
+    Some("parallel") => Ok(Constraint::Parallel(a, b)),
+    _ => Err(SketchFileError::Invalid(format!(
+      "unknown constraint {}",
+      value["kind"]
+    ))),
+  }
+}
+
+pub(super) fn number(value: &Value) -> Result<f64, SketchFileError> {
+  value
+    .as_f64()
+    .ok_or_else(|| SketchFileError::Invalid(format!("expected a number, found {}", value)))
+}
+
+pub(super) fn reference(value: &Value, refs: &HashMap<u64, Entity>) -> Result<Entity, SketchFileError> {
+  let id = value
+    .as_u64()
+    .ok_or_else(|| SketchFileError::Invalid(format!("expected an id, found {}", value)))?;
+  refs.get(&id).cloned().ok_or(SketchFileError::UnknownReference(id))
+}
+
+/// The arguments of a symbolic geometry, written as `{ "type": kind, "args": [...] }`
+struct Args<'a> {
+  kind: &'a str,
+  args: &'a [Value],
+  refs: &'a HashMap<u64, Entity>,
+}
+
+impl<'a> Args<'a> {
+  fn new(value: &'a Value, refs: &'a HashMap<u64, Entity>) -> Result<Self, SketchFileError> {
+    match (value["type"].as_str(), value["args"].as_array()) {
+      (Some(kind), Some(args)) => Ok(Self { kind, args, refs }),
+      _ => Err(SketchFileError::Invalid(format!(
+        "expected a symbolic geometry, found {}",
+        value
+      ))),
+    }
+  }
+
+  fn get(&self, i: usize) -> Result<&Value, SketchFileError> {
+    self
+      .args
+      .get(i)
+      .ok_or_else(|| SketchFileError::Invalid(format!("missing argument {} of {}", i, self.kind)))
+  }
+
+  fn number(&self, i: usize) -> Result<f64, SketchFileError> {
+    number(self.get(i)?)
+  }
+
+  fn entity(&self, i: usize) -> Result<Entity, SketchFileError> {
+    reference(self.get(i)?, self.refs)
+  }
+
+  fn scalar(&self, i: usize) -> Result<ScalarId, SketchFileError> {
+    Ok(ScalarId(self.number(i)? as usize))
+  }
+
+  fn position(&self) -> Result<VirtualPosition, SketchFileError> {
+    Ok(VirtualPosition(vec2![self.number(0)?, self.number(1)?]))
+  }
+
+  fn unknown<T>(&self) -> Result<T, SketchFileError> {
+    Err(SketchFileError::Invalid(format!("unknown type {}", self.kind)))
+  }
+}
+
+fn symbolic(kind: &str, args: Vec<Value>) -> Value {
+  json!({ "type": kind, "args": args })
+}
+
+pub(super) fn point_to_json(sym_point: &SymbolicPoint) -> Value {
+  let id = |ent: Entity| json!(ent.id());
+  let intersect_id = |id: CircleIntersectId| match id {
+    CircleIntersectId::First => json!(0),
+    CircleIntersectId::Second => json!(1),
+  };
+  let args = match *sym_point {
+    SymbolicPoint::Fixed(pos) | SymbolicPoint::Free(pos) => vec![json!(pos.0.x), json!(pos.0.y)],
+    SymbolicPoint::MidPoint(p1, p2) => vec![id(p1), id(p2)],
+    SymbolicPoint::Ratio(p1, p2, ratio) => vec![id(p1), id(p2), json!(ratio)],
+    SymbolicPoint::OnLine(l, t) => vec![id(l), json!(t.0)],
+    SymbolicPoint::LineLineIntersect(l1, l2) => vec![id(l1), id(l2)],
+    SymbolicPoint::OnCircle(c, theta) => vec![id(c), json!(theta)],
+    SymbolicPoint::OnConic(c, t) => vec![id(c), json!(t)],
+    SymbolicPoint::CircleLineIntersect(c, l, i) => vec![id(c), id(l), intersect_id(i)],
+    SymbolicPoint::CircleCircleIntersect(c1, c2, i) => vec![id(c1), id(c2), intersect_id(i)],
+    SymbolicPoint::ArcLineIntersect(a, l, i) => vec![id(a), id(l), intersect_id(i)],
+    SymbolicPoint::ArcCircleIntersect(a, c, i) => vec![id(a), id(c), intersect_id(i)],
+    SymbolicPoint::PointReflection(source, center) => vec![id(source), id(center)],
+    SymbolicPoint::Reflect(p, mirror) => vec![id(p), id(mirror)],
+    SymbolicPoint::Rotate(p, center, radians) => vec![id(p), id(center), json!(radians)],
+    SymbolicPoint::RotateByScalar(p, center, scalar) => vec![id(p), id(center), json!(scalar.0)],
+    SymbolicPoint::Translate(p, vector) => vec![id(p), json!(vector.0.x), json!(vector.0.y)],
+  };
+  symbolic(sym_point.kind(), args)
+}
+
+pub(super) fn point_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<SymbolicPoint, SketchFileError> {
+  let args = Args::new(value, refs)?;
+  let intersect_id = |i: usize| match args.number(i)? as u64 {
+    0 => Ok(CircleIntersectId::First),
+    1 => Ok(CircleIntersectId::Second),
+    id => Err(SketchFileError::Invalid(format!("unknown intersection {}", id))),
+  };
+  Ok(match args.kind {
+    "Fixed" => SymbolicPoint::Fixed(args.position()?),
+    "Free" => SymbolicPoint::Free(args.position()?),
+    "MidPoint" => SymbolicPoint::MidPoint(args.entity(0)?, args.entity(1)?),
+    "Ratio" => SymbolicPoint::Ratio(args.entity(0)?, args.entity(1)?, args.number(2)?),
+    "OnLine" => SymbolicPoint::OnLine(args.entity(0)?, VirtualScalar(args.number(1)?)),
+    "LineLineIntersect" => SymbolicPoint::LineLineIntersect(args.entity(0)?, args.entity(1)?),
+    "OnCircle" => SymbolicPoint::OnCircle(args.entity(0)?, args.number(1)?),
+    "OnConic" => SymbolicPoint::OnConic(args.entity(0)?, args.number(1)?),
+    "CircleLineIntersect" => SymbolicPoint::CircleLineIntersect(args.entity(0)?, args.entity(1)?, intersect_id(2)?),
+    "CircleCircleIntersect" => SymbolicPoint::CircleCircleIntersect(args.entity(0)?, args.entity(1)?, intersect_id(2)?),
+    "ArcLineIntersect" => SymbolicPoint::ArcLineIntersect(args.entity(0)?, args.entity(1)?, intersect_id(2)?),
+    "ArcCircleIntersect" => SymbolicPoint::ArcCircleIntersect(args.entity(0)?, args.entity(1)?, intersect_id(2)?),
+    "PointReflection" => SymbolicPoint::PointReflection(args.entity(0)?, args.entity(1)?),
+    "Reflect" => SymbolicPoint::Reflect(args.entity(0)?, args.entity(1)?),
+    "Rotate" => SymbolicPoint::Rotate(args.entity(0)?, args.entity(1)?, args.number(2)?),
+    "RotateByScalar" => SymbolicPoint::RotateByScalar(args.entity(0)?, args.entity(1)?, args.scalar(2)?),
+    "Translate" => SymbolicPoint::Translate(
+      args.entity(0)?,
+      VirtualPosition(vec2![args.number(1)?, args.number(2)?]),
+    ),
+    _ => return args.unknown(),
+  })
+}
+
+fn tangent_kind_to_str(kind: TangentKind) -> &'static str {
+  match kind {
+    TangentKind::ExternalUpper => "ExternalUpper",
+    TangentKind::ExternalLower => "ExternalLower",
+    TangentKind::InternalUpper => "InternalUpper",
+    TangentKind::InternalLower => "InternalLower",
+  }
+}
+
+pub(super) fn line_to_json(sym_line: &SymbolicLine) -> Value {
+  let id = |ent: Entity| json!(ent.id());
+  let args = match *sym_line {
+    SymbolicLine::Straight(p1, p2)
+    | SymbolicLine::Ray(p1, p2)
+    | SymbolicLine::Segment(p1, p2)
+    | SymbolicLine::PerpendicularBisector(p1, p2) => vec![id(p1), id(p2)],
+    SymbolicLine::Parallel(l, p) | SymbolicLine::Perpendicular(l, p) => vec![id(l), id(p)],
+    SymbolicLine::Reflect(l, mirror) => vec![id(l), id(mirror)],
+    SymbolicLine::CommonTangent(c1, c2, kind) => vec![id(c1), id(c2), json!(tangent_kind_to_str(kind))],
+    SymbolicLine::Tangent(c, p, index) => vec![id(c), id(p), json!(index)],
+  };
+  symbolic(sym_line.kind(), args)
+}
+
+pub(super) fn line_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<SymbolicLine, SketchFileError> {
+  let args = Args::new(value, refs)?;
+  let tangent_kind = |i: usize| match args.get(i)?.as_str() {
+    Some("ExternalUpper") => Ok(TangentKind::ExternalUpper),
+    Some("ExternalLower") => Ok(TangentKind::ExternalLower),
+    Some("InternalUpper") => Ok(TangentKind::InternalUpper),
+    Some("InternalLower") => Ok(TangentKind::InternalLower),
+    _ => Err(SketchFileError::Invalid(format!("unknown tangent {}", args.args[i]))),
+  };
+  Ok(match args.kind {
+    "Straight" => SymbolicLine::Straight(args.entity(0)?, args.entity(1)?),
+    "Ray" => SymbolicLine::Ray(args.entity(0)?, args.entity(1)?),
+    "Segment" => SymbolicLine::Segment(args.entity(0)?, args.entity(1)?),
+    "Parallel" => SymbolicLine::Parallel(args.entity(0)?, args.entity(1)?),
+    "Perpendicular" => SymbolicLine::Perpendicular(args.entity(0)?, args.entity(1)?),
+    "CommonTangent" => SymbolicLine::CommonTangent(args.entity(0)?, args.entity(1)?, tangent_kind(2)?),
+    "Tangent" => SymbolicLine::Tangent(args.entity(0)?, args.entity(1)?, args.number(2)? as u8),
+    "PerpendicularBisector" => SymbolicLine::PerpendicularBisector(args.entity(0)?, args.entity(1)?),
+    "Reflect" => SymbolicLine::Reflect(args.entity(0)?, args.entity(1)?),
+    _ => return args.unknown(),
+  })
+}
+
+pub(super) fn circle_to_json(sym_circle: &SymbolicCircle) -> Value {
+  let id = |ent: Entity| json!(ent.id());
+  let args = match *sym_circle {
+    SymbolicCircle::CenterRadius(p1, p2) => vec![id(p1), id(p2)],
+    SymbolicCircle::EqualRadius(p, c) => vec![id(p), id(c)],
+    SymbolicCircle::CompassRadius(p, p1, p2) => vec![id(p), id(p1), id(p2)],
+    SymbolicCircle::ScalarRadius(p, scalar) => vec![id(p), json!(scalar.0)],
+    SymbolicCircle::ThreePoints(p1, p2, p3) => vec![id(p1), id(p2), id(p3)],
+    SymbolicCircle::Reflect(c, mirror) => vec![id(c), id(mirror)],
+  };
+  symbolic(sym_circle.kind(), args)
+}
+
+pub(super) fn circle_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<SymbolicCircle, SketchFileError> {
+  let args = Args::new(value, refs)?;
+  Ok(match args.kind {
+    "CenterRadius" => SymbolicCircle::CenterRadius(args.entity(0)?, args.entity(1)?),
+    "EqualRadius" => SymbolicCircle::EqualRadius(args.entity(0)?, args.entity(1)?),
+    "CompassRadius" => SymbolicCircle::CompassRadius(args.entity(0)?, args.entity(1)?, args.entity(2)?),
+    "ScalarRadius" => SymbolicCircle::ScalarRadius(args.entity(0)?, args.scalar(1)?),
+    "ThreePoints" => SymbolicCircle::ThreePoints(args.entity(0)?, args.entity(1)?, args.entity(2)?),
+    "Reflect" => SymbolicCircle::Reflect(args.entity(0)?, args.entity(1)?),
+    _ => return args.unknown(),
+  })
+}
+
+pub(super) fn arc_to_json(sym_arc: &SymbolicArc) -> Value {
+  symbolic(
+    sym_arc.kind(),
+    sym_arc.points().iter().map(|ent| json!(ent.id())).collect(),
+  )
+}
+
+pub(super) fn arc_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<SymbolicArc, SketchFileError> {
+  let args = Args::new(value, refs)?;
+  Ok(match args.kind {
+    "ThreePoint" => SymbolicArc::ThreePoint(args.entity(0)?, args.entity(1)?, args.entity(2)?),
+    "CenterTwoPoint" => SymbolicArc::CenterTwoPoint(args.entity(0)?, args.entity(1)?, args.entity(2)?),
+    _ => return args.unknown(),
+  })
+}
+
+pub(super) fn conic_to_json(sym_conic: &SymbolicConic) -> Value {
+  symbolic(
+    sym_conic.kind(),
+    sym_conic.points().iter().map(|ent| json!(ent.id())).collect(),
+  )
+}
+
+pub(super) fn conic_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<SymbolicConic, SketchFileError> {
+  let args = Args::new(value, refs)?;
+  Ok(match args.kind {
+    "Ellipse" => SymbolicConic::Ellipse(args.entity(0)?, args.entity(1)?, args.entity(2)?),
+    "FivePoints" => SymbolicConic::FivePoints(
+      args.entity(0)?,
+      args.entity(1)?,
+      args.entity(2)?,
+      args.entity(3)?,
+      args.entity(4)?,
+    ),
+    _ => return args.unknown(),
+  })
+}
+
+pub(super) fn measurement_from_json(
+  value: &Value,
+  refs: &HashMap<u64, Entity>,
+) -> Result<Measurement, SketchFileError> {
+  let args = Args::new(value, refs)?;
+  Ok(match args.kind {
+    "DistancePP" => Measurement::DistancePP(args.entity(0)?, args.entity(1)?),
+    "Angle" => Measurement::Angle(args.entity(0)?, args.entity(1)?, args.entity(2)?),
+    "Radius" => Measurement::Radius(args.entity(0)?),
+    "SegmentLength" => Measurement::SegmentLength(args.entity(0)?),
+    "PolygonArea" => Measurement::PolygonArea(args.entity(0)?),
+    _ => return args.unknown(),
+  })
+}
+
+pub(super) fn text_anchor_to_json(anchor: &TextAnchor) -> Value {
+  match *anchor {
+    TextAnchor::Position(pos) => symbolic("Position", vec![json!(pos.0.x), json!(pos.0.y)]),
+    TextAnchor::Entity(ent, offset) => symbolic("Entity", vec![json!(ent.id()), json!(offset.0.x), json!(offset.0.y)]),
+  }
+}
+
+pub(super) fn text_anchor_from_json(value: &Value, refs: &HashMap<u64, Entity>) -> Result<TextAnchor, SketchFileError> {
+  let args = Args::new(value, refs)?;
+  Ok(match args.kind {
+    "Position" => TextAnchor::Position(args.position()?),
+    "Entity" => TextAnchor::Entity(
+      args.entity(0)?,
+      VirtualPosition(vec2![args.number(1)?, args.number(2)?]),
+    ),
+    _ => return args.unknown(),
+  })
+}
+
+/// The samples of the stroke, each as `[x, y, pressure]`
+pub(super) fn freehand_to_json(stroke: &FreehandStroke) -> Value {
+  json!(stroke
+    .samples
+    .iter()
+    .map(|(position, pressure)| json!([position.0.x, position.0.y, pressure]))
+    .collect::<Vec<_>>())
+}
+
+pub(super) fn freehand_from_json(value: &Value) -> Result<FreehandStroke, SketchFileError> {
+  let samples = value
+    .as_array()
+    .ok_or_else(|| SketchFileError::Invalid(format!("expected samples, found {}", value)))?;
+  Ok(FreehandStroke {
+    samples: samples
+      .iter()
+      .map(|sample| {
+        Ok((
+          VirtualPosition(vec2![number(&sample[0])?, number(&sample[1])?]),
+          number(&sample[2])?,
+        ))
+      })
+      .collect::<Result<Vec<_>, SketchFileError>>()?,
+  })
+}
+
+fn color_to_json(color: &Color) -> Value {
+  json!([color.r, color.g, color.b, color.a])
+}
+
+fn color_from_json(value: &Value) -> Result<Color, SketchFileError> {
+  match value.as_array().map(|channels| channels.as_slice()) {
+    Some([r, g, b, a]) => Ok(rgba!(
+      number(r)? as f32,
+      number(g)? as f32,
+      number(b)? as f32,
+      number(a)? as f32
+    )),
+    _ => Err(SketchFileError::Invalid(format!("expected a color, found {}", value))),
+  }
+}
+
+fn point_fill_to_str(fill: PointFill) -> &'static str {
+  match fill {
+    PointFill::Auto => "Auto",
+    PointFill::Solid => "Solid",
+    PointFill::Hollow => "Hollow",
+  }
+}
+
+fn marker_shape_to_str(marker_shape: MarkerShape) -> &'static str {
+  match marker_shape {
+    MarkerShape::FilledCircle => "FilledCircle",
+    MarkerShape::HollowCircle => "HollowCircle",
+    MarkerShape::Square => "Square",
+    MarkerShape::Cross => "Cross",
+    MarkerShape::Plus => "Plus",
+    MarkerShape::Diamond => "Diamond",
+  }
+}
+
+pub(super) fn point_style_to_json(style: &PointStyle) -> Value {
+  json!({
+    "color": color_to_json(&style.color),
+    "radius": style.radius,
+    "border_color": color_to_json(&style.border_color),
+    "border_width": style.border_width,
+    "alpha": style.alpha,
+    "fill": point_fill_to_str(style.fill),
+    "marker_shape": marker_shape_to_str(style.marker_shape),
+  })
+}
+
+pub(super) fn point_style_from_json(value: &Value) -> Result<PointStyle, SketchFileError> {
+  let fill = match value["fill"].as_str() {
+    Some("Auto") => PointFill::Auto,
+    Some("Solid") => PointFill::Solid,
+    Some("Hollow") => PointFill::Hollow,
+    _ => return Err(SketchFileError::Invalid(format!("unknown fill {}", value["fill"]))),
+  };
+  let marker_shape = match value["marker_shape"].as_str() {
+    Some("FilledCircle") => MarkerShape::FilledCircle,
+    Some("HollowCircle") => MarkerShape::HollowCircle,
+    Some("Square") => MarkerShape::Square,
+    Some("Cross") => MarkerShape::Cross,
+    Some("Plus") => MarkerShape::Plus,
+    Some("Diamond") => MarkerShape::Diamond,
+    _ => {
+      return Err(SketchFileError::Invalid(format!(
+        "unknown marker shape {}",
+        value["marker_shape"]
+      )))
+    }
+  };
+  Ok(PointStyle {
+    color: color_from_json(&value["color"])?,
+    radius: number(&value["radius"])?,
+    border_color: color_from_json(&value["border_color"])?,
+    border_width: number(&value["border_width"])?,
+    alpha: number(&value["alpha"])?,
+    fill,
+    marker_shape,
+  })
+}
+
+fn dash_pattern_to_str(dash: DashPattern) -> &'static str {
+  match dash {
+    DashPattern::Solid => "Solid",
+    DashPattern::Dashed => "Dashed",
+    DashPattern::Dotted => "Dotted",
+    DashPattern::DashDot => "DashDot",
+  }
+}
+
+pub(super) fn line_style_to_json(style: &LineStyle) -> Value {
+  json!({
+    "color": color_to_json(&style.color),
+    "width": style.width,
+    "marks": style.marks.0,
+    "dash": dash_pattern_to_str(style.dash),
+    "alpha": style.alpha,
+  })
+}
+
+pub(super) fn line_style_from_json(value: &Value) -> Result<LineStyle, SketchFileError> {
+  // Sketches saved before lines could be dashed have no dash pattern
+  let dash = match value["dash"].as_str() {
+    None | Some("Solid") => DashPattern::Solid,
+    Some("Dashed") => DashPattern::Dashed,
+    Some("Dotted") => DashPattern::Dotted,
+    Some("DashDot") => DashPattern::DashDot,
+    _ => {
+      return Err(SketchFileError::Invalid(format!(
+        "unknown dash pattern {}",
+        value["dash"]
+      )))
+    }
+  };
+  Ok(LineStyle {
+    color: color_from_json(&value["color"])?,
+    width: number(&value["width"])?,
+    marks: EqualityMarks(number(&value["marks"])? as u8),
+    dash,
+    alpha: number(&value["alpha"])?,
+  })
+}
+
+pub(super) fn circle_style_to_json(style: &CircleStyle) -> Value {
+  json!({
+    "fill": color_to_json(&style.fill),
+    "border": line_style_to_json(&style.border),
+    "alpha": style.alpha,
+  })
+}
+
+pub(super) fn circle_style_from_json(value: &Value) -> Result<CircleStyle, SketchFileError> {
+  Ok(CircleStyle {
+    fill: color_from_json(&value["fill"])?,
+    border: line_style_from_json(&value["border"])?,
+    alpha: number(&value["alpha"])?,
+  })
+}
+
+pub(super) fn text_style_to_json(style: &TextStyle) -> Value {
+  json!({
+    "color": color_to_json(&style.color),
+    "font_size": style.font_size,
+    "alpha": style.alpha,
+  })
+}
+
+pub(super) fn text_style_from_json(value: &Value) -> Result<TextStyle, SketchFileError> {
+  Ok(TextStyle {
+    color: color_from_json(&value["color"])?,
+    font_size: number(&value["font_size"])?,
+    alpha: number(&value["alpha"])?,
+  })
+}
+
+pub(super) fn arc_style_to_json(style: &ArcStyle) -> Value {
+  json!({
+    "color": color_to_json(&style.color),
+    "width": style.width,
+    "alpha": style.alpha,
+  })
+}
+
+pub(super) fn arc_style_from_json(value: &Value) -> Result<ArcStyle, SketchFileError> {
+  Ok(ArcStyle {
+    color: color_from_json(&value["color"])?,
+    width: number(&value["width"])?,
+    alpha: number(&value["alpha"])?,
+  })
+}
+
+pub(super) fn conic_style_to_json(style: &ConicStyle) -> Value {
+  json!({
+    "color": color_to_json(&style.color),
+    "width": style.width,
+    "alpha": style.alpha,
+  })
+}
+
+pub(super) fn conic_style_from_json(value: &Value) -> Result<ConicStyle, SketchFileError> {
+  Ok(ConicStyle {
+    color: color_from_json(&value["color"])?,
+    width: number(&value["width"])?,
+    alpha: number(&value["alpha"])?,
+  })
+}
+
+pub(super) fn polygon_style_to_json(style: &PolygonStyle) -> Value {
+  json!({
+    "fill": color_to_json(&style.fill),
+    "border": line_style_to_json(&style.border),
+    "alpha": style.alpha,
+  })
+}
+
+pub(super) fn polygon_style_from_json(value: &Value) -> Result<PolygonStyle, SketchFileError> {
+  Ok(PolygonStyle {
+    fill: color_from_json(&value["fill"])?,
+    border: line_style_from_json(&value["border"])?,
+    alpha: number(&value["alpha"])?,
+  })
+}
+
+pub(super) fn vector_style_to_json(style: &VectorStyle) -> Value {
+  json!({
+    "color": color_to_json(&style.color),
+    "width": style.width,
+    "head_length": style.head_length,
+    "alpha": style.alpha,
+  })
+}
+
+pub(super) fn vector_style_from_json(value: &Value) -> Result<VectorStyle, SketchFileError> {
+  Ok(VectorStyle {
+    color: color_from_json(&value["color"])?,
+    width: number(&value["width"])?,
+    head_length: number(&value["head_length"])?,
+    alpha: number(&value["alpha"])?,
+  })
+}
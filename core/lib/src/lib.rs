@@ -6,14 +6,22 @@ use specs::prelude::*;
 pub mod math;
 pub mod components;
 pub mod events;
+pub mod io;
 pub mod resources;
 pub mod systems;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod utilities;
 
 use systems::*;
 
 pub fn setup_core_lib<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
   builder.add_barrier();
+  builder.add(
+    data_managers::EntityBudgetManager::default(),
+    "entity_budget_manager",
+    &[],
+  );
   builder.add(
     event_handlers::ViewportEventHandler::default(),
     "viewport_event_handler",
@@ -32,16 +40,61 @@ pub fn setup_core_lib<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
   builder.add(
     command_handlers::InsertPointHandler::default(),
     "insert_point_handler",
-    &["history_event_handler"],
+    &["history_event_handler", "entity_budget_manager"],
   );
   builder.add(
     command_handlers::InsertLineHandler::default(),
     "insert_line_handler",
-    &["history_event_handler"],
+    &["history_event_handler", "entity_budget_manager"],
   );
   builder.add(
     command_handlers::InsertCircleHandler::default(),
     "insert_circle_handler",
+    &["history_event_handler", "entity_budget_manager"],
+  );
+  builder.add(
+    command_handlers::InsertArcHandler::default(),
+    "insert_arc_handler",
+    &["history_event_handler", "entity_budget_manager"],
+  );
+  builder.add(
+    command_handlers::InsertConicHandler::default(),
+    "insert_conic_handler",
+    &["history_event_handler", "entity_budget_manager"],
+  );
+  builder.add(
+    command_handlers::InsertPolygonHandler::default(),
+    "insert_polygon_handler",
+    &["history_event_handler", "entity_budget_manager"],
+  );
+  builder.add(
+    command_handlers::InsertVectorHandler::default(),
+    "insert_vector_handler",
+    &["history_event_handler", "entity_budget_manager"],
+  );
+  builder.add(
+    command_handlers::InsertTextHandler::default(),
+    "insert_text_handler",
+    &["history_event_handler", "entity_budget_manager"],
+  );
+  builder.add(
+    command_handlers::InsertFreehandHandler::default(),
+    "insert_freehand_handler",
+    &["history_event_handler", "entity_budget_manager"],
+  );
+  builder.add(
+    command_handlers::InsertMeasurementHandler::default(),
+    "insert_measurement_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::SliderHandler::default(),
+    "slider_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::UnderlayHandler::default(),
+    "underlay_handler",
     &["history_event_handler"],
   );
   builder.add(
@@ -54,11 +107,116 @@ pub fn setup_core_lib<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
     "hide_handler",
     &["history_event_handler"],
   );
+  builder.add(
+    command_handlers::LayerHandler::default(),
+    "layer_handler",
+    &["history_event_handler"],
+  );
   builder.add(
     command_handlers::SelectHandler::default(),
     "select_handler",
     &["history_event_handler"],
   );
+  builder.add(
+    command_handlers::RenameHandler::default(),
+    "rename_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::RestyleHandler::default(),
+    "restyle_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::ThemeHandler::default(),
+    "theme_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::DumpDependencyGraphHandler::default(),
+    "dump_dependency_graph_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::CoordinatesHandler::default(),
+    "coordinates_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::TraceHandler::default(),
+    "trace_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::AnimationHandler::default(),
+    "animation_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::AlignHandler::default(),
+    "align_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::RotateHandler::default(),
+    "rotate_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::ReflectHandler::default(),
+    "reflect_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::ClipboardHandler::default(),
+    "clipboard_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::MacroHandler::default(),
+    "macro_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::TransformHandler::default(),
+    "transform_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::RedefinePointHandler::default(),
+    "redefine_point_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::ScaleHandler::default(),
+    "scale_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::LineTypeHandler::default(),
+    "line_type_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::ConstraintHandler::default(),
+    "constraint_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::ScalarHandler::default(),
+    "scalar_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::SolverHandler::default(),
+    "solver_handler",
+    &["history_event_handler"],
+  );
+  builder.add(
+    command_handlers::SketchFileHandler::default(),
+    "sketch_file_handler",
+    &["history_event_handler"],
+  );
   builder.add(
     data_managers::HistoryManager::default(),
     "history_manager",
@@ -67,10 +225,47 @@ pub fn setup_core_lib<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
       "insert_point_handler",
       "insert_line_handler",
       "insert_circle_handler",
+      "insert_arc_handler",
+      "insert_conic_handler",
+      "insert_polygon_handler",
+      "insert_vector_handler",
+      "insert_text_handler",
+      "insert_freehand_handler",
+      "insert_measurement_handler",
+      "slider_handler",
+      "underlay_handler",
       "update_point_handler",
+      "line_type_handler",
       "hide_handler",
+      "restyle_handler",
+      "rename_handler",
+    ],
+  );
+  builder.add(
+    data_managers::LabelManager::default(),
+    "label_manager",
+    &[
+      "remove_handler",
+      "insert_point_handler",
+      "insert_line_handler",
+      "insert_circle_handler",
+      "insert_arc_handler",
+      "insert_conic_handler",
+      "insert_polygon_handler",
+      "insert_vector_handler",
+      "insert_text_handler",
+      "insert_freehand_handler",
+      "insert_measurement_handler",
+      "slider_handler",
+      "underlay_handler",
+      "rename_handler",
     ],
   );
+  builder.add(
+    data_managers::ViewportHistoryManager::default(),
+    "viewport_history_manager",
+    &["viewport_event_handler", "history_manager"],
+  );
   builder.add(
     data_managers::DependencyGraphManager::default(),
     "dependency_graph_manager",
@@ -79,23 +274,128 @@ pub fn setup_core_lib<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
       "insert_point_handler",
       "insert_line_handler",
       "insert_circle_handler",
+      "insert_arc_handler",
+      "insert_conic_handler",
+      "insert_polygon_handler",
+      "insert_vector_handler",
+      "insert_text_handler",
+      "insert_freehand_handler",
+      "insert_measurement_handler",
+      "slider_handler",
+      "underlay_handler",
+      "update_point_handler",
+      "line_type_handler",
+    ],
+  );
+  builder.add(
+    data_managers::CommandRecorder::default(),
+    "command_recorder",
+    &[
+      "remove_handler",
+      "insert_point_handler",
+      "insert_line_handler",
+      "insert_circle_handler",
+      "insert_arc_handler",
+      "insert_conic_handler",
+      "insert_polygon_handler",
+      "insert_vector_handler",
+      "insert_text_handler",
+      "insert_freehand_handler",
+      "insert_measurement_handler",
+      "slider_handler",
+      "underlay_handler",
       "update_point_handler",
+      "hide_handler",
+      "layer_handler",
+      "select_handler",
+      "rename_handler",
+      "restyle_handler",
+      "theme_handler",
+      "dump_dependency_graph_handler",
+      "coordinates_handler",
+      "trace_handler",
+      "animation_handler",
+      "align_handler",
+      "rotate_handler",
+      "reflect_handler",
+      "clipboard_handler",
+      "macro_handler",
+      "scale_handler",
+      "transform_handler",
+      "redefine_point_handler",
+      "line_type_handler",
+      "constraint_handler",
+      "scalar_handler",
+      "solver_handler",
+      "sketch_file_handler",
     ],
   );
   builder.add(
     solvers::VirtualShapeSolver::default(),
     "virtual_shape_solver",
-    &["dependency_graph_manager"],
+    &["dependency_graph_manager", "solver_handler", "scalar_handler"],
+  );
+  builder.add(
+    solvers::AngleConstraintSolver::default(),
+    "angle_constraint_solver",
+    &["virtual_shape_solver", "constraint_handler"],
+  );
+  builder.add(
+    solvers::ConstraintSolverSystem::default(),
+    "constraint_solver_system",
+    &["virtual_shape_solver", "constraint_handler"],
+  );
+  builder.add(
+    solvers::ScalarExpressionSolver::default(),
+    "scalar_expression_solver",
+    &["virtual_shape_solver", "scalar_handler", "slider_handler"],
   );
   builder.add(
     solvers::ScreenShapeSolver::default(),
     "screen_shape_solver",
     &["virtual_shape_solver", "viewport_event_handler"],
   );
+  builder.add(
+    solvers::CoordinatesLabelSolver::default(),
+    "coordinates_label_solver",
+    &["screen_shape_solver", "coordinates_handler"],
+  );
+  builder.add(
+    solvers::MeasurementSolverSystem::default(),
+    "measurement_solver_system",
+    &["screen_shape_solver", "coordinates_label_solver"],
+  );
+  builder.add(
+    solvers::MeasurementSystem::default(),
+    "measurement_system",
+    &["virtual_shape_solver", "select_handler"],
+  );
+  builder.add(
+    data_managers::TraceCollectorSystem::default(),
+    "trace_collector_system",
+    &[
+      "virtual_shape_solver",
+      "angle_constraint_solver",
+      "constraint_solver_system",
+      "trace_handler",
+    ],
+  );
+  builder.add(
+    data_managers::InspectionManager::default(),
+    "inspection_manager",
+    &[
+      "virtual_shape_solver",
+      "angle_constraint_solver",
+      "constraint_solver_system",
+      "select_handler",
+      "restyle_handler",
+      "rename_handler",
+    ],
+  );
   builder.add(
     data_managers::SpatialEntityMapManager::default(),
     "spatial_entity_map_manager",
-    &["screen_shape_solver", "hide_handler"],
+    &["virtual_shape_solver", "hide_handler", "layer_handler"],
   );
   builder.add_barrier();
 }
@@ -1,6 +1,6 @@
 use super::Vector2;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct AABB {
   pub x: f64,
   pub y: f64,
@@ -23,6 +23,16 @@ impl AABB {
     }
   }
 
+  /// The same box grown by `margin` on every side
+  pub fn expand(&self, margin: f64) -> Self {
+    Self {
+      x: self.x - margin,
+      y: self.y - margin,
+      width: self.width + margin * 2.0,
+      height: self.height + margin * 2.0,
+    }
+  }
+
   pub fn min(&self) -> Vector2 {
     vec2![self.x, self.y]
   }
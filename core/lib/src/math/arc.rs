@@ -0,0 +1,145 @@
+use super::{Circle, Vector2, AABB};
+use std::f64::consts::PI;
+
+static ARC_ANGLE_THRESHOLD: f64 = 1e-9;
+
+/// Part of a circle, going counter clockwise from the `start` angle over `sweep` radians. Angles
+/// are measured from the x axis, the same way as `atan2`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Arc {
+  pub center: Vector2,
+  pub radius: f64,
+  pub start: f64,
+  pub sweep: f64,
+}
+
+fn angle_of(v: Vector2) -> f64 {
+  v.y.atan2(v.x)
+}
+
+impl Arc {
+  /// The arc counter clockwise from `from` to the direction of `to`. The radius is the distance
+  /// from the center to `from`, `to` does not have to be on the circle
+  pub fn from_center_points(center: Vector2, from: Vector2, to: Vector2) -> Self {
+    let start = angle_of(from - center);
+    Self {
+      center,
+      radius: (from - center).magnitude(),
+      start,
+      sweep: (angle_of(to - center) - start).rem_euclid(2.0 * PI),
+    }
+  }
+
+  /// The arc from `from` to `to` going through `through`, `None` when they are on a same line
+  pub fn through_points(from: Vector2, through: Vector2, to: Vector2) -> Option<Self> {
+    let Circle { center, .. } = Circle::through_points(from, through, to)?;
+    let arc = Self::from_center_points(center, from, to);
+    if arc.contains(through) {
+      Some(arc)
+    } else {
+      Some(Self::from_center_points(center, to, from))
+    }
+  }
+
+  pub fn circle(&self) -> Circle {
+    Circle {
+      center: self.center,
+      radius: self.radius,
+    }
+  }
+
+  pub fn end(&self) -> f64 {
+    self.start + self.sweep
+  }
+
+  pub fn point_at_angle(&self, theta: f64) -> Vector2 {
+    self.center + vec2![theta.cos(), theta.sin()] * self.radius
+  }
+
+  pub fn start_point(&self) -> Vector2 {
+    self.point_at_angle(self.start)
+  }
+
+  pub fn end_point(&self) -> Vector2 {
+    self.point_at_angle(self.end())
+  }
+
+  pub fn contains_angle(&self, theta: f64) -> bool {
+    let offset = (theta - self.start).rem_euclid(2.0 * PI);
+    offset <= self.sweep + ARC_ANGLE_THRESHOLD || offset >= 2.0 * PI - ARC_ANGLE_THRESHOLD
+  }
+
+  /// Whether the direction of `p` from the center is covered by the arc. The distance of `p` to
+  /// the center is not checked
+  pub fn contains(&self, p: Vector2) -> bool {
+    self.contains_angle(angle_of(p - self.center))
+  }
+
+  /// The point of the arc closest to `p`, one of the two ends when `p` is not in front of the arc
+  pub fn get_closest_point(&self, p: Vector2) -> Vector2 {
+    if self.contains(p) && !(p - self.center).is_zero() {
+      self.center + (p - self.center).normalized() * self.radius
+    } else {
+      let (start, end) = (self.start_point(), self.end_point());
+      if (start - p).magnitude() <= (end - p).magnitude() {
+        start
+      } else {
+        end
+      }
+    }
+  }
+
+  /// The bounding box of the ends and of the extreme points of the circle covered by the arc
+  pub fn aabb(&self) -> AABB {
+    let (start, end) = (self.start_point(), self.end_point());
+    let mut min = vec2![start.x.min(end.x), start.y.min(end.y)];
+    let mut max = vec2![start.x.max(end.x), start.y.max(end.y)];
+    for i in 0..4 {
+      let theta = i as f64 * PI / 2.0;
+      if self.contains_angle(theta) {
+        let p = self.point_at_angle(theta);
+        min = vec2![min.x.min(p.x), min.y.min(p.y)];
+        max = vec2![max.x.max(p.x), max.y.max(p.y)];
+      }
+    }
+    AABB::two_points(min, max)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn assert_close(a: Vector2, b: Vector2) {
+    assert!((a - b).magnitude() < 1e-10, "{:?} != {:?}", a, b);
+  }
+
+  #[test]
+  fn test_arc_from_center_points_goes_counter_clockwise() {
+    let arc = Arc::from_center_points(vec2![0., 0.], vec2![0., -2.], vec2![1., 0.]);
+    assert!((arc.radius - 2.).abs() < 1e-10);
+    assert!((arc.sweep - PI / 2.).abs() < 1e-10);
+    assert_close(arc.end_point(), vec2![2., 0.]);
+    assert!(arc.contains(vec2![1., -1.]));
+    assert!(!arc.contains(vec2![-1., 1.]));
+    assert_close(
+      arc.get_closest_point(vec2![3., -3.]),
+      vec2![2f64.sqrt(), -(2f64.sqrt())],
+    );
+    assert_close(arc.get_closest_point(vec2![-1., 3.]), vec2![2., 0.]);
+    let aabb = arc.aabb();
+    assert_close(aabb.min(), vec2![0., -2.]);
+    assert_close(aabb.max(), vec2![2., 0.]);
+  }
+
+  #[test]
+  fn test_arc_through_points_keeps_the_middle_point() {
+    // Clockwise from the top to the bottom through the right
+    let arc = Arc::through_points(vec2![0., 1.], vec2![1., 0.], vec2![0., -1.]).unwrap();
+    assert_close(arc.start_point(), vec2![0., -1.]);
+    assert_close(arc.end_point(), vec2![0., 1.]);
+    assert!(arc.contains(vec2![1., 0.]));
+    assert!(!arc.contains(vec2![-1., 0.]));
+    assert!(Arc::through_points(vec2![0., 0.], vec2![1., 1.], vec2![2., 2.]).is_none());
+  }
+}
@@ -1,6 +1,194 @@
-use super::Vector2;
+use super::{Line, LineType, Vector2};
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Circle {
   pub center: Vector2,
   pub radius: f64,
 }
+
+/// Which of the four common tangents of two circles. Upper means the tangent point on the first
+/// circle is on the left side when looking from the first circle center to the second one
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TangentKind {
+  ExternalUpper,
+  ExternalLower,
+  InternalUpper,
+  InternalLower,
+}
+
+impl Circle {
+  pub fn from_center_point(center: Vector2, on_circle: Vector2) -> Self {
+    Self {
+      center,
+      radius: (on_circle - center).magnitude(),
+    }
+  }
+
+  /// The circle going through the three points, `None` when they are on a same line
+  pub fn through_points(p1: Vector2, p2: Vector2, p3: Vector2) -> Option<Self> {
+    let d = 2.0 * (p1.x * (p2.y - p3.y) + p2.x * (p3.y - p1.y) + p3.x * (p1.y - p2.y));
+    if d.abs() < 1e-10 {
+      return None;
+    }
+    let (s1, s2, s3) = (
+      p1.x * p1.x + p1.y * p1.y,
+      p2.x * p2.x + p2.y * p2.y,
+      p3.x * p3.x + p3.y * p3.y,
+    );
+    let center = vec2![
+      (s1 * (p2.y - p3.y) + s2 * (p3.y - p1.y) + s3 * (p1.y - p2.y)) / d,
+      (s1 * (p3.x - p2.x) + s2 * (p1.x - p3.x) + s3 * (p2.x - p1.x)) / d
+    ];
+    Some(Self::from_center_point(center, p1))
+  }
+
+  pub fn approx_eq(&self, other: &Circle, tol: f64) -> bool {
+    (self.center - other.center).magnitude() <= tol && (self.radius - other.radius).abs() <= tol
+  }
+
+  /// One of the two tangent lines of the circle through `p`, the first one (`index` 0) touching
+  /// the circle counter clockwise from `p` as seen from the center and any other index the
+  /// second one. `None` if `p` is inside the circle
+  pub fn tangent_through(&self, p: Vector2, index: u8) -> Option<Line> {
+    let diff = p - self.center;
+    let dist = diff.magnitude();
+    if dist < self.radius || dist == 0.0 {
+      return None;
+    }
+    let alpha = (self.radius / dist).acos();
+    let alpha = if index == 0 { alpha } else { -alpha };
+    let touch = self.center + (diff / dist).rotate(alpha) * self.radius;
+
+    // On the circle both tangents are the same, perpendicular to the radius
+    let to = if (touch - p).magnitude() < 1e-10 {
+      p + vec2![-diff.y, diff.x]
+    } else {
+      touch
+    };
+    Some(Line {
+      from: p,
+      to,
+      line_type: LineType::Straight,
+    })
+  }
+
+  /// The common tangent line of the two circles, `None` if the tangent does not exist
+  pub fn common_tangent(&self, other: &Circle, kind: TangentKind) -> Option<Line> {
+    let diff = other.center - self.center;
+    let dist = diff.magnitude();
+    if dist == 0.0 {
+      return None;
+    }
+
+    // n is the normal of the tangent line, pointing from the line towards the first circle center
+    let v = diff / dist;
+    let perp = vec2![-v.y, v.x];
+    let cos = match kind {
+      TangentKind::ExternalUpper | TangentKind::ExternalLower => (other.radius - self.radius) / dist,
+      TangentKind::InternalUpper | TangentKind::InternalLower => -(self.radius + other.radius) / dist,
+    };
+    if cos.abs() > 1.0 {
+      return None;
+    }
+    let sin = (1.0 - cos * cos).sqrt();
+    let n = match kind {
+      TangentKind::ExternalUpper | TangentKind::InternalUpper => v * cos - perp * sin,
+      TangentKind::ExternalLower | TangentKind::InternalLower => v * cos + perp * sin,
+    };
+
+    let from = self.center - n * self.radius;
+    Some(Line {
+      from,
+      to: from + vec2![-n.y, n.x],
+      line_type: LineType::Straight,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::math::DotProduct;
+
+  #[test]
+  fn test_circle_from_center_point() {
+    let c = Circle::from_center_point(vec2![1., 1.], vec2![4., 5.]);
+    assert_eq!(c.center, vec2![1., 1.]);
+    assert!((c.radius - 5.0).abs() < 1e-10);
+  }
+
+  fn dist_to_line(p: Vector2, l: Line) -> f64 {
+    let dir = l.direction();
+    let normal = vec2![-dir.y, dir.x];
+    (p - l.from).dot(normal).abs()
+  }
+
+  #[test]
+  fn test_circle_common_tangent_separated_equal_radius() {
+    let c1 = Circle::from_center_point(vec2![0., 0.], vec2![1., 0.]);
+    let c2 = Circle::from_center_point(vec2![5., 0.], vec2![6., 0.]);
+
+    let upper = c1.common_tangent(&c2, TangentKind::ExternalUpper).unwrap();
+    let lower = c1.common_tangent(&c2, TangentKind::ExternalLower).unwrap();
+
+    // Two parallel tangents, one above and one below
+    assert!(upper.direction().y.abs() < 1e-10);
+    assert!(lower.direction().y.abs() < 1e-10);
+    assert!((upper.from.y - 1.0).abs() < 1e-10);
+    assert!((lower.from.y + 1.0).abs() < 1e-10);
+
+    // Internal tangents exist and touch both circles
+    for kind in &[TangentKind::InternalUpper, TangentKind::InternalLower] {
+      let l = c1.common_tangent(&c2, *kind).unwrap();
+      assert!((dist_to_line(c1.center, l) - c1.radius).abs() < 1e-10);
+      assert!((dist_to_line(c2.center, l) - c2.radius).abs() < 1e-10);
+    }
+  }
+
+  #[test]
+  fn test_circle_common_tangent_overlapping() {
+    let c1 = Circle::from_center_point(vec2![0., 0.], vec2![2., 0.]);
+    let c2 = Circle::from_center_point(vec2![2.5, 0.], vec2![3.5, 0.]);
+    assert!(c1.common_tangent(&c2, TangentKind::InternalUpper).is_none());
+    assert!(c1.common_tangent(&c2, TangentKind::InternalLower).is_none());
+    for kind in &[TangentKind::ExternalUpper, TangentKind::ExternalLower] {
+      let l = c1.common_tangent(&c2, *kind).unwrap();
+      assert!((dist_to_line(c1.center, l) - c1.radius).abs() < 1e-10);
+      assert!((dist_to_line(c2.center, l) - c2.radius).abs() < 1e-10);
+    }
+  }
+
+  #[test]
+  fn test_circle_through_points() {
+    let c = Circle::through_points(vec2![0., 0.], vec2![6., 0.], vec2![0., 8.]).unwrap();
+    assert!(c.approx_eq(&Circle::from_center_point(vec2![3., 4.], vec2![0., 0.]), 1e-10));
+    assert!((c.radius - 5.0).abs() < 1e-10);
+    assert!(Circle::through_points(vec2![0., 0.], vec2![1., 1.], vec2![3., 3.]).is_none());
+  }
+
+  #[test]
+  fn test_circle_tangent_through() {
+    let c = Circle::from_center_point(vec2![1., 1.], vec2![1., 3.]);
+    let p = vec2![5., 1.];
+    let first = c.tangent_through(p, 0).unwrap();
+    let second = c.tangent_through(p, 1).unwrap();
+    for l in &[first, second] {
+      assert_eq!(l.from, p);
+      assert!((dist_to_line(c.center, *l) - c.radius).abs() < 1e-10);
+    }
+    assert!(first.to.y > 1. && second.to.y < 1.);
+
+    // On the circle there's a single tangent, inside there's none
+    let on = c.tangent_through(vec2![3., 1.], 0).unwrap();
+    assert!((dist_to_line(c.center, on) - c.radius).abs() < 1e-10);
+    assert!(c.tangent_through(vec2![2., 1.], 0).is_none());
+  }
+
+  #[test]
+  fn test_circle_approx_eq() {
+    let c = Circle::from_center_point(vec2![1., 1.], vec2![4., 5.]);
+    assert!(c.approx_eq(&Circle::from_center_point(vec2![1., 1.], vec2![-2., -3.]), 1e-6));
+    assert!(!c.approx_eq(&Circle::from_center_point(vec2![1., 1.], vec2![4., 5.01]), 1e-6));
+    assert!(!c.approx_eq(&Circle::from_center_point(vec2![1.01, 1.], vec2![4.01, 5.]), 1e-6));
+  }
+}
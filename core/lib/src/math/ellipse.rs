@@ -0,0 +1,227 @@
+use super::{Vector2, AABB};
+
+static ELLIPSE_THRESHOLD: f64 = 1e-10;
+
+/// Ellipse with radius `rx` along its own x axis and `ry` along its own y axis, the x axis of the
+/// ellipse being turned counter clockwise from the x axis by `rotation` radians
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ellipse {
+  pub center: Vector2,
+  pub rx: f64,
+  pub ry: f64,
+  pub rotation: f64,
+}
+
+/// Determinant by gaussian elimination with partial pivoting
+fn determinant(mut m: [[f64; 5]; 5]) -> f64 {
+  let mut det = 1.0;
+  for i in 0..5 {
+    let pivot = (i..5)
+      .max_by(|r1, r2| m[*r1][i].abs().partial_cmp(&m[*r2][i].abs()).unwrap())
+      .unwrap();
+    if m[pivot][i] == 0.0 {
+      return 0.0;
+    }
+    if pivot != i {
+      m.swap(pivot, i);
+      det = -det;
+    }
+    det *= m[i][i];
+    let pivot_row = m[i];
+    for row in m.iter_mut().skip(i + 1) {
+      let factor = row[i] / pivot_row[i];
+      for (cell, pivot_cell) in row.iter_mut().zip(pivot_row.iter()).skip(i) {
+        *cell -= factor * pivot_cell;
+      }
+    }
+  }
+  det
+}
+
+impl Ellipse {
+  /// The ellipse with the two foci going through `p`, `None` when `p` is on the segment between
+  /// the foci
+  pub fn from_foci_point(f1: Vector2, f2: Vector2, p: Vector2) -> Option<Self> {
+    let c = (f2 - f1).magnitude() / 2.0;
+    let rx = ((p - f1).magnitude() + (p - f2).magnitude()) / 2.0;
+    let ry = (rx * rx - c * c).max(0.0).sqrt();
+    if ry < ELLIPSE_THRESHOLD {
+      return None;
+    }
+    let dir = f2 - f1;
+    Some(Self {
+      center: (f1 + f2) / 2.0,
+      rx,
+      ry,
+      rotation: if dir.is_zero() { 0.0 } else { dir.y.atan2(dir.x) },
+    })
+  }
+
+  /// The ellipse going through the five points. `None` when the conic through them is not an
+  /// ellipse, e.g. a hyperbola or a pair of lines
+  pub fn through_points(points: [Vector2; 5]) -> Option<Self> {
+    // Work around the centroid at unit scale to keep the equation well conditioned
+    let centroid = points.iter().fold(Vector2::zero(), |sum, p| sum + *p) / 5.0;
+    let scale = points.iter().map(|p| (*p - centroid).magnitude()).sum::<f64>() / 5.0;
+    if scale < ELLIPSE_THRESHOLD {
+      return None;
+    }
+    let rows = points
+      .iter()
+      .map(|p| {
+        let Vector2 { x, y } = (*p - centroid) / scale;
+        [x * x, x * y, y * y, x, y, 1.0]
+      })
+      .collect::<Vec<_>>();
+
+    // The coefficients of a x^2 + b xy + c y^2 + d x + e y + f = 0 are the signed minors
+    let mut coefs = [0.0; 6];
+    for (k, coef) in coefs.iter_mut().enumerate() {
+      let mut minor = [[0.0; 5]; 5];
+      for (r, row) in rows.iter().enumerate() {
+        let mut cols = row.iter().enumerate().filter(|(c, _)| *c != k).map(|(_, v)| *v);
+        for entry in minor[r].iter_mut() {
+          *entry = cols.next().unwrap();
+        }
+      }
+      *coef = if k % 2 == 0 { 1.0 } else { -1.0 } * determinant(minor);
+    }
+    let norm = coefs.iter().map(|c| c * c).sum::<f64>().sqrt();
+    if norm < ELLIPSE_THRESHOLD {
+      return None;
+    }
+    let [a, b, c, d, e, f] = coefs;
+
+    // An ellipse has a negative discriminant
+    let det = 4.0 * a * c - b * b;
+    if det < ELLIPSE_THRESHOLD * norm * norm {
+      return None;
+    }
+    let center = vec2![(b * e - 2.0 * c * d) / det, (b * d - 2.0 * a * e) / det];
+    let f_center = f + (d * center.x + e * center.y) / 2.0;
+    let rotation = 0.5 * b.atan2(a - c);
+    let (sin, cos) = rotation.sin_cos();
+    let lambda_x = a * cos * cos + b * cos * sin + c * sin * sin;
+    let lambda_y = a * sin * sin - b * cos * sin + c * cos * cos;
+    let (rx2, ry2) = (-f_center / lambda_x, -f_center / lambda_y);
+    if rx2 <= 0.0 || ry2 <= 0.0 {
+      return None;
+    }
+    Some(Self {
+      center: centroid + center * scale,
+      rx: rx2.sqrt() * scale,
+      ry: ry2.sqrt() * scale,
+      rotation,
+    })
+  }
+
+  /// The point at the parameter `t`, which is the angle of the point on the circle of radius `rx`
+  /// squashed onto the ellipse
+  pub fn point_at(&self, t: f64) -> Vector2 {
+    self.center + vec2![self.rx * t.cos(), self.ry * t.sin()].rotate(self.rotation)
+  }
+
+  /// The parameter of the point of the ellipse in the direction of `p` after the squashing
+  pub fn parameter_of(&self, p: Vector2) -> f64 {
+    let local = (p - self.center).rotate(-self.rotation);
+    (local.y / self.ry).atan2(local.x / self.rx)
+  }
+
+  /// The point of the ellipse closest to `p`, found by a few iterations on the quarter of the
+  /// ellipse facing `p`
+  pub fn get_closest_point(&self, p: Vector2) -> Vector2 {
+    let local = (p - self.center).rotate(-self.rotation);
+    let (px, py) = (local.x.abs(), local.y.abs());
+    let (rx, ry) = (self.rx, self.ry);
+    let (mut tx, mut ty) = (std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2);
+    for _ in 0..4 {
+      let (x, y) = (rx * tx, ry * ty);
+      let ex = (rx * rx - ry * ry) * tx.powi(3) / rx;
+      let ey = (ry * ry - rx * rx) * ty.powi(3) / ry;
+      let (qx, qy) = (px - ex, py - ey);
+      let r = (x - ex).hypot(y - ey);
+      let q = qx.hypot(qy);
+      if q < ELLIPSE_THRESHOLD {
+        break;
+      }
+      tx = ((qx * r / q + ex) / rx).clamp(0.0, 1.0);
+      ty = ((qy * r / q + ey) / ry).clamp(0.0, 1.0);
+      let t = tx.hypot(ty);
+      tx /= t;
+      ty /= t;
+    }
+    let closest = vec2![(rx * tx).copysign(local.x), (ry * ty).copysign(local.y)];
+    self.center + closest.rotate(self.rotation)
+  }
+
+  pub fn aabb(&self) -> AABB {
+    let (sin, cos) = self.rotation.sin_cos();
+    let half = vec2![
+      (self.rx * self.rx * cos * cos + self.ry * self.ry * sin * sin).sqrt(),
+      (self.rx * self.rx * sin * sin + self.ry * self.ry * cos * cos).sqrt()
+    ];
+    AABB::two_points(self.center - half, self.center + half)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn assert_close(a: Vector2, b: Vector2) {
+    assert!((a - b).magnitude() < 1e-9, "{:?} != {:?}", a, b);
+  }
+
+  #[test]
+  fn test_ellipse_from_foci_point() {
+    let e = Ellipse::from_foci_point(vec2![-3., 1.], vec2![3., 1.], vec2![0., 5.]).unwrap();
+    assert_close(e.center, vec2![0., 1.]);
+    assert!((e.rx - 5.).abs() < 1e-9 && (e.ry - 4.).abs() < 1e-9);
+    assert_close(e.point_at(0.), vec2![5., 1.]);
+    assert!((e.parameter_of(vec2![0., 5.]) - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    assert_close(e.get_closest_point(vec2![0., 9.]), vec2![0., 5.]);
+    assert_close(e.get_closest_point(vec2![-8., 1.]), vec2![-5., 1.]);
+    let aabb = e.aabb();
+    assert_close(aabb.min(), vec2![-5., -3.]);
+    assert_close(aabb.max(), vec2![5., 5.]);
+    assert!(Ellipse::from_foci_point(vec2![-3., 1.], vec2![3., 1.], vec2![1., 1.]).is_none());
+  }
+
+  #[test]
+  fn test_ellipse_through_five_points() {
+    let expected = Ellipse {
+      center: vec2![10., -4.],
+      rx: 6.,
+      ry: 2.,
+      rotation: 0.5,
+    };
+    let points = [0.1, 1.3, 2.2, 3.9, 5.0];
+    let e = Ellipse::through_points([
+      expected.point_at(points[0]),
+      expected.point_at(points[1]),
+      expected.point_at(points[2]),
+      expected.point_at(points[3]),
+      expected.point_at(points[4]),
+    ])
+    .unwrap();
+    for t in &[0.0, 0.7, 2.5, 4.4] {
+      let p = expected.point_at(*t);
+      assert_close(e.get_closest_point(p), p);
+    }
+
+    // Points on a hyperbola or with four of them on a line are no ellipse
+    let hyperbola = [1., 2., 3., -1., -2.]
+      .iter()
+      .map(|x| vec2![*x, 1. / x])
+      .collect::<Vec<_>>();
+    assert!(Ellipse::through_points([hyperbola[0], hyperbola[1], hyperbola[2], hyperbola[3], hyperbola[4]]).is_none());
+    let aligned = [
+      vec2![0., 0.],
+      vec2![1., 0.],
+      vec2![2., 0.],
+      vec2![3., 0.],
+      vec2![0., 1.],
+    ];
+    assert!(Ellipse::through_points(aligned).is_none());
+  }
+}
@@ -14,7 +14,22 @@ pub enum LineType {
   Segment,
 }
 
+impl LineType {
+  /// The next line type in the drawing cycle: Segment -> Ray -> Straight -> Segment
+  pub fn next(&self) -> Self {
+    match self {
+      LineType::Segment => LineType::Ray,
+      LineType::Ray => LineType::Straight,
+      LineType::Straight => LineType::Segment,
+    }
+  }
+}
+
 impl Line {
+  pub fn from_two_points(from: Vector2, to: Vector2, line_type: LineType) -> Self {
+    Self { from, to, line_type }
+  }
+
   pub fn direction(&self) -> Vector2 {
     (self.to - self.from).normalized()
   }
@@ -44,6 +59,11 @@ impl Line {
     }
   }
 
+  /// The mirror image of `p` across the line, seen as a straight line whatever its type
+  pub fn reflect(&self, p: Vector2) -> Vector2 {
+    p.project(*self) * 2.0 - p
+  }
+
   pub fn get_closest_point(&self, p: Vector2) -> Vector2 {
     let proj = p.project(*self);
     let t = self.t_of_point(proj);
@@ -69,6 +89,57 @@ impl Line {
       }
     }
   }
+
+  /// Whether the two lines have the same shape: same type, parallel directions, and for segments
+  /// the same length. Straight lines and segments have no orientation while rays do
+  pub fn approx_congruent(&self, other: &Line, tol: f64) -> bool {
+    if self.line_type != other.line_type {
+      return false;
+    }
+    let (d1, d2) = (self.direction(), other.direction());
+    if (d1.x * d2.y - d1.y * d2.x).abs() > tol {
+      return false;
+    }
+    match self.line_type {
+      LineType::Straight => true,
+      LineType::Ray => d1.dot(d2) > 0.0,
+      LineType::Segment => (self.from_to_length() - other.from_to_length()).abs() <= tol,
+    }
+  }
+
+  /// Whether the two lines cover the same points, regardless of the points defining them
+  pub fn approx_coincident(&self, other: &Line, tol: f64) -> bool {
+    if !self.approx_congruent(other, tol) {
+      return false;
+    }
+    let close = |a: Vector2, b: Vector2| (a - b).magnitude() <= tol;
+    match self.line_type {
+      LineType::Straight => close(other.from, other.from.project(*self)),
+      LineType::Ray => close(self.from, other.from),
+      LineType::Segment => {
+        (close(self.from, other.from) && close(self.to, other.to))
+          || (close(self.from, other.to) && close(self.to, other.from))
+      }
+    }
+  }
+
+  /// The `count` tick marks of a segment, each of them crossing the segment perpendicularly
+  /// around its midpoint. The ticks are squeezed together on segments too short for `spacing`
+  pub fn tick_marks(&self, count: u8, length: f64, spacing: f64) -> Vec<(Vector2, Vector2)> {
+    if self.line_type != LineType::Segment || count == 0 || self.from_to_length() == 0.0 {
+      return vec![];
+    }
+    let dir = self.direction();
+    let perp = vec2![-dir.y, dir.x] * (length / 2.0);
+    let spacing = spacing.min(self.from_to_length() / (count as f64 + 1.0));
+    let mid = (self.from + self.to) / 2.0;
+    (0..count)
+      .map(|i| {
+        let center = mid + dir * ((i as f64 - (count as f64 - 1.0) / 2.0) * spacing);
+        (center - perp, center + perp)
+      })
+      .collect()
+  }
 }
 
 #[cfg(test)]
@@ -88,4 +159,86 @@ mod test {
       assert!(proj.x == p.x, "Expected: {}, Actual: {}", p.x, proj.x);
     }
   }
+
+  #[test]
+  fn test_line_type_next_cycles() {
+    let start = LineType::Segment;
+    assert_eq!(start.next(), LineType::Ray);
+    assert_eq!(start.next().next(), LineType::Straight);
+    assert_eq!(start.next().next().next(), start);
+  }
+
+  #[test]
+  fn test_line_from_two_points() {
+    let (a, b) = (vec2![1., 2.], vec2![4., 6.]);
+    let l = Line::from_two_points(a, b, LineType::Segment);
+    assert!((l.direction().magnitude() - 1.0).abs() < 1e-10);
+    assert!((l.from_to_length() - 5.0).abs() < 1e-10);
+    assert_eq!(l.line_type, LineType::Segment);
+  }
+
+  #[test]
+  fn test_line_reflect() {
+    let l = Line::from_two_points(vec2![0., 1.], vec2![1., 2.], LineType::Segment);
+    let p = l.reflect(vec2![3., 0.]);
+    assert!((p - vec2![-1., 4.]).magnitude() < 1e-10);
+    assert!((l.reflect(p) - vec2![3., 0.]).magnitude() < 1e-10);
+  }
+
+  #[test]
+  fn test_line_tick_marks() {
+    let l = Line::from_two_points(vec2![0., 0.], vec2![30., 40.], LineType::Segment);
+    let ticks = l.tick_marks(2, 10., 4.);
+    assert_eq!(ticks.len(), 2);
+
+    // Centered around the midpoint along the segment, perpendicular to it
+    let dir = l.direction();
+    let mid = vec2![15., 20.];
+    let centers = ticks.iter().map(|(a, b)| (*a + *b) / 2.0).collect::<Vec<_>>();
+    assert!(((centers[0] + centers[1]) / 2.0 - mid).magnitude() < 1e-10);
+    assert!(((centers[1] - centers[0]).magnitude() - 4.).abs() < 1e-10);
+    for (a, b) in ticks {
+      assert!(((b - a).magnitude() - 10.).abs() < 1e-10);
+      assert!((b - a).dot(dir).abs() < 1e-10);
+    }
+
+    // Only segments have tick marks
+    let l = Line::from_two_points(vec2![0., 0.], vec2![30., 40.], LineType::Straight);
+    assert!(l.tick_marks(2, 10., 4.).is_empty());
+  }
+
+  #[test]
+  fn test_line_approx_congruent() {
+    let tol = 1e-6;
+    let l = Line::from_two_points(vec2![0., 0.], vec2![3., 4.], LineType::Segment);
+
+    // Same length and direction, elsewhere and flipped
+    let moved = Line::from_two_points(vec2![10., 10.], vec2![7., 6.], LineType::Segment);
+    assert!(l.approx_congruent(&moved, tol));
+    assert!(!l.approx_coincident(&moved, tol));
+    let flipped = Line::from_two_points(vec2![3., 4.], vec2![0., 0.], LineType::Segment);
+    assert!(l.approx_coincident(&flipped, tol));
+
+    // Near misses
+    let longer = Line::from_two_points(vec2![0., 0.], vec2![3.001, 4.001], LineType::Segment);
+    assert!(!l.approx_congruent(&longer, tol));
+    let tilted = Line::from_two_points(vec2![0., 0.], vec2![3., 4.01], LineType::Segment);
+    assert!(!l.approx_congruent(&tilted, tol));
+    let straight = Line::from_two_points(vec2![0., 0.], vec2![3., 4.], LineType::Straight);
+    assert!(!l.approx_congruent(&straight, tol));
+
+    // Straight lines defined by other points on them, in the other direction
+    let other_straight = Line::from_two_points(vec2![6., 8.], vec2![-3., -4.], LineType::Straight);
+    assert!(straight.approx_coincident(&other_straight, tol));
+    let parallel = Line::from_two_points(vec2![6., 8.1], vec2![-3., -3.9], LineType::Straight);
+    assert!(straight.approx_congruent(&parallel, tol));
+    assert!(!straight.approx_coincident(&parallel, tol));
+
+    // Rays are oriented
+    let ray = Line::from_two_points(vec2![0., 0.], vec2![3., 4.], LineType::Ray);
+    let opposite = Line::from_two_points(vec2![0., 0.], vec2![-3., -4.], LineType::Ray);
+    assert!(!ray.approx_congruent(&opposite, tol));
+    let further = Line::from_two_points(vec2![0., 0.], vec2![6., 8.], LineType::Ray);
+    assert!(ray.approx_coincident(&further, tol));
+  }
 }
@@ -3,13 +3,19 @@ mod color;
 #[macro_use]
 mod vector2;
 mod aabb;
+mod arc;
 mod circle;
+mod ellipse;
 mod line;
+mod polygon;
 mod traits;
 
 pub use aabb::*;
+pub use arc::*;
 pub use circle::*;
 pub use color::*;
+pub use ellipse::*;
 pub use line::*;
+pub use polygon::*;
 pub use traits::*;
 pub use vector2::*;
@@ -0,0 +1,90 @@
+use super::{Vector2, AABB};
+
+/// A closed polygon, the last vertex connects back to the first one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+  pub vertices: Vec<Vector2>,
+}
+
+impl Polygon {
+  pub fn new(vertices: Vec<Vector2>) -> Self {
+    Self { vertices }
+  }
+
+  pub fn aabb(&self) -> AABB {
+    let (mut min, mut max) = match self.vertices.first() {
+      Some(first) => (*first, *first),
+      None => return AABB::new(0., 0., 0., 0.),
+    };
+    for v in &self.vertices {
+      min = vec2![min.x.min(v.x), min.y.min(v.y)];
+      max = vec2![max.x.max(v.x), max.y.max(v.y)];
+    }
+    AABB::two_points(min, max)
+  }
+
+  /// Even-odd rule: cast a horizontal ray from `p` and count how many edges it crosses. Works
+  /// for concave and self-intersecting polygons
+  pub fn contains(&self, p: Vector2) -> bool {
+    let n = self.vertices.len();
+    if n < 3 {
+      return false;
+    }
+    let mut inside = false;
+    for i in 0..n {
+      let (a, b) = (self.vertices[i], self.vertices[(i + n - 1) % n]);
+      if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+        inside = !inside;
+      }
+    }
+    inside
+  }
+
+  /// Shoelace formula, the area enclosed whichever way the vertices turn. Self-intersecting
+  /// polygons have their lobes of opposite turning subtracted from each other
+  pub fn area(&self) -> f64 {
+    let n = self.vertices.len();
+    let twice_signed_area: f64 = (0..n)
+      .map(|i| {
+        let (a, b) = (self.vertices[i], self.vertices[(i + 1) % n]);
+        a.x * b.y - b.x * a.y
+      })
+      .sum();
+    twice_signed_area.abs() / 2.0
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_concave_polygon_contains() {
+    // A "U" shape opening upwards (y pointing down as on screen)
+    let polygon = Polygon::new(vec![
+      vec2![0., 0.],
+      vec2![1., 0.],
+      vec2![1., 2.],
+      vec2![2., 2.],
+      vec2![2., 0.],
+      vec2![3., 0.],
+      vec2![3., 3.],
+      vec2![0., 3.],
+    ]);
+    assert!(polygon.contains(vec2![0.5, 1.]));
+    assert!(polygon.contains(vec2![2.5, 1.]));
+    assert!(polygon.contains(vec2![1.5, 2.5]));
+    assert!(!polygon.contains(vec2![1.5, 1.])); // In the notch
+    assert!(!polygon.contains(vec2![-1., 1.]));
+    assert!(!polygon.contains(vec2![1.5, 4.]));
+    assert_eq!(polygon.aabb(), AABB::new(0., 0., 3., 3.));
+    assert_eq!(polygon.area(), 7.);
+  }
+
+  #[test]
+  fn test_degenerate_polygon_contains_nothing() {
+    let polygon = Polygon::new(vec![vec2![0., 0.], vec2![1., 1.]]);
+    assert!(!polygon.contains(vec2![0.5, 0.5]));
+    assert_eq!(polygon.area(), 0.);
+  }
+}
@@ -242,6 +242,119 @@ impl Intersect<Circle> for Circle {
   }
 }
 
+impl Arc {
+  /// Keeps the intersections with the circle of the arc that are on the arc itself
+  fn keep_on_arc(&self, itsct: CircleIntersect) -> CircleIntersect {
+    match itsct {
+      CircleIntersect::TwoPoints(p1, p2) => match (self.contains(p1), self.contains(p2)) {
+        (true, true) => CircleIntersect::TwoPoints(p1, p2),
+        (true, false) => CircleIntersect::OnePoint(p1),
+        (false, true) => CircleIntersect::OnePoint(p2),
+        (false, false) => CircleIntersect::None,
+      },
+      CircleIntersect::OnePoint(p) if self.contains(p) => CircleIntersect::OnePoint(p),
+      _ => CircleIntersect::None,
+    }
+  }
+}
+
+impl Intersect<Line> for Arc {
+  type Output = CircleIntersect;
+
+  fn intersect(self, line: Line) -> Self::Output {
+    self.keep_on_arc(self.circle().intersect(line))
+  }
+}
+
+impl Intersect<Arc> for Line {
+  type Output = CircleIntersect;
+
+  fn intersect(self, arc: Arc) -> Self::Output {
+    arc.intersect(self)
+  }
+}
+
+impl Intersect<Circle> for Arc {
+  type Output = CircleIntersect;
+
+  fn intersect(self, circle: Circle) -> Self::Output {
+    self.keep_on_arc(self.circle().intersect(circle))
+  }
+}
+
+impl Intersect<Arc> for Circle {
+  type Output = CircleIntersect;
+
+  fn intersect(self, arc: Arc) -> Self::Output {
+    arc.intersect(self)
+  }
+}
+
+impl AABB {
+  /// The four sides of the box as segments
+  fn sides(&self) -> Vec<Line> {
+    let corners = [
+      vec2![self.x_min(), self.y_min()],
+      vec2![self.x_max(), self.y_min()],
+      vec2![self.x_max(), self.y_max()],
+      vec2![self.x_min(), self.y_max()],
+    ];
+    (0..4)
+      .map(|i| Line {
+        from: corners[i],
+        to: corners[(i + 1) % 4],
+        line_type: LineType::Segment,
+      })
+      .collect()
+  }
+}
+
+impl Intersect<AABB> for Arc {
+  type Output = Option<()>;
+
+  /// The arc is either crossing a side of the box, or entirely inside when it is not
+  fn intersect(self, aabb: AABB) -> Self::Output {
+    let crossing = aabb
+      .sides()
+      .into_iter()
+      .any(|side| !matches!(self.intersect(side), CircleIntersect::None));
+    if crossing || aabb.contains(self.start_point()) {
+      Some(())
+    } else {
+      None
+    }
+  }
+}
+
+impl Intersect<AABB> for Ellipse {
+  type Output = Option<()>;
+
+  /// Same as the arc, the sides are tested against the unit circle the ellipse is squashed from
+  fn intersect(self, aabb: AABB) -> Self::Output {
+    let unit_circle = Circle {
+      center: vec2![0.0, 0.0],
+      radius: 1.0,
+    };
+    let unsquash = |p: Vector2| {
+      let local = (p - self.center).rotate(-self.rotation);
+      vec2![local.x / self.rx, local.y / self.ry]
+    };
+    let crossing = aabb.sides().into_iter().any(|side| {
+      let side = Line {
+        from: unsquash(side.from),
+        to: unsquash(side.to),
+        line_type: LineType::Segment,
+      };
+      !matches!(unit_circle.intersect(side), CircleIntersect::None)
+    });
+    if crossing || aabb.contains(self.point_at(0.0)) {
+      Some(())
+    } else {
+      None
+    }
+  }
+}
+
 impl Intersect<AABB> for AABB {
   type Output = Option<AABB>;
 
@@ -282,4 +395,55 @@ mod test {
     };
     assert!(l.intersect(aabb) == Some((vec2![-0.5, 0.0], vec2![0.5, 0.0])));
   }
+
+  #[test]
+  fn test_arc_and_ellipse_aabb_intersect() {
+    // The upper half of the unit circle
+    let arc = Arc::from_center_points(vec2![0.0, 0.0], vec2![1.0, 0.0], vec2![-1.0, 0.0]);
+    assert!(arc.intersect(AABB::new(-0.2, 0.9, 0.4, 0.2)).is_some());
+    assert!(arc.intersect(AABB::new(-0.2, -1.1, 0.4, 0.2)).is_none());
+    assert!(arc.intersect(AABB::new(-0.5, -0.5, 1.0, 1.0)).is_none());
+    assert!(arc.intersect(AABB::new(-2.0, -2.0, 4.0, 4.0)).is_some());
+
+    let ellipse = Ellipse {
+      center: vec2![0.0, 0.0],
+      rx: 2.0,
+      ry: 1.0,
+      rotation: std::f64::consts::FRAC_PI_2,
+    };
+    assert!(ellipse.intersect(AABB::new(-0.1, 1.9, 0.2, 0.2)).is_some());
+    assert!(ellipse.intersect(AABB::new(1.9, -0.1, 0.2, 0.2)).is_none());
+    assert!(ellipse.intersect(AABB::new(-0.5, -0.5, 1.0, 1.0)).is_none());
+    assert!(ellipse.intersect(AABB::new(-3.0, -3.0, 6.0, 6.0)).is_some());
+  }
+
+  #[test]
+  fn test_arc_intersections_are_on_the_arc() {
+    // The upper half of the unit circle
+    let arc = Arc::from_center_points(vec2![0.0, 0.0], vec2![1.0, 0.0], vec2![-1.0, 0.0]);
+    let vertical = Line {
+      from: vec2![0.0, -2.0],
+      to: vec2![0.0, 2.0],
+      line_type: LineType::Straight,
+    };
+    match arc.intersect(vertical) {
+      CircleIntersect::OnePoint(p) => assert!((p - vec2![0.0, 1.0]).magnitude() < 1e-10),
+      itsct => panic!("Expected only the upper intersection, got {:?}", itsct),
+    }
+    let below = Line {
+      from: vec2![-2.0, -0.5],
+      to: vec2![2.0, -0.5],
+      line_type: LineType::Straight,
+    };
+    assert!(matches!(arc.intersect(below), CircleIntersect::None));
+
+    let circle = Circle::from_center_point(vec2![0.0, 1.0], vec2![0.0, 0.0]);
+    match circle.intersect(arc) {
+      CircleIntersect::TwoPoints(p1, p2) => {
+        assert!(p1.y > 0.0 && p2.y > 0.0);
+        assert!((p1.magnitude() - 1.0).abs() < 1e-10 && (p2.magnitude() - 1.0).abs() < 1e-10);
+      }
+      itsct => panic!("Expected two intersections, got {:?}", itsct),
+    }
+  }
 }
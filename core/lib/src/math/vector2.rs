@@ -35,6 +35,11 @@ impl Vector2 {
   pub fn is_zero(&self) -> bool {
     self.x == 0.0 && self.y == 0.0
   }
+  /// Counter clockwise rotation around the origin
+  pub fn rotate(self, radians: f64) -> Self {
+    let (sin, cos) = radians.sin_cos();
+    Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+  }
 }
 
 impl Default for Vector2 {
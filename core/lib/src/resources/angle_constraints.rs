@@ -0,0 +1,33 @@
+use specs::prelude::*;
+
+/// Keeps the counter clockwise angle from the direction of `line_a` to the direction of
+/// `line_b` at `radians`, the direction of a line going from its first point to its second
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AngleConstraint {
+  pub line_a: Entity,
+  pub line_b: Entity,
+  pub radians: f64,
+}
+
+/// The angle constraints, at most one per pair of lines
+#[derive(Debug, Default, Clone)]
+pub struct AngleConstraints(Vec<AngleConstraint>);
+
+impl AngleConstraints {
+  /// Adds the constraint, replacing the one already set between the same lines
+  pub fn insert(&mut self, constraint: AngleConstraint) {
+    self.remove(constraint.line_a, constraint.line_b);
+    self.0.push(constraint);
+  }
+
+  pub fn remove(&mut self, line_a: Entity, line_b: Entity) {
+    let pair = |c: &AngleConstraint| (c.line_a, c.line_b);
+    self
+      .0
+      .retain(|c| pair(c) != (line_a, line_b) && pair(c) != (line_b, line_a));
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &AngleConstraint> {
+    self.0.iter()
+  }
+}
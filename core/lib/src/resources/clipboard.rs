@@ -0,0 +1,41 @@
+use crate::utilities::Geometry;
+use specs::prelude::*;
+
+/// The geometries last copied, each one after the ones it depends on. They are kept under the
+/// entities they were copied from, which only tie them together until they get pasted under new
+/// entities
+pub struct Clipboard {
+  geometries: Vec<(Entity, Geometry)>,
+  pastes: usize, // Since the last copy
+}
+
+impl Default for Clipboard {
+  fn default() -> Self {
+    Self {
+      geometries: Vec::new(),
+      pastes: 0,
+    }
+  }
+}
+
+impl Clipboard {
+  pub fn set(&mut self, geometries: Vec<(Entity, Geometry)>) {
+    self.geometries = geometries;
+    self.pastes = 0;
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.geometries.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &(Entity, Geometry)> {
+    self.geometries.iter()
+  }
+
+  /// Count one more paste and return how many there have been since the last copy, so that the
+  /// successive pastes can be offset further and further
+  pub fn next_paste(&mut self) -> usize {
+    self.pastes += 1;
+    self.pastes
+  }
+}
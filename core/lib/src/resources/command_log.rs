@@ -0,0 +1,130 @@
+use crate::{events::*, utilities::*};
+use specs::prelude::*;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct RecordedCommand {
+  pub frame: usize,
+  pub time: Duration, // Relative to the start of the recording
+  pub event: CommandEvent,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedInsertion {
+  pub frame: usize,
+  pub entity: Entity,
+  pub kind: GeometryKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedHistoryEvent {
+  pub frame: usize,
+  pub time: Duration, // Relative to the start of the recording
+  pub event: HistoryEvent,
+}
+
+/// Log of all the commands emitted while recording, together with the entities each frame inserted
+/// so that the commands referring to them can be remapped when replayed into another world.
+/// Disabled by default. The commands emitted by undo and redo are recorded as they are, a log read
+/// from a session file has the history events instead.
+#[derive(Clone)]
+pub struct CommandLog {
+  recording: bool,
+  start: Option<Instant>,
+  frame: usize,
+  commands: Vec<RecordedCommand>,
+  insertions: Vec<RecordedInsertion>,
+  history_events: Vec<RecordedHistoryEvent>,
+}
+
+impl Default for CommandLog {
+  fn default() -> Self {
+    Self {
+      recording: false,
+      start: None,
+      frame: 0,
+      commands: vec![],
+      insertions: vec![],
+      history_events: vec![],
+    }
+  }
+}
+
+impl CommandLog {
+  /// A finished recording, e.g. one read back from a session file
+  pub fn from_records(
+    commands: Vec<RecordedCommand>,
+    insertions: Vec<RecordedInsertion>,
+    history_events: Vec<RecordedHistoryEvent>,
+  ) -> Self {
+    Self {
+      commands,
+      insertions,
+      history_events,
+      ..Self::default()
+    }
+  }
+
+  pub fn is_recording(&self) -> bool {
+    self.recording
+  }
+
+  /// Start a fresh recording, dropping whatever has been recorded before
+  pub fn start_recording(&mut self) {
+    self.clear();
+    self.recording = true;
+    self.start = Some(Instant::now());
+  }
+
+  pub fn stop_recording(&mut self) {
+    self.recording = false;
+  }
+
+  pub fn clear(&mut self) {
+    self.start = None;
+    self.frame = 0;
+    self.commands.clear();
+    self.insertions.clear();
+    self.history_events.clear();
+  }
+
+  pub fn commands(&self) -> &Vec<RecordedCommand> {
+    &self.commands
+  }
+
+  pub fn insertions(&self) -> &Vec<RecordedInsertion> {
+    &self.insertions
+  }
+
+  pub fn history_events(&self) -> &Vec<RecordedHistoryEvent> {
+    &self.history_events
+  }
+
+  pub fn record_command(&mut self, event: CommandEvent) {
+    if self.recording {
+      let time = self.start.map_or(Duration::from_secs(0), |start| start.elapsed());
+      self.commands.push(RecordedCommand {
+        frame: self.frame,
+        time,
+        event,
+      });
+    }
+  }
+
+  pub fn record_insertion(&mut self, entity: Entity, kind: GeometryKind) {
+    if self.recording {
+      self.insertions.push(RecordedInsertion {
+        frame: self.frame,
+        entity,
+        kind,
+      });
+    }
+  }
+
+  /// Move on to the next frame. Should be called once at the end of every recorded frame
+  pub fn end_frame(&mut self) {
+    if self.recording {
+      self.frame += 1;
+    }
+  }
+}
@@ -0,0 +1,55 @@
+use specs::prelude::*;
+
+/// A relation the constraint solver keeps between points or lines, by moving their free points
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Constraint {
+  FixedDistance(Entity, Entity, f64), // Two points and the distance between them
+  FixedAngle(Entity, Entity, f64),    // Two lines and the counter clockwise angle from the first to the second
+  Coincident(Entity, Entity),         // Two points
+  Parallel(Entity, Entity),           // Two lines
+}
+
+impl Constraint {
+  pub fn entities(&self) -> (Entity, Entity) {
+    match self {
+      Constraint::FixedDistance(a, b, _)
+      | Constraint::FixedAngle(a, b, _)
+      | Constraint::Coincident(a, b)
+      | Constraint::Parallel(a, b) => (*a, *b),
+    }
+  }
+
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match self {
+      Constraint::FixedDistance(a, b, distance) => Constraint::FixedDistance(f(*a), f(*b), *distance),
+      Constraint::FixedAngle(a, b, radians) => Constraint::FixedAngle(f(*a), f(*b), *radians),
+      Constraint::Coincident(a, b) => Constraint::Coincident(f(*a), f(*b)),
+      Constraint::Parallel(a, b) => Constraint::Parallel(f(*a), f(*b)),
+    }
+  }
+}
+
+/// The constraints solved numerically after the symbolic solver, in the order they got added
+#[derive(Debug, Default, Clone)]
+pub struct Constraints(Vec<Constraint>);
+
+impl Constraints {
+  /// Adds the constraint unless it is there already
+  pub fn insert(&mut self, constraint: Constraint) {
+    if !self.0.contains(&constraint) {
+      self.0.push(constraint);
+    }
+  }
+
+  pub fn remove(&mut self, constraint: &Constraint) {
+    self.0.retain(|c| c != constraint);
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Constraint> {
+    self.0.iter()
+  }
+}
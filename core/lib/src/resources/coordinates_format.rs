@@ -0,0 +1,19 @@
+use crate::utilities::*;
+
+/// How the coordinates of the points showing them are formatted
+#[derive(Debug, Copy, Clone)]
+pub struct CoordinatesFormat {
+  pub decimals: usize,
+}
+
+impl Default for CoordinatesFormat {
+  fn default() -> Self {
+    Self { decimals: 2 }
+  }
+}
+
+impl CoordinatesFormat {
+  pub fn format(&self, VirtualPosition(p): VirtualPosition) -> String {
+    format!("({:.*}, {:.*})", self.decimals, p.x, self.decimals, p.y)
+  }
+}
@@ -1,11 +1,13 @@
+use crate::utilities::ScalarId;
 use specs::prelude::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-pub struct DependencyGraph(HashMap<Entity, HashSet<Entity>>);
+/// The entities depending on each entity, and the ones depending on each scalar
+pub struct DependencyGraph(HashMap<Entity, HashSet<Entity>>, HashMap<ScalarId, HashSet<Entity>>);
 
 impl Default for DependencyGraph {
   fn default() -> Self {
-    Self(HashMap::new())
+    Self(HashMap::new(), HashMap::new())
   }
 }
 
@@ -24,6 +26,21 @@ impl DependencyGraph {
     });
   }
 
+  pub fn add_scalar_dependent(&mut self, scalar: &ScalarId, child: &Entity) {
+    self.1.entry(*scalar).or_insert(HashSet::new()).insert(*child);
+  }
+
+  pub fn remove_scalar_dependent(&mut self, scalar: &ScalarId, child: &Entity) {
+    self.1.entry(*scalar).and_modify(|set| {
+      set.remove(child);
+    });
+  }
+
+  /// Whether there's no dependency left between any of the entities
+  pub fn is_empty(&self) -> bool {
+    self.0.values().all(HashSet::is_empty) && self.1.values().all(HashSet::is_empty)
+  }
+
   pub fn get_direct_dependents(&self, parent: &Entity) -> Option<&HashSet<Entity>> {
     self.0.get(parent)
   }
@@ -44,4 +61,205 @@ impl DependencyGraph {
 
     result
   }
+
+  /// Get all the dependents of the scalar, the ones using it directly or not
+  pub fn get_scalar_dependents(&self, scalar: &ScalarId) -> HashSet<Entity> {
+    let mut result = HashSet::new();
+    if let Some(children) = self.1.get(scalar) {
+      for child in children {
+        result.extend(self.get_all_dependents(child));
+      }
+    }
+    result
+  }
+
+  /// Get all the entities the child depends on, directly or not, including child itself
+  pub fn get_all_ancestors(&self, child: &Entity) -> HashSet<Entity> {
+    let mut result: HashSet<Entity> = HashSet::new();
+    let mut stack: Vec<Entity> = vec![*child];
+
+    while let Some(ancestor) = stack.pop() {
+      if result.insert(ancestor) {
+        for (parent, children) in &self.0 {
+          if children.contains(&ancestor) {
+            stack.push(*parent);
+          }
+        }
+      }
+    }
+
+    result
+  }
+
+  /// The dependencies among the given entities only, the ones from or to any other entity are
+  /// left out
+  pub fn subgraph(&self, ents: &HashSet<Entity>) -> DependencyGraph {
+    let mut subgraph = DependencyGraph::default();
+    for (parent, children) in self.0.iter().filter(|(parent, _)| ents.contains(parent)) {
+      for child in children.iter().filter(|child| ents.contains(child)) {
+        subgraph.add(parent, child);
+      }
+    }
+    subgraph
+  }
+
+  /// The given entities ordered so that each one comes after the ones it depends on among them,
+  /// the entities that can come in any order are sorted by id
+  pub fn dependency_order(&self, ents: &HashSet<Entity>) -> Vec<Entity> {
+    // Looked up from the given entities rather than the whole graph, so that ordering a few of
+    // them stays cheap in a large sketch
+    let dependents = |ent: &Entity| {
+      self
+        .0
+        .get(ent)
+        .into_iter()
+        .flatten()
+        .filter(|child| ents.contains(child))
+    };
+    let mut parent_counts: HashMap<Entity, usize> = ents.iter().map(|ent| (*ent, 0)).collect();
+    for child in ents.iter().flat_map(dependents) {
+      *parent_counts.entry(*child).or_insert(0) += 1;
+    }
+
+    let mut ready: BTreeSet<Entity> = parent_counts
+      .iter()
+      .filter(|(_, count)| **count == 0)
+      .map(|(ent, _)| *ent)
+      .collect();
+    let mut order = Vec::with_capacity(ents.len());
+    while let Some(ent) = ready.iter().next().copied() {
+      ready.remove(&ent);
+      order.push(ent);
+      for child in dependents(&ent) {
+        if let Some(count) = parent_counts.get_mut(child) {
+          *count -= 1;
+          if *count == 0 {
+            ready.insert(*child);
+          }
+        }
+      }
+    }
+    order
+  }
+
+  /// The given entities split into the parts sharing no ancestor among them, so that each part
+  /// can be solved apart from the others. Each part is in dependency order, the parts are sorted
+  /// by their first entity
+  pub fn independent_parts(&self, ents: &HashSet<Entity>) -> Vec<Vec<Entity>> {
+    // Each entity is joined to the part of every dependent it has among the entities
+    let mut roots: HashMap<Entity, Entity> = ents.iter().map(|ent| (*ent, *ent)).collect();
+    for parent in ents {
+      for child in self
+        .0
+        .get(parent)
+        .into_iter()
+        .flatten()
+        .filter(|child| ents.contains(child))
+      {
+        let (parent_root, child_root) = (find_root(&mut roots, *parent), find_root(&mut roots, *child));
+        if parent_root != child_root {
+          roots.insert(parent_root.max(child_root), parent_root.min(child_root));
+        }
+      }
+    }
+
+    let mut parts: BTreeMap<Entity, Vec<Entity>> = BTreeMap::new();
+    for ent in self.dependency_order(ents) {
+      parts.entry(find_root(&mut roots, ent)).or_default().push(ent);
+    }
+    let mut parts: Vec<Vec<Entity>> = parts.into_values().collect();
+    parts.sort_by_key(|part| part[0]);
+    parts
+  }
+
+  /// Export the graph in Graphviz DOT format. Every node is given with its label, edges go from
+  /// the parent to the dependent
+  pub fn to_dot(&self, nodes: &[(Entity, String)]) -> String {
+    let mut nodes = nodes.to_vec();
+    nodes.sort_by_key(|(ent, _)| ent.id());
+    let mut edges: Vec<(Entity, Entity)> = self
+      .0
+      .iter()
+      .flat_map(|(parent, children)| children.iter().map(move |child| (*parent, *child)))
+      .collect();
+    edges.sort_by_key(|(parent, child)| (parent.id(), child.id()));
+
+    let mut dot = String::from("digraph dependencies {\n");
+    for (ent, label) in nodes {
+      dot += &format!("  e{} [label=\"{}\"];\n", ent.id(), label);
+    }
+    for (parent, child) in edges {
+      dot += &format!("  e{} -> e{};\n", parent.id(), child.id());
+    }
+    dot += "}\n";
+    dot
+  }
+}
+
+/// The entity standing for the part of the given one, every entity on the way being pointed to it
+fn find_root(roots: &mut HashMap<Entity, Entity>, ent: Entity) -> Entity {
+  let mut root = ent;
+  while roots[&root] != root {
+    root = roots[&root];
+  }
+  let mut ent = ent;
+  while ent != root {
+    ent = roots.insert(ent, root).unwrap();
+  }
+  root
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_subgraph_keeps_the_dependencies_among_the_entities() {
+    let mut world = World::new();
+    let ents: Vec<Entity> = (0..5).map(|_| world.create_entity().build()).collect();
+    let (a, b, c, d, e) = (ents[0], ents[1], ents[2], ents[3], ents[4]);
+    // a and b define c, c and d define e
+    let mut graph = DependencyGraph::default();
+    graph.add(&a, &c);
+    graph.add(&b, &c);
+    graph.add(&c, &e);
+    graph.add(&d, &e);
+
+    let kept: HashSet<Entity> = [e, c, a].iter().cloned().collect();
+    let subgraph = graph.subgraph(&kept);
+    assert_eq!(subgraph.get_all_dependents(&a), [a, c, e].iter().cloned().collect());
+    assert!(subgraph.get_direct_dependents(&b).is_none());
+    assert!(subgraph.get_direct_dependents(&d).is_none());
+    assert_eq!(graph.dependency_order(&kept), vec![a, c, e]);
+    assert_eq!(graph.get_all_ancestors(&e), ents.iter().cloned().collect());
+    assert_eq!(graph.get_all_ancestors(&c), [a, b, c].iter().cloned().collect());
+
+    // Without c, nothing ties a to e anymore
+    let kept: HashSet<Entity> = [e, a, d].iter().cloned().collect();
+    assert!(graph.subgraph(&kept).get_direct_dependents(&a).is_none());
+    assert_eq!(graph.dependency_order(&kept), vec![a, d, e]);
+  }
+
+  #[test]
+  fn test_independent_parts_share_no_ancestor() {
+    let mut world = World::new();
+    let ents: Vec<Entity> = (0..7).map(|_| world.create_entity().build()).collect();
+    let (a, b, c, d, e, f, g) = (ents[0], ents[1], ents[2], ents[3], ents[4], ents[5], ents[6]);
+    // a defines c and d, b defines d. e defines f, g is alone
+    let mut graph = DependencyGraph::default();
+    graph.add(&a, &c);
+    graph.add(&a, &d);
+    graph.add(&b, &d);
+    graph.add(&e, &f);
+
+    let all: HashSet<Entity> = ents.iter().cloned().collect();
+    assert_eq!(
+      graph.independent_parts(&all),
+      vec![vec![a, b, c, d], vec![e, f], vec![g]]
+    );
+
+    // Without a, nothing ties c to b and d anymore
+    let kept: HashSet<Entity> = [b, c, d, f].iter().cloned().collect();
+    assert_eq!(graph.independent_parts(&kept), vec![vec![b, d], vec![c], vec![f]]);
+  }
 }
@@ -0,0 +1,4 @@
+use crate::utilities::Quadtree;
+use specs::prelude::*;
+
+pub type EntityQuadtree = Quadtree<Entity>;
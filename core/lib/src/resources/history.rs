@@ -1,63 +1,260 @@
-use crate::{components::symbolics::SymbolicPoint, utilities::Geometry};
+use crate::{
+  components::{
+    symbolics::{SymbolicLine, SymbolicPoint},
+    underlays::UnderlayPlacement,
+  },
+  utilities::{Geometry, Style},
+};
 use specs::prelude::*;
 use std::collections::{HashMap, HashSet};
 
 pub enum Modification {
   RemoveMany(HashMap<Entity, Geometry>),
   InsertMany(HashMap<Entity, Geometry>),
-  UpdatePoint(Entity, SymbolicPoint, SymbolicPoint), // Entity, old, new
+  UpdatePointMany(HashMap<Entity, (SymbolicPoint, SymbolicPoint)>), // Entity to old, new
+  UpdateLine(Entity, SymbolicLine, SymbolicLine),                   // Entity, old, new
+  UpdateSlider(Entity, f64, f64),                                   // Entity, old value, new value
+  UpdateUnderlay(Entity, UnderlayPlacement, UnderlayPlacement),     // Entity, old, new
   HideMany(HashSet<Entity>),
   UnhideMany(HashSet<Entity>),
+  RestyleMany(HashMap<Entity, (Style, Style)>), // Entity to old, new
+  RenameMany(HashMap<Entity, (Option<String>, Option<String>)>), // Entity to old, new
+  Transaction(String, Vec<Modification>),       // Label, modifications in the order they happened
 }
 
+impl Modification {
+  /// Short description of the modification, e.g. for listing the history
+  pub fn label(&self) -> String {
+    match self {
+      Modification::RemoveMany(_) => "Remove".to_string(),
+      Modification::InsertMany(_) => "Insert".to_string(),
+      Modification::UpdatePointMany(_) => "Move".to_string(),
+      Modification::UpdateLine(_, _, _) => "Change line type".to_string(),
+      Modification::UpdateSlider(_, _, _) => "Change slider".to_string(),
+      Modification::UpdateUnderlay(_, _, _) => "Change underlay".to_string(),
+      Modification::HideMany(_) => "Hide".to_string(),
+      Modification::UnhideMany(_) => "Unhide".to_string(),
+      Modification::RestyleMany(_) => "Restyle".to_string(),
+      Modification::RenameMany(_) => "Rename".to_string(),
+      Modification::Transaction(label, _) => label.clone(),
+    }
+  }
+}
+
+/// Identifies a state of the history tree, the root being the state before any modification
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NodeId(pub usize);
+
+struct HistoryNode {
+  parent: Option<NodeId>,
+  children: Vec<NodeId>,              // In the order they got created
+  modification: Option<Modification>, // Leading from the parent to this node, none for the root
+  redo_child: Option<NodeId>,         // The child visited last, the one redo goes to
+}
+
+/// The modifications as a tree of states. Modifying after an undo starts a new branch instead of
+/// dropping the undone modifications, and any state of the tree can be jumped to
 pub struct History {
-  history: Vec<Modification>,
-  cursor: usize,
-  head: usize,
+  nodes: Vec<HistoryNode>,
+  current: NodeId,
+  transaction: Option<(String, Vec<Modification>)>,
+  transaction_depth: usize,
 }
 
 impl Default for History {
   fn default() -> Self {
     Self {
-      history: Vec::new(),
-      cursor: 0,
-      head: 0,
+      nodes: vec![HistoryNode {
+        parent: None,
+        children: vec![],
+        modification: None,
+        redo_child: None,
+      }],
+      current: NodeId(0),
+      transaction: None,
+      transaction_depth: 0,
     }
   }
 }
 
 impl History {
+  /// Identifies the current state, the viewport history stamps its changes with it
+  pub fn cursor(&self) -> usize {
+    self.current.0
+  }
+
   pub fn clear(&mut self) {
-    self.history.clear();
-    self.cursor = 0;
-    self.head = 0;
+    *self = Self::default();
   }
 
-  pub fn undo(&mut self) -> Option<&Modification> {
-    if self.cursor > 0 {
-      self.cursor -= 1;
-      Some(&self.history[self.cursor])
-    } else {
-      None
+  /// From now on the modifications are grouped into a single step, undone and redone at once.
+  /// A transaction begun inside another one is part of the outer one, keeping its label
+  pub fn begin_transaction(&mut self, label: String) {
+    if self.transaction_depth == 0 {
+      self.transaction = Some((label, vec![]));
     }
+    self.transaction_depth += 1;
   }
 
-  pub fn redo(&mut self) -> Option<&Modification> {
-    if self.cursor < self.head {
-      self.cursor += 1;
-      Some(&self.history[self.cursor - 1])
-    } else {
-      None
+  /// Ends the transaction, which becomes a step of the history once the outermost one ends
+  pub fn end_transaction(&mut self) {
+    if self.transaction_depth == 0 {
+      return;
+    }
+    self.transaction_depth -= 1;
+    if self.transaction_depth == 0 {
+      if let Some((label, modifications)) = self.transaction.take() {
+        if !modifications.is_empty() {
+          self.push(Modification::Transaction(label, modifications));
+        }
+      }
     }
   }
 
+  /// The label of the transaction that the next undo would revert
+  pub fn undo_label(&self) -> Option<&str> {
+    match self.modification(self.current) {
+      Some(Modification::Transaction(label, _)) => Some(label),
+      _ => None,
+    }
+  }
+
+  pub fn undo(&mut self) -> Option<&Modification> {
+    let current = self.current;
+    let parent = self.nodes[current.0].parent?;
+    self.nodes[parent.0].redo_child = Some(current);
+    self.current = parent;
+    self.nodes[current.0].modification.as_ref()
+  }
+
+  /// Redoes along the branch visited last
+  pub fn redo(&mut self) -> Option<&Modification> {
+    let child = self.nodes[self.current.0].redo_child?;
+    self.current = child;
+    self.nodes[child.0].modification.as_ref()
+  }
+
+  /// Adds the modification as a new child of the current state, the other branches are kept
   pub fn push(&mut self, event: Modification) {
-    if self.cursor < self.history.len() {
-      self.history[self.cursor] = event;
-    } else {
-      self.history.push(event);
+    if let Some((_, modifications)) = &mut self.transaction {
+      modifications.push(event);
+      return;
+    }
+    let node = NodeId(self.nodes.len());
+    self.nodes.push(HistoryNode {
+      parent: Some(self.current),
+      children: vec![],
+      modification: Some(event),
+      redo_child: None,
+    });
+    let current = &mut self.nodes[self.current.0];
+    current.children.push(node);
+    current.redo_child = Some(node);
+    self.current = node;
+  }
+
+  /// Moves to the node through their closest common ancestor. Gives back the nodes whose
+  /// modifications are to be undone and then the ones to be redone, in order, `None` when there
+  /// is no such node
+  pub fn jump_to(&mut self, target: NodeId) -> Option<(Vec<NodeId>, Vec<NodeId>)> {
+    if target.0 >= self.nodes.len() {
+      return None;
+    }
+    let target_path = self.path_from_root(target);
+    let mut undone = vec![];
+    let mut node = self.current;
+    while !target_path.contains(&node) {
+      undone.push(node);
+      node = self.nodes[node.0].parent?;
     }
-    self.cursor += 1;
-    self.head = self.cursor;
+    let redone = target_path
+      .into_iter()
+      .skip_while(|ancestor| *ancestor != node)
+      .skip(1)
+      .collect::<Vec<_>>();
+
+    // Later redos follow the path that got jumped along
+    for undone_node in &undone {
+      if let Some(parent) = self.nodes[undone_node.0].parent {
+        self.nodes[parent.0].redo_child = Some(*undone_node);
+      }
+    }
+    for redone_node in &redone {
+      if let Some(parent) = self.nodes[redone_node.0].parent {
+        self.nodes[parent.0].redo_child = Some(*redone_node);
+      }
+    }
+    self.current = target;
+    Some((undone, redone))
+  }
+
+  pub fn root(&self) -> NodeId {
+    NodeId(0)
+  }
+
+  pub fn current(&self) -> NodeId {
+    self.current
+  }
+
+  pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+    self.nodes.get(node.0).and_then(|node| node.parent)
+  }
+
+  pub fn children(&self, node: NodeId) -> &[NodeId] {
+    self.nodes.get(node.0).map_or(&[], |node| &node.children)
+  }
+
+  /// The modification leading to the node from its parent
+  pub fn modification(&self, node: NodeId) -> Option<&Modification> {
+    self.nodes.get(node.0).and_then(|node| node.modification.as_ref())
+  }
+
+  /// The nodes from the root down to the node, both included
+  pub fn path_from_root(&self, node: NodeId) -> Vec<NodeId> {
+    let mut path = vec![node];
+    while let Some(parent) = self.parent(*path.last().unwrap()) {
+      path.push(parent);
+    }
+    path.reverse();
+    path
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn hide(world: &mut World) -> Modification {
+    let mut entities = HashSet::new();
+    entities.insert(world.create_entity().build());
+    Modification::HideMany(entities)
+  }
+
+  #[test]
+  fn test_edits_after_undo_branch_off() {
+    let mut world = World::new();
+    let mut history = History::default();
+    history.push(hide(&mut world));
+    let a = history.current();
+    history.push(hide(&mut world));
+    let b = history.current();
+    assert!(history.undo().is_some());
+    history.push(hide(&mut world));
+    let c = history.current();
+
+    // Both branches are kept, redo follows the last one
+    assert_eq!(history.children(a), &[b, c]);
+    assert!(history.undo().is_some());
+    assert!(history.redo().is_some());
+    assert_eq!(history.current(), c);
+
+    // Jumping goes up to the common ancestor and down the other branch
+    assert_eq!(history.jump_to(b), Some((vec![c], vec![b])));
+    assert_eq!(history.path_from_root(b), vec![history.root(), a, b]);
+    assert_eq!(history.jump_to(history.root()), Some((vec![b, a], vec![])));
+    assert!(history.redo().is_some());
+    assert!(history.redo().is_some());
+    assert_eq!(history.current(), b);
+    assert_eq!(history.jump_to(NodeId(10)), None);
   }
 }
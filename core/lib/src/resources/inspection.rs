@@ -0,0 +1,104 @@
+use crate::{
+  components::{markers::*, symbolics::*},
+  events::*,
+  math::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// The description of a selected geometry, for the frontends to show in a property panel
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedGeometry {
+  pub entity: Entity,
+  pub name: Option<String>,
+  pub geometry: Geometry,                    // Its definition and its style
+  pub coordinates: Vec<(&'static str, f64)>, // As solved, e.g. `x` and `y` of a point, none while undefined
+  pub unsolvable: Option<UnsolvableReason>,  // Why it is undefined, if it is
+}
+
+impl InspectedGeometry {
+  pub fn kind(&self) -> GeometryKind {
+    self.geometry.kind()
+  }
+
+  /// The geometries it is directly defined by
+  pub fn parents(&self) -> Vec<Entity> {
+    self.geometry.dependencies()
+  }
+
+  /// The numbers in the definition of a point that can be edited, e.g. the `ratio` of a ratio point
+  pub fn parameters(&self) -> Vec<(&'static str, f64)> {
+    match self.geometry {
+      Geometry::Point(sym_point, _) => match sym_point {
+        SymbolicPoint::Fixed(position) | SymbolicPoint::Free(position) => {
+          vec![("x", position.0.x), ("y", position.0.y)]
+        }
+        SymbolicPoint::Ratio(_, _, ratio) => vec![("ratio", ratio)],
+        SymbolicPoint::OnLine(_, t) => vec![("t", t.0)],
+        SymbolicPoint::OnCircle(_, theta) => vec![("theta", theta)],
+        SymbolicPoint::OnConic(_, t) => vec![("t", t)],
+        SymbolicPoint::Rotate(_, _, radians) => vec![("angle", radians)],
+        SymbolicPoint::Translate(_, vector) => vec![("dx", vector.0.x), ("dy", vector.0.y)],
+        _ => vec![],
+      },
+      _ => vec![],
+    }
+  }
+
+  /// The commands setting one of the parameters to `value`, to be sent back by the frontends. None
+  /// when the geometry has no such parameter
+  pub fn edit(&self, parameter: &str, value: f64) -> Option<Vec<Command>> {
+    let old_sym_point = match self.geometry {
+      Geometry::Point(sym_point, _) => sym_point,
+      _ => return None,
+    };
+    let new_sym_point = match (old_sym_point, parameter) {
+      (SymbolicPoint::Fixed(position), "x") => SymbolicPoint::Fixed(VirtualPosition(vec2![value, position.0.y])),
+      (SymbolicPoint::Fixed(position), "y") => SymbolicPoint::Fixed(VirtualPosition(vec2![position.0.x, value])),
+      (SymbolicPoint::Free(position), "x") => SymbolicPoint::Free(VirtualPosition(vec2![value, position.0.y])),
+      (SymbolicPoint::Free(position), "y") => SymbolicPoint::Free(VirtualPosition(vec2![position.0.x, value])),
+      (SymbolicPoint::Ratio(p1, p2, _), "ratio") => SymbolicPoint::Ratio(p1, p2, value),
+      (SymbolicPoint::OnLine(line, _), "t") => SymbolicPoint::OnLine(line, value.into()),
+      (SymbolicPoint::OnCircle(circle, _), "theta") => SymbolicPoint::OnCircle(circle, value),
+      (SymbolicPoint::OnConic(conic, _), "t") => SymbolicPoint::OnConic(conic, value),
+      (SymbolicPoint::Rotate(p, center, _), "angle") => SymbolicPoint::Rotate(p, center, value),
+      (SymbolicPoint::Translate(p, vector), "dx") => {
+        SymbolicPoint::Translate(p, VirtualPosition(vec2![value, vector.0.y]))
+      }
+      (SymbolicPoint::Translate(p, vector), "dy") => {
+        SymbolicPoint::Translate(p, VirtualPosition(vec2![vector.0.x, value]))
+      }
+      _ => return None,
+    };
+    // Like a finished drag, so that the descendants are solved again and the edit can be undone
+    Some(vec![
+      Command::Update(UpdateEvent::UpdatePoint(self.entity, old_sym_point, new_sym_point)),
+      Command::Update(UpdateEvent::UpdatePointEnd(self.entity, old_sym_point, new_sym_point)),
+    ])
+  }
+}
+
+/// The descriptions of the selected geometries, in the order they got selected
+pub struct Inspection(Vec<InspectedGeometry>);
+
+impl Default for Inspection {
+  fn default() -> Self {
+    Self(vec![])
+  }
+}
+
+impl Inspection {
+  pub fn get(&self) -> &[InspectedGeometry] {
+    &self.0
+  }
+
+  /// Replaces the descriptions, returns whether they changed
+  pub fn set(&mut self, inspected: Vec<InspectedGeometry>) -> bool {
+    if self.0 == inspected {
+      false
+    } else {
+      self.0 = inspected;
+      true
+    }
+  }
+}
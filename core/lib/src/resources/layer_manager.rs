@@ -0,0 +1,99 @@
+use crate::components::markers::Layer;
+use std::collections::BTreeMap;
+
+pub static DEFAULT_LAYER: Layer = Layer(0);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerInfo {
+  pub name: String,
+  pub hidden: bool, // The entities on the layer are not drawn, selected nor snapped to
+  pub locked: bool, // The entities on the layer cannot be dragged nor removed
+}
+
+impl LayerInfo {
+  fn new(name: String) -> Self {
+    Self {
+      name,
+      hidden: false,
+      locked: false,
+    }
+  }
+}
+
+/// The layers of the sketch by their id. The default layer always exists, and is the layer of all
+/// the entities without a `Layer` component
+#[derive(Debug)]
+pub struct LayerManager {
+  layers: BTreeMap<u32, LayerInfo>,
+  next_id: u32,
+}
+
+impl Default for LayerManager {
+  fn default() -> Self {
+    let mut layers = BTreeMap::new();
+    layers.insert(DEFAULT_LAYER.0, LayerInfo::new("Default".to_string()));
+    Self {
+      layers,
+      next_id: DEFAULT_LAYER.0 + 1,
+    }
+  }
+}
+
+impl LayerManager {
+  pub fn create(&mut self, name: String) -> Layer {
+    let id = self.next_id;
+    self.next_id += 1;
+    self.layers.insert(id, LayerInfo::new(name));
+    Layer(id)
+  }
+
+  pub fn get(&self, layer: Layer) -> Option<&LayerInfo> {
+    self.layers.get(&layer.0)
+  }
+
+  pub fn get_mut(&mut self, layer: Layer) -> Option<&mut LayerInfo> {
+    self.layers.get_mut(&layer.0)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (Layer, &LayerInfo)> {
+    self.layers.iter().map(|(id, info)| (Layer(*id), info))
+  }
+
+  /// Whether the layer of an entity is hidden, given its `Layer` component if it has one
+  pub fn is_hidden(&self, layer: Option<&Layer>) -> bool {
+    self
+      .get(*layer.unwrap_or(&DEFAULT_LAYER))
+      .is_some_and(|info| info.hidden)
+  }
+
+  /// Whether the layer of an entity is locked, given its `Layer` component if it has one
+  pub fn is_locked(&self, layer: Option<&Layer>) -> bool {
+    self
+      .get(*layer.unwrap_or(&DEFAULT_LAYER))
+      .is_some_and(|info| info.locked)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_entities_without_layer_are_on_the_default_layer() {
+    let mut layer_manager = LayerManager::default();
+    let layer = layer_manager.create("Construction".to_string());
+    assert_ne!(layer, DEFAULT_LAYER);
+    assert_eq!(layer_manager.iter().count(), 2);
+
+    layer_manager.get_mut(DEFAULT_LAYER).unwrap().locked = true;
+    assert!(layer_manager.is_locked(None));
+    assert!(!layer_manager.is_locked(Some(&layer)));
+
+    layer_manager.get_mut(layer).unwrap().hidden = true;
+    assert!(layer_manager.is_hidden(Some(&layer)));
+    assert!(!layer_manager.is_hidden(None));
+
+    // Unknown layers are neither hidden nor locked
+    assert!(!layer_manager.is_locked(Some(&Layer(42))));
+  }
+}
@@ -0,0 +1,20 @@
+use super::Viewport;
+use crate::math::*;
+
+/// How far, in pixels, lines and rays are drawn past the border of the screen. Without it the
+/// end of a thick line shows at the very border of the screen, as if it was cut off
+#[derive(Debug, Copy, Clone)]
+pub struct LineClipMargin(pub f64);
+
+impl Default for LineClipMargin {
+  fn default() -> Self {
+    Self(10.0)
+  }
+}
+
+impl LineClipMargin {
+  /// The screen grown by the margin, to clip the lines to
+  pub fn clip_aabb(&self, viewport: &Viewport) -> AABB {
+    viewport.screen_aabb().expand(self.0)
+  }
+}
@@ -0,0 +1,230 @@
+use crate::utilities::{Geometry, GeometryKind};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MacroId(pub usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroError {
+  NoInputs,
+  NoOutputs,
+  NotDeterminedByInputs(Entity), // Geometry the outputs depend on, which does not follow from the inputs
+  UnknownMacro(MacroId),
+  WrongInputs(MacroId), // Not as many inputs as the macro has, or not of the same kinds
+}
+
+/// A construction recorded as a template, from its inputs to its outputs through the intermediate
+/// geometries. The template keeps the entities it was recorded from, which only tie the geometries
+/// together until they get instantiated under new entities
+#[derive(Debug, Clone)]
+pub struct ConstructionMacro {
+  pub name: String,
+  inputs: Vec<(Entity, GeometryKind)>, // In the order they are to be given
+  steps: Vec<(Entity, Geometry)>,      // Each one after the ones it depends on
+  outputs: Vec<Entity>,
+}
+
+impl ConstructionMacro {
+  /// The steps are to depend only on the inputs and on the steps before them, a step standing on
+  /// its own, e.g. a free point, is to be one of the inputs instead
+  pub fn new(
+    name: String,
+    inputs: Vec<(Entity, GeometryKind)>,
+    steps: Vec<(Entity, Geometry)>,
+    outputs: Vec<Entity>,
+  ) -> Result<Self, MacroError> {
+    if inputs.is_empty() {
+      return Err(MacroError::NoInputs);
+    }
+    if outputs.is_empty() {
+      return Err(MacroError::NoOutputs);
+    }
+    for (i, (step, geometry)) in steps.iter().enumerate() {
+      let known =
+        |ent: &Entity| inputs.iter().any(|(input, _)| input == ent) || steps[..i].iter().any(|(step, _)| step == ent);
+      let dependencies = geometry.dependencies();
+      if dependencies.is_empty() {
+        return Err(MacroError::NotDeterminedByInputs(*step));
+      }
+      if let Some(unknown) = dependencies.into_iter().find(|ent| !known(ent)) {
+        return Err(MacroError::NotDeterminedByInputs(unknown));
+      }
+    }
+    Ok(Self {
+      name,
+      inputs,
+      steps,
+      outputs,
+    })
+  }
+
+  pub fn input_kinds(&self) -> Vec<GeometryKind> {
+    self.inputs.iter().map(|(_, kind)| *kind).collect()
+  }
+
+  /// The steps of the construction applied to the given inputs, each under an entity from
+  /// `create` and marked as an output or not, in the order they are to be inserted. `None` when
+  /// the inputs are not as many or not of the same kinds as the ones of the macro
+  pub fn instantiate<F: FnMut() -> Entity>(
+    &self,
+    inputs: &[(Entity, GeometryKind)],
+    mut create: F,
+  ) -> Option<Vec<(Entity, Geometry, bool)>> {
+    let matching = inputs.len() == self.inputs.len()
+      && inputs
+        .iter()
+        .zip(&self.inputs)
+        .all(|((_, kind), (_, expected))| kind == expected);
+    if !matching {
+      return None;
+    }
+
+    let mut substitutions = self
+      .inputs
+      .iter()
+      .zip(inputs)
+      .map(|((template, _), (ent, _))| (*template, *ent))
+      .collect::<HashMap<_, _>>();
+    let mut instances = vec![];
+    for (template, geometry) in &self.steps {
+      let geometry = geometry.remap(&mut |dependency| substitutions[&dependency]);
+      let ent = create();
+      substitutions.insert(*template, ent);
+      instances.push((ent, geometry, self.outputs.contains(template)));
+    }
+    Some(instances)
+  }
+}
+
+/// The macros defined so far, each one usable as a tool. A macro defined again under the same name
+/// replaces the former one and keeps its id
+pub struct MacroRegistry {
+  macros: Vec<(MacroId, ConstructionMacro)>,
+  next_id: usize,
+}
+
+impl Default for MacroRegistry {
+  fn default() -> Self {
+    Self {
+      macros: Vec::new(),
+      next_id: 0,
+    }
+  }
+}
+
+impl MacroRegistry {
+  pub fn register(&mut self, construction_macro: ConstructionMacro) -> MacroId {
+    if let Some((id, existing)) = self
+      .macros
+      .iter_mut()
+      .find(|(_, existing)| existing.name == construction_macro.name)
+    {
+      *existing = construction_macro;
+      return *id;
+    }
+    let id = MacroId(self.next_id);
+    self.next_id += 1;
+    self.macros.push((id, construction_macro));
+    id
+  }
+
+  pub fn remove(&mut self, id: MacroId) -> Option<ConstructionMacro> {
+    let i = self.macros.iter().position(|(other, _)| *other == id)?;
+    Some(self.macros.remove(i).1)
+  }
+
+  pub fn get(&self, id: MacroId) -> Option<&ConstructionMacro> {
+    self
+      .macros
+      .iter()
+      .find(|(other, _)| *other == id)
+      .map(|(_, construction_macro)| construction_macro)
+  }
+
+  pub fn by_name(&self, name: &str) -> Option<MacroId> {
+    self
+      .macros
+      .iter()
+      .find(|(_, construction_macro)| construction_macro.name == name)
+      .map(|(id, _)| *id)
+  }
+
+  pub fn len(&self) -> usize {
+    self.macros.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.macros.is_empty()
+  }
+
+  /// In the order they got defined
+  pub fn iter(&self) -> impl Iterator<Item = &(MacroId, ConstructionMacro)> {
+    self.macros.iter()
+  }
+
+  /// Instantiates the macro on the given inputs, see `ConstructionMacro::instantiate`
+  pub fn instantiate<F: FnMut() -> Entity>(
+    &self,
+    id: MacroId,
+    inputs: &[(Entity, GeometryKind)],
+    create: F,
+  ) -> Result<Vec<(Entity, Geometry, bool)>, MacroError> {
+    self
+      .get(id)
+      .ok_or(MacroError::UnknownMacro(id))?
+      .instantiate(inputs, create)
+      .ok_or(MacroError::WrongInputs(id))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, resources::*};
+
+  #[test]
+  fn test_instantiate_on_new_inputs() {
+    let mut world = World::new();
+    let mut create = || world.create_entity().build();
+    let (a, b, m, l) = (create(), create(), create(), create());
+    let point_style = DefaultPointStyle::default().get();
+    let line_style = DefaultLineStyle::default().get();
+    let bisector = ConstructionMacro::new(
+      "Bisector".to_string(),
+      vec![(a, GeometryKind::Point), (b, GeometryKind::Point)],
+      vec![
+        (m, Geometry::Point(SymbolicPoint::MidPoint(a, b), point_style)),
+        (l, Geometry::Line(SymbolicLine::PerpendicularBisector(a, b), line_style)),
+      ],
+      vec![l],
+    )
+    .unwrap();
+    let mut registry = MacroRegistry::default();
+    let id = registry.register(bisector);
+
+    let (c, d) = (create(), create());
+    let instances = registry
+      .instantiate(id, &[(c, GeometryKind::Point), (d, GeometryKind::Point)], &mut create)
+      .unwrap();
+    assert_eq!(instances.len(), 2);
+    assert!(matches!(instances[0].1, Geometry::Point(SymbolicPoint::MidPoint(p, q), _) if p == c && q == d));
+    assert!(!instances[0].2);
+    assert!(matches!(instances[1].1, Geometry::Line(SymbolicLine::PerpendicularBisector(p, q), _) if p == c && q == d));
+    assert!(instances[1].2);
+    assert_eq!(
+      registry.instantiate(id, &[(c, GeometryKind::Point)], &mut create).err(),
+      Some(MacroError::WrongInputs(id))
+    );
+
+    // A step depending on something else than the inputs
+    let other = create();
+    let result = ConstructionMacro::new(
+      "Broken".to_string(),
+      vec![(a, GeometryKind::Point)],
+      vec![(m, Geometry::Point(SymbolicPoint::MidPoint(a, other), point_style))],
+      vec![m],
+    );
+    assert_eq!(result.err(), Some(MacroError::NotDeterminedByInputs(other)));
+  }
+}
@@ -0,0 +1,37 @@
+use crate::events::ErrorEvent;
+
+/// Upper bound on the amount of geometries, inserting more of them is refused with an error.
+/// Protects against scripts or command loops inserting without end
+#[derive(Debug, Copy, Clone)]
+pub struct MaxEntities(pub usize);
+
+impl Default for MaxEntities {
+  fn default() -> Self {
+    Self(100_000)
+  }
+}
+
+/// What is left of `MaxEntities` for the current frame, shared by all the insert handlers. Refilled
+/// once at the start of every frame by `EntityBudgetManager`, taken from for every geometry created
+#[derive(Debug, Copy, Clone, Default)]
+pub struct EntityBudget {
+  max: usize,
+  remaining: usize,
+}
+
+impl EntityBudget {
+  pub fn refill(&mut self, max_entities: MaxEntities, count: usize) {
+    self.max = max_entities.0;
+    self.remaining = max_entities.0.saturating_sub(count);
+  }
+
+  /// Takes the room of one geometry about to be created, the error to report when there is none
+  pub fn take(&mut self) -> Result<(), ErrorEvent> {
+    if self.remaining == 0 {
+      Err(ErrorEvent::TooManyEntities(self.max))
+    } else {
+      self.remaining -= 1;
+      Ok(())
+    }
+  }
+}
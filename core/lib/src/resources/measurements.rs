@@ -0,0 +1,19 @@
+use specs::prelude::*;
+
+/// Angle measured on three selected points, at the second one selected. All the angles are in
+/// radians
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AngleMeasurement {
+  Angle(f64),
+  Inscribed {
+    circle: Entity,
+    inscribed: f64,
+    central: f64,
+  }, // All three points lie on the circle
+}
+
+/// Measurements of the current selection, kept up to date by the measurement system
+#[derive(Debug, Default, Clone)]
+pub struct Measurements {
+  pub angle: Option<AngleMeasurement>,
+}
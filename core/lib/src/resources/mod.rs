@@ -1,11 +1,47 @@
+mod angle_constraints;
+mod clipboard;
+mod command_log;
+mod constraints;
+mod coordinates_format;
 mod dependency_graph;
+mod entity_quadtree;
 mod history;
+mod inspection;
+mod layer_manager;
+mod line_clip_margin;
+mod macro_registry;
+mod max_entities;
+mod measurements;
+mod names;
+mod scalar_expressions;
+mod selection_order;
+mod solver_enabled;
 mod spatial_entity_map;
 mod styles;
+mod theme;
+mod trace_store;
 mod viewport;
 
+pub use angle_constraints::*;
+pub use clipboard::*;
+pub use command_log::*;
+pub use constraints::*;
+pub use coordinates_format::*;
 pub use dependency_graph::*;
+pub use entity_quadtree::*;
 pub use history::*;
+pub use inspection::*;
+pub use layer_manager::*;
+pub use line_clip_margin::*;
+pub use macro_registry::*;
+pub use max_entities::*;
+pub use measurements::*;
+pub use names::*;
+pub use scalar_expressions::*;
+pub use selection_order::*;
+pub use solver_enabled::*;
 pub use spatial_entity_map::*;
 pub use styles::*;
+pub use theme::*;
+pub use trace_store::*;
 pub use viewport::*;
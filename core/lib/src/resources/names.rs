@@ -0,0 +1,4 @@
+use crate::utilities::NameTable;
+use specs::prelude::*;
+
+pub type Names = NameTable<Entity>;
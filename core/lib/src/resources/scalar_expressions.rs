@@ -0,0 +1,164 @@
+use crate::utilities::*;
+use std::collections::{HashMap, HashSet};
+
+/// A named value computed from an expression, e.g. `r = dist(A, B) * 2`
+#[derive(Debug, Clone)]
+pub struct ScalarExpression {
+  pub id: ScalarId,
+  pub name: String,
+  pub source: String, // The expression as it was typed
+  pub expression: Expression,
+  pub value: Option<f64>, // Missing when what the expression refers to is undefined or cyclic
+}
+
+/// The scalars in the order they got defined. Their values are evaluated again every frame
+#[derive(Debug, Clone)]
+pub struct ScalarExpressions {
+  scalars: Vec<ScalarExpression>,
+  next_id: usize,
+}
+
+impl Default for ScalarExpressions {
+  fn default() -> Self {
+    Self {
+      scalars: Vec::new(),
+      next_id: 0,
+    }
+  }
+}
+
+impl ScalarExpressions {
+  /// Defines the scalar of a definition such as `r = dist(A, B) * 2`. A scalar defined again
+  /// keeps its id, so that the geometries using it follow the new definition
+  pub fn define(&mut self, definition: &str) -> Result<ScalarId, ExpressionError> {
+    let (name, _) = parse_definition(definition)?;
+    let id = match self.by_name(&name) {
+      Some(scalar) => scalar.id,
+      None => ScalarId(self.next_id),
+    };
+    let source = definition.split_once('=').map_or("", |(_, source)| source.trim());
+    self.insert(id, &name, source)?;
+    Ok(id)
+  }
+
+  /// Defines the scalar under the given id, e.g. when loading a sketch
+  pub fn insert(&mut self, id: ScalarId, name: &str, source: &str) -> Result<(), ExpressionError> {
+    let expression = parse_expression(source)?;
+    self.scalars.retain(|scalar| scalar.id != id && scalar.name != name);
+    self.scalars.push(ScalarExpression {
+      id,
+      name: name.to_string(),
+      source: source.to_string(),
+      expression,
+      value: None,
+    });
+    self.next_id = self.next_id.max(id.0 + 1);
+    Ok(())
+  }
+
+  pub fn remove(&mut self, name: &str) -> Option<ScalarId> {
+    let id = self.by_name(name)?.id;
+    self.scalars.retain(|scalar| scalar.id != id);
+    Some(id)
+  }
+
+  pub fn get(&self, id: ScalarId) -> Option<&ScalarExpression> {
+    self.scalars.iter().find(|scalar| scalar.id == id)
+  }
+
+  pub fn by_name(&self, name: &str) -> Option<&ScalarExpression> {
+    self.scalars.iter().find(|scalar| scalar.name == name)
+  }
+
+  pub fn value(&self, id: ScalarId) -> Option<f64> {
+    self.get(id).and_then(|scalar| scalar.value)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &ScalarExpression> {
+    self.scalars.iter()
+  }
+
+  /// Evaluates every scalar after the ones it refers to, and returns the ones whose value changed.
+  /// The scalars referring to themselves through others have no value
+  pub fn evaluate<C: ExpressionContext>(&mut self, context: &C) -> Vec<ScalarId> {
+    let mut values = HashMap::new();
+    let mut visiting = HashSet::new();
+    for i in 0..self.scalars.len() {
+      self.evaluate_at(i, context, &mut values, &mut visiting);
+    }
+
+    let mut changed = vec![];
+    for scalar in &mut self.scalars {
+      let value = values.get(&scalar.name).cloned();
+      if value != scalar.value {
+        scalar.value = value;
+        changed.push(scalar.id);
+      }
+    }
+    changed
+  }
+
+  fn evaluate_at<C: ExpressionContext>(
+    &self,
+    i: usize,
+    context: &C,
+    values: &mut HashMap<String, f64>,
+    visiting: &mut HashSet<usize>,
+  ) {
+    let scalar = &self.scalars[i];
+    if values.contains_key(&scalar.name) || !visiting.insert(i) {
+      return;
+    }
+    for name in scalar.expression.scalars() {
+      if let Some(j) = self.scalars.iter().position(|other| other.name == name) {
+        self.evaluate_at(j, context, values, visiting);
+      }
+    }
+    if let Some(value) = scalar.expression.evaluate(context, values) {
+      values.insert(scalar.name.clone(), value);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::math::*;
+
+  struct NoGeometry;
+
+  impl ExpressionContext for NoGeometry {
+    fn point(&self, _: &str) -> Option<Vector2> {
+      None
+    }
+    fn radius(&self, _: &str) -> Option<f64> {
+      None
+    }
+    fn length(&self, _: &str) -> Option<f64> {
+      None
+    }
+    fn slider(&self, _: &str) -> Option<f64> {
+      None
+    }
+  }
+
+  #[test]
+  fn test_evaluate_in_dependency_order() {
+    let mut scalars = ScalarExpressions::default();
+    let a = scalars.define("a = b * 2").unwrap();
+    let b = scalars.define("b = 3").unwrap();
+    let c = scalars.define("c = c + 1").unwrap();
+    assert_eq!(scalars.evaluate(&NoGeometry), vec![a, b]);
+    assert_eq!(scalars.value(a), Some(6.));
+    assert_eq!(scalars.value(c), None);
+    assert!(scalars.evaluate(&NoGeometry).is_empty());
+
+    // Defined again, keeping the id
+    assert_eq!(scalars.define("b = 4"), Ok(b));
+    assert_eq!(scalars.evaluate(&NoGeometry), vec![a, b]);
+    assert_eq!(scalars.value(a), Some(8.));
+    assert_eq!(scalars.remove("b"), Some(b));
+    assert_eq!(scalars.evaluate(&NoGeometry), vec![a]);
+    assert_eq!(scalars.value(a), None);
+  }
+}
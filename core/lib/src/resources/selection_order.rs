@@ -0,0 +1,24 @@
+use specs::prelude::*;
+
+/// The selected entities in the order they got selected, oldest first
+#[derive(Debug, Default)]
+pub struct SelectionOrder(Vec<Entity>);
+
+impl SelectionOrder {
+  pub fn push(&mut self, ent: Entity) {
+    self.remove(ent);
+    self.0.push(ent);
+  }
+
+  pub fn remove(&mut self, ent: Entity) {
+    self.0.retain(|e| *e != ent);
+  }
+
+  pub fn clear(&mut self) {
+    self.0.clear();
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+    self.0.iter()
+  }
+}
@@ -0,0 +1,10 @@
+/// Whether the solvers run. While disabled the geometry events keep accumulating, and they are
+/// all solved at once when the solvers are enabled again
+#[derive(Debug, Copy, Clone)]
+pub struct SolverEnabled(pub bool);
+
+impl Default for SolverEnabled {
+  fn default() -> Self {
+    Self(true)
+  }
+}
@@ -0,0 +1,25 @@
+use crate::components::styles::*;
+use crate::math::*;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DefaultArcStyle(ArcStyle);
+
+impl Default for DefaultArcStyle {
+  fn default() -> Self {
+    Self(ArcStyle {
+      color: rgb!(0.0, 0.6, 0.0),
+      width: 2.0,
+      alpha: 1.0,
+    })
+  }
+}
+
+impl DefaultArcStyle {
+  pub fn get(&self) -> ArcStyle {
+    self.0
+  }
+
+  pub fn set(&mut self, style: ArcStyle) {
+    self.0 = style;
+  }
+}
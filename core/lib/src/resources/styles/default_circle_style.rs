@@ -11,7 +11,11 @@ impl Default for DefaultCircleStyle {
       border: LineStyle {
         color: rgb!(0.0, 0.6, 0.0),
         width: 2.0,
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: 1.0,
       },
+      alpha: 1.0,
     })
   }
 }
@@ -20,4 +24,8 @@ impl DefaultCircleStyle {
   pub fn get(&self) -> CircleStyle {
     self.0
   }
+
+  pub fn set(&mut self, style: CircleStyle) {
+    self.0 = style;
+  }
 }
@@ -0,0 +1,25 @@
+use crate::components::styles::*;
+use crate::math::*;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DefaultConicStyle(ConicStyle);
+
+impl Default for DefaultConicStyle {
+  fn default() -> Self {
+    Self(ConicStyle {
+      color: rgb!(0.6, 0.0, 0.6),
+      width: 2.0,
+      alpha: 1.0,
+    })
+  }
+}
+
+impl DefaultConicStyle {
+  pub fn get(&self) -> ConicStyle {
+    self.0
+  }
+
+  pub fn set(&mut self, style: ConicStyle) {
+    self.0 = style;
+  }
+}
@@ -9,6 +9,9 @@ impl Default for DefaultLineStyle {
     Self(LineStyle {
       color: Color::blue(),
       width: 2.0,
+      marks: EqualityMarks::default(),
+      dash: DashPattern::Solid,
+      alpha: 1.0,
     })
   }
 }
@@ -17,4 +20,8 @@ impl DefaultLineStyle {
   pub fn get(&self) -> LineStyle {
     self.0
   }
+
+  pub fn set(&mut self, style: LineStyle) {
+    self.0 = style;
+  }
 }
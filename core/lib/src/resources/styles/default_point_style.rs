@@ -11,6 +11,9 @@ impl Default for DefaultPointStyle {
       radius: 5.0,
       border_color: Color::black(),
       border_width: 1.5,
+      alpha: 1.0,
+      fill: PointFill::Auto,
+      marker_shape: MarkerShape::FilledCircle,
     })
   }
 }
@@ -19,4 +22,8 @@ impl DefaultPointStyle {
   pub fn get(&self) -> PointStyle {
     self.0
   }
+
+  pub fn set(&mut self, style: PointStyle) {
+    self.0 = style;
+  }
 }
@@ -0,0 +1,31 @@
+use crate::components::styles::*;
+use crate::math::*;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DefaultPolygonStyle(PolygonStyle);
+
+impl Default for DefaultPolygonStyle {
+  fn default() -> Self {
+    Self(PolygonStyle {
+      fill: rgba!(0.2, 0.4, 0.9, 0.2),
+      border: LineStyle {
+        color: rgb!(0.2, 0.4, 0.9),
+        width: 1.5,
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: 1.0,
+      },
+      alpha: 1.0,
+    })
+  }
+}
+
+impl DefaultPolygonStyle {
+  pub fn get(&self) -> PolygonStyle {
+    self.0
+  }
+
+  pub fn set(&mut self, style: PolygonStyle) {
+    self.0 = style;
+  }
+}
@@ -0,0 +1,25 @@
+use crate::components::styles::*;
+use crate::math::*;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DefaultTextStyle(TextStyle);
+
+impl Default for DefaultTextStyle {
+  fn default() -> Self {
+    Self(TextStyle {
+      color: Color::blue(),
+      font_size: 16.0,
+      alpha: 1.0,
+    })
+  }
+}
+
+impl DefaultTextStyle {
+  pub fn get(&self) -> TextStyle {
+    self.0
+  }
+
+  pub fn set(&mut self, style: TextStyle) {
+    self.0 = style;
+  }
+}
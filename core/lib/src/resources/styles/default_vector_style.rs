@@ -0,0 +1,26 @@
+use crate::components::styles::*;
+use crate::math::*;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DefaultVectorStyle(VectorStyle);
+
+impl Default for DefaultVectorStyle {
+  fn default() -> Self {
+    Self(VectorStyle {
+      color: Color::blue(),
+      width: 2.0,
+      head_length: 12.0,
+      alpha: 1.0,
+    })
+  }
+}
+
+impl DefaultVectorStyle {
+  pub fn get(&self) -> VectorStyle {
+    self.0
+  }
+
+  pub fn set(&mut self, style: VectorStyle) {
+    self.0 = style;
+  }
+}
@@ -1,7 +1,17 @@
+mod default_arc_style;
 mod default_circle_style;
+mod default_conic_style;
 mod default_line_style;
 mod default_point_style;
+mod default_polygon_style;
+mod default_text_style;
+mod default_vector_style;
 
+pub use default_arc_style::*;
 pub use default_circle_style::*;
+pub use default_conic_style::*;
 pub use default_line_style::*;
 pub use default_point_style::*;
+pub use default_polygon_style::*;
+pub use default_text_style::*;
+pub use default_vector_style::*;
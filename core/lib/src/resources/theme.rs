@@ -0,0 +1,56 @@
+use crate::math::*;
+
+/// Colors of the canvas. The geometry colors are used as the default styles of the newly
+/// inserted elements. When the theme changes, the elements still having the default colors of
+/// the previous theme take the ones of the new theme, the others keep their own colors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Theme {
+  pub background: Color,
+  pub point: Color,
+  pub point_border: Color,
+  pub line: Color,
+  pub circle: Color,
+  pub grid: Color,
+  pub selection: Color,
+  pub hover: Color,
+  pub snap_point: Color,
+  pub overlay: Color, // Of the select rectangle and lasso, drawn translucent
+}
+
+impl Default for Theme {
+  fn default() -> Self {
+    Self::light()
+  }
+}
+
+impl Theme {
+  pub fn light() -> Self {
+    Self {
+      background: Color::white(),
+      point: Color::red(),
+      point_border: Color::black(),
+      line: Color::blue(),
+      circle: rgb!(0.0, 0.6, 0.0),
+      grid: rgb!(0.9, 0.9, 0.9),
+      selection: Color::magenta(),
+      hover: rgb!(1.0, 0.6, 0.0),
+      snap_point: rgb!(0.9, 0.3, 0.0),
+      overlay: Color::black(),
+    }
+  }
+
+  pub fn dark() -> Self {
+    Self {
+      background: rgb!(0.12, 0.12, 0.14),
+      point: rgb!(1.0, 0.35, 0.35),
+      point_border: Color::white(),
+      line: rgb!(0.4, 0.6, 1.0),
+      circle: rgb!(0.3, 0.85, 0.3),
+      grid: rgb!(0.25, 0.25, 0.28),
+      selection: rgb!(1.0, 0.4, 1.0),
+      hover: rgb!(1.0, 0.75, 0.2),
+      snap_point: rgb!(1.0, 0.55, 0.2),
+      overlay: Color::white(),
+    }
+  }
+}
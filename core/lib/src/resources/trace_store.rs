@@ -0,0 +1,44 @@
+use crate::utilities::*;
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// The polylines left by the traced points, in virtual space. Every drag starts new polylines so
+/// that two drags are not joined together
+#[derive(Debug, Default)]
+pub struct TraceStore(HashMap<Entity, Vec<Vec<VirtualPosition>>>);
+
+impl TraceStore {
+  pub fn record(&mut self, ent: Entity, position: VirtualPosition) {
+    let polylines = self.0.entry(ent).or_insert_with(|| vec![vec![]]);
+    if let Some(polyline) = polylines.last_mut() {
+      // Nothing to add if the point did not move since last frame
+      if polyline.last().is_none_or(|last| last.0 != position.0) {
+        polyline.push(position);
+      }
+    }
+  }
+
+  /// The next position recorded for every entity starts a new polyline
+  pub fn end_polylines(&mut self) {
+    for polylines in self.0.values_mut() {
+      if polylines.last().is_some_and(|polyline| !polyline.is_empty()) {
+        polylines.push(vec![]);
+      }
+    }
+  }
+
+  pub fn retain<F: Fn(Entity) -> bool>(&mut self, f: F) {
+    self.0.retain(|ent, _| f(*ent));
+  }
+
+  pub fn clear(&mut self) {
+    self.0.clear();
+  }
+
+  pub fn polylines(&self) -> impl Iterator<Item = (Entity, &Vec<VirtualPosition>)> {
+    self
+      .0
+      .iter()
+      .flat_map(|(ent, polylines)| polylines.iter().map(move |polyline| (*ent, polyline)))
+  }
+}
@@ -1,5 +1,7 @@
 mod transform;
 mod viewport;
+mod viewport_history;
 
 pub use transform::*;
 pub use viewport::*;
+pub use viewport_history::*;
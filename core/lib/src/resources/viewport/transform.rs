@@ -1,4 +1,5 @@
 use super::*;
+use crate::components::freehand::*;
 use crate::math::*;
 use crate::utilities::*;
 
@@ -100,6 +101,166 @@ impl ToScreen for VirtualCircle {
   }
 }
 
+// Flipping the y axis turns counter clockwise into clockwise, so the arc starts at the other end
+
+impl ToVirtual for ScreenArc {
+  type Output = VirtualArc;
+
+  fn to_virtual(self, vp: &Viewport) -> Self::Output {
+    let Self {
+      center,
+      radius,
+      start,
+      sweep,
+    } = self;
+    Self::Output {
+      center: center.to_virtual(vp),
+      radius: radius.to_virtual(vp),
+      start: -(start + sweep),
+      sweep,
+    }
+  }
+}
+
+impl ToScreen for VirtualArc {
+  type Output = ScreenArc;
+
+  fn to_screen(self, vp: &Viewport) -> Self::Output {
+    let Self {
+      center,
+      radius,
+      start,
+      sweep,
+    } = self;
+    Self::Output {
+      center: center.to_screen(vp),
+      radius: radius.to_screen(vp),
+      start: -(start + sweep),
+      sweep,
+    }
+  }
+}
+
+// Flipping the y axis turns the ellipse the other way, and its parameters go the other way too
+
+impl ToVirtual for ScreenConic {
+  type Output = VirtualConic;
+
+  fn to_virtual(self, vp: &Viewport) -> Self::Output {
+    let Self {
+      center,
+      rx,
+      ry,
+      rotation,
+    } = self;
+    Self::Output {
+      center: center.to_virtual(vp),
+      rx: rx.to_virtual(vp),
+      ry: ry.to_virtual(vp),
+      rotation: -rotation,
+    }
+  }
+}
+
+impl ToScreen for VirtualConic {
+  type Output = ScreenConic;
+
+  fn to_screen(self, vp: &Viewport) -> Self::Output {
+    let Self {
+      center,
+      rx,
+      ry,
+      rotation,
+    } = self;
+    Self::Output {
+      center: center.to_screen(vp),
+      rx: rx.to_screen(vp),
+      ry: ry.to_screen(vp),
+      rotation: -rotation,
+    }
+  }
+}
+
+impl ToVirtual for ScreenPolygon {
+  type Output = VirtualPolygon;
+
+  fn to_virtual(self, vp: &Viewport) -> Self::Output {
+    Self::Output {
+      vertices: self.vertices.into_iter().map(|v| v.to_virtual(vp)).collect(),
+    }
+  }
+}
+
+impl ToScreen for VirtualPolygon {
+  type Output = ScreenPolygon;
+
+  fn to_screen(self, vp: &Viewport) -> Self::Output {
+    Self::Output {
+      vertices: self.vertices.into_iter().map(|v| v.to_screen(vp)).collect(),
+    }
+  }
+}
+
+impl ToVirtual for ScreenVector {
+  type Output = VirtualVector;
+
+  fn to_virtual(self, vp: &Viewport) -> Self::Output {
+    let Self { from, to } = self;
+    Self::Output {
+      from: from.to_virtual(vp),
+      to: to.to_virtual(vp),
+    }
+  }
+}
+
+impl ToScreen for VirtualVector {
+  type Output = ScreenVector;
+
+  fn to_screen(self, vp: &Viewport) -> Self::Output {
+    let Self { from, to } = self;
+    Self::Output {
+      from: from.to_screen(vp),
+      to: to.to_screen(vp),
+    }
+  }
+}
+
+impl ToVirtual for ScreenText {
+  type Output = VirtualText;
+
+  fn to_virtual(self, vp: &Viewport) -> Self::Output {
+    Self::Output {
+      position: self.position.to_virtual(vp),
+      text: self.text,
+    }
+  }
+}
+
+impl ToScreen for VirtualText {
+  type Output = ScreenText;
+
+  fn to_screen(self, vp: &Viewport) -> Self::Output {
+    Self::Output {
+      position: self.position.to_screen(vp),
+      text: self.text,
+    }
+  }
+}
+
+impl ToScreen for FreehandStroke {
+  type Output = ScreenFreehandStroke;
+
+  fn to_screen(self, vp: &Viewport) -> Self::Output {
+    Self::Output {
+      samples: self
+        .samples
+        .into_iter()
+        .map(|(position, pressure)| (position.to_screen(vp), pressure))
+        .collect(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -113,4 +274,41 @@ mod test {
     // let something = vp + sp; // Should not work
     let _vp_add = vp + sp.to_virtual(&viewport); // This should work
   }
+
+  #[test]
+  fn test_arc_keeps_its_ends_on_screen() {
+    let viewport = Viewport::default();
+    let virt_arc: VirtualArc = Arc::from_center_points(vec2![1., 1.], vec2![3., 1.], vec2![1., 2.]).into();
+    let scrn_arc = virt_arc.to_screen(&viewport);
+    let (virt, scrn): (Arc, Arc) = (virt_arc.into(), scrn_arc.into());
+    let virt_ends = [virt.start_point(), virt.end_point()];
+    let scrn_ends = [scrn.end_point(), scrn.start_point()];
+    for (v, s) in virt_ends.iter().zip(scrn_ends.iter()) {
+      let s = ScreenPosition(*s).to_virtual(&viewport).0;
+      assert!((*v - s).magnitude() < 1e-9, "{:?} != {:?}", v, s);
+    }
+  }
+
+  #[test]
+  fn test_conic_parameter_is_flipped_on_screen() {
+    let viewport = Viewport::default();
+    let virt_conic: VirtualConic = Ellipse {
+      center: vec2![1., 2.],
+      rx: 3.,
+      ry: 1.,
+      rotation: 0.4,
+    }
+    .into();
+    let scrn_conic = virt_conic.to_screen(&viewport);
+    let (virt, scrn): (Ellipse, Ellipse) = (virt_conic.into(), scrn_conic.into());
+    for t in &[0.0, 1.0, 2.5, 4.0] {
+      let s = ScreenPosition(scrn.point_at(-t)).to_virtual(&viewport).0;
+      assert!(
+        (virt.point_at(*t) - s).magnitude() < 1e-9,
+        "{:?} != {:?}",
+        virt.point_at(*t),
+        s
+      );
+    }
+  }
 }
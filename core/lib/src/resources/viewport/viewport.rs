@@ -1,6 +1,9 @@
 use crate::math::*;
 
 pub static WINDOW_SIZE: [f64; 2] = [960., 720.];
+pub static MIN_SCALE: f64 = 0.01;
+pub static MAX_SCALE: f64 = 100.0;
+static DEFAULT_VIRTUAL_WIDTH: f64 = 20.0;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Viewport {
@@ -13,7 +16,11 @@ pub struct Viewport {
 
 impl Default for Viewport {
   fn default() -> Self {
-    Self::new(vec2![0., 0.], vec2![20., 15.], WINDOW_SIZE.into())
+    Self::new(
+      vec2![0., 0.],
+      vec2![DEFAULT_VIRTUAL_WIDTH, DEFAULT_VIRTUAL_WIDTH * 0.75],
+      WINDOW_SIZE.into(),
+    )
   }
 }
 
@@ -41,6 +48,20 @@ impl Viewport {
     self.half_virtual_size = self.virtual_size / 2.0;
   }
 
+  /// How much the viewport is zoomed in compared to the default one
+  pub fn scale(&self) -> f64 {
+    DEFAULT_VIRTUAL_WIDTH / self.virtual_size.x
+  }
+
+  /// Zooms in by the factor, or out when it is below 1, keeping the virtual position `anchor` at
+  /// the same place on screen. The scale is kept within `MIN_SCALE` and `MAX_SCALE`
+  pub fn zoom(&mut self, factor: f64, anchor: Vector2) {
+    let scale = (self.scale() * factor).max(MIN_SCALE).min(MAX_SCALE);
+    let ratio = self.scale() / scale;
+    self.virtual_center = anchor + (self.virtual_center - anchor) * ratio;
+    self.set_virtual_size_x(DEFAULT_VIRTUAL_WIDTH / scale);
+  }
+
   pub fn aspect_ratio(&self) -> f64 {
     self.screen_size.y / self.screen_size.x
   }
@@ -105,3 +126,27 @@ impl Viewport {
     AABB::new(0., 0., self.screen_width(), self.screen_height())
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{resources::ToScreen, utilities::*};
+
+  #[test]
+  fn test_zoom_keeps_the_anchor_in_place_and_clamps() {
+    let mut viewport = Viewport::default();
+    let anchor = vec2![4., 3.];
+    let ScreenPosition(before) = VirtualPosition(anchor).to_screen(&viewport);
+    viewport.zoom(2.0, anchor);
+    let ScreenPosition(after) = VirtualPosition(anchor).to_screen(&viewport);
+    assert_eq!(viewport.scale(), 2.0);
+    assert!((after.x - before.x).abs() < 1e-9 && (after.y - before.y).abs() < 1e-9);
+
+    viewport.zoom(1e6, anchor);
+    assert_eq!(viewport.scale(), MAX_SCALE);
+    let ScreenPosition(after) = VirtualPosition(anchor).to_screen(&viewport);
+    assert!((after.x - before.x).abs() < 1e-9 && (after.y - before.y).abs() < 1e-9);
+    viewport.zoom(1e-9, anchor);
+    assert_eq!(viewport.scale(), MIN_SCALE);
+  }
+}
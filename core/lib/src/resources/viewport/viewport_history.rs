@@ -0,0 +1,136 @@
+use super::Viewport;
+
+/// Undo stack of the viewport, kept apart from the geometry `History` and disabled by default.
+/// Every snapshot is stamped with the geometry history cursor at the time it's recorded so that
+/// undo and redo can be interleaved with geometry modifications in the right order.
+pub struct ViewportHistory {
+  enabled: bool,
+  undo_stack: Vec<(Viewport, usize)>,
+  redo_stack: Vec<(Viewport, usize)>,
+  before_change: Option<Viewport>,
+}
+
+impl Default for ViewportHistory {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      undo_stack: vec![],
+      redo_stack: vec![],
+      before_change: None,
+    }
+  }
+}
+
+impl ViewportHistory {
+  pub fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+
+  pub fn set_enabled(&mut self, enabled: bool) {
+    self.enabled = enabled;
+    if !enabled {
+      self.clear();
+    }
+  }
+
+  pub fn clear(&mut self) {
+    self.undo_stack.clear();
+    self.redo_stack.clear();
+    self.before_change = None;
+  }
+
+  /// The viewport is being changed. Only the viewport before the first change is remembered
+  pub fn record_change(&mut self, before: Viewport) {
+    if self.enabled && self.before_change.is_none() {
+      self.before_change = Some(before);
+    }
+  }
+
+  /// The viewport stopped changing, push the change to the undo stack if it is significant
+  pub fn settle(&mut self, current: Viewport, stamp: usize) {
+    if let Some(before) = self.before_change.take() {
+      if !same_view(&before, &current) {
+        self.undo_stack.push((before, stamp));
+        self.redo_stack.clear();
+      }
+    }
+  }
+
+  pub fn can_undo_at(&self, stamp: usize) -> bool {
+    self.enabled && self.undo_stack.last().is_some_and(|(_, s)| *s == stamp)
+  }
+
+  pub fn can_redo_at(&self, stamp: usize) -> bool {
+    self.enabled && self.redo_stack.last().is_some_and(|(_, s)| *s == stamp)
+  }
+
+  pub fn undo(&mut self, current: Viewport) -> Option<Viewport> {
+    let (viewport, stamp) = self.undo_stack.pop()?;
+    self.redo_stack.push((current, stamp));
+    Some(viewport)
+  }
+
+  pub fn redo(&mut self, current: Viewport) -> Option<Viewport> {
+    let (viewport, stamp) = self.redo_stack.pop()?;
+    self.undo_stack.push((current, stamp));
+    Some(viewport)
+  }
+}
+
+fn same_view(v1: &Viewport, v2: &Viewport) -> bool {
+  v1.virtual_center == v2.virtual_center && v1.virtual_width() == v2.virtual_width()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::math::*;
+
+  fn panned(viewport: Viewport, movement: Vector2) -> Viewport {
+    let mut viewport = viewport;
+    viewport.virtual_center = viewport.virtual_center + movement;
+    viewport
+  }
+
+  #[test]
+  fn test_viewport_history_disabled() {
+    let mut history = ViewportHistory::default();
+    let before = Viewport::default();
+    history.record_change(before);
+    history.settle(panned(before, vec2![1., 0.]), 0);
+    assert!(!history.can_undo_at(0));
+  }
+
+  #[test]
+  fn test_viewport_history_pan_undo_redo() {
+    let mut history = ViewportHistory::default();
+    history.set_enabled(true);
+
+    // A pan spanning multiple frames is recorded as a single change
+    let before = Viewport::default();
+    history.record_change(before);
+    history.record_change(panned(before, vec2![1., 0.]));
+    let after = panned(before, vec2![2., 1.]);
+    history.settle(after, 3);
+
+    assert!(!history.can_undo_at(2));
+    assert!(history.can_undo_at(3));
+    let restored = history.undo(after).unwrap();
+    assert_eq!(restored.virtual_center, before.virtual_center);
+    assert!(!history.can_undo_at(3));
+
+    assert!(history.can_redo_at(3));
+    let redone = history.redo(restored).unwrap();
+    assert_eq!(redone.virtual_center, after.virtual_center);
+  }
+
+  #[test]
+  fn test_viewport_history_ignore_insignificant() {
+    let mut history = ViewportHistory::default();
+    history.set_enabled(true);
+    let before = Viewport::default();
+    history.record_change(before);
+    history.settle(before, 0);
+    assert!(!history.can_undo_at(0));
+  }
+}
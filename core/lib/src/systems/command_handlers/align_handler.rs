@@ -0,0 +1,248 @@
+use crate::{
+  components::{markers::*, symbolics::*},
+  events::*,
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+pub struct AlignHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for AlignHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for AlignHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Read<'a, SelectionOrder>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (entities, mut command_event_channel, mut error_event_channel, selection_order, selecteds, sym_points): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let commands = command_event_channel
+        .read(reader)
+        .filter(|event| {
+          matches!(
+            event.command,
+            Command::AlignSelected(_, _) | Command::DistributeSelected(_)
+          )
+        })
+        .map(|event| event.command.clone())
+        .collect::<Vec<_>>();
+      for command in commands {
+        let mut free_points = vec![];
+        for (ent, _, sym_point) in (&entities, &selecteds, &sym_points).join() {
+          match sym_point {
+            SymbolicPoint::Free(position) => free_points.push((ent, *position)),
+            _ => error_event_channel.single_write(ErrorEvent::PointNotMovable(ent)),
+          }
+        }
+
+        let moves = match command {
+          Command::AlignSelected(alignment, reference) => align(&free_points, alignment, reference, &selection_order),
+          Command::DistributeSelected(axis) => distribute(&mut free_points, axis),
+          _ => vec![],
+        };
+
+        // The points are moved like dragging them, each of them is a separate update
+        for (ent, old_position, new_position) in moves {
+          let (old_sym_point, new_sym_point) = (SymbolicPoint::Free(old_position), SymbolicPoint::Free(new_position));
+          command_event_channel.single_write(CommandEvent {
+            command: Command::Update(UpdateEvent::UpdatePoint(ent, old_sym_point, new_sym_point)),
+            event_id: None,
+          });
+          command_event_channel.single_write(CommandEvent {
+            command: Command::Update(UpdateEvent::UpdatePointEnd(ent, old_sym_point, new_sym_point)),
+            event_id: None,
+          });
+        }
+      }
+    }
+  }
+}
+
+/// The free points moved onto the shared coordinate, points already on it are left untouched
+fn align(
+  points: &[(Entity, VirtualPosition)],
+  alignment: Alignment,
+  reference: Reference,
+  selection_order: &SelectionOrder,
+) -> Vec<(Entity, VirtualPosition, VirtualPosition)> {
+  let coord = |VirtualPosition(p): VirtualPosition| match alignment {
+    Alignment::Horizontal => p.y,
+    Alignment::Vertical => p.x,
+  };
+  let target = match reference {
+    Reference::Average => {
+      if points.is_empty() {
+        return vec![];
+      }
+      points.iter().map(|(_, position)| coord(*position)).sum::<f64>() / points.len() as f64
+    }
+    Reference::FirstSelected => {
+      let first = selection_order
+        .iter()
+        .find_map(|ent| points.iter().find(|(point_ent, _)| point_ent == ent));
+      match first {
+        Some((_, position)) => coord(*position),
+        None => return vec![],
+      }
+    }
+  };
+  points
+    .iter()
+    .filter(|(_, position)| coord(*position) != target)
+    .map(|(ent, VirtualPosition(p))| {
+      let new_position = match alignment {
+        Alignment::Horizontal => vec2![p.x, target],
+        Alignment::Vertical => vec2![target, p.y],
+      };
+      (*ent, VirtualPosition(*p), VirtualPosition(new_position))
+    })
+    .collect()
+}
+
+/// The interior points moved to be evenly spaced along `axis` between the two extreme points,
+/// which stay where they are
+fn distribute(points: &mut [(Entity, VirtualPosition)], axis: Axis) -> Vec<(Entity, VirtualPosition, VirtualPosition)> {
+  if points.len() < 3 {
+    return vec![];
+  }
+  let coord = |VirtualPosition(p): VirtualPosition| match axis {
+    Axis::X => p.x,
+    Axis::Y => p.y,
+  };
+  points.sort_by(|(_, a), (_, b)| coord(*a).partial_cmp(&coord(*b)).unwrap());
+  let min = coord(points[0].1);
+  let step = (coord(points[points.len() - 1].1) - min) / (points.len() - 1) as f64;
+  points
+    .iter()
+    .enumerate()
+    .skip(1)
+    .take(points.len() - 2)
+    .filter_map(|(i, (ent, VirtualPosition(p)))| {
+      let target = min + step * i as f64;
+      let new_position = match axis {
+        Axis::X => vec2![target, p.y],
+        Axis::Y => vec2![p.x, target],
+      };
+      if new_position == *p {
+        None
+      } else {
+        Some((*ent, VirtualPosition(*p), VirtualPosition(new_position)))
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, test_utils::*};
+
+  fn position(world: &World, ent: Entity) -> Vector2 {
+    match world.read_storage::<SymbolicPoint>().get(ent) {
+      Some(SymbolicPoint::Free(VirtualPosition(p))) => *p,
+      _ => panic!("Expected a free point"),
+    }
+  }
+
+  #[test]
+  fn test_align_three_points_horizontally() {
+    let (mut world, mut dispatcher) = headless();
+
+    let p1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 1.].into()));
+    let p2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 3.].into()));
+    let p3 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![5., 5.].into()));
+    for ent in &[p2, p1, p3] {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+
+    // The updates issued by the align are handled on the next frame
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::AlignSelected(Alignment::Horizontal, Reference::Average),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, p1), vec2![0., 3.]);
+    assert_eq!(position(&world, p2), vec2![2., 3.]);
+    assert_eq!(position(&world, p3), vec2![5., 3.]);
+    let solved = world.read_storage::<VirtualPoint>().get(p1).unwrap().0;
+    assert_eq!(solved, vec2![0., 3.]);
+
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePointEnd(
+        p3,
+        SymbolicPoint::Free(vec2![5., 3.].into()),
+        SymbolicPoint::Free(vec2![5., 7.].into()),
+      )),
+    );
+    for ent in &[p3, p1, p2] {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::AlignSelected(Alignment::Horizontal, Reference::FirstSelected),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, p1), vec2![0., 7.]);
+    assert_eq!(position(&world, p2), vec2![2., 7.]);
+    assert_eq!(position(&world, p3), vec2![5., 7.]);
+  }
+
+  #[test]
+  fn test_distribute_five_points_along_x() {
+    let (mut world, mut dispatcher) = headless();
+
+    let xs = [0., 7., 1.5, 10., 2.];
+    let points = xs
+      .iter()
+      .enumerate()
+      .map(|(i, x)| {
+        insert_point(
+          &mut world,
+          &mut dispatcher,
+          SymbolicPoint::Free(vec2![*x, i as f64].into()),
+        )
+      })
+      .collect::<Vec<_>>();
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::SelectAll));
+    step(&mut world, &mut dispatcher, Command::DistributeSelected(Axis::X));
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    // Outer points stay, the interior ones keep their order and their y
+    assert_eq!(position(&world, points[0]), vec2![0., 0.]);
+    assert_eq!(position(&world, points[3]), vec2![10., 3.]);
+    assert_eq!(position(&world, points[2]), vec2![2.5, 2.]);
+    assert_eq!(position(&world, points[4]), vec2![5., 4.]);
+    assert_eq!(position(&world, points[1]), vec2![7.5, 1.]);
+  }
+}
@@ -0,0 +1,81 @@
+use crate::{
+  components::{markers::*, symbolics::*},
+  events::*,
+};
+use specs::prelude::*;
+
+pub struct AnimationHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for AnimationHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for AnimationHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, Animated>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (entities, command_event_channel, selecteds, sym_points, mut animateds): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::Animation(animation_event) => match animation_event {
+            AnimationEvent::Play(ent) => update(ent, &sym_points, &mut animateds, |animated| animated.playing = true),
+            AnimationEvent::Pause(ent) => update(ent, &sym_points, &mut animateds, |animated| animated.playing = false),
+            AnimationEvent::TogglePlaySelected => {
+              let selected_points = (&entities, &selecteds, &sym_points)
+                .join()
+                .map(|(ent, _, _)| ent)
+                .collect::<Vec<_>>();
+              for ent in selected_points {
+                update(ent, &sym_points, &mut animateds, |animated| {
+                  animated.playing = !animated.playing
+                });
+              }
+            }
+            AnimationEvent::SetSpeed(ent, speed) => {
+              update(ent, &sym_points, &mut animateds, |animated| animated.speed = speed)
+            }
+            AnimationEvent::SetMode(ent, mode) => {
+              update(ent, &sym_points, &mut animateds, |animated| animated.mode = mode)
+            }
+          },
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn update<'a, F: FnOnce(&mut Animated)>(
+  ent: Entity,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  animateds: &mut WriteStorage<'a, Animated>,
+  f: F,
+) {
+  // Only the points with a parameter along a path can be animated
+  match sym_points.get(ent) {
+    Some(SymbolicPoint::OnLine(_, _)) | Some(SymbolicPoint::OnCircle(_, _)) => (),
+    _ => return,
+  }
+  let mut animated = animateds.get(ent).copied().unwrap_or_default();
+  f(&mut animated);
+  if let Err(err) = animateds.insert(ent, animated) {
+    panic!(err)
+  }
+}
@@ -0,0 +1,428 @@
+use crate::{
+  components::{freehand::*, markers::*, styles::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+static PASTE_OFFSET: f64 = 20.0; // Pixel, in both directions for every paste
+
+/// Copies the selected geometries into the clipboard and pastes them back as new geometries. The
+/// dependencies among the copied geometries are kept, the geometries they depend on outside of the
+/// selection are copied along, the points among them as free points where they stand. Duplicating
+/// skips the clipboard and copies all the ancestors of the selection as they are, so that the
+/// duplicated constructions stay the same
+pub struct ClipboardHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for ClipboardHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ClipboardHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, Clipboard>,
+    Write<'a, History>,
+    Read<'a, DependencyGraph>,
+    Read<'a, Viewport>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, PointStyle>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, LineStyle>,
+    ReadStorage<'a, SymbolicCircle>,
+    ReadStorage<'a, CircleStyle>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, ArcStyle>,
+    ReadStorage<'a, SymbolicConic>,
+    ReadStorage<'a, ConicStyle>,
+    ReadStorage<'a, SymbolicPolygon>,
+    ReadStorage<'a, PolygonStyle>,
+    ReadStorage<'a, SymbolicVector>,
+    ReadStorage<'a, VectorStyle>,
+    ReadStorage<'a, SymbolicText>,
+    ReadStorage<'a, TextStyle>,
+    ReadStorage<'a, FreehandStroke>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      mut command_event_channel,
+      mut clipboard,
+      mut history,
+      dependency_graph,
+      viewport,
+      selecteds,
+      virt_points,
+      sym_points,
+      point_styles,
+      sym_lines,
+      line_styles,
+      sym_circles,
+      circle_styles,
+      sym_arcs,
+      arc_styles,
+      sym_conics,
+      conic_styles,
+      sym_polygons,
+      polygon_styles,
+      sym_vectors,
+      vector_styles,
+      sym_texts,
+      text_styles,
+      strokes,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let commands = command_event_channel
+        .read(reader)
+        .filter(|event| {
+          matches!(
+            event.command,
+            Command::CopySelected | Command::Paste | Command::DuplicateSelection
+          )
+        })
+        .map(|event| event.command.clone())
+        .collect::<Vec<_>>();
+      let geometry_of = |ent: Entity| -> Option<Geometry> {
+        if let (Some(sym_point), Some(point_style)) = (sym_points.get(ent), point_styles.get(ent)) {
+          Some(Geometry::Point(*sym_point, *point_style))
+        } else if let (Some(sym_line), Some(line_style)) = (sym_lines.get(ent), line_styles.get(ent)) {
+          Some(Geometry::Line(*sym_line, *line_style))
+        } else if let (Some(sym_circle), Some(circle_style)) = (sym_circles.get(ent), circle_styles.get(ent)) {
+          Some(Geometry::Circle(*sym_circle, *circle_style))
+        } else if let (Some(sym_arc), Some(arc_style)) = (sym_arcs.get(ent), arc_styles.get(ent)) {
+          Some(Geometry::Arc(*sym_arc, *arc_style))
+        } else if let (Some(sym_conic), Some(conic_style)) = (sym_conics.get(ent), conic_styles.get(ent)) {
+          Some(Geometry::Conic(*sym_conic, *conic_style))
+        } else if let (Some(sym_polygon), Some(polygon_style)) = (sym_polygons.get(ent), polygon_styles.get(ent)) {
+          Some(Geometry::Polygon(sym_polygon.clone(), *polygon_style))
+        } else if let (Some(sym_vector), Some(vector_style)) = (sym_vectors.get(ent), vector_styles.get(ent)) {
+          Some(Geometry::Vector(*sym_vector, *vector_style))
+        } else if let (Some(sym_text), Some(text_style)) = (sym_texts.get(ent), text_styles.get(ent)) {
+          Some(Geometry::Text(sym_text.clone(), *text_style))
+        } else if let (Some(stroke), Some(line_style)) = (strokes.get(ent), line_styles.get(ent)) {
+          Some(Geometry::Freehand(stroke.clone(), *line_style))
+        } else {
+          None
+        }
+      };
+      for command in commands {
+        match command {
+          Command::CopySelected => {
+            let selected = (&entities, &selecteds)
+              .join()
+              .map(|(ent, _)| ent)
+              .filter(|ent| geometry_of(*ent).is_some())
+              .collect::<HashSet<_>>();
+            if selected.is_empty() {
+              continue;
+            }
+            let mut copied: HashMap<Entity, Geometry> = HashMap::new();
+            let mut stack = selected.iter().cloned().collect::<Vec<_>>();
+            while let Some(ent) = stack.pop() {
+              if copied.contains_key(&ent) {
+                continue;
+              }
+              let geometry = match (geometry_of(ent), virt_points.get(ent)) {
+                (Some(Geometry::Point(_, point_style)), Some(position)) if !selected.contains(&ent) => {
+                  Geometry::Point(SymbolicPoint::Free(*position), point_style)
+                }
+                (Some(geometry), _) => geometry,
+                (None, _) => continue,
+              };
+              stack.extend(geometry.dependencies());
+              copied.insert(ent, geometry);
+            }
+
+            let order = dependency_graph.dependency_order(&copied.keys().cloned().collect());
+            clipboard.set(
+              order
+                .into_iter()
+                .filter_map(|ent| copied.remove(&ent).map(|geometry| (ent, geometry)))
+                .collect(),
+            );
+          }
+          Command::Paste => {
+            if clipboard.is_empty() {
+              continue;
+            }
+            let offset = ScreenScalar(PASTE_OFFSET * clipboard.next_paste() as f64)
+              .to_virtual(&*viewport)
+              .0;
+            // The screen y axis points down, the virtual one up
+            let offset = vec2![offset, -offset];
+
+            let geometries = clipboard.iter().cloned().collect::<Vec<_>>();
+            let insertions = paste(&geometries, offset, &entities, &mut command_event_channel);
+            history.push(Modification::InsertMany(insertions));
+          }
+          Command::DuplicateSelection => {
+            let ancestors = (&entities, &selecteds)
+              .join()
+              .flat_map(|(ent, _)| dependency_graph.get_all_ancestors(&ent))
+              .collect::<HashSet<_>>();
+            let geometries = dependency_graph
+              .dependency_order(&ancestors)
+              .into_iter()
+              .filter_map(|ent| geometry_of(ent).map(|geometry| (ent, geometry)))
+              .collect::<Vec<_>>();
+            if geometries.is_empty() {
+              continue;
+            }
+            let offset = ScreenScalar(PASTE_OFFSET).to_virtual(&*viewport).0;
+            let insertions = paste(
+              &geometries,
+              vec2![offset, -offset],
+              &entities,
+              &mut command_event_channel,
+            );
+            history.push(Modification::InsertMany(insertions));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// Insert the geometries under new entities, the ones they depend on among them being replaced by
+/// their new entities. The new geometries are the only ones selected after. They are inserted
+/// under the entities created here, like the history does when it restores them, so they are to be
+/// recorded by the caller, as a single step
+fn paste<'a>(
+  geometries: &[(Entity, Geometry)],
+  offset: Vector2,
+  entities: &Entities<'a>,
+  command_event_channel: &mut CommandEventChannel,
+) -> HashMap<Entity, Geometry> {
+  let pasted = geometries
+    .iter()
+    .map(|(ent, _)| (*ent, entities.create()))
+    .collect::<HashMap<_, _>>();
+  let mut insertions = HashMap::new();
+  let mut commands = Vec::new();
+  for (ent, geometry) in geometries {
+    let geometry = offset_by(
+      geometry.remap(&mut |dependency| pasted.get(&dependency).cloned().unwrap_or(dependency)),
+      offset,
+    );
+    commands.push(Command::insert_by_history(pasted[ent], &geometry));
+    insertions.insert(pasted[ent], geometry);
+  }
+  // Inserting selects, only the new geometries stay selected
+  commands.push(Command::Select(SelectEvent::DeselectAll));
+  commands.extend(
+    geometries
+      .iter()
+      .map(|(ent, _)| Command::Select(SelectEvent::Select(pasted[ent]))),
+  );
+  for command in commands {
+    command_event_channel.single_write(CommandEvent {
+      command,
+      event_id: None,
+    });
+  }
+  insertions
+}
+
+/// The geometry moved by `offset` when it stands on its own, the other ones follow what they
+/// depend on
+fn offset_by(geometry: Geometry, offset: Vector2) -> Geometry {
+  match geometry {
+    Geometry::Point(SymbolicPoint::Free(position), point_style) => {
+      Geometry::Point(SymbolicPoint::Free(VirtualPosition(position.0 + offset)), point_style)
+    }
+    Geometry::Point(SymbolicPoint::Fixed(position), point_style) => {
+      Geometry::Point(SymbolicPoint::Fixed(VirtualPosition(position.0 + offset)), point_style)
+    }
+    Geometry::Text(
+      SymbolicText {
+        text,
+        anchor: TextAnchor::Position(position),
+      },
+      text_style,
+    ) => Geometry::Text(
+      SymbolicText {
+        text,
+        anchor: TextAnchor::Position(VirtualPosition(position.0 + offset)),
+      },
+      text_style,
+    ),
+    Geometry::Freehand(stroke, line_style) => Geometry::Freehand(
+      FreehandStroke {
+        samples: stroke
+          .samples
+          .into_iter()
+          .map(|(position, pressure)| (VirtualPosition(position.0 + offset), pressure))
+          .collect(),
+      },
+      line_style,
+    ),
+    geometry => geometry,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn elements(world: &World) -> usize {
+    world.read_storage::<Element>().join().count()
+  }
+
+  fn select_only(world: &mut World, dispatcher: &mut Dispatcher, ents: &[Entity]) {
+    step(world, dispatcher, Command::Select(SelectEvent::DeselectAll));
+    for ent in ents {
+      step(world, dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+  }
+
+  #[test]
+  fn test_paste_copies_the_selected_subgraph() {
+    let (mut world, mut dispatcher) = headless();
+
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 0.].into()));
+    let c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 2.].into()));
+    let mid = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, c));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(b, mid))),
+    );
+    let segment = last_inserted::<SymbolicLine>(&world);
+
+    // The mid point is outside of the selection, the segment is pasted with a free copy of it
+    select_only(&mut world, &mut dispatcher, &[b, segment]);
+    step(&mut world, &mut dispatcher, Command::CopySelected);
+    let before = elements(&world);
+    step(&mut world, &mut dispatcher, Command::Paste);
+    // The inserts issued by the paste are handled on the next frame
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(elements(&world), before + 3);
+
+    let pasted_segment = last_inserted::<SymbolicLine>(&world);
+    let (from, to) = match world.read_storage::<SymbolicLine>().get(pasted_segment) {
+      Some(SymbolicLine::Segment(from, to)) => (*from, *to),
+      _ => panic!("The segment is pasted as a segment"),
+    };
+    assert!(from != b && to != mid);
+    let offset = ScreenScalar(PASTE_OFFSET).to_virtual(&*world.fetch::<Viewport>()).0;
+    {
+      let sym_points = world.read_storage::<SymbolicPoint>();
+      let virt_points = world.read_storage::<VirtualPoint>();
+      assert!(matches!(sym_points.get(to), Some(SymbolicPoint::Free(_))));
+      assert_eq!(virt_points.get(from).unwrap().0, vec2![2. + offset, -offset]);
+      assert_eq!(virt_points.get(to).unwrap().0, vec2![1. + offset, 1. - offset]);
+
+      // Only the pasted geometries are selected
+      let selecteds = world.read_storage::<Selected>();
+      let selected = (&world.entities(), &selecteds)
+        .join()
+        .map(|(ent, _)| ent)
+        .collect::<HashSet<_>>();
+      assert_eq!(selected, [from, to, pasted_segment].iter().cloned().collect());
+    }
+
+    // The whole paste is a single step of the history
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(elements(&world), before);
+    assert!(world.read_storage::<SymbolicLine>().get(segment).is_some());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Redo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(elements(&world), before + 3);
+
+    // The pasted geometries keep their dependencies, the pasted point drags the pasted segment
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        from,
+        SymbolicPoint::Free(vec2![2. + offset, -offset].into()),
+        SymbolicPoint::Free(vec2![5., 5.].into()),
+      )),
+    );
+    assert_eq!(
+      world.read_storage::<VirtualLine>().get(pasted_segment).unwrap().from.0,
+      vec2![5., 5.]
+    );
+  }
+
+  #[test]
+  fn test_duplicate_copies_the_ancestors() {
+    let (mut world, mut dispatcher) = headless();
+
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 2.].into()));
+    let c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 2.].into()));
+    let d = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 0.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(a, b))),
+    );
+    let l1 = last_inserted::<SymbolicLine>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(c, d))),
+    );
+    let l2 = last_inserted::<SymbolicLine>(&world);
+    let intersection = insert_point(&mut world, &mut dispatcher, SymbolicPoint::LineLineIntersect(l1, l2));
+
+    // The lines are duplicated along with the intersection, once even when one of them is selected
+    let before = elements(&world);
+    for selection in &[vec![intersection], vec![intersection, l1]] {
+      select_only(&mut world, &mut dispatcher, selection);
+      step(&mut world, &mut dispatcher, Command::DuplicateSelection);
+      dispatcher.dispatch(&world);
+      world.maintain();
+      assert_eq!(elements(&world), before + 7);
+
+      let duplicate = last_inserted::<SymbolicPoint>(&world);
+      let (d1, d2) = match world.read_storage::<SymbolicPoint>().get(duplicate) {
+        Some(SymbolicPoint::LineLineIntersect(d1, d2)) => (*d1, *d2),
+        _ => panic!("The intersection is duplicated as an intersection"),
+      };
+      assert!(d1 != l1 && d2 != l2);
+      let offset = ScreenScalar(PASTE_OFFSET).to_virtual(&*world.fetch::<Viewport>()).0;
+      let position = world.read_storage::<VirtualPoint>().get(duplicate).unwrap().0;
+      assert!((position - vec2![1. + offset, 1. - offset]).magnitude() < 1e-9);
+
+      world
+        .fetch_mut::<HistoryEventChannel>()
+        .single_write(HistoryEvent::Undo);
+      dispatcher.dispatch(&world);
+      world.maintain();
+      assert_eq!(elements(&world), before);
+    }
+  }
+}
@@ -0,0 +1,41 @@
+use crate::{events::*, resources::*};
+use specs::prelude::*;
+
+pub struct ConstraintHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for ConstraintHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ConstraintHandler {
+  type SystemData = (
+    Read<'a, CommandEventChannel>,
+    Write<'a, AngleConstraints>,
+    Write<'a, Constraints>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (command_event_channel, mut angle_constraints, mut constraints): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::ConstrainAngle(constraint) => angle_constraints.insert(constraint),
+          Command::RemoveAngleConstraint(line_a, line_b) => angle_constraints.remove(line_a, line_b),
+          Command::Constrain(constraint) => constraints.insert(constraint),
+          Command::RemoveConstraint(constraint) => constraints.remove(&constraint),
+          _ => (),
+        }
+      }
+    }
+  }
+}
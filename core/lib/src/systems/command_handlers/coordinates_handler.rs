@@ -0,0 +1,70 @@
+use crate::{
+  components::{markers::*, symbolics::*},
+  events::*,
+};
+use specs::prelude::*;
+
+pub struct CoordinatesHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for CoordinatesHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for CoordinatesHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, ShowCoordinates>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (entities, command_event_channel, selecteds, sym_points, mut show_coordinates): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::Coordinates(coordinates_event) => match coordinates_event {
+            CoordinatesEvent::Toggle(ent) => toggle(ent, &sym_points, &mut show_coordinates),
+            CoordinatesEvent::ToggleSelected => {
+              let selected_points = (&entities, &selecteds, &sym_points)
+                .join()
+                .map(|(ent, _, _)| ent)
+                .collect::<Vec<_>>();
+              for ent in selected_points {
+                toggle(ent, &sym_points, &mut show_coordinates);
+              }
+            }
+          },
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn toggle<'a>(
+  ent: Entity,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  show_coordinates: &mut WriteStorage<'a, ShowCoordinates>,
+) {
+  // Only points have coordinates
+  if sym_points.get(ent).is_none() {
+    return;
+  }
+  if show_coordinates.remove(ent).is_none() {
+    if let Err(err) = show_coordinates.insert(ent, ShowCoordinates) {
+      panic!(err)
+    }
+  }
+}
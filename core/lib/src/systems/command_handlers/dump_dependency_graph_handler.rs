@@ -0,0 +1,124 @@
+use crate::{components::symbolics::*, events::*, resources::*};
+use specs::prelude::*;
+
+pub struct DumpDependencyGraphHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for DumpDependencyGraphHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for DumpDependencyGraphHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, DebugEventChannel>,
+    Read<'a, DependencyGraph>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (entities, command_event_channel, mut debug_event_channel, dependency_graph, sym_points, sym_lines, sym_circles): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        if let Command::DumpDependencyGraph = event.command {
+          let mut nodes = vec![];
+          for (ent, sym_point) in (&entities, &sym_points).join() {
+            nodes.push((ent, format!("Point::{}", sym_point.kind())));
+          }
+          for (ent, sym_line) in (&entities, &sym_lines).join() {
+            nodes.push((ent, format!("Line::{}", sym_line.kind())));
+          }
+          for (ent, sym_circle) in (&entities, &sym_circles).join() {
+            nodes.push((ent, format!("Circle::{}", sym_circle.kind())));
+          }
+          debug_event_channel.single_write(DebugEvent::DependencyGraph(dependency_graph.to_dot(&nodes)));
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  fn find<T: Component>(world: &World, pred: impl Fn(&T) -> bool) -> Entity {
+    (&world.entities(), &world.read_storage::<T>())
+      .join()
+      .find(|(_, t)| pred(t))
+      .map(|(ent, _)| ent)
+      .unwrap()
+  }
+
+  #[test]
+  fn test_dump_midpoint_line_dependency_graph() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<DebugEventChannel>().register_reader();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![0., 0.])),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![2., 0.])),
+    );
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    let (p1, p2) = (points[0], points[1]);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::MidPoint(p1, p2))),
+    );
+    let mid = find::<SymbolicPoint>(&world, |p| p.kind() == "MidPoint");
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(p1, mid))),
+    );
+    let line = find::<SymbolicLine>(&world, |_| true);
+    step(&mut world, &mut dispatcher, Command::DumpDependencyGraph);
+
+    let dumps = world
+      .fetch::<DebugEventChannel>()
+      .read(&mut reader)
+      .map(|DebugEvent::DependencyGraph(dot)| dot.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(dumps.len(), 1);
+    let dot = &dumps[0];
+    assert!(dot.starts_with("digraph dependencies {\n"));
+    assert!(dot.contains(&format!("e{} [label=\"Point::Fixed\"];", p1.id())));
+    assert!(dot.contains(&format!("e{} [label=\"Point::Fixed\"];", p2.id())));
+    assert!(dot.contains(&format!("e{} [label=\"Point::MidPoint\"];", mid.id())));
+    assert!(dot.contains(&format!("e{} [label=\"Line::Straight\"];", line.id())));
+    assert_eq!(dot.matches("[label=").count(), 4);
+
+    let edges = vec![(p1, mid), (p2, mid), (p1, line), (mid, line)];
+    for (parent, child) in &edges {
+      assert!(dot.contains(&format!("e{} -> e{};", parent.id(), child.id())));
+    }
+    assert_eq!(dot.matches(" -> ").count(), edges.len());
+  }
+}
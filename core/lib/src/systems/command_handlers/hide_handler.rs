@@ -1,5 +1,10 @@
-use crate::{components::markers::*, events::*};
+use crate::{
+  components::{markers::*, symbolics::*},
+  events::*,
+  resources::*,
+};
 use specs::prelude::*;
+use std::collections::HashSet;
 
 pub struct HideHandler {
   command_event_reader: Option<CommandEventReader>,
@@ -18,6 +23,13 @@ impl<'a> System<'a> for HideHandler {
     Entities<'a>,
     Read<'a, CommandEventChannel>,
     Write<'a, MarkerEventChannel>,
+    Read<'a, LayerManager>,
+    Read<'a, DependencyGraph>,
+    ReadStorage<'a, Layer>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicCircle>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, SymbolicConic>,
     WriteStorage<'a, Selected>,
     WriteStorage<'a, Hidden>,
   );
@@ -29,7 +41,20 @@ impl<'a> System<'a> for HideHandler {
 
   fn run(
     &mut self,
-    (entities, command_event_channel, mut marker_event_channel, mut selecteds, mut hiddens): Self::SystemData,
+    (
+      entities,
+      command_event_channel,
+      mut marker_event_channel,
+      layer_manager,
+      dependency_graph,
+      layers,
+      sym_lines,
+      sym_circles,
+      sym_arcs,
+      sym_conics,
+      mut selecteds,
+      mut hiddens,
+    ): Self::SystemData,
   ) {
     if let Some(reader) = &mut self.command_event_reader {
       for event in command_event_channel.read(reader) {
@@ -81,15 +106,43 @@ impl<'a> System<'a> for HideHandler {
                 marker_event_channel.single_write(MarkerEvent::Deselect(ent));
               }
             }
+            HideEvent::HideAncestorsOfSelected => {
+              // Only the construction is hidden, the points and the selection itself are kept
+              let selection = (&entities, &selecteds).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+              let mut to_hide = HashSet::new();
+              for ent in &selection {
+                to_hide.extend(dependency_graph.get_all_ancestors(ent));
+              }
+              for ent in to_hide {
+                let is_curve = sym_lines.contains(ent)
+                  || sym_circles.contains(ent)
+                  || sym_arcs.contains(ent)
+                  || sym_conics.contains(ent);
+                if !is_curve || selection.contains(&ent) || hiddens.contains(ent) {
+                  continue;
+                }
+                if let Err(err) = hiddens.insert(ent, Hidden) {
+                  panic!(err)
+                }
+                marker_event_channel.single_write(MarkerEvent::hide(ent));
+              }
+            }
             HideEvent::UnhideAll => {
-              for (ent, _) in (&entities, &hiddens).join() {
+              // The entities on hidden layers stay hidden until their layer is shown
+              let mut to_unhide = Vec::new();
+              for (ent, _, layer) in (&entities, &hiddens, layers.maybe()).join() {
+                if !layer_manager.is_hidden(layer) {
+                  to_unhide.push(ent)
+                }
+              }
+              for ent in to_unhide {
+                hiddens.remove(ent);
                 if let Err(err) = selecteds.insert(ent, Selected) {
                   panic!(err)
                 }
                 marker_event_channel.single_write(MarkerEvent::unhide(ent));
                 marker_event_channel.single_write(MarkerEvent::Select(ent));
               }
-              hiddens.clear();
             }
           },
           _ => (),
@@ -98,3 +151,69 @@ impl<'a> System<'a> for HideHandler {
     }
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_hide_ancestors_of_selected() {
+    let (mut world, mut dispatcher) = headless();
+
+    // An equilateral triangle side, constructed with two circles
+    let mut points = vec![];
+    for position in &[vec2![0., 0.], vec2![2., 0.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+    let mut circles = vec![];
+    for (center, on_circle) in &[(points[0], points[1]), (points[1], points[0])] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(
+          *center, *on_circle,
+        ))),
+      );
+      circles.push(last_inserted::<SymbolicCircle>(&world));
+    }
+    let apex = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::CircleCircleIntersect(circles[0], circles[1], CircleIntersectId::First),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(points[0], apex))),
+    );
+    let side = last_inserted::<SymbolicLine>(&world);
+
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(side)));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Hide(HideEvent::HideAncestorsOfSelected),
+    );
+    {
+      let hiddens = world.read_storage::<Hidden>();
+      assert!(circles.iter().all(|circle| hiddens.contains(*circle)));
+      assert!(!hiddens.contains(side) && !hiddens.contains(apex));
+      assert!(points.iter().all(|point| !hiddens.contains(*point)));
+    }
+
+    // Shown again in a single undo step
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<Hidden>().join().count(), 0);
+  }
+}
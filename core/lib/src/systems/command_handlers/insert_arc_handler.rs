@@ -0,0 +1,219 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts arcs defined by three distinct points. Arcs defined by anything else than points are
+/// ignored
+pub struct InsertArcHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for InsertArcHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for InsertArcHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
+    Read<'a, DefaultArcStyle>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, SymbolicArc>,
+    WriteStorage<'a, ArcStyle>,
+    WriteStorage<'a, Selected>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
+      default_arc_style,
+      sym_points,
+      mut sym_arcs,
+      mut arc_styles,
+      mut selecteds,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::ArcInsert(InsertArcEvent::InsertArc(sym_arc)) => {
+            let points = sym_arc.points();
+            let [p1, p2, p3] = points;
+            if p1 == p2 || p2 == p3 || p1 == p3 || !points.iter().all(|p| sym_points.contains(*p)) {
+              continue;
+            }
+            if let Err(err) = entity_budget.take() {
+              error_event_channel.single_write(err);
+              continue;
+            }
+            let ent = entities.create();
+            let (ent, geom) = insert(
+              ent,
+              sym_arc,
+              default_arc_style.get(),
+              &mut sym_arcs,
+              &mut arc_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          Command::ArcInsert(InsertArcEvent::InsertArcByHistory(ent, sym_arc, arc_style)) => {
+            let (ent, geom) = insert(
+              ent,
+              sym_arc,
+              arc_style,
+              &mut sym_arcs,
+              &mut arc_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  sym_arc: SymbolicArc,
+  arc_style: ArcStyle,
+  sym_arcs: &mut WriteStorage<'a, SymbolicArc>,
+  arc_styles: &mut WriteStorage<'a, ArcStyle>,
+  selecteds: &mut WriteStorage<'a, Selected>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  if let Err(err) = sym_arcs.insert(ent, sym_arc) {
+    panic!(err)
+  }
+  if let Err(err) = arc_styles.insert(ent, arc_style) {
+    panic!(err)
+  }
+  if let Err(err) = selecteds.insert(ent, Selected) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Arc(sym_arc, arc_style))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, math::*, test_utils::*};
+  use std::f64::consts::PI;
+
+  #[test]
+  fn test_arc_intersection_follows_its_arc_and_undo() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for position in &[vec2![0., 0.], vec2![2., 0.], vec2![0., 2.], vec2![1., 1.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+
+    // An arc needs three different points
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ArcInsert(InsertArcEvent::InsertArc(SymbolicArc::CenterTwoPoint(
+        points[0], points[1], points[1],
+      ))),
+    );
+    assert_eq!(world.read_storage::<SymbolicArc>().join().count(), 0);
+
+    // A quarter of the circle of radius 2, from the x axis to the y axis
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ArcInsert(InsertArcEvent::InsertArc(SymbolicArc::CenterTwoPoint(
+        points[0], points[1], points[2],
+      ))),
+    );
+    let arc = last_inserted::<SymbolicArc>(&world);
+    let virt_arc = *world.read_storage::<VirtualArc>().get(arc).unwrap();
+    assert!((virt_arc.radius.0 - 2.).abs() < 1e-9);
+    assert!((virt_arc.sweep - PI / 2.).abs() < 1e-9);
+    assert!(world.read_storage::<ScreenArc>().get(arc).is_some());
+
+    // The diagonal crosses the circle twice but the arc only once
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(
+        points[0], points[3],
+      ))),
+    );
+    let line = last_inserted::<SymbolicLine>(&world);
+    let itsct = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::ArcLineIntersect(arc, line, CircleIntersectId::Second),
+    );
+    let position = |world: &World| world.read_storage::<VirtualPoint>().get(itsct).map(|p| p.0);
+    let diag = 2f64.sqrt();
+    assert!((position(&world).unwrap() - vec2![diag, diag]).magnitude() < 1e-9);
+
+    // Moving the start of the arc changes its radius
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        points[1],
+        SymbolicPoint::Free(vec2![2., 0.].into()),
+        SymbolicPoint::Free(vec2![4., 0.].into()),
+      )),
+    );
+    assert!((position(&world).unwrap() - vec2![2. * diag, 2. * diag]).magnitude() < 1e-9);
+
+    // Removing the arc removes the intersection, undo brings both back
+    step(&mut world, &mut dispatcher, Command::Remove(RemoveEvent::Remove(arc)));
+    assert!(world.read_storage::<SymbolicArc>().get(arc).is_none());
+    assert!(position(&world).is_none());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert!(world.read_storage::<SymbolicArc>().get(arc).is_some());
+    assert!((position(&world).unwrap() - vec2![2. * diag, 2. * diag]).magnitude() < 1e-9);
+  }
+}
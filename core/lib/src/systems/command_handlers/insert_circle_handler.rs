@@ -24,7 +24,11 @@ impl<'a> System<'a> for InsertCircleHandler {
     Read<'a, CommandEventChannel>,
     Write<'a, GeometryEventChannel>,
     Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
     Read<'a, DefaultCircleStyle>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
     WriteStorage<'a, SymbolicCircle>,
     WriteStorage<'a, CircleStyle>,
     WriteStorage<'a, Selected>,
@@ -43,7 +47,11 @@ impl<'a> System<'a> for InsertCircleHandler {
       command_event_channel,
       mut geometry_event_channel,
       mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
       default_circle_style,
+      sym_points,
+      sym_lines,
       mut sym_circles,
       mut circle_styles,
       mut selecteds,
@@ -55,6 +63,10 @@ impl<'a> System<'a> for InsertCircleHandler {
         match event.command {
           Command::CircleInsert(insert_circle_event) => match insert_circle_event {
             InsertCircleEvent::InsertCircle(sym_circle) => {
+              if let Err(err) = entity_budget.take() {
+                error_event_channel.single_write(err);
+                continue;
+              }
               let ent = entities.create();
               let circle_style = default_circle_style.get();
               let (ent, geom) = insert(
@@ -69,7 +81,53 @@ impl<'a> System<'a> for InsertCircleHandler {
               geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
               marker_event_channel.single_write(MarkerEvent::Select(ent));
             }
+            InsertCircleEvent::InsertCircumcircleFromSelection => {
+              if let Some(sym_circle) = create_circumcircle_from_selection(&entities, &sym_points, &selecteds) {
+                if let Err(err) = entity_budget.take() {
+                  error_event_channel.single_write(err);
+                  continue;
+                }
+                let ent = entities.create();
+                let circle_style = default_circle_style.get();
+                let (ent, geom) = insert(
+                  ent,
+                  sym_circle,
+                  circle_style,
+                  &mut sym_circles,
+                  &mut circle_styles,
+                  &mut selecteds,
+                  &mut elements,
+                );
+                geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+                marker_event_channel.single_write(MarkerEvent::Select(ent));
+              }
+            }
+            InsertCircleEvent::InsertCompassFromSelection => {
+              if let Some(sym_circle) = create_compass_from_selection(&entities, &sym_points, &sym_lines, &selecteds) {
+                if let Err(err) = entity_budget.take() {
+                  error_event_channel.single_write(err);
+                  continue;
+                }
+                let ent = entities.create();
+                let circle_style = default_circle_style.get();
+                let (ent, geom) = insert(
+                  ent,
+                  sym_circle,
+                  circle_style,
+                  &mut sym_circles,
+                  &mut circle_styles,
+                  &mut selecteds,
+                  &mut elements,
+                );
+                geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+                marker_event_channel.single_write(MarkerEvent::Select(ent));
+              }
+            }
             InsertCircleEvent::InsertCircleWithStyle(sym_circle, circle_style) => {
+              if let Err(err) = entity_budget.take() {
+                error_event_channel.single_write(err);
+                continue;
+              }
               let ent = entities.create();
               let (ent, geom) = insert(
                 ent,
@@ -127,3 +185,188 @@ fn insert<'a>(
   }
   (ent, Geometry::Circle(sym_circle, circle_style))
 }
+
+/// The circle through the selected points, when exactly three points and nothing else are selected
+fn create_circumcircle_from_selection<'a>(
+  entities: &Entities<'a>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  selecteds: &WriteStorage<'a, Selected>,
+) -> Option<SymbolicCircle> {
+  let mut points = vec![];
+  for (ent, _) in (entities, selecteds).join() {
+    if sym_points.get(ent).is_none() || points.len() == 3 {
+      return None;
+    }
+    points.push(ent);
+  }
+  match points.as_slice() {
+    [p1, p2, p3] => Some(SymbolicCircle::ThreePoints(*p1, *p2, *p3)),
+    _ => None,
+  }
+}
+
+/// The circle around the only selected point whose radius is the length of the only selected
+/// segment, like a compass set on the segment and moved to the point
+fn create_compass_from_selection<'a>(
+  entities: &Entities<'a>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  sym_lines: &ReadStorage<'a, SymbolicLine>,
+  selecteds: &WriteStorage<'a, Selected>,
+) -> Option<SymbolicCircle> {
+  let (mut center, mut segment) = (None, None);
+  for (ent, _) in (entities, selecteds).join() {
+    match (sym_points.get(ent), sym_lines.get(ent)) {
+      (Some(_), _) if center.is_none() => center = Some(ent),
+      (_, Some(&SymbolicLine::Segment(p1, p2))) if segment.is_none() => segment = Some((p1, p2)),
+      _ => return None,
+    }
+  }
+  match (center, segment) {
+    (Some(center), Some((p1, p2))) => Some(SymbolicCircle::CompassRadius(center, p1, p2)),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_insert_circumcircle_from_selection() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for position in &[vec2![0., 0.], vec2![6., 0.], vec2![0., 8.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+
+    // Two points are not enough
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    for ent in &points[..2] {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircumcircleFromSelection),
+    );
+    assert_eq!(world.read_storage::<SymbolicCircle>().join().count(), 0);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Select(SelectEvent::Select(points[2])),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircumcircleFromSelection),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+    {
+      let virt_circles = world.read_storage::<VirtualCircle>();
+      let virt_circle = virt_circles.get(circle).unwrap();
+      assert_eq!(virt_circle.center.0, vec2![3., 4.]);
+      assert!((virt_circle.radius.0 - 5.).abs() < 1e-12);
+    }
+
+    // The circle follows the points, and is undefined once they are on a line
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        points[2],
+        SymbolicPoint::Free(vec2![0., 8.].into()),
+        SymbolicPoint::Free(vec2![0., 6.].into()),
+      )),
+    );
+    assert_eq!(
+      world.read_storage::<VirtualCircle>().get(circle).unwrap().center.0,
+      vec2![3., 3.]
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        points[2],
+        SymbolicPoint::Free(vec2![0., 6.].into()),
+        SymbolicPoint::Free(vec2![3., 0.].into()),
+      )),
+    );
+    assert!(world.read_storage::<VirtualCircle>().get(circle).is_none());
+  }
+
+  #[test]
+  fn test_insert_compass_from_selection() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for position in &[vec2![0., 0.], vec2![3., 4.], vec2![10., 10.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(points[0], points[1]))),
+    );
+    let segment = last_inserted::<SymbolicLine>(&world);
+
+    // A point alone is not enough
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Select(SelectEvent::Select(points[2])),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCompassFromSelection),
+    );
+    assert_eq!(world.read_storage::<SymbolicCircle>().join().count(), 0);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Select(SelectEvent::Select(segment)),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCompassFromSelection),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+    {
+      let virt_circles = world.read_storage::<VirtualCircle>();
+      let virt_circle = virt_circles.get(circle).unwrap();
+      assert_eq!(virt_circle.center.0, vec2![10., 10.]);
+      assert!((virt_circle.radius.0 - 5.).abs() < 1e-12);
+    }
+
+    // The radius follows the length of the segment
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        points[1],
+        SymbolicPoint::Free(vec2![3., 4.].into()),
+        SymbolicPoint::Free(vec2![0., 2.].into()),
+      )),
+    );
+    let virt_circles = world.read_storage::<VirtualCircle>();
+    let virt_circle = virt_circles.get(circle).unwrap();
+    assert_eq!(virt_circle.center.0, vec2![10., 10.]);
+    assert!((virt_circle.radius.0 - 2.).abs() < 1e-12);
+  }
+}
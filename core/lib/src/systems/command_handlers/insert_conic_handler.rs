@@ -0,0 +1,213 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts conics defined by distinct points. Conics defined by anything else than points are
+/// ignored
+pub struct InsertConicHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for InsertConicHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for InsertConicHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
+    Read<'a, DefaultConicStyle>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, SymbolicConic>,
+    WriteStorage<'a, ConicStyle>,
+    WriteStorage<'a, Selected>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
+      default_conic_style,
+      sym_points,
+      mut sym_conics,
+      mut conic_styles,
+      mut selecteds,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::ConicInsert(InsertConicEvent::InsertConic(sym_conic)) => {
+            let points = sym_conic.points();
+            let distinct = points.iter().enumerate().all(|(i, p)| !points[..i].contains(p));
+            if !distinct || !points.iter().all(|p| sym_points.contains(*p)) {
+              continue;
+            }
+            if let Err(err) = entity_budget.take() {
+              error_event_channel.single_write(err);
+              continue;
+            }
+            let ent = entities.create();
+            let (ent, geom) = insert(
+              ent,
+              sym_conic,
+              default_conic_style.get(),
+              &mut sym_conics,
+              &mut conic_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          Command::ConicInsert(InsertConicEvent::InsertConicByHistory(ent, sym_conic, conic_style)) => {
+            let (ent, geom) = insert(
+              ent,
+              sym_conic,
+              conic_style,
+              &mut sym_conics,
+              &mut conic_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  sym_conic: SymbolicConic,
+  conic_style: ConicStyle,
+  sym_conics: &mut WriteStorage<'a, SymbolicConic>,
+  conic_styles: &mut WriteStorage<'a, ConicStyle>,
+  selecteds: &mut WriteStorage<'a, Selected>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  if let Err(err) = sym_conics.insert(ent, sym_conic) {
+    panic!(err)
+  }
+  if let Err(err) = conic_styles.insert(ent, conic_style) {
+    panic!(err)
+  }
+  if let Err(err) = selecteds.insert(ent, Selected) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Conic(sym_conic, conic_style))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, math::*, test_utils::*};
+
+  #[test]
+  fn test_point_on_conic_follows_its_conic_and_undo() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    let positions = [
+      vec2![-3., 0.],
+      vec2![3., 0.],
+      vec2![0., 4.],
+      vec2![1., 1.],
+      vec2![2., 0.5],
+      vec2![-1., -1.],
+    ];
+    for position in &positions {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+
+    // The ellipse with foci on the x axis going through the top point has radii 5 and 4
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ConicInsert(InsertConicEvent::InsertConic(SymbolicConic::Ellipse(
+        points[0], points[1], points[2],
+      ))),
+    );
+    let conic = last_inserted::<SymbolicConic>(&world);
+    let virt_conic = *world.read_storage::<VirtualConic>().get(conic).unwrap();
+    assert!((virt_conic.rx.0 - 5.).abs() < 1e-9 && (virt_conic.ry.0 - 4.).abs() < 1e-9);
+    assert!(world.read_storage::<ScreenConic>().get(conic).is_some());
+
+    let on_conic = insert_point(&mut world, &mut dispatcher, SymbolicPoint::OnConic(conic, 0.));
+    let position = |world: &World| world.read_storage::<VirtualPoint>().get(on_conic).map(|p| p.0);
+    assert!((position(&world).unwrap() - vec2![5., 0.]).magnitude() < 1e-9);
+
+    // Moving the point the ellipse goes through makes it wider
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        points[2],
+        SymbolicPoint::Free(vec2![0., 4.].into()),
+        SymbolicPoint::Free(vec2![0., 8.].into()),
+      )),
+    );
+    assert!((position(&world).unwrap() - vec2![73f64.sqrt(), 0.]).magnitude() < 1e-9);
+
+    // Five points on no ellipse give no conic
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ConicInsert(InsertConicEvent::InsertConic(SymbolicConic::FivePoints(
+        points[0], points[1], points[3], points[4], points[5],
+      ))),
+    );
+    let five_points = last_inserted::<SymbolicConic>(&world);
+    assert_ne!(five_points, conic);
+    assert!(world.read_storage::<VirtualConic>().get(five_points).is_none());
+
+    // Removing the conic removes the point on it, undo brings both back
+    step(&mut world, &mut dispatcher, Command::Remove(RemoveEvent::Remove(conic)));
+    assert!(world.read_storage::<SymbolicConic>().get(conic).is_none());
+    assert!(position(&world).is_none());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert!(world.read_storage::<SymbolicConic>().get(conic).is_some());
+    assert!((position(&world).unwrap() - vec2![73f64.sqrt(), 0.]).magnitude() < 1e-9);
+  }
+}
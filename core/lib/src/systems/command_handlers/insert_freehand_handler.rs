@@ -0,0 +1,179 @@
+use crate::{
+  components::{freehand::*, markers::*, styles::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts the freehand strokes, with the default line style. Strokes without any sample are
+/// ignored
+pub struct InsertFreehandHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for InsertFreehandHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for InsertFreehandHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
+    Read<'a, DefaultLineStyle>,
+    WriteStorage<'a, FreehandStroke>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, Selected>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
+      default_line_style,
+      mut strokes,
+      mut line_styles,
+      mut selecteds,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(stroke)) => {
+            if stroke.samples.is_empty() {
+              continue;
+            }
+            if let Err(err) = entity_budget.take() {
+              error_event_channel.single_write(err);
+              continue;
+            }
+            let ent = entities.create();
+            let (ent, geom) = insert(
+              ent,
+              stroke.clone(),
+              default_line_style.get(),
+              &mut strokes,
+              &mut line_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          Command::FreehandInsert(InsertFreehandEvent::InsertFreehandByHistory(ent, stroke, line_style)) => {
+            let (ent, geom) = insert(
+              *ent,
+              stroke.clone(),
+              *line_style,
+              &mut strokes,
+              &mut line_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  stroke: FreehandStroke,
+  line_style: LineStyle,
+  strokes: &mut WriteStorage<'a, FreehandStroke>,
+  line_styles: &mut WriteStorage<'a, LineStyle>,
+  selecteds: &mut WriteStorage<'a, Selected>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  if let Err(err) = strokes.insert(ent, stroke.clone()) {
+    panic!(err)
+  }
+  if let Err(err) = line_styles.insert(ent, line_style) {
+    panic!(err)
+  }
+  if let Err(err) = selecteds.insert(ent, Selected) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Freehand(stroke, line_style))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_insert_remove_and_undo_stroke() {
+    let (mut world, mut dispatcher) = headless();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(FreehandStroke { samples: vec![] })),
+    );
+    assert_eq!(world.read_storage::<FreehandStroke>().join().count(), 0);
+
+    let stroke = FreehandStroke {
+      samples: vec![(vec2![0., 0.].into(), 0.5), (vec2![1., 1.].into(), 1.)],
+    };
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(stroke.clone())),
+    );
+    let ent = (&world.entities(), &world.read_storage::<FreehandStroke>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+    assert!(world.read_storage::<Selected>().contains(ent));
+    let scrn_stroke = world.read_storage::<ScreenFreehandStroke>().get(ent).cloned().unwrap();
+    assert_eq!(scrn_stroke.samples.len(), 2);
+    assert_eq!(scrn_stroke.samples[1].1, 1.);
+
+    // Erased like any other geometry, undo brings it back as it was drawn
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Remove(RemoveEvent::RemoveSelected),
+    );
+    assert!(world.read_storage::<FreehandStroke>().get(ent).is_none());
+    assert!(world.read_storage::<LineStyle>().get(ent).is_none());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<FreehandStroke>().get(ent), Some(&stroke));
+    assert!(world.read_storage::<ScreenFreehandStroke>().get(ent).is_some());
+  }
+}
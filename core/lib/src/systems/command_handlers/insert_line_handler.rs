@@ -24,6 +24,8 @@ impl<'a> System<'a> for InsertLineHandler {
     Read<'a, CommandEventChannel>,
     Write<'a, GeometryEventChannel>,
     Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
     Read<'a, DefaultLineStyle>,
     ReadStorage<'a, SymbolicPoint>,
     WriteStorage<'a, SymbolicLine>,
@@ -44,6 +46,8 @@ impl<'a> System<'a> for InsertLineHandler {
       command_event_channel,
       mut geometry_event_channel,
       mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
       default_line_style,
       sym_points,
       mut sym_lines,
@@ -57,6 +61,10 @@ impl<'a> System<'a> for InsertLineHandler {
         match event.command {
           Command::LineInsert(insert_line_event) => match insert_line_event {
             InsertLineEvent::InsertLine(sym_line) => {
+              if let Err(err) = entity_budget.take() {
+                error_event_channel.single_write(err);
+                continue;
+              }
               let ent = entities.create();
               let line_style = default_line_style.get();
               let (ent, geom) = insert(
@@ -75,6 +83,10 @@ impl<'a> System<'a> for InsertLineHandler {
               if let Some((l_ent, p_ents)) = check_perp_para_selection(&entities, &sym_points, &sym_lines, &selecteds) {
                 for p_ent in p_ents {
                   let sym_line = SymbolicLine::Parallel(l_ent, p_ent);
+                  if let Err(err) = entity_budget.take() {
+                    error_event_channel.single_write(err);
+                    break;
+                  }
                   let ent = entities.create();
                   let line_style = default_line_style.get();
                   let (ent, geom) = insert(
@@ -95,6 +107,10 @@ impl<'a> System<'a> for InsertLineHandler {
               if let Some((l_ent, p_ents)) = check_perp_para_selection(&entities, &sym_points, &sym_lines, &selecteds) {
                 for p_ent in p_ents {
                   let sym_line = SymbolicLine::Perpendicular(l_ent, p_ent);
+                  if let Err(err) = entity_budget.take() {
+                    error_event_channel.single_write(err);
+                    break;
+                  }
                   let ent = entities.create();
                   let line_style = default_line_style.get();
                   let (ent, geom) = insert(
@@ -111,7 +127,33 @@ impl<'a> System<'a> for InsertLineHandler {
                 }
               }
             }
+            InsertLineEvent::InsertPerpendicularBisectorFromSelection => {
+              if let Some((p1_ent, p2_ent)) = check_bisector_selection(&entities, &sym_points, &sym_lines, &selecteds) {
+                let sym_line = SymbolicLine::PerpendicularBisector(p1_ent, p2_ent);
+                if let Err(err) = entity_budget.take() {
+                  error_event_channel.single_write(err);
+                  continue;
+                }
+                let ent = entities.create();
+                let line_style = default_line_style.get();
+                let (ent, geom) = insert(
+                  ent,
+                  sym_line,
+                  line_style,
+                  &mut sym_lines,
+                  &mut line_styles,
+                  &mut selecteds,
+                  &mut elements,
+                );
+                geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+                marker_event_channel.single_write(MarkerEvent::Select(ent));
+              }
+            }
             InsertLineEvent::InsertLineWithStyle(sym_line, line_style) => {
+              if let Err(err) = entity_budget.take() {
+                error_event_channel.single_write(err);
+                continue;
+              }
               let ent = entities.create();
               let (ent, geom) = insert(
                 ent,
@@ -200,3 +242,89 @@ pub fn check_perp_para_selection<'a>(
     None
   }
 }
+
+/// The two points to bisect, either selected alone or as the points of the only selected line
+fn check_bisector_selection<'a>(
+  entities: &Entities<'a>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  sym_lines: &WriteStorage<'a, SymbolicLine>,
+  selecteds: &WriteStorage<'a, Selected>,
+) -> Option<(Entity, Entity)> {
+  let selected = (entities, selecteds).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+  match selected.as_slice() {
+    [p1, p2] if sym_points.get(*p1).is_some() && sym_points.get(*p2).is_some() => Some((*p1, *p2)),
+    [line] => match sym_lines.get(*line) {
+      Some(SymbolicLine::Straight(p1, p2)) | Some(SymbolicLine::Ray(p1, p2)) | Some(SymbolicLine::Segment(p1, p2)) => {
+        Some((*p1, *p2))
+      }
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_insert_perpendicular_bisector_from_selection() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for position in &[vec2![0., 0.], vec2![4., 2.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(points[0], points[1]))),
+    );
+    let segment = last_inserted::<SymbolicLine>(&world);
+
+    // Selecting the segment is the same as selecting its two points
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Select(SelectEvent::Select(segment)),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertPerpendicularBisectorFromSelection),
+    );
+    let bisector = last_inserted::<SymbolicLine>(&world);
+    assert_ne!(bisector, segment);
+    let line: Line = (*world.read_storage::<VirtualLine>().get(bisector).unwrap()).into();
+    assert_eq!(line.from, vec2![2., 1.]);
+    assert_eq!(line.direction().dot(vec2![4., 2.]), 0.);
+
+    // The bisector follows its points and is undone as a single line
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        points[1],
+        SymbolicPoint::Free(vec2![4., 2.].into()),
+        SymbolicPoint::Free(vec2![0., 6.].into()),
+      )),
+    );
+    let line: Line = (*world.read_storage::<VirtualLine>().get(bisector).unwrap()).into();
+    assert_eq!(line.from, vec2![0., 3.]);
+    assert_eq!(line.direction().y, 0.);
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert!(world.read_storage::<SymbolicLine>().get(bisector).is_none());
+    assert!(world.read_storage::<SymbolicLine>().get(segment).is_some());
+  }
+}
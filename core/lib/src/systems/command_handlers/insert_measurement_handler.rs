@@ -0,0 +1,110 @@
+use crate::{
+  components::{markers::*, measurements::*, symbolics::*},
+  events::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts measurements as elements of their own, depending on the geometries they measure so
+/// that they are removed along with them and undone like any other geometry
+pub struct InsertMeasurementHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for InsertMeasurementHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for InsertMeasurementHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicCircle>,
+    ReadStorage<'a, SymbolicPolygon>,
+    WriteStorage<'a, Measurement>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      sym_points,
+      sym_lines,
+      sym_circles,
+      sym_polygons,
+      mut measurements,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::MeasurementInsert(insert_measurement_event) => match insert_measurement_event {
+            InsertMeasurementEvent::InsertMeasurement(measurement) => {
+              if measures_right_kinds(&measurement, &sym_points, &sym_lines, &sym_circles, &sym_polygons) {
+                let ent = entities.create();
+                let (ent, geom) = insert(ent, measurement, &mut measurements, &mut elements);
+                geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+              }
+            }
+            InsertMeasurementEvent::InsertMeasurementByHistory(ent, measurement) => {
+              let (ent, geom) = insert(ent, measurement, &mut measurements, &mut elements);
+              geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            }
+          },
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  measurement: Measurement,
+  measurements: &mut WriteStorage<'a, Measurement>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  if let Err(err) = measurements.insert(ent, measurement) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Measurement(measurement))
+}
+
+/// Distances and angles are measured on points, radii on circles, lengths on segments and areas
+/// on polygons
+fn measures_right_kinds<'a>(
+  measurement: &Measurement,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  sym_lines: &ReadStorage<'a, SymbolicLine>,
+  sym_circles: &ReadStorage<'a, SymbolicCircle>,
+  sym_polygons: &ReadStorage<'a, SymbolicPolygon>,
+) -> bool {
+  match *measurement {
+    Measurement::DistancePP(p1, p2) => sym_points.contains(p1) && sym_points.contains(p2),
+    Measurement::Angle(p1, vertex, p2) => {
+      sym_points.contains(p1) && sym_points.contains(vertex) && sym_points.contains(p2)
+    }
+    Measurement::Radius(c) => sym_circles.contains(c),
+    Measurement::SegmentLength(l) => matches!(sym_lines.get(l), Some(SymbolicLine::Segment(_, _))),
+    Measurement::PolygonArea(p) => sym_polygons.contains(p),
+  }
+}
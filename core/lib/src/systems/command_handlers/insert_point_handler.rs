@@ -1,11 +1,14 @@
 use crate::{
-  components::{markers::*, styles::*, symbolics::*},
+  components::{markers::*, styles::*, symbolics::*, virtual_shapes::*},
   events::*,
+  math::*,
   resources::*,
   utilities::*,
 };
 use specs::prelude::*;
 
+static SAME_POINT_THRESHOLD: f64 = 1e-9; // Virtual distance under which an intersection exists already
+
 pub struct InsertPointHandler {
   command_event_reader: Option<CommandEventReader>,
 }
@@ -24,11 +27,18 @@ impl<'a> System<'a> for InsertPointHandler {
     Read<'a, CommandEventChannel>,
     Write<'a, GeometryEventChannel>,
     Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
     Read<'a, DefaultPointStyle>,
+    Read<'a, DependencyGraph>,
     WriteStorage<'a, SymbolicPoint>,
     WriteStorage<'a, PointStyle>,
     WriteStorage<'a, Selected>,
     WriteStorage<'a, Element>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualLine>,
+    ReadStorage<'a, VirtualCircle>,
+    ReadStorage<'a, VirtualArc>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -43,11 +53,18 @@ impl<'a> System<'a> for InsertPointHandler {
       command_event_channel,
       mut geometry_event_channel,
       mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
       default_point_style,
+      dependency_graph,
       mut sym_points,
       mut point_styles,
       mut selecteds,
       mut elements,
+      virt_points,
+      virt_lines,
+      virt_circles,
+      virt_arcs,
     ): Self::SystemData,
   ) {
     if let Some(reader) = &mut self.command_event_reader {
@@ -55,7 +72,31 @@ impl<'a> System<'a> for InsertPointHandler {
         match event.command {
           Command::PointInsert(insert_point_event) => match insert_point_event {
             InsertPointEvent::InsertPoint(sym_point) => {
+              if let Err(err) = entity_budget.take() {
+                error_event_channel.single_write(err);
+                continue;
+              }
+              let ent = entities.create();
+              let point_style = default_point_style.get();
+              let (ent, geom) = insert(
+                ent,
+                sym_point,
+                point_style,
+                &mut sym_points,
+                &mut point_styles,
+                &mut selecteds,
+                &mut elements,
+              );
+              geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+              marker_event_channel.single_write(MarkerEvent::Select(ent));
+            }
+            InsertPointEvent::InsertPointAt(position) => {
+              if let Err(err) = entity_budget.take() {
+                error_event_channel.single_write(err);
+                continue;
+              }
               let ent = entities.create();
+              let sym_point = SymbolicPoint::Fixed(VirtualPosition(position));
               let point_style = default_point_style.get();
               let (ent, geom) = insert(
                 ent,
@@ -71,6 +112,40 @@ impl<'a> System<'a> for InsertPointHandler {
             }
             InsertPointEvent::InsertMidPointFromSelection => {
               if let Some(sym_point) = create_midpoint_from_selection(&entities, &sym_points, &selecteds) {
+                if let Err(err) = entity_budget.take() {
+                  error_event_channel.single_write(err);
+                  continue;
+                }
+                let ent = entities.create();
+                let point_style = default_point_style.get();
+                let (ent, geom) = insert(
+                  ent,
+                  sym_point,
+                  point_style,
+                  &mut sym_points,
+                  &mut point_styles,
+                  &mut selecteds,
+                  &mut elements,
+                );
+                geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+                marker_event_channel.single_write(MarkerEvent::Select(ent));
+              }
+            }
+            InsertPointEvent::InsertIntersectionsFromSelection => {
+              let sym_points_to_insert = create_intersections_from_selection(
+                &entities,
+                &selecteds,
+                &dependency_graph,
+                &virt_points,
+                &virt_lines,
+                &virt_circles,
+                &virt_arcs,
+              );
+              for sym_point in sym_points_to_insert {
+                if let Err(err) = entity_budget.take() {
+                  error_event_channel.single_write(err);
+                  break;
+                }
                 let ent = entities.create();
                 let point_style = default_point_style.get();
                 let (ent, geom) = insert(
@@ -87,6 +162,10 @@ impl<'a> System<'a> for InsertPointHandler {
               }
             }
             InsertPointEvent::InsertPointWithStyle(sym_point, point_style) => {
+              if let Err(err) = entity_budget.take() {
+                error_event_channel.single_write(err);
+                continue;
+              }
               let ent = entities.create();
               let (ent, geom) = insert(
                 ent,
@@ -169,3 +248,278 @@ pub fn create_midpoint_from_selection<'a>(
     _ => None,
   }
 }
+
+#[derive(Clone, Copy)]
+enum Curve {
+  Line(VirtualLine),
+  Circle(VirtualCircle),
+  Arc(VirtualArc),
+}
+
+/// All the intersections of the two selected lines, circles or arcs, but the ones that exist
+/// already. Two arcs have no intersection point to insert
+pub fn create_intersections_from_selection<'a>(
+  entities: &Entities<'a>,
+  selecteds: &WriteStorage<'a, Selected>,
+  dependency_graph: &DependencyGraph,
+  virt_points: &ReadStorage<'a, VirtualPoint>,
+  virt_lines: &ReadStorage<'a, VirtualLine>,
+  virt_circles: &ReadStorage<'a, VirtualCircle>,
+  virt_arcs: &ReadStorage<'a, VirtualArc>,
+) -> Vec<SymbolicPoint> {
+  let selected = (entities, selecteds).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+  if selected.len() != 2 {
+    return vec![];
+  }
+  let (a, b) = (selected[0], selected[1]);
+  let curve = |ent: Entity| {
+    if let Some(line) = virt_lines.get(ent) {
+      Some(Curve::Line(*line))
+    } else if let Some(circle) = virt_circles.get(ent) {
+      Some(Curve::Circle(*circle))
+    } else {
+      virt_arcs.get(ent).map(|arc| Curve::Arc(*arc))
+    }
+  };
+  let intersections = match (curve(a), curve(b)) {
+    (Some(Curve::Line(l1)), Some(Curve::Line(l2))) => match l1.intersect(l2) {
+      Some(p) => vec![(SymbolicPoint::LineLineIntersect(a, b), p)],
+      None => vec![],
+    },
+    (Some(Curve::Circle(c)), Some(Curve::Line(l))) => {
+      circle_intersections(c.intersect(l), |id| SymbolicPoint::CircleLineIntersect(a, b, id))
+    }
+    (Some(Curve::Line(l)), Some(Curve::Circle(c))) => {
+      circle_intersections(c.intersect(l), |id| SymbolicPoint::CircleLineIntersect(b, a, id))
+    }
+    (Some(Curve::Circle(c1)), Some(Curve::Circle(c2))) => {
+      circle_intersections(c1.intersect(c2), |id| SymbolicPoint::CircleCircleIntersect(a, b, id))
+    }
+    (Some(Curve::Arc(arc)), Some(Curve::Line(l))) => {
+      circle_intersections(arc.intersect(l), |id| SymbolicPoint::ArcLineIntersect(a, b, id))
+    }
+    (Some(Curve::Line(l)), Some(Curve::Arc(arc))) => {
+      circle_intersections(arc.intersect(l), |id| SymbolicPoint::ArcLineIntersect(b, a, id))
+    }
+    (Some(Curve::Arc(arc)), Some(Curve::Circle(c))) => {
+      circle_intersections(arc.intersect(c), |id| SymbolicPoint::ArcCircleIntersect(a, b, id))
+    }
+    (Some(Curve::Circle(c)), Some(Curve::Arc(arc))) => {
+      circle_intersections(arc.intersect(c), |id| SymbolicPoint::ArcCircleIntersect(b, a, id))
+    }
+    _ => vec![],
+  };
+
+  // The points depending on both curves are where their intersections can exist already
+  let dependents = |ent: Entity| {
+    dependency_graph
+      .get_direct_dependents(&ent)
+      .cloned()
+      .unwrap_or_default()
+  };
+  let existing = dependents(a)
+    .intersection(&dependents(b))
+    .filter_map(|ent| virt_points.get(*ent))
+    .cloned()
+    .collect::<Vec<_>>();
+  intersections
+    .into_iter()
+    .filter(|(_, p)| existing.iter().all(|q| (*q - *p).magnitude().0 > SAME_POINT_THRESHOLD))
+    .map(|(sym_point, _)| sym_point)
+    .collect()
+}
+
+fn circle_intersections<F: Fn(CircleIntersectId) -> SymbolicPoint>(
+  intersect: VirtualCircleIntersect,
+  sym_point: F,
+) -> Vec<(SymbolicPoint, VirtualPosition)> {
+  match intersect {
+    VirtualCircleIntersect::TwoPoints(p1, p2) => vec![
+      (sym_point(CircleIntersectId::First), p1),
+      (sym_point(CircleIntersectId::Second), p2),
+    ],
+    VirtualCircleIntersect::OnePoint(p) => vec![(sym_point(CircleIntersectId::First), p)],
+    VirtualCircleIntersect::None => vec![],
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::test_utils::*;
+
+  #[test]
+  fn test_insert_point_at_exact_position() {
+    let (mut world, mut dispatcher) = headless();
+
+    // Positions no mouse could land on, nor any snapping
+    for position in &[vec2![0.1, -0.3], vec2![1e-9, 12345.678901], vec2![-7.25, 1. / 3.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(*position)),
+      );
+      let p = last_inserted::<SymbolicPoint>(&world);
+      assert_eq!(
+        world.read_storage::<SymbolicPoint>().get(p),
+        Some(&SymbolicPoint::Fixed(VirtualPosition(*position)))
+      );
+      assert_eq!(world.read_storage::<VirtualPoint>().get(p).unwrap().0, *position);
+    }
+  }
+
+  #[test]
+  fn test_insert_past_max_entities_is_refused() {
+    let (mut world, mut dispatcher) = headless();
+    world.insert(MaxEntities(2));
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+
+    for x in &[0., 1., 2.] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, 0.])),
+      );
+    }
+    let p = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(p, p))),
+    );
+    assert_eq!(world.read_storage::<Element>().join().count(), 2);
+    assert_eq!(world.read_storage::<SymbolicLine>().join().count(), 0);
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter(|event| matches!(event, ErrorEvent::TooManyEntities(2)))
+      .count();
+    assert_eq!(errors, 2);
+
+    // Removing makes room again
+    step(&mut world, &mut dispatcher, Command::Remove(RemoveEvent::Remove(p)));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![3., 0.])),
+    );
+    assert_eq!(world.read_storage::<Element>().join().count(), 2);
+  }
+
+  #[test]
+  fn test_inserts_of_one_frame_share_max_entities() {
+    let (mut world, mut dispatcher) = headless();
+    world.insert(MaxEntities(3));
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+
+    let mut points = vec![];
+    for x in &[0., 1.] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, 0.])),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+
+    // Room for one more. The intersections of the selected point insert nothing and take no room,
+    // then the point and the line of the same frame compete for it
+    for command in vec![
+      Command::PointInsert(InsertPointEvent::InsertIntersectionsFromSelection),
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![2., 0.])),
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(
+        points[0], points[1],
+      ))),
+    ] {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+        command,
+        event_id: None,
+      });
+    }
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<Element>().join().count(), 3);
+    let inserted =
+      world.read_storage::<SymbolicPoint>().join().count() + world.read_storage::<SymbolicLine>().join().count();
+    assert_eq!(inserted, 3);
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter(|event| matches!(event, ErrorEvent::TooManyEntities(3)))
+      .count();
+    assert_eq!(errors, 1);
+  }
+
+  #[test]
+  fn test_insert_intersections_skips_the_existing_ones() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for (x, y) in &[(0., 0.), (2., 0.), (-3., 0.), (3., 0.), (-3., 2.), (3., 2.)] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, *y])),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(
+        points[0], points[1],
+      ))),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(
+        points[2], points[3],
+      ))),
+    );
+    let secant = last_inserted::<SymbolicLine>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(
+        points[4], points[5],
+      ))),
+    );
+    let tangent = last_inserted::<SymbolicLine>(&world);
+
+    let intersections = |world: &mut World, dispatcher: &mut Dispatcher, line: Entity| {
+      step(world, dispatcher, Command::Select(SelectEvent::DeselectAll));
+      step(world, dispatcher, Command::Select(SelectEvent::Select(circle)));
+      step(world, dispatcher, Command::Select(SelectEvent::Select(line)));
+      let before = world.read_storage::<Element>().join().count();
+      step(
+        world,
+        dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertIntersectionsFromSelection),
+      );
+      world.read_storage::<Element>().join().count() - before
+    };
+    assert_eq!(intersections(&mut world, &mut dispatcher, secant), 2);
+    assert_eq!(intersections(&mut world, &mut dispatcher, tangent), 1);
+    assert_eq!(
+      world
+        .read_storage::<VirtualPoint>()
+        .get(last_inserted::<SymbolicPoint>(&world))
+        .unwrap()
+        .0,
+      vec2![0., 2.]
+    );
+
+    // Only the missing intersections are inserted again
+    assert_eq!(intersections(&mut world, &mut dispatcher, secant), 0);
+    let removed = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Remove(RemoveEvent::Remove(removed)),
+    );
+    assert_eq!(intersections(&mut world, &mut dispatcher, tangent), 1);
+    assert_eq!(intersections(&mut world, &mut dispatcher, tangent), 0);
+  }
+}
@@ -0,0 +1,229 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts polygons through at least three distinct points. Polygons through anything else than
+/// points are ignored
+pub struct InsertPolygonHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for InsertPolygonHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for InsertPolygonHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
+    Read<'a, DefaultPolygonStyle>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, SymbolicPolygon>,
+    WriteStorage<'a, PolygonStyle>,
+    WriteStorage<'a, Selected>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
+      default_polygon_style,
+      sym_points,
+      mut sym_polygons,
+      mut polygon_styles,
+      mut selecteds,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          Command::PolygonInsert(InsertPolygonEvent::InsertPolygon(sym_polygon)) => {
+            if !is_valid_polygon(sym_polygon, &sym_points) {
+              continue;
+            }
+            if let Err(err) = entity_budget.take() {
+              error_event_channel.single_write(err);
+              continue;
+            }
+            let ent = entities.create();
+            let (ent, geom) = insert(
+              ent,
+              sym_polygon.clone(),
+              default_polygon_style.get(),
+              &mut sym_polygons,
+              &mut polygon_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          Command::PolygonInsert(InsertPolygonEvent::InsertPolygonByHistory(ent, sym_polygon, polygon_style)) => {
+            let (ent, geom) = insert(
+              *ent,
+              sym_polygon.clone(),
+              *polygon_style,
+              &mut sym_polygons,
+              &mut polygon_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  sym_polygon: SymbolicPolygon,
+  polygon_style: PolygonStyle,
+  sym_polygons: &mut WriteStorage<'a, SymbolicPolygon>,
+  polygon_styles: &mut WriteStorage<'a, PolygonStyle>,
+  selecteds: &mut WriteStorage<'a, Selected>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  if let Err(err) = sym_polygons.insert(ent, sym_polygon.clone()) {
+    panic!(err)
+  }
+  if let Err(err) = polygon_styles.insert(ent, polygon_style) {
+    panic!(err)
+  }
+  if let Err(err) = selecteds.insert(ent, Selected) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Polygon(sym_polygon, polygon_style))
+}
+
+fn is_valid_polygon<'a>(sym_polygon: &SymbolicPolygon, sym_points: &ReadStorage<'a, SymbolicPoint>) -> bool {
+  let vertices = &sym_polygon.0;
+  vertices.len() >= 3
+    && vertices.iter().all(|ent| sym_points.contains(*ent))
+    && vertices.iter().enumerate().all(|(i, ent)| !vertices[..i].contains(ent))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::measurements::*, math::*, test_utils::*};
+
+  #[test]
+  fn test_polygon_follows_vertices_and_undo() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut vertices = vec![];
+    for position in &[vec2![0., 0.], vec2![4., 0.], vec2![4., 3.], vec2![0., 3.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      vertices.push(last_inserted::<SymbolicPoint>(&world));
+    }
+
+    // Two vertices, or the same vertex twice, don't make a polygon
+    let invalid = vec![vertices[..2].to_vec(), vec![vertices[0], vertices[1], vertices[0]]];
+    for sym_polygon in invalid {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PolygonInsert(InsertPolygonEvent::InsertPolygon(SymbolicPolygon(sym_polygon))),
+      );
+    }
+    assert_eq!(world.read_storage::<SymbolicPolygon>().join().count(), 0);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PolygonInsert(InsertPolygonEvent::InsertPolygon(SymbolicPolygon(vertices.clone()))),
+    );
+    let polygon = last_inserted::<SymbolicPolygon>(&world);
+    assert_eq!(
+      world
+        .read_storage::<ScreenPolygon>()
+        .get(polygon)
+        .unwrap()
+        .vertices
+        .len(),
+      4
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::MeasurementInsert(InsertMeasurementEvent::InsertMeasurement(Measurement::PolygonArea(
+        polygon,
+      ))),
+    );
+    let area = last_inserted::<Measurement>(&world);
+    let area_of = |world: &World| world.read_storage::<MeasuredValue>().get(area).map(|value| value.0);
+    assert_eq!(area_of(&world), Some(12.));
+
+    // Dragging a vertex reshapes the polygon
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        vertices[2],
+        SymbolicPoint::Free(vec2![4., 3.].into()),
+        SymbolicPoint::Free(vec2![4., 6.].into()),
+      )),
+    );
+    assert_eq!(
+      world.read_storage::<VirtualPolygon>().get(polygon).unwrap().vertices[2].0,
+      vec2![4., 6.]
+    );
+    assert_eq!(area_of(&world), Some(18.));
+
+    // Removing any vertex removes the polygon and its area, undo brings them back
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Remove(RemoveEvent::Remove(vertices[3])),
+    );
+    assert!(world.read_storage::<SymbolicPolygon>().get(polygon).is_none());
+    assert!(world.read_storage::<Measurement>().get(area).is_none());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(
+      world.read_storage::<SymbolicPolygon>().get(polygon),
+      Some(&SymbolicPolygon(vertices))
+    );
+    assert_eq!(area_of(&world), Some(18.));
+  }
+}
@@ -0,0 +1,217 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts text annotations. Empty texts and texts anchored to anything else than a point, a line
+/// or a circle are ignored
+pub struct InsertTextHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for InsertTextHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for InsertTextHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
+    Read<'a, DefaultTextStyle>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicCircle>,
+    WriteStorage<'a, SymbolicText>,
+    WriteStorage<'a, TextStyle>,
+    WriteStorage<'a, Selected>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
+      default_text_style,
+      sym_points,
+      sym_lines,
+      sym_circles,
+      mut sym_texts,
+      mut text_styles,
+      mut selecteds,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          Command::TextInsert(InsertTextEvent::InsertText(sym_text)) => {
+            if sym_text.text.trim().is_empty() {
+              continue;
+            }
+            if let TextAnchor::Entity(anchor_ent, _) = sym_text.anchor {
+              if !sym_points.contains(anchor_ent)
+                && !sym_lines.contains(anchor_ent)
+                && !sym_circles.contains(anchor_ent)
+              {
+                continue;
+              }
+            }
+            if let Err(err) = entity_budget.take() {
+              error_event_channel.single_write(err);
+              continue;
+            }
+            let ent = entities.create();
+            let (ent, geom) = insert(
+              ent,
+              sym_text.clone(),
+              default_text_style.get(),
+              &mut sym_texts,
+              &mut text_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          Command::TextInsert(InsertTextEvent::InsertTextByHistory(ent, sym_text, text_style)) => {
+            let (ent, geom) = insert(
+              *ent,
+              sym_text.clone(),
+              *text_style,
+              &mut sym_texts,
+              &mut text_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  sym_text: SymbolicText,
+  text_style: TextStyle,
+  sym_texts: &mut WriteStorage<'a, SymbolicText>,
+  text_styles: &mut WriteStorage<'a, TextStyle>,
+  selecteds: &mut WriteStorage<'a, Selected>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  if let Err(err) = sym_texts.insert(ent, sym_text.clone()) {
+    panic!(err)
+  }
+  if let Err(err) = text_styles.insert(ent, text_style) {
+    panic!(err)
+  }
+  if let Err(err) = selecteds.insert(ent, Selected) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Text(sym_text, text_style))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  fn text(text: &str, anchor: TextAnchor) -> Command {
+    Command::TextInsert(InsertTextEvent::InsertText(SymbolicText {
+      text: text.to_string(),
+      anchor,
+    }))
+  }
+
+  #[test]
+  fn test_text_follows_its_anchor_and_undo() {
+    let (mut world, mut dispatcher) = headless();
+
+    let point = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 1.].into()));
+
+    // Blank texts say nothing
+    step(
+      &mut world,
+      &mut dispatcher,
+      text("  ", TextAnchor::Position(vec2![0., 0.].into())),
+    );
+    assert_eq!(world.read_storage::<SymbolicText>().join().count(), 0);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      text("Origin", TextAnchor::Position(vec2![0., 0.].into())),
+    );
+    let free_text = last_inserted::<SymbolicText>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      text("Apex", TextAnchor::Entity(point, vec2![0., 2.].into())),
+    );
+    let anchored_text = last_inserted::<SymbolicText>(&world);
+    let position = |world: &World, ent: Entity| world.read_storage::<VirtualText>().get(ent).map(|t| t.position.0);
+    assert_eq!(position(&world, free_text), Some(vec2![0., 0.]));
+    assert_eq!(position(&world, anchored_text), Some(vec2![1., 3.]));
+    assert!(world.read_storage::<ScreenText>().get(anchored_text).is_some());
+
+    // Dragging the point moves its text but not the other one
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        point,
+        SymbolicPoint::Free(vec2![1., 1.].into()),
+        SymbolicPoint::Free(vec2![-2., 0.].into()),
+      )),
+    );
+    assert_eq!(position(&world, anchored_text), Some(vec2![-2., 2.]));
+    assert_eq!(position(&world, free_text), Some(vec2![0., 0.]));
+
+    // Removing the point removes its text, undo brings it back
+    step(&mut world, &mut dispatcher, Command::Remove(RemoveEvent::Remove(point)));
+    assert!(world.read_storage::<SymbolicText>().get(anchored_text).is_none());
+    assert!(world.read_storage::<SymbolicText>().get(free_text).is_some());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(
+      world
+        .read_storage::<SymbolicText>()
+        .get(anchored_text)
+        .map(|t| t.text.clone()),
+      Some("Apex".to_string())
+    );
+    assert_eq!(position(&world, anchored_text), Some(vec2![-2., 2.]));
+  }
+}
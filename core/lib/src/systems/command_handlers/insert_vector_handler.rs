@@ -0,0 +1,199 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts vectors between two distinct points. Vectors from or to anything else than points are
+/// ignored
+pub struct InsertVectorHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for InsertVectorHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for InsertVectorHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, EntityBudget>,
+    Read<'a, DefaultVectorStyle>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, SymbolicVector>,
+    WriteStorage<'a, VectorStyle>,
+    WriteStorage<'a, Selected>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut marker_event_channel,
+      mut error_event_channel,
+      mut entity_budget,
+      default_vector_style,
+      sym_points,
+      mut sym_vectors,
+      mut vector_styles,
+      mut selecteds,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::VectorInsert(InsertVectorEvent::InsertVector(sym_vector)) => {
+            let SymbolicVector(from, to) = sym_vector;
+            if from == to || !sym_points.contains(from) || !sym_points.contains(to) {
+              continue;
+            }
+            if let Err(err) = entity_budget.take() {
+              error_event_channel.single_write(err);
+              continue;
+            }
+            let ent = entities.create();
+            let (ent, geom) = insert(
+              ent,
+              sym_vector,
+              default_vector_style.get(),
+              &mut sym_vectors,
+              &mut vector_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          Command::VectorInsert(InsertVectorEvent::InsertVectorByHistory(ent, sym_vector, vector_style)) => {
+            let (ent, geom) = insert(
+              ent,
+              sym_vector,
+              vector_style,
+              &mut sym_vectors,
+              &mut vector_styles,
+              &mut selecteds,
+              &mut elements,
+            );
+            geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            marker_event_channel.single_write(MarkerEvent::Select(ent));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  sym_vector: SymbolicVector,
+  vector_style: VectorStyle,
+  sym_vectors: &mut WriteStorage<'a, SymbolicVector>,
+  vector_styles: &mut WriteStorage<'a, VectorStyle>,
+  selecteds: &mut WriteStorage<'a, Selected>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  if let Err(err) = sym_vectors.insert(ent, sym_vector) {
+    panic!(err)
+  }
+  if let Err(err) = vector_styles.insert(ent, vector_style) {
+    panic!(err)
+  }
+  if let Err(err) = selecteds.insert(ent, Selected) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Vector(sym_vector, vector_style))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_vector_follows_its_points_and_undo() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for position in &[vec2![1., 1.], vec2![4., 5.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+
+    // A vector from a point to itself points nowhere
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::VectorInsert(InsertVectorEvent::InsertVector(SymbolicVector(points[0], points[0]))),
+    );
+    assert_eq!(world.read_storage::<SymbolicVector>().join().count(), 0);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::VectorInsert(InsertVectorEvent::InsertVector(SymbolicVector(points[0], points[1]))),
+    );
+    let vector = last_inserted::<SymbolicVector>(&world);
+    let tip = |world: &World| world.read_storage::<VirtualVector>().get(vector).map(|v| v.to.0);
+    assert_eq!(tip(&world), Some(vec2![4., 5.]));
+    assert!(world.read_storage::<ScreenVector>().get(vector).is_some());
+
+    // Dragging the tip moves the vector
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        points[1],
+        SymbolicPoint::Free(vec2![4., 5.].into()),
+        SymbolicPoint::Free(vec2![-2., 0.].into()),
+      )),
+    );
+    assert_eq!(tip(&world), Some(vec2![-2., 0.]));
+
+    // Removing either point removes the vector, undo brings it back
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Remove(RemoveEvent::Remove(points[0])),
+    );
+    assert!(world.read_storage::<SymbolicVector>().get(vector).is_none());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(
+      world.read_storage::<SymbolicVector>().get(vector),
+      Some(&SymbolicVector(points[0], points[1]))
+    );
+    assert_eq!(tip(&world), Some(vec2![-2., 0.]));
+  }
+}
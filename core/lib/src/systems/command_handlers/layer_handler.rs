@@ -0,0 +1,219 @@
+use crate::{components::markers::*, events::*, resources::*};
+use specs::prelude::*;
+use std::collections::HashSet;
+
+/// Handles the layer commands. Hiding a layer hides its entities, so that they are skipped like
+/// any hidden entity. Only the entities hidden by their layer come back when it is shown again
+pub struct LayerHandler {
+  command_event_reader: Option<CommandEventReader>,
+  hidden_by_layer: HashSet<Entity>,
+}
+
+impl Default for LayerHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+      hidden_by_layer: HashSet::new(),
+    }
+  }
+}
+
+impl<'a> System<'a> for LayerHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, LayerManager>,
+    Write<'a, SelectionOrder>,
+    WriteStorage<'a, Layer>,
+    WriteStorage<'a, Selected>,
+    WriteStorage<'a, Hidden>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut error_event_channel,
+      mut marker_event_channel,
+      mut layer_manager,
+      mut selection_order,
+      mut layers,
+      mut selecteds,
+      mut hiddens,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          Command::Layer(layer_event) => match layer_event {
+            LayerEvent::Create(name) => {
+              layer_manager.create(name.clone());
+            }
+            LayerEvent::Rename(layer, name) => match layer_manager.get_mut(*layer) {
+              Some(info) => info.name = name.clone(),
+              None => error_event_channel.single_write(ErrorEvent::UnknownLayer(*layer)),
+            },
+            LayerEvent::SetLocked(layer, locked) => match layer_manager.get_mut(*layer) {
+              Some(info) => info.locked = *locked,
+              None => error_event_channel.single_write(ErrorEvent::UnknownLayer(*layer)),
+            },
+            LayerEvent::SetHidden(layer, hidden) => match layer_manager.get_mut(*layer) {
+              Some(info) => {
+                info.hidden = *hidden;
+                let on_layer = (&entities, layers.maybe())
+                  .join()
+                  .filter(|(_, l)| *l.unwrap_or(&DEFAULT_LAYER) == *layer)
+                  .map(|(ent, _)| ent)
+                  .collect::<Vec<_>>();
+                for ent in on_layer {
+                  if *hidden {
+                    hide_by_layer(
+                      ent,
+                      &mut self.hidden_by_layer,
+                      &mut marker_event_channel,
+                      &mut selection_order,
+                      &mut selecteds,
+                      &mut hiddens,
+                    );
+                  } else if self.hidden_by_layer.remove(&ent) {
+                    hiddens.remove(ent);
+                    marker_event_channel.single_write(MarkerEvent::unhide_by_history(ent));
+                  }
+                }
+              }
+              None => error_event_channel.single_write(ErrorEvent::UnknownLayer(*layer)),
+            },
+            LayerEvent::MoveSelectedTo(layer) => {
+              if layer_manager.get(*layer).is_none() {
+                error_event_channel.single_write(ErrorEvent::UnknownLayer(*layer));
+                continue;
+              }
+              let to_move = (&entities, &selecteds).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+              for ent in to_move {
+                if let Err(err) = layers.insert(ent, *layer) {
+                  panic!(err)
+                }
+                if layer_manager.is_hidden(Some(layer)) {
+                  hide_by_layer(
+                    ent,
+                    &mut self.hidden_by_layer,
+                    &mut marker_event_channel,
+                    &mut selection_order,
+                    &mut selecteds,
+                    &mut hiddens,
+                  );
+                }
+              }
+            }
+          },
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// Hiding an entity because of its layer is not an undo step, the marker event is hence written
+/// as if done by history
+fn hide_by_layer<'a>(
+  ent: Entity,
+  hidden_by_layer: &mut HashSet<Entity>,
+  marker_event_channel: &mut MarkerEventChannel,
+  selection_order: &mut SelectionOrder,
+  selecteds: &mut WriteStorage<'a, Selected>,
+  hiddens: &mut WriteStorage<'a, Hidden>,
+) {
+  if hiddens.get(ent).is_none() {
+    if let Err(err) = hiddens.insert(ent, Hidden) {
+      panic!(err)
+    }
+    if selecteds.remove(ent).is_some() {
+      selection_order.remove(ent);
+      marker_event_channel.single_write(MarkerEvent::Deselect(ent));
+    }
+    marker_event_channel.single_write(MarkerEvent::hide_by_history(ent));
+    hidden_by_layer.insert(ent);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, math::*, test_utils::*};
+
+  #[test]
+  fn test_hidden_and_locked_layers() {
+    let (mut world, mut dispatcher) = headless();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![1., 1.])),
+    );
+    let point = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Layer(LayerEvent::Create("Construction".to_string())),
+    );
+    let layer = world
+      .fetch::<LayerManager>()
+      .iter()
+      .map(|(layer, _)| layer)
+      .last()
+      .unwrap();
+
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(point)));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Layer(LayerEvent::MoveSelectedTo(layer)),
+    );
+    assert_eq!(world.read_storage::<Layer>().get(point), Some(&layer));
+
+    // Locked layers keep their entities
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Layer(LayerEvent::SetLocked(layer, true)),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Remove(RemoveEvent::RemoveSelected),
+    );
+    assert!(world.read_storage::<SymbolicPoint>().get(point).is_some());
+
+    // Hidden layers hide their entities, unhiding all of them does not show the layer again
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Layer(LayerEvent::SetHidden(layer, true)),
+    );
+    assert!(world.read_storage::<Hidden>().get(point).is_some());
+    assert!(world.read_storage::<Selected>().get(point).is_none());
+    step(&mut world, &mut dispatcher, Command::Hide(HideEvent::UnhideAll));
+    assert!(world.read_storage::<Hidden>().get(point).is_some());
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::SelectAll));
+    assert!(world.read_storage::<Selected>().get(point).is_none());
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Layer(LayerEvent::SetHidden(layer, false)),
+    );
+    assert!(world.read_storage::<Hidden>().get(point).is_none());
+  }
+}
@@ -0,0 +1,140 @@
+use crate::{components::symbolics::*, events::*};
+use specs::prelude::*;
+
+pub struct LineTypeHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for LineTypeHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for LineTypeHandler {
+  type SystemData = (
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    WriteStorage<'a, SymbolicLine>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (command_event_channel, mut geometry_event_channel, mut sym_lines): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::ChangeLineType(ent, line_type) => {
+            // The entity and its two points stay the same, so do the dependents
+            let old_sym_line = match sym_lines.get(ent) {
+              Some(sym_line) => *sym_line,
+              None => continue,
+            };
+            if let Some(new_sym_line) = old_sym_line.with_line_type(line_type) {
+              if let Err(err) = sym_lines.insert(ent, new_sym_line) {
+                panic!(err)
+              }
+              geometry_event_channel.single_write(GeometryEvent::line_updated(ent, old_sym_line, new_sym_line));
+            }
+          }
+          Command::Update(UpdateEvent::UpdateLineByHistory(ent, old_sym_line, new_sym_line)) => {
+            if let Err(err) = sym_lines.insert(ent, new_sym_line) {
+              panic!(err)
+            }
+            geometry_event_channel.single_write(GeometryEvent::line_updated_by_history(
+              ent,
+              old_sym_line,
+              new_sym_line,
+            ));
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, math::*, resources::*, test_utils::*, utilities::*};
+
+  #[test]
+  fn test_segment_to_straight() {
+    let (mut world, mut dispatcher) = headless();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![-1., 0.])),
+    );
+    let p1 = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![1., 0.])),
+    );
+    let p2 = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, p2))),
+    );
+    let line = last_inserted::<SymbolicLine>(&world);
+    let on_line = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::OnLine(line, VirtualScalar(0.75)),
+    );
+    let before = *world.read_storage::<ScreenLine>().get(line).unwrap();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ChangeLineType(line, LineType::Straight),
+    );
+    {
+      let sym_lines = world.read_storage::<SymbolicLine>();
+      match sym_lines.get(line) {
+        Some(SymbolicLine::Straight(from, to)) => assert_eq!((*from, *to), (p1, p2)),
+        _ => panic!("Expected a straight line through the same points"),
+      }
+      let scrn_lines = world.read_storage::<ScreenLine>();
+      let after = scrn_lines.get(line).unwrap();
+      assert_eq!(after.line_type, LineType::Straight);
+      assert_eq!((after.from.0, after.to.0), (before.from.0, before.to.0));
+
+      // Clipped to the screen, the line now goes from one side of the viewport to the other
+      let screen_aabb = world.fetch::<Viewport>().screen_aabb();
+      let (from, to) = after.intersect(screen_aabb).unwrap();
+      assert!((from.0.x - screen_aabb.x_min()).abs() < 1e-9 || (from.0.x - screen_aabb.x_max()).abs() < 1e-9);
+      assert!((to.0.x - screen_aabb.x_min()).abs() < 1e-9 || (to.0.x - screen_aabb.x_max()).abs() < 1e-9);
+      assert!(((to.0 - from.0).magnitude() - screen_aabb.width).abs() < 1e-9);
+
+      // The dependents are kept and solved again
+      assert!(world.read_storage::<VirtualPoint>().get(on_line).is_some());
+    }
+
+    // The change is undone like any other
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    match world.read_storage::<SymbolicLine>().get(line) {
+      Some(SymbolicLine::Segment(_, _)) => (),
+      _ => panic!("Expected the segment back"),
+    }
+    assert_eq!(
+      world.read_storage::<ScreenLine>().get(line).unwrap().line_type,
+      LineType::Segment
+    );
+  }
+}
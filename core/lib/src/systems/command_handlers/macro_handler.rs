@@ -0,0 +1,315 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Records constructions as macros and applies them to new inputs. Defining a macro keeps every
+/// geometry between the inputs and the outputs as a template. Applying it inserts the whole
+/// construction again on the new inputs as a single undo step, the intermediate geometries hidden
+/// and the outputs selected. When defined from the selection, the inputs are the selected
+/// geometries depending on no other selected one, in the order they got selected, the outputs are
+/// all the others
+pub struct MacroHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for MacroHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for MacroHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, MacroRegistry>,
+    Write<'a, History>,
+    Read<'a, DependencyGraph>,
+    Read<'a, SelectionOrder>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, PointStyle>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, LineStyle>,
+    ReadStorage<'a, SymbolicCircle>,
+    ReadStorage<'a, CircleStyle>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, ArcStyle>,
+    ReadStorage<'a, SymbolicConic>,
+    ReadStorage<'a, ConicStyle>,
+    ReadStorage<'a, SymbolicPolygon>,
+    ReadStorage<'a, PolygonStyle>,
+    ReadStorage<'a, SymbolicVector>,
+    ReadStorage<'a, VectorStyle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      mut command_event_channel,
+      mut error_event_channel,
+      mut macro_registry,
+      mut history,
+      dependency_graph,
+      selection_order,
+      selecteds,
+      sym_points,
+      point_styles,
+      sym_lines,
+      line_styles,
+      sym_circles,
+      circle_styles,
+      sym_arcs,
+      arc_styles,
+      sym_conics,
+      conic_styles,
+      sym_polygons,
+      polygon_styles,
+      sym_vectors,
+      vector_styles,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let commands = command_event_channel
+        .read(reader)
+        .filter(|event| {
+          matches!(
+            event.command,
+            Command::DefineMacro { .. } | Command::DefineMacroFromSelection(_) | Command::ApplyMacro(_, _)
+          )
+        })
+        .map(|event| event.command.clone())
+        .collect::<Vec<_>>();
+      let geometry_of = |ent: Entity| -> Option<Geometry> {
+        if let (Some(sym_point), Some(point_style)) = (sym_points.get(ent), point_styles.get(ent)) {
+          Some(Geometry::Point(*sym_point, *point_style))
+        } else if let (Some(sym_line), Some(line_style)) = (sym_lines.get(ent), line_styles.get(ent)) {
+          Some(Geometry::Line(*sym_line, *line_style))
+        } else if let (Some(sym_circle), Some(circle_style)) = (sym_circles.get(ent), circle_styles.get(ent)) {
+          Some(Geometry::Circle(*sym_circle, *circle_style))
+        } else if let (Some(sym_arc), Some(arc_style)) = (sym_arcs.get(ent), arc_styles.get(ent)) {
+          Some(Geometry::Arc(*sym_arc, *arc_style))
+        } else if let (Some(sym_conic), Some(conic_style)) = (sym_conics.get(ent), conic_styles.get(ent)) {
+          Some(Geometry::Conic(*sym_conic, *conic_style))
+        } else if let (Some(sym_polygon), Some(polygon_style)) = (sym_polygons.get(ent), polygon_styles.get(ent)) {
+          Some(Geometry::Polygon(sym_polygon.clone(), *polygon_style))
+        } else if let (Some(sym_vector), Some(vector_style)) = (sym_vectors.get(ent), vector_styles.get(ent)) {
+          Some(Geometry::Vector(*sym_vector, *vector_style))
+        } else {
+          None
+        }
+      };
+
+      for command in commands {
+        let result = match command {
+          Command::DefineMacro { name, inputs, outputs } => {
+            define(name, &inputs, &outputs, &geometry_of, &dependency_graph).map(|construction_macro| {
+              macro_registry.register(construction_macro);
+            })
+          }
+          Command::DefineMacroFromSelection(name) => {
+            let selected = (&entities, &selecteds)
+              .join()
+              .map(|(ent, _)| ent)
+              .filter(|ent| geometry_of(*ent).is_some())
+              .collect::<HashSet<_>>();
+            let (inputs, outputs): (Vec<_>, Vec<_>) = selection_order
+              .iter()
+              .filter(|ent| selected.contains(ent))
+              .partition(|ent| {
+                dependency_graph
+                  .get_all_ancestors(ent)
+                  .iter()
+                  .all(|ancestor| *ancestor == **ent || !selected.contains(ancestor))
+              });
+            define(name, &inputs, &outputs, &geometry_of, &dependency_graph).map(|construction_macro| {
+              macro_registry.register(construction_macro);
+            })
+          }
+          Command::ApplyMacro(id, inputs) => {
+            let inputs = inputs
+              .iter()
+              .map(|ent| geometry_of(*ent).map(|geometry| (*ent, geometry.kind())))
+              .collect::<Option<Vec<_>>>()
+              .ok_or(MacroError::WrongInputs(id));
+            inputs
+              .and_then(|inputs| macro_registry.instantiate(id, &inputs, || entities.create()))
+              .map(|instances| {
+                let name = macro_registry.get(id).map_or(String::new(), |m| m.name.clone());
+                apply(name, instances, &mut command_event_channel, &mut history);
+              })
+          }
+          _ => Ok(()),
+        };
+        if let Err(err) = result {
+          error_event_channel.single_write(ErrorEvent::InvalidMacro(err));
+        }
+      }
+    }
+  }
+}
+
+/// The macro of the geometries the outputs depend on, up to the inputs
+fn define<F: Fn(Entity) -> Option<Geometry>>(
+  name: String,
+  inputs: &[Entity],
+  outputs: &[Entity],
+  geometry_of: &F,
+  dependency_graph: &DependencyGraph,
+) -> Result<ConstructionMacro, MacroError> {
+  let mut input_kinds = vec![];
+  for input in inputs {
+    let geometry = geometry_of(*input).ok_or(MacroError::NotDeterminedByInputs(*input))?;
+    input_kinds.push((*input, geometry.kind()));
+  }
+
+  let mut steps: HashMap<Entity, Geometry> = HashMap::new();
+  let mut stack = outputs.to_vec();
+  while let Some(ent) = stack.pop() {
+    if inputs.contains(&ent) || steps.contains_key(&ent) {
+      continue;
+    }
+    let geometry = geometry_of(ent).ok_or(MacroError::NotDeterminedByInputs(ent))?;
+    stack.extend(geometry.dependencies());
+    steps.insert(ent, geometry);
+  }
+  let order = dependency_graph.dependency_order(&steps.keys().cloned().collect());
+  let steps = order
+    .into_iter()
+    .filter_map(|ent| steps.remove(&ent).map(|geometry| (ent, geometry)))
+    .collect();
+  ConstructionMacro::new(name, input_kinds, steps, outputs.to_vec())
+}
+
+/// Insert the instantiated geometries under their new entities, hiding the intermediate ones and
+/// selecting the outputs. They are recorded here as a single step
+fn apply(
+  name: String,
+  instances: Vec<(Entity, Geometry, bool)>,
+  command_event_channel: &mut CommandEventChannel,
+  history: &mut History,
+) {
+  let mut commands = vec![];
+  let mut insertions = HashMap::new();
+  let mut hidden = HashSet::new();
+  for (ent, geometry, _) in &instances {
+    commands.push(Command::insert_by_history(*ent, geometry));
+    insertions.insert(*ent, geometry.clone());
+  }
+  // Inserting selects, only the outputs stay selected
+  commands.push(Command::Select(SelectEvent::DeselectAll));
+  for (ent, _, output) in &instances {
+    if *output {
+      commands.push(Command::Select(SelectEvent::Select(*ent)));
+    } else {
+      commands.push(Command::Hide(HideEvent::HideByHistory(*ent)));
+      hidden.insert(*ent);
+    }
+  }
+  for command in commands {
+    command_event_channel.single_write(CommandEvent {
+      command,
+      event_id: None,
+    });
+  }
+
+  let mut modifications = vec![Modification::InsertMany(insertions)];
+  if !hidden.is_empty() {
+    modifications.push(Modification::HideMany(hidden));
+  }
+  history.push(Modification::Transaction(name, modifications));
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_apply_macro_to_new_inputs() {
+    let (mut world, mut dispatcher) = headless();
+
+    // The circle through the two points centered at their mid point
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![4., 0.].into()));
+    let m = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, b));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(m, a))),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+
+    for ent in &[b, a, circle] {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::DefineMacroFromSelection("Diameter circle".to_string()),
+    );
+    let id = world.fetch::<MacroRegistry>().by_name("Diameter circle").unwrap();
+    let kinds = world.fetch::<MacroRegistry>().get(id).unwrap().input_kinds();
+    assert_eq!(kinds, vec![GeometryKind::Point, GeometryKind::Point]);
+
+    let c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![10., 0.].into()));
+    let d = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![10., 6.].into()));
+    let circles_before = world.read_storage::<SymbolicCircle>().join().count();
+    step(&mut world, &mut dispatcher, Command::ApplyMacro(id, vec![d, c]));
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    // The new circle is selected, its center hidden
+    let (new_circle, virt_circle) = (
+      &world.entities(),
+      &world.read_storage::<VirtualCircle>(),
+      &world.read_storage::<Selected>(),
+    )
+      .join()
+      .map(|(ent, virt_circle, _)| (ent, *virt_circle))
+      .next()
+      .unwrap();
+    assert_eq!(
+      world.read_storage::<SymbolicCircle>().join().count(),
+      circles_before + 1
+    );
+    assert_eq!(virt_circle.center.0, vec2![10., 3.]);
+    assert_eq!(virt_circle.radius.0, 3.);
+    let center = match world.read_storage::<SymbolicCircle>().get(new_circle) {
+      Some(SymbolicCircle::CenterRadius(center, _)) => *center,
+      _ => panic!("expected a circle from its center"),
+    };
+    assert!(world.read_storage::<Hidden>().contains(center));
+
+    // Undone at once, and refused on the wrong inputs
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert!(world.read_storage::<SymbolicCircle>().get(new_circle).is_none());
+    assert!(world.read_storage::<SymbolicPoint>().get(center).is_none());
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+    step(&mut world, &mut dispatcher, Command::ApplyMacro(id, vec![c]));
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter(|event| matches!(event, ErrorEvent::InvalidMacro(MacroError::WrongInputs(_))))
+      .count();
+    assert_eq!(errors, 1);
+  }
+}
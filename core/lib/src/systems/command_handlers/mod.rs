@@ -1,15 +1,75 @@
+mod align_handler;
+mod animation_handler;
+mod clipboard_handler;
+mod constraint_handler;
+mod coordinates_handler;
+mod dump_dependency_graph_handler;
 mod hide_handler;
+mod insert_arc_handler;
 mod insert_circle_handler;
+mod insert_conic_handler;
+mod insert_freehand_handler;
 mod insert_line_handler;
+mod insert_measurement_handler;
 mod insert_point_handler;
+mod insert_polygon_handler;
+mod insert_text_handler;
+mod insert_vector_handler;
+mod layer_handler;
+mod line_type_handler;
+mod macro_handler;
+mod redefine_point_handler;
+mod reflect_handler;
 mod remove_handler;
+mod rename_handler;
+mod restyle_handler;
+mod rotate_handler;
+mod scalar_handler;
+mod scale_handler;
 mod select_handler;
+mod sketch_file_handler;
+mod slider_handler;
+mod solver_handler;
+mod theme_handler;
+mod trace_handler;
+mod transform_handler;
+mod underlay_handler;
 mod update_point_handler;
 
+pub use align_handler::*;
+pub use animation_handler::*;
+pub use clipboard_handler::*;
+pub use constraint_handler::*;
+pub use coordinates_handler::*;
+pub use dump_dependency_graph_handler::*;
 pub use hide_handler::*;
+pub use insert_arc_handler::*;
 pub use insert_circle_handler::*;
+pub use insert_conic_handler::*;
+pub use insert_freehand_handler::*;
 pub use insert_line_handler::*;
+pub use insert_measurement_handler::*;
 pub use insert_point_handler::*;
+pub use insert_polygon_handler::*;
+pub use insert_text_handler::*;
+pub use insert_vector_handler::*;
+pub use layer_handler::*;
+pub use line_type_handler::*;
+pub use macro_handler::*;
+pub use redefine_point_handler::*;
+pub use reflect_handler::*;
 pub use remove_handler::*;
+pub use rename_handler::*;
+pub use restyle_handler::*;
+pub use rotate_handler::*;
+pub use scalar_handler::*;
+pub use scale_handler::*;
 pub use select_handler::*;
+pub use sketch_file_handler::*;
+pub use slider_handler::*;
+pub use solver_handler::*;
+pub use theme_handler::*;
+pub use trace_handler::*;
+pub use transform_handler::*;
+pub use underlay_handler::*;
 pub use update_point_handler::*;
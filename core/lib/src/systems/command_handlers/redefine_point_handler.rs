@@ -0,0 +1,210 @@
+use crate::{
+  components::{markers::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Changes what a point is defined by while keeping it where it is: freed from the geometries it
+/// depends on, or put on another line, circle or conic at the closest spot. A point cannot be
+/// put on a geometry depending on it
+pub struct RedefinePointHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for RedefinePointHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for RedefinePointHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    Read<'a, DependencyGraph>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualLine>,
+    ReadStorage<'a, VirtualCircle>,
+    ReadStorage<'a, VirtualConic>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      mut command_event_channel,
+      dependency_graph,
+      selecteds,
+      sym_points,
+      virt_points,
+      virt_lines,
+      virt_circles,
+      virt_conics,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let mut requests = vec![];
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::RedefinePoint(ent, target) => requests.push((ent, target)),
+          Command::RedefineSelectedPoint => {
+            // Exactly one point, and at most one line, circle or conic to put it on
+            let (points, others): (Vec<_>, Vec<_>) = (&entities, &selecteds)
+              .join()
+              .map(|(ent, _)| ent)
+              .partition(|ent| sym_points.contains(*ent));
+            let targets = others
+              .into_iter()
+              .filter(|ent| virt_lines.contains(*ent) || virt_circles.contains(*ent) || virt_conics.contains(*ent))
+              .collect::<Vec<_>>();
+            if points.len() == 1 && targets.len() <= 1 {
+              requests.push((points[0], targets.first().copied()));
+            }
+          }
+          _ => (),
+        }
+      }
+
+      for (ent, target) in requests {
+        let (old_sym_point, position) = match (sym_points.get(ent), virt_points.get(ent)) {
+          (Some(sym_point), Some(virt_point)) => (*sym_point, *virt_point),
+          _ => continue,
+        };
+        let new_sym_point = match target {
+          Some(target) if dependency_graph.get_all_dependents(&ent).contains(&target) => continue,
+          Some(target) => match on_curve(target, position, &virt_lines, &virt_circles, &virt_conics) {
+            Some(sym_point) => sym_point,
+            None => continue,
+          },
+          None if old_sym_point.is_constrained() => SymbolicPoint::Free(position),
+          None => continue,
+        };
+
+        let label = if target.is_some() {
+          "Redefine point"
+        } else {
+          "Free point"
+        };
+        let commands = vec![
+          Command::BeginTransaction(label.to_string()),
+          // Updated like a finished drag, so that the point and its descendants are solved again
+          Command::Update(UpdateEvent::UpdatePoint(ent, old_sym_point, new_sym_point)),
+          Command::Update(UpdateEvent::UpdatePointEnd(ent, old_sym_point, new_sym_point)),
+          Command::EndTransaction,
+        ];
+        for command in commands {
+          command_event_channel.single_write(CommandEvent {
+            command,
+            event_id: None,
+          });
+        }
+      }
+    }
+  }
+}
+
+/// The point of the line, circle or conic `target` closest to `position`
+fn on_curve<'a>(
+  target: Entity,
+  position: VirtualPoint,
+  virt_lines: &ReadStorage<'a, VirtualLine>,
+  virt_circles: &ReadStorage<'a, VirtualCircle>,
+  virt_conics: &ReadStorage<'a, VirtualConic>,
+) -> Option<SymbolicPoint> {
+  let p = position.0;
+  if let Some(&virt_line) = virt_lines.get(target) {
+    let line: Line = virt_line.into();
+    if line.from_to_length() == 0. {
+      return None;
+    }
+    let t = line.rel_t_of_point(line.get_closest_point(p));
+    Some(SymbolicPoint::OnLine(target, t.into()))
+  } else if let Some(&virt_circle) = virt_circles.get(target) {
+    let circle: Circle = virt_circle.into();
+    let p_to_cen = p - circle.center;
+    Some(SymbolicPoint::OnCircle(target, p_to_cen.y.atan2(p_to_cen.x)))
+  } else if let Some(&virt_conic) = virt_conics.get(target) {
+    let ellipse: Ellipse = virt_conic.into();
+    Some(SymbolicPoint::OnConic(
+      target,
+      ellipse.parameter_of(ellipse.get_closest_point(p)),
+    ))
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn assert_position(world: &World, ent: Entity, expected: Vector2) {
+    let position = world.read_storage::<VirtualPoint>().get(ent).unwrap().0;
+    assert!(
+      (position - expected).magnitude() < 1e-12,
+      "{:?} != {:?}",
+      position,
+      expected
+    );
+  }
+
+  #[test]
+  fn test_free_and_redefine_point() {
+    let (mut world, mut dispatcher) = headless();
+
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![4., 0.].into()));
+    let c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 2.].into()));
+    let mid = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, b));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(a, c))),
+    );
+    let line = last_inserted::<SymbolicLine>(&world);
+
+    // The update issued by the handler is handled on the next frame, hence the extra one
+    // Freed where it was, it does not follow its former parents anymore
+    step(&mut world, &mut dispatcher, Command::RedefinePoint(mid, None));
+    dispatch(&mut world, &mut dispatcher, 1);
+    assert_position(&world, mid, vec2![2., 0.]);
+    assert!(!world.fetch::<DependencyGraph>().get_all_dependents(&a).contains(&mid));
+
+    // Then put on the line at the closest spot
+    step(&mut world, &mut dispatcher, Command::RedefinePoint(mid, Some(line)));
+    dispatch(&mut world, &mut dispatcher, 1);
+    assert_position(&world, mid, vec2![0., 0.]);
+    assert!(world
+      .fetch::<DependencyGraph>()
+      .get_all_dependents(&line)
+      .contains(&mid));
+
+    // Not onto a line depending on it
+    step(&mut world, &mut dispatcher, Command::RedefinePoint(a, Some(line)));
+    dispatch(&mut world, &mut dispatcher, 1);
+    assert!(matches!(
+      world.read_storage::<SymbolicPoint>().get(a),
+      Some(SymbolicPoint::Free(_))
+    ));
+
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert!(matches!(
+      world.read_storage::<SymbolicPoint>().get(mid),
+      Some(SymbolicPoint::Free(_))
+    ));
+    assert_position(&world, mid, vec2![2., 0.]);
+  }
+}
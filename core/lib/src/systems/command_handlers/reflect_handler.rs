@@ -0,0 +1,184 @@
+use crate::{
+  components::{markers::*, symbolics::*},
+  events::*,
+};
+use specs::prelude::*;
+
+pub struct ReflectHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for ReflectHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ReflectHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (entities, mut command_event_channel, selecteds, sym_points, sym_lines, sym_circles): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let mirrors = command_event_channel
+        .read(reader)
+        .filter_map(|event| match event.command {
+          Command::ReflectSelected { mirror } => Some(mirror),
+          _ => None,
+        })
+        .collect::<Vec<_>>();
+      for mirror in mirrors {
+        if sym_lines.get(mirror).is_none() {
+          continue;
+        }
+
+        // The reflections depend on their originals and the mirror, so they follow both of them.
+        // They are all inserted on the next frame, which makes them a single step of the history
+        for (ent, _) in (&entities, &selecteds).join() {
+          if ent == mirror {
+            continue;
+          }
+          let command = if sym_points.get(ent).is_some() {
+            Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Reflect(ent, mirror)))
+          } else if sym_lines.get(ent).is_some() {
+            Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Reflect(ent, mirror)))
+          } else if sym_circles.get(ent).is_some() {
+            Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::Reflect(ent, mirror)))
+          } else {
+            continue;
+          };
+          command_event_channel.single_write(CommandEvent {
+            command,
+            event_id: None,
+          });
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, math::*, test_utils::*};
+
+  #[test]
+  fn test_reflections_follow_their_originals() {
+    let (mut world, mut dispatcher) = headless();
+
+    // The y axis as the mirror
+    let m1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let m2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 1.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(m1, m2))),
+    );
+    let mirror = last_inserted::<SymbolicLine>(&world);
+    let p1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 2.].into()));
+    let p2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![3., 2.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, p2))),
+    );
+    let segment = last_inserted::<SymbolicLine>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(p1, p2))),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    for ent in &[p2, segment, circle, mirror] {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+    let before = world.entities().join().count();
+    step(&mut world, &mut dispatcher, Command::ReflectSelected { mirror });
+    // The inserts issued by the reflection are handled on the next frame
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.entities().join().count(), before + 3);
+
+    let reflected_point = last_inserted::<SymbolicPoint>(&world);
+    let reflected_segment = last_inserted::<SymbolicLine>(&world);
+    let reflected_circle = last_inserted::<SymbolicCircle>(&world);
+    match world.read_storage::<SymbolicPoint>().get(reflected_point) {
+      Some(SymbolicPoint::Reflect(point, line)) => assert_eq!((*point, *line), (p2, mirror)),
+      _ => panic!("The selected point is reflected"),
+    }
+    {
+      let virt_points = world.read_storage::<VirtualPoint>();
+      let virt_lines = world.read_storage::<VirtualLine>();
+      let virt_circles = world.read_storage::<VirtualCircle>();
+      assert_eq!(virt_points.get(reflected_point).unwrap().0, vec2![-3., 2.]);
+      let line = virt_lines.get(reflected_segment).unwrap();
+      assert_eq!((line.from.0, line.to.0), (vec2![-1., 2.], vec2![-3., 2.]));
+      let circle = virt_circles.get(reflected_circle).unwrap();
+      assert_eq!((circle.center.0, circle.radius.0), (vec2![-1., 2.], 2.));
+    }
+
+    // Moving an original moves its reflection
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        p1,
+        SymbolicPoint::Free(vec2![1., 2.].into()),
+        SymbolicPoint::Free(vec2![2., -1.].into()),
+      )),
+    );
+    let virt_lines = world.read_storage::<VirtualLine>();
+    let virt_circles = world.read_storage::<VirtualCircle>();
+    assert_eq!(virt_lines.get(reflected_segment).unwrap().from.0, vec2![-2., -1.]);
+    assert_eq!(virt_circles.get(reflected_circle).unwrap().center.0, vec2![-2., -1.]);
+  }
+
+  #[test]
+  fn test_reflection_is_undone_at_once() {
+    let (mut world, mut dispatcher) = headless();
+
+    let m1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let m2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 1.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(m1, m2))),
+    );
+    let mirror = last_inserted::<SymbolicLine>(&world);
+    let p1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 0.].into()));
+    let p2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![3., 0.].into()));
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    for ent in &[p1, p2] {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+    step(&mut world, &mut dispatcher, Command::ReflectSelected { mirror });
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 6);
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 4);
+  }
+}
@@ -1,5 +1,8 @@
 use crate::{
-  components::{markers::*, screen_shapes::*, styles::*, symbolics::*, virtual_shapes::*},
+  components::{
+    freehand::*, markers::*, measurements::*, screen_shapes::*, sliders::*, styles::*, symbolics::*, underlays::*,
+    virtual_shapes::*,
+  },
   events::*,
   resources::*,
   utilities::*,
@@ -19,27 +22,65 @@ impl Default for RemoveHandler {
   }
 }
 
+storages! {
+  /// The storages a removed geometry is taken out of, whatever its kind
+  Removable {
+    sym_points: WriteStorage<'a, SymbolicPoint>,
+    point_styles: WriteStorage<'a, PointStyle>,
+    virt_points: WriteStorage<'a, VirtualPoint>,
+    scrn_points: WriteStorage<'a, ScreenPoint>,
+    sym_lines: WriteStorage<'a, SymbolicLine>,
+    line_styles: WriteStorage<'a, LineStyle>,
+    virt_lines: WriteStorage<'a, VirtualLine>,
+    scrn_lines: WriteStorage<'a, ScreenLine>,
+    sym_circles: WriteStorage<'a, SymbolicCircle>,
+    circle_styles: WriteStorage<'a, CircleStyle>,
+    virt_circles: WriteStorage<'a, VirtualCircle>,
+    scrn_circles: WriteStorage<'a, ScreenCircle>,
+    sym_arcs: WriteStorage<'a, SymbolicArc>,
+    arc_styles: WriteStorage<'a, ArcStyle>,
+    virt_arcs: WriteStorage<'a, VirtualArc>,
+    scrn_arcs: WriteStorage<'a, ScreenArc>,
+    sym_conics: WriteStorage<'a, SymbolicConic>,
+    conic_styles: WriteStorage<'a, ConicStyle>,
+    virt_conics: WriteStorage<'a, VirtualConic>,
+    scrn_conics: WriteStorage<'a, ScreenConic>,
+    sym_polygons: WriteStorage<'a, SymbolicPolygon>,
+    polygon_styles: WriteStorage<'a, PolygonStyle>,
+    virt_polygons: WriteStorage<'a, VirtualPolygon>,
+    scrn_polygons: WriteStorage<'a, ScreenPolygon>,
+    sym_vectors: WriteStorage<'a, SymbolicVector>,
+    vector_styles: WriteStorage<'a, VectorStyle>,
+    virt_vectors: WriteStorage<'a, VirtualVector>,
+    scrn_vectors: WriteStorage<'a, ScreenVector>,
+    sym_texts: WriteStorage<'a, SymbolicText>,
+    text_styles: WriteStorage<'a, TextStyle>,
+    virt_texts: WriteStorage<'a, VirtualText>,
+    scrn_texts: WriteStorage<'a, ScreenText>,
+    measurements: WriteStorage<'a, Measurement>,
+    measured_values: WriteStorage<'a, MeasuredValue>,
+    sliders: WriteStorage<'a, Slider>,
+    slider_values: WriteStorage<'a, SliderValue>,
+    underlays: WriteStorage<'a, ImageUnderlay>,
+    underlay_placements: WriteStorage<'a, UnderlayPlacement>,
+    strokes: WriteStorage<'a, FreehandStroke>,
+    scrn_strokes: WriteStorage<'a, ScreenFreehandStroke>,
+    elements: WriteStorage<'a, Element>,
+    selecteds: WriteStorage<'a, Selected>,
+    hiddens: WriteStorage<'a, Hidden>,
+  }
+}
+
 impl<'a> System<'a> for RemoveHandler {
   type SystemData = (
     Entities<'a>,
     Read<'a, DependencyGraph>,
     Read<'a, CommandEventChannel>,
     Write<'a, GeometryEventChannel>,
-    WriteStorage<'a, SymbolicPoint>,
-    WriteStorage<'a, PointStyle>,
-    WriteStorage<'a, VirtualPoint>,
-    WriteStorage<'a, ScreenPoint>,
-    WriteStorage<'a, SymbolicLine>,
-    WriteStorage<'a, LineStyle>,
-    WriteStorage<'a, VirtualLine>,
-    WriteStorage<'a, ScreenLine>,
-    WriteStorage<'a, SymbolicCircle>,
-    WriteStorage<'a, CircleStyle>,
-    WriteStorage<'a, VirtualCircle>,
-    WriteStorage<'a, ScreenCircle>,
-    WriteStorage<'a, Element>,
-    WriteStorage<'a, Selected>,
-    WriteStorage<'a, Hidden>,
+    Read<'a, LayerManager>,
+    ReadStorage<'a, Layer>,
+    Write<'a, ErrorEventChannel>,
+    Removable<'a>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -54,90 +95,86 @@ impl<'a> System<'a> for RemoveHandler {
       dependency_graph,
       command_event_channel,
       mut geometry_event_channel,
-      mut sym_points,
-      mut point_styles,
-      mut virt_points,
-      mut scrn_points,
-      mut sym_lines,
-      mut line_styles,
-      mut virt_lines,
-      mut scrn_lines,
-      mut sym_circles,
-      mut circle_styles,
-      mut virt_circles,
-      mut scrn_circles,
-      mut elements,
-      mut selecteds,
-      mut hiddens,
+      layer_manager,
+      layers,
+      mut error_event_channel,
+      mut removable,
     ): Self::SystemData,
   ) {
     if let Some(reader) = &mut self.command_event_reader {
       for event in command_event_channel.read(reader) {
-        macro_rules! remove {
-          ($ent:expr) => {
-            remove_element(
-              $ent,
-              &mut sym_points,
-              &mut point_styles,
-              &mut virt_points,
-              &mut scrn_points,
-              &mut sym_lines,
-              &mut line_styles,
-              &mut virt_lines,
-              &mut scrn_lines,
-              &mut sym_circles,
-              &mut circle_styles,
-              &mut virt_circles,
-              &mut scrn_circles,
-              &mut elements,
-              &mut selecteds,
-              &mut hiddens,
-            )
-          };
-        }
         match event.command {
           Command::Remove(remove_event) => match remove_event {
             RemoveEvent::Remove(ent) => {
-              for dep in dependency_graph.get_all_dependents(&ent) {
-                if let Some(geom) = remove!(&dep) {
+              let deps = dependency_graph.get_all_dependents(&ent);
+              if let Some(locked) = deps.iter().find(|dep| layer_manager.is_locked(layers.get(**dep))) {
+                error_event_channel.single_write(ErrorEvent::LayerLocked(*locked));
+                continue;
+              }
+              for dep in deps {
+                if let Some(geom) = removable.remove(dep) {
                   geometry_event_channel.single_write(GeometryEvent::removed(dep, geom));
                 }
               }
             }
             RemoveEvent::RemoveByHistory(ent) => {
               for dep in dependency_graph.get_all_dependents(&ent) {
-                if let Some(geom) = remove!(&dep) {
+                if let Some(geom) = removable.remove(dep) {
                   geometry_event_channel.single_write(GeometryEvent::removed_by_history(dep, geom));
                 }
               }
             }
             RemoveEvent::RemoveSelected => {
               let mut set = HashSet::new();
-              for (ent, _) in (&entities, &selecteds).join() {
-                for dep in dependency_graph.get_all_dependents(&ent) {
-                  set.insert(dep);
+              for (ent, _) in (&entities, &removable.selecteds).join() {
+                // Removing the selected entity would remove the entities depending on it too
+                let deps = dependency_graph.get_all_dependents(&ent);
+                match deps.iter().find(|dep| layer_manager.is_locked(layers.get(**dep))) {
+                  Some(locked) => error_event_channel.single_write(ErrorEvent::LayerLocked(*locked)),
+                  None => set.extend(deps),
                 }
               }
               for ent in set {
-                if let Some(geom) = remove!(&ent) {
+                if let Some(geom) = removable.remove(ent) {
                   geometry_event_channel.single_write(GeometryEvent::removed(ent, geom));
                 }
               }
             }
             RemoveEvent::RemoveAll => {
               let mut set = HashSet::new();
-              for (ent, _) in (&entities, &elements).join() {
+              for (ent, _) in (&entities, &removable.elements).join() {
                 for dep in dependency_graph.get_all_dependents(&ent) {
                   set.insert(dep);
                 }
               }
               for ent in set {
-                if let Some(geom) = remove!(&ent) {
+                if let Some(geom) = removable.remove(ent) {
                   geometry_event_channel.single_write(GeometryEvent::removed(ent, geom));
                 }
               }
             }
           },
+          Command::ClearAll => {
+            // Every geometry, even the ones somehow missing their element marker
+            let mut set = HashSet::new();
+            set.extend((&entities, &removable.sym_points).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sym_lines).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sym_circles).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sym_arcs).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sym_conics).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sym_polygons).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sym_vectors).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sym_texts).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.measurements).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.sliders).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.underlays).join().map(|(ent, _)| ent));
+            set.extend((&entities, &removable.strokes).join().map(|(ent, _)| ent));
+            for ent in set {
+              if let Some(geom) = removable.remove(ent) {
+                geometry_event_channel.single_write(GeometryEvent::removed(ent, geom));
+              }
+            }
+          }
           _ => (), // Don't care others
         }
       }
@@ -145,59 +182,155 @@ impl<'a> System<'a> for RemoveHandler {
   }
 }
 
-fn remove_element<'a>(
-  ent: &Entity,
+impl<'a> Removable<'a> {
+  fn remove(&mut self, ent: Entity) -> Option<Geometry> {
+    // Remove from markers
+    self.elements.remove(ent);
+    self.selecteds.remove(ent);
+    self.hiddens.remove(ent);
 
-  sym_points: &mut WriteStorage<'a, SymbolicPoint>,
-  point_styles: &mut WriteStorage<'a, PointStyle>,
-  virt_points: &mut WriteStorage<'a, VirtualPoint>,
-  scrn_points: &mut WriteStorage<'a, ScreenPoint>,
+    // Remove from geometry storages
+    if let Some(sym_point) = self.sym_points.remove(ent) {
+      if let Some(point_style) = self.point_styles.remove(ent) {
+        self.virt_points.remove(ent);
+        self.scrn_points.remove(ent);
+        Some(Geometry::Point(sym_point, point_style))
+      } else {
+        None
+      }
+    } else if let Some(sym_line) = self.sym_lines.remove(ent) {
+      if let Some(line_style) = self.line_styles.remove(ent) {
+        self.virt_lines.remove(ent);
+        self.scrn_lines.remove(ent);
+        Some(Geometry::Line(sym_line, line_style))
+      } else {
+        None
+      }
+    } else if let Some(sym_circle) = self.sym_circles.remove(ent) {
+      if let Some(circle_style) = self.circle_styles.remove(ent) {
+        self.virt_circles.remove(ent);
+        self.scrn_circles.remove(ent);
+        Some(Geometry::Circle(sym_circle, circle_style))
+      } else {
+        None
+      }
+    } else if let Some(sym_arc) = self.sym_arcs.remove(ent) {
+      if let Some(arc_style) = self.arc_styles.remove(ent) {
+        self.virt_arcs.remove(ent);
+        self.scrn_arcs.remove(ent);
+        Some(Geometry::Arc(sym_arc, arc_style))
+      } else {
+        None
+      }
+    } else if let Some(sym_conic) = self.sym_conics.remove(ent) {
+      if let Some(conic_style) = self.conic_styles.remove(ent) {
+        self.virt_conics.remove(ent);
+        self.scrn_conics.remove(ent);
+        Some(Geometry::Conic(sym_conic, conic_style))
+      } else {
+        None
+      }
+    } else if let Some(sym_polygon) = self.sym_polygons.remove(ent) {
+      if let Some(polygon_style) = self.polygon_styles.remove(ent) {
+        self.virt_polygons.remove(ent);
+        self.scrn_polygons.remove(ent);
+        Some(Geometry::Polygon(sym_polygon, polygon_style))
+      } else {
+        None
+      }
+    } else if let Some(sym_vector) = self.sym_vectors.remove(ent) {
+      if let Some(vector_style) = self.vector_styles.remove(ent) {
+        self.virt_vectors.remove(ent);
+        self.scrn_vectors.remove(ent);
+        Some(Geometry::Vector(sym_vector, vector_style))
+      } else {
+        None
+      }
+    } else if let Some(sym_text) = self.sym_texts.remove(ent) {
+      if let Some(text_style) = self.text_styles.remove(ent) {
+        self.virt_texts.remove(ent);
+        self.scrn_texts.remove(ent);
+        Some(Geometry::Text(sym_text, text_style))
+      } else {
+        None
+      }
+    } else if let Some(measurement) = self.measurements.remove(ent) {
+      self.measured_values.remove(ent);
+      Some(Geometry::Measurement(measurement))
+    } else if let Some(slider) = self.sliders.remove(ent) {
+      self
+        .slider_values
+        .remove(ent)
+        .map(|slider_value| Geometry::Slider(slider, slider_value))
+    } else if let Some(underlay) = self.underlays.remove(ent) {
+      self
+        .underlay_placements
+        .remove(ent)
+        .map(|placement| Geometry::Underlay(underlay, placement))
+    } else if let Some(stroke) = self.strokes.remove(ent) {
+      self.scrn_strokes.remove(ent);
+      self
+        .line_styles
+        .remove(ent)
+        .map(|line_style| Geometry::Freehand(stroke, line_style))
+    } else {
+      None
+    }
+  }
+}
 
-  sym_lines: &mut WriteStorage<'a, SymbolicLine>,
-  line_styles: &mut WriteStorage<'a, LineStyle>,
-  virt_lines: &mut WriteStorage<'a, VirtualLine>,
-  scrn_lines: &mut WriteStorage<'a, ScreenLine>,
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
 
-  sym_circles: &mut WriteStorage<'a, SymbolicCircle>,
-  circle_styles: &mut WriteStorage<'a, CircleStyle>,
-  virt_circles: &mut WriteStorage<'a, VirtualCircle>,
-  scrn_circles: &mut WriteStorage<'a, ScreenCircle>,
+  #[test]
+  fn test_clear_all_empties_everything() {
+    let (mut world, mut dispatcher) = headless();
 
-  elements: &mut WriteStorage<'a, Element>,
-  selecteds: &mut WriteStorage<'a, Selected>,
-  hiddens: &mut WriteStorage<'a, Hidden>,
-) -> Option<Geometry> {
-  // Remove from markers
-  elements.remove(*ent);
-  selecteds.remove(*ent);
-  hiddens.remove(*ent);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![0., 0.])),
+    );
+    let p1 = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![2., 1.])),
+    );
+    let p2 = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::MidPoint(p1, p2))),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, p2))),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(p1, p2))),
+    );
+    assert!(!world.fetch::<DependencyGraph>().is_empty());
+    assert!(world.fetch::<SpatialEntityMap>().occupied_tiles().count() > 0);
 
-  // Remove from geometry storages
-  if let Some(sym_point) = sym_points.remove(*ent) {
-    if let Some(point_style) = point_styles.remove(*ent) {
-      virt_points.remove(*ent);
-      scrn_points.remove(*ent);
-      Some(Geometry::Point(sym_point, point_style))
-    } else {
-      None
-    }
-  } else if let Some(sym_line) = sym_lines.remove(*ent) {
-    if let Some(line_style) = line_styles.remove(*ent) {
-      virt_lines.remove(*ent);
-      scrn_lines.remove(*ent);
-      Some(Geometry::Line(sym_line, line_style))
-    } else {
-      None
-    }
-  } else if let Some(sym_circle) = sym_circles.remove(*ent) {
-    if let Some(circle_style) = circle_styles.remove(*ent) {
-      virt_circles.remove(*ent);
-      scrn_circles.remove(*ent);
-      Some(Geometry::Circle(sym_circle, circle_style))
-    } else {
-      None
-    }
-  } else {
-    None
+    step(&mut world, &mut dispatcher, Command::ClearAll);
+    assert_eq!(world.read_storage::<Element>().join().count(), 0);
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 0);
+    assert_eq!(world.read_storage::<VirtualPoint>().join().count(), 0);
+    assert_eq!(world.read_storage::<ScreenPoint>().join().count(), 0);
+    assert_eq!(world.read_storage::<SymbolicLine>().join().count(), 0);
+    assert_eq!(world.read_storage::<VirtualLine>().join().count(), 0);
+    assert_eq!(world.read_storage::<ScreenLine>().join().count(), 0);
+    assert_eq!(world.read_storage::<SymbolicCircle>().join().count(), 0);
+    assert_eq!(world.read_storage::<VirtualCircle>().join().count(), 0);
+    assert_eq!(world.read_storage::<ScreenCircle>().join().count(), 0);
+    assert_eq!(world.read_storage::<Selected>().join().count(), 0);
+    assert!(world.fetch::<DependencyGraph>().is_empty());
+    assert_eq!(world.fetch::<SpatialEntityMap>().occupied_tiles().count(), 0);
   }
 }
@@ -0,0 +1,162 @@
+use crate::{components::markers::*, events::*, resources::*, utilities::*};
+use specs::prelude::*;
+
+pub struct RenameHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for RenameHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for RenameHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    Write<'a, Names>,
+    ReadStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut error_event_channel,
+      mut marker_event_channel,
+      mut names,
+      selecteds,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          Command::Rename(rename_event) => match rename_event {
+            RenameEvent::Rename(ent, name) => {
+              if let Some(old_name) = rename(*ent, name, &mut names, &mut error_event_channel) {
+                marker_event_channel.single_write(MarkerEvent::rename(*ent, old_name, Some(name.clone())));
+              }
+            }
+            RenameEvent::RenameByHistory(ent, Some(name)) => {
+              if let Some(old_name) = rename(*ent, name, &mut names, &mut error_event_channel) {
+                marker_event_channel.single_write(MarkerEvent::rename_by_history(*ent, old_name, Some(name.clone())));
+              }
+            }
+            RenameEvent::RenameByHistory(ent, None) => {
+              if let Some(old_name) = names.remove(*ent) {
+                marker_event_channel.single_write(MarkerEvent::rename_by_history(*ent, Some(old_name), None));
+              }
+            }
+            RenameEvent::RenameSelected(name) => {
+              // Names are unique so we can only rename when there's exactly one selected element
+              let selected = (&entities, &selecteds).join().map(|(ent, _)| ent).collect::<Vec<_>>();
+              if let [ent] = selected[..] {
+                if let Some(old_name) = rename(ent, name, &mut names, &mut error_event_channel) {
+                  marker_event_channel.single_write(MarkerEvent::rename(ent, old_name, Some(name.clone())));
+                }
+              } else {
+                error_event_channel.single_write(ErrorEvent::NotOneSelected(selected.len()));
+              }
+            }
+            RenameEvent::Unname(ent) => {
+              if let Some(old_name) = names.remove(*ent) {
+                marker_event_channel.single_write(MarkerEvent::rename(*ent, Some(old_name), None));
+              }
+            }
+          },
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// Gives back the previous name of the entity when it got renamed, `None` when the name is taken
+/// or is already the name of the entity
+fn rename(
+  ent: Entity,
+  name: &String,
+  names: &mut Names,
+  error_event_channel: &mut ErrorEventChannel,
+) -> Option<Option<String>> {
+  let old_name = names.name_of(ent).cloned();
+  if old_name.as_ref() == Some(name) {
+    return None;
+  }
+  match names.assign(ent, name.clone()) {
+    Ok(()) => Some(old_name),
+    Err(NameError::DuplicateName(name)) => {
+      error_event_channel.single_write(ErrorEvent::DuplicateName(ent, name));
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, math::*, test_utils::*};
+
+  #[test]
+  fn test_rename_selected_needs_exactly_one_selected() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+
+    let mut points = vec![];
+    for x in &[0., 1.] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, 0.])),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+    let name_of = |world: &World, ent: Entity| world.fetch::<Names>().name_of(ent).cloned();
+    let names_before = points.iter().map(|p| name_of(&world, *p)).collect::<Vec<_>>();
+
+    // Both points selected, neither is renamed
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::SelectAll));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Rename(RenameEvent::RenameSelected("P".to_string())),
+    );
+    assert_eq!(
+      points.iter().map(|p| name_of(&world, *p)).collect::<Vec<_>>(),
+      names_before
+    );
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .cloned()
+      .collect::<Vec<_>>();
+    assert!(matches!(errors[..], [ErrorEvent::NotOneSelected(2)]));
+
+    // Only the second one selected
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Select(SelectEvent::Select(points[1])),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Rename(RenameEvent::RenameSelected("P".to_string())),
+    );
+    assert_eq!(name_of(&world, points[0]), names_before[0]);
+    assert_eq!(name_of(&world, points[1]), Some("P".to_string()));
+    assert_eq!(world.fetch::<ErrorEventChannel>().read(&mut reader).count(), 0);
+  }
+}
@@ -0,0 +1,267 @@
+use crate::{
+  components::{markers::*, styles::*},
+  events::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Replaces the style of a geometry. A style of another kind than the geometry is ignored. The
+/// style commands restyle every selected geometry
+pub struct RestyleHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for RestyleHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for RestyleHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, MarkerEventChannel>,
+    WriteStorage<'a, PointStyle>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, CircleStyle>,
+    WriteStorage<'a, ArcStyle>,
+    WriteStorage<'a, ConicStyle>,
+    WriteStorage<'a, PolygonStyle>,
+    WriteStorage<'a, VectorStyle>,
+    WriteStorage<'a, TextStyle>,
+    ReadStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut marker_event_channel,
+      mut point_styles,
+      mut line_styles,
+      mut circle_styles,
+      mut arc_styles,
+      mut conic_styles,
+      mut polygon_styles,
+      mut vector_styles,
+      mut text_styles,
+      selecteds,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        let restyles = match event.command {
+          Command::Restyle(RestyleEvent::Restyle(ent, style)) => vec![(ent, style, false)],
+          Command::Restyle(RestyleEvent::RestyleByHistory(ent, style)) => vec![(ent, style, true)],
+          Command::Style(style_command) => (&entities, &selecteds)
+            .join()
+            .filter_map(|(ent, _)| {
+              let style = point_styles
+                .get(ent)
+                .map(|style| Style::Point(*style))
+                .or_else(|| line_styles.get(ent).map(|style| Style::Line(*style)))
+                .or_else(|| circle_styles.get(ent).map(|style| Style::Circle(*style)))
+                .or_else(|| arc_styles.get(ent).map(|style| Style::Arc(*style)))
+                .or_else(|| conic_styles.get(ent).map(|style| Style::Conic(*style)))
+                .or_else(|| polygon_styles.get(ent).map(|style| Style::Polygon(*style)))
+                .or_else(|| vector_styles.get(ent).map(|style| Style::Vector(*style)))
+                .or_else(|| text_styles.get(ent).map(|style| Style::Text(*style)))?;
+              restyled(style, style_command).map(|style| (ent, style, false))
+            })
+            .collect(),
+          _ => continue,
+        };
+        for (ent, new_style, by_history) in restyles {
+          let old_style = match new_style {
+            Style::Point(style) => replace(&mut point_styles, ent, style).map(Style::Point),
+            Style::Line(style) => replace(&mut line_styles, ent, style).map(Style::Line),
+            Style::Circle(style) => replace(&mut circle_styles, ent, style).map(Style::Circle),
+            Style::Arc(style) => replace(&mut arc_styles, ent, style).map(Style::Arc),
+            Style::Conic(style) => replace(&mut conic_styles, ent, style).map(Style::Conic),
+            Style::Polygon(style) => replace(&mut polygon_styles, ent, style).map(Style::Polygon),
+            Style::Vector(style) => replace(&mut vector_styles, ent, style).map(Style::Vector),
+            Style::Text(style) => replace(&mut text_styles, ent, style).map(Style::Text),
+          };
+          if let Some(old_style) = old_style {
+            marker_event_channel.single_write(if by_history {
+              MarkerEvent::restyle_by_history(ent, old_style, new_style)
+            } else {
+              MarkerEvent::restyle(ent, old_style, new_style)
+            });
+          }
+        }
+      }
+    }
+  }
+}
+
+/// The style changed by the style command, `None` when the command does not apply to this kind
+/// of style
+fn restyled(style: Style, style_command: StyleCommand) -> Option<Style> {
+  match (style, style_command) {
+    (Style::Point(style), StyleCommand::SetColor(color)) => Some(Style::Point(PointStyle { color, ..style })),
+    (Style::Point(style), StyleCommand::SetPointRadius(radius)) => Some(Style::Point(PointStyle { radius, ..style })),
+    (Style::Line(style), StyleCommand::SetColor(color)) => Some(Style::Line(LineStyle { color, ..style })),
+    (Style::Line(style), StyleCommand::SetThickness(width)) => Some(Style::Line(LineStyle { width, ..style })),
+    (Style::Line(style), StyleCommand::SetDashPattern(dash)) => Some(Style::Line(LineStyle { dash, ..style })),
+    (Style::Circle(mut style), StyleCommand::SetColor(color)) => {
+      style.border.color = color;
+      Some(Style::Circle(style))
+    }
+    (Style::Circle(mut style), StyleCommand::SetThickness(width)) => {
+      style.border.width = width;
+      Some(Style::Circle(style))
+    }
+    (Style::Arc(style), StyleCommand::SetColor(color)) => Some(Style::Arc(ArcStyle { color, ..style })),
+    (Style::Arc(style), StyleCommand::SetThickness(width)) => Some(Style::Arc(ArcStyle { width, ..style })),
+    (Style::Conic(style), StyleCommand::SetColor(color)) => Some(Style::Conic(ConicStyle { color, ..style })),
+    (Style::Conic(style), StyleCommand::SetThickness(width)) => Some(Style::Conic(ConicStyle { width, ..style })),
+    (Style::Polygon(mut style), StyleCommand::SetColor(color)) => {
+      style.border.color = color;
+      Some(Style::Polygon(style))
+    }
+    (Style::Polygon(mut style), StyleCommand::SetThickness(width)) => {
+      style.border.width = width;
+      Some(Style::Polygon(style))
+    }
+    (Style::Vector(style), StyleCommand::SetColor(color)) => Some(Style::Vector(VectorStyle { color, ..style })),
+    (Style::Vector(style), StyleCommand::SetThickness(width)) => Some(Style::Vector(VectorStyle { width, ..style })),
+    (Style::Text(style), StyleCommand::SetColor(color)) => Some(Style::Text(TextStyle { color, ..style })),
+    _ => None,
+  }
+}
+
+/// Puts the new style on the entity and gives back the old one, `None` when the entity has no
+/// style of this kind
+fn replace<'a, T: Component + Copy>(styles: &mut WriteStorage<'a, T>, ent: Entity, new_style: T) -> Option<T> {
+  let old_style = *styles.get(ent)?;
+  if let Err(err) = styles.insert(ent, new_style) {
+    panic!(err)
+  }
+  Some(old_style)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, math::*, resources::*, test_utils::*};
+
+  #[test]
+  fn test_restyle_is_undone_and_redone() {
+    let (mut world, mut dispatcher) = headless();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![1., 2.])),
+    );
+    let point = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+    let red = |world: &World| world.read_storage::<PointStyle>().get(point).unwrap().color.r;
+    let old_red = red(&world);
+    let mut style = *world.read_storage::<PointStyle>().get(point).unwrap();
+    style.color.r = old_red / 2.0 + 0.25;
+
+    // A line style does not fit a point
+    let line_style = world.fetch::<DefaultLineStyle>().get();
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Restyle(RestyleEvent::Restyle(point, Style::Line(line_style))),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Restyle(RestyleEvent::Restyle(point, Style::Point(style))),
+    );
+    assert_eq!(red(&world), old_red / 2.0 + 0.25);
+
+    // The restyle is undone before the insertion
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(red(&world), old_red);
+    assert!(world.read_storage::<SymbolicPoint>().get(point).is_some());
+
+    history(&mut world, &mut dispatcher, HistoryEvent::Redo);
+    assert_eq!(red(&world), old_red / 2.0 + 0.25);
+  }
+
+  #[test]
+  fn test_style_command_restyles_the_selection() {
+    let (mut world, mut dispatcher) = headless();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![0., 0.])),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![1., 2.])),
+    );
+    let points = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .collect::<Vec<_>>();
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(points[0], points[1]))),
+    );
+    let line = (&world.entities(), &world.read_storage::<SymbolicLine>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(line)));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Select(SelectEvent::Select(points[0])),
+    );
+
+    // The dash pattern only applies to the line, the radius only to the point
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Style(StyleCommand::SetDashPattern(DashPattern::Dotted)),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Style(StyleCommand::SetPointRadius(7.)),
+    );
+    let radius = |world: &World, ent: Entity| world.read_storage::<PointStyle>().get(ent).unwrap().radius;
+    assert_eq!(
+      world.read_storage::<LineStyle>().get(line).unwrap().dash,
+      DashPattern::Dotted
+    );
+    assert_eq!(radius(&world, points[0]), 7.);
+    assert_ne!(radius(&world, points[1]), 7.);
+
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_ne!(radius(&world, points[0]), 7.);
+    assert_eq!(
+      world.read_storage::<LineStyle>().get(line).unwrap().dash,
+      DashPattern::Dotted
+    );
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(
+      world.read_storage::<LineStyle>().get(line).unwrap().dash,
+      DashPattern::Solid
+    );
+  }
+}
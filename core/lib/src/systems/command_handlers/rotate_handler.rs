@@ -0,0 +1,142 @@
+use crate::{
+  components::{markers::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+pub struct RotateHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for RotateHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for RotateHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, VirtualPoint>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (entities, mut command_event_channel, selecteds, sym_points, virt_points): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let rotations = command_event_channel
+        .read(reader)
+        .filter_map(|event| match event.command {
+          Command::RotateSelected { pivot, radians } => Some((pivot, radians)),
+          _ => None,
+        })
+        .collect::<Vec<_>>();
+      for (pivot, radians) in rotations {
+        let pivot_position = match virt_points.get(pivot) {
+          Some(position) => *position,
+          None => continue,
+        };
+
+        // Only the free and fixed points are moved, the constrained ones follow their base points
+        // once the solver runs
+        for (ent, _, sym_point) in (&entities, &selecteds, &sym_points).join() {
+          if ent == pivot {
+            continue;
+          }
+          if let Some(new_sym_point) = rotate_point(sym_point, pivot_position, radians) {
+            // Updated like a finished drag, so that it is solved and recorded in the history
+            command_event_channel.single_write(CommandEvent {
+              command: Command::Update(UpdateEvent::UpdatePoint(ent, *sym_point, new_sym_point)),
+              event_id: None,
+            });
+            command_event_channel.single_write(CommandEvent {
+              command: Command::Update(UpdateEvent::UpdatePointEnd(ent, *sym_point, new_sym_point)),
+              event_id: None,
+            });
+          }
+        }
+      }
+    }
+  }
+}
+
+/// The free or fixed point rotated counter clockwise by `radians` around `pivot`. Constrained
+/// points can't be rotated by themselves
+pub fn rotate_point(sym_point: &SymbolicPoint, pivot: VirtualPosition, radians: f64) -> Option<SymbolicPoint> {
+  let rotate = |position: VirtualPosition| VirtualPosition(pivot.0 + (position - pivot).0.rotate(radians));
+  match *sym_point {
+    SymbolicPoint::Free(position) => Some(SymbolicPoint::Free(rotate(position))),
+    SymbolicPoint::Fixed(position) => Some(SymbolicPoint::Fixed(rotate(position))),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+  use std::f64::consts::FRAC_PI_2;
+
+  fn assert_position(world: &World, ent: Entity, expected: Vector2) {
+    let position = world.read_storage::<VirtualPoint>().get(ent).unwrap().0;
+    assert!(
+      (position - expected).magnitude() < 1e-12,
+      "{:?} != {:?}",
+      position,
+      expected
+    );
+  }
+
+  #[test]
+  fn test_rotate_selected_by_right_angle_around_origin() {
+    let (mut world, mut dispatcher) = headless();
+
+    let origin = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![0., 0.].into()));
+    let p1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 0.].into()));
+    let p2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 2.].into()));
+    let p3 = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::Fixed(vec2![-3., -1.].into()),
+    );
+    let mid = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(p1, p2));
+    let unselected = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![4., 4.].into()));
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    for ent in &[origin, p1, p2, p3, mid] {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(*ent)));
+    }
+
+    // The updates issued by the rotation are handled on the next frame
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::RotateSelected {
+        pivot: origin,
+        radians: FRAC_PI_2,
+      },
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    assert_position(&world, origin, vec2![0., 0.]);
+    assert_position(&world, p1, vec2![0., 1.]);
+    assert_position(&world, p2, vec2![-2., 0.]);
+    assert_position(&world, p3, vec2![1., -3.]);
+    assert_position(&world, mid, vec2![-1., 0.5]);
+    assert_position(&world, unselected, vec2![4., 4.]);
+    let sym_points = world.read_storage::<SymbolicPoint>();
+    match sym_points.get(p3) {
+      Some(SymbolicPoint::Fixed(_)) => (),
+      _ => panic!("A fixed point stays fixed when rotated"),
+    }
+  }
+}
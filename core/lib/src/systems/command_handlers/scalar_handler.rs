@@ -0,0 +1,53 @@
+use crate::{events::*, resources::*};
+use specs::prelude::*;
+
+pub struct ScalarHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for ScalarHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ScalarHandler {
+  type SystemData = (
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, ScalarExpressions>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (command_event_channel, mut geometry_event_channel, mut error_event_channel, mut scalar_expressions): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          // The value is evaluated by the scalar expression solver, which tells the geometries
+          // using the scalar once it changes
+          Command::DefineScalar(definition) => {
+            if let Err(err) = scalar_expressions.define(definition) {
+              error_event_channel.single_write(ErrorEvent::InvalidExpression(err));
+            }
+          }
+          Command::RemoveScalar(name) => {
+            if let Some(id) = scalar_expressions.remove(name) {
+              geometry_event_channel.single_write(GeometryEvent::ScalarUpdated(id));
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,157 @@
+use crate::{
+  components::{markers::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+pub struct ScaleHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for ScaleHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ScaleHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, VirtualPoint>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (entities, mut command_event_channel, mut error_event_channel, selecteds, sym_points, virt_points): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let scalings = command_event_channel
+        .read(reader)
+        .filter_map(|event| match event.command {
+          Command::ScaleSelected { pivot, factor } => Some((pivot, factor)),
+          _ => None,
+        })
+        .collect::<Vec<_>>();
+      for (pivot, factor) in scalings {
+        // A zero factor would collapse the figure and a negative one would mirror it
+        if factor <= 0.0 {
+          error_event_channel.single_write(ErrorEvent::InvalidScaleFactor(factor));
+          continue;
+        }
+        let pivot_position = match virt_points.get(pivot) {
+          Some(position) => *position,
+          None => continue,
+        };
+
+        // Same as rotating, the constrained points follow their base points
+        for (ent, _, sym_point) in (&entities, &selecteds, &sym_points).join() {
+          if ent == pivot {
+            continue;
+          }
+          if let Some(new_sym_point) = scale_point(sym_point, pivot_position, factor) {
+            command_event_channel.single_write(CommandEvent {
+              command: Command::Update(UpdateEvent::UpdatePoint(ent, *sym_point, new_sym_point)),
+              event_id: None,
+            });
+            command_event_channel.single_write(CommandEvent {
+              command: Command::Update(UpdateEvent::UpdatePointEnd(ent, *sym_point, new_sym_point)),
+              event_id: None,
+            });
+          }
+        }
+      }
+    }
+  }
+}
+
+/// The free or fixed point moved `factor` times as far away from `pivot`
+pub fn scale_point(sym_point: &SymbolicPoint, pivot: VirtualPosition, factor: f64) -> Option<SymbolicPoint> {
+  let scale = |position: VirtualPosition| VirtualPosition(pivot.0 + (position - pivot).0 * factor);
+  match *sym_point {
+    SymbolicPoint::Free(position) => Some(SymbolicPoint::Free(scale(position))),
+    SymbolicPoint::Fixed(position) => Some(SymbolicPoint::Fixed(scale(position))),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  fn position(world: &World, ent: Entity) -> Vector2 {
+    world.read_storage::<VirtualPoint>().get(ent).unwrap().0
+  }
+
+  #[test]
+  fn test_scale_square_around_center() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+
+    let vertices = [vec2![0., 0.], vec2![2., 0.], vec2![2., 2.], vec2![0., 2.]]
+      .iter()
+      .map(|p| insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free((*p).into())))
+      .collect::<Vec<_>>();
+    let center = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![1., 1.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(
+        center,
+        vertices[0],
+      ))),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::SelectAll));
+
+    // Non positive factors are refused and leave the square as it is
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ScaleSelected {
+        pivot: center,
+        factor: -1.0,
+      },
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, vertices[2]), vec2![2., 2.]);
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter(|event| matches!(event, ErrorEvent::InvalidScaleFactor(_)))
+      .count();
+    assert_eq!(errors, 1);
+
+    // The updates issued by the scaling are handled on the next frame
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ScaleSelected {
+        pivot: center,
+        factor: 2.0,
+      },
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, center), vec2![1., 1.]);
+    assert_eq!(position(&world, vertices[0]), vec2![-1., -1.]);
+    assert_eq!(position(&world, vertices[1]), vec2![3., -1.]);
+    assert_eq!(position(&world, vertices[2]), vec2![3., 3.]);
+    assert_eq!(position(&world, vertices[3]), vec2![-1., 3.]);
+    let radius = world.read_storage::<VirtualCircle>().get(circle).unwrap().radius.0;
+    assert!((radius - 2.0 * 2f64.sqrt()).abs() < 1e-12);
+  }
+}
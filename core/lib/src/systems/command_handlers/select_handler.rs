@@ -1,4 +1,4 @@
-use crate::{components::markers::*, events::*};
+use crate::{components::markers::*, events::*, resources::*};
 use specs::prelude::*;
 
 pub struct SelectHandler {
@@ -18,7 +18,10 @@ impl<'a> System<'a> for SelectHandler {
     Entities<'a>,
     Read<'a, CommandEventChannel>,
     Write<'a, MarkerEventChannel>,
+    Write<'a, SelectionOrder>,
+    Read<'a, LayerManager>,
     ReadStorage<'a, Element>,
+    ReadStorage<'a, Layer>,
     WriteStorage<'a, Selected>,
   );
 
@@ -29,7 +32,16 @@ impl<'a> System<'a> for SelectHandler {
 
   fn run(
     &mut self,
-    (entities, command_event_channel, mut marker_event_channel, elements, mut selecteds): Self::SystemData,
+    (
+      entities,
+      command_event_channel,
+      mut marker_event_channel,
+      mut selection_order,
+      layer_manager,
+      elements,
+      layers,
+      mut selecteds,
+    ): Self::SystemData,
   ) {
     if let Some(reader) = &mut self.command_event_reader {
       for event in command_event_channel.read(reader) {
@@ -39,17 +51,23 @@ impl<'a> System<'a> for SelectHandler {
               if let Err(err) = selecteds.insert(ent, Selected) {
                 panic!(err)
               }
+              selection_order.push(ent);
               marker_event_channel.single_write(MarkerEvent::Select(ent));
             }
             SelectEvent::Deselect(ent) => {
               selecteds.remove(ent);
+              selection_order.remove(ent);
               marker_event_channel.single_write(MarkerEvent::Deselect(ent));
             }
             SelectEvent::SelectAll => {
-              for (ent, _) in (&entities, &elements).join() {
+              for (ent, _, _) in (&entities, &elements, layers.maybe())
+                .join()
+                .filter(|(_, _, layer)| !layer_manager.is_hidden(*layer))
+              {
                 if let Err(err) = selecteds.insert(ent, Selected) {
                   panic!(err)
                 }
+                selection_order.push(ent);
                 marker_event_channel.single_write(MarkerEvent::Select(ent));
               }
             }
@@ -58,6 +76,7 @@ impl<'a> System<'a> for SelectHandler {
                 marker_event_channel.single_write(MarkerEvent::Deselect(ent));
               }
               selecteds.clear();
+              selection_order.clear();
             }
           },
           _ => (),
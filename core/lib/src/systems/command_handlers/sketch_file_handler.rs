@@ -0,0 +1,487 @@
+use crate::{
+  components::{freehand::*, markers::*, measurements::*, sliders::*, styles::*, symbolics::*, underlays::*},
+  events::*,
+  io::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+use std::fs;
+
+/// Saves the sketch to a file and loads it back. Loading replaces every geometry with the ones
+/// of the file, inserted the same way undo does, so that the dependency graph and the solved
+/// shapes are rebuilt on the next frame. The undo history is cleared since it refers to the
+/// geometries being replaced
+pub struct SketchFileHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for SketchFileHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for SketchFileHandler {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, HistoryEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, Names>,
+    Write<'a, AngleConstraints>,
+    Write<'a, Constraints>,
+    Write<'a, ScalarExpressions>,
+    ReadStorage<'a, Element>,
+    ReadStorage<'a, Hidden>,
+    (ReadStorage<'a, SymbolicPoint>, ReadStorage<'a, PointStyle>),
+    (ReadStorage<'a, SymbolicLine>, ReadStorage<'a, LineStyle>),
+    (ReadStorage<'a, SymbolicCircle>, ReadStorage<'a, CircleStyle>),
+    (ReadStorage<'a, SymbolicArc>, ReadStorage<'a, ArcStyle>),
+    (ReadStorage<'a, SymbolicConic>, ReadStorage<'a, ConicStyle>),
+    (ReadStorage<'a, SymbolicPolygon>, ReadStorage<'a, PolygonStyle>),
+    (ReadStorage<'a, SymbolicVector>, ReadStorage<'a, VectorStyle>),
+    (ReadStorage<'a, SymbolicText>, ReadStorage<'a, TextStyle>),
+    ReadStorage<'a, Measurement>,
+    (ReadStorage<'a, Slider>, ReadStorage<'a, SliderValue>),
+    (ReadStorage<'a, ImageUnderlay>, ReadStorage<'a, UnderlayPlacement>),
+    ReadStorage<'a, FreehandStroke>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      mut command_event_channel,
+      mut history_event_channel,
+      mut error_event_channel,
+      mut names,
+      mut angle_constraints,
+      mut constraints,
+      mut scalar_expressions,
+      elements,
+      hiddens,
+      (sym_points, point_styles),
+      (sym_lines, line_styles),
+      (sym_circles, circle_styles),
+      (sym_arcs, arc_styles),
+      (sym_conics, conic_styles),
+      (sym_polygons, polygon_styles),
+      (sym_vectors, vector_styles),
+      (sym_texts, text_styles),
+      measurements,
+      (sliders, slider_values),
+      (underlays, underlay_placements),
+      strokes,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let commands = command_event_channel
+        .read(reader)
+        .map(|event| event.command.clone())
+        .collect::<Vec<_>>();
+      for command in commands {
+        match command {
+          Command::SaveSketch(path) => {
+            let mut geometries = vec![];
+            for (ent, sym_point, point_style) in (&entities, &sym_points, &point_styles).join() {
+              geometries.push((ent, Geometry::Point(*sym_point, *point_style)));
+            }
+            for (ent, sym_line, line_style) in (&entities, &sym_lines, &line_styles).join() {
+              geometries.push((ent, Geometry::Line(*sym_line, *line_style)));
+            }
+            for (ent, sym_circle, circle_style) in (&entities, &sym_circles, &circle_styles).join() {
+              geometries.push((ent, Geometry::Circle(*sym_circle, *circle_style)));
+            }
+            for (ent, sym_arc, arc_style) in (&entities, &sym_arcs, &arc_styles).join() {
+              geometries.push((ent, Geometry::Arc(*sym_arc, *arc_style)));
+            }
+            for (ent, sym_conic, conic_style) in (&entities, &sym_conics, &conic_styles).join() {
+              geometries.push((ent, Geometry::Conic(*sym_conic, *conic_style)));
+            }
+            for (ent, sym_polygon, polygon_style) in (&entities, &sym_polygons, &polygon_styles).join() {
+              geometries.push((ent, Geometry::Polygon(sym_polygon.clone(), *polygon_style)));
+            }
+            for (ent, sym_vector, vector_style) in (&entities, &sym_vectors, &vector_styles).join() {
+              geometries.push((ent, Geometry::Vector(*sym_vector, *vector_style)));
+            }
+            for (ent, sym_text, text_style) in (&entities, &sym_texts, &text_styles).join() {
+              geometries.push((ent, Geometry::Text(sym_text.clone(), *text_style)));
+            }
+            for (ent, measurement) in (&entities, &measurements).join() {
+              geometries.push((ent, Geometry::Measurement(*measurement)));
+            }
+            for (ent, slider, slider_value) in (&entities, &sliders, &slider_values).join() {
+              geometries.push((ent, Geometry::Slider(*slider, *slider_value)));
+            }
+            for (ent, underlay, placement) in (&entities, &underlays, &underlay_placements).join() {
+              geometries.push((ent, Geometry::Underlay(underlay.clone(), *placement)));
+            }
+            for (ent, stroke, line_style) in (&entities, &strokes, &line_styles).join() {
+              geometries.push((ent, Geometry::Freehand(stroke.clone(), *line_style)));
+            }
+            geometries.sort_by_key(|(ent, _)| ent.id());
+            let document = SketchDocument {
+              geometries,
+              hidden: (&entities, &hiddens).join().map(|(ent, _)| ent).collect(),
+              names: names.iter().map(|(ent, name)| (ent, name.clone())).collect(),
+              angle_constraints: angle_constraints.iter().cloned().collect(),
+              constraints: constraints.iter().cloned().collect(),
+              scalars: scalar_expressions
+                .iter()
+                .map(|scalar| (scalar.id, scalar.name.clone(), scalar.source.clone()))
+                .collect(),
+            };
+            if let Err(err) = fs::write(&path, write_sketch(&document)) {
+              error_event_channel.single_write(ErrorEvent::SketchFile(SketchFileError::Io(err.to_string())));
+            }
+          }
+          Command::LoadSketch(path) => {
+            let document = fs::read_to_string(&path)
+              .map_err(|err| SketchFileError::Io(err.to_string()))
+              .and_then(|text| read_sketch(&text, &entities));
+            let document = match document {
+              Ok(document) => document,
+              Err(err) => {
+                error_event_channel.single_write(ErrorEvent::SketchFile(err));
+                continue;
+              }
+            };
+
+            history_event_channel.single_write(HistoryEvent::Clear);
+            names.clear();
+            for (ent, name) in document.names {
+              let _ = names.assign(ent, name);
+            }
+            *angle_constraints = AngleConstraints::default();
+            for constraint in document.angle_constraints {
+              angle_constraints.insert(constraint);
+            }
+            *constraints = Constraints::default();
+            for constraint in document.constraints {
+              constraints.insert(constraint);
+            }
+            *scalar_expressions = ScalarExpressions::default();
+            for (id, name, source) in document.scalars {
+              let _ = scalar_expressions.insert(id, &name, &source);
+            }
+
+            let mut commands = (&entities, &elements)
+              .join()
+              .map(|(ent, _)| Command::Remove(RemoveEvent::RemoveByHistory(ent)))
+              .collect::<Vec<_>>();
+            commands.extend(
+              document
+                .geometries
+                .iter()
+                .map(|(ent, geometry)| Command::insert_by_history(*ent, geometry)),
+            );
+
+            // Inserting selects, a loaded sketch starts with nothing selected
+            commands.push(Command::Select(SelectEvent::DeselectAll));
+            commands.extend(
+              document
+                .hidden
+                .into_iter()
+                .map(|ent| Command::Hide(HideEvent::HideByHistory(ent))),
+            );
+            for command in commands {
+              command_event_channel.single_write(CommandEvent {
+                command,
+                event_id: None,
+              });
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, math::*, test_utils::*};
+  use std::env;
+
+  #[test]
+  fn test_save_and_load_sketch() {
+    let path = env::temp_dir().join(format!("sketch_file_handler_{}.json", std::process::id()));
+    let (mut world, mut dispatcher) = headless();
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![0., 0.])),
+    );
+    let a = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![4., 2.])),
+    );
+    let b = last_inserted::<SymbolicPoint>(&world);
+    let mid = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, b));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(mid, b))),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Rename(RenameEvent::Rename(mid, "M".to_string())),
+    );
+    step(&mut world, &mut dispatcher, Command::Hide(HideEvent::Hide(a)));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Constrain(Constraint::FixedDistance(a, b, 20f64.sqrt())),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::TextInsert(InsertTextEvent::InsertText(SymbolicText {
+        text: "Middle".to_string(),
+        anchor: TextAnchor::Entity(mid, vec2![0., 1.].into()),
+      })),
+    );
+    let stroke = FreehandStroke {
+      samples: vec![(vec2![1., 1.].into(), 0.25), (vec2![2., 3.].into(), 0.75)],
+    };
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(stroke.clone())),
+    );
+    step(&mut world, &mut dispatcher, Command::SaveSketch(path.clone()));
+
+    // Loaded into a sketch that already has something, which gets replaced
+    let (mut loaded, mut loaded_dispatcher) = headless();
+    step(
+      &mut loaded,
+      &mut loaded_dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![9., 9.])),
+    );
+    step(&mut loaded, &mut loaded_dispatcher, Command::LoadSketch(path.clone()));
+    loaded_dispatcher.dispatch(&loaded);
+    loaded.maintain();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.read_storage::<SymbolicPoint>().join().count(), 3);
+    let mid = loaded.fetch::<Names>().by_name("M").unwrap();
+    match loaded.read_storage::<SymbolicPoint>().get(mid) {
+      Some(SymbolicPoint::MidPoint(_, _)) => (),
+      _ => panic!("Expected the mid point to be named M"),
+    }
+    assert_eq!(loaded.read_storage::<VirtualPoint>().get(mid).unwrap().0, vec2![2., 1.]);
+    let circle = last_inserted::<SymbolicCircle>(&loaded);
+    let radius = loaded.read_storage::<VirtualCircle>().get(circle).unwrap().radius.0;
+    assert!((radius - 5f64.sqrt()).abs() < 1e-12);
+    assert_eq!(loaded.read_storage::<Hidden>().join().count(), 1);
+    assert_eq!(loaded.read_storage::<Selected>().join().count(), 0);
+    let constraints = loaded.fetch::<Constraints>().iter().cloned().collect::<Vec<_>>();
+    match constraints.as_slice() {
+      [Constraint::FixedDistance(_, _, distance)] => assert_eq!(*distance, 20f64.sqrt()),
+      _ => panic!("Expected the distance constraint"),
+    }
+
+    // The solver follows the loaded dependencies
+    let b = (&loaded.entities(), &loaded.read_storage::<VirtualPoint>())
+      .join()
+      .find(|(_, p)| p.0 == vec2![4., 2.])
+      .map(|(ent, _)| ent)
+      .unwrap();
+    let moved = SymbolicPoint::Fixed(vec2![6., 2.].into());
+    let before = *loaded.read_storage::<SymbolicPoint>().get(b).unwrap();
+    step(
+      &mut loaded,
+      &mut loaded_dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(b, before, moved)),
+    );
+    assert_eq!(loaded.read_storage::<VirtualPoint>().get(mid).unwrap().0, vec2![3., 1.]);
+
+    // The text stays anchored to the mid point
+    let text = last_inserted::<SymbolicText>(&loaded);
+    assert_eq!(loaded.read_storage::<SymbolicText>().get(text).unwrap().text, "Middle");
+    assert_eq!(
+      loaded.read_storage::<VirtualText>().get(text).unwrap().position.0,
+      vec2![3., 2.]
+    );
+    let loaded_stroke = last_inserted::<FreehandStroke>(&loaded);
+    assert_eq!(
+      loaded.read_storage::<FreehandStroke>().get(loaded_stroke),
+      Some(&stroke)
+    );
+  }
+
+  #[test]
+  fn test_save_and_load_every_kind() {
+    let path = env::temp_dir().join(format!("sketch_file_handler_kinds_{}.json", std::process::id()));
+    let (mut world, mut dispatcher) = headless();
+    let mut point_at = |world: &mut World, x: f64, y: f64| {
+      step(
+        world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![x, y])),
+      );
+      last_inserted::<SymbolicPoint>(world)
+    };
+    let center = point_at(&mut world, 0., 0.);
+    let start = point_at(&mut world, 1., 0.);
+    let end = point_at(&mut world, -1., 0.);
+    let below = point_at(&mut world, 0.5, -2.);
+    let above = point_at(&mut world, 0.5, 2.);
+    let focus = point_at(&mut world, 3., 0.);
+    let on_ellipse = point_at(&mut world, 2., 1.);
+    let mut insert = |world: &mut World, command: Command| step(world, &mut dispatcher, command);
+    insert(
+      &mut world,
+      Command::ArcInsert(InsertArcEvent::InsertArc(SymbolicArc::CenterTwoPoint(
+        center, start, end,
+      ))),
+    );
+    let arc = last_inserted::<SymbolicArc>(&world);
+    let mut arc_style = *world.read_storage::<ArcStyle>().get(arc).unwrap();
+    arc_style.color = Color::green();
+    arc_style.width = 4.;
+    insert(
+      &mut world,
+      Command::Restyle(RestyleEvent::Restyle(arc, Style::Arc(arc_style))),
+    );
+    insert(
+      &mut world,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(below, above))),
+    );
+    let line = last_inserted::<SymbolicLine>(&world);
+    insert(
+      &mut world,
+      Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::ArcLineIntersect(
+        arc,
+        line,
+        CircleIntersectId::First,
+      ))),
+    );
+    let intersection = last_inserted::<SymbolicPoint>(&world);
+    insert(
+      &mut world,
+      Command::ConicInsert(InsertConicEvent::InsertConic(SymbolicConic::Ellipse(
+        start, focus, on_ellipse,
+      ))),
+    );
+    let conic = last_inserted::<SymbolicConic>(&world);
+    insert(
+      &mut world,
+      Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::OnConic(conic, 0.3))),
+    );
+    let on_conic = last_inserted::<SymbolicPoint>(&world);
+    insert(
+      &mut world,
+      Command::PolygonInsert(InsertPolygonEvent::InsertPolygon(SymbolicPolygon(vec![
+        center, start, above,
+      ]))),
+    );
+    let polygon = last_inserted::<SymbolicPolygon>(&world);
+    insert(
+      &mut world,
+      Command::MeasurementInsert(InsertMeasurementEvent::InsertMeasurement(Measurement::PolygonArea(
+        polygon,
+      ))),
+    );
+    insert(
+      &mut world,
+      Command::VectorInsert(InsertVectorEvent::InsertVector(SymbolicVector(below, above))),
+    );
+    let slider = Slider {
+      position: vec2![-3., -3.].into(),
+      min: 0.,
+      max: 10.,
+    };
+    insert(
+      &mut world,
+      Command::SliderInsert(InsertSliderEvent::InsertSlider(slider, SliderValue(2.5))),
+    );
+    insert(
+      &mut world,
+      Command::Rename(RenameEvent::Rename(intersection, "Cut".to_string())),
+    );
+    insert(
+      &mut world,
+      Command::Rename(RenameEvent::Rename(on_conic, "OnEllipse".to_string())),
+    );
+    let position = |world: &World, ent: Entity| world.read_storage::<VirtualPoint>().get(ent).unwrap().0;
+    let intersection_position = position(&world, intersection);
+    let on_conic_position = position(&world, on_conic);
+    assert!((intersection_position - vec2![0.5, 0.75f64.sqrt()]).magnitude() < 1e-9);
+    insert(&mut world, Command::SaveSketch(path.clone()));
+
+    let (mut loaded, mut loaded_dispatcher) = headless();
+    step(&mut loaded, &mut loaded_dispatcher, Command::LoadSketch(path.clone()));
+    loaded_dispatcher.dispatch(&loaded);
+    loaded.maintain();
+    std::fs::remove_file(&path).unwrap();
+
+    let names = loaded.fetch::<Names>();
+    let (intersection, on_conic) = (names.by_name("Cut").unwrap(), names.by_name("OnEllipse").unwrap());
+    drop(names);
+    match loaded.read_storage::<SymbolicPoint>().get(intersection) {
+      Some(SymbolicPoint::ArcLineIntersect(_, _, CircleIntersectId::First)) => (),
+      sym_point => panic!("Expected the arc line intersection, got {:?}", sym_point),
+    }
+    match loaded.read_storage::<SymbolicPoint>().get(on_conic) {
+      Some(SymbolicPoint::OnConic(_, t)) => assert_eq!(*t, 0.3),
+      sym_point => panic!("Expected the point on the conic, got {:?}", sym_point),
+    }
+    assert_eq!(position(&loaded, intersection), intersection_position);
+    assert_eq!(position(&loaded, on_conic), on_conic_position);
+
+    let arc = last_inserted::<SymbolicArc>(&loaded);
+    assert_eq!(loaded.read_storage::<ArcStyle>().get(arc), Some(&arc_style));
+    assert_eq!(loaded.read_storage::<SymbolicConic>().join().count(), 1);
+    let polygon = last_inserted::<SymbolicPolygon>(&loaded);
+    assert_eq!(
+      loaded.read_storage::<SymbolicPolygon>().get(polygon).unwrap().0.len(),
+      3
+    );
+    match loaded.read_storage::<Measurement>().join().next() {
+      Some(Measurement::PolygonArea(measured)) => assert_eq!(*measured, polygon),
+      measurement => panic!("Expected the area of the polygon, got {:?}", measurement),
+    }
+    assert_eq!(loaded.read_storage::<SymbolicVector>().join().count(), 1);
+    let loaded_slider = last_inserted::<Slider>(&loaded);
+    assert_eq!(loaded.read_storage::<Slider>().get(loaded_slider), Some(&slider));
+    assert_eq!(
+      loaded.read_storage::<SliderValue>().get(loaded_slider),
+      Some(&SliderValue(2.5))
+    );
+  }
+
+  #[test]
+  fn test_load_invalid_sketch() {
+    let path = env::temp_dir().join(format!("sketch_file_handler_invalid_{}.json", std::process::id()));
+    std::fs::write(&path, r#"{ "version": 1, "elements": [{ "id": 3, "kind": "line", "symbolic": { "type": "Straight", "args": [3, 8] } }] }"#).unwrap();
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+    step(&mut world, &mut dispatcher, Command::LoadSketch(path.clone()));
+    std::fs::remove_file(&path).unwrap();
+
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter_map(|event| match event {
+        ErrorEvent::SketchFile(err) => Some(err.clone()),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(errors, vec![SketchFileError::UnknownReference(8)]);
+    world.maintain();
+    assert_eq!(world.entities().join().count(), 0);
+  }
+}
@@ -0,0 +1,192 @@
+use crate::{
+  components::{markers::*, sliders::*},
+  events::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts the sliders as elements of their own and sets their values. Only the value a drag ends
+/// with is recorded, so that undoing brings the slider back to where the drag began
+pub struct SliderHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for SliderHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for SliderHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    WriteStorage<'a, Slider>,
+    WriteStorage<'a, SliderValue>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut sliders,
+      mut slider_values,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::SliderInsert(insert_slider_event) => match insert_slider_event {
+            InsertSliderEvent::InsertSlider(slider, slider_value) => {
+              let ent = entities.create();
+              let (ent, geom) = insert(
+                ent,
+                slider,
+                slider_value,
+                &mut sliders,
+                &mut slider_values,
+                &mut elements,
+              );
+              geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            }
+            InsertSliderEvent::InsertSliderByHistory(ent, slider, slider_value) => {
+              let (ent, geom) = insert(
+                ent,
+                slider,
+                slider_value,
+                &mut sliders,
+                &mut slider_values,
+                &mut elements,
+              );
+              geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            }
+          },
+          Command::Update(UpdateEvent::UpdateSlider(ent, _, new_value)) => {
+            set_value(ent, new_value, &sliders, &mut slider_values);
+          }
+          Command::Update(UpdateEvent::UpdateSliderEnd(ent, old_value, new_value)) => {
+            if let Some(new_value) = set_value(ent, new_value, &sliders, &mut slider_values) {
+              geometry_event_channel.single_write(GeometryEvent::slider_update_finished(ent, old_value, new_value));
+            }
+          }
+          Command::Update(UpdateEvent::UpdateSliderByHistory(ent, old_value, new_value)) => {
+            if let Some(new_value) = set_value(ent, new_value, &sliders, &mut slider_values) {
+              geometry_event_channel.single_write(GeometryEvent::slider_update_finished_by_history(
+                ent, old_value, new_value,
+              ));
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  slider: Slider,
+  slider_value: SliderValue,
+  sliders: &mut WriteStorage<'a, Slider>,
+  slider_values: &mut WriteStorage<'a, SliderValue>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  let slider_value = SliderValue(slider.clamp(slider_value.0));
+  if let Err(err) = sliders.insert(ent, slider) {
+    panic!(err)
+  }
+  if let Err(err) = slider_values.insert(ent, slider_value) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Slider(slider, slider_value))
+}
+
+/// Gives back the value kept within the range of the slider, `None` when there's no such slider
+fn set_value<'a>(
+  ent: Entity,
+  value: f64,
+  sliders: &WriteStorage<'a, Slider>,
+  slider_values: &mut WriteStorage<'a, SliderValue>,
+) -> Option<f64> {
+  let value = sliders.get(ent)?.clamp(value);
+  if let Err(err) = slider_values.insert(ent, SliderValue(value)) {
+    panic!(err)
+  }
+  Some(value)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, resources::*, test_utils::*};
+
+  fn scalar(world: &World, name: &str) -> Option<f64> {
+    world
+      .fetch::<ScalarExpressions>()
+      .by_name(name)
+      .and_then(|scalar| scalar.value)
+  }
+
+  #[test]
+  fn test_scalars_follow_the_dragged_slider() {
+    let (mut world, mut dispatcher) = headless();
+
+    let slider = Slider {
+      position: vec2![0., 0.].into(),
+      min: 0.,
+      max: 2.,
+    };
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::SliderInsert(InsertSliderEvent::InsertSlider(slider, SliderValue(0.5))),
+    );
+    let ent = world.fetch::<Names>().by_name("t").unwrap();
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::DefineScalar("a = t * 10".to_string()),
+    );
+    assert_eq!(scalar(&world, "a"), Some(5.));
+
+    // Only the end of the drag is recorded, the values are kept in the range
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdateSlider(ent, 0.5, 1.)),
+    );
+    assert_eq!(scalar(&world, "a"), Some(10.));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdateSliderEnd(ent, 0.5, 3.)),
+    );
+    assert_eq!(world.read_storage::<SliderValue>().get(ent), Some(&SliderValue(2.)));
+    assert_eq!(scalar(&world, "a"), Some(20.));
+
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(world.read_storage::<SliderValue>().get(ent), Some(&SliderValue(0.5)));
+    assert_eq!(scalar(&world, "a"), Some(5.));
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert!(world.read_storage::<Slider>().get(ent).is_none());
+    assert_eq!(scalar(&world, "a"), None);
+    history(&mut world, &mut dispatcher, HistoryEvent::Redo);
+    assert_eq!(world.read_storage::<SliderValue>().get(ent), Some(&SliderValue(0.5)));
+    assert_eq!(world.fetch::<Names>().by_name("t"), Some(ent));
+  }
+}
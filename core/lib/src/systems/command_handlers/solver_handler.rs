@@ -0,0 +1,97 @@
+use crate::{events::*, resources::*};
+use specs::prelude::*;
+
+pub struct SolverHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for SolverHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for SolverHandler {
+  type SystemData = (Read<'a, CommandEventChannel>, Write<'a, SolverEnabled>);
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (command_event_channel, mut solver_enabled): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::SuspendSolve => solver_enabled.0 = false,
+          Command::ResumeSolve => solver_enabled.0 = true,
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{
+    components::{screen_shapes::*, symbolics::*, virtual_shapes::*},
+    math::*,
+    test_utils::*,
+  };
+
+  /// Inserts two points, their midpoint, a segment from the first point to the midpoint and a point
+  /// on that segment. Returns the last point
+  fn insert_chain(world: &mut World, dispatcher: &mut Dispatcher) -> Entity {
+    step(
+      world,
+      dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![0., 0.])),
+    );
+    let p1 = last_inserted::<SymbolicPoint>(world);
+    step(
+      world,
+      dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![4., 2.])),
+    );
+    let p2 = last_inserted::<SymbolicPoint>(world);
+    let mid = insert_point(world, dispatcher, SymbolicPoint::MidPoint(p1, p2));
+    step(
+      world,
+      dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, mid))),
+    );
+    let line = last_inserted::<SymbolicLine>(world);
+    insert_point(world, dispatcher, SymbolicPoint::OnLine(line, 0.25.into()))
+  }
+
+  #[test]
+  fn test_suspended_solve_matches_incremental_solve() {
+    let (mut incremental, mut incremental_dispatcher) = headless();
+    let expected = insert_chain(&mut incremental, &mut incremental_dispatcher);
+
+    let (mut batch, mut batch_dispatcher) = headless();
+    step(&mut batch, &mut batch_dispatcher, Command::SuspendSolve);
+    let last = insert_chain(&mut batch, &mut batch_dispatcher);
+    assert!(batch.read_storage::<VirtualPoint>().get(last).is_none());
+    assert_eq!(batch.read_storage::<VirtualPoint>().join().count(), 0);
+    step(&mut batch, &mut batch_dispatcher, Command::ResumeSolve);
+
+    let expected_point = *incremental.read_storage::<VirtualPoint>().get(expected).unwrap();
+    let point = *batch.read_storage::<VirtualPoint>().get(last).unwrap();
+    assert_eq!(point.0, expected_point.0);
+    assert_eq!(point.0, vec2![0.5, 0.25]);
+    assert!(batch.read_storage::<ScreenPoint>().get(last).is_some());
+    assert_eq!(
+      batch.read_storage::<VirtualPoint>().join().count(),
+      incremental.read_storage::<VirtualPoint>().join().count()
+    );
+    assert_eq!(
+      batch.read_storage::<VirtualLine>().join().count(),
+      incremental.read_storage::<VirtualLine>().join().count()
+    );
+  }
+}
@@ -0,0 +1,215 @@
+use crate::{components::styles::*, events::*, resources::*};
+use specs::prelude::*;
+
+pub struct ThemeHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for ThemeHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ThemeHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, Theme>,
+    Write<'a, DefaultPointStyle>,
+    Write<'a, DefaultLineStyle>,
+    Write<'a, DefaultCircleStyle>,
+    Write<'a, DefaultArcStyle>,
+    Write<'a, DefaultConicStyle>,
+    Write<'a, DefaultVectorStyle>,
+    Write<'a, DefaultTextStyle>,
+    WriteStorage<'a, PointStyle>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, CircleStyle>,
+    WriteStorage<'a, ArcStyle>,
+    WriteStorage<'a, ConicStyle>,
+    WriteStorage<'a, VectorStyle>,
+    WriteStorage<'a, TextStyle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut theme,
+      mut default_point_style,
+      mut default_line_style,
+      mut default_circle_style,
+      mut default_arc_style,
+      mut default_conic_style,
+      mut default_vector_style,
+      mut default_text_style,
+      mut point_styles,
+      mut line_styles,
+      mut circle_styles,
+      mut arc_styles,
+      mut conic_styles,
+      mut vector_styles,
+      mut text_styles,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        let new_theme = match event.command {
+          Command::SetTheme(new_theme) => new_theme,
+          Command::ToggleTheme if *theme == Theme::dark() => Theme::light(),
+          Command::ToggleTheme => Theme::dark(),
+          _ => continue,
+        };
+        let old_theme = *theme;
+        *theme = new_theme;
+
+        // The colors still being the default ones of the old theme follow the theme. Only the
+        // styles changing are written so that the others are not flagged as modified
+        macro_rules! follow {
+          ($styles:expr, |$style:ident| $color:expr, $old:expr, $new:expr) => {
+            let following = (&entities, &$styles)
+              .join()
+              .filter(|(_, $style)| $color == $old)
+              .map(|(ent, _)| ent)
+              .collect::<Vec<_>>();
+            for ent in following {
+              if let Some($style) = $styles.get_mut(ent) {
+                $color = $new;
+              }
+            }
+          };
+        }
+        follow!(point_styles, |style| style.color, old_theme.point, new_theme.point);
+        follow!(
+          point_styles,
+          |style| style.border_color,
+          old_theme.point_border,
+          new_theme.point_border
+        );
+        follow!(line_styles, |style| style.color, old_theme.line, new_theme.line);
+        follow!(
+          circle_styles,
+          |style| style.border.color,
+          old_theme.circle,
+          new_theme.circle
+        );
+        follow!(arc_styles, |style| style.color, old_theme.circle, new_theme.circle);
+        follow!(conic_styles, |style| style.color, old_theme.circle, new_theme.circle);
+        follow!(vector_styles, |style| style.color, old_theme.line, new_theme.line);
+        follow!(text_styles, |style| style.color, old_theme.line, new_theme.line);
+
+        // Only the colors come from the theme, the sizes are kept
+        let mut point_style = default_point_style.get();
+        point_style.color = new_theme.point;
+        point_style.border_color = new_theme.point_border;
+        default_point_style.set(point_style);
+
+        let mut line_style = default_line_style.get();
+        line_style.color = new_theme.line;
+        default_line_style.set(line_style);
+
+        let mut circle_style = default_circle_style.get();
+        circle_style.border.color = new_theme.circle;
+        default_circle_style.set(circle_style);
+
+        let mut arc_style = default_arc_style.get();
+        arc_style.color = new_theme.circle;
+        default_arc_style.set(arc_style);
+
+        let mut conic_style = default_conic_style.get();
+        conic_style.color = new_theme.circle;
+        default_conic_style.set(conic_style);
+
+        let mut vector_style = default_vector_style.get();
+        vector_style.color = new_theme.line;
+        default_vector_style.set(vector_style);
+
+        let mut text_style = default_text_style.get();
+        text_style.color = new_theme.line;
+        default_text_style.set(text_style);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, math::*, test_utils::*, utilities::*};
+
+  #[test]
+  fn test_set_dark_theme() {
+    let (world, mut dispatcher) = headless();
+
+    world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+      command: Command::SetTheme(Theme::dark()),
+      event_id: None,
+    });
+    dispatcher.dispatch(&world);
+
+    let dark = Theme::dark();
+    let light = Theme::light();
+    let background: [f32; 4] = world.fetch::<Theme>().background.into();
+    assert_eq!(background, Into::<[f32; 4]>::into(dark.background));
+    assert_ne!(background, Into::<[f32; 4]>::into(light.background));
+
+    let line_color: [f32; 4] = world.fetch::<DefaultLineStyle>().get().color.into();
+    assert_eq!(line_color, Into::<[f32; 4]>::into(dark.line));
+    let circle_color: [f32; 4] = world.fetch::<DefaultCircleStyle>().get().border.color.into();
+    assert_eq!(circle_color, Into::<[f32; 4]>::into(dark.circle));
+    let point_style = world.fetch::<DefaultPointStyle>().get();
+    let point_color: [f32; 4] = point_style.color.into();
+    assert_eq!(point_color, Into::<[f32; 4]>::into(dark.point));
+    assert_eq!(point_style.radius, 5.0);
+  }
+
+  #[test]
+  fn test_default_colors_follow_the_theme() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut step = |world: &mut World, command: Command| {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+        command,
+        event_id: None,
+      });
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+    step(
+      &mut world,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![0., 0.])),
+    );
+    step(
+      &mut world,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![1., 0.])),
+    );
+    let points = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .collect::<Vec<_>>();
+    let mut style = *world.read_storage::<PointStyle>().get(points[1]).unwrap();
+    style.color = Color::green();
+    step(
+      &mut world,
+      Command::Restyle(RestyleEvent::Restyle(points[1], Style::Point(style))),
+    );
+
+    step(&mut world, Command::ToggleTheme);
+    assert_eq!(*world.fetch::<Theme>(), Theme::dark());
+    let color = |world: &World, ent: Entity| world.read_storage::<PointStyle>().get(ent).unwrap().color;
+    assert_eq!(color(&world, points[0]), Theme::dark().point);
+    assert_eq!(color(&world, points[1]), Color::green());
+
+    step(&mut world, Command::ToggleTheme);
+    assert_eq!(color(&world, points[0]), Theme::light().point);
+  }
+}
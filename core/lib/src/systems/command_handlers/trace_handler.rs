@@ -0,0 +1,75 @@
+use crate::{
+  components::{markers::*, symbolics::*},
+  events::*,
+  resources::*,
+};
+use specs::prelude::*;
+
+pub struct TraceHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for TraceHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for TraceHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, TraceStore>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, Traced>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (entities, command_event_channel, mut trace_store, selecteds, sym_points, mut traceds): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::Trace(trace_event) => match trace_event {
+            TraceEvent::Toggle(ent) => toggle(ent, &sym_points, &mut traceds),
+            TraceEvent::ToggleSelected => {
+              let selected_points = (&entities, &selecteds, &sym_points)
+                .join()
+                .map(|(ent, _, _)| ent)
+                .collect::<Vec<_>>();
+              for ent in selected_points {
+                toggle(ent, &sym_points, &mut traceds);
+              }
+            }
+            TraceEvent::Clear => trace_store.clear(),
+          },
+          _ => (),
+        }
+      }
+    }
+
+    // Also drops the traces of the removed points
+    trace_store.retain(|ent| traceds.contains(ent));
+  }
+}
+
+fn toggle<'a>(ent: Entity, sym_points: &ReadStorage<'a, SymbolicPoint>, traceds: &mut WriteStorage<'a, Traced>) {
+  // Only points are traced
+  if sym_points.get(ent).is_none() {
+    return;
+  }
+  if traceds.remove(ent).is_none() {
+    if let Err(err) = traceds.insert(ent, Traced) {
+      panic!(err)
+    }
+  }
+}
@@ -0,0 +1,132 @@
+use crate::{components::symbolics::*, events::*};
+use specs::prelude::*;
+
+/// Edits the angle of rotated points and the vector of translated points after they are created
+pub struct TransformHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for TransformHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for TransformHandler {
+  type SystemData = (Write<'a, CommandEventChannel>, ReadStorage<'a, SymbolicPoint>);
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (mut command_event_channel, sym_points): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      let updates = command_event_channel
+        .read(reader)
+        .filter_map(
+          |event| match (&event.command, sym_points.get(event_entity(&event.command)?)) {
+            (Command::SetRotationAngle(ent, radians), Some(&old @ SymbolicPoint::Rotate(p, center, _))) => {
+              Some((*ent, old, SymbolicPoint::Rotate(p, center, *radians)))
+            }
+            (Command::SetTranslation(ent, vector), Some(&old @ SymbolicPoint::Translate(p, _))) => {
+              Some((*ent, old, SymbolicPoint::Translate(p, *vector)))
+            }
+            _ => None,
+          },
+        )
+        .collect::<Vec<_>>();
+
+      // Updated like a finished drag, so that all the descendants are solved again and the
+      // change is recorded in the history
+      for (ent, old_sym_point, new_sym_point) in updates {
+        command_event_channel.single_write(CommandEvent {
+          command: Command::Update(UpdateEvent::UpdatePoint(ent, old_sym_point, new_sym_point)),
+          event_id: None,
+        });
+        command_event_channel.single_write(CommandEvent {
+          command: Command::Update(UpdateEvent::UpdatePointEnd(ent, old_sym_point, new_sym_point)),
+          event_id: None,
+        });
+      }
+    }
+  }
+}
+
+fn event_entity(command: &Command) -> Option<Entity> {
+  match command {
+    Command::SetRotationAngle(ent, _) | Command::SetTranslation(ent, _) => Some(*ent),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, math::*, test_utils::*, utilities::*};
+  use std::f64::consts::{FRAC_PI_2, PI};
+
+  fn assert_position(world: &World, ent: Entity, expected: Vector2) {
+    let position = world.read_storage::<VirtualPoint>().get(ent).unwrap().0;
+    assert!(
+      (position - expected).magnitude() < 1e-12,
+      "{:?} != {:?}",
+      position,
+      expected
+    );
+  }
+
+  #[test]
+  fn test_changing_angle_moves_descendants() {
+    let (mut world, mut dispatcher) = headless();
+
+    let center = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let source = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 0.].into()));
+    let rotated = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::Rotate(source, center, FRAC_PI_2),
+    );
+    let translated = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::Translate(rotated, VirtualPosition(vec2![1., 1.])),
+    );
+    assert_position(&world, rotated, vec2![0., 1.]);
+    assert_position(&world, translated, vec2![1., 2.]);
+
+    // The updates issued by the handler are handled on the next frame
+    step(&mut world, &mut dispatcher, Command::SetRotationAngle(rotated, PI));
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_position(&world, rotated, vec2![-1., 0.]);
+    assert_position(&world, translated, vec2![0., 1.]);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::SetTranslation(translated, VirtualPosition(vec2![0., -2.])),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_position(&world, translated, vec2![-1., -2.]);
+
+    // Only the kind of point the property belongs to is changed
+    step(&mut world, &mut dispatcher, Command::SetRotationAngle(translated, 0.));
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_position(&world, translated, vec2![-1., -2.]);
+
+    for _ in 0..2 {
+      world
+        .fetch_mut::<HistoryEventChannel>()
+        .single_write(HistoryEvent::Undo);
+      dispatcher.dispatch(&world);
+      world.maintain();
+    }
+    assert_position(&world, rotated, vec2![0., 1.]);
+    assert_position(&world, translated, vec2![1., 2.]);
+  }
+}
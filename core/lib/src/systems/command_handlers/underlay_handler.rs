@@ -0,0 +1,203 @@
+use crate::{
+  components::{markers::*, underlays::*},
+  events::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Inserts the image underlays and places them. Like for the sliders, only the placement a drag
+/// ends with is recorded, so that undoing brings the underlay back to where the drag began
+pub struct UnderlayHandler {
+  command_event_reader: Option<CommandEventReader>,
+}
+
+impl Default for UnderlayHandler {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for UnderlayHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, GeometryEventChannel>,
+    WriteStorage<'a, ImageUnderlay>,
+    WriteStorage<'a, UnderlayPlacement>,
+    WriteStorage<'a, Element>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      command_event_channel,
+      mut geometry_event_channel,
+      mut underlays,
+      mut underlay_placements,
+      mut elements,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          Command::UnderlayInsert(insert_underlay_event) => match insert_underlay_event {
+            InsertUnderlayEvent::InsertUnderlay(underlay, placement) => {
+              let ent = entities.create();
+              let (ent, geom) = insert(
+                ent,
+                underlay.clone(),
+                *placement,
+                &mut underlays,
+                &mut underlay_placements,
+                &mut elements,
+              );
+              geometry_event_channel.single_write(GeometryEvent::inserted(ent, geom));
+            }
+            InsertUnderlayEvent::InsertUnderlayByHistory(ent, underlay, placement) => {
+              let (ent, geom) = insert(
+                *ent,
+                underlay.clone(),
+                *placement,
+                &mut underlays,
+                &mut underlay_placements,
+                &mut elements,
+              );
+              geometry_event_channel.single_write(GeometryEvent::inserted_by_history(ent, geom));
+            }
+          },
+          Command::Update(UpdateEvent::UpdateUnderlay(ent, _, new_placement)) => {
+            place(*ent, *new_placement, &underlays, &mut underlay_placements);
+          }
+          Command::Update(UpdateEvent::UpdateUnderlayEnd(ent, old_placement, new_placement)) => {
+            if let Some(new_placement) = place(*ent, *new_placement, &underlays, &mut underlay_placements) {
+              geometry_event_channel.single_write(GeometryEvent::underlay_update_finished(
+                *ent,
+                *old_placement,
+                new_placement,
+              ));
+            }
+          }
+          Command::Update(UpdateEvent::UpdateUnderlayByHistory(ent, old_placement, new_placement)) => {
+            if let Some(new_placement) = place(*ent, *new_placement, &underlays, &mut underlay_placements) {
+              geometry_event_channel.single_write(GeometryEvent::underlay_update_finished_by_history(
+                *ent,
+                *old_placement,
+                new_placement,
+              ));
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+fn insert<'a>(
+  ent: Entity,
+  underlay: ImageUnderlay,
+  placement: UnderlayPlacement,
+  underlays: &mut WriteStorage<'a, ImageUnderlay>,
+  underlay_placements: &mut WriteStorage<'a, UnderlayPlacement>,
+  elements: &mut WriteStorage<'a, Element>,
+) -> (Entity, Geometry) {
+  let placement = placement.with_opacity(placement.opacity);
+  if let Err(err) = underlays.insert(ent, underlay.clone()) {
+    panic!(err)
+  }
+  if let Err(err) = underlay_placements.insert(ent, placement) {
+    panic!(err)
+  }
+  if let Err(err) = elements.insert(ent, Element) {
+    panic!(err)
+  }
+  (ent, Geometry::Underlay(underlay, placement))
+}
+
+/// Gives back the placement with its opacity kept between 0 and 1, `None` when there's no such
+/// underlay
+fn place<'a>(
+  ent: Entity,
+  placement: UnderlayPlacement,
+  underlays: &WriteStorage<'a, ImageUnderlay>,
+  underlay_placements: &mut WriteStorage<'a, UnderlayPlacement>,
+) -> Option<UnderlayPlacement> {
+  underlays.get(ent)?;
+  let placement = placement.with_opacity(placement.opacity);
+  if let Err(err) = underlay_placements.insert(ent, placement) {
+    panic!(err)
+  }
+  Some(placement)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+  use std::path::PathBuf;
+
+  fn placement_of(world: &World, ent: Entity) -> Option<(Vector2, f64, f64)> {
+    world
+      .read_storage::<UnderlayPlacement>()
+      .get(ent)
+      .map(|placement| (placement.position.0, placement.scale, placement.opacity))
+  }
+
+  #[test]
+  fn test_undo_the_placement_of_an_underlay() {
+    let (mut world, mut dispatcher) = headless();
+
+    let underlay = ImageUnderlay {
+      path: PathBuf::from("diagram.png"),
+      size: vec2![200., 100.],
+    };
+    let placement = UnderlayPlacement::centered_at(&underlay, vec2![0., 0.].into(), 0.1);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::UnderlayInsert(InsertUnderlayEvent::InsertUnderlay(underlay, placement)),
+    );
+    let ent = (&world.entities(), &world.read_storage::<ImageUnderlay>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+
+    // Only the end of the drag is recorded, the opacity is kept in its range
+    let moved = placement.moved_by(vec2![1., 0.].into());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdateUnderlay(ent, placement, moved)),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdateUnderlayEnd(
+        ent,
+        placement,
+        moved.moved_by(vec2![1., 0.].into()).with_opacity(0.5),
+      )),
+    );
+    assert_eq!(placement_of(&world, ent), Some((vec2![-8., 5.], 0.1, 0.5)));
+
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(placement_of(&world, ent), Some((vec2![-10., 5.], 0.1, 1.)));
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert!(world.read_storage::<ImageUnderlay>().get(ent).is_none());
+    history(&mut world, &mut dispatcher, HistoryEvent::Redo);
+    history(&mut world, &mut dispatcher, HistoryEvent::Redo);
+    assert_eq!(placement_of(&world, ent), Some((vec2![-8., 5.], 0.1, 0.5)));
+
+    step(&mut world, &mut dispatcher, Command::Remove(RemoveEvent::Remove(ent)));
+    assert!(world.read_storage::<UnderlayPlacement>().get(ent).is_none());
+  }
+}
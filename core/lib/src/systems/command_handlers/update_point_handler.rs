@@ -61,6 +61,13 @@ impl<'a> System<'a> for UpdatePointHandler {
                 new_sym_point,
               ));
             }
+            UpdateEvent::UpdateLineByHistory(_, _, _) => (), // Handled with the line types
+            UpdateEvent::UpdateSlider(_, _, _)
+            | UpdateEvent::UpdateSliderEnd(_, _, _)
+            | UpdateEvent::UpdateSliderByHistory(_, _, _) => (), // Handled with the sliders
+            UpdateEvent::UpdateUnderlay(_, _, _)
+            | UpdateEvent::UpdateUnderlayEnd(_, _, _)
+            | UpdateEvent::UpdateUnderlayByHistory(_, _, _) => (), // Handled with the underlays
           },
           _ => (),
         }
@@ -0,0 +1,49 @@
+use crate::{events::*, resources::*};
+use specs::prelude::*;
+
+pub struct CommandRecorder {
+  command_event_reader: Option<CommandEventReader>,
+  geometry_event_reader: Option<GeometryEventReader>,
+}
+
+impl Default for CommandRecorder {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+      geometry_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for CommandRecorder {
+  type SystemData = (
+    Read<'a, CommandEventChannel>,
+    Read<'a, GeometryEventChannel>,
+    Write<'a, CommandLog>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+    self.geometry_event_reader = Some(world.fetch_mut::<GeometryEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (command_event_channel, geometry_event_channel, mut command_log): Self::SystemData) {
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        command_log.record_command(event.clone());
+      }
+    }
+
+    // Entities inserted by history already existed before, so only the fresh ones are recorded
+    if let Some(reader) = &mut self.geometry_event_reader {
+      for event in geometry_event_channel.read(reader) {
+        if let GeometryEvent::Inserted(ent, geom, false) = event {
+          command_log.record_insertion(*ent, geom.kind());
+        }
+      }
+    }
+
+    command_log.end_frame();
+  }
+}
@@ -1,4 +1,9 @@
-use crate::{components::symbolics::*, events::*, resources::*, utilities::*};
+use crate::{
+  components::{measurements::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
 use specs::prelude::*;
 
 pub struct DependencyGraphManager {
@@ -29,6 +34,15 @@ impl<'a> System<'a> for DependencyGraphManager {
             Geometry::Point(sym_point, _) => insert_point(ent, sym_point, &mut *dependency_graph),
             Geometry::Line(sym_line, _) => insert_line(ent, sym_line, &mut *dependency_graph),
             Geometry::Circle(sym_circle, _) => insert_circle(ent, sym_circle, &mut *dependency_graph),
+            Geometry::Arc(sym_arc, _) => insert_arc(ent, sym_arc, &mut *dependency_graph),
+            Geometry::Conic(sym_conic, _) => insert_conic(ent, sym_conic, &mut *dependency_graph),
+            Geometry::Polygon(sym_polygon, _) => insert_polygon(ent, sym_polygon, &mut *dependency_graph),
+            Geometry::Vector(sym_vector, _) => insert_vector(ent, sym_vector, &mut *dependency_graph),
+            Geometry::Text(sym_text, _) => insert_text(ent, sym_text, &mut *dependency_graph),
+            Geometry::Measurement(measurement) => insert_measurement(ent, measurement, &mut *dependency_graph),
+            Geometry::Slider(_, _) => (), // Depends on nothing, the scalars using it are evaluated every frame
+            Geometry::Underlay(_, _) => (),
+            Geometry::Freehand(_, _) => (),
           },
           GeometryEvent::Removed(ent, geom, _) => {
             dependency_graph.remove(ent);
@@ -36,8 +50,23 @@ impl<'a> System<'a> for DependencyGraphManager {
               Geometry::Point(sym_point, _) => remove_point(ent, sym_point, &mut *dependency_graph),
               Geometry::Line(sym_line, _) => remove_line(ent, sym_line, &mut *dependency_graph),
               Geometry::Circle(sym_circle, _) => remove_circle(ent, sym_circle, &mut *dependency_graph),
+              Geometry::Arc(sym_arc, _) => remove_arc(ent, sym_arc, &mut *dependency_graph),
+              Geometry::Conic(sym_conic, _) => remove_conic(ent, sym_conic, &mut *dependency_graph),
+              Geometry::Polygon(sym_polygon, _) => remove_polygon(ent, sym_polygon, &mut *dependency_graph),
+              Geometry::Vector(sym_vector, _) => remove_vector(ent, sym_vector, &mut *dependency_graph),
+              Geometry::Text(sym_text, _) => remove_text(ent, sym_text, &mut *dependency_graph),
+              Geometry::Measurement(measurement) => remove_measurement(ent, measurement, &mut *dependency_graph),
+              Geometry::Slider(_, _) => (),
+              Geometry::Underlay(_, _) => (),
+              Geometry::Freehand(_, _) => (),
             }
           }
+          // A point can be moved onto something else, e.g. a free point glued on a line
+          GeometryEvent::PointUpdated(ent, old_sym_point, new_sym_point, _)
+          | GeometryEvent::PointUpdateFinished(ent, old_sym_point, new_sym_point, _) => {
+            remove_point(ent, old_sym_point, &mut *dependency_graph);
+            insert_point(ent, new_sym_point, &mut *dependency_graph);
+          }
           _ => (),
         }
       }
@@ -49,7 +78,7 @@ fn insert_point(ent: &Entity, sym_point: &SymbolicPoint, dependency_graph: &mut
   match sym_point {
     SymbolicPoint::Fixed(_) => (),
     SymbolicPoint::Free(_) => (),
-    SymbolicPoint::MidPoint(p1_ent, p2_ent) => {
+    SymbolicPoint::MidPoint(p1_ent, p2_ent) | SymbolicPoint::Ratio(p1_ent, p2_ent, _) => {
       dependency_graph.add(p1_ent, ent);
       dependency_graph.add(p2_ent, ent);
     }
@@ -59,6 +88,7 @@ fn insert_point(ent: &Entity, sym_point: &SymbolicPoint, dependency_graph: &mut
       dependency_graph.add(l2_ent, ent);
     }
     SymbolicPoint::OnCircle(circle_ent, _) => dependency_graph.add(circle_ent, ent),
+    SymbolicPoint::OnConic(conic_ent, _) => dependency_graph.add(conic_ent, ent),
     SymbolicPoint::CircleLineIntersect(circle_ent, line_ent, _) => {
       dependency_graph.add(circle_ent, ent);
       dependency_graph.add(line_ent, ent);
@@ -67,6 +97,32 @@ fn insert_point(ent: &Entity, sym_point: &SymbolicPoint, dependency_graph: &mut
       dependency_graph.add(c1_ent, ent);
       dependency_graph.add(c2_ent, ent);
     }
+    SymbolicPoint::ArcLineIntersect(arc_ent, line_ent, _) => {
+      dependency_graph.add(arc_ent, ent);
+      dependency_graph.add(line_ent, ent);
+    }
+    SymbolicPoint::ArcCircleIntersect(arc_ent, circle_ent, _) => {
+      dependency_graph.add(arc_ent, ent);
+      dependency_graph.add(circle_ent, ent);
+    }
+    SymbolicPoint::PointReflection(source_ent, center_ent) => {
+      dependency_graph.add(source_ent, ent);
+      dependency_graph.add(center_ent, ent);
+    }
+    SymbolicPoint::Reflect(point_ent, mirror_ent) => {
+      dependency_graph.add(point_ent, ent);
+      dependency_graph.add(mirror_ent, ent);
+    }
+    SymbolicPoint::Rotate(point_ent, center_ent, _) => {
+      dependency_graph.add(point_ent, ent);
+      dependency_graph.add(center_ent, ent);
+    }
+    SymbolicPoint::RotateByScalar(point_ent, center_ent, scalar) => {
+      dependency_graph.add(point_ent, ent);
+      dependency_graph.add(center_ent, ent);
+      dependency_graph.add_scalar_dependent(scalar, ent);
+    }
+    SymbolicPoint::Translate(point_ent, _) => dependency_graph.add(point_ent, ent),
   }
 }
 
@@ -92,6 +148,22 @@ fn insert_line(ent: &Entity, sym_line: &SymbolicLine, dependency_graph: &mut Dep
       dependency_graph.add(line_ent, ent);
       dependency_graph.add(point_ent, ent);
     }
+    SymbolicLine::CommonTangent(c1_ent, c2_ent, _) => {
+      dependency_graph.add(c1_ent, ent);
+      dependency_graph.add(c2_ent, ent);
+    }
+    SymbolicLine::Tangent(circle_ent, point_ent, _) => {
+      dependency_graph.add(circle_ent, ent);
+      dependency_graph.add(point_ent, ent);
+    }
+    SymbolicLine::PerpendicularBisector(p1_ent, p2_ent) => {
+      dependency_graph.add(p1_ent, ent);
+      dependency_graph.add(p2_ent, ent);
+    }
+    SymbolicLine::Reflect(line_ent, mirror_ent) => {
+      dependency_graph.add(line_ent, ent);
+      dependency_graph.add(mirror_ent, ent);
+    }
   }
 }
 
@@ -101,6 +173,28 @@ fn insert_circle(ent: &Entity, sym_circle: &SymbolicCircle, dependency_graph: &m
       dependency_graph.add(p1_ent, ent);
       dependency_graph.add(p2_ent, ent);
     }
+    SymbolicCircle::EqualRadius(point_ent, circle_ent) => {
+      dependency_graph.add(point_ent, ent);
+      dependency_graph.add(circle_ent, ent);
+    }
+    SymbolicCircle::CompassRadius(point_ent, p1_ent, p2_ent) => {
+      dependency_graph.add(point_ent, ent);
+      dependency_graph.add(p1_ent, ent);
+      dependency_graph.add(p2_ent, ent);
+    }
+    SymbolicCircle::ScalarRadius(point_ent, scalar) => {
+      dependency_graph.add(point_ent, ent);
+      dependency_graph.add_scalar_dependent(scalar, ent);
+    }
+    SymbolicCircle::ThreePoints(p1_ent, p2_ent, p3_ent) => {
+      dependency_graph.add(p1_ent, ent);
+      dependency_graph.add(p2_ent, ent);
+      dependency_graph.add(p3_ent, ent);
+    }
+    SymbolicCircle::Reflect(circle_ent, mirror_ent) => {
+      dependency_graph.add(circle_ent, ent);
+      dependency_graph.add(mirror_ent, ent);
+    }
   }
 }
 
@@ -108,7 +202,7 @@ fn remove_point(ent: &Entity, sym_point: &SymbolicPoint, dependency_graph: &mut
   match sym_point {
     SymbolicPoint::Fixed(_) => (),
     SymbolicPoint::Free(_) => (),
-    SymbolicPoint::MidPoint(p1_ent, p2_ent) => {
+    SymbolicPoint::MidPoint(p1_ent, p2_ent) | SymbolicPoint::Ratio(p1_ent, p2_ent, _) => {
       dependency_graph.remove_dependent(p1_ent, ent);
       dependency_graph.remove_dependent(p2_ent, ent);
     }
@@ -118,6 +212,7 @@ fn remove_point(ent: &Entity, sym_point: &SymbolicPoint, dependency_graph: &mut
       dependency_graph.remove_dependent(l2_ent, ent);
     }
     SymbolicPoint::OnCircle(circle_ent, _) => dependency_graph.remove_dependent(circle_ent, ent),
+    SymbolicPoint::OnConic(conic_ent, _) => dependency_graph.remove_dependent(conic_ent, ent),
     SymbolicPoint::CircleLineIntersect(circle_ent, line_ent, _) => {
       dependency_graph.remove_dependent(circle_ent, ent);
       dependency_graph.remove_dependent(line_ent, ent);
@@ -126,6 +221,32 @@ fn remove_point(ent: &Entity, sym_point: &SymbolicPoint, dependency_graph: &mut
       dependency_graph.remove_dependent(c1_ent, ent);
       dependency_graph.remove_dependent(c2_ent, ent);
     }
+    SymbolicPoint::ArcLineIntersect(arc_ent, line_ent, _) => {
+      dependency_graph.remove_dependent(arc_ent, ent);
+      dependency_graph.remove_dependent(line_ent, ent);
+    }
+    SymbolicPoint::ArcCircleIntersect(arc_ent, circle_ent, _) => {
+      dependency_graph.remove_dependent(arc_ent, ent);
+      dependency_graph.remove_dependent(circle_ent, ent);
+    }
+    SymbolicPoint::PointReflection(source_ent, center_ent) => {
+      dependency_graph.remove_dependent(source_ent, ent);
+      dependency_graph.remove_dependent(center_ent, ent);
+    }
+    SymbolicPoint::Reflect(point_ent, mirror_ent) => {
+      dependency_graph.remove_dependent(point_ent, ent);
+      dependency_graph.remove_dependent(mirror_ent, ent);
+    }
+    SymbolicPoint::Rotate(point_ent, center_ent, _) => {
+      dependency_graph.remove_dependent(point_ent, ent);
+      dependency_graph.remove_dependent(center_ent, ent);
+    }
+    SymbolicPoint::RotateByScalar(point_ent, center_ent, scalar) => {
+      dependency_graph.remove_dependent(point_ent, ent);
+      dependency_graph.remove_dependent(center_ent, ent);
+      dependency_graph.remove_scalar_dependent(scalar, ent);
+    }
+    SymbolicPoint::Translate(point_ent, _) => dependency_graph.remove_dependent(point_ent, ent),
   }
 }
 
@@ -151,6 +272,22 @@ fn remove_line(ent: &Entity, sym_line: &SymbolicLine, dependency_graph: &mut Dep
       dependency_graph.remove_dependent(line_ent, ent);
       dependency_graph.remove_dependent(point_ent, ent);
     }
+    SymbolicLine::CommonTangent(c1_ent, c2_ent, _) => {
+      dependency_graph.remove_dependent(c1_ent, ent);
+      dependency_graph.remove_dependent(c2_ent, ent);
+    }
+    SymbolicLine::Tangent(circle_ent, point_ent, _) => {
+      dependency_graph.remove_dependent(circle_ent, ent);
+      dependency_graph.remove_dependent(point_ent, ent);
+    }
+    SymbolicLine::PerpendicularBisector(p1_ent, p2_ent) => {
+      dependency_graph.remove_dependent(p1_ent, ent);
+      dependency_graph.remove_dependent(p2_ent, ent);
+    }
+    SymbolicLine::Reflect(line_ent, mirror_ent) => {
+      dependency_graph.remove_dependent(line_ent, ent);
+      dependency_graph.remove_dependent(mirror_ent, ent);
+    }
   }
 }
 
@@ -160,5 +297,172 @@ fn remove_circle(ent: &Entity, sym_circle: &SymbolicCircle, dependency_graph: &m
       dependency_graph.remove_dependent(p1_ent, ent);
       dependency_graph.remove_dependent(p2_ent, ent);
     }
+    SymbolicCircle::EqualRadius(point_ent, circle_ent) => {
+      dependency_graph.remove_dependent(point_ent, ent);
+      dependency_graph.remove_dependent(circle_ent, ent);
+    }
+    SymbolicCircle::CompassRadius(point_ent, p1_ent, p2_ent) => {
+      dependency_graph.remove_dependent(point_ent, ent);
+      dependency_graph.remove_dependent(p1_ent, ent);
+      dependency_graph.remove_dependent(p2_ent, ent);
+    }
+    SymbolicCircle::ScalarRadius(point_ent, scalar) => {
+      dependency_graph.remove_dependent(point_ent, ent);
+      dependency_graph.remove_scalar_dependent(scalar, ent);
+    }
+    SymbolicCircle::ThreePoints(p1_ent, p2_ent, p3_ent) => {
+      dependency_graph.remove_dependent(p1_ent, ent);
+      dependency_graph.remove_dependent(p2_ent, ent);
+      dependency_graph.remove_dependent(p3_ent, ent);
+    }
+    SymbolicCircle::Reflect(circle_ent, mirror_ent) => {
+      dependency_graph.remove_dependent(circle_ent, ent);
+      dependency_graph.remove_dependent(mirror_ent, ent);
+    }
+  }
+}
+
+fn insert_arc(ent: &Entity, sym_arc: &SymbolicArc, dependency_graph: &mut DependencyGraph) {
+  for point_ent in &sym_arc.points() {
+    dependency_graph.add(point_ent, ent);
+  }
+}
+
+fn remove_arc(ent: &Entity, sym_arc: &SymbolicArc, dependency_graph: &mut DependencyGraph) {
+  for point_ent in &sym_arc.points() {
+    dependency_graph.remove_dependent(point_ent, ent);
+  }
+}
+
+fn insert_conic(ent: &Entity, sym_conic: &SymbolicConic, dependency_graph: &mut DependencyGraph) {
+  for point_ent in &sym_conic.points() {
+    dependency_graph.add(point_ent, ent);
+  }
+}
+
+fn remove_conic(ent: &Entity, sym_conic: &SymbolicConic, dependency_graph: &mut DependencyGraph) {
+  for point_ent in &sym_conic.points() {
+    dependency_graph.remove_dependent(point_ent, ent);
+  }
+}
+
+fn insert_polygon(ent: &Entity, sym_polygon: &SymbolicPolygon, dependency_graph: &mut DependencyGraph) {
+  for vertex_ent in &sym_polygon.0 {
+    dependency_graph.add(vertex_ent, ent);
+  }
+}
+
+fn remove_polygon(ent: &Entity, sym_polygon: &SymbolicPolygon, dependency_graph: &mut DependencyGraph) {
+  for vertex_ent in &sym_polygon.0 {
+    dependency_graph.remove_dependent(vertex_ent, ent);
+  }
+}
+
+fn insert_vector(ent: &Entity, sym_vector: &SymbolicVector, dependency_graph: &mut DependencyGraph) {
+  dependency_graph.add(&sym_vector.0, ent);
+  dependency_graph.add(&sym_vector.1, ent);
+}
+
+fn remove_vector(ent: &Entity, sym_vector: &SymbolicVector, dependency_graph: &mut DependencyGraph) {
+  dependency_graph.remove_dependent(&sym_vector.0, ent);
+  dependency_graph.remove_dependent(&sym_vector.1, ent);
+}
+
+fn insert_text(ent: &Entity, sym_text: &SymbolicText, dependency_graph: &mut DependencyGraph) {
+  if let TextAnchor::Entity(anchor_ent, _) = sym_text.anchor {
+    dependency_graph.add(&anchor_ent, ent);
+  }
+}
+
+fn remove_text(ent: &Entity, sym_text: &SymbolicText, dependency_graph: &mut DependencyGraph) {
+  if let TextAnchor::Entity(anchor_ent, _) = sym_text.anchor {
+    dependency_graph.remove_dependent(&anchor_ent, ent);
+  }
+}
+
+fn insert_measurement(ent: &Entity, measurement: &Measurement, dependency_graph: &mut DependencyGraph) {
+  for target in measurement.targets() {
+    dependency_graph.add(&target, ent);
+  }
+}
+
+fn remove_measurement(ent: &Entity, measurement: &Measurement, dependency_graph: &mut DependencyGraph) {
+  for target in measurement.targets() {
+    dependency_graph.remove_dependent(&target, ent);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{components::symbolics::*, events::*, math::*, resources::*, test_utils::*};
+  use specs::prelude::*;
+
+  #[test]
+  fn test_glued_point_follows_its_line() {
+    let (mut world, mut dispatcher) = headless();
+    let mut run = |world: &mut World, command: Option<Command>| {
+      if let Some(command) = command {
+        world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+          command,
+          event_id: None,
+        });
+      }
+      dispatcher.dispatch(world);
+      world.maintain();
+    };
+
+    for (x, y) in &[(0., 0.), (4., 0.), (1., 3.)] {
+      run(
+        &mut world,
+        Some(Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, *y]))),
+      );
+    }
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    run(
+      &mut world,
+      Some(Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(
+        points[0], points[1],
+      )))),
+    );
+    let line = (&world.entities(), &world.read_storage::<SymbolicLine>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+
+    let free = SymbolicPoint::Free(vec2![1., 3.].into());
+    let glued = SymbolicPoint::OnLine(line, 0.25.into());
+    let commands = vec![
+      Command::BeginTransaction("Reparent".to_string()),
+      Command::Update(UpdateEvent::UpdatePointEnd(points[2], free, glued)),
+      Command::EndTransaction,
+    ];
+    for command in commands {
+      run(&mut world, Some(command));
+    }
+    run(&mut world, None);
+    assert!(world
+      .fetch::<DependencyGraph>()
+      .get_all_dependents(&line)
+      .contains(&points[2]));
+    assert_eq!(world.fetch::<History>().undo_label(), Some("Reparent"));
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    run(&mut world, None);
+    run(&mut world, None);
+    assert!(!world
+      .fetch::<DependencyGraph>()
+      .get_all_dependents(&line)
+      .contains(&points[2]));
+    assert!(matches!(
+      world.read_storage::<SymbolicPoint>().get(points[2]),
+      Some(SymbolicPoint::Free(_))
+    ));
   }
 }
@@ -0,0 +1,15 @@
+use crate::{components::markers::*, resources::*};
+use specs::prelude::*;
+
+/// Refills the `EntityBudget` from the geometries there are before any command of the frame is
+/// handled, so that the insert handlers together never go past `MaxEntities`
+#[derive(Default)]
+pub struct EntityBudgetManager;
+
+impl<'a> System<'a> for EntityBudgetManager {
+  type SystemData = (Read<'a, MaxEntities>, Write<'a, EntityBudget>, ReadStorage<'a, Element>);
+
+  fn run(&mut self, (max_entities, mut entity_budget, elements): Self::SystemData) {
+    entity_budget.refill(*max_entities, (&elements).join().count());
+  }
+}
@@ -1,4 +1,9 @@
-use crate::{components::symbolics::SymbolicPoint, events::*, resources::*, utilities::*};
+use crate::{
+  components::{symbolics::*, underlays::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
 use specs::prelude::*;
 use std::collections::{HashMap, HashSet};
 
@@ -6,12 +11,18 @@ enum Mod {
   None,
   Insert(HashMap<Entity, Geometry>),
   Remove(HashMap<Entity, Geometry>),
-  UpdatePoint(Entity, SymbolicPoint, SymbolicPoint),
+  UpdatePoint(HashMap<Entity, (SymbolicPoint, SymbolicPoint)>),
+  UpdateLine(Entity, SymbolicLine, SymbolicLine),
+  UpdateSlider(Entity, f64, f64),
+  UpdateUnderlay(Entity, UnderlayPlacement, UnderlayPlacement),
   Hide(HashSet<Entity>),
   Unhide(HashSet<Entity>),
+  Restyle(HashMap<Entity, (Style, Style)>),
+  Rename(HashMap<Entity, (Option<String>, Option<String>)>),
 }
 
 pub struct HistoryManager {
+  command_event_reader: Option<CommandEventReader>,
   geometry_event_reader: Option<GeometryEventReader>,
   marker_event_reader: Option<MarkerEventReader>,
 }
@@ -19,6 +30,7 @@ pub struct HistoryManager {
 impl Default for HistoryManager {
   fn default() -> Self {
     Self {
+      command_event_reader: None,
       geometry_event_reader: None,
       marker_event_reader: None,
     }
@@ -27,6 +39,7 @@ impl Default for HistoryManager {
 
 impl<'a> System<'a> for HistoryManager {
   type SystemData = (
+    Read<'a, CommandEventChannel>,
     Read<'a, GeometryEventChannel>,
     Read<'a, MarkerEventChannel>,
     Write<'a, History>,
@@ -34,14 +47,32 @@ impl<'a> System<'a> for HistoryManager {
 
   fn setup(&mut self, world: &mut World) {
     Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
     self.geometry_event_reader = Some(world.fetch_mut::<GeometryEventChannel>().register_reader());
     self.marker_event_reader = Some(world.fetch_mut::<MarkerEventChannel>().register_reader());
   }
 
-  fn run(&mut self, (geometry_event_channel, marker_event_channel, mut history): Self::SystemData) {
+  fn run(
+    &mut self,
+    (command_event_channel, geometry_event_channel, marker_event_channel, mut history): Self::SystemData,
+  ) {
+    assert!(self.command_event_reader.is_some());
     assert!(self.geometry_event_reader.is_some());
     assert!(self.marker_event_reader.is_some());
 
+    // Transactions are begun before and ended after the modifications of the frame, so a
+    // transaction always covers whole frames
+    let mut transaction_ends = 0;
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match &event.command {
+          Command::BeginTransaction(label) => history.begin_transaction(label.clone()),
+          Command::EndTransaction => transaction_ends += 1,
+          _ => (),
+        }
+      }
+    }
+
     // First do geometry events
     if let Some(reader_id) = &mut self.geometry_event_reader {
       let mut curr_event = Mod::None;
@@ -49,27 +80,48 @@ impl<'a> System<'a> for HistoryManager {
         match event {
           GeometryEvent::Inserted(entity, geom, false) => {
             if let Mod::Insert(insertions) = &mut curr_event {
-              insertions.insert(*entity, *geom);
+              insertions.insert(*entity, geom.clone());
             } else {
               push_event(curr_event, &mut history);
               let mut insertions = HashMap::new();
-              insertions.insert(*entity, *geom);
+              insertions.insert(*entity, geom.clone());
               curr_event = Mod::Insert(insertions);
             }
           }
           GeometryEvent::Removed(entity, geom, false) => {
             if let Mod::Remove(removals) = &mut curr_event {
-              removals.insert(*entity, *geom);
+              removals.insert(*entity, geom.clone());
             } else {
               push_event(curr_event, &mut history);
               let mut removals = HashMap::new();
-              removals.insert(*entity, *geom);
+              removals.insert(*entity, geom.clone());
               curr_event = Mod::Remove(removals);
             }
           }
+          // The points moved by a same gesture finish their update in the same frame and are
+          // undone together
           GeometryEvent::PointUpdateFinished(entity, old_sym_point, new_sym_point, false) => {
+            if let Mod::UpdatePoint(updates) = &mut curr_event {
+              let old_sym_point = updates.get(entity).map_or(*old_sym_point, |(old, _)| *old);
+              updates.insert(*entity, (old_sym_point, *new_sym_point));
+            } else {
+              push_event(curr_event, &mut history);
+              let mut updates = HashMap::new();
+              updates.insert(*entity, (*old_sym_point, *new_sym_point));
+              curr_event = Mod::UpdatePoint(updates);
+            }
+          }
+          GeometryEvent::LineUpdated(entity, old_sym_line, new_sym_line, false) => {
+            push_event(curr_event, &mut history);
+            curr_event = Mod::UpdateLine(*entity, *old_sym_line, *new_sym_line);
+          }
+          GeometryEvent::SliderUpdateFinished(entity, old_value, new_value, false) => {
+            push_event(curr_event, &mut history);
+            curr_event = Mod::UpdateSlider(*entity, *old_value, *new_value);
+          }
+          GeometryEvent::UnderlayUpdateFinished(entity, old_placement, new_placement, false) => {
             push_event(curr_event, &mut history);
-            curr_event = Mod::UpdatePoint(*entity, *old_sym_point, *new_sym_point);
+            curr_event = Mod::UpdateUnderlay(*entity, *old_placement, *new_placement);
           }
           _ => (),
         }
@@ -102,11 +154,37 @@ impl<'a> System<'a> for HistoryManager {
               curr_event = Mod::Unhide(entities);
             }
           }
+          MarkerEvent::Restyle(entity, old_style, new_style, false) => {
+            if let Mod::Restyle(restyles) = &mut curr_event {
+              let old_style = restyles.get(entity).map_or(*old_style, |(old, _)| *old);
+              restyles.insert(*entity, (old_style, *new_style));
+            } else {
+              push_event(curr_event, &mut history);
+              let mut restyles = HashMap::new();
+              restyles.insert(*entity, (*old_style, *new_style));
+              curr_event = Mod::Restyle(restyles);
+            }
+          }
+          MarkerEvent::Rename(entity, old_name, new_name, false) => {
+            if let Mod::Rename(renames) = &mut curr_event {
+              let old_name = renames.get(entity).map_or(old_name.clone(), |(old, _)| old.clone());
+              renames.insert(*entity, (old_name, new_name.clone()));
+            } else {
+              push_event(curr_event, &mut history);
+              let mut renames = HashMap::new();
+              renames.insert(*entity, (old_name.clone(), new_name.clone()));
+              curr_event = Mod::Rename(renames);
+            }
+          }
           _ => (),
         }
       }
       push_event(curr_event, &mut history);
     }
+
+    for _ in 0..transaction_ends {
+      history.end_transaction();
+    }
   }
 }
 
@@ -115,10 +193,186 @@ fn push_event(event: Mod, history: &mut History) {
     Mod::None => (),
     Mod::Insert(insertions) => history.push(Modification::InsertMany(insertions)),
     Mod::Remove(removals) => history.push(Modification::RemoveMany(removals)),
-    Mod::UpdatePoint(ent, old_sym_point, new_sym_point) => {
-      history.push(Modification::UpdatePoint(ent, old_sym_point, new_sym_point))
+    Mod::UpdatePoint(updates) => history.push(Modification::UpdatePointMany(updates)),
+    Mod::UpdateLine(ent, old_sym_line, new_sym_line) => {
+      history.push(Modification::UpdateLine(ent, old_sym_line, new_sym_line))
+    }
+    Mod::UpdateSlider(ent, old_value, new_value) => history.push(Modification::UpdateSlider(ent, old_value, new_value)),
+    Mod::UpdateUnderlay(ent, old_placement, new_placement) => {
+      history.push(Modification::UpdateUnderlay(ent, old_placement, new_placement))
     }
     Mod::Hide(entities) => history.push(Modification::HideMany(entities)),
     Mod::Unhide(entities) => history.push(Modification::UnhideMany(entities)),
+    Mod::Restyle(restyles) => history.push(Modification::RestyleMany(restyles)),
+    Mod::Rename(renames) => history.push(Modification::RenameMany(renames)),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_transaction_is_undone_at_once() {
+    let (mut world, mut dispatcher) = headless();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![-1., -1.])),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::BeginTransaction("Three points".to_string()),
+    );
+    // A nested transaction is part of the outer one
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::BeginTransaction("Inner".to_string()),
+    );
+    for x in &[0., 1., 2.] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, 0.])),
+      );
+    }
+    step(&mut world, &mut dispatcher, Command::EndTransaction);
+    assert_eq!(world.fetch::<History>().undo_label(), None);
+    step(&mut world, &mut dispatcher, Command::EndTransaction);
+    assert_eq!(world.fetch::<History>().undo_label(), Some("Three points"));
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 4);
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 1);
+    assert_eq!(world.fetch::<History>().undo_label(), None);
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Redo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 4);
+  }
+
+  #[test]
+  fn test_drag_gesture_is_undone_at_once() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for x in &[0., 1.] {
+      let point = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![*x, 0.].into()));
+      points.push(point);
+    }
+    let position = |world: &World, ent: Entity| match world.read_storage::<SymbolicPoint>().get(ent) {
+      Some(SymbolicPoint::Free(p)) => Some(p.0),
+      _ => None,
+    };
+
+    // Both points are dragged over a few frames, as when dragging a line through them
+    let free = |x: f64, y: f64| SymbolicPoint::Free(vec2![x, y].into());
+    for y in &[1., 2., 3.] {
+      for (ent, x) in points.iter().zip(&[0., 1.]) {
+        step(
+          &mut world,
+          &mut dispatcher,
+          Command::Update(UpdateEvent::UpdatePoint(*ent, free(*x, y - 1.), free(*x, *y))),
+        );
+      }
+    }
+    for (ent, x) in points.iter().zip(&[0., 1.]) {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+        command: Command::Update(UpdateEvent::UpdatePointEnd(*ent, free(*x, 0.), free(*x, 3.))),
+        event_id: None,
+      });
+    }
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, points[1]), Some(vec2![1., 3.]));
+
+    // A single undo brings both points back to where the drag began, without removing them
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, points[0]), Some(vec2![0., 0.]));
+    assert_eq!(position(&world, points[1]), Some(vec2![1., 0.]));
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Redo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, points[0]), Some(vec2![0., 3.]));
+    assert_eq!(position(&world, points[1]), Some(vec2![1., 3.]));
+  }
+
+  #[test]
+  fn test_jump_to_another_branch() {
+    let (mut world, mut dispatcher) = headless();
+    let xs = |world: &World| {
+      let mut xs = world
+        .read_storage::<SymbolicPoint>()
+        .join()
+        .filter_map(|sym_point| match sym_point {
+          SymbolicPoint::Free(p) => Some(p.0.x),
+          _ => None,
+        })
+        .collect::<Vec<_>>();
+      xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+      xs
+    };
+
+    for x in &[0., 1.] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![*x, 0.].into()))),
+      );
+    }
+    let first_branch = world.fetch::<History>().current();
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    // Inserting after the undo branches off, keeping the undone point around
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![2., 0.].into()))),
+    );
+    let second_branch = world.fetch::<History>().current();
+    assert_eq!(xs(&world), vec![0., 2.]);
+    {
+      let history = world.fetch::<History>();
+      let parent = history.parent(second_branch).unwrap();
+      assert_eq!(history.children(parent), &[first_branch, second_branch]);
+      assert_eq!(history.modification(first_branch).unwrap().label(), "Insert");
+    }
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::JumpTo(first_branch));
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(xs(&world), vec![0., 1.]);
+    assert_eq!(world.fetch::<History>().current(), first_branch);
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::JumpTo(second_branch));
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(xs(&world), vec![0., 2.]);
   }
 }
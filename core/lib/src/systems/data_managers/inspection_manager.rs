@@ -0,0 +1,214 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Describes the selected points, lines, circles, arcs, conics, polygons and vectors after they
+/// are solved, and emits the description whenever it changes, e.g. while a parent is dragged
+#[derive(Default)]
+pub struct InspectionManager;
+
+impl<'a> System<'a> for InspectionManager {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Names>,
+    Read<'a, SelectionOrder>,
+    Write<'a, Inspection>,
+    Write<'a, InspectionEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, Unsolvable>,
+    (
+      ReadStorage<'a, SymbolicPoint>,
+      ReadStorage<'a, PointStyle>,
+      ReadStorage<'a, VirtualPoint>,
+    ),
+    (
+      ReadStorage<'a, SymbolicLine>,
+      ReadStorage<'a, LineStyle>,
+      ReadStorage<'a, VirtualLine>,
+    ),
+    (
+      ReadStorage<'a, SymbolicCircle>,
+      ReadStorage<'a, CircleStyle>,
+      ReadStorage<'a, VirtualCircle>,
+    ),
+    (
+      ReadStorage<'a, SymbolicArc>,
+      ReadStorage<'a, ArcStyle>,
+      ReadStorage<'a, VirtualArc>,
+    ),
+    (
+      ReadStorage<'a, SymbolicConic>,
+      ReadStorage<'a, ConicStyle>,
+      ReadStorage<'a, VirtualConic>,
+    ),
+    (ReadStorage<'a, SymbolicPolygon>, ReadStorage<'a, PolygonStyle>),
+    (
+      ReadStorage<'a, SymbolicVector>,
+      ReadStorage<'a, VectorStyle>,
+      ReadStorage<'a, VirtualVector>,
+    ),
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      names,
+      selection_order,
+      mut inspection,
+      mut inspection_event_channel,
+      selecteds,
+      unsolvables,
+      (sym_points, point_styles, virt_points),
+      (sym_lines, line_styles, virt_lines),
+      (sym_circles, circle_styles, virt_circles),
+      (sym_arcs, arc_styles, virt_arcs),
+      (sym_conics, conic_styles, virt_conics),
+      (sym_polygons, polygon_styles),
+      (sym_vectors, vector_styles, virt_vectors),
+    ): Self::SystemData,
+  ) {
+    let inspect = |ent: Entity| -> Option<InspectedGeometry> {
+      let (geometry, coordinates) =
+        if let (Some(sym_point), Some(point_style)) = (sym_points.get(ent), point_styles.get(ent)) {
+          let coordinates = virt_points
+            .get(ent)
+            .map_or(vec![], |p| vec![("x", p.0.x), ("y", p.0.y)]);
+          (Geometry::Point(*sym_point, *point_style), coordinates)
+        } else if let (Some(sym_line), Some(line_style)) = (sym_lines.get(ent), line_styles.get(ent)) {
+          let coordinates = virt_lines.get(ent).map_or(vec![], |l| {
+            vec![
+              ("x1", l.from.0.x),
+              ("y1", l.from.0.y),
+              ("x2", l.to.0.x),
+              ("y2", l.to.0.y),
+            ]
+          });
+          (Geometry::Line(*sym_line, *line_style), coordinates)
+        } else if let (Some(sym_circle), Some(circle_style)) = (sym_circles.get(ent), circle_styles.get(ent)) {
+          let coordinates = virt_circles.get(ent).map_or(vec![], |c| {
+            vec![("x", c.center.0.x), ("y", c.center.0.y), ("radius", c.radius.0)]
+          });
+          (Geometry::Circle(*sym_circle, *circle_style), coordinates)
+        } else if let (Some(sym_arc), Some(arc_style)) = (sym_arcs.get(ent), arc_styles.get(ent)) {
+          let coordinates = virt_arcs.get(ent).map_or(vec![], |a| {
+            vec![
+              ("x", a.center.0.x),
+              ("y", a.center.0.y),
+              ("radius", a.radius.0),
+              ("start", a.start),
+              ("sweep", a.sweep),
+            ]
+          });
+          (Geometry::Arc(*sym_arc, *arc_style), coordinates)
+        } else if let (Some(sym_conic), Some(conic_style)) = (sym_conics.get(ent), conic_styles.get(ent)) {
+          let coordinates = virt_conics.get(ent).map_or(vec![], |c| {
+            vec![
+              ("x", c.center.0.x),
+              ("y", c.center.0.y),
+              ("rx", c.rx.0),
+              ("ry", c.ry.0),
+              ("rotation", c.rotation),
+            ]
+          });
+          (Geometry::Conic(*sym_conic, *conic_style), coordinates)
+        } else if let (Some(sym_polygon), Some(polygon_style)) = (sym_polygons.get(ent), polygon_styles.get(ent)) {
+          // The vertices are points of their own
+          (Geometry::Polygon(sym_polygon.clone(), *polygon_style), vec![])
+        } else if let (Some(sym_vector), Some(vector_style)) = (sym_vectors.get(ent), vector_styles.get(ent)) {
+          let coordinates = virt_vectors.get(ent).map_or(vec![], |v| {
+            vec![("dx", v.to.0.x - v.from.0.x), ("dy", v.to.0.y - v.from.0.y)]
+          });
+          (Geometry::Vector(*sym_vector, *vector_style), coordinates)
+        } else {
+          return None;
+        };
+      Some(InspectedGeometry {
+        entity: ent,
+        name: names.name_of(ent).cloned(),
+        geometry,
+        coordinates,
+        unsolvable: unsolvables.get(ent).map(|unsolvable| unsolvable.0),
+      })
+    };
+
+    // In the order they got selected, the ones selected as they got inserted coming last
+    let mut selected = selection_order
+      .iter()
+      .copied()
+      .filter(|ent| selecteds.contains(*ent))
+      .collect::<Vec<_>>();
+    for (ent, _) in (&entities, &selecteds).join() {
+      if !selected.contains(&ent) {
+        selected.push(ent);
+      }
+    }
+
+    let inspected = selected.into_iter().filter_map(inspect).collect::<Vec<_>>();
+    if inspection.set(inspected) {
+      inspection_event_channel.single_write(InspectionEvent::Inspected(inspection.get().to_vec()));
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  #[test]
+  fn test_inspect_and_edit_selection() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<InspectionEventChannel>().register_reader();
+
+    let mut points = vec![];
+    for position in &[vec2![0., 0.], vec2![4., 0.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free((*position).into()))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+    let ratio_point = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::Ratio(points[0], points[1], 0.25),
+    );
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Select(SelectEvent::Select(ratio_point)),
+    );
+
+    let inspected = world.fetch::<Inspection>().get().to_vec();
+    assert_eq!(inspected.len(), 1);
+    assert_eq!(inspected[0].kind(), GeometryKind::Point);
+    assert_eq!(inspected[0].parents(), points);
+    assert_eq!(inspected[0].coordinates, vec![("x", 1.), ("y", 0.)]);
+    assert_eq!(inspected[0].parameters(), vec![("ratio", 0.25)]);
+    assert!(inspected[0].edit("x", 2.).is_none());
+
+    // The edit sent back moves the point, and the new description is emitted
+    world.fetch_mut::<InspectionEventChannel>().read(&mut reader).count();
+    for command in inspected[0].edit("ratio", 0.5).unwrap() {
+      step(&mut world, &mut dispatcher, command);
+    }
+    let events = world
+      .fetch::<InspectionEventChannel>()
+      .read(&mut reader)
+      .map(|InspectionEvent::Inspected(inspected)| inspected.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0][0].coordinates, vec![("x", 2.), ("y", 0.)]);
+
+    // Nothing is described once the selection is empty
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    assert!(world.fetch::<Inspection>().get().is_empty());
+  }
+}
@@ -0,0 +1,164 @@
+use crate::{components::markers::*, events::*, resources::*, utilities::*};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Names every new point with the first free name of A, B, ..., Z, AA, AB, ... and every new
+/// slider with the first free one of t, t1, t2, ..., and keeps the labels in line with the names. The name of a removed geometry is freed and given back when the
+/// history inserts the geometry again, unless another one took it in the meantime
+pub struct LabelManager {
+  geometry_event_reader: Option<GeometryEventReader>,
+  removed_names: HashMap<Entity, String>,
+}
+
+impl Default for LabelManager {
+  fn default() -> Self {
+    Self {
+      geometry_event_reader: None,
+      removed_names: HashMap::new(),
+    }
+  }
+}
+
+impl<'a> System<'a> for LabelManager {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryEventChannel>,
+    Write<'a, Names>,
+    WriteStorage<'a, Label>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_event_reader = Some(world.fetch_mut::<GeometryEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (entities, geometry_event_channel, mut names, mut labels): Self::SystemData) {
+    if let Some(reader) = &mut self.geometry_event_reader {
+      for event in geometry_event_channel.read(reader) {
+        match event {
+          GeometryEvent::Removed(ent, _, _) => {
+            if let Some(name) = names.remove(*ent) {
+              self.removed_names.insert(*ent, name);
+            }
+          }
+          GeometryEvent::Inserted(ent, geometry, by_history) => {
+            let name = match self.removed_names.remove(ent) {
+              Some(name) if *by_history && names.by_name(&name).is_none() => Some(name),
+              _ => match geometry {
+                Geometry::Point(_, _) if names.name_of(*ent).is_none() => Some(first_free_name(&names)),
+                Geometry::Slider(_, _) if names.name_of(*ent).is_none() => Some(first_free_slider_name(&names)),
+                _ => None,
+              },
+            };
+            if let Some(name) = name {
+              if let Err(err) = names.assign(*ent, name) {
+                panic!(err)
+              }
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+
+    // Labels of entities that lost their name go away, the other ones follow their name
+    let unnamed = (&entities, &labels)
+      .join()
+      .filter(|(ent, _)| names.name_of(*ent).is_none())
+      .map(|(ent, _)| ent)
+      .collect::<Vec<_>>();
+    for ent in unnamed {
+      labels.remove(ent);
+    }
+    for (ent, name) in names.iter() {
+      match labels.get_mut(ent) {
+        Some(label) => {
+          if label.text != *name {
+            label.text = name.clone();
+          }
+        }
+        None => {
+          if let Err(err) = labels.insert(ent, Label::new(name.clone())) {
+            panic!(err)
+          }
+        }
+      }
+    }
+  }
+}
+
+fn first_free_name(names: &Names) -> String {
+  (0..)
+    .map(alphabetic_name)
+    .find(|name| names.by_name(name).is_none())
+    .unwrap()
+}
+
+fn first_free_slider_name(names: &Names) -> String {
+  (0..)
+    .map(|i| if i == 0 { "t".to_string() } else { format!("t{}", i) })
+    .find(|name| names.by_name(name).is_none())
+    .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, math::*, test_utils::*};
+
+  fn label_of(world: &World, ent: Entity) -> Option<String> {
+    world.read_storage::<Label>().get(ent).map(|label| label.text.clone())
+  }
+
+  #[test]
+  fn test_points_are_labelled_and_renaming_is_undone() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut points = vec![];
+    for x in &[0., 1., 2.] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, 0.])),
+      );
+      let point = last_inserted::<SymbolicPoint>(&world);
+      points.push(point);
+    }
+    let labels = |world: &World| points.iter().map(|p| label_of(world, *p)).collect::<Vec<_>>();
+    let some = |name: &str| Some(name.to_string());
+    assert_eq!(labels(&world), vec![some("A"), some("B"), some("C")]);
+
+    // The freed name goes to the next point
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Rename(RenameEvent::Rename(points[0], "O".to_string())),
+    );
+    assert_eq!(labels(&world), vec![some("O"), some("B"), some("C")]);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![3., 0.])),
+    );
+    let last = last_inserted::<SymbolicPoint>(&world);
+    assert_eq!(label_of(&world, last), some("A"));
+
+    // Undoing the insertion frees the name for the undone rename, redoing both brings it back
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(labels(&world), vec![some("A"), some("B"), some("C")]);
+    history(&mut world, &mut dispatcher, HistoryEvent::Redo);
+    history(&mut world, &mut dispatcher, HistoryEvent::Redo);
+    assert_eq!(labels(&world), vec![some("O"), some("B"), some("C")]);
+    assert_eq!(label_of(&world, last), some("A"));
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Rename(RenameEvent::Unname(points[1])),
+    );
+    assert_eq!(labels(&world), vec![some("O"), None, some("C")]);
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(labels(&world), vec![some("O"), some("B"), some("C")]);
+  }
+}
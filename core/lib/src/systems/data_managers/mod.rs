@@ -1,7 +1,21 @@
+mod command_recorder;
 mod dependency_graph_manager;
+mod entity_budget_manager;
 mod history_manager;
+mod inspection_manager;
+mod label_manager;
+mod session_recorder;
 mod spatial_entity_map_manager;
+mod trace_collector_system;
+mod viewport_history_manager;
 
+pub use command_recorder::*;
 pub use dependency_graph_manager::*;
+pub use entity_budget_manager::*;
 pub use history_manager::*;
+pub use inspection_manager::*;
+pub use label_manager::*;
+pub use session_recorder::*;
 pub use spatial_entity_map_manager::*;
+pub use trace_collector_system::*;
+pub use viewport_history_manager::*;
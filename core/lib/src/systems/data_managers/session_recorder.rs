@@ -0,0 +1,207 @@
+use crate::{events::*, io::*, utilities::Instant};
+use specs::prelude::*;
+use std::fs::File;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+/// Appends every command and history event of the session to a log file, together with the
+/// geometries they insert, so that the session can be replayed later on. Every frame is written
+/// as soon as it ends, the log is complete up to the last frame even when the app crashes.
+/// Not part of the core lib systems, the apps add it when asked to record
+pub struct SessionRecorder {
+  path: PathBuf,
+  file: Option<File>, // None when the log could not be written
+  start: Instant,
+  frame: usize,
+  command_event_reader: Option<CommandEventReader>,
+  history_event_reader: Option<HistoryEventReader>,
+  geometry_event_reader: Option<GeometryEventReader>,
+}
+
+impl SessionRecorder {
+  pub fn new(path: PathBuf) -> Self {
+    Self {
+      path,
+      file: None,
+      start: Instant::now(),
+      frame: 0,
+      command_event_reader: None,
+      history_event_reader: None,
+      geometry_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for SessionRecorder {
+  type SystemData = (
+    Read<'a, CommandEventChannel>,
+    Read<'a, HistoryEventChannel>,
+    Read<'a, GeometryEventChannel>,
+    Write<'a, ErrorEventChannel>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+    self.history_event_reader = Some(world.fetch_mut::<HistoryEventChannel>().register_reader());
+    self.geometry_event_reader = Some(world.fetch_mut::<GeometryEventChannel>().register_reader());
+    let file = File::create(&self.path).and_then(|mut file| {
+      writeln!(file, "{}", write_session_header())?;
+      Ok(file)
+    });
+    match file {
+      Ok(file) => self.file = Some(file),
+      Err(err) => world
+        .fetch_mut::<ErrorEventChannel>()
+        .single_write(ErrorEvent::SketchFile(SketchFileError::Io(err.to_string()))),
+    }
+    self.start = Instant::now();
+  }
+
+  fn run(
+    &mut self,
+    (command_event_channel, history_event_channel, geometry_event_channel, mut error_event_channel): Self::SystemData,
+  ) {
+    let time = self.start.elapsed();
+    let mut lines = vec![];
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        if let Some(line) = write_session_command(self.frame, time, event) {
+          lines.push(line);
+        }
+      }
+    }
+    if let Some(reader) = &mut self.history_event_reader {
+      for event in history_event_channel.read(reader) {
+        lines.push(write_session_history_event(self.frame, time, *event));
+      }
+    }
+
+    // Entities inserted by history already existed before, so only the fresh ones are written
+    if let Some(reader) = &mut self.geometry_event_reader {
+      for event in geometry_event_channel.read(reader) {
+        if let GeometryEvent::Inserted(ent, geom, false) = event {
+          lines.push(write_session_insertion(self.frame, *ent, geom.kind()));
+        }
+      }
+    }
+
+    if let Some(file) = &mut self.file {
+      if !lines.is_empty() {
+        if let Err(err) = writeln!(file, "{}", lines.join("\n")) {
+          error_event_channel.single_write(ErrorEvent::SketchFile(SketchFileError::Io(err.to_string())));
+          self.file = None;
+        }
+      }
+    }
+    self.frame += 1;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{
+    components::{symbolics::*, virtual_shapes::*},
+    math::*,
+    test_utils::*,
+    utilities::*,
+  };
+  use std::fs;
+
+  fn recording<'a, 'b>(path: PathBuf) -> (World, Dispatcher<'a, 'b>) {
+    headless_with(|builder| {
+      builder.add_barrier();
+      builder.add(SessionRecorder::new(path), "session_recorder", &[]);
+    })
+  }
+
+  fn solved(world: &World) -> (Vec<(f64, f64)>, usize) {
+    let mut points: Vec<_> = (&world.read_storage::<VirtualPoint>())
+      .join()
+      .map(|p| (p.0.x, p.0.y))
+      .collect();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    (points, world.read_storage::<VirtualLine>().join().count())
+  }
+
+  #[test]
+  fn test_recorded_session_replays() {
+    let path = std::env::temp_dir().join(format!("geopad-session-{}.jsonl", std::process::id()));
+    let (mut world, mut dispatcher) = recording(path.clone());
+
+    for (x, y) in &[(0., 0.), (4., 2.), (1., 5.)] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![*x, *y])),
+      );
+    }
+    let mut points = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .collect::<Vec<_>>();
+    points.sort_by_key(|ent| ent.id());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(points[0], points[1]))),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePointEnd(
+        points[1],
+        SymbolicPoint::Fixed(vec2![4., 2.].into()),
+        SymbolicPoint::Fixed(vec2![4., 3.].into()),
+      )),
+    );
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Remove(RemoveEvent::Remove(points[2])),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    // Shift the entity ids of the fresh world so that remapping is actually needed
+    let (mut replay_world, mut replay_dispatcher) = headless();
+    replay_world.create_entity().build();
+    let text = fs::read_to_string(&path).unwrap();
+    let _ = fs::remove_file(&path);
+    let log = read_session_log(&text, &replay_world.entities()).unwrap();
+    assert!(log
+      .commands()
+      .iter()
+      .all(|recorded| !matches!(recorded.event.command, Command::Remove(RemoveEvent::RemoveByHistory(_)))));
+    assert_eq!(log.history_events().len(), 1);
+    replay(
+      &mut replay_world,
+      &mut replay_dispatcher,
+      &log,
+      ReplaySpeed::AsFastAsPossible,
+    );
+    replay_dispatcher.dispatch(&replay_world);
+    replay_world.maintain();
+
+    let (points, lines) = solved(&replay_world);
+    assert_eq!(points, vec![(0., 0.), (4., 2.)]);
+    assert_eq!(lines, 1);
+    assert_eq!((points, lines), solved(&world));
+  }
+
+  #[test]
+  fn test_invalid_session_log() {
+    let world = World::new();
+    assert_eq!(
+      read_session_log("{\"version\": 2}", &world.entities()).err(),
+      Some(SketchFileError::UnsupportedVersion(2))
+    );
+    let text = "{\"version\": 1}\n{\"frame\": 0, \"time\": 0, \"command\": {\"type\": \"remove\", \"entity\": 7}}";
+    assert_eq!(
+      read_session_log(text, &world.entities()).err(),
+      Some(SketchFileError::UnknownReference(7))
+    );
+  }
+}
@@ -1,15 +1,15 @@
 use crate::{
-  components::{markers::*, screen_shapes::*},
+  components::{markers::*, virtual_shapes::*},
   events::*,
-  math::*,
   resources::*,
   utilities::*,
 };
 use specs::prelude::*;
 
+/// Keeps the spatial entity map and the entity quadtree in step with the virtual shapes, leaving
+/// the hidden geometries out of both
 pub struct SpatialEntityMapManager {
   geometry_event_reader: Option<GeometryEventReader>,
-  viewport_event_reader: Option<ViewportEventReader>,
   marker_event_reader: Option<MarkerEventReader>,
 }
 
@@ -17,7 +17,6 @@ impl Default for SpatialEntityMapManager {
   fn default() -> Self {
     Self {
       geometry_event_reader: None,
-      viewport_event_reader: None,
       marker_event_reader: None,
     }
   }
@@ -25,91 +24,73 @@ impl Default for SpatialEntityMapManager {
 
 impl<'a> System<'a> for SpatialEntityMapManager {
   type SystemData = (
-    Entities<'a>,
+    Read<'a, SolverEnabled>,
     Read<'a, GeometryEventChannel>,
-    Read<'a, ViewportEventChannel>,
     Read<'a, MarkerEventChannel>,
     Read<'a, DependencyGraph>,
     Write<'a, SpatialEntityMap>,
-    ReadStorage<'a, ScreenPosition>,
-    ReadStorage<'a, ScreenLine>,
-    ReadStorage<'a, ScreenCircle>,
+    Write<'a, EntityQuadtree>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualLine>,
+    ReadStorage<'a, VirtualCircle>,
+    ReadStorage<'a, VirtualArc>,
+    ReadStorage<'a, VirtualConic>,
     ReadStorage<'a, Hidden>,
   );
 
   fn setup(&mut self, world: &mut World) {
     Self::SystemData::setup(world);
     self.geometry_event_reader = Some(world.fetch_mut::<GeometryEventChannel>().register_reader());
-    self.viewport_event_reader = Some(world.fetch_mut::<ViewportEventChannel>().register_reader());
     self.marker_event_reader = Some(world.fetch_mut::<MarkerEventChannel>().register_reader());
   }
 
   fn run(
     &mut self,
     (
-      entities,
+      solver_enabled,
       geometry_event_channel,
-      viewport_event_channel,
       marker_event_channel,
       dependency_graph,
       mut spatial_entity_map,
-      screen_points,
-      screen_lines,
-      screen_circles,
+      mut entity_quadtree,
+      virt_points,
+      virt_lines,
+      virt_circles,
+      virt_arcs,
+      virt_conics,
       hiddens,
     ): Self::SystemData,
   ) {
-    // First check if we need to update all. It happens when a viewport event happens
-    if let Some(reader) = &mut self.viewport_event_reader {
-      let mut need_add_all = false;
-      for event in viewport_event_channel.read(reader) {
-        match event {
-          ViewportEvent::Move(_) => spatial_entity_map.clear(),
-          ViewportEvent::Scale(_) => spatial_entity_map.clear(),
-          ViewportEvent::Resize(Vector2 { x, y }) => spatial_entity_map.set_size(*x, *y),
-        }
-        need_add_all = true;
-      }
-      if need_add_all {
-        for (ent, screen_point, _) in (&entities, &screen_points, !&hiddens).join() {
-          spatial_entity_map.insert_point(ent, (*screen_point).into());
-        }
-        for (ent, screen_line, _) in (&entities, &screen_lines, !&hiddens).join() {
-          spatial_entity_map.insert_line(ent, (*screen_line).into());
-        }
-        for (ent, screen_circle, _) in (&entities, &screen_circles, !&hiddens).join() {
-          spatial_entity_map.insert_circle(ent, (*screen_circle).into());
-        }
-      }
+    // The shapes are not solved yet, keep the events for when the solver is enabled again
+    if !solver_enabled.0 {
+      return;
     }
 
-    // Then read the geometry events to determine which ones to add
+    // Read the geometry events to determine which ones to add. Both indexes are in virtual space,
+    // so moving the viewport leaves them as they are
     if let Some(reader) = &mut self.geometry_event_reader {
       for event in geometry_event_channel.read(reader) {
         match event {
           GeometryEvent::Inserted(ent, _, _) => {
-            insert(
-              ent,
-              &mut spatial_entity_map,
-              &screen_points,
-              &screen_lines,
-              &screen_circles,
-            );
+            if let Some(shape) = shape_of(ent, &virt_points, &virt_lines, &virt_circles, &virt_arcs, &virt_conics) {
+              insert(ent, shape, &mut spatial_entity_map, &mut entity_quadtree);
+            }
           }
           GeometryEvent::Removed(ent, _, _) => {
-            spatial_entity_map.remove_from_all(*ent);
+            spatial_entity_map.remove(ent);
+            entity_quadtree.remove(ent);
           }
-          GeometryEvent::PointUpdated(ent, _, _, _) => {
+          GeometryEvent::PointUpdated(ent, _, _, _) | GeometryEvent::LineUpdated(ent, _, _, _) => {
+            // Each dependent only leaves the tiles it was in, however many entities the map holds.
+            // The ones that could not be solved anymore are only taken out
             for dep in dependency_graph.get_all_dependents(ent) {
               if hiddens.get(dep).is_none() {
-                spatial_entity_map.remove_from_all(dep);
-                insert(
-                  &dep,
-                  &mut spatial_entity_map,
-                  &screen_points,
-                  &screen_lines,
-                  &screen_circles,
-                );
+                spatial_entity_map.remove(&dep);
+                entity_quadtree.remove(&dep);
+                if let Some(shape) = shape_of(&dep, &virt_points, &virt_lines, &virt_circles, &virt_arcs, &virt_conics)
+                {
+                  insert(&dep, shape, &mut spatial_entity_map, &mut entity_quadtree);
+                }
               }
             }
           }
@@ -123,16 +104,13 @@ impl<'a> System<'a> for SpatialEntityMapManager {
       for event in marker_event_channel.read(reader) {
         match event {
           MarkerEvent::Hide(ent, _) => {
-            spatial_entity_map.remove_from_all(*ent);
+            spatial_entity_map.remove(ent);
+            entity_quadtree.remove(ent);
           }
           MarkerEvent::Unhide(ent, _) => {
-            insert_without_geom(
-              ent,
-              &mut spatial_entity_map,
-              &screen_points,
-              &screen_lines,
-              &screen_circles,
-            );
+            if let Some(shape) = shape_of(ent, &virt_points, &virt_lines, &virt_circles, &virt_arcs, &virt_conics) {
+              insert(ent, shape, &mut spatial_entity_map, &mut entity_quadtree);
+            }
           }
           _ => (), // Do nothing otherwise
         }
@@ -141,34 +119,42 @@ impl<'a> System<'a> for SpatialEntityMapManager {
   }
 }
 
-fn insert<'a>(
+/// The virtual shape of the entity, None when it has none or it could not be solved
+fn shape_of<'a>(
   ent: &Entity,
-  spatial_entity_map: &mut SpatialEntityMap,
-  screen_points: &ReadStorage<'a, ScreenPoint>,
-  screen_lines: &ReadStorage<'a, ScreenLine>,
-  screen_circles: &ReadStorage<'a, ScreenCircle>,
-) {
-  if let Some(screen_point) = screen_points.get(*ent) {
-    spatial_entity_map.insert_point(*ent, (*screen_point).into());
-  } else if let Some(screen_line) = screen_lines.get(*ent) {
-    spatial_entity_map.insert_line(*ent, (*screen_line).into());
-  } else if let Some(screen_circle) = screen_circles.get(*ent) {
-    spatial_entity_map.insert_circle(*ent, (*screen_circle).into());
+  virt_points: &ReadStorage<'a, VirtualPoint>,
+  virt_lines: &ReadStorage<'a, VirtualLine>,
+  virt_circles: &ReadStorage<'a, VirtualCircle>,
+  virt_arcs: &ReadStorage<'a, VirtualArc>,
+  virt_conics: &ReadStorage<'a, VirtualConic>,
+) -> Option<QuadtreeShape> {
+  if let Some(virt_point) = virt_points.get(*ent) {
+    Some(QuadtreeShape::Point((*virt_point).into()))
+  } else if let Some(virt_line) = virt_lines.get(*ent) {
+    Some(QuadtreeShape::Line((*virt_line).into()))
+  } else if let Some(virt_circle) = virt_circles.get(*ent) {
+    Some(QuadtreeShape::Circle((*virt_circle).into()))
+  } else if let Some(virt_arc) = virt_arcs.get(*ent) {
+    Some(QuadtreeShape::Arc((*virt_arc).into()))
+  } else {
+    virt_conics
+      .get(*ent)
+      .map(|virt_conic| QuadtreeShape::Conic((*virt_conic).into()))
   }
 }
 
-fn insert_without_geom<'a>(
+fn insert(
   ent: &Entity,
+  shape: QuadtreeShape,
   spatial_entity_map: &mut SpatialEntityMap,
-  screen_points: &ReadStorage<'a, ScreenPoint>,
-  screen_lines: &ReadStorage<'a, ScreenLine>,
-  screen_circles: &ReadStorage<'a, ScreenCircle>,
+  entity_quadtree: &mut EntityQuadtree,
 ) {
-  if let Some(screen_point) = screen_points.get(*ent) {
-    spatial_entity_map.insert_point(*ent, (*screen_point).into());
-  } else if let Some(screen_line) = screen_lines.get(*ent) {
-    spatial_entity_map.insert_line(*ent, (*screen_line).into());
-  } else if let Some(screen_circle) = screen_circles.get(*ent) {
-    spatial_entity_map.insert_circle(*ent, (*screen_circle).into());
+  match shape {
+    QuadtreeShape::Point(p) => spatial_entity_map.insert_point(*ent, p),
+    QuadtreeShape::Line(l) => spatial_entity_map.insert_line(*ent, l),
+    QuadtreeShape::Circle(c) => spatial_entity_map.insert_circle(*ent, c),
+    QuadtreeShape::Arc(a) => spatial_entity_map.insert_arc(*ent, a),
+    QuadtreeShape::Conic(e) => spatial_entity_map.insert_conic(*ent, e),
   }
+  entity_quadtree.insert(*ent, shape);
 }
@@ -0,0 +1,134 @@
+use crate::{
+  components::{markers::*, virtual_shapes::*},
+  events::*,
+  resources::*,
+};
+use specs::prelude::*;
+
+/// Records the solved positions of the traced points every frame while a point is dragged
+pub struct TraceCollectorSystem {
+  command_event_reader: Option<CommandEventReader>,
+  dragging: bool,
+}
+
+impl Default for TraceCollectorSystem {
+  fn default() -> Self {
+    Self {
+      command_event_reader: None,
+      dragging: false,
+    }
+  }
+}
+
+impl<'a> System<'a> for TraceCollectorSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CommandEventChannel>,
+    Write<'a, TraceStore>,
+    ReadStorage<'a, Traced>,
+    ReadStorage<'a, VirtualPoint>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.command_event_reader = Some(world.fetch_mut::<CommandEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (entities, command_event_channel, mut trace_store, traceds, virt_points): Self::SystemData) {
+    let mut drag_ended = false;
+    if let Some(reader) = &mut self.command_event_reader {
+      for event in command_event_channel.read(reader) {
+        match event.command {
+          Command::Update(UpdateEvent::UpdatePoint(_, _, _)) => self.dragging = true,
+          Command::Update(UpdateEvent::UpdatePointEnd(_, _, _)) => drag_ended = true,
+          _ => (),
+        }
+      }
+    }
+
+    // The positions are already solved for this frame, including the end of the drag
+    if self.dragging {
+      for (ent, virt_point, _) in (&entities, &virt_points, &traceds).join() {
+        trace_store.record(ent, *virt_point);
+      }
+    }
+    if drag_ended && self.dragging {
+      self.dragging = false;
+      trace_store.end_polylines();
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, math::*, test_utils::*};
+
+  fn traces(world: &World) -> Vec<Vec<(f64, f64)>> {
+    world
+      .read_resource::<TraceStore>()
+      .polylines()
+      .filter(|(_, polyline)| !polyline.is_empty())
+      .map(|(_, polyline)| polyline.iter().map(|p| (p.0.x, p.0.y)).collect())
+      .collect()
+  }
+
+  #[test]
+  fn test_trace_of_mid_point_while_dragging() {
+    let (mut world, mut dispatcher) = headless();
+
+    let a_start = SymbolicPoint::Free(vec2![0., 0.].into());
+    let a = insert_point(&mut world, &mut dispatcher, a_start);
+    let b_sym = SymbolicPoint::Free(vec2![4., 0.].into());
+    let b = insert_point(&mut world, &mut dispatcher, b_sym);
+    let mid = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, b));
+    step(&mut world, &mut dispatcher, Command::Trace(TraceEvent::Toggle(mid)));
+
+    // Nothing is recorded until a point gets dragged
+    assert!(traces(&world).is_empty());
+    let mut old = a_start;
+    for y in &[2., 4.] {
+      let new = SymbolicPoint::Free(vec2![0., *y].into());
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::Update(UpdateEvent::UpdatePoint(a, old, new)),
+      );
+      old = new;
+    }
+    dispatcher.dispatch(&world); // The point did not move this frame
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePointEnd(a, a_start, old)),
+    );
+    dispatcher.dispatch(&world); // The drag is over
+    assert_eq!(traces(&world), vec![vec![(2., 1.), (2., 2.)]]);
+
+    // Another drag starts another polyline
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(a, old, a_start)),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePointEnd(a, old, a_start)),
+    );
+    assert_eq!(traces(&world).len(), 2);
+
+    step(&mut world, &mut dispatcher, Command::Trace(TraceEvent::Clear));
+    assert!(traces(&world).is_empty());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(a, a_start, old)),
+    );
+    assert_eq!(traces(&world), vec![vec![(2., 2.)]]);
+
+    // Untracing the point drops its trace
+    step(&mut world, &mut dispatcher, Command::Trace(TraceEvent::Toggle(mid)));
+    assert!(traces(&world).is_empty());
+  }
+}
@@ -0,0 +1,52 @@
+use crate::{events::*, resources::*};
+use specs::prelude::*;
+
+pub struct ViewportHistoryManager {
+  viewport_event_reader: Option<ViewportEventReader>,
+  last_viewport: Option<Viewport>,
+}
+
+impl Default for ViewportHistoryManager {
+  fn default() -> Self {
+    Self {
+      viewport_event_reader: None,
+      last_viewport: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ViewportHistoryManager {
+  type SystemData = (
+    Read<'a, ViewportEventChannel>,
+    Read<'a, Viewport>,
+    Read<'a, History>,
+    Write<'a, ViewportHistory>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.viewport_event_reader = Some(world.fetch_mut::<ViewportEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (viewport_event_channel, viewport, history, mut viewport_history): Self::SystemData) {
+    if let Some(reader) = &mut self.viewport_event_reader {
+      let mut changed = false;
+      for event in viewport_event_channel.read(reader) {
+        match event {
+          ViewportEvent::Move(_) | ViewportEvent::Scale(_) | ViewportEvent::Zoom(_, _) => changed = true,
+          ViewportEvent::Resize(_) | ViewportEvent::Restore(_) => (),
+        }
+      }
+
+      // A change spanning multiple frames is only settled once a frame passes without change
+      if changed {
+        if let Some(last_viewport) = self.last_viewport {
+          viewport_history.record_change(last_viewport);
+        }
+      } else {
+        viewport_history.settle(*viewport, history.cursor());
+      }
+    }
+    self.last_viewport = Some(*viewport);
+  }
+}
@@ -1,4 +1,9 @@
-use crate::{components::symbolics::*, events::*, resources::*, utilities::*};
+use crate::{
+  components::{symbolics::*, underlays::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
 use specs::prelude::*;
 use std::collections::{HashMap, HashSet};
 
@@ -18,7 +23,10 @@ impl<'a> System<'a> for HistoryEventHandler {
   type SystemData = (
     Read<'a, HistoryEventChannel>,
     Write<'a, CommandEventChannel>,
+    Write<'a, ViewportEventChannel>,
     Write<'a, History>,
+    Write<'a, ViewportHistory>,
+    Read<'a, Viewport>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -26,36 +34,57 @@ impl<'a> System<'a> for HistoryEventHandler {
     self.history_event_reader = Some(world.fetch_mut::<HistoryEventChannel>().register_reader());
   }
 
-  fn run(&mut self, (history_event_channel, mut command_event_channel, mut history): Self::SystemData) {
+  fn run(
+    &mut self,
+    (
+      history_event_channel,
+      mut command_event_channel,
+      mut viewport_event_channel,
+      mut history,
+      mut viewport_history,
+      viewport,
+    ): Self::SystemData,
+  ) {
     if let Some(reader) = &mut self.history_event_reader {
       for event in history_event_channel.read(reader) {
         match event {
           HistoryEvent::Clear => {
             history.clear();
+            viewport_history.clear();
           }
           HistoryEvent::Undo => {
-            if let Some(modification) = history.undo() {
-              match modification {
-                Modification::InsertMany(insertions) => write_remove_events(&mut command_event_channel, insertions),
-                Modification::RemoveMany(removals) => write_insert_events(&mut command_event_channel, removals),
-                Modification::UpdatePoint(ent, old_sym_point, new_sym_point) => {
-                  write_update_event(&mut command_event_channel, ent, new_sym_point, old_sym_point)
-                }
-                Modification::HideMany(unhidden_ents) => write_unhide_events(&mut command_event_channel, unhidden_ents),
-                Modification::UnhideMany(hidden_ents) => write_hide_events(&mut command_event_channel, hidden_ents),
+            if viewport_history.can_undo_at(history.cursor()) {
+              if let Some(restored) = viewport_history.undo(*viewport) {
+                viewport_event_channel.single_write(ViewportEvent::Restore(restored));
               }
+            } else if let Some(modification) = history.undo() {
+              write_undo_events(&mut command_event_channel, modification);
             }
           }
           HistoryEvent::Redo => {
-            if let Some(modification) = history.redo() {
-              match modification {
-                Modification::InsertMany(insertions) => write_insert_events(&mut command_event_channel, insertions),
-                Modification::RemoveMany(removals) => write_remove_events(&mut command_event_channel, removals),
-                Modification::UpdatePoint(ent, old_sym_point, new_sym_point) => {
-                  write_update_event(&mut command_event_channel, ent, old_sym_point, new_sym_point)
+            if viewport_history.can_redo_at(history.cursor()) {
+              if let Some(restored) = viewport_history.redo(*viewport) {
+                viewport_event_channel.single_write(ViewportEvent::Restore(restored));
+              }
+            } else if let Some(modification) = history.redo() {
+              write_redo_events(&mut command_event_channel, modification);
+            }
+          }
+          HistoryEvent::ToggleViewportHistory => {
+            let enabled = viewport_history.is_enabled();
+            viewport_history.set_enabled(!enabled);
+          }
+          HistoryEvent::JumpTo(node) => {
+            if let Some((undone, redone)) = history.jump_to(*node) {
+              for node in undone {
+                if let Some(modification) = history.modification(node) {
+                  write_undo_events(&mut command_event_channel, modification);
+                }
+              }
+              for node in redone {
+                if let Some(modification) = history.modification(node) {
+                  write_redo_events(&mut command_event_channel, modification);
                 }
-                Modification::HideMany(unhidden_ents) => write_hide_events(&mut command_event_channel, unhidden_ents),
-                Modification::UnhideMany(hidden_ents) => write_unhide_events(&mut command_event_channel, hidden_ents),
               }
             }
           }
@@ -65,6 +94,81 @@ impl<'a> System<'a> for HistoryEventHandler {
   }
 }
 
+fn write_undo_events(command_event_channel: &mut CommandEventChannel, modification: &Modification) {
+  match modification {
+    Modification::InsertMany(insertions) => write_remove_events(command_event_channel, insertions),
+    Modification::RemoveMany(removals) => write_insert_events(command_event_channel, removals),
+    Modification::UpdatePointMany(updates) => {
+      for (ent, (old_sym_point, new_sym_point)) in updates {
+        write_update_event(command_event_channel, ent, new_sym_point, old_sym_point)
+      }
+    }
+    Modification::UpdateLine(ent, old_sym_line, new_sym_line) => {
+      write_update_line_event(command_event_channel, ent, new_sym_line, old_sym_line)
+    }
+    Modification::UpdateSlider(ent, old_value, new_value) => {
+      write_update_slider_event(command_event_channel, ent, *new_value, *old_value)
+    }
+    Modification::UpdateUnderlay(ent, old_placement, new_placement) => {
+      write_update_underlay_event(command_event_channel, ent, *new_placement, *old_placement)
+    }
+    Modification::HideMany(unhidden_ents) => write_unhide_events(command_event_channel, unhidden_ents),
+    Modification::UnhideMany(hidden_ents) => write_hide_events(command_event_channel, hidden_ents),
+    Modification::RestyleMany(restyles) => {
+      for (ent, (old_style, _)) in restyles {
+        write_restyle_event(command_event_channel, ent, old_style)
+      }
+    }
+    Modification::RenameMany(renames) => write_rename_events(
+      command_event_channel,
+      renames.iter().map(|(ent, (old_name, _))| (ent, old_name)),
+    ),
+    Modification::Transaction(_, modifications) => {
+      // Undone from the last modification back to the first
+      for modification in modifications.iter().rev() {
+        write_undo_events(command_event_channel, modification);
+      }
+    }
+  }
+}
+
+fn write_redo_events(command_event_channel: &mut CommandEventChannel, modification: &Modification) {
+  match modification {
+    Modification::InsertMany(insertions) => write_insert_events(command_event_channel, insertions),
+    Modification::RemoveMany(removals) => write_remove_events(command_event_channel, removals),
+    Modification::UpdatePointMany(updates) => {
+      for (ent, (old_sym_point, new_sym_point)) in updates {
+        write_update_event(command_event_channel, ent, old_sym_point, new_sym_point)
+      }
+    }
+    Modification::UpdateLine(ent, old_sym_line, new_sym_line) => {
+      write_update_line_event(command_event_channel, ent, old_sym_line, new_sym_line)
+    }
+    Modification::UpdateSlider(ent, old_value, new_value) => {
+      write_update_slider_event(command_event_channel, ent, *old_value, *new_value)
+    }
+    Modification::UpdateUnderlay(ent, old_placement, new_placement) => {
+      write_update_underlay_event(command_event_channel, ent, *old_placement, *new_placement)
+    }
+    Modification::HideMany(unhidden_ents) => write_hide_events(command_event_channel, unhidden_ents),
+    Modification::UnhideMany(hidden_ents) => write_unhide_events(command_event_channel, hidden_ents),
+    Modification::RestyleMany(restyles) => {
+      for (ent, (_, new_style)) in restyles {
+        write_restyle_event(command_event_channel, ent, new_style)
+      }
+    }
+    Modification::RenameMany(renames) => write_rename_events(
+      command_event_channel,
+      renames.iter().map(|(ent, (_, new_name))| (ent, new_name)),
+    ),
+    Modification::Transaction(_, modifications) => {
+      for modification in modifications {
+        write_redo_events(command_event_channel, modification);
+      }
+    }
+  }
+}
+
 fn write_remove_events(command_event_channel: &mut CommandEventChannel, entities: &HashMap<Entity, Geometry>) {
   for (entity, _) in entities {
     command_event_channel.single_write(CommandEvent {
@@ -76,25 +180,10 @@ fn write_remove_events(command_event_channel: &mut CommandEventChannel, entities
 
 fn write_insert_events(command_event_channel: &mut CommandEventChannel, entities: &HashMap<Entity, Geometry>) {
   for (ent, geometry) in entities {
-    let command = match geometry {
-      Geometry::Point(sym_point, point_style) => CommandEvent {
-        command: Command::PointInsert(InsertPointEvent::InsertPointByHistory(*ent, *sym_point, *point_style)),
-        event_id: None,
-      },
-      Geometry::Line(sym_line, line_style) => CommandEvent {
-        command: Command::LineInsert(InsertLineEvent::InsertLineByHistory(*ent, *sym_line, *line_style)),
-        event_id: None,
-      },
-      Geometry::Circle(sym_circle, circle_style) => CommandEvent {
-        command: Command::CircleInsert(InsertCircleEvent::InsertCircleByHistory(
-          *ent,
-          *sym_circle,
-          *circle_style,
-        )),
-        event_id: None,
-      },
-    };
-    command_event_channel.single_write(command);
+    command_event_channel.single_write(CommandEvent {
+      command: Command::insert_by_history(*ent, geometry),
+      event_id: None,
+    });
   }
 }
 
@@ -112,6 +201,42 @@ fn write_update_event(
   });
 }
 
+fn write_update_line_event(
+  command_event_channel: &mut CommandEventChannel,
+  ent: &Entity,
+  old_sym_line: &SymbolicLine,
+  new_sym_line: &SymbolicLine,
+) {
+  command_event_channel.single_write(CommandEvent {
+    command: Command::Update(UpdateEvent::UpdateLineByHistory(*ent, *old_sym_line, *new_sym_line)),
+    event_id: None,
+  });
+}
+
+fn write_update_slider_event(
+  command_event_channel: &mut CommandEventChannel,
+  ent: &Entity,
+  old_value: f64,
+  new_value: f64,
+) {
+  command_event_channel.single_write(CommandEvent {
+    command: Command::Update(UpdateEvent::UpdateSliderByHistory(*ent, old_value, new_value)),
+    event_id: None,
+  });
+}
+
+fn write_update_underlay_event(
+  command_event_channel: &mut CommandEventChannel,
+  ent: &Entity,
+  old_placement: UnderlayPlacement,
+  new_placement: UnderlayPlacement,
+) {
+  command_event_channel.single_write(CommandEvent {
+    command: Command::Update(UpdateEvent::UpdateUnderlayByHistory(*ent, old_placement, new_placement)),
+    event_id: None,
+  });
+}
+
 fn write_hide_events(command_event_channel: &mut CommandEventChannel, entities: &HashSet<Entity>) {
   for entity in entities {
     command_event_channel.single_write(CommandEvent {
@@ -129,3 +254,75 @@ fn write_unhide_events(command_event_channel: &mut CommandEventChannel, entities
     });
   }
 }
+
+fn write_restyle_event(command_event_channel: &mut CommandEventChannel, ent: &Entity, style: &Style) {
+  command_event_channel.single_write(CommandEvent {
+    command: Command::Restyle(RestyleEvent::RestyleByHistory(*ent, *style)),
+    event_id: None,
+  });
+}
+
+/// Names are unique, so they are all taken away before being given, in case the entities swap
+/// their names
+fn write_rename_events<'a, I: Iterator<Item = (&'a Entity, &'a Option<String>)> + Clone>(
+  command_event_channel: &mut CommandEventChannel,
+  renames: I,
+) {
+  for (ent, _) in renames.clone() {
+    command_event_channel.single_write(CommandEvent {
+      command: Command::Rename(RenameEvent::RenameByHistory(*ent, None)),
+      event_id: None,
+    });
+  }
+  for (ent, name) in renames {
+    if name.is_some() {
+      command_event_channel.single_write(CommandEvent {
+        command: Command::Rename(RenameEvent::RenameByHistory(*ent, name.clone())),
+        event_id: None,
+      });
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+
+  fn pan(world: &mut World, dispatcher: &mut Dispatcher, movement: Vector2) {
+    world
+      .fetch_mut::<ViewportEventChannel>()
+      .single_write(ViewportEvent::Move(movement));
+    dispatcher.dispatch(world);
+    world.maintain();
+    // The pan is only settled once a frame passes without change
+    dispatcher.dispatch(world);
+    world.maintain();
+  }
+
+  #[test]
+  fn test_toggle_viewport_history() {
+    let (mut world, mut dispatcher) = headless();
+    dispatcher.dispatch(&world);
+    let center = world.fetch::<Viewport>().virtual_center;
+
+    // Off by default, undoing leaves the pan alone
+    pan(&mut world, &mut dispatcher, vec2![1., 0.]);
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(world.fetch::<Viewport>().virtual_center, center + vec2![1., 0.]);
+
+    history(&mut world, &mut dispatcher, HistoryEvent::ToggleViewportHistory);
+    assert!(world.fetch::<ViewportHistory>().is_enabled());
+    pan(&mut world, &mut dispatcher, vec2![0., 2.]);
+    assert_eq!(world.fetch::<Viewport>().virtual_center, center + vec2![1., 2.]);
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(world.fetch::<Viewport>().virtual_center, center + vec2![1., 0.]);
+    history(&mut world, &mut dispatcher, HistoryEvent::Redo);
+    assert_eq!(world.fetch::<Viewport>().virtual_center, center + vec2![1., 2.]);
+
+    history(&mut world, &mut dispatcher, HistoryEvent::ToggleViewportHistory);
+    assert!(!world.fetch::<ViewportHistory>().is_enabled());
+    history(&mut world, &mut dispatcher, HistoryEvent::Undo);
+    assert_eq!(world.fetch::<Viewport>().virtual_center, center + vec2![1., 2.]);
+  }
+}
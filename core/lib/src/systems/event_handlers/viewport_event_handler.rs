@@ -32,9 +32,16 @@ impl<'a> System<'a> for ViewportEventHandler {
             let new_x = viewport.virtual_size.x + rel_diff;
             viewport.set_virtual_size_x(new_x);
           }
+          ViewportEvent::Zoom(factor, anchor) => {
+            viewport.zoom(*factor, *anchor);
+          }
           ViewportEvent::Resize(scrn_size) => {
             viewport.set_screen_size(*scrn_size);
           }
+          ViewportEvent::Restore(restored) => {
+            viewport.virtual_center = restored.virtual_center;
+            viewport.set_virtual_size_x(restored.virtual_width());
+          }
         }
       }
     }
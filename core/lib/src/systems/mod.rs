@@ -1,4 +1,9 @@
+#[macro_use]
+mod storages;
+
 pub mod command_handlers;
 pub mod data_managers;
 pub mod event_handlers;
+#[cfg(feature = "scripting")]
+pub mod scripting;
 pub mod solvers;
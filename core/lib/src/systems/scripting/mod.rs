@@ -0,0 +1,3 @@
+mod script_system;
+
+pub use script_system::*;
@@ -0,0 +1,297 @@
+use crate::{
+  components::{symbolics::*, virtual_shapes::*},
+  events::*,
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use mlua::{Function, Lua};
+use specs::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// What a script did during one call, only applied to the world once the call returns
+#[derive(Default)]
+struct ScriptEffects {
+  created: Vec<Entity>,
+  names: HashMap<String, Entity>,
+  insertions: Vec<(Entity, Geometry)>,
+  moves: HashMap<Entity, (SymbolicPoint, SymbolicPoint)>, // Entity to old, new
+}
+
+/// Runs a Lua script against the sketch. The body of the script runs once on the first frame,
+/// then its global `update(frame)` function, when it defines one, on every frame after that.
+/// Scripts see the points through their names and get the following functions:
+///
+/// - `point(name)`, the `x, y` position of the named point or `nil`
+/// - `insert_point(x, y, [name])`, a fixed point at the given virtual position
+/// - `insert_line(from, to, [name])`, a straight line through the two named points
+/// - `move_point(name, x, y)`, only for fixed points
+///
+/// The insertions of one call are undone in a single step, the moves are not undoable, the same as
+/// animations. A script raising an error stops running. Not part of the core lib systems, the apps
+/// add it as a thread local system when given a script
+pub struct ScriptSystem {
+  lua: Lua,
+  name: String, // Of the script, for the error messages
+  source: String,
+  frame: usize,
+  stopped: bool,
+}
+
+impl ScriptSystem {
+  pub fn new(name: String, source: String) -> Self {
+    Self {
+      lua: Lua::new(),
+      name,
+      source,
+      frame: 0,
+      stopped: false,
+    }
+  }
+
+  fn call(&self) -> mlua::Result<()> {
+    if self.frame == 0 {
+      self.lua.load(&self.source).set_name(self.name.as_str()).exec()
+    } else {
+      match self.lua.globals().get::<_, Option<Function>>("update")? {
+        Some(update) => update.call(self.frame),
+        None => Ok(()),
+      }
+    }
+  }
+}
+
+impl<'a> System<'a> for ScriptSystem {
+  type SystemData = (
+    Entities<'a>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Write<'a, History>,
+    Read<'a, Names>,
+    Read<'a, DefaultPointStyle>,
+    Read<'a, DefaultLineStyle>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, VirtualPoint>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      mut command_event_channel,
+      mut error_event_channel,
+      mut history,
+      names,
+      default_point_style,
+      default_line_style,
+      sym_points,
+      virt_points,
+    ): Self::SystemData,
+  ) {
+    if self.stopped {
+      return;
+    }
+
+    let effects = RefCell::new(ScriptEffects::default());
+    let point_named = |name: &str| -> mlua::Result<Entity> {
+      let effects = effects.borrow();
+      let ent = effects.names.get(name).cloned().or_else(|| names.by_name(name));
+      let is_point = |ent: Entity| {
+        sym_points.contains(ent)
+          || effects
+            .insertions
+            .iter()
+            .any(|(other, geometry)| *other == ent && matches!(geometry, Geometry::Point(_, _)))
+      };
+      match ent {
+        Some(ent) if is_point(ent) => Ok(ent),
+        _ => Err(mlua::Error::RuntimeError(format!("no point named {}", name))),
+      }
+    };
+    let symbolic_point = |ent: Entity| -> Option<SymbolicPoint> {
+      let effects = effects.borrow();
+      if let Some((_, new)) = effects.moves.get(&ent) {
+        return Some(*new);
+      }
+      let inserted = effects.insertions.iter().find_map(|(other, geometry)| match geometry {
+        Geometry::Point(sym_point, _) if *other == ent => Some(*sym_point),
+        _ => None,
+      });
+      inserted.or_else(|| sym_points.get(ent).cloned())
+    };
+    let insert = |geometry: Geometry, name: Option<String>| -> mlua::Result<()> {
+      let mut effects = effects.borrow_mut();
+      if let Some(name) = &name {
+        if effects.names.contains_key(name) || names.by_name(name).is_some() {
+          return Err(mlua::Error::RuntimeError(format!("the name {} is already taken", name)));
+        }
+      }
+      let ent = entities.create();
+      effects.created.push(ent);
+      effects.insertions.push((ent, geometry));
+      if let Some(name) = name {
+        effects.names.insert(name, ent);
+      }
+      Ok(())
+    };
+
+    let result = self.lua.scope(|scope| {
+      let globals = self.lua.globals();
+      globals.set(
+        "point",
+        scope.create_function(|_, name: String| {
+          let ent = match point_named(&name) {
+            Ok(ent) => ent,
+            Err(_) => return Ok((None, None)),
+          };
+          // Points inserted or moved during this call are not solved yet, only fixed ones are
+          let position = match symbolic_point(ent) {
+            Some(SymbolicPoint::Fixed(position)) => Some(position.0),
+            _ => virt_points.get(ent).map(|position| position.0),
+          };
+          Ok((position.map(|position| position.x), position.map(|position| position.y)))
+        })?,
+      )?;
+      globals.set(
+        "insert_point",
+        scope.create_function(|_, (x, y, name): (f64, f64, Option<String>)| {
+          let sym_point = SymbolicPoint::Fixed(vec2![x, y].into());
+          insert(Geometry::Point(sym_point, default_point_style.get()), name)
+        })?,
+      )?;
+      globals.set(
+        "insert_line",
+        scope.create_function(|_, (from, to, name): (String, String, Option<String>)| {
+          let sym_line = SymbolicLine::Straight(point_named(&from)?, point_named(&to)?);
+          insert(Geometry::Line(sym_line, default_line_style.get()), name)
+        })?,
+      )?;
+      globals.set(
+        "move_point",
+        scope.create_function(|_, (name, x, y): (String, f64, f64)| {
+          let ent = point_named(&name)?;
+          let new = SymbolicPoint::Fixed(vec2![x, y].into());
+          let mut effects = effects.borrow_mut();
+          // The points inserted by the script are all fixed
+          let inserted = effects.insertions.iter_mut().find(|(other, _)| *other == ent);
+          if let Some((_, Geometry::Point(sym_point, _))) = inserted {
+            *sym_point = new;
+            return Ok(());
+          }
+          let old = match effects.moves.get(&ent) {
+            Some((old, _)) => *old,
+            None => match sym_points.get(ent) {
+              Some(old @ SymbolicPoint::Fixed(_)) => *old,
+              _ => return Err(mlua::Error::RuntimeError(format!("{} is not a fixed point", name))),
+            },
+          };
+          effects.moves.insert(ent, (old, new));
+          Ok(())
+        })?,
+      )?;
+      self.call()
+    });
+
+    let effects = effects.into_inner();
+    self.frame += 1;
+    if let Err(err) = result {
+      for ent in effects.created {
+        if let Err(err) = entities.delete(ent) {
+          panic!(err)
+        }
+      }
+      error_event_channel.single_write(ErrorEvent::Script(err.to_string()));
+      self.stopped = true;
+      return;
+    }
+
+    let mut commands = vec![];
+    for (ent, geometry) in &effects.insertions {
+      commands.push(Command::insert_by_history(*ent, geometry));
+    }
+    for (name, ent) in &effects.names {
+      commands.push(Command::Rename(RenameEvent::RenameByHistory(*ent, Some(name.clone()))));
+    }
+    for (ent, (old, new)) in &effects.moves {
+      commands.push(Command::Update(UpdateEvent::UpdatePoint(*ent, *old, *new)));
+    }
+    for command in commands {
+      command_event_channel.single_write(CommandEvent {
+        command,
+        event_id: None,
+      });
+    }
+
+    if !effects.insertions.is_empty() {
+      let mut modifications = vec![Modification::InsertMany(effects.insertions.into_iter().collect())];
+      if !effects.names.is_empty() {
+        let renames = effects
+          .names
+          .into_iter()
+          .map(|(name, ent)| (ent, (None, Some(name))))
+          .collect();
+        modifications.push(Modification::RenameMany(renames));
+      }
+      history.push(Modification::Transaction("Script".to_string(), modifications));
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn headless<'a, 'b>(source: &str) -> (World, Dispatcher<'a, 'b>) {
+    headless_with(|builder| builder.add_thread_local(ScriptSystem::new("test".to_string(), source.to_string())))
+  }
+
+  fn position(world: &World, name: &str) -> Option<Vector2> {
+    let ent = world.fetch::<Names>().by_name(name)?;
+    world.read_storage::<VirtualPoint>().get(ent).map(|position| position.0)
+  }
+
+  #[test]
+  fn test_script_inserts_and_moves_points() {
+    let source = "
+      insert_point(0, 0, 'A')
+      insert_point(4, 0, 'B')
+      insert_line('A', 'B', 'l')
+      function update(frame)
+        local x, y = point('B')
+        move_point('B', x, y + 1)
+      end
+    ";
+    let (mut world, mut dispatcher) = headless(source);
+    dispatch(&mut world, &mut dispatcher, 3);
+    assert_eq!(position(&world, "A"), Some(vec2![0., 0.]));
+    let b = position(&world, "B").unwrap();
+    assert_eq!(b.x, 4.);
+    assert!(b.y > 0.);
+    let l = world.fetch::<Names>().by_name("l").unwrap();
+    assert!(world.read_storage::<VirtualLine>().contains(l));
+
+    // The insertions are a single undo step
+    assert_eq!(world.fetch::<History>().undo_label(), Some("Script"));
+  }
+
+  #[test]
+  fn test_script_error_stops_the_script() {
+    let source = "
+      insert_point(0, 0, 'A')
+      insert_line('A', 'missing')
+    ";
+    let (mut world, mut dispatcher) = headless(source);
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+    dispatch(&mut world, &mut dispatcher, 2);
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .cloned()
+      .collect::<Vec<_>>();
+    assert!(matches!(&errors[..], [ErrorEvent::Script(message)] if message.contains("no point named missing")));
+    assert!(world.fetch::<Names>().by_name("A").is_none());
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 0);
+  }
+}
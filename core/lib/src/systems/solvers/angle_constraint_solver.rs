@@ -0,0 +1,247 @@
+use crate::{
+  components::{symbolics::*, virtual_shapes::*},
+  events::*,
+  resources::*,
+};
+use specs::prelude::*;
+use std::collections::HashSet;
+
+static ANGLE_TOLERANCE: f64 = 1e-9;
+
+/// Keeps the angle constraints satisfied. `line_b` is the driven line: its second point is
+/// rotated around its first one, or the other way around when only the first point is free.
+/// When `line_b` has no free point `line_a` is driven instead, and when neither has one the
+/// constraint is reported as over constrained.
+///
+/// The driven point is moved with updates that are not finished, so they are never recorded in
+/// the history. Undoing a move of the driving line moves the driven one back through the
+/// constraint.
+pub struct AngleConstraintSolver {
+  over_constrained: HashSet<(Entity, Entity)>,
+}
+
+impl Default for AngleConstraintSolver {
+  fn default() -> Self {
+    Self {
+      over_constrained: HashSet::new(),
+    }
+  }
+}
+
+impl<'a> System<'a> for AngleConstraintSolver {
+  type SystemData = (
+    Read<'a, SolverEnabled>,
+    Read<'a, AngleConstraints>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, VirtualPoint>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      solver_enabled,
+      angle_constraints,
+      mut command_event_channel,
+      mut error_event_channel,
+      sym_points,
+      sym_lines,
+      virt_points,
+    ): Self::SystemData,
+  ) {
+    if !solver_enabled.0 {
+      return;
+    }
+
+    for constraint in angle_constraints.iter() {
+      let (a, b) = match (
+        two_points(constraint.line_a, &sym_lines),
+        two_points(constraint.line_b, &sym_lines),
+      ) {
+        (Some(a), Some(b)) => (a, b),
+        _ => continue,
+      };
+      let direction = |(p1, p2): (Entity, Entity)| match (virt_points.get(p1), virt_points.get(p2)) {
+        (Some(from), Some(to)) => Some((to.0 - from.0).normalized()),
+        _ => None,
+      };
+      let (dir_a, dir_b) = match (direction(a), direction(b)) {
+        (Some(dir_a), Some(dir_b)) => (dir_a, dir_b),
+        _ => continue,
+      };
+
+      let (driven, target) = if free_end(b, &sym_points).is_some() {
+        (b, dir_a.rotate(constraint.radians))
+      } else if free_end(a, &sym_points).is_some() {
+        (a, dir_b.rotate(-constraint.radians))
+      } else {
+        let pair = (constraint.line_a, constraint.line_b);
+        if self.over_constrained.insert(pair) {
+          error_event_channel.single_write(ErrorEvent::OverConstrained(pair.0, pair.1));
+        }
+        continue;
+      };
+      self.over_constrained.remove(&(constraint.line_a, constraint.line_b));
+
+      if let Some((free, anchor, sign)) = free_end(driven, &sym_points) {
+        let (free_position, anchor_position) = match (virt_points.get(free), virt_points.get(anchor)) {
+          (Some(free_position), Some(anchor_position)) => (free_position.0, anchor_position.0),
+          _ => continue,
+        };
+        let length = (free_position - anchor_position).magnitude();
+        let new_position = anchor_position + target * (sign * length);
+        if (new_position - free_position).magnitude() > ANGLE_TOLERANCE {
+          let old_sym_point = sym_points.get(free).unwrap();
+          command_event_channel.single_write(CommandEvent {
+            command: Command::Update(UpdateEvent::UpdatePoint(
+              free,
+              *old_sym_point,
+              SymbolicPoint::Free(new_position.into()),
+            )),
+            event_id: None,
+          });
+        }
+      }
+    }
+  }
+}
+
+/// The two points defining the line, when it is defined by two points
+fn two_points(line: Entity, sym_lines: &ReadStorage<SymbolicLine>) -> Option<(Entity, Entity)> {
+  match sym_lines.get(line) {
+    Some(SymbolicLine::Straight(p1, p2)) | Some(SymbolicLine::Ray(p1, p2)) | Some(SymbolicLine::Segment(p1, p2)) => {
+      Some((*p1, *p2))
+    }
+    _ => None,
+  }
+}
+
+/// The free point to move, the point to move it around, and the sign of the direction from the
+/// latter to the former
+fn free_end((p1, p2): (Entity, Entity), sym_points: &ReadStorage<SymbolicPoint>) -> Option<(Entity, Entity, f64)> {
+  let is_free = |ent: Entity| matches!(sym_points.get(ent), Some(SymbolicPoint::Free(_)));
+  if is_free(p2) {
+    Some((p2, p1, 1.0))
+  } else if is_free(p1) {
+    Some((p1, p2, -1.0))
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{math::*, test_utils::*};
+  use std::f64::consts::FRAC_PI_3;
+
+  fn insert_line(world: &mut World, dispatcher: &mut Dispatcher, p1: SymbolicPoint, p2: SymbolicPoint) -> Entity {
+    let p1 = insert_point(world, dispatcher, p1);
+    let p2 = insert_point(world, dispatcher, p2);
+    step(
+      world,
+      dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, p2))),
+    );
+    last_inserted::<SymbolicLine>(world)
+  }
+
+  fn angle(world: &World, line_a: Entity, line_b: Entity) -> f64 {
+    let virt_lines = world.read_storage::<VirtualLine>();
+    let direction = |line: Entity| {
+      let line = virt_lines.get(line).unwrap();
+      line.to.0 - line.from.0
+    };
+    let (dir_a, dir_b) = (direction(line_a), direction(line_b));
+    (dir_a.x * dir_b.y - dir_a.y * dir_b.x).atan2(dir_a.x * dir_b.x + dir_a.y * dir_b.y)
+  }
+
+  #[test]
+  fn test_moving_line_a_keeps_angle() {
+    let (mut world, mut dispatcher) = headless();
+
+    let line_a = insert_line(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::Fixed(vec2![0., 0.].into()),
+      SymbolicPoint::Free(vec2![2., 0.].into()),
+    );
+    let line_b = insert_line(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::Fixed(vec2![3., 3.].into()),
+      SymbolicPoint::Free(vec2![5., 3.].into()),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ConstrainAngle(AngleConstraint {
+        line_a,
+        line_b,
+        radians: FRAC_PI_3,
+      }),
+    );
+    // The driven point is updated on the next frame
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert!((angle(&world, line_a, line_b) - FRAC_PI_3).abs() < 1e-9);
+
+    // Drag the free point of line_a around
+    let a_end = match world.read_storage::<SymbolicLine>().get(line_a) {
+      Some(SymbolicLine::Segment(_, p2)) => *p2,
+      _ => panic!("Expected a segment"),
+    };
+    let old = *world.read_storage::<SymbolicPoint>().get(a_end).unwrap();
+    let new = SymbolicPoint::Free(vec2![-1., 2.].into());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(a_end, old, new)),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePointEnd(a_end, old, new)),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert!((angle(&world, line_a, line_b) - FRAC_PI_3).abs() < 1e-9);
+
+    // line_b keeps its anchor and its length
+    let virt_lines = world.read_storage::<VirtualLine>();
+    let b = virt_lines.get(line_b).unwrap();
+    assert_eq!(b.from.0, vec2![3., 3.]);
+    assert!(((b.to.0 - b.from.0).magnitude() - 2.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_over_constrained_is_reported() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+
+    let fixed = |x: f64, y: f64| SymbolicPoint::Fixed(vec2![x, y].into());
+    let line_a = insert_line(&mut world, &mut dispatcher, fixed(0., 0.), fixed(1., 0.));
+    let line_b = insert_line(&mut world, &mut dispatcher, fixed(0., 1.), fixed(1., 1.));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::ConstrainAngle(AngleConstraint {
+        line_a,
+        line_b,
+        radians: FRAC_PI_3,
+      }),
+    );
+    // Reported once, not on every frame
+    dispatcher.dispatch(&world);
+    world.maintain();
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter(|event| matches!(event, ErrorEvent::OverConstrained(a, b) if (*a, *b) == (line_a, line_b)))
+      .count();
+    assert_eq!(errors, 1);
+    assert!(angle(&world, line_a, line_b).abs() < 1e-9);
+  }
+}
@@ -0,0 +1,351 @@
+use crate::{
+  components::{symbolics::*, virtual_shapes::*},
+  events::*,
+  math::*,
+  resources::*,
+};
+use specs::prelude::*;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+static MAX_ITERATIONS: usize = 100;
+static TOLERANCE: f64 = 1e-9;
+static UNSATISFIABLE_TOLERANCE: f64 = 1e-6; // What is left of a constraint once the iterations are over
+
+/// Keeps the constraints satisfied by relaxation, after the symbolic solver: every iteration
+/// satisfies each constraint in turn by moving its free points, until they are all satisfied at
+/// once. Only the free points are moved, a point depending on others is only moved along with
+/// them. A constraint left unsatisfied, e.g. when none of its points is free, is reported once.
+///
+/// The moved points are updated with updates that are not finished, so they are never recorded in
+/// the history, just like the ones of the angle constraints.
+pub struct ConstraintSolverSystem {
+  unsatisfiable: Vec<Constraint>,
+}
+
+impl Default for ConstraintSolverSystem {
+  fn default() -> Self {
+    Self { unsatisfiable: vec![] }
+  }
+}
+
+impl<'a> System<'a> for ConstraintSolverSystem {
+  type SystemData = (
+    Read<'a, SolverEnabled>,
+    Read<'a, Constraints>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, VirtualPoint>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      solver_enabled,
+      constraints,
+      mut command_event_channel,
+      mut error_event_channel,
+      sym_points,
+      sym_lines,
+      virt_points,
+    ): Self::SystemData,
+  ) {
+    if !solver_enabled.0 || constraints.is_empty() {
+      return;
+    }
+
+    // The constraints whose geometries are all there, with the points they are made of
+    let mut positions = HashMap::new();
+    let mut solvable = vec![];
+    for constraint in constraints.iter() {
+      let (a, b) = constraint.entities();
+      let points = match constraint {
+        Constraint::FixedDistance(_, _, _) | Constraint::Coincident(_, _) => vec![a, b],
+        Constraint::FixedAngle(_, _, _) | Constraint::Parallel(_, _) => {
+          match (two_points(a, &sym_lines), two_points(b, &sym_lines)) {
+            (Some((p1, p2)), Some((q1, q2))) => vec![p1, p2, q1, q2],
+            _ => continue,
+          }
+        }
+      };
+      if points.iter().any(|p| virt_points.get(*p).is_none()) {
+        continue;
+      }
+      for p in &points {
+        positions.insert(*p, virt_points.get(*p).unwrap().0);
+      }
+      solvable.push((*constraint, points));
+    }
+
+    let is_free = |ent: Entity| matches!(sym_points.get(ent), Some(SymbolicPoint::Free(_)));
+    for _ in 0..MAX_ITERATIONS {
+      let mut worst = 0.0f64;
+      for (constraint, points) in &solvable {
+        worst = worst.max(relax(constraint, points, &mut positions, &is_free));
+      }
+      if worst < TOLERANCE {
+        break;
+      }
+    }
+
+    for (ent, position) in &positions {
+      let old_position = virt_points.get(*ent).unwrap().0;
+      if is_free(*ent) && (*position - old_position).magnitude() > TOLERANCE {
+        command_event_channel.single_write(CommandEvent {
+          command: Command::Update(UpdateEvent::UpdatePoint(
+            *ent,
+            *sym_points.get(*ent).unwrap(),
+            SymbolicPoint::Free((*position).into()),
+          )),
+          event_id: None,
+        });
+      }
+    }
+
+    for (constraint, points) in &solvable {
+      let satisfied = residual(constraint, points, &positions) < UNSATISFIABLE_TOLERANCE;
+      let reported = self.unsatisfiable.contains(constraint);
+      if satisfied && reported {
+        self.unsatisfiable.retain(|c| c != constraint);
+      } else if !satisfied && !reported {
+        self.unsatisfiable.push(*constraint);
+        error_event_channel.single_write(ErrorEvent::Unsatisfiable(*constraint));
+      }
+    }
+  }
+}
+
+/// The two points defining the line, when it is defined by two points
+fn two_points(line: Entity, sym_lines: &ReadStorage<SymbolicLine>) -> Option<(Entity, Entity)> {
+  match sym_lines.get(line) {
+    Some(SymbolicLine::Straight(p1, p2)) | Some(SymbolicLine::Ray(p1, p2)) | Some(SymbolicLine::Segment(p1, p2)) => {
+      Some((*p1, *p2))
+    }
+    _ => None,
+  }
+}
+
+/// The angle in `[-period / 2, period / 2]` equal to `radians` up to a multiple of `period`
+fn wrap(radians: f64, period: f64) -> f64 {
+  radians - period * (radians / period).round()
+}
+
+/// How far the points are from satisfying the constraint, as a distance or as an angle
+fn residual(constraint: &Constraint, points: &[Entity], positions: &HashMap<Entity, Vector2>) -> f64 {
+  let position = |i: usize| positions[&points[i]];
+  match constraint {
+    Constraint::FixedDistance(_, _, distance) => ((position(1) - position(0)).magnitude() - distance).abs(),
+    Constraint::Coincident(_, _) => (position(1) - position(0)).magnitude(),
+    Constraint::FixedAngle(_, _, _) | Constraint::Parallel(_, _) => {
+      angle_error(constraint, position(1) - position(0), position(3) - position(2)).abs()
+    }
+  }
+}
+
+/// How much the angle from `dir_a` to `dir_b` exceeds the one of the constraint
+fn angle_error(constraint: &Constraint, dir_a: Vector2, dir_b: Vector2) -> f64 {
+  if dir_a.is_zero() || dir_b.is_zero() {
+    return 0.0;
+  }
+  let angle = (dir_a.x * dir_b.y - dir_a.y * dir_b.x).atan2(dir_a.x * dir_b.x + dir_a.y * dir_b.y);
+  match constraint {
+    Constraint::FixedAngle(_, _, radians) => wrap(angle - radians, 2.0 * PI),
+    _ => wrap(angle, PI), // Either direction is parallel
+  }
+}
+
+/// Moves the free points of the constraint so that it gets satisfied, and returns how far they
+/// were from satisfying it
+fn relax<F: Fn(Entity) -> bool>(
+  constraint: &Constraint,
+  points: &[Entity],
+  positions: &mut HashMap<Entity, Vector2>,
+  is_free: &F,
+) -> f64 {
+  let error = residual(constraint, points, positions);
+  if error < TOLERANCE {
+    return error;
+  }
+  match constraint {
+    Constraint::FixedDistance(_, _, _) | Constraint::Coincident(_, _) => {
+      let distance = match constraint {
+        Constraint::FixedDistance(_, _, distance) => *distance,
+        _ => 0.0,
+      };
+      let (a, b) = (points[0], points[1]);
+      let delta = positions[&b] - positions[&a];
+      let direction = if delta.magnitude() < TOLERANCE {
+        vec2![1., 0.]
+      } else {
+        delta.normalized()
+      };
+      let correction = direction * (delta.magnitude() - distance);
+      // The free points share the correction
+      let free = [a, b].iter().filter(|p| is_free(**p)).count();
+      if free == 0 {
+        return error;
+      }
+      if is_free(a) {
+        *positions.get_mut(&a).unwrap() = positions[&a] + correction / free as f64;
+      }
+      if is_free(b) {
+        *positions.get_mut(&b).unwrap() = positions[&b] - correction / free as f64;
+      }
+    }
+    Constraint::FixedAngle(_, _, _) | Constraint::Parallel(_, _) => {
+      let direction =
+        |positions: &HashMap<Entity, Vector2>, i: usize| positions[&points[i + 1]] - positions[&points[i]];
+      let angle = angle_error(constraint, direction(positions, 0), direction(positions, 2));
+      // The lines with a free point share the rotation
+      let movable = |i: usize| is_free(points[i]) || is_free(points[i + 1]);
+      let lines = [0, 2].iter().filter(|i| movable(**i)).count();
+      if lines == 0 {
+        return error;
+      }
+      if movable(0) {
+        rotate(points[0], points[1], angle / lines as f64, positions, is_free);
+      }
+      if movable(2) {
+        rotate(points[2], points[3], -angle / lines as f64, positions, is_free);
+      }
+    }
+  }
+  error
+}
+
+/// Rotates the line counter clockwise around its point that is not free, or around its middle when
+/// both are
+fn rotate<F: Fn(Entity) -> bool>(
+  p1: Entity,
+  p2: Entity,
+  radians: f64,
+  positions: &mut HashMap<Entity, Vector2>,
+  is_free: &F,
+) {
+  let pivot = match (is_free(p1), is_free(p2)) {
+    (true, true) => (positions[&p1] + positions[&p2]) / 2.0,
+    (true, false) => positions[&p2],
+    (false, true) => positions[&p1],
+    (false, false) => return,
+  };
+  for p in &[p1, p2] {
+    if is_free(*p) {
+      *positions.get_mut(p).unwrap() = pivot + (positions[p] - pivot).rotate(radians);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::test_utils::*;
+  use std::f64::consts::FRAC_PI_2;
+
+  fn insert_segment(world: &mut World, dispatcher: &mut Dispatcher, p1: Entity, p2: Entity) -> Entity {
+    step(
+      world,
+      dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, p2))),
+    );
+    last_inserted::<SymbolicLine>(world)
+  }
+
+  fn position(world: &World, point: Entity) -> Vector2 {
+    world.read_storage::<VirtualPoint>().get(point).unwrap().0
+  }
+
+  #[test]
+  fn test_fixed_distance_moves_the_free_point() {
+    let (mut world, mut dispatcher) = headless();
+    let fixed = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![0., 0.].into()));
+    let free = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![3., 4.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Constrain(Constraint::FixedDistance(fixed, free, 10.)),
+    );
+    // The free point is updated on the next frame
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(position(&world, fixed), vec2![0., 0.]);
+    assert!((position(&world, free) - vec2![6., 8.]).magnitude() < 1e-9);
+  }
+
+  #[test]
+  fn test_constraints_are_satisfied_together() {
+    let (mut world, mut dispatcher) = headless();
+    let a1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![0., 0.].into()));
+    let a2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 0.].into()));
+    let b1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 1.].into()));
+    let b2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 3.].into()));
+    let line_a = insert_segment(&mut world, &mut dispatcher, a1, a2);
+    let line_b = insert_segment(&mut world, &mut dispatcher, b1, b2);
+    for constraint in vec![
+      Constraint::FixedAngle(line_a, line_b, FRAC_PI_2),
+      Constraint::FixedDistance(b1, b2, 3.),
+      Constraint::Coincident(a2, b1),
+    ] {
+      step(&mut world, &mut dispatcher, Command::Constrain(constraint));
+    }
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    let (a1, a2, b1, b2) = (
+      position(&world, a1),
+      position(&world, a2),
+      position(&world, b1),
+      position(&world, b2),
+    );
+    assert_eq!(a1, vec2![0., 0.]);
+    assert!((a2 - b1).magnitude() < 1e-6);
+    assert!(((b2 - b1).magnitude() - 3.).abs() < 1e-6);
+    let (dir_a, dir_b) = (a2 - a1, b2 - b1);
+    assert!((dir_a.x * dir_b.x + dir_a.y * dir_b.y).abs() < 1e-6);
+    assert!(dir_a.x * dir_b.y - dir_a.y * dir_b.x > 0.);
+  }
+
+  #[test]
+  fn test_parallel_keeps_either_direction() {
+    let (mut world, mut dispatcher) = headless();
+    let a1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![0., 0.].into()));
+    let a2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![2., 0.].into()));
+    let b1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![0., 1.].into()));
+    let b2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![-2., 2.].into()));
+    let line_a = insert_segment(&mut world, &mut dispatcher, a1, a2);
+    let line_b = insert_segment(&mut world, &mut dispatcher, b1, b2);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Constrain(Constraint::Parallel(line_a, line_b)),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    let b2 = position(&world, b2);
+    assert!((b2.y - 1.).abs() < 1e-9);
+    assert!(b2.x < 0.);
+  }
+
+  #[test]
+  fn test_unsatisfiable_is_reported_once() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+    let p1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![0., 0.].into()));
+    let p2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![1., 0.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Constrain(Constraint::Coincident(p1, p2)),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter(|event| matches!(event, ErrorEvent::Unsatisfiable(Constraint::Coincident(_, _))))
+      .count();
+    assert_eq!(errors, 1);
+    assert_eq!(position(&world, p2), vec2![1., 0.]);
+  }
+}
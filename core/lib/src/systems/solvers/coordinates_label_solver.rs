@@ -0,0 +1,106 @@
+use crate::{
+  components::{markers::*, measurements::*, screen_shapes::*, virtual_shapes::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static LABEL_OFFSET: Vector2 = Vector2 { x: 8.0, y: 8.0 }; // Pixel, to the bottom right of the point
+
+/// Keeps the coordinates label of every point showing its coordinates up to date with the solved
+/// virtual position of the point
+#[derive(Default)]
+pub struct CoordinatesLabelSolver;
+
+impl<'a> System<'a> for CoordinatesLabelSolver {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, CoordinatesFormat>,
+    ReadStorage<'a, ShowCoordinates>,
+    ReadStorage<'a, Measurement>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, ScreenPoint>,
+    WriteStorage<'a, ScreenLabel>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, coordinates_format, show_coordinates, measurements, virt_points, scrn_points, mut scrn_labels): Self::SystemData,
+  ) {
+    // Remove the labels of points no longer showing coordinates (or no longer existing), the
+    // labels of measurements are kept by the measurement solver
+    let to_remove = (&entities, &scrn_labels, !&measurements)
+      .join()
+      .filter(|(ent, _, _)| show_coordinates.get(*ent).is_none() || virt_points.get(*ent).is_none())
+      .map(|(ent, _, _)| ent)
+      .collect::<Vec<_>>();
+    for ent in to_remove {
+      scrn_labels.remove(ent);
+    }
+
+    for (ent, _, virt_point, scrn_point) in (&entities, &show_coordinates, &virt_points, &scrn_points).join() {
+      let label = ScreenLabel {
+        position: *scrn_point + ScreenPosition(LABEL_OFFSET),
+        text: coordinates_format.format(*virt_point),
+      };
+      if let Err(err) = scrn_labels.insert(ent, label) {
+        panic!(err)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, events::*, test_utils::*};
+
+  fn label_of(world: &World, ent: Entity) -> Option<String> {
+    world
+      .read_storage::<ScreenLabel>()
+      .get(ent)
+      .map(|label| label.text.clone())
+  }
+
+  #[test]
+  fn test_coordinates_label_follows_point() {
+    let (mut world, mut dispatcher) = headless();
+
+    let start = SymbolicPoint::Free(vec2![1., 2.].into());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPoint(start)),
+    );
+    let ent = (&world.entities(), &world.read_storage::<SymbolicPoint>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+    assert_eq!(label_of(&world, ent), None);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Coordinates(CoordinatesEvent::ToggleSelected),
+    );
+    assert_eq!(label_of(&world, ent), Some("(1.00, 2.00)".to_string()));
+
+    let moved = SymbolicPoint::Free(vec2![3.5, -1.25].into());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(ent, start, moved)),
+    );
+    assert_eq!(label_of(&world, ent), Some("(3.50, -1.25)".to_string()));
+
+    world.write_resource::<CoordinatesFormat>().decimals = 1;
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Coordinates(CoordinatesEvent::Toggle(ent)),
+    );
+    assert_eq!(label_of(&world, ent), None);
+  }
+}
@@ -0,0 +1,185 @@
+use super::angle_at;
+use crate::{
+  components::{measurements::*, screen_shapes::*, virtual_shapes::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static LABEL_OFFSET: Vector2 = Vector2 { x: 6.0, y: -18.0 }; // Pixel, to the top right of the anchor
+
+/// Recomputes the value of every measurement from the solved virtual shapes, along with the
+/// label showing it next to the measured geometries. Measurements of undefined geometries have
+/// neither a value nor a label
+#[derive(Default)]
+pub struct MeasurementSolverSystem;
+
+impl<'a> System<'a> for MeasurementSolverSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    ReadStorage<'a, Measurement>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualLine>,
+    ReadStorage<'a, VirtualCircle>,
+    ReadStorage<'a, VirtualPolygon>,
+    WriteStorage<'a, MeasuredValue>,
+    WriteStorage<'a, ScreenLabel>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      viewport,
+      measurements,
+      virt_points,
+      virt_lines,
+      virt_circles,
+      virt_polygons,
+      mut measured_values,
+      mut scrn_labels,
+    ): Self::SystemData,
+  ) {
+    for (ent, measurement) in (&entities, &measurements).join() {
+      let point = |ent: Entity| virt_points.get(ent).map(|p| p.0);
+      let measured = match *measurement {
+        Measurement::DistancePP(p1, p2) => match (point(p1), point(p2)) {
+          (Some(p1), Some(p2)) => Some(((p2 - p1).magnitude(), (p1 + p2) / 2.0)),
+          _ => None,
+        },
+        Measurement::Angle(p1, vertex, p2) => match (point(p1), point(vertex), point(p2)) {
+          (Some(p1), Some(vertex), Some(p2)) => Some((angle_at(p1, vertex, p2), vertex)),
+          _ => None,
+        },
+        Measurement::Radius(c) => virt_circles
+          .get(c)
+          .map(|circle| (circle.radius.0, circle.center.0 + vec2![circle.radius.0 / 2.0, 0.0])),
+        Measurement::SegmentLength(l) => virt_lines
+          .get(l)
+          .map(|line| ((line.to - line.from).0.magnitude(), (line.from.0 + line.to.0) / 2.0)),
+        Measurement::PolygonArea(p) => virt_polygons.get(p).map(|polygon| {
+          let polygon: Polygon = polygon.clone().into();
+          let center = polygon.vertices.iter().fold(vec2![0., 0.], |sum, v| sum + *v) / polygon.vertices.len() as f64;
+          (polygon.area(), center)
+        }),
+      };
+
+      match measured {
+        Some((value, anchor)) => {
+          if let Err(err) = measured_values.insert(ent, MeasuredValue(value)) {
+            panic!(err)
+          }
+          let label = ScreenLabel {
+            position: VirtualPosition(anchor).to_screen(&viewport) + ScreenPosition(LABEL_OFFSET),
+            text: format_measurement(measurement, value),
+          };
+
+          // Only touch the label when it changes, so that renderers are told about it once
+          if scrn_labels.get(ent) != Some(&label) {
+            if let Err(err) = scrn_labels.insert(ent, label) {
+              panic!(err)
+            }
+          }
+        }
+        None => {
+          measured_values.remove(ent);
+          scrn_labels.remove(ent);
+        }
+      }
+    }
+  }
+}
+
+/// Lengths and areas with two decimals, angles in degrees with one
+pub fn format_measurement(measurement: &Measurement, value: f64) -> String {
+  match measurement {
+    Measurement::DistancePP(_, _) | Measurement::SegmentLength(_) => format!("{:.2}", value),
+    Measurement::Angle(_, _, _) => format!("{:.1}°", value.to_degrees()),
+    Measurement::Radius(_) => format!("r = {:.2}", value),
+    Measurement::PolygonArea(_) => format!("A = {:.2}", value),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, events::*, test_utils::*};
+
+  fn value_of(world: &World, ent: Entity) -> Option<f64> {
+    world.read_storage::<MeasuredValue>().get(ent).map(|value| value.0)
+  }
+
+  fn label_of(world: &World, ent: Entity) -> Option<String> {
+    world
+      .read_storage::<ScreenLabel>()
+      .get(ent)
+      .map(|label| label.text.clone())
+  }
+
+  #[test]
+  fn test_measurements_follow_points_and_undo() {
+    let (mut world, mut dispatcher) = headless();
+
+    let insert_point = |world: &mut World, dispatcher: &mut Dispatcher, x: f64, y: f64| {
+      insert_point(world, dispatcher, SymbolicPoint::Free(vec2![x, y].into()))
+    };
+    let a = insert_point(&mut world, &mut dispatcher, 0., 0.);
+    let b = insert_point(&mut world, &mut dispatcher, 3., 4.);
+    let c = insert_point(&mut world, &mut dispatcher, 3., 0.);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(a, c))),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+
+    let mut measure = |world: &mut World, measurement: Measurement| {
+      step(
+        world,
+        &mut dispatcher,
+        Command::MeasurementInsert(InsertMeasurementEvent::InsertMeasurement(measurement)),
+      );
+      last_inserted::<Measurement>(world)
+    };
+    let distance = measure(&mut world, Measurement::DistancePP(a, b));
+    let angle = measure(&mut world, Measurement::Angle(b, a, c));
+    let radius = measure(&mut world, Measurement::Radius(circle));
+    assert_eq!(value_of(&world, distance), Some(5.0));
+    assert_eq!(label_of(&world, distance), Some("5.00".to_string()));
+    assert_eq!(label_of(&world, angle), Some("53.1°".to_string()));
+    assert_eq!(label_of(&world, radius), Some("r = 3.00".to_string()));
+
+    // A radius is not measured on a point
+    let count = world.read_storage::<Measurement>().join().count();
+    measure(&mut world, Measurement::Radius(a));
+    assert_eq!(world.read_storage::<Measurement>().join().count(), count);
+
+    // Values follow the points being moved
+    let old = SymbolicPoint::Free(vec2![3., 4.].into());
+    let new = SymbolicPoint::Free(vec2![6., 8.].into());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(b, old, new)),
+    );
+    assert_eq!(value_of(&world, distance), Some(10.0));
+
+    // Removing a measured point removes its measurements, and undo brings them back
+    step(&mut world, &mut dispatcher, Command::Remove(RemoveEvent::Remove(b)));
+    assert!(world.read_storage::<Measurement>().get(distance).is_none());
+    assert!(world.read_storage::<Measurement>().get(angle).is_none());
+    assert!(label_of(&world, distance).is_none());
+    assert!(world.read_storage::<Measurement>().get(radius).is_some());
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(value_of(&world, distance), Some(10.0));
+    assert_eq!(label_of(&world, angle), Some("53.1°".to_string()));
+  }
+}
@@ -0,0 +1,171 @@
+use crate::{
+  components::{markers::*, symbolics::*, virtual_shapes::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+use std::f64::consts::PI;
+
+static ON_CIRCLE_THRES: f64 = 1e-6; // Virtual distance
+
+/// Measures the selected points. When exactly three points are selected, the angle at the second
+/// one selected is measured. If the three of them lie on a common circle, the inscribed angle and
+/// the central angle subtending the same arc are measured instead
+#[derive(Default)]
+pub struct MeasurementSystem;
+
+impl<'a> System<'a> for MeasurementSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, SelectionOrder>,
+    Write<'a, Measurements>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualCircle>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, selection_order, mut measurements, selecteds, sym_points, virt_points, virt_circles): Self::SystemData,
+  ) {
+    let points = selection_order
+      .iter()
+      .filter(|ent| selecteds.get(**ent).is_some() && sym_points.get(**ent).is_some())
+      .filter_map(|ent| virt_points.get(*ent).map(|VirtualPosition(p)| *p))
+      .collect::<Vec<_>>();
+    measurements.angle = match points.as_slice() {
+      [a, b, c] => {
+        let circle = (&entities, &virt_circles)
+          .join()
+          .map(|(ent, VirtualCircle { center, radius })| {
+            (
+              ent,
+              Circle {
+                center: center.0,
+                radius: radius.0,
+              },
+            )
+          })
+          .find(|(_, circle)| [a, b, c].iter().all(|p| on_circle(**p, circle)));
+        Some(match circle {
+          Some((ent, circle)) => inscribed_angle(*a, *b, *c, circle, ent),
+          None => AngleMeasurement::Angle(angle_at(*a, *b, *c)),
+        })
+      }
+      _ => None,
+    };
+  }
+}
+
+fn on_circle(p: Vector2, circle: &Circle) -> bool {
+  ((p - circle.center).magnitude() - circle.radius).abs() < ON_CIRCLE_THRES
+}
+
+/// The angle between `a` and `c` seen from `vertex`, within `[0, PI]`
+pub fn angle_at(a: Vector2, vertex: Vector2, c: Vector2) -> f64 {
+  let (u, v) = (a - vertex, c - vertex);
+  (u.x * v.y - u.y * v.x).atan2(u.dot(v)).abs()
+}
+
+/// The inscribed angle at `b` and the central angle over the arc from `a` to `c` not containing `b`
+fn inscribed_angle(a: Vector2, b: Vector2, c: Vector2, circle: Circle, ent: Entity) -> AngleMeasurement {
+  let minor = angle_at(a, circle.center, c);
+
+  // `b` on the same side of the chord as the center means `b` is on the major arc, which leaves
+  // the minor arc to subtend
+  let side = |p: Vector2| {
+    let (u, v) = (c - a, p - a);
+    u.x * v.y - u.y * v.x
+  };
+  let central = if side(b) * side(circle.center) >= 0.0 {
+    minor
+  } else {
+    2.0 * PI - minor
+  };
+  AngleMeasurement::Inscribed {
+    circle: ent,
+    inscribed: angle_at(a, b, c),
+    central,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{events::*, test_utils::*};
+
+  fn measure_on_circle(thetas: [f64; 3]) -> AngleMeasurement {
+    let (mut world, mut dispatcher) = headless();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![1., 1.])),
+    );
+    let center = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![3., 1.])),
+    );
+    let on_circle = last_inserted::<SymbolicPoint>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(
+        center, on_circle,
+      ))),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+
+    let mut points = vec![];
+    for theta in thetas.iter() {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::OnCircle(circle, *theta))),
+      );
+      points.push(last_inserted::<SymbolicPoint>(&world));
+    }
+    for ent in points {
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(ent)));
+    }
+    dispatcher.dispatch(&world);
+    let measurement = world.fetch::<Measurements>().angle;
+    measurement.unwrap()
+  }
+
+  #[test]
+  fn test_central_angle_twice_inscribed_angle() {
+    for thetas in &[[0.0, PI, PI / 2.0], [0.3, 2.0, 4.5], [0.0, 0.5, 2.5], [1.0, 5.0, 3.0]] {
+      match measure_on_circle(*thetas) {
+        AngleMeasurement::Inscribed { inscribed, central, .. } => {
+          assert!((central - 2.0 * inscribed).abs() < 1e-9);
+        }
+        measurement => panic!("Expected an inscribed angle, got {:?}", measurement),
+      }
+    }
+  }
+
+  #[test]
+  fn test_plain_angle_off_circle() {
+    let (mut world, mut dispatcher) = headless();
+    for position in &[vec2![1., 0.], vec2![0., 0.], vec2![0., 2.]] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(*position)),
+      );
+      let ent = last_inserted::<SymbolicPoint>(&world);
+      step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(ent)));
+    }
+    dispatcher.dispatch(&world);
+    let measurement = world.fetch::<Measurements>().angle;
+    match measurement {
+      Some(AngleMeasurement::Angle(angle)) => assert!((angle - PI / 2.0).abs() < 1e-9),
+      measurement => panic!("Expected a plain angle, got {:?}", measurement),
+    }
+  }
+}
@@ -1,5 +1,17 @@
+mod angle_constraint_solver;
+mod constraint_solver_system;
+mod coordinates_label_solver;
+mod measurement_solver_system;
+mod measurement_system;
+mod scalar_expression_solver;
 mod screen_shape_solver;
 mod virtual_shape_solver;
 
+pub use angle_constraint_solver::*;
+pub use constraint_solver_system::*;
+pub use coordinates_label_solver::*;
+pub use measurement_solver_system::*;
+pub use measurement_system::*;
+pub use scalar_expression_solver::*;
 pub use screen_shape_solver::*;
 pub use virtual_shape_solver::*;
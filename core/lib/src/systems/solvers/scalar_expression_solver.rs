@@ -0,0 +1,187 @@
+use crate::{
+  components::{sliders::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Evaluates the scalars again every frame, from the solved geometries and the sliders they refer
+/// to by name. The geometries using a scalar whose value changed are solved again on the next frame
+#[derive(Default)]
+pub struct ScalarExpressionSolver;
+
+impl<'a> System<'a> for ScalarExpressionSolver {
+  type SystemData = (
+    Read<'a, SolverEnabled>,
+    Read<'a, Names>,
+    Write<'a, ScalarExpressions>,
+    Write<'a, GeometryEventChannel>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualLine>,
+    ReadStorage<'a, VirtualCircle>,
+    ReadStorage<'a, SliderValue>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      solver_enabled,
+      names,
+      mut scalar_expressions,
+      mut geometry_event_channel,
+      sym_lines,
+      virt_points,
+      virt_lines,
+      virt_circles,
+      slider_values,
+    ): Self::SystemData,
+  ) {
+    if !solver_enabled.0 {
+      return;
+    }
+
+    let geometries = NamedGeometries {
+      names: &names,
+      sym_lines: &sym_lines,
+      virt_points: &virt_points,
+      virt_lines: &virt_lines,
+      virt_circles: &virt_circles,
+      slider_values: &slider_values,
+    };
+    for id in scalar_expressions.evaluate(&geometries) {
+      geometry_event_channel.single_write(GeometryEvent::ScalarUpdated(id));
+    }
+  }
+}
+
+struct NamedGeometries<'s, 'a> {
+  names: &'s Names,
+  sym_lines: &'s ReadStorage<'a, SymbolicLine>,
+  virt_points: &'s ReadStorage<'a, VirtualPoint>,
+  virt_lines: &'s ReadStorage<'a, VirtualLine>,
+  virt_circles: &'s ReadStorage<'a, VirtualCircle>,
+  slider_values: &'s ReadStorage<'a, SliderValue>,
+}
+
+impl<'s, 'a> ExpressionContext for NamedGeometries<'s, 'a> {
+  fn point(&self, name: &str) -> Option<Vector2> {
+    self.virt_points.get(self.names.by_name(name)?).map(|p| p.0)
+  }
+
+  fn radius(&self, name: &str) -> Option<f64> {
+    self.virt_circles.get(self.names.by_name(name)?).map(|c| c.radius.0)
+  }
+
+  fn length(&self, name: &str) -> Option<f64> {
+    let ent = self.names.by_name(name)?;
+    match self.sym_lines.get(ent) {
+      Some(SymbolicLine::Segment(_, _)) => self.virt_lines.get(ent).map(|l| (l.to.0 - l.from.0).magnitude()),
+      _ => None,
+    }
+  }
+
+  fn slider(&self, name: &str) -> Option<f64> {
+    self.slider_values.get(self.names.by_name(name)?).map(|v| v.0)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn insert_named_point(world: &mut World, dispatcher: &mut Dispatcher, name: &str, x: f64, y: f64) -> Entity {
+    let point = insert_point(world, dispatcher, SymbolicPoint::Free(vec2![x, y].into()));
+    step(
+      world,
+      dispatcher,
+      Command::Rename(RenameEvent::Rename(point, name.to_string())),
+    );
+    point
+  }
+
+  #[test]
+  fn test_geometries_follow_the_scalars() {
+    let (mut world, mut dispatcher) = headless();
+
+    let a = insert_named_point(&mut world, &mut dispatcher, "A", 0., 0.);
+    let b = insert_named_point(&mut world, &mut dispatcher, "B", 3., 4.);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::DefineScalar("r = dist(A, B) * 2".to_string()),
+    );
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::DefineScalar("quarter = pi / 2".to_string()),
+    );
+    let r = world.fetch::<ScalarExpressions>().by_name("r").unwrap().id;
+    let quarter = world.fetch::<ScalarExpressions>().by_name("quarter").unwrap().id;
+    assert_eq!(world.fetch::<ScalarExpressions>().value(r), Some(10.));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::ScalarRadius(a, r))),
+    );
+    let circle = last_inserted::<SymbolicCircle>(&world);
+    let rotated = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::RotateByScalar(b, a, quarter),
+    );
+    assert_eq!(world.read_storage::<VirtualCircle>().get(circle).unwrap().radius.0, 10.);
+    let position = world.read_storage::<VirtualPoint>().get(rotated).unwrap().0;
+    assert!((position - vec2![-4., 3.]).magnitude() < 1e-12);
+
+    // Moving B changes r, and the circle follows on the next frame
+    let old = *world.read_storage::<SymbolicPoint>().get(b).unwrap();
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        b,
+        old,
+        SymbolicPoint::Free(vec2![6., 8.].into()),
+      )),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<VirtualCircle>().get(circle).unwrap().radius.0, 20.);
+
+    // Defined again, then removed
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::DefineScalar("quarter = -pi / 2".to_string()),
+    );
+    dispatcher.dispatch(&world);
+    world.maintain();
+    let position = world.read_storage::<VirtualPoint>().get(rotated).unwrap().0;
+    assert!((position - vec2![8., -6.]).magnitude() < 1e-12);
+    step(&mut world, &mut dispatcher, Command::RemoveScalar("r".to_string()));
+    assert!(world.read_storage::<VirtualCircle>().get(circle).is_none());
+  }
+
+  #[test]
+  fn test_invalid_definition_is_reported() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<ErrorEventChannel>().register_reader();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::DefineScalar("r = dist(A".to_string()),
+    );
+    let errors = world
+      .fetch::<ErrorEventChannel>()
+      .read(&mut reader)
+      .filter(|event| matches!(event, ErrorEvent::InvalidExpression(_)))
+      .count();
+    assert_eq!(errors, 1);
+    assert!(world.fetch::<ScalarExpressions>().by_name("r").is_none());
+  }
+}
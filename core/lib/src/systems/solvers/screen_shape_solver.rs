@@ -1,5 +1,5 @@
 use crate::{
-  components::{screen_shapes::*, virtual_shapes::*},
+  components::{freehand::*, markers::*, screen_shapes::*, virtual_shapes::*},
   events::*,
   resources::*,
 };
@@ -8,6 +8,7 @@ use specs::prelude::*;
 pub struct ScreenShapeSolver {
   viewport_event_reader: Option<ViewportEventReader>,
   geometry_event_reader: Option<GeometryEventReader>,
+  viewport: Viewport, // The one the screen shapes were last computed with
 }
 
 impl Default for ScreenShapeSolver {
@@ -15,6 +16,7 @@ impl Default for ScreenShapeSolver {
     Self {
       viewport_event_reader: None,
       geometry_event_reader: None,
+      viewport: Viewport::default(),
     }
   }
 }
@@ -23,21 +25,36 @@ impl<'a> System<'a> for ScreenShapeSolver {
   type SystemData = (
     Entities<'a>,
     Read<'a, Viewport>,
+    Read<'a, SolverEnabled>,
     Read<'a, DependencyGraph>,
     Read<'a, GeometryEventChannel>,
     Read<'a, ViewportEventChannel>,
     ReadStorage<'a, VirtualPoint>,
     ReadStorage<'a, VirtualLine>,
     ReadStorage<'a, VirtualCircle>,
+    ReadStorage<'a, VirtualArc>,
+    ReadStorage<'a, VirtualConic>,
+    ReadStorage<'a, VirtualPolygon>,
+    ReadStorage<'a, VirtualVector>,
+    ReadStorage<'a, VirtualText>,
+    ReadStorage<'a, FreehandStroke>,
+    ReadStorage<'a, Unsolvable>,
     WriteStorage<'a, ScreenPoint>,
     WriteStorage<'a, ScreenLine>,
     WriteStorage<'a, ScreenCircle>,
+    WriteStorage<'a, ScreenArc>,
+    WriteStorage<'a, ScreenConic>,
+    WriteStorage<'a, ScreenPolygon>,
+    WriteStorage<'a, ScreenVector>,
+    WriteStorage<'a, ScreenText>,
+    WriteStorage<'a, ScreenFreehandStroke>,
   );
 
   fn setup(&mut self, world: &mut World) {
     Self::SystemData::setup(world);
     self.viewport_event_reader = Some(world.fetch_mut::<ViewportEventChannel>().register_reader());
     self.geometry_event_reader = Some(world.fetch_mut::<GeometryEventChannel>().register_reader());
+    self.viewport = *world.fetch::<Viewport>();
   }
 
   fn run(
@@ -45,17 +62,36 @@ impl<'a> System<'a> for ScreenShapeSolver {
     (
       entities,
       viewport,
+      solver_enabled,
       dependency_graph,
       geometry_event_channel,
       viewport_event_channel,
       virt_points,
       virt_lines,
       virt_circles,
+      virt_arcs,
+      virt_conics,
+      virt_polygons,
+      virt_vectors,
+      virt_texts,
+      strokes,
+      unsolvables,
       mut scrn_points,
       mut scrn_lines,
       mut scrn_circles,
+      mut scrn_arcs,
+      mut scrn_conics,
+      mut scrn_polygons,
+      mut scrn_vectors,
+      mut scrn_texts,
+      mut scrn_strokes,
     ): Self::SystemData,
   ) {
+    // The virtual shapes are not solved yet, keep the events for when the solver is enabled again
+    if !solver_enabled.0 {
+      return;
+    }
+
     // Check if there's viewport event
     let mut need_update_all = false;
     if let Some(reader) = &mut self.viewport_event_reader {
@@ -83,6 +119,47 @@ impl<'a> System<'a> for ScreenShapeSolver {
           panic!(err)
         }
       }
+      for (ent, virt_arc) in (&entities, &virt_arcs).join() {
+        if let Err(err) = scrn_arcs.insert(ent, virt_arc.to_screen(&*viewport)) {
+          panic!(err)
+        }
+      }
+      for (ent, virt_conic) in (&entities, &virt_conics).join() {
+        if let Err(err) = scrn_conics.insert(ent, virt_conic.to_screen(&*viewport)) {
+          panic!(err)
+        }
+      }
+      for (ent, virt_polygon) in (&entities, &virt_polygons).join() {
+        if let Err(err) = scrn_polygons.insert(ent, virt_polygon.clone().to_screen(&*viewport)) {
+          panic!(err)
+        }
+      }
+      for (ent, virt_vector) in (&entities, &virt_vectors).join() {
+        if let Err(err) = scrn_vectors.insert(ent, virt_vector.to_screen(&*viewport)) {
+          panic!(err)
+        }
+      }
+      for (ent, virt_text) in (&entities, &virt_texts).join() {
+        if let Err(err) = scrn_texts.insert(ent, virt_text.clone().to_screen(&*viewport)) {
+          panic!(err)
+        }
+      }
+      for (ent, stroke) in (&entities, &strokes).join() {
+        if let Err(err) = scrn_strokes.insert(ent, stroke.clone().to_screen(&*viewport)) {
+          panic!(err)
+        }
+      }
+
+      // The unsolvable ones have no virtual shape, their last screen shapes move with the viewport
+      follow_viewport(&mut scrn_points, &unsolvables, &self.viewport, &viewport);
+      follow_viewport(&mut scrn_lines, &unsolvables, &self.viewport, &viewport);
+      follow_viewport(&mut scrn_circles, &unsolvables, &self.viewport, &viewport);
+      follow_viewport(&mut scrn_arcs, &unsolvables, &self.viewport, &viewport);
+      follow_viewport(&mut scrn_conics, &unsolvables, &self.viewport, &viewport);
+      follow_viewport(&mut scrn_polygons, &unsolvables, &self.viewport, &viewport);
+      follow_viewport(&mut scrn_vectors, &unsolvables, &self.viewport, &viewport);
+      follow_viewport(&mut scrn_texts, &unsolvables, &self.viewport, &viewport);
+      self.viewport = *viewport;
     } else {
       // Only update what's needed
       if let Some(reader) = &mut self.geometry_event_reader {
@@ -95,27 +172,55 @@ impl<'a> System<'a> for ScreenShapeSolver {
                 &virt_points,
                 &virt_lines,
                 &virt_circles,
+                &virt_arcs,
+                &virt_conics,
+                &virt_polygons,
+                &virt_vectors,
+                &virt_texts,
+                &strokes,
                 &mut scrn_points,
                 &mut scrn_lines,
                 &mut scrn_circles,
+                &mut scrn_arcs,
+                &mut scrn_conics,
+                &mut scrn_polygons,
+                &mut scrn_vectors,
+                &mut scrn_texts,
+                &mut scrn_strokes,
               );
             }
             GeometryEvent::Removed(_, _, _) => (),
-            GeometryEvent::PointUpdated(ent, _, _, _) => {
-              for dep in dependency_graph.get_all_dependents(ent) {
+            GeometryEvent::PointUpdated(_, _, _, _)
+            | GeometryEvent::LineUpdated(_, _, _, _)
+            | GeometryEvent::ScalarUpdated(_) => {
+              for dep in event.updated_dependents(&dependency_graph) {
                 calc_scrn_shape(
                   dep,
                   &viewport,
                   &virt_points,
                   &virt_lines,
                   &virt_circles,
+                  &virt_arcs,
+                  &virt_conics,
+                  &virt_polygons,
+                  &virt_vectors,
+                  &virt_texts,
+                  &strokes,
                   &mut scrn_points,
                   &mut scrn_lines,
                   &mut scrn_circles,
+                  &mut scrn_arcs,
+                  &mut scrn_conics,
+                  &mut scrn_polygons,
+                  &mut scrn_vectors,
+                  &mut scrn_texts,
+                  &mut scrn_strokes,
                 );
               }
             }
-            GeometryEvent::PointUpdateFinished(_, _, _, _) => (),
+            GeometryEvent::PointUpdateFinished(_, _, _, _)
+            | GeometryEvent::SliderUpdateFinished(_, _, _, _)
+            | GeometryEvent::UnderlayUpdateFinished(_, _, _, _) => (),
           }
         }
       }
@@ -129,9 +234,21 @@ fn calc_scrn_shape<'a>(
   virt_points: &ReadStorage<'a, VirtualPoint>,
   virt_lines: &ReadStorage<'a, VirtualLine>,
   virt_circles: &ReadStorage<'a, VirtualCircle>,
+  virt_arcs: &ReadStorage<'a, VirtualArc>,
+  virt_conics: &ReadStorage<'a, VirtualConic>,
+  virt_polygons: &ReadStorage<'a, VirtualPolygon>,
+  virt_vectors: &ReadStorage<'a, VirtualVector>,
+  virt_texts: &ReadStorage<'a, VirtualText>,
+  strokes: &ReadStorage<'a, FreehandStroke>,
   scrn_points: &mut WriteStorage<'a, ScreenPoint>,
   scrn_lines: &mut WriteStorage<'a, ScreenLine>,
   scrn_circles: &mut WriteStorage<'a, ScreenCircle>,
+  scrn_arcs: &mut WriteStorage<'a, ScreenArc>,
+  scrn_conics: &mut WriteStorage<'a, ScreenConic>,
+  scrn_polygons: &mut WriteStorage<'a, ScreenPolygon>,
+  scrn_vectors: &mut WriteStorage<'a, ScreenVector>,
+  scrn_texts: &mut WriteStorage<'a, ScreenText>,
+  scrn_strokes: &mut WriteStorage<'a, ScreenFreehandStroke>,
 ) {
   if let Some(virt_point) = virt_points.get(ent) {
     if let Err(err) = scrn_points.insert(ent, virt_point.to_screen(&*viewport)) {
@@ -145,5 +262,44 @@ fn calc_scrn_shape<'a>(
     if let Err(err) = scrn_circles.insert(ent, virt_circle.to_screen(&*viewport)) {
       panic!(err)
     }
+  } else if let Some(virt_arc) = virt_arcs.get(ent) {
+    if let Err(err) = scrn_arcs.insert(ent, virt_arc.to_screen(&*viewport)) {
+      panic!(err)
+    }
+  } else if let Some(virt_conic) = virt_conics.get(ent) {
+    if let Err(err) = scrn_conics.insert(ent, virt_conic.to_screen(&*viewport)) {
+      panic!(err)
+    }
+  } else if let Some(virt_polygon) = virt_polygons.get(ent) {
+    if let Err(err) = scrn_polygons.insert(ent, virt_polygon.clone().to_screen(&*viewport)) {
+      panic!(err)
+    }
+  } else if let Some(virt_vector) = virt_vectors.get(ent) {
+    if let Err(err) = scrn_vectors.insert(ent, virt_vector.to_screen(&*viewport)) {
+      panic!(err)
+    }
+  } else if let Some(virt_text) = virt_texts.get(ent) {
+    if let Err(err) = scrn_texts.insert(ent, virt_text.clone().to_screen(&*viewport)) {
+      panic!(err)
+    }
+  } else if let Some(stroke) = strokes.get(ent) {
+    if let Err(err) = scrn_strokes.insert(ent, stroke.clone().to_screen(&*viewport)) {
+      panic!(err)
+    }
+  }
+  // Otherwise it could not be solved, its last screen shape is kept for the frontends to gray out
+}
+
+fn follow_viewport<'a, T>(
+  scrn_shapes: &mut WriteStorage<'a, T>,
+  unsolvables: &ReadStorage<'a, Unsolvable>,
+  from: &Viewport,
+  to: &Viewport,
+) where
+  T: Component + Clone + ToVirtual,
+  T::Output: ToScreen<Output = T>,
+{
+  for (scrn_shape, _) in (scrn_shapes, unsolvables).join() {
+    *scrn_shape = scrn_shape.clone().to_virtual(from).to_screen(to);
   }
 }
@@ -1,21 +1,36 @@
 use crate::{
-  components::{symbolics::*, virtual_shapes::*},
+  components::{markers::*, measurements::*, symbolics::*, virtual_shapes::*},
   events::*,
   math::*,
   resources::*,
   utilities::*,
 };
 use specs::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub struct VirtualShapeSolver {
   geometry_event_reader: Option<GeometryEventReader>,
+  #[cfg(feature = "parallel")]
+  parallel: bool, // Whether the independent parts of the dirty entities are solved on worker threads
 }
 
 impl Default for VirtualShapeSolver {
   fn default() -> Self {
     Self {
       geometry_event_reader: None,
+      #[cfg(feature = "parallel")]
+      parallel: true,
+    }
+  }
+}
+
+#[cfg(feature = "parallel")]
+impl VirtualShapeSolver {
+  /// The solver solving everything on the thread it runs on, even with the `parallel` feature
+  pub fn serial() -> Self {
+    Self {
+      parallel: false,
+      ..Self::default()
     }
   }
 }
@@ -23,24 +38,59 @@ impl Default for VirtualShapeSolver {
 struct ToCompute(Entity, GeometrySymbol);
 
 enum SolveResult {
-  AlreadyComputed,             // Already Computed
-  SolvedPoint(VirtualPoint),   // The result of point
-  SolvedLine(VirtualLine),     // The result of line
-  SolvedCircle(VirtualCircle), // The result of circle
-  Request(Entity),             // Need other dependency
-  Undefined,                   // The result does not exist
+  AlreadyComputed,               // Already Computed
+  SolvedPoint(VirtualPoint),     // The result of point
+  SolvedLine(VirtualLine),       // The result of line
+  SolvedCircle(VirtualCircle),   // The result of circle
+  SolvedArc(VirtualArc),         // The result of arc
+  SolvedConic(VirtualConic),     // The result of conic
+  SolvedPolygon(VirtualPolygon), // The result of polygon
+  SolvedVector(VirtualVector),   // The result of vector
+  SolvedText(VirtualText),       // The result of text
+  Request(Entity),               // Need other dependency
+  Undefined,                     // The result does not exist
+}
+
+storages! {
+  /// The storages the symbols of the geometries are read from
+  Symbols {
+    points: ReadStorage<'a, SymbolicPoint>,
+    lines: ReadStorage<'a, SymbolicLine>,
+    circles: ReadStorage<'a, SymbolicCircle>,
+    arcs: ReadStorage<'a, SymbolicArc>,
+    conics: ReadStorage<'a, SymbolicConic>,
+    polygons: ReadStorage<'a, SymbolicPolygon>,
+    vectors: ReadStorage<'a, SymbolicVector>,
+    texts: ReadStorage<'a, SymbolicText>,
+    measurements: ReadStorage<'a, Measurement>,
+  }
+}
+
+storages! {
+  /// The storages the solved shapes are written to
+  VirtualShapes {
+    points: WriteStorage<'a, VirtualPoint>,
+    lines: WriteStorage<'a, VirtualLine>,
+    circles: WriteStorage<'a, VirtualCircle>,
+    arcs: WriteStorage<'a, VirtualArc>,
+    conics: WriteStorage<'a, VirtualConic>,
+    polygons: WriteStorage<'a, VirtualPolygon>,
+    vectors: WriteStorage<'a, VirtualVector>,
+    texts: WriteStorage<'a, VirtualText>,
+  }
 }
 
 impl<'a> System<'a> for VirtualShapeSolver {
   type SystemData = (
+    Entities<'a>,
+    Read<'a, SolverEnabled>,
     Read<'a, GeometryEventChannel>,
     Read<'a, DependencyGraph>,
-    ReadStorage<'a, SymbolicPoint>,
-    ReadStorage<'a, SymbolicLine>,
-    ReadStorage<'a, SymbolicCircle>,
-    WriteStorage<'a, VirtualPoint>,
-    WriteStorage<'a, VirtualLine>,
-    WriteStorage<'a, VirtualCircle>,
+    Read<'a, ScalarExpressions>,
+    Write<'a, DiagnosticEventChannel>,
+    Symbols<'a>,
+    VirtualShapes<'a>,
+    WriteStorage<'a, Unsolvable>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -51,148 +101,443 @@ impl<'a> System<'a> for VirtualShapeSolver {
   fn run(
     &mut self,
     (
+      entities,
+      solver_enabled,
       geometry_event_channel,
       dependency_graph,
-      sym_points,
-      sym_lines,
-      sym_circles,
-      mut virt_points,
-      mut virt_lines,
-      mut virt_circles,
+      scalar_expressions,
+      mut diagnostic_event_channel,
+      symbols,
+      mut virt_shapes,
+      mut unsolvables,
     ): Self::SystemData,
   ) {
-    let mut to_process = Vec::new();
-    let mut cannot_compute = HashSet::new();
+    // Leave the events in the channel, they are all solved once the solver is enabled again
+    if !solver_enabled.0 {
+      return;
+    }
 
-    // First get all the things to process
+    // The entities to solve again, with the symbols of the ones just inserted. An entity updated
+    // by several events is still solved once
+    let mut dirty = HashSet::new();
+    let mut inserted = HashMap::new();
+
+    // First get all the things to process. Events accumulated while the solver was disabled
+    // might refer to entities removed since then
     if let Some(reader) = &mut self.geometry_event_reader {
       for event in geometry_event_channel.read(reader) {
         match event {
           GeometryEvent::Inserted(ent, geom, _) => {
-            to_process.push(ToCompute(*ent, geom.clone().into()));
+            if entities.is_alive(*ent) {
+              dirty.insert(*ent);
+              inserted.insert(*ent, geom.clone().into());
+            }
           }
           GeometryEvent::Removed(_, _, _) => (),
-          GeometryEvent::PointUpdated(ent, _, _, _) => {
-            for dep in dependency_graph.get_all_dependents(ent) {
-              to_process.push(ToCompute(dep, get_symbol(dep, &sym_points, &sym_lines, &sym_circles)));
+          GeometryEvent::PointUpdated(_, _, _, _)
+          | GeometryEvent::LineUpdated(_, _, _, _)
+          | GeometryEvent::ScalarUpdated(_) => {
+            // The symbols of the updated entities are read from their storages
+            for dep in event.updated_dependents(&dependency_graph) {
+              if entities.is_alive(dep) {
+                inserted.remove(&dep);
+                dirty.insert(dep);
+              }
             }
           }
-          GeometryEvent::PointUpdateFinished(_, _, _, _) => (),
+          GeometryEvent::PointUpdateFinished(_, _, _, _)
+          | GeometryEvent::SliderUpdateFinished(_, _, _, _)
+          | GeometryEvent::UnderlayUpdateFinished(_, _, _, _) => (),
         }
       }
     }
 
+    // Only the dirty entities are ordered, the rest of the sketch is left as solved. With the
+    // `parallel` feature they are split into the parts sharing no dirty ancestor
+    #[cfg(feature = "parallel")]
+    let parts = if self.parallel {
+      dependency_graph.independent_parts(&dirty)
+    } else {
+      vec![dependency_graph.dependency_order(&dirty)]
+    };
+    #[cfg(not(feature = "parallel"))]
+    let parts = vec![dependency_graph.dependency_order(&dirty)];
+
+    // Each part is a stack popped from its end, so the parents go last to be solved first
+    let parts = parts
+      .into_iter()
+      .map(|part| {
+        part
+          .into_iter()
+          .rev()
+          .map(|ent| match inserted.remove(&ent) {
+            Some(sym) => ToCompute(ent, sym),
+            None => ToCompute(ent, symbols.get(ent)),
+          })
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
     // Then remove them from computed
-    for elem in &to_process {
+    for elem in parts.iter().flatten() {
       match elem {
         ToCompute(ent, GeometrySymbol::Point(_)) => {
-          virt_points.remove(*ent);
+          virt_shapes.points.remove(*ent);
         }
         ToCompute(ent, GeometrySymbol::Line(_)) => {
-          virt_lines.remove(*ent);
+          virt_shapes.lines.remove(*ent);
         }
         ToCompute(ent, GeometrySymbol::Circle(_)) => {
-          virt_circles.remove(*ent);
+          virt_shapes.circles.remove(*ent);
+        }
+        ToCompute(ent, GeometrySymbol::Arc(_)) => {
+          virt_shapes.arcs.remove(*ent);
+        }
+        ToCompute(ent, GeometrySymbol::Conic(_)) => {
+          virt_shapes.conics.remove(*ent);
         }
+        ToCompute(ent, GeometrySymbol::Polygon(_)) => {
+          virt_shapes.polygons.remove(*ent);
+        }
+        ToCompute(ent, GeometrySymbol::Vector(_)) => {
+          virt_shapes.vectors.remove(*ent);
+        }
+        ToCompute(ent, GeometrySymbol::Text(_)) => {
+          virt_shapes.texts.remove(*ent);
+        }
+        ToCompute(_, GeometrySymbol::Measurement(_))
+        | ToCompute(_, GeometrySymbol::Slider(_))
+        | ToCompute(_, GeometrySymbol::Underlay(_))
+        | ToCompute(_, GeometrySymbol::Freehand(_)) => (),
       }
     }
 
-    // Finally process them in sequence
-    while !to_process.is_empty() {
-      let to_comp = to_process.pop().unwrap(); // We can do this because we have checked it is not empty
-      let ent = to_comp.0.clone();
-      let sym = to_comp.1.clone();
-      match solve(ent, sym, &virt_points, &virt_lines, &virt_circles) {
-        SolveResult::AlreadyComputed => (),
-        SolveResult::Undefined => {
-          cannot_compute.insert(ent);
-        }
-        SolveResult::SolvedPoint(vp) => {
-          if let Err(err) = virt_points.insert(ent, vp) {
+    // Then process them, the parts at the same time when there are several
+    let mut unsolved = HashMap::new();
+    #[cfg(feature = "parallel")]
+    let parts = if parts.len() > 1 {
+      unsolved = solve_in_parallel(parts, &symbols, &scalar_expressions, &mut virt_shapes);
+      vec![]
+    } else {
+      parts
+    };
+    for part in parts {
+      unsolved.extend(solve_stack(
+        part,
+        &symbols,
+        &scalar_expressions,
+        &mut virt_shapes.solved(),
+      ));
+    }
+
+    // Finally mark the ones that could not be solved, with a parent requested on the way among
+    // them, and unmark the dirty ones that got solved. Only the changes are reported
+    let mut reported = dirty.iter().chain(unsolved.keys()).copied().collect::<Vec<_>>();
+    reported.sort_by_key(|ent| ent.id());
+    reported.dedup();
+    for ent in reported {
+      let before = unsolvables.get(ent).map(|unsolvable| unsolvable.0);
+      match unsolved.get(&ent) {
+        Some(reason) if before != Some(*reason) => {
+          if let Err(err) = unsolvables.insert(ent, Unsolvable(*reason)) {
             panic!(err)
           }
+          diagnostic_event_channel.single_write(DiagnosticEvent::Unsolvable(ent, *reason));
         }
-        SolveResult::SolvedLine(vl) => {
-          if let Err(err) = virt_lines.insert(ent, vl) {
-            panic!(err)
-          }
+        None if before.is_some() => {
+          unsolvables.remove(ent);
+          diagnostic_event_channel.single_write(DiagnosticEvent::Solvable(ent));
         }
-        SolveResult::SolvedCircle(vc) => {
-          if let Err(err) = virt_circles.insert(ent, vc) {
-            panic!(err)
-          }
+        _ => (),
+      }
+    }
+  }
+}
+
+/// The solved shapes of one kind, read by the solve functions and given what they solve
+trait Solved<T> {
+  fn get(&self, ent: Entity) -> Option<&T>;
+
+  fn insert(&mut self, ent: Entity, shape: T);
+
+  fn contains(&self, ent: Entity) -> bool {
+    self.get(ent).is_some()
+  }
+}
+
+impl<'a, T: Component> Solved<T> for WriteStorage<'a, T> {
+  fn get(&self, ent: Entity) -> Option<&T> {
+    Storage::get(self, ent)
+  }
+
+  fn insert(&mut self, ent: Entity, shape: T) {
+    if let Err(err) = Storage::insert(self, ent, shape) {
+      panic!(err)
+    }
+  }
+}
+
+/// The shapes solved by one worker thread, in front of the ones solved before the workers started
+#[cfg(feature = "parallel")]
+struct Overlay<'s, T> {
+  solved: HashMap<Entity, T>,
+  below: &'s (dyn Solved<T> + Sync),
+}
+
+#[cfg(feature = "parallel")]
+impl<'s, T> Overlay<'s, T> {
+  fn new(below: &'s (dyn Solved<T> + Sync)) -> Self {
+    Self {
+      solved: HashMap::new(),
+      below,
+    }
+  }
+}
+
+#[cfg(feature = "parallel")]
+impl<'s, T> Solved<T> for Overlay<'s, T> {
+  fn get(&self, ent: Entity) -> Option<&T> {
+    self.solved.get(&ent).or_else(|| self.below.get(ent))
+  }
+
+  fn insert(&mut self, ent: Entity, shape: T) {
+    self.solved.insert(ent, shape);
+  }
+}
+
+/// The solved shapes of each kind, read by the solve functions and given what they solve
+struct SolvedShapes<'s> {
+  points: &'s mut dyn Solved<VirtualPoint>,
+  lines: &'s mut dyn Solved<VirtualLine>,
+  circles: &'s mut dyn Solved<VirtualCircle>,
+  arcs: &'s mut dyn Solved<VirtualArc>,
+  conics: &'s mut dyn Solved<VirtualConic>,
+  polygons: &'s mut dyn Solved<VirtualPolygon>,
+  vectors: &'s mut dyn Solved<VirtualVector>,
+  texts: &'s mut dyn Solved<VirtualText>,
+}
+
+impl<'a> VirtualShapes<'a> {
+  fn solved(&mut self) -> SolvedShapes<'_> {
+    SolvedShapes {
+      points: &mut self.points,
+      lines: &mut self.lines,
+      circles: &mut self.circles,
+      arcs: &mut self.arcs,
+      conics: &mut self.conics,
+      polygons: &mut self.polygons,
+      vectors: &mut self.vectors,
+      texts: &mut self.texts,
+    }
+  }
+}
+
+/// What a worker thread solved of each kind
+#[cfg(feature = "parallel")]
+struct PartSolution {
+  points: HashMap<Entity, VirtualPoint>,
+  lines: HashMap<Entity, VirtualLine>,
+  circles: HashMap<Entity, VirtualCircle>,
+  arcs: HashMap<Entity, VirtualArc>,
+  conics: HashMap<Entity, VirtualConic>,
+  polygons: HashMap<Entity, VirtualPolygon>,
+  vectors: HashMap<Entity, VirtualVector>,
+  texts: HashMap<Entity, VirtualText>,
+  unsolved: HashMap<Entity, UnsolvableReason>,
+}
+
+/// Solves each part on the rayon worker threads, reading the shapes solved before from the
+/// storages, then puts what the workers solved in them
+#[cfg(feature = "parallel")]
+fn solve_in_parallel(
+  parts: Vec<Vec<ToCompute>>,
+  symbols: &Symbols,
+  scalar_expressions: &ScalarExpressions,
+  virt_shapes: &mut VirtualShapes,
+) -> HashMap<Entity, UnsolvableReason> {
+  use rayon::prelude::*;
+
+  let solutions = {
+    let below = &*virt_shapes;
+    parts
+      .into_par_iter()
+      .map(|part| {
+        let (mut points, mut lines) = (Overlay::new(&below.points), Overlay::new(&below.lines));
+        let (mut circles, mut arcs) = (Overlay::new(&below.circles), Overlay::new(&below.arcs));
+        let (mut conics, mut polygons) = (Overlay::new(&below.conics), Overlay::new(&below.polygons));
+        let (mut vectors, mut texts) = (Overlay::new(&below.vectors), Overlay::new(&below.texts));
+        let unsolved = solve_stack(
+          part,
+          symbols,
+          scalar_expressions,
+          &mut SolvedShapes {
+            points: &mut points,
+            lines: &mut lines,
+            circles: &mut circles,
+            arcs: &mut arcs,
+            conics: &mut conics,
+            polygons: &mut polygons,
+            vectors: &mut vectors,
+            texts: &mut texts,
+          },
+        );
+        PartSolution {
+          points: points.solved,
+          lines: lines.solved,
+          circles: circles.solved,
+          arcs: arcs.solved,
+          conics: conics.solved,
+          polygons: polygons.solved,
+          vectors: vectors.solved,
+          texts: texts.solved,
+          unsolved,
         }
-        SolveResult::Request(req_ent) => {
-          if !cannot_compute.contains(&req_ent) {
-            to_process.push(to_comp);
-            to_process.push(ToCompute(
-              req_ent,
-              get_symbol(req_ent, &sym_points, &sym_lines, &sym_circles),
-            ));
-          }
+      })
+      .collect::<Vec<_>>()
+  };
+
+  // A parent requested by several parts is solved by each of them, to the same shape
+  let mut unsolved = HashMap::new();
+  for solution in solutions {
+    merge(&mut virt_shapes.points, solution.points);
+    merge(&mut virt_shapes.lines, solution.lines);
+    merge(&mut virt_shapes.circles, solution.circles);
+    merge(&mut virt_shapes.arcs, solution.arcs);
+    merge(&mut virt_shapes.conics, solution.conics);
+    merge(&mut virt_shapes.polygons, solution.polygons);
+    merge(&mut virt_shapes.vectors, solution.vectors);
+    merge(&mut virt_shapes.texts, solution.texts);
+    unsolved.extend(solution.unsolved);
+  }
+  unsolved
+}
+
+#[cfg(feature = "parallel")]
+fn merge<T>(storage: &mut dyn Solved<T>, solved: HashMap<Entity, T>) {
+  for (ent, shape) in solved {
+    storage.insert(ent, shape);
+  }
+}
+
+/// Solves the entities popped from the end of the stack. A parent that was not dirty but never got
+/// solved is requested on the way. Returns the ones that could not be solved and why, the
+/// dependents of those being given up along with them
+fn solve_stack(
+  mut to_process: Vec<ToCompute>,
+  symbols: &Symbols,
+  scalar_expressions: &ScalarExpressions,
+  shapes: &mut SolvedShapes,
+) -> HashMap<Entity, UnsolvableReason> {
+  let mut cannot_compute = HashMap::new();
+  while let Some(to_comp) = to_process.pop() {
+    let ent = to_comp.0;
+    let sym = to_comp.1.clone();
+    match solve(ent, sym, scalar_expressions, shapes) {
+      SolveResult::AlreadyComputed => (),
+      SolveResult::Undefined => {
+        cannot_compute.insert(ent, UnsolvableReason::Degenerate);
+      }
+      SolveResult::SolvedPoint(vp) => shapes.points.insert(ent, vp),
+      SolveResult::SolvedLine(vl) => shapes.lines.insert(ent, vl),
+      SolveResult::SolvedCircle(vc) => shapes.circles.insert(ent, vc),
+      SolveResult::SolvedArc(va) => shapes.arcs.insert(ent, va),
+      SolveResult::SolvedConic(vc) => shapes.conics.insert(ent, vc),
+      SolveResult::SolvedPolygon(vp) => shapes.polygons.insert(ent, vp),
+      SolveResult::SolvedVector(vv) => shapes.vectors.insert(ent, vv),
+      SolveResult::SolvedText(vt) => shapes.texts.insert(ent, vt),
+      SolveResult::Request(req_ent) => {
+        if cannot_compute.contains_key(&req_ent) {
+          cannot_compute.insert(ent, UnsolvableReason::UndefinedParent(req_ent));
+        } else {
+          to_process.push(to_comp);
+          to_process.push(ToCompute(req_ent, symbols.get(req_ent)));
         }
       }
     }
   }
+  cannot_compute
 }
 
-fn get_symbol<'a>(
-  ent: Entity,
-  sym_points: &ReadStorage<'a, SymbolicPoint>,
-  sym_lines: &ReadStorage<'a, SymbolicLine>,
-  sym_circles: &ReadStorage<'a, SymbolicCircle>,
-) -> GeometrySymbol {
-  if let Some(sym_point) = sym_points.get(ent) {
-    GeometrySymbol::Point(*sym_point)
-  } else if let Some(sym_line) = sym_lines.get(ent) {
-    GeometrySymbol::Line(*sym_line)
-  } else if let Some(sym_circle) = sym_circles.get(ent) {
-    GeometrySymbol::Circle(*sym_circle)
-  } else {
-    panic!("Cannot find symbol");
+impl<'a> Symbols<'a> {
+  fn get(&self, ent: Entity) -> GeometrySymbol {
+    if let Some(sym_point) = self.points.get(ent) {
+      GeometrySymbol::Point(*sym_point)
+    } else if let Some(sym_line) = self.lines.get(ent) {
+      GeometrySymbol::Line(*sym_line)
+    } else if let Some(sym_circle) = self.circles.get(ent) {
+      GeometrySymbol::Circle(*sym_circle)
+    } else if let Some(sym_arc) = self.arcs.get(ent) {
+      GeometrySymbol::Arc(*sym_arc)
+    } else if let Some(sym_conic) = self.conics.get(ent) {
+      GeometrySymbol::Conic(*sym_conic)
+    } else if let Some(sym_polygon) = self.polygons.get(ent) {
+      GeometrySymbol::Polygon(sym_polygon.clone())
+    } else if let Some(sym_vector) = self.vectors.get(ent) {
+      GeometrySymbol::Vector(*sym_vector)
+    } else if let Some(sym_text) = self.texts.get(ent) {
+      GeometrySymbol::Text(sym_text.clone())
+    } else if let Some(measurement) = self.measurements.get(ent) {
+      GeometrySymbol::Measurement(*measurement)
+    } else {
+      panic!("Cannot find symbol");
+    }
   }
 }
 
-fn solve<'a>(
+fn solve(
   ent: Entity,
   sym: GeometrySymbol,
-  virt_points: &WriteStorage<'a, VirtualPoint>,
-  virt_lines: &WriteStorage<'a, VirtualLine>,
-  virt_circles: &WriteStorage<'a, VirtualCircle>,
+  scalar_expressions: &ScalarExpressions,
+  shapes: &SolvedShapes,
 ) -> SolveResult {
   match sym {
-    GeometrySymbol::Point(sym_point) => solve_point(ent, sym_point, &virt_points, &virt_lines, &virt_circles),
-    GeometrySymbol::Line(sym_line) => solve_line(ent, sym_line, &virt_points, &virt_lines, &virt_circles),
-    GeometrySymbol::Circle(sym_circle) => solve_circle(ent, sym_circle, &virt_points, &virt_lines, &virt_circles),
+    GeometrySymbol::Point(sym_point) => solve_point(ent, sym_point, scalar_expressions, shapes),
+    GeometrySymbol::Line(sym_line) => solve_line(ent, sym_line, shapes),
+    GeometrySymbol::Circle(sym_circle) => solve_circle(ent, sym_circle, scalar_expressions, shapes),
+    GeometrySymbol::Arc(sym_arc) => solve_arc(ent, sym_arc, shapes),
+    GeometrySymbol::Conic(sym_conic) => solve_conic(ent, sym_conic, shapes),
+    GeometrySymbol::Polygon(sym_polygon) => solve_polygon(ent, sym_polygon, shapes),
+    GeometrySymbol::Vector(sym_vector) => solve_vector(ent, sym_vector, shapes),
+    GeometrySymbol::Text(sym_text) => solve_text(ent, sym_text, shapes),
+    GeometrySymbol::Measurement(_) => SolveResult::AlreadyComputed, // Measured by the measurement solver
+    GeometrySymbol::Slider(_) => SolveResult::AlreadyComputed,      // Placed by the user
+    GeometrySymbol::Underlay(_) => SolveResult::AlreadyComputed,    // Placed by the user
+    GeometrySymbol::Freehand(_) => SolveResult::AlreadyComputed,    // Drawn by the user
   }
 }
 
-fn solve_point<'a>(
+fn solve_point(
   ent: Entity,
   sym_point: SymbolicPoint,
-  virt_points: &WriteStorage<'a, VirtualPoint>,
-  virt_lines: &WriteStorage<'a, VirtualLine>,
-  virt_circles: &WriteStorage<'a, VirtualCircle>,
+  scalar_expressions: &ScalarExpressions,
+  shapes: &SolvedShapes,
 ) -> SolveResult {
-  if virt_points.contains(ent) {
+  if shapes.points.contains(ent) {
     SolveResult::AlreadyComputed
   } else {
     match sym_point {
       SymbolicPoint::Fixed(pos) => SolveResult::SolvedPoint(pos),
       SymbolicPoint::Free(pos) => SolveResult::SolvedPoint(pos),
-      SymbolicPoint::MidPoint(p1_ent, p2_ent) => match virt_points.get(p1_ent) {
-        Some(&vp1) => match virt_points.get(p2_ent) {
+      SymbolicPoint::MidPoint(p1_ent, p2_ent) => match shapes.points.get(p1_ent) {
+        Some(&vp1) => match shapes.points.get(p2_ent) {
           Some(&vp2) => SolveResult::SolvedPoint((vp1 + vp2) / 2.0.into()),
           None => SolveResult::Request(p2_ent),
         },
         None => SolveResult::Request(p1_ent),
       },
-      SymbolicPoint::OnLine(l_ent, t) => match virt_lines.get(l_ent) {
+      SymbolicPoint::Ratio(p1_ent, p2_ent, ratio) => match shapes.points.get(p1_ent) {
+        Some(&vp1) => match shapes.points.get(p2_ent) {
+          Some(&vp2) => SolveResult::SolvedPoint(vp1 + (vp2 - vp1) * ratio.into()),
+          None => SolveResult::Request(p2_ent),
+        },
+        None => SolveResult::Request(p1_ent),
+      },
+      SymbolicPoint::OnLine(l_ent, t) => match shapes.lines.get(l_ent) {
         Some(VirtualLine { from, to, .. }) => SolveResult::SolvedPoint(*from + (*to - *from) * t),
         None => SolveResult::Request(l_ent),
       },
-      SymbolicPoint::LineLineIntersect(l1_ent, l2_ent) => match virt_lines.get(l1_ent) {
-        Some(&vl1) => match virt_lines.get(l2_ent) {
+      SymbolicPoint::LineLineIntersect(l1_ent, l2_ent) => match shapes.lines.get(l1_ent) {
+        Some(&vl1) => match shapes.lines.get(l2_ent) {
           Some(&vl2) => match vl1.intersect(vl2) {
             Some(p) => SolveResult::SolvedPoint(p),
             None => SolveResult::Undefined,
@@ -201,12 +546,19 @@ fn solve_point<'a>(
         },
         None => SolveResult::Request(l1_ent),
       },
-      SymbolicPoint::OnCircle(c_ent, theta) => match virt_circles.get(c_ent) {
+      SymbolicPoint::OnCircle(c_ent, theta) => match shapes.circles.get(c_ent) {
         Some(c) => SolveResult::SolvedPoint(c.center + VirtualPosition(vec2![theta.cos(), theta.sin()]) * c.radius),
         None => SolveResult::Request(c_ent),
       },
-      SymbolicPoint::CircleLineIntersect(c_ent, l_ent, ity) => match virt_circles.get(c_ent) {
-        Some(&c) => match virt_lines.get(l_ent) {
+      SymbolicPoint::OnConic(c_ent, t) => match shapes.conics.get(c_ent) {
+        Some(&c) => {
+          let e: Ellipse = c.into();
+          SolveResult::SolvedPoint(e.point_at(t).into())
+        }
+        None => SolveResult::Request(c_ent),
+      },
+      SymbolicPoint::CircleLineIntersect(c_ent, l_ent, ity) => match shapes.circles.get(c_ent) {
+        Some(&c) => match shapes.lines.get(l_ent) {
           Some(&l) => match c.intersect(l) {
             VirtualCircleIntersect::TwoPoints(p1, p2) => match ity {
               CircleIntersectId::First => SolveResult::SolvedPoint(p1),
@@ -219,8 +571,8 @@ fn solve_point<'a>(
         },
         None => SolveResult::Request(c_ent),
       },
-      SymbolicPoint::CircleCircleIntersect(c1_ent, c2_ent, ity) => match virt_circles.get(c1_ent) {
-        Some(&c1) => match virt_circles.get(c2_ent) {
+      SymbolicPoint::CircleCircleIntersect(c1_ent, c2_ent, ity) => match shapes.circles.get(c1_ent) {
+        Some(&c1) => match shapes.circles.get(c2_ent) {
           Some(&c2) => match c1.intersect(c2) {
             VirtualCircleIntersect::TwoPoints(p1, p2) => match ity {
               CircleIntersectId::First => SolveResult::SolvedPoint(p1),
@@ -233,56 +585,115 @@ fn solve_point<'a>(
         },
         None => SolveResult::Request(c1_ent),
       },
+      SymbolicPoint::ArcLineIntersect(a_ent, l_ent, ity) => match shapes.arcs.get(a_ent) {
+        Some(&a) => match shapes.lines.get(l_ent) {
+          Some(&l) => match a.intersect(l) {
+            VirtualCircleIntersect::TwoPoints(p1, p2) => match ity {
+              CircleIntersectId::First => SolveResult::SolvedPoint(p1),
+              CircleIntersectId::Second => SolveResult::SolvedPoint(p2),
+            },
+            VirtualCircleIntersect::OnePoint(p) => SolveResult::SolvedPoint(p),
+            VirtualCircleIntersect::None => SolveResult::Undefined,
+          },
+          None => SolveResult::Request(l_ent),
+        },
+        None => SolveResult::Request(a_ent),
+      },
+      SymbolicPoint::ArcCircleIntersect(a_ent, c_ent, ity) => match shapes.arcs.get(a_ent) {
+        Some(&a) => match shapes.circles.get(c_ent) {
+          Some(&c) => match a.intersect(c) {
+            VirtualCircleIntersect::TwoPoints(p1, p2) => match ity {
+              CircleIntersectId::First => SolveResult::SolvedPoint(p1),
+              CircleIntersectId::Second => SolveResult::SolvedPoint(p2),
+            },
+            VirtualCircleIntersect::OnePoint(p) => SolveResult::SolvedPoint(p),
+            VirtualCircleIntersect::None => SolveResult::Undefined,
+          },
+          None => SolveResult::Request(c_ent),
+        },
+        None => SolveResult::Request(a_ent),
+      },
+      SymbolicPoint::PointReflection(source_ent, center_ent) => match shapes.points.get(source_ent) {
+        // The half turn of the source around the center
+        Some(&source) => match shapes.points.get(center_ent) {
+          Some(&center) => SolveResult::SolvedPoint(VirtualPosition(center.0 * 2.0 - source.0)),
+          None => SolveResult::Request(center_ent),
+        },
+        None => SolveResult::Request(source_ent),
+      },
+      SymbolicPoint::Reflect(point_ent, mirror_ent) => match shapes.points.get(point_ent) {
+        Some(&p) => match shapes.lines.get(mirror_ent) {
+          Some(&mirror) => match reflect_across(mirror) {
+            Some(reflect) => SolveResult::SolvedPoint(reflect(p)),
+            None => SolveResult::Undefined,
+          },
+          None => SolveResult::Request(mirror_ent),
+        },
+        None => SolveResult::Request(point_ent),
+      },
+      SymbolicPoint::Rotate(point_ent, center_ent, radians) => match shapes.points.get(point_ent) {
+        Some(&p) => match shapes.points.get(center_ent) {
+          Some(&center) => SolveResult::SolvedPoint(VirtualPosition(center.0 + (p - center).0.rotate(radians))),
+          None => SolveResult::Request(center_ent),
+        },
+        None => SolveResult::Request(point_ent),
+      },
+      SymbolicPoint::RotateByScalar(point_ent, center_ent, scalar) => match shapes.points.get(point_ent) {
+        Some(&p) => match shapes.points.get(center_ent) {
+          Some(&center) => match scalar_expressions.value(scalar) {
+            Some(radians) => SolveResult::SolvedPoint(VirtualPosition(center.0 + (p - center).0.rotate(radians))),
+            None => SolveResult::Undefined,
+          },
+          None => SolveResult::Request(center_ent),
+        },
+        None => SolveResult::Request(point_ent),
+      },
+      SymbolicPoint::Translate(point_ent, vector) => match shapes.points.get(point_ent) {
+        Some(&p) => SolveResult::SolvedPoint(p + vector),
+        None => SolveResult::Request(point_ent),
+      },
     }
   }
 }
 
-fn solve_line<'a>(
-  ent: Entity,
-  sym_line: SymbolicLine,
-  virt_points: &WriteStorage<'a, VirtualPoint>,
-  virt_lines: &WriteStorage<'a, VirtualLine>,
-  _virt_circles: &WriteStorage<'a, VirtualCircle>, // Don't need circle for now
-) -> SolveResult {
-  if virt_lines.contains(ent) {
+/// The reflection across the mirror line, if the line has a direction to reflect across
+fn reflect_across(mirror: VirtualLine) -> Option<impl Fn(VirtualPosition) -> VirtualPosition> {
+  let mirror: Line = mirror.into();
+  if mirror.from_to_length() == 0.0 {
+    None
+  } else {
+    Some(move |p: VirtualPosition| VirtualPosition(mirror.reflect(p.0)))
+  }
+}
+
+fn solve_line(ent: Entity, sym_line: SymbolicLine, shapes: &SolvedShapes) -> SolveResult {
+  if shapes.lines.contains(ent) {
     SolveResult::AlreadyComputed
   } else {
     match sym_line {
-      SymbolicLine::Straight(p1_ent, p2_ent) => match virt_points.get(p1_ent) {
-        Some(&p1) => match virt_points.get(p2_ent) {
-          Some(&p2) => SolveResult::SolvedLine(VirtualLine {
-            from: p1,
-            to: p2,
-            line_type: LineType::Straight,
-          }),
+      SymbolicLine::Straight(p1_ent, p2_ent) => match shapes.points.get(p1_ent) {
+        Some(&p1) => match shapes.points.get(p2_ent) {
+          Some(&p2) => SolveResult::SolvedLine(Line::from_two_points(p1.0, p2.0, LineType::Straight).into()),
           None => SolveResult::Request(p2_ent),
         },
         None => SolveResult::Request(p1_ent),
       },
-      SymbolicLine::Ray(p1_ent, p2_ent) => match virt_points.get(p1_ent) {
-        Some(&p1) => match virt_points.get(p2_ent) {
-          Some(&p2) => SolveResult::SolvedLine(VirtualLine {
-            from: p1,
-            to: p2,
-            line_type: LineType::Ray,
-          }),
+      SymbolicLine::Ray(p1_ent, p2_ent) => match shapes.points.get(p1_ent) {
+        Some(&p1) => match shapes.points.get(p2_ent) {
+          Some(&p2) => SolveResult::SolvedLine(Line::from_two_points(p1.0, p2.0, LineType::Ray).into()),
           None => SolveResult::Request(p2_ent),
         },
         None => SolveResult::Request(p1_ent),
       },
-      SymbolicLine::Segment(p1_ent, p2_ent) => match virt_points.get(p1_ent) {
-        Some(&p1) => match virt_points.get(p2_ent) {
-          Some(&p2) => SolveResult::SolvedLine(VirtualLine {
-            from: p1,
-            to: p2,
-            line_type: LineType::Segment,
-          }),
+      SymbolicLine::Segment(p1_ent, p2_ent) => match shapes.points.get(p1_ent) {
+        Some(&p1) => match shapes.points.get(p2_ent) {
+          Some(&p2) => SolveResult::SolvedLine(Line::from_two_points(p1.0, p2.0, LineType::Segment).into()),
           None => SolveResult::Request(p2_ent),
         },
         None => SolveResult::Request(p1_ent),
       },
-      SymbolicLine::Parallel(l_ent, p_ent) => match virt_lines.get(l_ent) {
-        Some(&l) => match virt_points.get(p_ent) {
+      SymbolicLine::Parallel(l_ent, p_ent) => match shapes.lines.get(l_ent) {
+        Some(&l) => match shapes.points.get(p_ent) {
           Some(&p) => SolveResult::SolvedLine(VirtualLine {
             from: p,
             to: p + (l.to - l.from),
@@ -292,8 +703,8 @@ fn solve_line<'a>(
         },
         None => SolveResult::Request(l_ent),
       },
-      SymbolicLine::Perpendicular(l_ent, p_ent) => match virt_lines.get(l_ent) {
-        Some(&l) => match virt_points.get(p_ent) {
+      SymbolicLine::Perpendicular(l_ent, p_ent) => match shapes.lines.get(l_ent) {
+        Some(&l) => match shapes.points.get(p_ent) {
           Some(&p) => {
             let dir: Vector2 = (l.to - l.from).into();
             let perp_dir: Vector2 = vec2![-dir.y, dir.x];
@@ -307,31 +718,755 @@ fn solve_line<'a>(
         },
         None => SolveResult::Request(l_ent),
       },
+      SymbolicLine::CommonTangent(c1_ent, c2_ent, kind) => match shapes.circles.get(c1_ent) {
+        Some(&c1) => match shapes.circles.get(c2_ent) {
+          Some(&c2) => {
+            let (c1, c2): (Circle, Circle) = (c1.into(), c2.into());
+            match c1.common_tangent(&c2, kind) {
+              Some(l) => SolveResult::SolvedLine(l.into()),
+              None => SolveResult::Undefined,
+            }
+          }
+          None => SolveResult::Request(c2_ent),
+        },
+        None => SolveResult::Request(c1_ent),
+      },
+      SymbolicLine::Tangent(c_ent, p_ent, index) => match shapes.circles.get(c_ent) {
+        Some(&c) => match shapes.points.get(p_ent) {
+          Some(&p) => {
+            let c: Circle = c.into();
+            match c.tangent_through(p.0, index) {
+              Some(l) => SolveResult::SolvedLine(l.into()),
+              None => SolveResult::Undefined,
+            }
+          }
+          None => SolveResult::Request(p_ent),
+        },
+        None => SolveResult::Request(c_ent),
+      },
+      SymbolicLine::PerpendicularBisector(p1_ent, p2_ent) => match shapes.points.get(p1_ent) {
+        Some(&p1) => match shapes.points.get(p2_ent) {
+          Some(&p2) => {
+            let dir: Vector2 = (p2 - p1).into();
+            if dir.is_zero() {
+              SolveResult::Undefined
+            } else {
+              let mid = (p1 + p2) / 2.0.into();
+              SolveResult::SolvedLine(VirtualLine {
+                from: mid,
+                to: mid + vec2![-dir.y, dir.x].into(),
+                line_type: LineType::Straight,
+              })
+            }
+          }
+          None => SolveResult::Request(p2_ent),
+        },
+        None => SolveResult::Request(p1_ent),
+      },
+      SymbolicLine::Reflect(l_ent, mirror_ent) => match shapes.lines.get(l_ent) {
+        // The orientation is kept, so that reflected rays start from the reflected origin
+        Some(&l) => match shapes.lines.get(mirror_ent) {
+          Some(&mirror) => match reflect_across(mirror) {
+            Some(reflect) => SolveResult::SolvedLine(VirtualLine {
+              from: reflect(l.from),
+              to: reflect(l.to),
+              line_type: l.line_type,
+            }),
+            None => SolveResult::Undefined,
+          },
+          None => SolveResult::Request(mirror_ent),
+        },
+        None => SolveResult::Request(l_ent),
+      },
     }
   }
 }
 
-fn solve_circle<'a>(
+fn solve_circle(
   ent: Entity,
   sym_circle: SymbolicCircle,
-  virt_points: &WriteStorage<'a, VirtualPoint>,
-  _virt_lines: &WriteStorage<'a, VirtualLine>, // Don't need virtual lines for now
-  virt_circles: &WriteStorage<'a, VirtualCircle>,
+  scalar_expressions: &ScalarExpressions,
+  shapes: &SolvedShapes,
 ) -> SolveResult {
-  if virt_circles.contains(ent) {
+  if shapes.circles.contains(ent) {
     SolveResult::AlreadyComputed
   } else {
     match sym_circle {
-      SymbolicCircle::CenterRadius(p1_ent, p2_ent) => match virt_points.get(p1_ent) {
-        Some(&p1) => match virt_points.get(p2_ent) {
-          Some(&p2) => SolveResult::SolvedCircle(VirtualCircle {
-            center: p1,
-            radius: (p2 - p1).magnitude(),
+      SymbolicCircle::CenterRadius(p1_ent, p2_ent) => match shapes.points.get(p1_ent) {
+        Some(&p1) => match shapes.points.get(p2_ent) {
+          Some(&p2) => SolveResult::SolvedCircle(Circle::from_center_point(p1.0, p2.0).into()),
+          None => SolveResult::Request(p2_ent),
+        },
+        None => SolveResult::Request(p1_ent),
+      },
+      SymbolicCircle::ScalarRadius(p_ent, scalar) => match shapes.points.get(p_ent) {
+        Some(&p) => match scalar_expressions.value(scalar) {
+          Some(radius) if radius >= 0.0 => SolveResult::SolvedCircle(VirtualCircle {
+            center: p,
+            radius: radius.into(),
           }),
+          _ => SolveResult::Undefined,
+        },
+        None => SolveResult::Request(p_ent),
+      },
+      SymbolicCircle::EqualRadius(p_ent, c_ent) => match shapes.points.get(p_ent) {
+        Some(&p) => match shapes.circles.get(c_ent) {
+          Some(c) => SolveResult::SolvedCircle(VirtualCircle {
+            center: p,
+            radius: c.radius,
+          }),
+          None => SolveResult::Request(c_ent),
+        },
+        None => SolveResult::Request(p_ent),
+      },
+      SymbolicCircle::CompassRadius(p_ent, p1_ent, p2_ent) => match shapes.points.get(p_ent) {
+        Some(&p) => match shapes.points.get(p1_ent) {
+          Some(&p1) => match shapes.points.get(p2_ent) {
+            Some(&p2) => SolveResult::SolvedCircle(VirtualCircle {
+              center: p,
+              radius: (p2 - p1).magnitude(),
+            }),
+            None => SolveResult::Request(p2_ent),
+          },
+          None => SolveResult::Request(p1_ent),
+        },
+        None => SolveResult::Request(p_ent),
+      },
+      SymbolicCircle::ThreePoints(p1_ent, p2_ent, p3_ent) => match shapes.points.get(p1_ent) {
+        Some(&p1) => match shapes.points.get(p2_ent) {
+          Some(&p2) => match shapes.points.get(p3_ent) {
+            Some(&p3) => match Circle::through_points(p1.0, p2.0, p3.0) {
+              Some(circle) => SolveResult::SolvedCircle(circle.into()),
+              None => SolveResult::Undefined,
+            },
+            None => SolveResult::Request(p3_ent),
+          },
           None => SolveResult::Request(p2_ent),
         },
         None => SolveResult::Request(p1_ent),
       },
+      SymbolicCircle::Reflect(c_ent, mirror_ent) => match shapes.circles.get(c_ent) {
+        Some(&c) => match shapes.lines.get(mirror_ent) {
+          Some(&mirror) => match reflect_across(mirror) {
+            Some(reflect) => SolveResult::SolvedCircle(VirtualCircle {
+              center: reflect(c.center),
+              radius: c.radius,
+            }),
+            None => SolveResult::Undefined,
+          },
+          None => SolveResult::Request(mirror_ent),
+        },
+        None => SolveResult::Request(c_ent),
+      },
+    }
+  }
+}
+
+fn solve_arc(ent: Entity, sym_arc: SymbolicArc, shapes: &SolvedShapes) -> SolveResult {
+  if shapes.arcs.contains(ent) {
+    SolveResult::AlreadyComputed
+  } else {
+    let mut positions = [Vector2::zero(); 3];
+    for (position, point_ent) in positions.iter_mut().zip(&sym_arc.points()) {
+      match shapes.points.get(*point_ent) {
+        Some(p) => *position = p.0,
+        None => return SolveResult::Request(*point_ent),
+      }
+    }
+    let [p1, p2, p3] = positions;
+    match sym_arc {
+      SymbolicArc::ThreePoint(_, _, _) => match Arc::through_points(p1, p2, p3) {
+        Some(arc) => SolveResult::SolvedArc(arc.into()),
+        None => SolveResult::Undefined,
+      },
+      SymbolicArc::CenterTwoPoint(_, _, _) => {
+        if (p2 - p1).is_zero() || (p3 - p1).is_zero() {
+          SolveResult::Undefined
+        } else {
+          SolveResult::SolvedArc(Arc::from_center_points(p1, p2, p3).into())
+        }
+      }
+    }
+  }
+}
+
+fn solve_conic(ent: Entity, sym_conic: SymbolicConic, shapes: &SolvedShapes) -> SolveResult {
+  if shapes.conics.contains(ent) {
+    SolveResult::AlreadyComputed
+  } else {
+    let mut positions = vec![];
+    for point_ent in sym_conic.points() {
+      match shapes.points.get(point_ent) {
+        Some(p) => positions.push(p.0),
+        None => return SolveResult::Request(point_ent),
+      }
+    }
+    let ellipse = match sym_conic {
+      SymbolicConic::Ellipse(_, _, _) => Ellipse::from_foci_point(positions[0], positions[1], positions[2]),
+      SymbolicConic::FivePoints(_, _, _, _, _) => {
+        Ellipse::through_points([positions[0], positions[1], positions[2], positions[3], positions[4]])
+      }
+    };
+    match ellipse {
+      Some(ellipse) => SolveResult::SolvedConic(ellipse.into()),
+      None => SolveResult::Undefined,
+    }
+  }
+}
+
+fn solve_polygon(ent: Entity, sym_polygon: SymbolicPolygon, shapes: &SolvedShapes) -> SolveResult {
+  if shapes.polygons.contains(ent) {
+    SolveResult::AlreadyComputed
+  } else {
+    let mut vertices = Vec::with_capacity(sym_polygon.0.len());
+    for vertex_ent in sym_polygon.0 {
+      match shapes.points.get(vertex_ent) {
+        Some(&vertex) => vertices.push(vertex),
+        None => return SolveResult::Request(vertex_ent),
+      }
+    }
+    SolveResult::SolvedPolygon(VirtualPolygon { vertices })
+  }
+}
+
+fn solve_vector(ent: Entity, SymbolicVector(from_ent, to_ent): SymbolicVector, shapes: &SolvedShapes) -> SolveResult {
+  if shapes.vectors.contains(ent) {
+    SolveResult::AlreadyComputed
+  } else {
+    match shapes.points.get(from_ent) {
+      Some(&from) => match shapes.points.get(to_ent) {
+        Some(&to) => SolveResult::SolvedVector(VirtualVector { from, to }),
+        None => SolveResult::Request(to_ent),
+      },
+      None => SolveResult::Request(from_ent),
+    }
+  }
+}
+
+/// A text anchored to a point is placed from the point, to a line from the middle of the two
+/// points defining it and to a circle from its center
+fn solve_text(ent: Entity, sym_text: SymbolicText, shapes: &SolvedShapes) -> SolveResult {
+  if shapes.texts.contains(ent) {
+    SolveResult::AlreadyComputed
+  } else {
+    match sym_text.anchor {
+      TextAnchor::Position(position) => SolveResult::SolvedText(VirtualText {
+        position,
+        text: sym_text.text,
+      }),
+      TextAnchor::Entity(anchor_ent, offset) => {
+        let anchor = if let Some(&point) = shapes.points.get(anchor_ent) {
+          point
+        } else if let Some(line) = shapes.lines.get(anchor_ent) {
+          (line.from + line.to) / VirtualScalar(2.0)
+        } else if let Some(circle) = shapes.circles.get(anchor_ent) {
+          circle.center
+        } else {
+          return SolveResult::Request(anchor_ent);
+        };
+        SolveResult::SolvedText(VirtualText {
+          position: anchor + offset,
+          text: sym_text.text,
+        })
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::screen_shapes::ScreenPoint, test_utils::*};
+
+  fn insert_circle(world: &mut World, dispatcher: &mut Dispatcher, sym_circle: SymbolicCircle) -> Entity {
+    step(
+      world,
+      dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(sym_circle)),
+    );
+    last_inserted::<SymbolicCircle>(world)
+  }
+
+  #[test]
+  fn test_equal_radius_follows_source_circle() {
+    let (mut world, mut dispatcher) = headless();
+
+    let center_a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let on_a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![2., 0.].into()));
+    let circle_a = insert_circle(
+      &mut world,
+      &mut dispatcher,
+      SymbolicCircle::CenterRadius(center_a, on_a),
+    );
+    let center_b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![5., 5.].into()));
+    let circle_b = insert_circle(
+      &mut world,
+      &mut dispatcher,
+      SymbolicCircle::EqualRadius(center_b, circle_a),
+    );
+    let center_c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![-4., 1.].into()));
+    let circle_c = insert_circle(
+      &mut world,
+      &mut dispatcher,
+      SymbolicCircle::EqualRadius(center_c, circle_b),
+    );
+    for circle in &[circle_b, circle_c] {
+      assert_eq!(world.read_storage::<VirtualCircle>().get(*circle).unwrap().radius.0, 2.);
+    }
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        on_a,
+        SymbolicPoint::Free(vec2![2., 0.].into()),
+        SymbolicPoint::Free(vec2![0., 3.].into()),
+      )),
+    );
+    let virt_circles = world.read_storage::<VirtualCircle>();
+    let (b, c) = (virt_circles.get(circle_b).unwrap(), virt_circles.get(circle_c).unwrap());
+    assert_eq!(b.radius.0, 3.);
+    assert_eq!(b.center.0, vec2![5., 5.]);
+    assert_eq!(c.radius.0, 3.);
+    assert_eq!(c.center.0, vec2![-4., 1.]);
+  }
+
+  #[test]
+  fn test_ratio_point_divides_the_way() {
+    let (mut world, mut dispatcher) = headless();
+
+    let p1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let p2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![6., 3.].into()));
+    let third = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Ratio(p1, p2, 1. / 3.));
+    let beyond = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Ratio(p1, p2, 1.5));
+    let position = |world: &World, ent: Entity| world.read_storage::<VirtualPoint>().get(ent).unwrap().0;
+    assert_eq!(position(&world, third), vec2![2., 1.]);
+    assert_eq!(position(&world, beyond), vec2![9., 4.5]);
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        p2,
+        SymbolicPoint::Free(vec2![6., 3.].into()),
+        SymbolicPoint::Free(vec2![-3., 0.].into()),
+      )),
+    );
+    assert_eq!(position(&world, third), vec2![-1., 0.]);
+    assert_eq!(position(&world, beyond), vec2![-4.5, 0.]);
+  }
+
+  #[test]
+  fn test_point_reflection_follows_source_and_center() {
+    let (mut world, mut dispatcher) = headless();
+
+    let source = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 2.].into()));
+    let center = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![3., 3.].into()));
+    let reflection = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::PointReflection(source, center),
+    );
+    let twice = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::PointReflection(reflection, center),
+    );
+    let position = |world: &World, ent: Entity| world.read_storage::<VirtualPoint>().get(ent).unwrap().0;
+    assert_eq!(position(&world, reflection), vec2![5., 4.]);
+    assert_eq!(position(&world, twice), vec2![1., 2.]);
+
+    // Moving either point keeps the center as the midpoint of the source and its reflection
+    let moves = [
+      (source, vec2![1., 2.], vec2![-2., 0.5]),
+      (center, vec2![3., 3.], vec2![0., -1.]),
+    ];
+    for (ent, from, to) in &moves {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::Update(UpdateEvent::UpdatePoint(
+          *ent,
+          SymbolicPoint::Free((*from).into()),
+          SymbolicPoint::Free((*to).into()),
+        )),
+      );
+      let (s, c, r) = (
+        position(&world, source),
+        position(&world, center),
+        position(&world, reflection),
+      );
+      assert_eq!((s + r) / 2.0, c);
+      let (cs, cr) = (s - c, r - c);
+      assert_eq!(cs.x * cr.y - cs.y * cr.x, 0.);
+      assert_eq!(position(&world, twice), s);
+    }
+    assert_eq!(position(&world, reflection), vec2![2., -2.5]);
+  }
+
+  #[test]
+  fn test_tangent_through_point() {
+    let (mut world, mut dispatcher) = headless();
+
+    let center = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let on_circle = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 5.].into()));
+    let circle = insert_circle(
+      &mut world,
+      &mut dispatcher,
+      SymbolicCircle::CenterRadius(center, on_circle),
+    );
+    let p = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![10., 0.].into()));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Tangent(circle, p, 0))),
+    );
+    let tangent = last_inserted::<SymbolicLine>(&world);
+    {
+      let virt_lines = world.read_storage::<VirtualLine>();
+      let l = virt_lines.get(tangent).unwrap();
+      assert_eq!(l.from.0, vec2![10., 0.]);
+      assert!((l.to.0 - vec2![2.5, 2.5 * 3f64.sqrt()]).magnitude() < 1e-12);
+    }
+
+    // Inside the circle there's no tangent
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePoint(
+        p,
+        SymbolicPoint::Free(vec2![10., 0.].into()),
+        SymbolicPoint::Free(vec2![1., 0.].into()),
+      )),
+    );
+    assert!(world.read_storage::<VirtualLine>().get(tangent).is_none());
+
+    // Removing the circle removes the tangent along with it
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Remove(RemoveEvent::Remove(circle)),
+    );
+    assert!(world.read_storage::<SymbolicLine>().get(tangent).is_none());
+    assert!(world.read_storage::<SymbolicPoint>().get(p).is_some());
+  }
+
+  #[test]
+  fn test_parallel_lines_mark_their_intersection_and_its_dependents_unsolvable() {
+    let (mut world, mut dispatcher) = headless();
+    let mut reader = world.fetch_mut::<DiagnosticEventChannel>().register_reader();
+
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![4., 0.].into()));
+    let c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 2.].into()));
+    let d = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![4., 4.].into()));
+    let mut lines = vec![];
+    for (from, to) in &[(a, b), (c, d)] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(*from, *to))),
+      );
+      lines.push(last_inserted::<SymbolicLine>(&world));
+    }
+    let x = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::LineLineIntersect(lines[0], lines[1]),
+    );
+    let mid = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(x, a));
+    let quarter = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(mid, a));
+    assert_eq!(
+      world.read_storage::<VirtualPoint>().get(quarter).unwrap().0,
+      vec2![-1., 0.]
+    );
+
+    // The lines become parallel, the intersection and the points built on it are given up. They
+    // are still drawn where they last were
+    let move_d = |from: Vector2, to: Vector2| {
+      Command::Update(UpdateEvent::UpdatePoint(
+        d,
+        SymbolicPoint::Free(from.into()),
+        SymbolicPoint::Free(to.into()),
+      ))
+    };
+    step(&mut world, &mut dispatcher, move_d(vec2![4., 4.], vec2![4., 2.]));
+    let reasons = [
+      (x, UnsolvableReason::Degenerate),
+      (mid, UnsolvableReason::UndefinedParent(x)),
+      (quarter, UnsolvableReason::UndefinedParent(mid)),
+    ];
+    for (ent, reason) in &reasons {
+      assert_eq!(world.read_storage::<Unsolvable>().get(*ent), Some(&Unsolvable(*reason)));
+      assert!(world.read_storage::<VirtualPoint>().get(*ent).is_none());
+      assert!(world.read_storage::<ScreenPoint>().get(*ent).is_some());
+    }
+    let events = world
+      .fetch::<DiagnosticEventChannel>()
+      .read(&mut reader)
+      .cloned()
+      .collect::<Vec<_>>();
+    let expected = reasons
+      .iter()
+      .map(|(ent, reason)| DiagnosticEvent::Unsolvable(*ent, *reason))
+      .collect::<Vec<_>>();
+    assert_eq!(events, expected);
+
+    // Panning moves them along with the rest of the sketch
+    let before = (
+      *world.fetch::<Viewport>(),
+      *world.read_storage::<ScreenPoint>().get(x).unwrap(),
+    );
+    world
+      .fetch_mut::<ViewportEventChannel>()
+      .single_write(ViewportEvent::Move(vec2![1., 0.]));
+    dispatcher.dispatch(&world);
+    world.maintain();
+    let after = (
+      *world.fetch::<Viewport>(),
+      *world.read_storage::<ScreenPoint>().get(x).unwrap(),
+    );
+    assert!((after.1 .0 - before.1 .0).magnitude() > 1.);
+    assert!((after.1.to_virtual(&after.0).0 - before.1.to_virtual(&before.0).0).magnitude() < 1e-9);
+
+    // Still parallel, nothing changes
+    step(&mut world, &mut dispatcher, move_d(vec2![4., 2.], vec2![6., 2.]));
+    assert_eq!(world.fetch::<DiagnosticEventChannel>().read(&mut reader).count(), 0);
+
+    // Apart again, they are all solved
+    step(&mut world, &mut dispatcher, move_d(vec2![6., 2.], vec2![4., 4.]));
+    assert_eq!(world.read_storage::<Unsolvable>().join().count(), 0);
+    assert_eq!(
+      world.read_storage::<VirtualPoint>().get(quarter).unwrap().0,
+      vec2![-1., 0.]
+    );
+    assert!(world.read_storage::<ScreenPoint>().get(quarter).is_some());
+    let events = world
+      .fetch::<DiagnosticEventChannel>()
+      .read(&mut reader)
+      .cloned()
+      .collect::<Vec<_>>();
+    assert_eq!(
+      events,
+      vec![
+        DiagnosticEvent::Solvable(x),
+        DiagnosticEvent::Solvable(mid),
+        DiagnosticEvent::Solvable(quarter),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_dependents_of_an_unsolvable_parent_are_unsolvable() {
+    let (mut world, mut dispatcher) = headless();
+
+    // Two parallel lines, their intersection is undefined from the start
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![4., 0.].into()));
+    let c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 2.].into()));
+    let d = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![4., 2.].into()));
+    let mut lines = vec![];
+    for (from, to) in &[(a, b), (c, d)] {
+      step(
+        &mut world,
+        &mut dispatcher,
+        Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(*from, *to))),
+      );
+      lines.push(last_inserted::<SymbolicLine>(&world));
+    }
+    let x = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::LineLineIntersect(lines[0], lines[1]),
+    );
+    assert_eq!(
+      world.read_storage::<Unsolvable>().get(x),
+      Some(&Unsolvable(UnsolvableReason::Degenerate))
+    );
+
+    // A segment and a circle on it are inserted undefined as well, because of it
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(x, a))),
+    );
+    let segment = last_inserted::<SymbolicLine>(&world);
+    let circle = insert_circle(&mut world, &mut dispatcher, SymbolicCircle::CenterRadius(x, c));
+    for ent in &[segment, circle] {
+      assert_eq!(
+        world.read_storage::<Unsolvable>().get(*ent),
+        Some(&Unsolvable(UnsolvableReason::UndefinedParent(x)))
+      );
+    }
+    assert!(world.read_storage::<VirtualLine>().get(segment).is_none());
+    assert!(world.read_storage::<VirtualCircle>().get(circle).is_none());
+    assert_eq!(
+      world.read_storage::<Unsolvable>().get(x),
+      Some(&Unsolvable(UnsolvableReason::Degenerate))
+    );
+  }
+
+  #[test]
+  fn test_updates_in_one_frame_solve_each_dependent_after_its_parents() {
+    let (mut world, mut dispatcher) = headless();
+
+    // b depends on a and e, c on a and b, d on b and c. f is left alone
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let e = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![8., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, e));
+    let c = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, b));
+    let d = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(b, c));
+    let f = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Free(vec2![1., 1.].into()));
+    assert_eq!(world.read_storage::<VirtualPoint>().get(d).unwrap().0, vec2![3., 0.]);
+
+    // Both free points move before the solver runs
+    for (ent, from, to) in &[(a, vec2![0., 0.], vec2![0., 8.]), (e, vec2![8., 0.], vec2![8., 8.])] {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+        command: Command::Update(UpdateEvent::UpdatePoint(
+          *ent,
+          SymbolicPoint::Free((*from).into()),
+          SymbolicPoint::Free((*to).into()),
+        )),
+        event_id: None,
+      });
+    }
+    dispatcher.dispatch(&world);
+    world.maintain();
+
+    let virt_points = world.read_storage::<VirtualPoint>();
+    assert_eq!(virt_points.get(b).unwrap().0, vec2![4., 8.]);
+    assert_eq!(virt_points.get(c).unwrap().0, vec2![2., 8.]);
+    assert_eq!(virt_points.get(d).unwrap().0, vec2![3., 8.]);
+    assert_eq!(virt_points.get(f).unwrap().0, vec2![1., 1.]);
+  }
+
+  /// Independent copies of a point, a circle around it and the lines and points built on them
+  #[cfg(feature = "parallel")]
+  fn build_clusters(solver: &mut VirtualShapeSolver) -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    System::setup(solver, &mut world);
+    let point_style = DefaultPointStyle::default().get();
+    let line_style = DefaultLineStyle::default().get();
+    let circle_style = DefaultCircleStyle::default().get();
+
+    let mut free_points = vec![];
+    for i in 0..8 {
+      let x = i as f64 * 10.;
+      let a = insert(
+        &mut world,
+        Geometry::Point(SymbolicPoint::Free(vec2![x, 0.].into()), point_style),
+        &[],
+      );
+      let b = insert(
+        &mut world,
+        Geometry::Point(SymbolicPoint::Free(vec2![x + 4., 2.].into()), point_style),
+        &[],
+      );
+      let mid = insert(
+        &mut world,
+        Geometry::Point(SymbolicPoint::MidPoint(a, b), point_style),
+        &[a, b],
+      );
+      let line = insert(
+        &mut world,
+        Geometry::Line(SymbolicLine::Straight(a, b), line_style),
+        &[a, b],
+      );
+      let bisector = insert(
+        &mut world,
+        Geometry::Line(SymbolicLine::Perpendicular(line, mid), line_style),
+        &[line, mid],
+      );
+      let circle = insert(
+        &mut world,
+        Geometry::Circle(SymbolicCircle::CenterRadius(mid, b), circle_style),
+        &[mid, b],
+      );
+      for id in &[CircleIntersectId::First, CircleIntersectId::Second] {
+        let sym_point = SymbolicPoint::CircleLineIntersect(circle, bisector, *id);
+        insert(&mut world, Geometry::Point(sym_point, point_style), &[circle, bisector]);
+      }
+      free_points.push(a);
+      free_points.push(b);
+    }
+    solver.run_now(&world);
+    (world, free_points)
+  }
+
+  #[cfg(feature = "parallel")]
+  fn insert(world: &mut World, geometry: Geometry, parents: &[Entity]) -> Entity {
+    let ent = match &geometry {
+      Geometry::Point(sym_point, _) => world.create_entity().with(*sym_point).build(),
+      Geometry::Line(sym_line, _) => world.create_entity().with(*sym_line).build(),
+      Geometry::Circle(sym_circle, _) => world.create_entity().with(*sym_circle).build(),
+      _ => unreachable!(),
+    };
+    for parent in parents {
+      world.fetch_mut::<DependencyGraph>().add(parent, &ent);
+    }
+    world
+      .fetch_mut::<GeometryEventChannel>()
+      .single_write(GeometryEvent::inserted(ent, geometry));
+    ent
+  }
+
+  /// The points, the ends of the lines and the centers and radii of the circles, by entity id
+  #[cfg(feature = "parallel")]
+  type SolvedShapes = (Vec<(u32, VirtualPoint)>, Vec<(u32, [f64; 4])>, Vec<(u32, [f64; 3])>);
+
+  #[cfg(feature = "parallel")]
+  fn solved_shapes(world: &World) -> SolvedShapes {
+    let entities = world.entities();
+    let points = (&entities, &world.read_storage::<VirtualPoint>())
+      .join()
+      .map(|(ent, p)| (ent.id(), *p))
+      .collect();
+    let lines = (&entities, &world.read_storage::<VirtualLine>())
+      .join()
+      .map(|(ent, l)| (ent.id(), [l.from.0.x, l.from.0.y, l.to.0.x, l.to.0.y]))
+      .collect();
+    let circles = (&entities, &world.read_storage::<VirtualCircle>())
+      .join()
+      .map(|(ent, c)| (ent.id(), [c.center.0.x, c.center.0.y, c.radius.0]))
+      .collect();
+    (points, lines, circles)
+  }
+
+  #[cfg(feature = "parallel")]
+  #[test]
+  fn test_parallel_solver_matches_serial_one() {
+    let mut serial_solver = VirtualShapeSolver::serial();
+    let mut parallel_solver = VirtualShapeSolver::default();
+    let (serial_world, free_points) = build_clusters(&mut serial_solver);
+    let (parallel_world, _) = build_clusters(&mut parallel_solver);
+    let (points, lines, circles) = solved_shapes(&serial_world);
+    assert_eq!(points.len(), 40);
+    assert_eq!(lines.len(), 16);
+    assert_eq!(circles.len(), 8);
+    assert_eq!(solved_shapes(&parallel_world), (points, lines, circles));
+
+    // Points of three of the clusters move in the same frame, and one goes back where it was
+    let moves = [
+      (0, vec2![1., 3.]),
+      (5, vec2![50., -2.]),
+      (11, vec2![54., 2.]),
+      (11, vec2![100., 1.]),
+    ];
+    for world in &[&serial_world, &parallel_world] {
+      for (i, position) in &moves {
+        let ent = free_points[*i];
+        let new_sym_point = SymbolicPoint::Free((*position).into());
+        let old_sym_point = *world.read_storage::<SymbolicPoint>().get(ent).unwrap();
+        if let Err(err) = world.write_storage::<SymbolicPoint>().insert(ent, new_sym_point) {
+          panic!(err)
+        }
+        world
+          .fetch_mut::<GeometryEventChannel>()
+          .single_write(GeometryEvent::point_updated(ent, old_sym_point, new_sym_point));
+      }
     }
+    serial_solver.run_now(&serial_world);
+    parallel_solver.run_now(&parallel_world);
+    assert_eq!(solved_shapes(&parallel_world), solved_shapes(&serial_world));
   }
 }
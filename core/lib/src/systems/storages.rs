@@ -0,0 +1,34 @@
+/// Declares a struct of storages fetched together, as a part of the data of a system. Passed around
+/// as one instead of a parameter per storage
+macro_rules! storages {
+  ($(#[$attr:meta])* $name:ident { $($field:ident: $storage:ty,)* }) => {
+    $(#[$attr])*
+    pub struct $name<'a> {
+      $($field: $storage,)*
+    }
+
+    impl<'a> specs::SystemData<'a> for $name<'a> {
+      fn setup(world: &mut specs::World) {
+        $(<$storage as specs::SystemData<'a>>::setup(world);)*
+      }
+
+      fn fetch(world: &'a specs::World) -> Self {
+        Self {
+          $($field: <$storage as specs::SystemData<'a>>::fetch(world),)*
+        }
+      }
+
+      fn reads() -> Vec<specs::shred::ResourceId> {
+        let mut reads = vec![];
+        $(reads.extend(<$storage as specs::SystemData<'a>>::reads());)*
+        reads
+      }
+
+      fn writes() -> Vec<specs::shred::ResourceId> {
+        let mut writes = vec![];
+        $(writes.extend(<$storage as specs::SystemData<'a>>::writes());)*
+        writes
+      }
+    }
+  };
+}
@@ -0,0 +1,61 @@
+//! Helpers for the tests driving the systems through the command channel
+use crate::{components::symbolics::*, events::*, setup_core_lib};
+use specs::prelude::*;
+
+/// The world and the dispatcher of the core library, without any frontend
+pub fn headless<'a, 'b>() -> (World, Dispatcher<'a, 'b>) {
+  headless_with(|_| ())
+}
+
+/// Like `headless`, with the systems under test added after the ones of the core library
+pub fn headless_with<'a, 'b>(add_systems: impl FnOnce(&mut DispatcherBuilder<'a, 'b>)) -> (World, Dispatcher<'a, 'b>) {
+  let mut world = World::new();
+  let mut builder = DispatcherBuilder::new();
+  setup_core_lib(&mut builder);
+  add_systems(&mut builder);
+  let mut dispatcher = builder.build();
+  dispatcher.setup(&mut world);
+  (world, dispatcher)
+}
+
+/// Runs the dispatcher for the given number of frames without sending anything
+pub fn dispatch(world: &mut World, dispatcher: &mut Dispatcher, times: usize) {
+  for _ in 0..times {
+    dispatcher.dispatch(world);
+    world.maintain();
+  }
+}
+
+/// Sends the command then runs the dispatcher for one frame
+pub fn step(world: &mut World, dispatcher: &mut Dispatcher, command: Command) {
+  world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+    command,
+    event_id: None,
+  });
+  dispatch(world, dispatcher, 1);
+}
+
+/// Sends the history event then runs two frames, the commands it issues are handled on the second
+pub fn history(world: &mut World, dispatcher: &mut Dispatcher, event: HistoryEvent) {
+  world.fetch_mut::<HistoryEventChannel>().single_write(event);
+  dispatch(world, dispatcher, 2);
+}
+
+/// Inserts the point and returns its entity
+pub fn insert_point(world: &mut World, dispatcher: &mut Dispatcher, sym_point: SymbolicPoint) -> Entity {
+  step(
+    world,
+    dispatcher,
+    Command::PointInsert(InsertPointEvent::InsertPoint(sym_point)),
+  );
+  last_inserted::<SymbolicPoint>(world)
+}
+
+/// The entity created last among the ones with the component
+pub fn last_inserted<T: Component>(world: &World) -> Entity {
+  (&world.entities(), &world.read_storage::<T>())
+    .join()
+    .map(|(ent, _)| ent)
+    .max_by_key(|ent| ent.id())
+    .unwrap()
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::SystemTime;
+#[cfg(target_arch = "wasm32")]
+pub use web_time::SystemTime;
+
+/// A point in time to measure the time elapsed since, like `std::time::Instant` whose `now` panics
+/// in the browser. There the time comes from `Date.now()`
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instant(std::time::Instant);
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Instant(f64); // Milliseconds since the epoch
+
+impl Instant {
+  #[cfg(not(target_arch = "wasm32"))]
+  #[allow(clippy::disallowed_methods)] // The only place reading the clock of the standard library
+  pub fn now() -> Self {
+    Self(std::time::Instant::now())
+  }
+
+  #[cfg(target_arch = "wasm32")]
+  pub fn now() -> Self {
+    Self(js_sys::Date::now())
+  }
+
+  /// Zero when the earlier one is actually later
+  #[cfg(not(target_arch = "wasm32"))]
+  pub fn duration_since(&self, earlier: Instant) -> Duration {
+    self.0.saturating_duration_since(earlier.0)
+  }
+
+  /// Zero when the earlier one is actually later
+  #[cfg(target_arch = "wasm32")]
+  pub fn duration_since(&self, earlier: Instant) -> Duration {
+    Duration::from_secs_f64((self.0 - earlier.0).max(0.0) / 1000.0)
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    Self::now().duration_since(*self)
+  }
+}
+
+/// `std::time::SystemTime::now` panics in the browser, there the time comes from `Date.now()`.
+/// Only has the methods of the standard one that the apps use
+#[cfg(target_arch = "wasm32")]
+mod web_time {
+  use std::ops::Add;
+  use std::time::Duration;
+
+  #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+  pub struct SystemTime(f64); // Milliseconds since the epoch
+
+  impl SystemTime {
+    pub fn now() -> Self {
+      Self(js_sys::Date::now())
+    }
+
+    pub fn duration_since(&self, earlier: SystemTime) -> Result<Duration, Duration> {
+      let millis = self.0 - earlier.0;
+      if millis >= 0.0 {
+        Ok(Duration::from_secs_f64(millis / 1000.0))
+      } else {
+        Err(Duration::from_secs_f64(-millis / 1000.0))
+      }
+    }
+
+    pub fn elapsed(&self) -> Result<Duration, Duration> {
+      Self::now().duration_since(*self)
+    }
+  }
+
+  impl Add<Duration> for SystemTime {
+    type Output = SystemTime;
+
+    fn add(self, duration: Duration) -> SystemTime {
+      Self(self.0 + duration.as_secs_f64() * 1000.0)
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_elapsed_is_never_negative() {
+    let earlier = Instant::now();
+    let later = Instant::now();
+    assert!(later >= earlier);
+    assert_eq!(earlier.duration_since(later), Duration::from_secs(0));
+    assert!(earlier.elapsed() >= later.duration_since(earlier));
+  }
+}
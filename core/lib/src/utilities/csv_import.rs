@@ -0,0 +1,298 @@
+use super::Geometry;
+use crate::{components::symbolics::*, events::*, math::*, resources::*};
+use specs::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+  UnknownKind(usize, String),      // Line number, the first field
+  MissingField(usize),             // Line number
+  InvalidNumber(usize, String),    // Line number, the field that is not a number
+  DuplicateName(usize, String),    // Line number, the name already taken
+  UnknownReference(usize, String), // Line number, the name of no point
+}
+
+#[derive(Debug, Clone)]
+enum Row {
+  Point(String, Vector2),
+  Line(String, String, String),
+  Circle(String, String, String), // Name, center, point on the circle
+}
+
+/// Imports the points, lines and circles of a comma or tab separated text, one element per line:
+///
+/// ```text
+/// P,name,x,y
+/// L,name,p1,p2
+/// C,name,center,radius_point
+/// ```
+///
+/// Points are fixed at their coordinates, lines and circles refer to the points by name, which
+/// can be defined anywhere in the text or already exist in the world. Empty lines and lines
+/// starting with `#` are skipped. Nothing is inserted when there is an error.
+pub fn import_csv<'a, 'b>(
+  world: &mut World,
+  dispatcher: &mut Dispatcher<'a, 'b>,
+  text: &str,
+) -> Result<(), ImportError> {
+  let rows = parse(text)?;
+  check_names(&rows, &world.fetch::<Names>(), &world.read_storage::<SymbolicPoint>())?;
+  let rows = rows.into_iter().map(|(_, row)| row).collect::<Vec<_>>();
+  let mut geometry_event_reader = world.fetch_mut::<GeometryEventChannel>().register_reader();
+
+  // The points go first so that the lines and circles can refer to them
+  let mut named = HashMap::new();
+  let points = rows
+    .iter()
+    .filter_map(|row| match row {
+      Row::Point(name, position) => Some((name, *position)),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  // The whole import is a single step of the history
+  let commands = std::iter::once(Command::BeginTransaction("Import".to_string()))
+    .chain(
+      points
+        .iter()
+        .map(|(_, position)| Command::PointInsert(InsertPointEvent::InsertPointAt(*position))),
+    )
+    .collect();
+  let (inserted_points, _, _) = step(world, dispatcher, &mut geometry_event_reader, commands);
+  for ((name, _), ent) in points.iter().zip(inserted_points) {
+    named.insert(name.to_string(), ent);
+  }
+
+  let point = |name: &String| match named.get(name) {
+    Some(ent) => *ent,
+    None => world.fetch::<Names>().by_name(name).unwrap(),
+  };
+  // The new points first give up the names they got when inserted, which may be the names of
+  // other points of the text
+  let mut commands = named
+    .values()
+    .map(|ent| Command::Rename(RenameEvent::Unname(*ent)))
+    .chain(
+      named
+        .iter()
+        .map(|(name, ent)| Command::Rename(RenameEvent::Rename(*ent, name.clone()))),
+    )
+    .collect::<Vec<_>>();
+  let (mut lines, mut circles) = (vec![], vec![]);
+  for row in &rows {
+    match row {
+      Row::Line(name, p1, p2) => {
+        lines.push(name);
+        commands.push(Command::LineInsert(InsertLineEvent::InsertLine(
+          SymbolicLine::Straight(point(p1), point(p2)),
+        )));
+      }
+      Row::Circle(name, center, on_circle) => {
+        circles.push(name);
+        commands.push(Command::CircleInsert(InsertCircleEvent::InsertCircle(
+          SymbolicCircle::CenterRadius(point(center), point(on_circle)),
+        )));
+      }
+      Row::Point(_, _) => (),
+    }
+  }
+  let (_, inserted_lines, inserted_circles) = step(world, dispatcher, &mut geometry_event_reader, commands);
+
+  let commands = lines
+    .into_iter()
+    .zip(inserted_lines)
+    .chain(circles.into_iter().zip(inserted_circles))
+    .map(|(name, ent)| Command::Rename(RenameEvent::Rename(ent, name.clone())))
+    .chain(std::iter::once(Command::EndTransaction))
+    .collect();
+  step(world, dispatcher, &mut geometry_event_reader, commands);
+  Ok(())
+}
+
+/// The rows along with their line numbers
+fn parse(text: &str) -> Result<Vec<(usize, Row)>, ImportError> {
+  let mut rows = vec![];
+  for (i, line) in text.lines().enumerate() {
+    let line_number = i + 1;
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let fields = line.split(&[',', '\t'][..]).map(str::trim).collect::<Vec<_>>();
+    if fields.len() < 4 {
+      return Err(ImportError::MissingField(line_number));
+    }
+    let (name, a, b) = (fields[1].to_string(), fields[2], fields[3]);
+    if name.is_empty() {
+      return Err(ImportError::MissingField(line_number));
+    }
+    let number = |field: &str| {
+      field
+        .parse::<f64>()
+        .map_err(|_| ImportError::InvalidNumber(line_number, field.to_string()))
+    };
+    let row = match fields[0] {
+      "P" => Row::Point(name, vec2![number(a)?, number(b)?]),
+      "L" => Row::Line(name, a.to_string(), b.to_string()),
+      "C" => Row::Circle(name, a.to_string(), b.to_string()),
+      kind => return Err(ImportError::UnknownKind(line_number, kind.to_string())),
+    };
+    rows.push((line_number, row));
+  }
+  Ok(rows)
+}
+
+/// Every name has to be new and every reference has to be a point of the text or of the world
+fn check_names(
+  rows: &[(usize, Row)],
+  names: &Names,
+  sym_points: &ReadStorage<SymbolicPoint>,
+) -> Result<(), ImportError> {
+  let mut defined = HashSet::new();
+  let mut points = HashSet::new();
+  for (line_number, row) in rows {
+    let name = match row {
+      Row::Point(name, _) | Row::Line(name, _, _) | Row::Circle(name, _, _) => name,
+    };
+    if names.by_name(name).is_some() || !defined.insert(name) {
+      return Err(ImportError::DuplicateName(*line_number, name.clone()));
+    }
+    if let Row::Point(name, _) = row {
+      points.insert(name);
+    }
+  }
+  let world_point = |name: &String| names.by_name(name).and_then(|ent| sym_points.get(ent)).is_some();
+  for (line_number, row) in rows {
+    if let Row::Line(_, p1, p2) | Row::Circle(_, p1, p2) = row {
+      for reference in &[p1, p2] {
+        if !points.contains(reference) && !world_point(reference) {
+          return Err(ImportError::UnknownReference(*line_number, reference.to_string()));
+        }
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Dispatches the commands together and gives back the points, lines and circles inserted, each
+/// kind in the order of the commands
+fn step<'a, 'b>(
+  world: &mut World,
+  dispatcher: &mut Dispatcher<'a, 'b>,
+  geometry_event_reader: &mut GeometryEventReader,
+  commands: Vec<Command>,
+) -> (Vec<Entity>, Vec<Entity>, Vec<Entity>) {
+  {
+    let mut command_event_channel = world.fetch_mut::<CommandEventChannel>();
+    for command in commands {
+      command_event_channel.single_write(CommandEvent {
+        command,
+        event_id: None,
+      });
+    }
+  }
+  dispatcher.dispatch(world);
+  world.maintain();
+
+  let (mut points, mut lines, mut circles) = (vec![], vec![], vec![]);
+  for event in world.fetch::<GeometryEventChannel>().read(geometry_event_reader) {
+    match event {
+      GeometryEvent::Inserted(ent, Geometry::Point(_, _), false) => points.push(*ent),
+      GeometryEvent::Inserted(ent, Geometry::Line(_, _), false) => lines.push(*ent),
+      GeometryEvent::Inserted(ent, Geometry::Circle(_, _), false) => circles.push(*ent),
+      _ => (),
+    }
+  }
+  (points, lines, circles)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, test_utils::*};
+
+  #[test]
+  fn test_import_points_line_and_circle() {
+    let (mut world, mut dispatcher) = headless();
+    let text = "# A right triangle\nP,A,0,0\nP\tB\t4\t0\n\nL,AB,A,B\nC,around,A,C\nP, C, 0, 3\n";
+    assert_eq!(import_csv(&mut world, &mut dispatcher, text), Ok(()));
+
+    let names = world.fetch::<Names>();
+    let (a, b, c) = (
+      names.by_name("A").unwrap(),
+      names.by_name("B").unwrap(),
+      names.by_name("C").unwrap(),
+    );
+    let sym_points = world.read_storage::<SymbolicPoint>();
+    let sym_lines = world.read_storage::<SymbolicLine>();
+    let sym_circles = world.read_storage::<SymbolicCircle>();
+    match sym_points.get(b) {
+      Some(SymbolicPoint::Fixed(position)) => assert_eq!(position.0, vec2![4., 0.]),
+      _ => panic!("Expected a fixed point"),
+    }
+    match sym_lines.get(names.by_name("AB").unwrap()) {
+      Some(SymbolicLine::Straight(p1, p2)) => assert_eq!((*p1, *p2), (a, b)),
+      _ => panic!("Expected a line through A and B"),
+    }
+    let circle = names.by_name("around").unwrap();
+    match sym_circles.get(circle) {
+      Some(SymbolicCircle::CenterRadius(center, on_circle)) => assert_eq!((*center, *on_circle), (a, c)),
+      _ => panic!("Expected a circle around A through C"),
+    }
+    assert_eq!(world.read_storage::<VirtualCircle>().get(circle).unwrap().radius.0, 3.0);
+  }
+
+  #[test]
+  fn test_import_names_taken_by_new_points_and_undo() {
+    let (mut world, mut dispatcher) = headless();
+
+    // The point named B is inserted first, so it is given the name A at first
+    assert_eq!(import_csv(&mut world, &mut dispatcher, "P,B,0,0\nP,A,1,0\n"), Ok(()));
+    {
+      let names = world.fetch::<Names>();
+      let sym_points = world.read_storage::<SymbolicPoint>();
+      match sym_points.get(names.by_name("A").unwrap()) {
+        Some(SymbolicPoint::Fixed(position)) => assert_eq!(position.0, vec2![1., 0.]),
+        _ => panic!("Expected a fixed point"),
+      }
+    }
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 0);
+  }
+
+  #[test]
+  fn test_import_unknown_reference() {
+    let (mut world, mut dispatcher) = headless();
+    assert_eq!(import_csv(&mut world, &mut dispatcher, "P,A,0,0\n"), Ok(()));
+
+    // Points of the world can be referred to, but nothing is inserted on an error
+    let text = "P,B,1,1\nL,AB,A,B\n\nC,c,A,Z\n";
+    assert_eq!(
+      import_csv(&mut world, &mut dispatcher, text),
+      Err(ImportError::UnknownReference(4, "Z".to_string()))
+    );
+    assert_eq!(world.read_storage::<SymbolicPoint>().join().count(), 1);
+    assert_eq!(
+      import_csv(&mut world, &mut dispatcher, "P,B,1,x\n"),
+      Err(ImportError::InvalidNumber(1, "x".to_string()))
+    );
+    assert_eq!(
+      import_csv(&mut world, &mut dispatcher, "P,A,1,1\n"),
+      Err(ImportError::DuplicateName(1, "A".to_string()))
+    );
+
+    assert_eq!(import_csv(&mut world, &mut dispatcher, "P,B,1,1\nL,AB,A,B\n"), Ok(()));
+    let names = world.fetch::<Names>();
+    let sym_lines = world.read_storage::<SymbolicLine>();
+    match sym_lines.get(names.by_name("AB").unwrap()) {
+      Some(SymbolicLine::Straight(p1, _)) => assert_eq!(Some(*p1), names.by_name("A")),
+      _ => panic!("Expected a line from A"),
+    }
+  }
+}
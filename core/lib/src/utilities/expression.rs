@@ -0,0 +1,413 @@
+use crate::math::*;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Identifies a named scalar, it is kept when the scalar is defined again under the same name
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ScalarId(pub usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+  Unexpected(String),      // The token found, or `end` when the expression stops too early
+  UnknownFunction(String), // Name of the function
+  WrongArguments(String),  // Name of the function
+  MissingName,             // Definition without `name =`
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Operator {
+  Add,
+  Subtract,
+  Multiply,
+  Divide,
+  Power,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MathFunction {
+  Sqrt,
+  Abs,
+  Sin,
+  Cos,
+  Tan,
+}
+
+/// A scalar computed from numbers, other scalars and the named geometries:
+///
+/// ```text
+/// dist(A, B)     distance between the points A and B
+/// angle(A, B, C) angle at B between A and C, in radians
+/// radius(c)      radius of the circle c
+/// length(s)      length of the segment s
+/// ```
+///
+/// together with `+ - * / ^`, parentheses, `sqrt`, `abs`, `sin`, `cos`, `tan` and `pi`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+  Number(f64),
+  Scalar(String), // Name of another scalar or of a slider
+  Negate(Box<Expression>),
+  Binary(Operator, Box<Expression>, Box<Expression>),
+  Math(MathFunction, Box<Expression>),
+  Distance(String, String),      // (Point name, Point name)
+  Angle(String, String, String), // (Point name, Vertex point name, Point name)
+  Radius(String),                // (Circle name)
+  Length(String),                // (Segment name)
+}
+
+/// The named geometries an expression can refer to, `None` when there is no such geometry or it
+/// is undefined
+pub trait ExpressionContext {
+  fn point(&self, name: &str) -> Option<Vector2>;
+  fn radius(&self, name: &str) -> Option<f64>;
+  fn length(&self, name: &str) -> Option<f64>;
+  fn slider(&self, name: &str) -> Option<f64>;
+}
+
+impl Expression {
+  /// The value of the expression, `None` when a geometry or a scalar it refers to has no value or
+  /// the result is not a number
+  pub fn evaluate<C: ExpressionContext>(&self, context: &C, scalars: &HashMap<String, f64>) -> Option<f64> {
+    let value = match self {
+      Expression::Number(number) => *number,
+      // The scalars go before the sliders of the same name
+      Expression::Scalar(name) => scalars.get(name).cloned().or_else(|| context.slider(name))?,
+      Expression::Negate(operand) => -operand.evaluate(context, scalars)?,
+      Expression::Binary(operator, lhs, rhs) => {
+        let (lhs, rhs) = (lhs.evaluate(context, scalars)?, rhs.evaluate(context, scalars)?);
+        match operator {
+          Operator::Add => lhs + rhs,
+          Operator::Subtract => lhs - rhs,
+          Operator::Multiply => lhs * rhs,
+          Operator::Divide => lhs / rhs,
+          Operator::Power => lhs.powf(rhs),
+        }
+      }
+      Expression::Math(function, operand) => {
+        let operand = operand.evaluate(context, scalars)?;
+        match function {
+          MathFunction::Sqrt => operand.sqrt(),
+          MathFunction::Abs => operand.abs(),
+          MathFunction::Sin => operand.sin(),
+          MathFunction::Cos => operand.cos(),
+          MathFunction::Tan => operand.tan(),
+        }
+      }
+      Expression::Distance(a, b) => (context.point(b)? - context.point(a)?).magnitude(),
+      Expression::Angle(a, vertex, b) => {
+        let vertex = context.point(vertex)?;
+        let (u, v) = (context.point(a)? - vertex, context.point(b)? - vertex);
+        (u.x * v.y - u.y * v.x).abs().atan2(u.x * v.x + u.y * v.y)
+      }
+      Expression::Radius(circle) => context.radius(circle)?,
+      Expression::Length(segment) => context.length(segment)?,
+    };
+    if value.is_finite() {
+      Some(value)
+    } else {
+      None
+    }
+  }
+
+  /// The names of the other scalars the expression refers to
+  pub fn scalars(&self) -> Vec<&str> {
+    match self {
+      Expression::Scalar(name) => vec![name],
+      Expression::Negate(operand) | Expression::Math(_, operand) => operand.scalars(),
+      Expression::Binary(_, lhs, rhs) => {
+        let mut names = lhs.scalars();
+        names.extend(rhs.scalars());
+        names
+      }
+      _ => vec![],
+    }
+  }
+}
+
+/// Parses a definition such as `r = dist(A, B) * 2` into the name and the expression
+pub fn parse_definition(text: &str) -> Result<(String, Expression), ExpressionError> {
+  let (name, expression) = text.split_once('=').ok_or(ExpressionError::MissingName)?;
+  let name = name.trim();
+  let is_identifier = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+    && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+  if !is_identifier {
+    return Err(ExpressionError::MissingName);
+  }
+  Ok((name.to_string(), parse_expression(expression)?))
+}
+
+pub fn parse_expression(text: &str) -> Result<Expression, ExpressionError> {
+  let mut parser = Parser {
+    tokens: tokenize(text)?,
+    position: 0,
+  };
+  let expression = parser.sum()?;
+  match parser.next() {
+    None => Ok(expression),
+    Some(token) => Err(unexpected(Some(token))),
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f64),
+  Identifier(String),
+  Symbol(char), // One of `+ - * / ^ ( ) ,`
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ExpressionError> {
+  let mut tokens = vec![];
+  let mut chars = text.chars().peekable();
+  while let Some(&c) = chars.peek() {
+    if c.is_whitespace() {
+      chars.next();
+    } else if c.is_ascii_digit() || c == '.' {
+      let mut number = String::new();
+      while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+        number.push(c);
+        chars.next();
+      }
+      let number = number.parse().map_err(|_| ExpressionError::Unexpected(number))?;
+      tokens.push(Token::Number(number));
+    } else if c.is_alphabetic() || c == '_' {
+      let mut identifier = String::new();
+      while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+        identifier.push(c);
+        chars.next();
+      }
+      tokens.push(Token::Identifier(identifier));
+    } else if "+-*/^(),".contains(c) {
+      tokens.push(Token::Symbol(c));
+      chars.next();
+    } else {
+      return Err(ExpressionError::Unexpected(c.to_string()));
+    }
+  }
+  Ok(tokens)
+}
+
+fn unexpected(token: Option<Token>) -> ExpressionError {
+  ExpressionError::Unexpected(match token {
+    Some(Token::Number(number)) => number.to_string(),
+    Some(Token::Identifier(identifier)) => identifier,
+    Some(Token::Symbol(symbol)) => symbol.to_string(),
+    None => "end".to_string(),
+  })
+}
+
+/// Recursive descent, from the operators binding the least to the ones binding the most
+struct Parser {
+  tokens: Vec<Token>,
+  position: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.position)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.position).cloned();
+    self.position += 1;
+    token
+  }
+
+  fn expect(&mut self, symbol: char) -> Result<(), ExpressionError> {
+    match self.next() {
+      Some(Token::Symbol(c)) if c == symbol => Ok(()),
+      token => Err(unexpected(token)),
+    }
+  }
+
+  fn sum(&mut self) -> Result<Expression, ExpressionError> {
+    let mut lhs = self.product()?;
+    while let Some(Token::Symbol(c @ '+')) | Some(Token::Symbol(c @ '-')) = self.peek().cloned() {
+      self.next();
+      let operator = if c == '+' { Operator::Add } else { Operator::Subtract };
+      lhs = Expression::Binary(operator, Box::new(lhs), Box::new(self.product()?));
+    }
+    Ok(lhs)
+  }
+
+  fn product(&mut self) -> Result<Expression, ExpressionError> {
+    let mut lhs = self.unary()?;
+    while let Some(Token::Symbol(c @ '*')) | Some(Token::Symbol(c @ '/')) = self.peek().cloned() {
+      self.next();
+      let operator = if c == '*' { Operator::Multiply } else { Operator::Divide };
+      lhs = Expression::Binary(operator, Box::new(lhs), Box::new(self.unary()?));
+    }
+    Ok(lhs)
+  }
+
+  fn unary(&mut self) -> Result<Expression, ExpressionError> {
+    if let Some(Token::Symbol('-')) = self.peek() {
+      self.next();
+      return Ok(Expression::Negate(Box::new(self.unary()?)));
+    }
+    self.power()
+  }
+
+  // Right associative, and binding more than the negation: `-2^2` is `-(2^2)`
+  fn power(&mut self) -> Result<Expression, ExpressionError> {
+    let base = self.primary()?;
+    if let Some(Token::Symbol('^')) = self.peek() {
+      self.next();
+      return Ok(Expression::Binary(
+        Operator::Power,
+        Box::new(base),
+        Box::new(self.unary()?),
+      ));
+    }
+    Ok(base)
+  }
+
+  fn primary(&mut self) -> Result<Expression, ExpressionError> {
+    match self.next() {
+      Some(Token::Number(number)) => Ok(Expression::Number(number)),
+      Some(Token::Symbol('(')) => {
+        let expression = self.sum()?;
+        self.expect(')')?;
+        Ok(expression)
+      }
+      Some(Token::Identifier(name)) => {
+        if let Some(Token::Symbol('(')) = self.peek() {
+          self.next();
+          self.call(name)
+        } else if name == "pi" {
+          Ok(Expression::Number(PI))
+        } else {
+          Ok(Expression::Scalar(name))
+        }
+      }
+      token => Err(unexpected(token)),
+    }
+  }
+
+  /// The function applied to its arguments, once past the opening parenthesis
+  fn call(&mut self, function: String) -> Result<Expression, ExpressionError> {
+    let math_function = match function.as_str() {
+      "sqrt" => Some(MathFunction::Sqrt),
+      "abs" => Some(MathFunction::Abs),
+      "sin" => Some(MathFunction::Sin),
+      "cos" => Some(MathFunction::Cos),
+      "tan" => Some(MathFunction::Tan),
+      _ => None,
+    };
+    if let Some(math_function) = math_function {
+      let operand = self.sum()?;
+      self.expect(')')?;
+      return Ok(Expression::Math(math_function, Box::new(operand)));
+    }
+
+    // The geometries are given by name
+    let mut names = vec![];
+    loop {
+      match self.next() {
+        Some(Token::Identifier(name)) => names.push(name),
+        _ => return Err(ExpressionError::WrongArguments(function)),
+      }
+      match self.next() {
+        Some(Token::Symbol(',')) => (),
+        Some(Token::Symbol(')')) => break,
+        token => return Err(unexpected(token)),
+      }
+    }
+    let arity = names.len();
+    let mut names = names.into_iter();
+    let mut name = || names.next().unwrap();
+    match (function.as_str(), arity) {
+      ("dist", 2) => Ok(Expression::Distance(name(), name())),
+      ("angle", 3) => Ok(Expression::Angle(name(), name(), name())),
+      ("radius", 1) => Ok(Expression::Radius(name())),
+      ("length", 1) => Ok(Expression::Length(name())),
+      ("dist", _) | ("angle", _) | ("radius", _) | ("length", _) => Err(ExpressionError::WrongArguments(function)),
+      _ => Err(ExpressionError::UnknownFunction(function)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  struct Points;
+
+  impl ExpressionContext for Points {
+    fn point(&self, name: &str) -> Option<Vector2> {
+      match name {
+        "A" => Some(vec2![0., 0.]),
+        "B" => Some(vec2![3., 4.]),
+        "C" => Some(vec2![3., 0.]),
+        _ => None,
+      }
+    }
+    fn radius(&self, _: &str) -> Option<f64> {
+      None
+    }
+    fn length(&self, _: &str) -> Option<f64> {
+      None
+    }
+    fn slider(&self, name: &str) -> Option<f64> {
+      match name {
+        "t" | "k" => Some(0.5),
+        _ => None,
+      }
+    }
+  }
+
+  fn evaluate(text: &str) -> Option<f64> {
+    let mut scalars = HashMap::new();
+    scalars.insert("k".to_string(), 3.);
+    parse_expression(text).unwrap().evaluate(&Points, &scalars)
+  }
+
+  #[test]
+  fn test_evaluate() {
+    assert_eq!(evaluate("dist(A, B) * 2"), Some(10.));
+    assert_eq!(evaluate("1 + 2 * k - 8 / 4"), Some(5.));
+    assert_eq!(evaluate("t * 4 + k"), Some(5.));
+    assert_eq!(evaluate("-2 ^ 2"), Some(-4.));
+    assert_eq!(evaluate("2 ^ 3 ^ 2"), Some(512.));
+    assert_eq!(evaluate("(1 + 2) * sqrt(16)"), Some(12.));
+    assert!((evaluate("angle(B, A, C)").unwrap() - (3f64 / 5.).acos()).abs() < 1e-12);
+    assert!((evaluate("cos(pi)").unwrap() + 1.).abs() < 1e-12);
+    assert_eq!(evaluate("dist(A, D)"), None);
+    assert_eq!(evaluate("unknown + 1"), None);
+    assert_eq!(evaluate("1 / 0"), None);
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    assert_eq!(parse_definition("r = dist(A, B) * 2").unwrap().0, "r".to_string());
+    assert_eq!(parse_definition("dist(A, B)"), Err(ExpressionError::MissingName));
+    assert_eq!(parse_definition("2r = 1"), Err(ExpressionError::MissingName));
+    assert_eq!(
+      parse_expression("1 +"),
+      Err(ExpressionError::Unexpected("end".to_string()))
+    );
+    assert_eq!(
+      parse_expression("(1 + 2"),
+      Err(ExpressionError::Unexpected("end".to_string()))
+    );
+    assert_eq!(
+      parse_expression("1 2"),
+      Err(ExpressionError::Unexpected("2".to_string()))
+    );
+    assert_eq!(
+      parse_expression("dist(A)"),
+      Err(ExpressionError::WrongArguments("dist".to_string()))
+    );
+    assert_eq!(
+      parse_expression("dist(A, 2)"),
+      Err(ExpressionError::WrongArguments("dist".to_string()))
+    );
+    assert_eq!(
+      parse_expression("area(P)"),
+      Err(ExpressionError::UnknownFunction("area".to_string()))
+    );
+    assert_eq!(
+      parse_expression("1 $ 2"),
+      Err(ExpressionError::Unexpected("$".to_string()))
+    );
+  }
+}
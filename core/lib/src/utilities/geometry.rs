@@ -1,17 +1,113 @@
-use crate::components::{styles::*, symbolics::*};
+use crate::components::{freehand::*, measurements::*, sliders::*, styles::*, symbolics::*, underlays::*};
+use specs::prelude::*;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Geometry {
   Point(SymbolicPoint, PointStyle),
   Line(SymbolicLine, LineStyle),
   Circle(SymbolicCircle, CircleStyle),
+  Arc(SymbolicArc, ArcStyle),
+  Conic(SymbolicConic, ConicStyle),
+  Polygon(SymbolicPolygon, PolygonStyle),
+  Vector(SymbolicVector, VectorStyle),
+  Text(SymbolicText, TextStyle),
+  Measurement(Measurement),
+  Slider(Slider, SliderValue),
+  Underlay(ImageUnderlay, UnderlayPlacement),
+  Freehand(FreehandStroke, LineStyle),
 }
 
-#[derive(Debug, Copy, Clone)]
+impl Geometry {
+  /// The same geometry with all the entities it depends on mapped through `f`
+  pub fn remap<F: FnMut(Entity) -> Entity>(&self, f: &mut F) -> Self {
+    match self {
+      Geometry::Point(sym_point, point_style) => Geometry::Point(sym_point.remap(f), *point_style),
+      Geometry::Line(sym_line, line_style) => Geometry::Line(sym_line.remap(f), *line_style),
+      Geometry::Circle(sym_circle, circle_style) => Geometry::Circle(sym_circle.remap(f), *circle_style),
+      Geometry::Arc(sym_arc, arc_style) => Geometry::Arc(sym_arc.remap(f), *arc_style),
+      Geometry::Conic(sym_conic, conic_style) => Geometry::Conic(sym_conic.remap(f), *conic_style),
+      Geometry::Polygon(sym_polygon, polygon_style) => Geometry::Polygon(sym_polygon.remap(f), *polygon_style),
+      Geometry::Vector(sym_vector, vector_style) => Geometry::Vector(sym_vector.remap(f), *vector_style),
+      Geometry::Text(sym_text, text_style) => Geometry::Text(sym_text.remap(f), *text_style),
+      Geometry::Measurement(measurement) => Geometry::Measurement(measurement.remap(f)),
+      Geometry::Slider(slider, slider_value) => Geometry::Slider(*slider, *slider_value),
+      Geometry::Underlay(underlay, placement) => Geometry::Underlay(underlay.clone(), *placement),
+      Geometry::Freehand(stroke, line_style) => Geometry::Freehand(stroke.clone(), *line_style),
+    }
+  }
+
+  pub fn kind(&self) -> GeometryKind {
+    match self {
+      Geometry::Point(_, _) => GeometryKind::Point,
+      Geometry::Line(_, _) => GeometryKind::Line,
+      Geometry::Circle(_, _) => GeometryKind::Circle,
+      Geometry::Arc(_, _) => GeometryKind::Arc,
+      Geometry::Conic(_, _) => GeometryKind::Conic,
+      Geometry::Polygon(_, _) => GeometryKind::Polygon,
+      Geometry::Vector(_, _) => GeometryKind::Vector,
+      Geometry::Text(_, _) => GeometryKind::Text,
+      Geometry::Measurement(_) => GeometryKind::Measurement,
+      Geometry::Slider(_, _) => GeometryKind::Slider,
+      Geometry::Underlay(_, _) => GeometryKind::Underlay,
+      Geometry::Freehand(_, _) => GeometryKind::Freehand,
+    }
+  }
+
+  /// The entities the geometry depends on
+  pub fn dependencies(&self) -> Vec<Entity> {
+    let mut dependencies = Vec::new();
+    self.remap(&mut |ent| {
+      dependencies.push(ent);
+      ent
+    });
+    dependencies
+  }
+}
+
+/// Which kind of geometry, each kind is inserted by a system of its own
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GeometryKind {
+  Point,
+  Line,
+  Circle,
+  Arc,
+  Conic,
+  Polygon,
+  Vector,
+  Text,
+  Measurement,
+  Slider,
+  Underlay,
+  Freehand,
+}
+
+/// The style of any kind of geometry, measurements, sliders and underlays have none
+#[derive(Debug, Clone, Copy)]
+pub enum Style {
+  Point(PointStyle),
+  Line(LineStyle),
+  Circle(CircleStyle),
+  Arc(ArcStyle),
+  Conic(ConicStyle),
+  Polygon(PolygonStyle),
+  Vector(VectorStyle),
+  Text(TextStyle),
+}
+
+#[derive(Debug, Clone)]
 pub enum GeometrySymbol {
   Point(SymbolicPoint),
   Line(SymbolicLine),
   Circle(SymbolicCircle),
+  Arc(SymbolicArc),
+  Conic(SymbolicConic),
+  Polygon(SymbolicPolygon),
+  Vector(SymbolicVector),
+  Text(SymbolicText),
+  Measurement(Measurement),
+  Slider(Slider),
+  Underlay(ImageUnderlay),
+  Freehand(FreehandStroke),
 }
 
 impl Into<GeometrySymbol> for Geometry {
@@ -20,6 +116,15 @@ impl Into<GeometrySymbol> for Geometry {
       Geometry::Point(sym_point, _) => GeometrySymbol::Point(sym_point),
       Geometry::Line(sym_line, _) => GeometrySymbol::Line(sym_line),
       Geometry::Circle(sym_circle, _) => GeometrySymbol::Circle(sym_circle),
+      Geometry::Arc(sym_arc, _) => GeometrySymbol::Arc(sym_arc),
+      Geometry::Conic(sym_conic, _) => GeometrySymbol::Conic(sym_conic),
+      Geometry::Polygon(sym_polygon, _) => GeometrySymbol::Polygon(sym_polygon),
+      Geometry::Vector(sym_vector, _) => GeometrySymbol::Vector(sym_vector),
+      Geometry::Text(sym_text, _) => GeometrySymbol::Text(sym_text),
+      Geometry::Measurement(measurement) => GeometrySymbol::Measurement(measurement),
+      Geometry::Slider(slider, _) => GeometrySymbol::Slider(slider),
+      Geometry::Underlay(underlay, _) => GeometrySymbol::Underlay(underlay),
+      Geometry::Freehand(stroke, _) => GeometrySymbol::Freehand(stroke),
     }
   }
 }
@@ -0,0 +1,68 @@
+use crate::{components::virtual_shapes::*, math::*};
+use specs::prelude::*;
+
+/// Whether the solved geometries of the two entities coincide within `tol`: points at the same
+/// position, lines covering the same points and equal circles. Useful to check a construction
+/// against a reference one built independently
+pub fn geometry_matches(world: &World, a: Entity, b: Entity, tol: f64) -> bool {
+  let virt_points = world.read_storage::<VirtualPoint>();
+  let virt_lines = world.read_storage::<VirtualLine>();
+  let virt_circles = world.read_storage::<VirtualCircle>();
+  if let (Some(p1), Some(p2)) = (virt_points.get(a), virt_points.get(b)) {
+    (p1.0 - p2.0).magnitude() <= tol
+  } else if let (Some(l1), Some(l2)) = (virt_lines.get(a), virt_lines.get(b)) {
+    let (l1, l2): (Line, Line) = ((*l1).into(), (*l2).into());
+    l1.approx_coincident(&l2, tol)
+  } else if let (Some(c1), Some(c2)) = (virt_circles.get(a), virt_circles.get(b)) {
+    let (c1, c2): (Circle, Circle) = ((*c1).into(), (*c2).into());
+    c1.approx_eq(&c2, tol)
+  } else {
+    false
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, events::*, test_utils::*};
+
+  fn insert_line(world: &mut World, dispatcher: &mut Dispatcher, sym_line: SymbolicLine) -> Entity {
+    step(
+      world,
+      dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(sym_line)),
+    );
+    last_inserted::<SymbolicLine>(world)
+  }
+
+  #[test]
+  fn test_perpendicular_bisector_matches_reference() {
+    let (mut world, mut dispatcher) = headless();
+
+    // Construction: the perpendicular to AB through its midpoint
+    let a = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![0., 0.].into()));
+    let b = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![4., 2.].into()));
+    let ab = insert_line(&mut world, &mut dispatcher, SymbolicLine::Straight(a, b));
+    let mid = insert_point(&mut world, &mut dispatcher, SymbolicPoint::MidPoint(a, b));
+    let bisector = insert_line(&mut world, &mut dispatcher, SymbolicLine::Perpendicular(ab, mid));
+
+    // Reference: the line through two known points of the bisector
+    let r1 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![3., -1.].into()));
+    let r2 = insert_point(&mut world, &mut dispatcher, SymbolicPoint::Fixed(vec2![1., 3.].into()));
+    let reference = insert_line(&mut world, &mut dispatcher, SymbolicLine::Straight(r1, r2));
+    assert!(geometry_matches(&world, bisector, reference, 1e-6));
+    assert!(geometry_matches(&world, mid, mid, 1e-6));
+
+    // Near misses: a line off by a bit, a segment on the same line and a point
+    let r3 = insert_point(
+      &mut world,
+      &mut dispatcher,
+      SymbolicPoint::Fixed(vec2![1., 3.01].into()),
+    );
+    let off = insert_line(&mut world, &mut dispatcher, SymbolicLine::Straight(r1, r3));
+    assert!(!geometry_matches(&world, bisector, off, 1e-6));
+    let segment = insert_line(&mut world, &mut dispatcher, SymbolicLine::Segment(r1, r2));
+    assert!(!geometry_matches(&world, bisector, segment, 1e-6));
+    assert!(!geometry_matches(&world, bisector, mid, 1e-6));
+  }
+}
@@ -0,0 +1,169 @@
+use super::ScreenLine;
+use crate::math::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Caches the clipped version of screen lines so that a line is only clipped again when either
+/// the line itself or the clipping bounds (the screen) changed
+pub struct LineClipCache<T: Copy + Eq + Hash> {
+  bounds: Option<AABB>,
+  generation: usize,
+  clips: usize,
+  cache: HashMap<T, ClippedLine>,
+}
+
+struct ClippedLine {
+  line: ScreenLine, // The one before clipping
+  generation: usize,
+  clipped: Option<(Vector2, Vector2)>,
+}
+
+impl<T: Copy + Eq + Hash> Default for LineClipCache<T> {
+  fn default() -> Self {
+    Self {
+      bounds: None,
+      generation: 0,
+      clips: 0,
+      cache: HashMap::new(),
+    }
+  }
+}
+
+impl<T: Copy + Eq + Hash> LineClipCache<T> {
+  pub fn generation(&self) -> usize {
+    self.generation
+  }
+
+  /// How many times a line was actually clipped, the ones taken from the cache aside
+  pub fn clips(&self) -> usize {
+    self.clips
+  }
+
+  /// Invalidate all the cached lines
+  pub fn invalidate(&mut self) {
+    self.generation += 1;
+  }
+
+  pub fn remove(&mut self, key: T) {
+    self.cache.remove(&key);
+  }
+
+  /// Forget the lines that are not there anymore
+  pub fn retain(&mut self, mut keep: impl FnMut(T) -> bool) {
+    self.cache.retain(|key, _| keep(*key));
+  }
+
+  pub fn needs_clip(&self, key: T, line: ScreenLine, bounds: AABB) -> bool {
+    if self.bounds != Some(bounds) {
+      return true;
+    }
+    match self.cache.get(&key) {
+      Some(cached) => cached.line != line || cached.generation != self.generation,
+      None => true,
+    }
+  }
+
+  /// Get the part of the line inside the bounds, none if the line is not inside the bounds
+  pub fn clip(&mut self, key: T, line: ScreenLine, bounds: AABB) -> Option<(Vector2, Vector2)> {
+    if self.bounds != Some(bounds) {
+      self.bounds = Some(bounds);
+      self.invalidate();
+    }
+    if !self.needs_clip(key, line, bounds) {
+      if let Some(cached) = self.cache.get(&key) {
+        return cached.clipped;
+      }
+    }
+    self.clips += 1;
+    let clipped = Into::<Line>::into(line).intersect(bounds);
+    self.cache.insert(
+      key,
+      ClippedLine {
+        line,
+        generation: self.generation,
+        clipped,
+      },
+    );
+    clipped
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::resources::{LineClipMargin, Viewport};
+
+  fn line(from: Vector2, to: Vector2) -> ScreenLine {
+    ScreenLine {
+      from: from.into(),
+      to: to.into(),
+      line_type: LineType::Straight,
+    }
+  }
+
+  #[test]
+  fn test_unchanged_frame_is_not_clipped_again() {
+    let mut cache = LineClipCache::<usize>::default();
+    let bounds = AABB::new(0., 0., 100., 100.);
+    let mut lines = vec![
+      line(vec2![10., 50.], vec2![20., 50.]),
+      line(vec2![50., 10.], vec2![50., 20.]),
+      line(vec2![10., 10.], vec2![20., 20.]),
+    ];
+    let frame = |cache: &mut LineClipCache<usize>, lines: &[ScreenLine], bounds: AABB| {
+      let clips = cache.clips();
+      let clipped: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| cache.clip(i, *l, bounds))
+        .collect();
+      (clipped, cache.clips() - clips)
+    };
+
+    // Every line is clipped on the first frame, none on a frame where nothing changed
+    let (first, clips) = frame(&mut cache, &lines, bounds);
+    assert_eq!(clips, 3);
+    let (second, clips) = frame(&mut cache, &lines, bounds);
+    assert_eq!(clips, 0);
+    assert_eq!(first, second);
+
+    // Only the line that moved is clipped again
+    lines[1] = line(vec2![60., 10.], vec2![60., 20.]);
+    assert_eq!(frame(&mut cache, &lines, bounds).1, 1);
+
+    // All of them once the viewport changed
+    assert_eq!(frame(&mut cache, &lines, AABB::new(0., 0., 200., 100.)).1, 3);
+  }
+
+  #[test]
+  fn test_line_clip_margin() {
+    let mut cache = LineClipCache::<usize>::default();
+    let viewport = Viewport::new(vec2![0., 0.], vec2![10., 10.], vec2![100., 100.]);
+    let bounds = LineClipMargin(5.).clip_aabb(&viewport);
+
+    // The line goes past the border of the screen, up to the margin
+    let (from, to) = cache.clip(0, line(vec2![10., 50.], vec2![20., 50.]), bounds).unwrap();
+    let (from, to) = if from.x < to.x { (from, to) } else { (to, from) };
+    assert_eq!(from, vec2![-5., 50.]);
+    assert_eq!(to, vec2![105., 50.]);
+    assert!(!viewport.screen_aabb().contains(from));
+  }
+
+  #[test]
+  fn test_line_clip_cache_invalidation() {
+    let mut cache = LineClipCache::<usize>::default();
+    let bounds = AABB::new(0., 0., 100., 100.);
+    let l = line(vec2![10., 50.], vec2![20., 50.]);
+    cache.clip(0, l, bounds);
+
+    // Line changed
+    assert!(cache.needs_clip(0, line(vec2![10., 40.], vec2![20., 50.]), bounds));
+
+    // Bounds changed
+    assert!(cache.needs_clip(0, l, AABB::new(0., 0., 200., 100.)));
+
+    // Explicit invalidation
+    cache.invalidate();
+    assert!(cache.needs_clip(0, l, bounds));
+  }
+}
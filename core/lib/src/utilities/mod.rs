@@ -1,9 +1,33 @@
+mod clock;
+mod csv_import;
+mod expression;
 mod geometry;
+mod geometry_matches;
+mod line_clip_cache;
+mod name_table;
+mod nearest_entity;
+mod quadtree;
+mod replay;
 mod screen_space;
+mod sketch_builder;
+mod snapshot;
+mod solve_sketch;
 mod spatial_hash_table;
 mod virtual_space;
 
+pub use clock::*;
+pub use csv_import::*;
+pub use expression::*;
 pub use geometry::*;
+pub use geometry_matches::*;
+pub use line_clip_cache::*;
+pub use name_table::*;
+pub use nearest_entity::*;
+pub use quadtree::*;
+pub use replay::*;
 pub use screen_space::*;
+pub use sketch_builder::*;
+pub use snapshot::*;
+pub use solve_sketch::*;
 pub use spatial_hash_table::*;
 pub use virtual_space::*;
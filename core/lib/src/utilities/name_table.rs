@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NameError {
+  DuplicateName(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct NameTable<T: Copy + Eq + Hash> {
+  names: HashMap<T, String>,
+  owners: HashMap<String, T>,
+}
+
+impl<T: Copy + Eq + Hash> Default for NameTable<T> {
+  fn default() -> Self {
+    Self {
+      names: HashMap::new(),
+      owners: HashMap::new(),
+    }
+  }
+}
+
+/// The name at the index in the sequence A, B, ..., Z, AA, AB, ..., ZZ, AAA, ...
+pub fn alphabetic_name(index: usize) -> String {
+  let mut letters = vec![];
+  let mut rest = index + 1;
+  while rest > 0 {
+    rest -= 1;
+    letters.push((b'A' + (rest % 26) as u8) as char);
+    rest /= 26;
+  }
+  letters.iter().rev().collect()
+}
+
+impl<T: Copy + Eq + Hash> NameTable<T> {
+  /// Assign the name to the item, freeing the previous name of the item. Fails if the name is
+  /// already taken by another item
+  pub fn assign(&mut self, item: T, name: String) -> Result<(), NameError> {
+    match self.owners.get(&name) {
+      Some(owner) if *owner == item => Ok(()),
+      Some(_) => Err(NameError::DuplicateName(name)),
+      None => {
+        self.remove(item);
+        self.owners.insert(name.clone(), item);
+        self.names.insert(item, name);
+        Ok(())
+      }
+    }
+  }
+
+  pub fn remove(&mut self, item: T) -> Option<String> {
+    let name = self.names.remove(&item)?;
+    self.owners.remove(&name);
+    Some(name)
+  }
+
+  pub fn by_name(&self, name: &str) -> Option<T> {
+    self.owners.get(name).cloned()
+  }
+
+  pub fn name_of(&self, item: T) -> Option<&String> {
+    self.names.get(&item)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (T, &String)> {
+    self.names.iter().map(|(item, name)| (*item, name))
+  }
+
+  pub fn clear(&mut self) {
+    self.names.clear();
+    self.owners.clear();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_name_table_assign_and_lookup() {
+    let mut names = NameTable::<usize>::default();
+    assert!(names.assign(1, "A".to_string()).is_ok());
+    assert!(names.assign(2, "B".to_string()).is_ok());
+    assert_eq!(names.by_name("A"), Some(1));
+    assert_eq!(names.by_name("B"), Some(2));
+    assert_eq!(names.by_name("C"), None);
+    assert_eq!(names.name_of(1), Some(&"A".to_string()));
+  }
+
+  #[test]
+  fn test_name_table_duplicate() {
+    let mut names = NameTable::<usize>::default();
+    assert!(names.assign(1, "A".to_string()).is_ok());
+    assert_eq!(
+      names.assign(2, "A".to_string()),
+      Err(NameError::DuplicateName("A".to_string()))
+    );
+    assert_eq!(names.by_name("A"), Some(1));
+    assert_eq!(names.name_of(2), None);
+
+    // Assigning the same name again to the owner is fine
+    assert!(names.assign(1, "A".to_string()).is_ok());
+  }
+
+  #[test]
+  fn test_name_table_reassign_frees_old_name() {
+    let mut names = NameTable::<usize>::default();
+    assert!(names.assign(1, "A".to_string()).is_ok());
+    assert!(names.assign(1, "B".to_string()).is_ok());
+    assert_eq!(names.by_name("A"), None);
+    assert_eq!(names.by_name("B"), Some(1));
+    assert!(names.assign(2, "A".to_string()).is_ok());
+    assert_eq!(names.by_name("A"), Some(2));
+  }
+
+  #[test]
+  fn test_alphabetic_names() {
+    assert_eq!(alphabetic_name(0), "A");
+    assert_eq!(alphabetic_name(25), "Z");
+    assert_eq!(alphabetic_name(26), "AA");
+    assert_eq!(alphabetic_name(27), "AB");
+    assert_eq!(alphabetic_name(26 + 26 * 26 - 1), "ZZ");
+    assert_eq!(alphabetic_name(26 + 26 * 26), "AAA");
+  }
+}
@@ -0,0 +1,55 @@
+use super::VirtualPosition;
+use crate::resources::*;
+use specs::prelude::*;
+
+/// The geometry closest to `position` that is at most `max_dist` away, along with its distance,
+/// all in virtual space. Points are measured to their position, lines to their closest point
+/// (where rays and segments end) and curves to their closest point. On a tie points come before
+/// lines and lines before curves, same as when hitting them with the mouse. Hidden geometries are
+/// left out
+pub fn nearest_entity(world: &World, position: VirtualPosition, max_dist: f64) -> Option<(Entity, f64)> {
+  world.fetch::<EntityQuadtree>().nearest_entity(position.0, max_dist)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::symbolics::*, events::*, math::*, test_utils::*};
+
+  #[test]
+  fn test_nearest_of_point_and_line() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut insert_point = |world: &mut World, position: Vector2| {
+      step(
+        world,
+        &mut dispatcher,
+        Command::PointInsert(InsertPointEvent::InsertPointAt(position)),
+      );
+      last_inserted::<SymbolicPoint>(world)
+    };
+    let from = insert_point(&mut world, vec2![-5., 0.]);
+    let to = insert_point(&mut world, vec2![5., 0.]);
+    let point = insert_point(&mut world, vec2![3., 1.]);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Straight(from, to))),
+    );
+    let line = last_inserted::<SymbolicLine>(&world);
+
+    let (ent, dist) = nearest_entity(&world, vec2![3., 0.4].into(), 1.0).unwrap();
+    assert_eq!(ent, line);
+    assert!((dist - 0.4).abs() < 1e-9);
+
+    let (ent, dist) = nearest_entity(&world, vec2![3., 0.8].into(), 1.0).unwrap();
+    assert_eq!(ent, point);
+    assert!((dist - 0.2).abs() < 1e-9);
+
+    // Beyond the end of the points the line is still close, unless it's out of reach
+    let (ent, dist) = nearest_entity(&world, vec2![8., -0.5].into(), 1.0).unwrap();
+    assert_eq!(ent, line);
+    assert!((dist - 0.5).abs() < 1e-9);
+    assert!(nearest_entity(&world, vec2![8., -3.].into(), 1.0).is_none());
+  }
+}
@@ -0,0 +1,387 @@
+use crate::math::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+static MAX_NODE_ITEMS: usize = 8; // A node holding more is split in four
+static MIN_NODE_SIZE: f64 = 0.001; // Virtual unit, nodes this small are not split anymore
+static ROOT_SIZE: f64 = 64.0; // Virtual unit, the root doubles until it covers what is inserted
+static MAX_EXTENT: f64 = 1e12; // Virtual unit, shapes reaching further are kept apart like the lines
+
+/// The geometry of an entity, as the quadtree measures the distances to it
+#[derive(Debug, Clone, Copy)]
+pub enum QuadtreeShape {
+  Point(Vector2),
+  Line(Line),
+  Circle(Circle),
+  Arc(Arc),
+  Conic(Ellipse),
+}
+
+impl QuadtreeShape {
+  /// The exact distance from `p` to the shape. Lines stop where rays and segments end, curves are
+  /// measured to their closest point
+  pub fn distance_to(&self, p: Vector2) -> f64 {
+    match self {
+      QuadtreeShape::Point(q) => (*q - p).magnitude(),
+      QuadtreeShape::Line(l) => (l.get_closest_point(p) - p).magnitude(),
+      QuadtreeShape::Circle(c) => ((c.center - p).magnitude() - c.radius).abs(),
+      QuadtreeShape::Arc(a) => (a.get_closest_point(p) - p).magnitude(),
+      QuadtreeShape::Conic(e) => (e.get_closest_point(p) - p).magnitude(),
+    }
+  }
+
+  /// Points go before lines and lines before curves when they are as close, same as when hitting
+  /// them with the mouse
+  fn rank(&self) -> usize {
+    match self {
+      QuadtreeShape::Point(_) => 0,
+      QuadtreeShape::Line(_) => 1,
+      _ => 2,
+    }
+  }
+
+  /// None for the lines and rays, which never end
+  fn aabb(&self) -> Option<AABB> {
+    let aabb = match self {
+      QuadtreeShape::Point(p) => AABB::two_points(*p, *p),
+      QuadtreeShape::Line(l) if l.line_type == LineType::Segment => AABB::two_points(l.from, l.to),
+      QuadtreeShape::Line(_) => return None,
+      QuadtreeShape::Circle(c) => AABB::two_points(c.center - vec2![c.radius], c.center + vec2![c.radius]),
+      QuadtreeShape::Arc(a) => a.aabb(),
+      QuadtreeShape::Conic(e) => e.aabb(),
+    };
+    let Vector2 { x, y } = aabb.max();
+    if aabb.x.abs() < MAX_EXTENT && aabb.y.abs() < MAX_EXTENT && x.abs() < MAX_EXTENT && y.abs() < MAX_EXTENT {
+      Some(aabb)
+    } else {
+      None
+    }
+  }
+}
+
+/// Entities held in the smallest square of virtual space around their shape, so that the closest
+/// one to a position is found by looking at the squares nearby first. Unlike the spatial hash
+/// table, the shapes are kept along with the entities and the distances are exact, a curve
+/// passing through the corner of a tile is not as close as one through its middle. The lines and
+/// rays are looked at by every query
+#[derive(Debug)]
+pub struct Quadtree<T: Clone + Eq + Hash> {
+  root: Node<T>,
+  shapes: HashMap<T, (QuadtreeShape, Option<AABB>)>,
+  unbounded: Vec<T>,
+}
+
+#[derive(Debug)]
+struct Node<T> {
+  aabb: AABB,
+  items: Vec<T>, // The entities too large to fit in a single child
+  children: Option<Box<[Node<T>; 4]>>,
+}
+
+impl<T: Clone + Eq + Hash> Default for Quadtree<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Clone + Eq + Hash> Quadtree<T> {
+  pub fn new() -> Self {
+    Self {
+      root: Node::new(AABB::new(-ROOT_SIZE / 2.0, -ROOT_SIZE / 2.0, ROOT_SIZE, ROOT_SIZE)),
+      shapes: HashMap::new(),
+      unbounded: vec![],
+    }
+  }
+
+  pub fn clear(&mut self) {
+    *self = Self::new();
+  }
+
+  pub fn len(&self) -> usize {
+    self.shapes.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.shapes.is_empty()
+  }
+
+  /// Adds the entity with its shape, replacing the shape it had
+  pub fn insert(&mut self, ent: T, shape: QuadtreeShape) {
+    self.remove(&ent);
+    let aabb = shape.aabb();
+    self.shapes.insert(ent.clone(), (shape, aabb));
+    match aabb {
+      Some(aabb) => {
+        while !contains(&self.root.aabb, &aabb) {
+          self.grow_toward(&aabb);
+        }
+        self.root.insert(ent, aabb, &self.shapes);
+      }
+      None => self.unbounded.push(ent),
+    }
+  }
+
+  pub fn remove(&mut self, ent: &T) {
+    match self.shapes.remove(ent) {
+      Some((_, Some(aabb))) => self.root.remove(ent, &aabb),
+      Some((_, None)) => self.unbounded.retain(|other| other != ent),
+      None => (),
+    }
+  }
+
+  /// Doubles the root toward the area, the former root becoming one of the four children
+  fn grow_toward(&mut self, aabb: &AABB) {
+    let old = self.root.aabb;
+    let (left, up) = (aabb.x < old.x, aabb.y < old.y);
+    let grown = AABB::new(
+      if left { old.x - old.width } else { old.x },
+      if up { old.y - old.height } else { old.y },
+      old.width * 2.0,
+      old.height * 2.0,
+    );
+    let old_root = std::mem::replace(&mut self.root, Node::new(grown));
+    if !old_root.items.is_empty() || old_root.children.is_some() {
+      let mut children = quadrants(&grown).map(Node::new);
+      children[if left { 1 } else { 0 } + if up { 2 } else { 0 }] = old_root;
+      self.root.children = Some(Box::new(children));
+    }
+  }
+
+  /// The entity closest to `p` that is at most `max_dist` away, with its distance. The squares
+  /// further than the closest entity found so far are left out
+  pub fn nearest_entity(&self, p: Vector2, max_dist: f64) -> Option<(T, f64)> {
+    let mut nearest: Option<(&T, f64, usize)> = None;
+    for ent in &self.unbounded {
+      self.closer(ent, p, max_dist, &mut nearest);
+    }
+    let mut stack = vec![&self.root];
+    while let Some(node) = stack.pop() {
+      let reach = nearest.map_or(max_dist, |(_, dist, _)| dist);
+      if distance_to_aabb(&node.aabb, p) > reach {
+        continue;
+      }
+      for ent in &node.items {
+        self.closer(ent, p, max_dist, &mut nearest);
+      }
+      // The closest child is popped first, the others are then more likely to be too far
+      if let Some(children) = &node.children {
+        let mut children = children.iter().collect::<Vec<_>>();
+        children.sort_by(|c1, c2| {
+          distance_to_aabb(&c2.aabb, p)
+            .partial_cmp(&distance_to_aabb(&c1.aabb, p))
+            .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        stack.extend(children);
+      }
+    }
+    nearest.map(|(ent, dist, _)| (ent.clone(), dist))
+  }
+
+  /// Every entity at most `max_dist` away from `p` with its distance, the closest first
+  pub fn entities_within(&self, p: Vector2, max_dist: f64) -> Vec<(T, f64)> {
+    let mut within = vec![];
+    let mut measure = |ent: &T| {
+      let (shape, _) = &self.shapes[ent];
+      let dist = shape.distance_to(p);
+      if dist <= max_dist {
+        within.push((ent.clone(), dist, shape.rank()));
+      }
+    };
+    self.unbounded.iter().for_each(&mut measure);
+    let mut stack = vec![&self.root];
+    while let Some(node) = stack.pop() {
+      if distance_to_aabb(&node.aabb, p) <= max_dist {
+        node.items.iter().for_each(&mut measure);
+        if let Some(children) = &node.children {
+          stack.extend(children.iter());
+        }
+      }
+    }
+    within.sort_by(|(_, d1, r1), (_, d2, r2)| (d1, r1).partial_cmp(&(d2, r2)).unwrap_or(std::cmp::Ordering::Equal));
+    within.into_iter().map(|(ent, dist, _)| (ent, dist)).collect()
+  }
+
+  /// Keeps the entity as the nearest one when it is closer, or as close with a lower rank
+  fn closer<'a>(&self, ent: &'a T, p: Vector2, max_dist: f64, nearest: &mut Option<(&'a T, f64, usize)>) {
+    let (shape, _) = &self.shapes[ent];
+    let (dist, rank) = (shape.distance_to(p), shape.rank());
+    let closer = match nearest {
+      Some((_, nearest_dist, nearest_rank)) => (dist, rank) < (*nearest_dist, *nearest_rank),
+      None => true,
+    };
+    if dist <= max_dist && closer {
+      *nearest = Some((ent, dist, rank));
+    }
+  }
+}
+
+impl<T: Clone + Eq + Hash> Node<T> {
+  fn new(aabb: AABB) -> Self {
+    Self {
+      aabb,
+      items: vec![],
+      children: None,
+    }
+  }
+
+  fn insert(&mut self, ent: T, aabb: AABB, shapes: &HashMap<T, (QuadtreeShape, Option<AABB>)>) {
+    if let Some(children) = &mut self.children {
+      if let Some(index) = quadrant_of(&self.aabb, &aabb) {
+        children[index].insert(ent, aabb, shapes);
+        return;
+      }
+    }
+    self.items.push(ent);
+    if self.children.is_none() && self.items.len() > MAX_NODE_ITEMS && self.aabb.width > MIN_NODE_SIZE {
+      self.children = Some(Box::new(quadrants(&self.aabb).map(Node::new)));
+      for ent in std::mem::take(&mut self.items) {
+        if let (_, Some(aabb)) = shapes[&ent] {
+          self.insert(ent, aabb, shapes);
+        }
+      }
+    }
+  }
+
+  fn remove(&mut self, ent: &T, aabb: &AABB) {
+    if let Some(position) = self.items.iter().position(|other| other == ent) {
+      self.items.swap_remove(position);
+    } else if let (Some(children), Some(index)) = (&mut self.children, quadrant_of(&self.aabb, aabb)) {
+      children[index].remove(ent, aabb);
+    }
+  }
+}
+
+/// The four quarters of the area, left to right then top to bottom
+fn quadrants(aabb: &AABB) -> [AABB; 4] {
+  let (width, height) = (aabb.width / 2.0, aabb.height / 2.0);
+  let (mid_x, mid_y) = (aabb.x + width, aabb.y + height);
+  [
+    AABB::new(aabb.x, aabb.y, width, height),
+    AABB::new(mid_x, aabb.y, width, height),
+    AABB::new(aabb.x, mid_y, width, height),
+    AABB::new(mid_x, mid_y, width, height),
+  ]
+}
+
+/// The quarter of `outer` holding the whole of `inner`, None when it lies across the middle
+fn quadrant_of(outer: &AABB, inner: &AABB) -> Option<usize> {
+  let (mid_x, mid_y) = (outer.x + outer.width / 2.0, outer.y + outer.height / 2.0);
+  let column = if inner.x + inner.width < mid_x {
+    0
+  } else if inner.x >= mid_x {
+    1
+  } else {
+    return None;
+  };
+  let row = if inner.y + inner.height < mid_y {
+    0
+  } else if inner.y >= mid_y {
+    2
+  } else {
+    return None;
+  };
+  Some(column + row)
+}
+
+fn contains(outer: &AABB, inner: &AABB) -> bool {
+  outer.contains(inner.min()) && outer.contains(inner.max())
+}
+
+fn distance_to_aabb(aabb: &AABB, p: Vector2) -> f64 {
+  (aabb.get_closest_point_to(p) - p).magnitude()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn segment(from: Vector2, to: Vector2) -> QuadtreeShape {
+    QuadtreeShape::Line(Line {
+      from,
+      to,
+      line_type: LineType::Segment,
+    })
+  }
+
+  #[test]
+  fn test_nearest_curve_is_measured_exactly() {
+    let mut quadtree = Quadtree::<usize>::new();
+    quadtree.insert(0, segment(vec2![-5., 0.], vec2![5., 0.]));
+    quadtree.insert(1, segment(vec2![-5., 0.3], vec2![5., 0.3]));
+    quadtree.insert(
+      2,
+      QuadtreeShape::Circle(Circle {
+        center: vec2![0., 2.],
+        radius: 1.5,
+      }),
+    );
+
+    // Between the two lines the closer one wins, however close they are
+    assert_eq!(
+      quadtree.nearest_entity(vec2![1., 0.14], 1.).map(|(ent, _)| ent),
+      Some(0)
+    );
+    assert_eq!(
+      quadtree.nearest_entity(vec2![1., 0.16], 1.).map(|(ent, _)| ent),
+      Some(1)
+    );
+
+    // The circle is closer than the lines right below it
+    assert_eq!(
+      quadtree.nearest_entity(vec2![0., 0.45], 1.).map(|(ent, _)| ent),
+      Some(2)
+    );
+    assert_eq!(
+      quadtree
+        .entities_within(vec2![0., 0.45], 1.)
+        .into_iter()
+        .map(|(ent, _)| ent)
+        .collect::<Vec<_>>(),
+      vec![2, 1, 0]
+    );
+
+    // A point as close as a line goes first
+    quadtree.insert(3, QuadtreeShape::Point(vec2![1., 0.]));
+    assert_eq!(
+      quadtree.nearest_entity(vec2![1., -0.1], 1.).map(|(ent, _)| ent),
+      Some(3)
+    );
+    assert_eq!(quadtree.nearest_entity(vec2![10., 10.], 1.), None);
+  }
+
+  #[test]
+  fn test_quadtree_grows_and_splits() {
+    let mut quadtree = Quadtree::<usize>::new();
+    for i in 0..1000 {
+      let position = vec2![(i % 40) as f64 * 10. - 200., (i / 40) as f64 * 10. - 100.];
+      quadtree.insert(i, QuadtreeShape::Point(position));
+    }
+    quadtree.insert(
+      1000,
+      QuadtreeShape::Line(Line {
+        from: vec2![0., 1e6],
+        to: vec2![1., 1e6],
+        line_type: LineType::Straight,
+      }),
+    );
+    assert_eq!(quadtree.len(), 1001);
+    assert!(quadtree.root.aabb.width >= 400.);
+    assert!(quadtree.root.items.len() < 1000);
+
+    // Far from everything only the line is within reach
+    assert_eq!(quadtree.nearest_entity(vec2![5e5, 1e6 - 1.], 2.), Some((1000, 1.)));
+    assert_eq!(
+      quadtree.nearest_entity(vec2![-149., -99.], 2.).map(|(ent, _)| ent),
+      Some(5)
+    );
+
+    // Moving a point takes it out of the square it was in
+    quadtree.insert(5, QuadtreeShape::Point(vec2![1e3, 1e3]));
+    assert_eq!(quadtree.nearest_entity(vec2![-149., -99.], 2.), None);
+    assert_eq!(quadtree.nearest_entity(vec2![1e3, 1e3], 2.), Some((5, 0.)));
+    quadtree.remove(&5);
+    quadtree.remove(&1000);
+    assert_eq!(quadtree.len(), 999);
+    assert_eq!(quadtree.entities_within(vec2![1e3, 1e3], 2.), vec![]);
+  }
+}
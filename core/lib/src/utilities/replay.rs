@@ -0,0 +1,197 @@
+use super::{GeometryKind, Instant};
+use crate::{events::*, resources::*};
+use specs::prelude::*;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReplaySpeed {
+  RealTime,
+  AsFastAsPossible,
+}
+
+/// Feed a recorded command log back into `world`, dispatching once for every recorded frame.
+/// Entities created while recording are mapped to the ones created during the replay so that
+/// the later commands refer to the right geometries.
+pub fn replay<'a, 'b>(world: &mut World, dispatcher: &mut Dispatcher<'a, 'b>, log: &CommandLog, speed: ReplaySpeed) {
+  let mut geometry_event_reader = world.fetch_mut::<GeometryEventChannel>().register_reader();
+  let mut entity_map: HashMap<Entity, Entity> = HashMap::new();
+  let start = Instant::now();
+  let mut frames = log
+    .commands()
+    .iter()
+    .map(|recorded| (recorded.frame, recorded.time))
+    .chain(
+      log
+        .history_events()
+        .iter()
+        .map(|recorded| (recorded.frame, recorded.time)),
+    )
+    .collect::<Vec<_>>();
+  frames.sort_by_key(|(frame, _)| *frame);
+  frames.dedup_by_key(|(frame, _)| *frame);
+  let mut commands = log.commands().iter().peekable();
+  let mut history_events = log.history_events().iter().peekable();
+  for (frame, time) in frames {
+    if speed == ReplaySpeed::RealTime {
+      let elapsed = start.elapsed();
+      if time > elapsed {
+        // The browser cannot block its thread, the frames are replayed as fast as possible there
+        #[cfg(not(target_arch = "wasm32"))]
+        thread::sleep(time - elapsed);
+      }
+    }
+
+    // Write all the commands and history events of this frame
+    {
+      let mut command_event_channel = world.fetch_mut::<CommandEventChannel>();
+      while let Some(recorded) = commands.next_if(|recorded| recorded.frame == frame) {
+        let command = recorded
+          .event
+          .command
+          .remap(&mut |ent| *entity_map.get(&ent).unwrap_or(&ent));
+        command_event_channel.single_write(CommandEvent {
+          command,
+          event_id: recorded.event.event_id,
+        });
+      }
+      let mut history_event_channel = world.fetch_mut::<HistoryEventChannel>();
+      while let Some(recorded) = history_events.next_if(|recorded| recorded.frame == frame) {
+        history_event_channel.single_write(recorded.event);
+      }
+    }
+
+    dispatcher.dispatch(world);
+    world.maintain();
+
+    // Pair up the entities inserted in this frame with the recorded ones. Different kinds of
+    // geometries are inserted by different systems so only the order within a kind is reliable
+    let mut inserted: Vec<Option<(Entity, GeometryKind)>> = vec![];
+    for event in world.fetch::<GeometryEventChannel>().read(&mut geometry_event_reader) {
+      if let GeometryEvent::Inserted(ent, geom, false) = event {
+        inserted.push(Some((*ent, geom.kind())));
+      }
+    }
+    for recorded in log.insertions().iter().filter(|insertion| insertion.frame == frame) {
+      let maybe_slot = inserted.iter_mut().find(|slot| match slot {
+        Some((_, kind)) => *kind == recorded.kind,
+        None => false,
+      });
+      if let Some(slot) = maybe_slot {
+        if let Some((ent, _)) = slot.take() {
+          entity_map.insert(recorded.entity, ent);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{
+    components::{symbolics::*, virtual_shapes::*},
+    math::*,
+    test_utils::headless,
+    utilities::*,
+  };
+
+  fn step(world: &mut World, dispatcher: &mut Dispatcher, commands: Vec<Command>) {
+    for command in commands {
+      world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+        command,
+        event_id: None,
+      });
+    }
+    dispatcher.dispatch(world);
+    world.maintain();
+  }
+
+  fn last_insertions(world: &World, count: usize) -> Vec<Entity> {
+    let log = world.fetch::<CommandLog>();
+    let insertions = log.insertions();
+    insertions[insertions.len() - count..]
+      .iter()
+      .map(|i| i.entity)
+      .collect()
+  }
+
+  fn solved(world: &World) -> (Vec<(f64, f64)>, Vec<(f64, f64, f64, f64)>, Vec<(f64, f64, f64)>) {
+    let mut points: Vec<_> = (&world.read_storage::<VirtualPoint>())
+      .join()
+      .map(|p| (p.0.x, p.0.y))
+      .collect();
+    let mut lines: Vec<_> = (&world.read_storage::<VirtualLine>())
+      .join()
+      .map(|l| (l.from.0.x, l.from.0.y, l.to.0.x, l.to.0.y))
+      .collect();
+    let mut circles: Vec<_> = (&world.read_storage::<VirtualCircle>())
+      .join()
+      .map(|c| (c.center.0.x, c.center.0.y, c.radius.0))
+      .collect();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    lines.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    circles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    (points, lines, circles)
+  }
+
+  #[test]
+  fn test_replay_reproduces_construction() {
+    let (mut world, mut dispatcher) = headless();
+    world.fetch_mut::<CommandLog>().start_recording();
+
+    step(
+      &mut world,
+      &mut dispatcher,
+      vec![
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![0., 0.])),
+        Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![4., 2.])),
+      ],
+    );
+    let ps = last_insertions(&world, 2);
+    step(
+      &mut world,
+      &mut dispatcher,
+      vec![
+        Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(
+          ps[0], ps[1],
+        ))),
+        Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(ps[0], ps[1]))),
+        Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::MidPoint(ps[0], ps[1]))),
+      ],
+    );
+    let line = (&world.entities(), &world.read_storage::<SymbolicLine>())
+      .join()
+      .map(|(ent, _)| ent)
+      .next()
+      .unwrap();
+    step(
+      &mut world,
+      &mut dispatcher,
+      vec![Command::PointInsert(InsertPointEvent::InsertPoint(
+        SymbolicPoint::OnLine(line, VirtualScalar(0.25)),
+      ))],
+    );
+    world.fetch_mut::<CommandLog>().stop_recording();
+    let log = world.fetch::<CommandLog>().clone();
+    assert_eq!(log.commands().len(), 6);
+
+    // Shift the entity ids of the fresh world so that remapping is actually needed
+    let (mut replay_world, mut replay_dispatcher) = headless();
+    replay_world.create_entity().build();
+    replay_world.create_entity().build();
+    replay(
+      &mut replay_world,
+      &mut replay_dispatcher,
+      &log,
+      ReplaySpeed::AsFastAsPossible,
+    );
+
+    let (points, lines, circles) = solved(&replay_world);
+    assert_eq!(points.len(), 4);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(circles.len(), 1);
+    assert_eq!((points, lines, circles), solved(&world));
+  }
+}
@@ -24,7 +24,7 @@ impl Div<ScreenScalar> for ScreenScalar {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ScreenPosition(pub Vector2);
 
 impl ScreenPosition {
@@ -87,7 +87,7 @@ impl Div<ScreenScalar> for ScreenPosition {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ScreenLine {
   pub from: ScreenPosition,
   pub to: ScreenPosition,
@@ -189,6 +189,132 @@ impl From<Circle> for ScreenCircle {
   }
 }
 
+/// Part of a circle going from the `start` angle over `sweep` radians. As the y axis of the
+/// screen points down, the arc goes clockwise on screen
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenArc {
+  pub center: ScreenPosition,
+  pub radius: ScreenScalar,
+  pub start: f64,
+  pub sweep: f64,
+}
+
+impl ScreenArc {
+  pub fn get_closest_point(self, p: ScreenPosition) -> ScreenPosition {
+    let a: Arc = self.into();
+    a.get_closest_point(p.into()).into()
+  }
+}
+
+impl Into<Arc> for ScreenArc {
+  fn into(self) -> Arc {
+    Arc {
+      center: self.center.into(),
+      radius: self.radius.into(),
+      start: self.start,
+      sweep: self.sweep,
+    }
+  }
+}
+
+impl From<Arc> for ScreenArc {
+  fn from(a: Arc) -> Self {
+    Self {
+      center: a.center.into(),
+      radius: a.radius.into(),
+      start: a.start,
+      sweep: a.sweep,
+    }
+  }
+}
+
+/// Ellipse with radius `rx` along its own x axis and `ry` along its own y axis, turned by
+/// `rotation` radians. As the y axis of the screen points down, the ellipse is turned clockwise
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenConic {
+  pub center: ScreenPosition,
+  pub rx: ScreenScalar,
+  pub ry: ScreenScalar,
+  pub rotation: f64,
+}
+
+impl ScreenConic {
+  pub fn get_closest_point(self, p: ScreenPosition) -> ScreenPosition {
+    let e: Ellipse = self.into();
+    e.get_closest_point(p.into()).into()
+  }
+
+  pub fn parameter_of(self, p: ScreenPosition) -> f64 {
+    let e: Ellipse = self.into();
+    e.parameter_of(p.into())
+  }
+}
+
+impl Into<Ellipse> for ScreenConic {
+  fn into(self) -> Ellipse {
+    Ellipse {
+      center: self.center.into(),
+      rx: self.rx.into(),
+      ry: self.ry.into(),
+      rotation: self.rotation,
+    }
+  }
+}
+
+impl From<Ellipse> for ScreenConic {
+  fn from(e: Ellipse) -> Self {
+    Self {
+      center: e.center.into(),
+      rx: e.rx.into(),
+      ry: e.ry.into(),
+      rotation: e.rotation,
+    }
+  }
+}
+
+/// Closed polygon, the last vertex connects back to the first one
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenPolygon {
+  pub vertices: Vec<ScreenPosition>,
+}
+
+impl Into<Polygon> for ScreenPolygon {
+  fn into(self) -> Polygon {
+    Polygon::new(self.vertices.into_iter().map(|v| v.0).collect())
+  }
+}
+
+/// Angle between each side of the arrowhead and the body of the vector
+const ARROWHEAD_ANGLE: f64 = std::f64::consts::PI / 6.0;
+
+/// Arrow from one position to another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenVector {
+  pub from: ScreenPosition,
+  pub to: ScreenPosition,
+}
+
+impl ScreenVector {
+  /// The ends of the two sides of the arrowhead, drawn from the tip of the vector. None for a
+  /// vector of zero length, which points nowhere
+  pub fn arrowhead(self, head_length: f64) -> Option<(ScreenPosition, ScreenPosition)> {
+    let direction = (self.from - self.to).0;
+    if direction.is_zero() {
+      return None;
+    }
+    let side = direction.normalized() * head_length;
+    let side_end = |radians: f64| ScreenPosition(self.to.0 + side.rotate(radians));
+    Some((side_end(ARROWHEAD_ANGLE), side_end(-ARROWHEAD_ANGLE)))
+  }
+}
+
+/// Text drawn from a position, the bottom left corner of the first line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenText {
+  pub position: ScreenPosition,
+  pub text: String,
+}
+
 impl Project<ScreenCircle> for ScreenPosition {
   type Output = Self;
 
@@ -253,3 +379,49 @@ impl Intersect<ScreenCircle> for ScreenLine {
     c.intersect(l).into()
   }
 }
+
+impl Intersect<ScreenLine> for ScreenArc {
+  type Output = ScreenCircleIntersect;
+
+  fn intersect(self, other: ScreenLine) -> Self::Output {
+    let a: Arc = self.into();
+    let l: Line = other.into();
+    a.intersect(l).into()
+  }
+}
+
+impl Intersect<ScreenCircle> for ScreenArc {
+  type Output = ScreenCircleIntersect;
+
+  fn intersect(self, other: ScreenCircle) -> Self::Output {
+    let a: Arc = self.into();
+    let c: Circle = other.into();
+    a.intersect(c).into()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_arrowhead_points_back_from_the_tip() {
+    let vector = ScreenVector {
+      from: vec2![0., 0.].into(),
+      to: vec2![10., 0.].into(),
+    };
+    let (side1, side2) = vector.arrowhead(2.).unwrap();
+    let expected = [vec2![10. - 3f64.sqrt(), 1.], vec2![10. - 3f64.sqrt(), -1.]];
+    let mut sides = [side1.0, side2.0];
+    sides.sort_by(|a, b| b.y.partial_cmp(&a.y).unwrap());
+    for (side, expected) in sides.iter().zip(expected.iter()) {
+      assert!((*side - *expected).magnitude() < 1e-12, "{:?} != {:?}", side, expected);
+    }
+
+    let zero = ScreenVector {
+      from: vec2![1., 1.].into(),
+      to: vec2![1., 1.].into(),
+    };
+    assert_eq!(zero.arrowhead(2.), None);
+  }
+}
@@ -0,0 +1,201 @@
+use super::SolvedGeometry;
+use crate::{components::symbolics::*, events::*, math::*, setup_core_lib};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Builds a sketch from code, without any window. Every geometry is inserted right away so that
+/// the next ones can refer to it, and `solve` gives the concrete shapes. Panics when a geometry
+/// can't be inserted, which happens once the maximum amount of entities is reached
+pub struct SketchBuilder<'a, 'b> {
+  world: World,
+  dispatcher: Dispatcher<'a, 'b>,
+  geometry_event_reader: GeometryEventReader,
+  geometries: Vec<Entity>, // In the order they got inserted
+}
+
+impl<'a, 'b> Default for SketchBuilder<'a, 'b> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<'a, 'b> SketchBuilder<'a, 'b> {
+  pub fn new() -> Self {
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    setup_core_lib(&mut builder);
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+    let geometry_event_reader = world.fetch_mut::<GeometryEventChannel>().register_reader();
+    Self {
+      world,
+      dispatcher,
+      geometry_event_reader,
+      geometries: vec![],
+    }
+  }
+
+  /// The world holding the sketch, to read anything the builder doesn't give
+  pub fn world(&self) -> &World {
+    &self.world
+  }
+
+  pub fn free_point(&mut self, x: f64, y: f64) -> Entity {
+    self.insert(Command::PointInsert(InsertPointEvent::InsertPoint(
+      SymbolicPoint::Free(vec2![x, y].into()),
+    )))
+  }
+
+  pub fn fixed_point(&mut self, x: f64, y: f64) -> Entity {
+    self.insert(Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![x, y])))
+  }
+
+  pub fn midpoint(&mut self, p1: Entity, p2: Entity) -> Entity {
+    self.point_of(SymbolicPoint::MidPoint(p1, p2))
+  }
+
+  /// Point dividing the way from p1 to p2, `ratio` being the fraction of the way from p1
+  pub fn ratio_point(&mut self, p1: Entity, p2: Entity, ratio: f64) -> Entity {
+    self.point_of(SymbolicPoint::Ratio(p1, p2, ratio))
+  }
+
+  /// Intersection of two lines
+  pub fn intersect(&mut self, l1: Entity, l2: Entity) -> Entity {
+    self.point_of(SymbolicPoint::LineLineIntersect(l1, l2))
+  }
+
+  pub fn point_of(&mut self, sym_point: SymbolicPoint) -> Entity {
+    self.insert(Command::PointInsert(InsertPointEvent::InsertPoint(sym_point)))
+  }
+
+  pub fn line(&mut self, p1: Entity, p2: Entity) -> Entity {
+    self.line_of(SymbolicLine::Straight(p1, p2))
+  }
+
+  pub fn ray(&mut self, p1: Entity, p2: Entity) -> Entity {
+    self.line_of(SymbolicLine::Ray(p1, p2))
+  }
+
+  pub fn segment(&mut self, p1: Entity, p2: Entity) -> Entity {
+    self.line_of(SymbolicLine::Segment(p1, p2))
+  }
+
+  pub fn parallel(&mut self, line: Entity, point: Entity) -> Entity {
+    self.line_of(SymbolicLine::Parallel(line, point))
+  }
+
+  pub fn perpendicular(&mut self, line: Entity, point: Entity) -> Entity {
+    self.line_of(SymbolicLine::Perpendicular(line, point))
+  }
+
+  pub fn line_of(&mut self, sym_line: SymbolicLine) -> Entity {
+    self.insert(Command::LineInsert(InsertLineEvent::InsertLine(sym_line)))
+  }
+
+  /// Circle around `center` going through `on_circle`
+  pub fn circle(&mut self, center: Entity, on_circle: Entity) -> Entity {
+    self.circle_of(SymbolicCircle::CenterRadius(center, on_circle))
+  }
+
+  pub fn circle_of(&mut self, sym_circle: SymbolicCircle) -> Entity {
+    self.insert(Command::CircleInsert(InsertCircleEvent::InsertCircle(sym_circle)))
+  }
+
+  /// Moves a free or fixed point, the geometries depending on it follow
+  pub fn move_point(&mut self, point: Entity, x: f64, y: f64) {
+    let old_sym_point = *self.world.read_storage::<SymbolicPoint>().get(point).unwrap();
+    let new_sym_point = match old_sym_point {
+      SymbolicPoint::Free(_) => SymbolicPoint::Free(vec2![x, y].into()),
+      SymbolicPoint::Fixed(_) => SymbolicPoint::Fixed(vec2![x, y].into()),
+      _ => return,
+    };
+    self.step(vec![
+      Command::Update(UpdateEvent::UpdatePoint(point, old_sym_point, new_sym_point)),
+      Command::Update(UpdateEvent::UpdatePointEnd(point, old_sym_point, new_sym_point)),
+    ]);
+  }
+
+  /// The concrete shapes of all the geometries inserted. The undefined ones, such as the
+  /// intersection of parallel lines, are left out
+  pub fn solve(&mut self) -> HashMap<Entity, SolvedGeometry> {
+    self.step(vec![]);
+    let world = &self.world;
+    self
+      .geometries
+      .iter()
+      .filter_map(|ent| SolvedGeometry::of(world, *ent).map(|geometry| (*ent, geometry)))
+      .collect()
+  }
+
+  /// Position of the point, none when it is undefined
+  pub fn point(&mut self, point: Entity) -> Option<Vector2> {
+    match self.solve().get(&point) {
+      Some(SolvedGeometry::Point(position)) => Some(*position),
+      _ => None,
+    }
+  }
+
+  fn insert(&mut self, command: Command) -> Entity {
+    self.step(vec![command]);
+    let ent = self
+      .world
+      .fetch::<GeometryEventChannel>()
+      .read(&mut self.geometry_event_reader)
+      .find_map(|event| match event {
+        GeometryEvent::Inserted(ent, _, false) => Some(*ent),
+        _ => None,
+      })
+      .expect("The geometry could not be inserted");
+    self.geometries.push(ent);
+    ent
+  }
+
+  fn step(&mut self, commands: Vec<Command>) {
+    {
+      let mut command_event_channel = self.world.fetch_mut::<CommandEventChannel>();
+      for command in commands {
+        command_event_channel.single_write(CommandEvent {
+          command,
+          event_id: None,
+        });
+      }
+    }
+    self.dispatcher.dispatch(&self.world);
+    self.world.maintain();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_build_and_move_square_diagonals() {
+    let mut builder = SketchBuilder::new();
+    let a = builder.free_point(0., 0.);
+    let b = builder.free_point(4., 0.);
+    let c = builder.free_point(4., 4.);
+    let d = builder.free_point(0., 4.);
+    let ac = builder.segment(a, c);
+    let bd = builder.segment(b, d);
+    let center = builder.intersect(ac, bd);
+    let mid = builder.midpoint(a, b);
+    let circle = builder.circle(center, mid);
+
+    let solved = builder.solve();
+    assert_eq!(solved.len(), 9);
+    assert_eq!(solved[&center], SolvedGeometry::Point(vec2![2., 2.]));
+    match solved[&circle] {
+      SolvedGeometry::Circle(circle) => assert!((circle.radius - 2.).abs() < 1e-12),
+      _ => panic!("Expected a circle"),
+    }
+
+    builder.move_point(c, 8., 4.);
+    assert_eq!(builder.point(center), Some(vec2![8. / 3., 4. / 3.]));
+
+    // Parallel lines don't meet
+    let parallel = builder.parallel(ac, b);
+    let nowhere = builder.intersect(ac, parallel);
+    assert_eq!(builder.point(nowhere), None);
+  }
+}
@@ -0,0 +1,205 @@
+use crate::{
+  components::{markers::*, styles::*, symbolics::*},
+  events::*,
+  resources::*,
+};
+use specs::prelude::*;
+
+/// The whole state of the sketch kept in memory: the symbolic geometries with their styles, what
+/// is selected and hidden, the names, the constraints, the scalars and the viewport. The solved shapes
+/// are not kept, they are derived again when restoring
+#[derive(Debug, Clone)]
+pub struct SketchSnapshot {
+  points: Vec<(Entity, SymbolicPoint, PointStyle)>,
+  lines: Vec<(Entity, SymbolicLine, LineStyle)>,
+  circles: Vec<(Entity, SymbolicCircle, CircleStyle)>,
+  selected: Vec<Entity>, // In the order they got selected
+  hidden: Vec<Entity>,
+  names: Names,
+  angle_constraints: AngleConstraints,
+  constraints: Constraints,
+  scalars: ScalarExpressions,
+  viewport: Viewport,
+}
+
+pub fn snapshot(world: &World) -> SketchSnapshot {
+  let entities = world.entities();
+  let sym_points = world.read_storage::<SymbolicPoint>();
+  let point_styles = world.read_storage::<PointStyle>();
+  let sym_lines = world.read_storage::<SymbolicLine>();
+  let line_styles = world.read_storage::<LineStyle>();
+  let sym_circles = world.read_storage::<SymbolicCircle>();
+  let circle_styles = world.read_storage::<CircleStyle>();
+  let hiddens = world.read_storage::<Hidden>();
+  SketchSnapshot {
+    points: (&entities, &sym_points, &point_styles)
+      .join()
+      .map(|(ent, sym_point, point_style)| (ent, *sym_point, *point_style))
+      .collect(),
+    lines: (&entities, &sym_lines, &line_styles)
+      .join()
+      .map(|(ent, sym_line, line_style)| (ent, *sym_line, *line_style))
+      .collect(),
+    circles: (&entities, &sym_circles, &circle_styles)
+      .join()
+      .map(|(ent, sym_circle, circle_style)| (ent, *sym_circle, *circle_style))
+      .collect(),
+    selected: world.fetch::<SelectionOrder>().iter().cloned().collect(),
+    hidden: (&entities, &hiddens).join().map(|(ent, _)| ent).collect(),
+    names: (*world.fetch::<Names>()).clone(),
+    angle_constraints: (*world.fetch::<AngleConstraints>()).clone(),
+    constraints: (*world.fetch::<Constraints>()).clone(),
+    scalars: (*world.fetch::<ScalarExpressions>()).clone(),
+    viewport: *world.fetch::<Viewport>(),
+  }
+}
+
+/// Brings the world back to the snapshot taken from it. Every geometry is removed and the ones of
+/// the snapshot are inserted again under their own entities, all with commands that are handled
+/// on the next dispatch so that the dependency graph and the solved shapes are rebuilt. The undo
+/// history is cleared since it refers to the geometries being replaced
+pub fn restore(world: &mut World, snapshot: &SketchSnapshot) {
+  world
+    .fetch_mut::<HistoryEventChannel>()
+    .single_write(HistoryEvent::Clear);
+  *world.fetch_mut::<Names>() = snapshot.names.clone();
+  *world.fetch_mut::<AngleConstraints>() = snapshot.angle_constraints.clone();
+  *world.fetch_mut::<Constraints>() = snapshot.constraints.clone();
+  *world.fetch_mut::<ScalarExpressions>() = snapshot.scalars.clone();
+  world
+    .fetch_mut::<ViewportEventChannel>()
+    .single_write(ViewportEvent::Restore(snapshot.viewport));
+
+  let current = (&world.entities(), &world.read_storage::<Element>())
+    .join()
+    .map(|(ent, _)| ent)
+    .collect::<Vec<_>>();
+  let mut commands = current
+    .into_iter()
+    .map(|ent| Command::Remove(RemoveEvent::RemoveByHistory(ent)))
+    .collect::<Vec<_>>();
+
+  // Inserted in the order the entities got created, so that the geometries come after the ones
+  // they depend on
+  let mut points = snapshot.points.clone();
+  points.sort_by_key(|(ent, _, _)| ent.id());
+  let mut lines = snapshot.lines.clone();
+  lines.sort_by_key(|(ent, _, _)| ent.id());
+  let mut circles = snapshot.circles.clone();
+  circles.sort_by_key(|(ent, _, _)| ent.id());
+  commands.extend(points.into_iter().map(|(ent, sym_point, point_style)| {
+    Command::PointInsert(InsertPointEvent::InsertPointByHistory(ent, sym_point, point_style))
+  }));
+  commands.extend(lines.into_iter().map(|(ent, sym_line, line_style)| {
+    Command::LineInsert(InsertLineEvent::InsertLineByHistory(ent, sym_line, line_style))
+  }));
+  commands.extend(circles.into_iter().map(|(ent, sym_circle, circle_style)| {
+    Command::CircleInsert(InsertCircleEvent::InsertCircleByHistory(ent, sym_circle, circle_style))
+  }));
+
+  // Inserting selects, so the selection is set again after
+  commands.push(Command::Select(SelectEvent::DeselectAll));
+  commands.extend(
+    snapshot
+      .selected
+      .iter()
+      .map(|ent| Command::Select(SelectEvent::Select(*ent))),
+  );
+  commands.extend(
+    snapshot
+      .hidden
+      .iter()
+      .map(|ent| Command::Hide(HideEvent::HideByHistory(*ent))),
+  );
+
+  let mut command_event_channel = world.fetch_mut::<CommandEventChannel>();
+  for command in commands {
+    command_event_channel.single_write(CommandEvent {
+      command,
+      event_id: None,
+    });
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{components::virtual_shapes::*, math::*, test_utils::*};
+
+  fn solved(world: &World) -> Vec<String> {
+    let entities = world.entities();
+    let virt_points = world.read_storage::<VirtualPoint>();
+    let virt_lines = world.read_storage::<VirtualLine>();
+    let virt_circles = world.read_storage::<VirtualCircle>();
+    let mut shapes = vec![];
+    shapes.extend(
+      (&entities, &virt_points)
+        .join()
+        .map(|(ent, p)| format!("{:?} {:?}", ent, p)),
+    );
+    shapes.extend(
+      (&entities, &virt_lines)
+        .join()
+        .map(|(ent, l)| format!("{:?} {:?}", ent, l)),
+    );
+    shapes.extend(
+      (&entities, &virt_circles)
+        .join()
+        .map(|(ent, c)| format!("{:?} {:?}", ent, c)),
+    );
+    shapes
+  }
+
+  #[test]
+  fn test_snapshot_mutate_restore() {
+    let (mut world, mut dispatcher) = headless();
+
+    let mut insert_point =
+      |world: &mut World, sym_point: SymbolicPoint| insert_point(world, &mut dispatcher, sym_point);
+    let p1 = insert_point(&mut world, SymbolicPoint::Free(vec2![0., 0.].into()));
+    let p2 = insert_point(&mut world, SymbolicPoint::Free(vec2![4., 2.].into()));
+    let mid = insert_point(&mut world, SymbolicPoint::MidPoint(p1, p2));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, p2))),
+    );
+    let line = last_inserted::<SymbolicLine>(&world);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::CircleInsert(InsertCircleEvent::InsertCircle(SymbolicCircle::CenterRadius(mid, p2))),
+    );
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::DeselectAll));
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(line)));
+    step(&mut world, &mut dispatcher, Command::Select(SelectEvent::Select(p1)));
+    step(&mut world, &mut dispatcher, Command::Hide(HideEvent::Hide(mid)));
+    let before = solved(&world);
+    let saved = snapshot(&world);
+
+    // Move a point, remove the line and add another point
+    let new = SymbolicPoint::Free(vec2![-3., 1.].into());
+    let old = SymbolicPoint::Free(vec2![4., 2.].into());
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Update(UpdateEvent::UpdatePointEnd(p2, old, new)),
+    );
+    step(&mut world, &mut dispatcher, Command::Remove(RemoveEvent::Remove(line)));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPointAt(vec2![7., 7.])),
+    );
+    assert_ne!(solved(&world), before);
+
+    restore(&mut world, &saved);
+    dispatcher.dispatch(&world);
+    world.maintain();
+    assert_eq!(solved(&world), before);
+    let selection = world.fetch::<SelectionOrder>().iter().cloned().collect::<Vec<_>>();
+    assert_eq!(selection, vec![line, p1]);
+    assert!(world.read_storage::<Hidden>().get(mid).is_some());
+    assert!(world.fetch_mut::<History>().undo().is_none());
+  }
+}
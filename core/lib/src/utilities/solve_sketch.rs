@@ -0,0 +1,361 @@
+use super::VirtualPosition;
+use crate::{
+  components::{symbolics::*, virtual_shapes::*},
+  events::*,
+  math::*,
+  setup_core_lib,
+};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub enum SketchPoint {
+  At(Vector2),
+  MidPoint(String, String),                                 // (Point id, Point id)
+  Ratio(String, String, f64), // (Point id, Point id, Fraction of the way from the first to the second)
+  OnLine(String, f64),        // (Line id, frac{p_to_from}{to_to_from})
+  LineLineIntersect(String, String), // (Line id, Line id)
+  OnCircle(String, f64),      // (Circle id, theta)
+  CircleLineIntersect(String, String, CircleIntersectId), // (Circle id, Line id, Id)
+  CircleCircleIntersect(String, String, CircleIntersectId), // (Circle id, Circle id, Id)
+  PointReflection(String, String), // (Source point id, Center point id)
+  Rotate(String, String, f64), // (Point id, Center point id, Counter clockwise radians)
+  Translate(String, Vector2), // (Point id, Translation vector)
+}
+
+#[derive(Debug, Clone)]
+pub enum SketchLine {
+  Straight(String, String),                   // (Point id, Point id)
+  Ray(String, String),                        // (Point id, Point id)
+  Segment(String, String),                    // (Point id, Point id)
+  Parallel(String, String),                   // (Line id, Point id)
+  Perpendicular(String, String),              // (Line id, Point id)
+  CommonTangent(String, String, TangentKind), // (Circle id, Circle id, Which tangent)
+  Tangent(String, String, u8),                // (Circle id, Point id, Which of the two tangents)
+  PerpendicularBisector(String, String),      // (Point id, Point id)
+}
+
+#[derive(Debug, Clone)]
+pub enum SketchCircle {
+  CenterRadius(String, String),        // (Center point id, Point on circle id)
+  EqualRadius(String, String),         // (Center point id, Circle id whose radius is kept equal)
+  ThreePoints(String, String, String), // (Point on circle id, Point on circle id, Point on circle id)
+}
+
+/// A geometry of a sketch, given an id that the other definitions refer to it by
+#[derive(Debug, Clone)]
+pub enum SketchDef {
+  Point(String, SketchPoint),
+  Line(String, SketchLine),
+  Circle(String, SketchCircle),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SketchError {
+  DuplicateId(String),
+  UnknownReference(String, String), // Id of the definition, the reference to no definition
+  WrongKind(String, String),        // Id of the definition, the reference to another kind of geometry
+  Cycle(String),                    // Id of a definition depending on itself
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SolvedGeometry {
+  Point(Vector2),
+  Line(Line),
+  Circle(Circle),
+}
+
+impl SolvedGeometry {
+  /// The solved shape of the geometry, none when it is undefined
+  pub fn of(world: &World, ent: Entity) -> Option<Self> {
+    if let Some(point) = world.read_storage::<VirtualPoint>().get(ent) {
+      Some(SolvedGeometry::Point(point.0))
+    } else if let Some(line) = world.read_storage::<VirtualLine>().get(ent) {
+      Some(SolvedGeometry::Line((*line).into()))
+    } else {
+      world
+        .read_storage::<VirtualCircle>()
+        .get(ent)
+        .map(|circle| SolvedGeometry::Circle((*circle).into()))
+    }
+  }
+}
+
+/// The concrete geometries of a sketch by their id. Geometries that are undefined, such as the
+/// intersection of parallel lines, are left out
+#[derive(Debug, Clone)]
+pub struct SolvedSketch(HashMap<String, SolvedGeometry>);
+
+impl SolvedSketch {
+  pub fn get(&self, id: &str) -> Option<&SolvedGeometry> {
+    self.0.get(id)
+  }
+
+  pub fn point(&self, id: &str) -> Option<Vector2> {
+    match self.0.get(id) {
+      Some(SolvedGeometry::Point(position)) => Some(*position),
+      _ => None,
+    }
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (&String, &SolvedGeometry)> {
+    self.0.iter()
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Kind {
+  Point,
+  Line,
+  Circle,
+}
+
+impl SketchDef {
+  fn id(&self) -> &String {
+    match self {
+      SketchDef::Point(id, _) | SketchDef::Line(id, _) | SketchDef::Circle(id, _) => id,
+    }
+  }
+
+  fn kind(&self) -> Kind {
+    match self {
+      SketchDef::Point(_, _) => Kind::Point,
+      SketchDef::Line(_, _) => Kind::Line,
+      SketchDef::Circle(_, _) => Kind::Circle,
+    }
+  }
+
+  /// The ids the definition depends on, with the kind of geometry each has to be
+  fn references(&self) -> Vec<(&String, Kind)> {
+    match self {
+      SketchDef::Point(_, point) => match point {
+        SketchPoint::At(_) => vec![],
+        SketchPoint::MidPoint(p1, p2) | SketchPoint::PointReflection(p1, p2) => {
+          vec![(p1, Kind::Point), (p2, Kind::Point)]
+        }
+        SketchPoint::Ratio(p1, p2, _) | SketchPoint::Rotate(p1, p2, _) => vec![(p1, Kind::Point), (p2, Kind::Point)],
+        SketchPoint::Translate(p, _) => vec![(p, Kind::Point)],
+        SketchPoint::OnLine(l, _) => vec![(l, Kind::Line)],
+        SketchPoint::LineLineIntersect(l1, l2) => vec![(l1, Kind::Line), (l2, Kind::Line)],
+        SketchPoint::OnCircle(c, _) => vec![(c, Kind::Circle)],
+        SketchPoint::CircleLineIntersect(c, l, _) => vec![(c, Kind::Circle), (l, Kind::Line)],
+        SketchPoint::CircleCircleIntersect(c1, c2, _) => vec![(c1, Kind::Circle), (c2, Kind::Circle)],
+      },
+      SketchDef::Line(_, line) => match line {
+        SketchLine::Straight(p1, p2)
+        | SketchLine::Ray(p1, p2)
+        | SketchLine::Segment(p1, p2)
+        | SketchLine::PerpendicularBisector(p1, p2) => {
+          vec![(p1, Kind::Point), (p2, Kind::Point)]
+        }
+        SketchLine::Parallel(l, p) | SketchLine::Perpendicular(l, p) => vec![(l, Kind::Line), (p, Kind::Point)],
+        SketchLine::CommonTangent(c1, c2, _) => vec![(c1, Kind::Circle), (c2, Kind::Circle)],
+        SketchLine::Tangent(c, p, _) => vec![(c, Kind::Circle), (p, Kind::Point)],
+      },
+      SketchDef::Circle(_, circle) => match circle {
+        SketchCircle::CenterRadius(c, p) => vec![(c, Kind::Point), (p, Kind::Point)],
+        SketchCircle::EqualRadius(c, other) => vec![(c, Kind::Point), (other, Kind::Circle)],
+        SketchCircle::ThreePoints(p1, p2, p3) => vec![(p1, Kind::Point), (p2, Kind::Point), (p3, Kind::Point)],
+      },
+    }
+  }
+
+  /// The insert command of the definition, all its references being in `entities` already
+  fn to_command(&self, entities: &HashMap<String, Entity>) -> Command {
+    let e = |id: &String| entities[id];
+    match self {
+      SketchDef::Point(_, point) => {
+        let sym_point = match point {
+          SketchPoint::At(position) => SymbolicPoint::Fixed(VirtualPosition(*position)),
+          SketchPoint::MidPoint(p1, p2) => SymbolicPoint::MidPoint(e(p1), e(p2)),
+          SketchPoint::Ratio(p1, p2, ratio) => SymbolicPoint::Ratio(e(p1), e(p2), *ratio),
+          SketchPoint::OnLine(l, t) => SymbolicPoint::OnLine(e(l), (*t).into()),
+          SketchPoint::LineLineIntersect(l1, l2) => SymbolicPoint::LineLineIntersect(e(l1), e(l2)),
+          SketchPoint::OnCircle(c, theta) => SymbolicPoint::OnCircle(e(c), *theta),
+          SketchPoint::CircleLineIntersect(c, l, id) => SymbolicPoint::CircleLineIntersect(e(c), e(l), *id),
+          SketchPoint::CircleCircleIntersect(c1, c2, id) => SymbolicPoint::CircleCircleIntersect(e(c1), e(c2), *id),
+          SketchPoint::PointReflection(p, c) => SymbolicPoint::PointReflection(e(p), e(c)),
+          SketchPoint::Rotate(p, c, radians) => SymbolicPoint::Rotate(e(p), e(c), *radians),
+          SketchPoint::Translate(p, vector) => SymbolicPoint::Translate(e(p), VirtualPosition(*vector)),
+        };
+        Command::PointInsert(InsertPointEvent::InsertPoint(sym_point))
+      }
+      SketchDef::Line(_, line) => {
+        let sym_line = match line {
+          SketchLine::Straight(p1, p2) => SymbolicLine::Straight(e(p1), e(p2)),
+          SketchLine::Ray(p1, p2) => SymbolicLine::Ray(e(p1), e(p2)),
+          SketchLine::Segment(p1, p2) => SymbolicLine::Segment(e(p1), e(p2)),
+          SketchLine::Parallel(l, p) => SymbolicLine::Parallel(e(l), e(p)),
+          SketchLine::Perpendicular(l, p) => SymbolicLine::Perpendicular(e(l), e(p)),
+          SketchLine::CommonTangent(c1, c2, kind) => SymbolicLine::CommonTangent(e(c1), e(c2), *kind),
+          SketchLine::Tangent(c, p, index) => SymbolicLine::Tangent(e(c), e(p), *index),
+          SketchLine::PerpendicularBisector(p1, p2) => SymbolicLine::PerpendicularBisector(e(p1), e(p2)),
+        };
+        Command::LineInsert(InsertLineEvent::InsertLine(sym_line))
+      }
+      SketchDef::Circle(_, circle) => {
+        let sym_circle = match circle {
+          SketchCircle::CenterRadius(c, p) => SymbolicCircle::CenterRadius(e(c), e(p)),
+          SketchCircle::EqualRadius(c, other) => SymbolicCircle::EqualRadius(e(c), e(other)),
+          SketchCircle::ThreePoints(p1, p2, p3) => SymbolicCircle::ThreePoints(e(p1), e(p2), e(p3)),
+        };
+        Command::CircleInsert(InsertCircleEvent::InsertCircle(sym_circle))
+      }
+    }
+  }
+}
+
+/// Solves the sketch without a window or a dispatch loop to drive. The definitions can be in any
+/// order, each is inserted after the ones it refers to in a world of its own, which is dropped
+/// once the concrete geometries are taken out of it
+pub fn solve_sketch(definitions: Vec<SketchDef>) -> Result<SolvedSketch, SketchError> {
+  let order = dependency_order(&definitions)?;
+
+  let mut world = World::new();
+  let mut builder = DispatcherBuilder::new();
+  setup_core_lib(&mut builder);
+  let mut dispatcher = builder.build();
+  dispatcher.setup(&mut world);
+  let mut geometry_event_reader = world.fetch_mut::<GeometryEventChannel>().register_reader();
+
+  // One definition a frame, so that its entity is known before the next ones refer to it. The
+  // geometry is solved on the frame it is inserted
+  let mut entities = HashMap::new();
+  for i in order {
+    let def = &definitions[i];
+    world.fetch_mut::<CommandEventChannel>().single_write(CommandEvent {
+      command: def.to_command(&entities),
+      event_id: None,
+    });
+    dispatcher.dispatch(&world);
+    world.maintain();
+    let inserted = world
+      .fetch::<GeometryEventChannel>()
+      .read(&mut geometry_event_reader)
+      .find_map(|event| match event {
+        GeometryEvent::Inserted(ent, _, false) => Some(*ent),
+        _ => None,
+      });
+    if let Some(ent) = inserted {
+      entities.insert(def.id().clone(), ent);
+    }
+  }
+
+  let solved = entities
+    .into_iter()
+    .filter_map(|(id, ent)| SolvedGeometry::of(&world, ent).map(|geometry| (id, geometry)))
+    .collect();
+  Ok(SolvedSketch(solved))
+}
+
+/// The indices of the definitions, each one after all the ones it refers to
+fn dependency_order(definitions: &[SketchDef]) -> Result<Vec<usize>, SketchError> {
+  let mut indices = HashMap::new();
+  for (i, def) in definitions.iter().enumerate() {
+    if indices.insert(def.id(), i).is_some() {
+      return Err(SketchError::DuplicateId(def.id().clone()));
+    }
+  }
+
+  // None when not visited yet, Some(false) while visiting its references and Some(true) once done
+  fn visit(
+    i: usize,
+    definitions: &[SketchDef],
+    indices: &HashMap<&String, usize>,
+    visited: &mut Vec<Option<bool>>,
+    order: &mut Vec<usize>,
+  ) -> Result<(), SketchError> {
+    let def = &definitions[i];
+    match visited[i] {
+      Some(true) => return Ok(()),
+      Some(false) => return Err(SketchError::Cycle(def.id().clone())),
+      None => visited[i] = Some(false),
+    }
+    for (reference, kind) in def.references() {
+      let j = match indices.get(reference) {
+        Some(j) => *j,
+        None => return Err(SketchError::UnknownReference(def.id().clone(), reference.clone())),
+      };
+      if definitions[j].kind() != kind {
+        return Err(SketchError::WrongKind(def.id().clone(), reference.clone()));
+      }
+      visit(j, definitions, indices, visited, order)?;
+    }
+    visited[i] = Some(true);
+    order.push(i);
+    Ok(())
+  }
+
+  let mut visited = vec![None; definitions.len()];
+  let mut order = Vec::with_capacity(definitions.len());
+  for i in 0..definitions.len() {
+    visit(i, definitions, &indices, &mut visited, &mut order)?;
+  }
+  Ok(order)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn id(s: &str) -> String {
+    s.to_string()
+  }
+
+  #[test]
+  fn test_solve_triangle_centroid() {
+    // The centroid is where the medians meet, defined before everything it depends on
+    let definitions = vec![
+      SketchDef::Point(id("G"), SketchPoint::LineLineIntersect(id("median_c"), id("median_b"))),
+      SketchDef::Line(id("median_c"), SketchLine::Segment(id("C"), id("mid_ab"))),
+      SketchDef::Line(id("median_b"), SketchLine::Segment(id("B"), id("mid_ac"))),
+      SketchDef::Point(id("mid_ab"), SketchPoint::MidPoint(id("A"), id("B"))),
+      SketchDef::Point(id("mid_ac"), SketchPoint::MidPoint(id("A"), id("C"))),
+      SketchDef::Point(id("A"), SketchPoint::At(vec2![0., 0.])),
+      SketchDef::Point(id("B"), SketchPoint::At(vec2![6., 0.])),
+      SketchDef::Point(id("C"), SketchPoint::At(vec2![0., 3.])),
+    ];
+    let solved = solve_sketch(definitions).unwrap();
+    let centroid = solved.point("G").unwrap();
+    assert!((centroid - vec2![2., 1.]).magnitude() < 1e-12);
+    assert_eq!(solved.point("mid_ab"), Some(vec2![3., 0.]));
+    match solved.get("median_c") {
+      Some(SolvedGeometry::Line(line)) => assert_eq!(line.line_type, LineType::Segment),
+      _ => panic!("Expected the median to be a segment"),
+    }
+    assert_eq!(solved.iter().count(), 8);
+  }
+
+  #[test]
+  fn test_solve_sketch_errors() {
+    let point = |name: &str| SketchDef::Point(id(name), SketchPoint::At(vec2![0., 0.]));
+    assert_eq!(
+      solve_sketch(vec![point("A"), point("A")]).unwrap_err(),
+      SketchError::DuplicateId(id("A"))
+    );
+    assert_eq!(
+      solve_sketch(vec![
+        point("A"),
+        SketchDef::Line(id("l"), SketchLine::Straight(id("A"), id("B")))
+      ])
+      .unwrap_err(),
+      SketchError::UnknownReference(id("l"), id("B"))
+    );
+    assert_eq!(
+      solve_sketch(vec![
+        point("A"),
+        SketchDef::Point(id("P"), SketchPoint::OnLine(id("A"), 0.5))
+      ])
+      .unwrap_err(),
+      SketchError::WrongKind(id("P"), id("A"))
+    );
+    assert_eq!(
+      solve_sketch(vec![
+        SketchDef::Point(id("P"), SketchPoint::MidPoint(id("Q"), id("Q"))),
+        SketchDef::Point(id("Q"), SketchPoint::PointReflection(id("P"), id("P"))),
+      ])
+      .unwrap_err(),
+      SketchError::Cycle(id("P"))
+    );
+  }
+}
@@ -1,84 +1,67 @@
-use crate::{math::*, resources::WINDOW_SIZE};
-use std::collections::HashSet;
+use crate::math::*;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 
-static TILE_SIZE: f64 = 40.0;
+static TILE_SIZE: f64 = 1.0; // Virtual unit, about 48 pixels with the default viewport
+static MAX_SHAPE_TILES: i64 = 4096; // Shapes covering more tiles are not put in any of them
 
+/// Entities hashed by the tiles of virtual space their geometry goes through. Only the tiles
+/// holding something are stored, so the whole canvas is covered and nothing changes when the
+/// viewport moves. The straight lines, the rays and the shapes too large to be worth tiling are
+/// kept apart and returned by every query
 #[derive(Debug)]
 pub struct SpatialHashTable<T: Clone + Eq + Hash> {
-  width: f64,
-  height: f64,
-  x_tiles: usize,
-  y_tiles: usize,
-  table: Vec<HashSet<T>>,
+  table: HashMap<Tile, HashSet<T>>,
+  tiles: HashMap<T, Vec<Tile>>, // The tiles each entity is in, so that it's removed from them alone
+  unbounded: HashSet<T>,
 }
 
 impl<T: Clone + Eq + Hash> Default for SpatialHashTable<T> {
   fn default() -> Self {
-    Self::new(WINDOW_SIZE[0], WINDOW_SIZE[1])
+    Self::new()
   }
 }
 
 type Tile = (i64, i64);
 
-type TileId = usize;
-
 impl<T: Clone + Eq + Hash> SpatialHashTable<T> {
-  pub fn new(width: f64, height: f64) -> Self {
-    let x_tiles = (width / TILE_SIZE).ceil() as usize;
-    let y_tiles = (height / TILE_SIZE).ceil() as usize;
+  pub fn new() -> Self {
     Self {
-      width,
-      height,
-      x_tiles,
-      y_tiles,
-      table: vec![HashSet::new(); x_tiles * y_tiles],
+      table: HashMap::new(),
+      tiles: HashMap::new(),
+      unbounded: HashSet::new(),
     }
   }
 
-  pub fn set_size(&mut self, width: f64, height: f64) {
-    self.width = width;
-    self.height = height;
-    self.x_tiles = (width / TILE_SIZE).ceil() as usize;
-    self.y_tiles = (height / TILE_SIZE).ceil() as usize;
-    self.table = vec![HashSet::new(); self.x_tiles * self.y_tiles];
-  }
-
   pub fn clear(&mut self) {
-    for set in &mut self.table {
-      set.clear();
-    }
-  }
-
-  fn tile_to_id(&self, tile: Tile) -> Option<TileId> {
-    if 0 <= tile.0 && tile.0 < self.x_tiles as i64 && 0 <= tile.1 && tile.1 < self.y_tiles as i64 {
-      Some(tile.1 as usize * self.x_tiles + tile.0 as usize)
-    } else {
-      None
-    }
-  }
-
-  fn aabb(&self) -> AABB {
-    AABB {
-      x: 0.0,
-      y: 0.0,
-      width: self.width,
-      height: self.height,
-    }
+    self.table.clear();
+    self.tiles.clear();
+    self.unbounded.clear();
   }
 
   fn tile_to_aabb(&self, tile: Tile) -> AABB {
     AABB {
       x: tile.0 as f64 * TILE_SIZE,
       y: tile.1 as f64 * TILE_SIZE,
-      width: (tile.0 + 1) as f64 * TILE_SIZE,
-      height: (tile.1 + 1) as f64 * TILE_SIZE,
+      width: TILE_SIZE,
+      height: TILE_SIZE,
     }
   }
 
   fn insert(&mut self, ent: T, tile: Tile) {
-    if let Some(tile_id) = self.tile_to_id(tile) {
-      self.table[tile_id].insert(ent);
+    if self.table.entry(tile).or_default().insert(ent.clone()) {
+      self.tiles.entry(ent).or_default().push(tile);
+    }
+  }
+
+  /// The first and last tiles of the area, None when it spans too many tiles to go through them
+  fn tile_range(&self, aabb: AABB) -> Option<(Tile, Tile)> {
+    let (min, max) = (self.get_tile(aabb.min()), self.get_tile(aabb.max()));
+    let tile_count = tile_count(min, max);
+    if tile_count <= MAX_SHAPE_TILES {
+      Some((min, max))
+    } else {
+      None
     }
   }
 
@@ -86,157 +69,336 @@ impl<T: Clone + Eq + Hash> SpatialHashTable<T> {
     self.insert(ent, self.get_tile(p))
   }
 
+  /// Segments go through the tiles from one end to the other, crossing a tile border at a time.
+  /// The lines and rays never end, so they go with the unbounded entities
   pub fn insert_line(&mut self, ent: T, l: Line) {
-    let aabb = self.aabb();
-    if let Some((p1, p2)) = l.intersect(aabb) {
-      // Make sure p1 is on the left and is not right at position 0
-      let (p1, p2) = if p1.x > p2.x { (p2, p1) } else { (p1, p2) };
-      let dir = (p2 - p1).normalized();
-      let p1 = p1 + dir * 0.000001;
-
-      // Get the tiles
-      let (init_x_tile, init_y_tile) = self.get_tile(p1);
-      let (end_x_tile, end_y_tile) = self.get_tile(p2);
-
-      // Check if close to a straight line going up and down
-      if init_x_tile == end_x_tile && init_x_tile >= 0 && init_x_tile < self.x_tiles as i64 {
-        let (init_y_tile, end_y_tile) = if init_y_tile <= end_y_tile {
-          (init_y_tile, end_y_tile)
-        } else {
-          (end_y_tile, init_y_tile)
-        };
-        for j in init_y_tile..(end_y_tile + 1) {
-          self.insert(ent.clone(), (init_x_tile, j));
-        }
+    if l.line_type != LineType::Segment {
+      self.unbounded.insert(ent);
+      return;
+    }
+    let mut tile = self.get_tile(l.from);
+    let end = self.get_tile(l.to);
+    let steps =
+      (end.0.saturating_sub(tile.0).saturating_abs()).saturating_add(end.1.saturating_sub(tile.1).saturating_abs());
+    if steps > MAX_SHAPE_TILES {
+      self.unbounded.insert(ent);
+      return;
+    }
+
+    // How far along the segment the next vertical and horizontal borders are, and how far apart
+    // two borders are, 1 being the whole segment
+    let dir = l.to - l.from;
+    let crossing = |from: f64, dir: f64, tile: i64| {
+      if dir == 0.0 {
+        (f64::INFINITY, f64::INFINITY)
+      } else {
+        let border = if dir > 0.0 { tile + 1 } else { tile } as f64 * TILE_SIZE;
+        ((border - from) / dir, TILE_SIZE / dir.abs())
       }
-      if init_y_tile == end_y_tile && init_y_tile >= 0 && init_y_tile < self.y_tiles as i64 {
-        let (init_x_tile, end_x_tile) = if init_x_tile <= end_x_tile {
-          (init_x_tile, end_x_tile)
-        } else {
-          (end_x_tile, init_x_tile)
-        };
-        for i in init_x_tile..(end_x_tile + 1) {
-          self.insert(ent.clone(), (i, init_y_tile));
-        }
+    };
+    let (mut next_x, delta_x) = crossing(l.from.x, dir.x, tile.0);
+    let (mut next_y, delta_y) = crossing(l.from.y, dir.y, tile.1);
+    let step = (if dir.x > 0.0 { 1 } else { -1 }, if dir.y > 0.0 { 1 } else { -1 });
+
+    self.insert(ent.clone(), tile);
+    for _ in 0..steps {
+      if next_x < next_y {
+        tile.0 += step.0;
+        next_x += delta_x;
       } else {
-        // Setupt the state
-        let yi = if dir.y < 0.0 { -1.0 } else { 1.0 };
-        let mut curr_x = p1.x;
-        let mut curr_y = p1.y;
-        let mut curr_x_tile = init_x_tile as i64;
-        let mut curr_y_tile = init_y_tile as i64;
-
-        // Go through all the x tile in the same row that are covered by the line
-        while curr_x_tile <= end_x_tile as i64 && 0 <= curr_y_tile && curr_y_tile < self.y_tiles as i64 {
-          let next_y = (curr_y_tile + if dir.y > 0.0 { 1 } else { 0 }) as f64 * TILE_SIZE;
-          let tile_offset_y = (next_y - curr_y) * yi;
-          let next_x_diff = tile_offset_y / dir.y.abs() * dir.x;
-          let next_x = curr_x + next_x_diff;
-          let next_x_tile = (next_x / TILE_SIZE) as i64;
-          for i in curr_x_tile..(next_x_tile + 1) {
-            self.insert(ent.clone(), (i, curr_y_tile));
+        tile.1 += step.1;
+        next_y += delta_y;
+      }
+      self.insert(ent.clone(), tile);
+    }
+    // The rounding may have gone around the last tile
+    self.insert(ent, end);
+  }
+
+  pub fn insert_circle(&mut self, ent: T, c: Circle) {
+    let aabb = AABB::two_points(c.center - vec2![c.radius], c.center + vec2![c.radius]);
+    self.insert_round(ent, aabb, c.center, c.radius);
+  }
+
+  /// Same as `insert_circle` limited to the bounding box of the arc, so a few tiles crossed only
+  /// by the rest of the circle near the ends can be included
+  pub fn insert_arc(&mut self, ent: T, a: Arc) {
+    self.insert_round(ent, a.aabb(), a.center, a.radius);
+  }
+
+  /// Keeps the tiles of the area having the circle go through them
+  fn insert_round(&mut self, ent: T, aabb: AABB, center: Vector2, radius: f64) {
+    match self.tile_range(aabb) {
+      Some(((left, top), (right, bottom))) => {
+        for j in top..(bottom + 1) {
+          for i in left..(right + 1) {
+            let tile_aabb = self.tile_to_aabb((i, j));
+            let closest_dist = (tile_aabb.get_closest_point_to(center) - center).magnitude();
+            let furthest_dist = (tile_aabb.get_furthest_point_to(center) - center).magnitude();
+            if closest_dist <= radius && radius <= furthest_dist {
+              self.insert(ent.clone(), (i, j));
+            }
           }
-          curr_x = next_x;
-          curr_y = next_y;
-          curr_x_tile = next_x_tile;
-          curr_y_tile = curr_y_tile + yi as i64;
         }
       }
+      None => {
+        self.unbounded.insert(ent);
+      }
     }
   }
 
-  pub fn insert_circle(&mut self, ent: T, c: Circle) {
-    let (left, top) = self.get_tile(vec2![c.center.x - c.radius, c.center.y - c.radius]);
-    let (right, bottom) = self.get_tile(vec2![c.center.x + c.radius, c.center.y + c.radius]);
-    for j in top.max(0)..(bottom.min(self.y_tiles as i64) + 1) {
-      for i in left.max(0)..(right.min(self.x_tiles as i64) + 1) {
-        let tile_aabb = self.tile_to_aabb((i, j));
-        let closest_dist = (tile_aabb.get_closest_point_to(c.center) - c.center).magnitude();
-        let furthest_dist = (tile_aabb.get_furthest_point_to(c.center) - c.center).magnitude();
-        if closest_dist <= c.radius && c.radius <= furthest_dist {
-          self.insert(ent.clone(), (i, j));
+  /// Goes through the tiles of the bounding box of the ellipse and keeps the ones where the
+  /// ellipse gets closer to the tile center than the corners of the tile
+  pub fn insert_conic(&mut self, ent: T, e: Ellipse) {
+    let half_diagonal = TILE_SIZE / 2.0 * 2f64.sqrt();
+    match self.tile_range(e.aabb()) {
+      Some(((left, top), (right, bottom))) => {
+        for j in top..(bottom + 1) {
+          for i in left..(right + 1) {
+            let tile_center = vec2![(i as f64 + 0.5) * TILE_SIZE, (j as f64 + 0.5) * TILE_SIZE];
+            if (e.get_closest_point(tile_center) - tile_center).magnitude() <= half_diagonal {
+              self.insert(ent.clone(), (i, j));
+            }
+          }
         }
       }
+      None => {
+        self.unbounded.insert(ent);
+      }
     }
   }
 
-  pub fn remove_from_all(&mut self, ent: T) {
-    for set in &mut self.table {
-      set.remove(&ent);
+  /// Removes the entity from the tiles it was inserted in, the other tiles are not looked at.
+  /// Tiles left empty are dropped
+  pub fn remove(&mut self, ent: &T) {
+    if let Some(tiles) = self.tiles.remove(ent) {
+      for tile in tiles {
+        if let Some(set) = self.table.get_mut(&tile) {
+          set.remove(ent);
+          if set.is_empty() {
+            self.table.remove(&tile);
+          }
+        }
+      }
     }
+    self.unbounded.remove(ent);
   }
 
-  fn get_tile(&self, Vector2 { x, y }: Vector2) -> Tile {
-    ((x / TILE_SIZE) as i64, (y / TILE_SIZE) as i64)
+  /// Moves the point from the tiles it was in to the one of its new position
+  pub fn update_point(&mut self, ent: T, p: Vector2) {
+    self.remove(&ent);
+    self.insert_point(ent, p)
   }
 
-  fn get_entities_in_tile(&self, tile: Tile) -> Option<&HashSet<T>> {
-    if let Some(tile_id) = self.tile_to_id(tile) {
-      Some(&self.table[tile_id])
-    } else {
-      None
-    }
+  pub fn update_line(&mut self, ent: T, l: Line) {
+    self.remove(&ent);
+    self.insert_line(ent, l)
+  }
+
+  pub fn update_circle(&mut self, ent: T, c: Circle) {
+    self.remove(&ent);
+    self.insert_circle(ent, c)
+  }
+
+  pub fn update_arc(&mut self, ent: T, a: Arc) {
+    self.remove(&ent);
+    self.insert_arc(ent, a)
+  }
+
+  pub fn update_conic(&mut self, ent: T, e: Ellipse) {
+    self.remove(&ent);
+    self.insert_conic(ent, e)
+  }
+
+  fn get_tile(&self, Vector2 { x, y }: Vector2) -> Tile {
+    ((x / TILE_SIZE).floor() as i64, (y / TILE_SIZE).floor() as i64)
   }
 
+  /// The entities with some geometry in the tiles overlapped by `aabb`, which is in virtual
+  /// space, along with the unbounded ones
   pub fn get_entities_near_aabb(&self, aabb: AABB) -> HashSet<T> {
-    let mut entities = HashSet::new();
-    if let Some(itsct) = self.aabb().intersect(aabb) {
-      let (i_min, j_min) = self.get_tile(itsct.min());
-      let (i_max, j_max) = self.get_tile(itsct.max());
-      for j in (j_min - 1)..(j_max + 2) {
-        for i in (i_min - 1)..(i_max + 2) {
-          if let Some(tile_ents) = self.get_entities_in_tile((i, j)) {
-            for ent in tile_ents {
-              entities.insert(ent.clone());
-            }
+    let mut entities = self.unbounded.clone();
+    let (min, max) = (self.get_tile(aabb.min()), self.get_tile(aabb.max()));
+    let tile_count = tile_count(min, max);
+    if tile_count as usize <= self.table.len() {
+      for j in min.1..(max.1 + 1) {
+        for i in min.0..(max.0 + 1) {
+          if let Some(tile_ents) = self.table.get(&(i, j)) {
+            entities.extend(tile_ents.iter().cloned());
           }
         }
       }
+    } else {
+      // A large area has more tiles than the ones holding something
+      for ((i, j), tile_ents) in &self.table {
+        if min.0 <= *i && *i <= max.0 && min.1 <= *j && *j <= max.1 {
+          entities.extend(tile_ents.iter().cloned());
+        }
+      }
     }
-    return entities;
+    entities
   }
 
+  /// The entities in the tiles at most `dist` away from `p` in both directions, in virtual space
   pub fn get_entities_near_point(&self, p: Vector2, dist: f64) -> HashSet<T> {
-    let mut entities = HashSet::new();
-    let (center_i, center_j) = self.get_tile(p);
-    for j in (center_j - 1)..(center_j + 2) {
-      for i in (center_i - 1)..(center_i + 2) {
-        let tile_aabb = self.tile_to_aabb((i, j));
-        let d = (tile_aabb.get_closest_point_to(p) - p).magnitude();
-        if d <= dist {
-          if let Some(tile_ents) = self.get_entities_in_tile((i, j)) {
-            for ent in tile_ents {
-              entities.insert(ent.clone());
-            }
-          }
-        }
-      }
-    }
-    return entities;
+    self.get_entities_near_aabb(AABB::two_points(p - vec2![dist], p + vec2![dist]))
+  }
+
+  /// All the non-empty tiles, with their area in virtual space and the amount of entities inside
+  pub fn occupied_tiles<'a>(&'a self) -> impl Iterator<Item = (AABB, usize)> + 'a {
+    self
+      .table
+      .iter()
+      .map(move |(tile, set)| (self.tile_to_aabb(*tile), set.len()))
   }
 }
 
+/// The amount of tiles from `min` to `max`, both included. The far away tiles of the shapes
+/// reaching infinity do not overflow
+fn tile_count(min: Tile, max: Tile) -> i64 {
+  let span = |min: i64, max: i64| max.saturating_sub(min).saturating_add(1);
+  span(min.0, max.0).saturating_mul(span(min.1, max.1))
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
 
+  fn sorted(entities: HashSet<usize>) -> Vec<usize> {
+    let mut entities = entities.into_iter().collect::<Vec<_>>();
+    entities.sort();
+    entities
+  }
+
   #[test]
   fn test_sht_tile() {
-    let sht = SpatialHashTable::<bool>::new(80., 80.);
-
-    // Make sure there are only 4 (2x2) tiles in the sht
-    assert!(sht.table.len() == 4);
+    let sht = SpatialHashTable::<bool>::new();
 
     // Test the getting the correct tile
     assert!(sht.get_tile(vec2![0., 0.]) == (0, 0));
-    assert!(sht.get_tile(vec2![20., 20.]) == (0, 0));
-    assert!(sht.get_tile(vec2![50., 20.]) == (1, 0));
-    assert!(sht.get_tile(vec2![50., 50.]) == (1, 1));
+    assert!(sht.get_tile(vec2![0.5, 0.5]) == (0, 0));
+    assert!(sht.get_tile(vec2![1.5, 0.5]) == (1, 0));
+    assert!(sht.get_tile(vec2![1.5, 1.5]) == (1, 1));
+
+    // The tiles go on past the origin
+    assert!(sht.get_tile(vec2![-0.5, -1.5]) == (-1, -2));
+    assert!(sht.get_tile(vec2![1e6, -1e6]) == (1_000_000, -1_000_000));
+  }
+
+  #[test]
+  fn test_sht_occupied_tiles() {
+    let mut sht = SpatialHashTable::<usize>::new();
+    sht.insert_point(0, vec2![0.25, 0.25]);
+    sht.insert_point(1, vec2![0.5, 0.75]);
+    sht.insert_point(2, vec2![-1.25, 1.25]);
+
+    let mut tiles = sht
+      .occupied_tiles()
+      .map(|(aabb, count)| (aabb.x, aabb.y, aabb.width, count))
+      .collect::<Vec<_>>();
+    tiles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(tiles, vec![(-2., 1., 1., 1), (0., 0., 1., 2)]);
+  }
+
+  #[test]
+  fn test_sht_entities_near_aabb() {
+    let mut sht = SpatialHashTable::<usize>::new();
+    sht.insert_point(0, vec2![-5., 5.]);
+    sht.insert_point(1, vec2![1., 1.]);
+    sht.insert_point(2, vec2![6.5, -6.5]);
+    sht.insert_circle(
+      3,
+      Circle {
+        center: vec2![6.5, -6.5],
+        radius: 0.3,
+      },
+    );
+    sht.insert_point(4, vec2![1e6, 1e6]);
+
+    let in_aabb = |p1: Vector2, p2: Vector2| sorted(sht.get_entities_near_aabb(AABB::two_points(p1, p2)));
+    assert_eq!(in_aabb(vec2![-6., 0.], vec2![2., 6.]), vec![0, 1]);
+    assert_eq!(in_aabb(vec2![4., -8.], vec2![8., -4.]), vec![2, 3]);
+
+    // The areas larger than the occupied tiles give the same entities
+    assert_eq!(in_aabb(vec2![-100., -100.], vec2![100., 100.]), vec![0, 1, 2, 3]);
+    assert_eq!(in_aabb(vec2![-1e9, -1e9], vec2![1e9, 1e9]), vec![0, 1, 2, 3, 4]);
+    assert_eq!(in_aabb(vec2![20., 20.], vec2![30., 30.]), Vec::<usize>::new());
+  }
+
+  #[test]
+  fn test_sht_lines() {
+    let mut sht = SpatialHashTable::<usize>::new();
+    sht.insert_line(
+      0,
+      Line {
+        from: vec2![0.5, 0.5],
+        to: vec2![3.5, 2.5],
+        line_type: LineType::Segment,
+      },
+    );
+    let mut tiles = sht.tiles[&0].clone();
+    tiles.sort();
+    assert_eq!(tiles, vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (3, 2)]);
+
+    // The straight lines are near everything, the segments only near their tiles
+    sht.insert_line(
+      1,
+      Line {
+        from: vec2![0., 0.],
+        to: vec2![1., 1.],
+        line_type: LineType::Straight,
+      },
+    );
+    assert_eq!(sorted(sht.get_entities_near_point(vec2![1e5, 0.], 0.1)), vec![1]);
+    assert_eq!(sorted(sht.get_entities_near_point(vec2![2.5, 1.5], 0.1)), vec![0, 1]);
+    sht.remove(&1);
+    assert!(sht.get_entities_near_point(vec2![1e5, 0.], 0.1).is_empty());
+  }
+
+  #[test]
+  fn test_sht_update_leaves_the_old_tiles() {
+    let mut sht = SpatialHashTable::<usize>::new();
+    for i in 0..100 {
+      sht.insert_point(i, vec2![(i % 10) as f64 + 0.1, (i / 10) as f64 + 0.1]);
+    }
+    let line = Line {
+      from: vec2![0., 0.25],
+      to: vec2![9.99, 0.25],
+      line_type: LineType::Segment,
+    };
+    sht.insert_line(100, line);
+    let in_tile = |sht: &SpatialHashTable<usize>, p: Vector2| {
+      let mut entities = sht.table[&sht.get_tile(p)].iter().cloned().collect::<Vec<_>>();
+      entities.sort();
+      entities
+    };
+    assert_eq!(in_tile(&sht, vec2![0.1, 0.1]), vec![0, 100]);
+
+    // The point goes from the first tile to the last one, the others keep their entities
+    sht.update_point(0, vec2![9.9, 9.9]);
+    assert_eq!(in_tile(&sht, vec2![0.1, 0.1]), vec![100]);
+    assert_eq!(in_tile(&sht, vec2![9.9, 9.9]), vec![0, 99]);
+    assert_eq!(sht.occupied_tiles().count(), 100);
 
-    // Test edge cases of tiling
-    assert!(sht.get_tile(vec2![80., 80.]) == (2, 2));
+    // The segment is taken out of the whole first row before going up to the last one
+    sht.update_line(
+      100,
+      Line {
+        from: vec2![0., 9.75],
+        to: vec2![9.99, 9.75],
+        line_type: LineType::Segment,
+      },
+    );
+    assert_eq!(in_tile(&sht, vec2![5., 0.1]), vec![5]);
+    assert_eq!(in_tile(&sht, vec2![5.1, 9.9]), vec![95, 100]);
+    sht.remove(&100);
+    assert_eq!(sht.occupied_tiles().map(|(_, count)| count).sum::<usize>(), 100);
+    assert!(!sht.tiles.contains_key(&100));
 
-    // Test getting tile id
-    assert!(sht.tile_to_id(sht.get_tile(vec2![80., 80.])).is_none());
+    // Emptied tiles are dropped, the first one lost its point then the segment
+    sht.remove(&5);
+    assert!(!sht.table.contains_key(&(0, 0)));
+    assert!(!sht.table.contains_key(&(5, 0)));
+    assert_eq!(sht.occupied_tiles().count(), 98);
   }
 }
@@ -1,7 +1,7 @@
 use crate::math::*;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VirtualScalar(pub f64);
 
 impl Into<f64> for VirtualScalar {
@@ -16,7 +16,7 @@ impl From<f64> for VirtualScalar {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VirtualPosition(pub Vector2);
 
 impl VirtualPosition {
@@ -140,6 +140,95 @@ impl From<Circle> for VirtualCircle {
   }
 }
 
+/// Part of a circle going counter clockwise from the `start` angle over `sweep` radians
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualArc {
+  pub center: VirtualPosition,
+  pub radius: VirtualScalar,
+  pub start: f64,
+  pub sweep: f64,
+}
+
+impl Into<Arc> for VirtualArc {
+  fn into(self) -> Arc {
+    Arc {
+      center: self.center.into(),
+      radius: self.radius.into(),
+      start: self.start,
+      sweep: self.sweep,
+    }
+  }
+}
+
+impl From<Arc> for VirtualArc {
+  fn from(a: Arc) -> Self {
+    Self {
+      center: a.center.into(),
+      radius: a.radius.into(),
+      start: a.start,
+      sweep: a.sweep,
+    }
+  }
+}
+
+/// Ellipse with radius `rx` along its own x axis and `ry` along its own y axis, turned counter
+/// clockwise by `rotation` radians
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualConic {
+  pub center: VirtualPosition,
+  pub rx: VirtualScalar,
+  pub ry: VirtualScalar,
+  pub rotation: f64,
+}
+
+impl Into<Ellipse> for VirtualConic {
+  fn into(self) -> Ellipse {
+    Ellipse {
+      center: self.center.into(),
+      rx: self.rx.into(),
+      ry: self.ry.into(),
+      rotation: self.rotation,
+    }
+  }
+}
+
+impl From<Ellipse> for VirtualConic {
+  fn from(e: Ellipse) -> Self {
+    Self {
+      center: e.center.into(),
+      rx: e.rx.into(),
+      ry: e.ry.into(),
+      rotation: e.rotation,
+    }
+  }
+}
+
+/// Closed polygon, the last vertex connects back to the first one
+#[derive(Debug, Clone)]
+pub struct VirtualPolygon {
+  pub vertices: Vec<VirtualPosition>,
+}
+
+impl Into<Polygon> for VirtualPolygon {
+  fn into(self) -> Polygon {
+    Polygon::new(self.vertices.into_iter().map(|v| v.0).collect())
+  }
+}
+
+/// Arrow from one position to another
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualVector {
+  pub from: VirtualPosition,
+  pub to: VirtualPosition,
+}
+
+/// Text drawn from a position, the bottom left corner of the first line
+#[derive(Debug, Clone)]
+pub struct VirtualText {
+  pub position: VirtualPosition,
+  pub text: String,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum VirtualCircleIntersect {
   TwoPoints(VirtualPosition, VirtualPosition),
@@ -186,3 +275,23 @@ impl Intersect<VirtualCircle> for VirtualLine {
     c.intersect(l).into()
   }
 }
+
+impl Intersect<VirtualLine> for VirtualArc {
+  type Output = VirtualCircleIntersect;
+
+  fn intersect(self, other: VirtualLine) -> Self::Output {
+    let a: Arc = self.into();
+    let l: Line = other.into();
+    a.intersect(l).into()
+  }
+}
+
+impl Intersect<VirtualCircle> for VirtualArc {
+  type Output = VirtualCircleIntersect;
+
+  fn intersect(self, other: VirtualCircle) -> Self::Output {
+    let a: Arc = self.into();
+    let c: Circle = other.into();
+    a.intersect(c).into()
+  }
+}
@@ -1,9 +1,11 @@
 mod active_point_event;
 mod exit_event;
 mod mouse_event;
+mod status_event;
 mod tool_change_event;
 
 pub use active_point_event::*;
 pub use exit_event::*;
 pub use mouse_event::*;
+pub use status_event::*;
 pub use tool_change_event::*;
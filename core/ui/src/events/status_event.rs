@@ -0,0 +1,11 @@
+use shrev::*;
+
+/// Short messages for the status bar of the frontends
+#[derive(Debug, Clone)]
+pub enum StatusEvent {
+  CursorPosition(String), // The formatted virtual coordinates under the cursor
+}
+
+pub type StatusEventReader = ReaderId<StatusEvent>;
+
+pub type StatusEventChannel = EventChannel<StatusEvent>;
@@ -38,15 +38,30 @@ pub fn setup_core_ui<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
     "move_viewport_via_scroll",
     &[],
   );
+  builder.add(
+    interactions::viewport::ZoomViewportViaScroll::default(),
+    "zoom_viewport_via_scroll",
+    &[],
+  );
   builder.add(
     interactions::history::UndoRedoViaKeyboard::default(),
     "undo_redo_via_keyboard",
     &[],
   );
+  builder.add(
+    interactions::file::SaveLoadViaKeyboard::default(),
+    "save_load_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::point::ChangeSnapSettingsViaKeyboard::default(),
+    "change_snap_settings_via_keyboard",
+    &[],
+  );
   builder.add(
     interactions::geometry::point::SnapPointViaMouse::default(),
     "snap_point_via_mouse",
-    &[],
+    &["change_snap_settings_via_keyboard"],
   );
   builder.add(interactions::marker::SeldeViaMouse::default(), "selde_via_mouse", &[]);
   builder.add(
@@ -59,6 +74,46 @@ pub fn setup_core_ui<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
     "hide_via_keyboard",
     &[],
   );
+  builder.add(
+    interactions::marker::RestyleViaKeyboard::default(),
+    "restyle_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::clipboard::CopyPasteViaKeyboard::default(),
+    "copy_paste_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::animation::AnimateViaKeyboard::default(),
+    "animate_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::marker::TraceViaKeyboard::default(),
+    "trace_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::theme::ToggleThemeViaKeyboard::default(),
+    "toggle_theme_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::viewport::ToggleGridViaKeyboard::default(),
+    "toggle_grid_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::viewport::ToggleMeasureOverlaysViaKeyboard::default(),
+    "toggle_measure_overlays_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::debug::ToggleSpatialHashOverlayViaKeyboard::default(),
+    "toggle_spatial_hash_overlay_via_keyboard",
+    &[],
+  );
 
   // Geometry interactions (not depend on snap point)
   builder.add(
@@ -66,11 +121,51 @@ pub fn setup_core_ui<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
     "move_point_via_drag",
     &[],
   );
+  builder.add(
+    interactions::geometry::line::MoveLineViaDrag::default(),
+    "move_line_via_drag",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::slider::DragSliderViaMouse::default(),
+    "drag_slider_via_mouse",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::underlay::DragUnderlayViaMouse::default(),
+    "drag_underlay_via_mouse",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::underlay::ChangeUnderlayOpacityViaKeyboard::default(),
+    "change_underlay_opacity_via_keyboard",
+    &[],
+  );
   builder.add(
     interactions::geometry::point::CreateMidpointViaKeyboard::default(),
     "create_midpoint_via_keyboard",
     &[],
   );
+  builder.add(
+    interactions::geometry::point::CreateIntersectionsViaKeyboard::default(),
+    "create_intersections_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::point::RedefinePointViaKeyboard::default(),
+    "redefine_point_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::circle::CreateCircumcircleViaKeyboard::default(),
+    "create_circumcircle_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::circle::CreateCompassViaKeyboard::default(),
+    "create_compass_via_keyboard",
+    &[],
+  );
   builder.add(
     interactions::geometry::line::CreateParallelViaKeyboard::default(),
     "create_parallel_via_keyboard",
@@ -81,11 +176,46 @@ pub fn setup_core_ui<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
     "create_perpendicular_via_keyboard",
     &[],
   );
+  builder.add(
+    interactions::geometry::line::CreatePerpendicularBisectorViaKeyboard::default(),
+    "create_perpendicular_bisector_via_keyboard",
+    &[],
+  );
   builder.add(
     interactions::geometry::RemoveSelectedViaKeyboard::default(),
     "remove_selected_via_keyboard",
     &[],
   );
+  builder.add(
+    interactions::geometry::RotateSelectionViaDrag::default(),
+    "rotate_selection_via_drag",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::ScaleSelectionViaDrag::default(),
+    "scale_selection_via_drag",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::macros::DefineMacroViaKeyboard::default(),
+    "define_macro_via_keyboard",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::macros::ApplyMacroViaClick::default(),
+    "apply_macro_via_click",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::ReflectSelectionViaClick::default(),
+    "reflect_selection_via_click",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::point::CreateRatioPointViaMouse::default(),
+    "create_ratio_point_via_mouse",
+    &[],
+  );
 
   // Geometry creation (will depend on snap point)
   builder.add(
@@ -113,6 +243,26 @@ pub fn setup_core_ui<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
     "create_circle_via_mouse",
     &["emit_active_point_event", "click_on_existing_point"],
   );
+  builder.add(
+    interactions::geometry::polygon::CreatePolygonViaMouse::default(),
+    "create_polygon_via_mouse",
+    &["emit_active_point_event", "click_on_existing_point"],
+  );
+  builder.add(
+    interactions::geometry::text::CreateTextViaMouse::default(),
+    "create_text_via_mouse",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::slider::CreateSliderViaMouse::default(),
+    "create_slider_via_mouse",
+    &[],
+  );
+  builder.add(
+    interactions::geometry::freehand::CreateFreehandViaMouse::default(),
+    "create_freehand_via_mouse",
+    &[],
+  );
 
   // State managers
   builder.add(
@@ -126,18 +276,49 @@ pub fn setup_core_ui<'a, 'b>(builder: &mut DispatcherBuilder<'a, 'b>) {
     &["change_tool_via_keyboard", "change_line_tool_via_keyboard"],
   );
 
+  // Animations move points before the core library solves them
+  builder.add(animations::AnimationSystem::default(), "animation_system", &[]);
+
   // Setup the core library
   setup_core_lib(builder);
 
+  // The viewport is up to date once the core library is done
+  builder.add(
+    state_managers::CursorReadoutManager::default(),
+    "cursor_readout_manager",
+    &[],
+  );
+  builder.add(state_managers::HoverSystem::default(), "hover_system", &[]);
+
   // Renderers
+  builder.add(renderers::GridRenderSystem::default(), "grid_render_system", &[]);
   builder.add(renderers::SnapPointRenderer::default(), "snap_point_renderer", &[]);
   builder.add(renderers::SnapLineRenderer::default(), "snap_line_renderer", &[]);
+  builder.add(renderers::RatioPointRenderer::default(), "ratio_point_renderer", &[]);
   builder.add(renderers::SnapCircleRenderer::default(), "snap_circle_renderer", &[]);
+  builder.add(renderers::SnapPolygonRenderer::default(), "snap_polygon_renderer", &[]);
+  builder.add(
+    renderers::FreehandDraftRenderer::default(),
+    "freehand_draft_renderer",
+    &[],
+  );
+  builder.add(renderers::TraceRenderer::default(), "trace_renderer", &[]);
   builder.add(
     renderers::SelectRectangleRenderer::default(),
     "select_rectangle_renderer",
     &[],
   );
+  builder.add(renderers::SelectLassoRenderer::default(), "select_lasso_renderer", &[]);
+  builder.add(renderers::SliderRenderer::default(), "slider_renderer", &[]);
+  builder.add(renderers::UnderlayRenderer::default(), "underlay_renderer", &[]);
+  builder.add(renderers::TypingTextRenderer::default(), "typing_text_renderer", &[]);
+  builder.add(renderers::RulerRenderer::default(), "ruler_renderer", &[]);
+  builder.add(renderers::ProtractorRenderer::default(), "protractor_renderer", &[]);
+  builder.add(
+    renderers::SpatialHashOverlayRenderer::default(),
+    "spatial_hash_overlay_renderer",
+    &[],
+  );
 
   // Final barrier
   builder.add_barrier();
@@ -0,0 +1,30 @@
+use core_lib::{resources::CoordinatesFormat, utilities::*};
+
+/// Virtual coordinates under the cursor, the format sets how many decimals are shown
+pub struct CursorReadout {
+  pub format: CoordinatesFormat,
+  position: Option<VirtualPosition>,
+}
+
+impl Default for CursorReadout {
+  fn default() -> Self {
+    Self {
+      format: CoordinatesFormat::default(),
+      position: None,
+    }
+  }
+}
+
+impl CursorReadout {
+  pub fn set(&mut self, position: VirtualPosition) {
+    self.position = Some(position);
+  }
+
+  pub fn get(&self) -> Option<VirtualPosition> {
+    self.position
+  }
+
+  pub fn text(&self) -> Option<String> {
+    self.position.map(|position| self.format.format(position))
+  }
+}
@@ -1,5 +1,7 @@
-use core_lib::{components::styles::*, math::*};
+use core_lib::{components::styles::*, math::*, resources::Theme};
 
+/// Style of the select rectangle and lasso. Only the alphas of its colors are kept, the colors
+/// come from the overlay color of the theme
 #[derive(Debug, Copy, Clone)]
 pub struct DefaultSelectRectangleStyle(RectangleStyle);
 
@@ -10,13 +12,20 @@ impl Default for DefaultSelectRectangleStyle {
       border: LineStyle {
         color: rgba!(0.0, 0.0, 0.0, 0.2),
         width: 1.0,
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: 1.0,
       },
+      alpha: 1.0,
     })
   }
 }
 
 impl DefaultSelectRectangleStyle {
-  pub fn get(&self) -> RectangleStyle {
-    self.0
+  pub fn get(&self, theme: &Theme) -> RectangleStyle {
+    let mut style = self.0;
+    style.fill = theme.overlay.apply_alpha(style.fill.a);
+    style.border.color = theme.overlay.apply_alpha(style.border.color.a);
+    style
   }
 }
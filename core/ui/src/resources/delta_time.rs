@@ -1,17 +1,30 @@
-pub struct DeltaTime(f64);
+/// Seconds elapsed for the current frame. A fixed delta time is not overridden by the window
+pub struct DeltaTime {
+  dt: f64,
+  fixed: bool,
+}
 
 impl Default for DeltaTime {
   fn default() -> Self {
-    Self(0.016)
+    Self {
+      dt: 0.016,
+      fixed: false,
+    }
   }
 }
 
 impl DeltaTime {
+  pub fn fixed(dt: f64) -> Self {
+    Self { dt, fixed: true }
+  }
+
   pub fn set(&mut self, dt: f64) {
-    self.0 = dt;
+    if !self.fixed {
+      self.dt = dt;
+    }
   }
 
   pub fn get(&self) -> f64 {
-    self.0
+    self.dt
   }
 }
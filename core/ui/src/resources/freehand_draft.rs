@@ -0,0 +1,12 @@
+use core_lib::components::freehand::*;
+
+/// The stroke being drawn with the freehand tool, until the pen is lifted
+pub struct FreehandDraft {
+  pub stroke: Option<FreehandStroke>,
+}
+
+impl Default for FreehandDraft {
+  fn default() -> Self {
+    Self { stroke: None }
+  }
+}
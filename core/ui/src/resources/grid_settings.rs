@@ -0,0 +1,59 @@
+use core_lib::{math::*, resources::*, utilities::*};
+
+static MIN_GRID_SPACING: f64 = 8.0; // Pixel
+
+pub struct GridSettings {
+  pub spacing: f64, // In virtual units
+  pub visible: bool,
+  pub axes: bool,
+}
+
+impl Default for GridSettings {
+  fn default() -> Self {
+    Self {
+      spacing: 1.0,
+      visible: false,
+      axes: false,
+    }
+  }
+}
+
+impl GridSettings {
+  /// The spacing of the grid lines in the viewport. When zoomed out the spacing is doubled until
+  /// the lines are far enough from each other on screen
+  pub fn spacing_in(&self, viewport: &Viewport) -> f64 {
+    let mut spacing = self.spacing;
+    while spacing / viewport.virtual_to_screen_scale() < MIN_GRID_SPACING {
+      spacing *= 2.0;
+    }
+    spacing
+  }
+
+  /// The grid intersection closest to the given position
+  pub fn closest_intersection(&self, VirtualPosition(p): VirtualPosition, viewport: &Viewport) -> VirtualPosition {
+    let spacing = self.spacing_in(viewport);
+    VirtualPosition(vec2![
+      (p.x / spacing).round() * spacing,
+      (p.y / spacing).round() * spacing
+    ])
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_grid_spacing_grows_when_zoomed_out() {
+    let grid_settings = GridSettings::default();
+    let mut viewport = Viewport::default();
+    assert_eq!(grid_settings.spacing_in(&viewport), 1.0);
+
+    // 200 units across 960 pixels puts the unit lines less than 5 pixels apart
+    viewport.set_virtual_size_x(200.0);
+    assert_eq!(grid_settings.spacing_in(&viewport), 2.0);
+
+    let VirtualPosition(p) = grid_settings.closest_intersection(vec2![2.9, -1.2].into(), &viewport);
+    assert_eq!((p.x, p.y), (2.0, -2.0));
+  }
+}
@@ -1,6 +1,10 @@
+use crate::events::*;
+pub use core_lib::utilities::SystemTime;
 use core_lib::{math::*, utilities::*};
-use std::collections::HashMap;
-use std::time::SystemTime;
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+
+static MOUSE_HISTORY_CAPACITY: usize = 16; // Frames
 
 pub struct InputState {
   pub mouse_left_button: ActiveState,
@@ -11,9 +15,17 @@ pub struct InputState {
 
   pub mouse_abs_pos: ScreenPosition,
   pub mouse_rel_movement: ScreenPosition,
+  pub mouse_history: VecDeque<(SystemTime, ScreenPosition)>, // Oldest first
   pub rel_scroll: Vector2,
+  pub rel_pinch: f64, // Relative magnification of a pinch on the trackpad or of two fingers
+  pub touches: HashMap<i64, ScreenPosition>, // The fingers on the screen, by id
+  pub rel_pan: ScreenPosition, // Movement of the middle of two fingers
+  pub rel_rotation: f64, // Radians two fingers turned by, clockwise on screen as its y axis goes down
+  pub pen_pressure: Option<f64>, // From 0 to 1 while a pen or stylus touches, none for the mouse
   pub in_focus: ActiveState,
   pub keyboard: Keyboard,
+  pub text_mode: bool,    // Typing a text, the keys are characters instead of shortcuts
+  pub typed_text: String, // Characters typed this frame in text mode
 }
 
 impl Default for InputState {
@@ -25,9 +37,17 @@ impl Default for InputState {
       mouse_left_button_last_pressed: None,
       mouse_abs_pos: vec2![0., 0.].into(),
       mouse_rel_movement: vec2![0., 0.].into(),
+      mouse_history: VecDeque::with_capacity(MOUSE_HISTORY_CAPACITY),
       in_focus: ActiveState::default(),
       rel_scroll: vec2![0., 0.],
+      rel_pinch: 0.0,
+      touches: HashMap::new(),
+      rel_pan: vec2![0., 0.].into(),
+      rel_rotation: 0.0,
+      pen_pressure: None,
       keyboard: Keyboard::default(),
+      text_mode: false,
+      typed_text: String::new(),
     }
   }
 }
@@ -39,8 +59,143 @@ impl InputState {
     self.mouse_rel_movement = vec2![0., 0.].into();
     self.in_focus.reset_relative_data();
     self.rel_scroll = vec2![0., 0.];
+    self.rel_pinch = 0.0;
+    self.rel_pan = vec2![0., 0.].into();
+    self.rel_rotation = 0.0;
     self.keyboard.reset_relative_data();
+    self.typed_text.clear();
+  }
+
+  /// Records a key being pressed or released. In text mode only the keys editing the text are
+  /// pressed, the other ones are typed as characters and would trigger shortcuts
+  pub fn set_key(&mut self, key: Key, pressed: bool) {
+    if !pressed || !self.text_mode || key.edits_text() {
+      self.keyboard.set(key, pressed);
+    }
+  }
+
+  /// Records a finger touching the screen, moving or being lifted. Two fingers moving pan, pinch
+  /// and rotate, the fingers beyond two are only tracked
+  pub fn touch(&mut self, id: i64, phase: TouchPhase, position: ScreenPosition) {
+    match phase {
+      TouchPhase::Start => {
+        self.touches.insert(id, position);
+      }
+      TouchPhase::Move => {
+        if let Some(previous) = self.touches.insert(id, position) {
+          if self.touches.len() == 2 {
+            let other = self
+              .touches
+              .iter()
+              .find(|(other_id, _)| **other_id != id)
+              .map(|(_, other)| *other)
+              .unwrap();
+            self.two_finger_gesture(other, previous, position);
+          }
+        }
+      }
+      TouchPhase::End => {
+        self.touches.remove(&id);
+      }
+    }
+  }
+
+  /// How hard the pen presses, from 0 to 1. The mouse always presses fully
+  pub fn pressure(&self) -> f64 {
+    self.pen_pressure.unwrap_or(1.0)
+  }
+
+  /// The middle of the two fingers making a gesture, if there are two on the screen
+  pub fn gesture_center(&self) -> Option<ScreenPosition> {
+    if self.touches.len() == 2 {
+      let mut positions = self.touches.values();
+      let (ScreenPosition(a), ScreenPosition(b)) = (*positions.next()?, *positions.next()?);
+      Some(ScreenPosition((a + b) / 2.))
+    } else {
+      None
+    }
+  }
+
+  /// Adds up the gesture of one finger staying at `fixed` while the other moves from `from` to `to`
+  fn two_finger_gesture(&mut self, fixed: ScreenPosition, from: ScreenPosition, to: ScreenPosition) {
+    let (ScreenPosition(fixed), ScreenPosition(from), ScreenPosition(to)) = (fixed, from, to);
+    self.rel_pan = self.rel_pan + ScreenPosition((to - from) / 2.);
+    let (before, after) = (from - fixed, to - fixed);
+    if before.is_zero() || after.is_zero() {
+      return;
+    }
+    self.rel_pinch = (1.0 + self.rel_pinch) * after.magnitude() / before.magnitude() - 1.0;
+    let turn = after.y.atan2(after.x) - before.y.atan2(before.x);
+    self.rel_rotation += (turn + PI).rem_euclid(2. * PI) - PI;
+  }
+
+  /// Records characters typed by the user, which only matter in text mode
+  pub fn type_text(&mut self, text: &str) {
+    if self.text_mode {
+      self.typed_text.push_str(text);
+    }
+  }
+
+  /// Entering or leaving text mode releases the keys held, so that no shortcut fires for a key
+  /// pressed in the other mode
+  pub fn set_text_mode(&mut self, text_mode: bool) {
+    if self.text_mode != text_mode {
+      self.text_mode = text_mode;
+      self.typed_text.clear();
+      self.keyboard.release_all();
+    }
+  }
+
+  /// Records the current mouse position, called once per frame. Only the last few frames are kept
+  pub fn record_mouse_position(&mut self, time: SystemTime) {
+    if self.mouse_history.len() == MOUSE_HISTORY_CAPACITY {
+      self.mouse_history.pop_front();
+    }
+    self.mouse_history.push_back((time, self.mouse_abs_pos));
   }
+
+  /// Average velocity of the mouse over the recorded history, in pixels per second
+  pub fn recent_velocity(&self) -> ScreenPosition {
+    if let (Some((t0, p0)), Some((t1, p1))) = (self.mouse_history.front(), self.mouse_history.back()) {
+      if let Ok(duration) = t1.duration_since(*t0) {
+        let secs = duration.as_secs_f64();
+        if secs > 0.0 {
+          return (*p1 - *p0) / secs.into();
+        }
+      }
+    }
+    vec2![0., 0.].into()
+  }
+
+  /// The history would be stale when the focus comes back, so it is dropped when losing focus.
+  /// The buttons and keys held when losing focus won't see their release either, so they are all
+  /// released and the ongoing drag is ended
+  pub fn set_focus(&mut self, focus: bool, mouse_event_channel: &mut MouseEventChannel) {
+    self.in_focus.set(focus);
+    if !focus {
+      self.mouse_history.clear();
+      self.touches.clear();
+      self.pen_pressure = None;
+      if self.mouse_left_button.is_activated() {
+        mouse_event_channel.single_write(MouseEvent::MouseUp(self.mouse_abs_pos));
+      }
+      if self.is_mouse_left_button_dragging {
+        self.is_mouse_left_button_dragging = false;
+        mouse_event_channel.single_write(MouseEvent::DragEnd(self.mouse_abs_pos));
+      }
+      self.mouse_left_button.set(false);
+      self.mouse_left_button_last_pressed = None;
+      self.mouse_right_button.set(false);
+      self.keyboard.release_all();
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TouchPhase {
+  Start,
+  Move,
+  End, // Lifted, or cancelled by the system
 }
 
 pub struct ActiveState {
@@ -133,6 +288,10 @@ impl Keyboard {
     self.is_activated(Key::LShift) || self.is_activated(Key::RShift)
   }
 
+  pub fn is_alt_activated(&self) -> bool {
+    self.is_activated(Key::LAlt) || self.is_activated(Key::RAlt)
+  }
+
   pub fn is_command_activated(&self) -> bool {
     if cfg!(target_os = "macos") {
       self.is_activated(Key::LCommand) || self.is_activated(Key::RCommand)
@@ -141,6 +300,13 @@ impl Keyboard {
     }
   }
 
+  /// Releases every pressed key, they are all just deactivated
+  pub fn release_all(&mut self) {
+    for (_, state) in self.keys.iter_mut() {
+      state.set(false);
+    }
+  }
+
   pub fn reset_relative_data(&mut self) {
     for (_, state) in self.keys.iter_mut() {
       state.reset_relative_data();
@@ -393,3 +559,135 @@ pub enum Key {
   Eject = 0x40000119,
   Sleep = 0x4000011A,
 }
+
+impl Key {
+  /// Keys editing the text being typed rather than typing a character
+  pub fn edits_text(self) -> bool {
+    match self {
+      Key::Backspace | Key::Return | Key::Return2 | Key::Escape => true,
+      _ => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::time::Duration;
+
+  #[test]
+  fn test_recent_velocity_of_steady_drag() {
+    let mut input_state = InputState::default();
+    assert_eq!(input_state.recent_velocity(), vec2![0., 0.].into());
+
+    let start = SystemTime::now();
+    for i in 0..40 {
+      input_state.mouse_abs_pos = vec2![100. + 5. * i as f64, 300. - 2. * i as f64].into();
+      input_state.record_mouse_position(start + Duration::from_millis(10 * i));
+    }
+    assert_eq!(input_state.mouse_history.len(), MOUSE_HISTORY_CAPACITY);
+    let ScreenPosition(velocity) = input_state.recent_velocity();
+    assert!((velocity.x - 500.).abs() < 1e-6);
+    assert!((velocity.y + 200.).abs() < 1e-6);
+
+    input_state.set_focus(false, &mut MouseEventChannel::new());
+    assert!(input_state.mouse_history.is_empty());
+    assert_eq!(input_state.recent_velocity(), vec2![0., 0.].into());
+  }
+
+  #[test]
+  fn test_focus_loss_ends_drag_and_releases_keys() {
+    let mut input_state = InputState::default();
+    let mut mouse_event_channel = MouseEventChannel::new();
+    let mut reader = mouse_event_channel.register_reader();
+    input_state.set_focus(true, &mut mouse_event_channel);
+
+    // Dragging with shift held
+    input_state.keyboard.set(Key::LShift, true);
+    input_state.keyboard.set(Key::A, true);
+    input_state.mouse_left_button.set(true);
+    input_state.is_mouse_left_button_dragging = true;
+    input_state.mouse_abs_pos = vec2![30., 40.].into();
+    input_state.reset_relative_data();
+
+    input_state.set_focus(false, &mut mouse_event_channel);
+    assert!(input_state.in_focus.just_deactivated());
+    assert!(!input_state.is_mouse_left_button_dragging);
+    assert!(input_state.mouse_left_button.just_deactivated());
+    assert!(!input_state.mouse_right_button.is_activated());
+    assert!(input_state.keyboard.just_deactivated(Key::LShift));
+    assert!(input_state.keyboard.just_deactivated(Key::A));
+    assert!(!input_state.keyboard.is_shift_activated());
+
+    let events = mouse_event_channel.read(&mut reader).collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    match (events[0], events[1]) {
+      (MouseEvent::MouseUp(up), MouseEvent::DragEnd(end)) => {
+        assert_eq!(up.0, vec2![30., 40.]);
+        assert_eq!(end.0, vec2![30., 40.]);
+      }
+      _ => panic!("Expected the drag to end"),
+    }
+
+    // Nothing more to end when losing the focus again
+    input_state.reset_relative_data();
+    input_state.set_focus(false, &mut mouse_event_channel);
+    assert_eq!(mouse_event_channel.read(&mut reader).count(), 0);
+    assert!(!input_state.keyboard.just_deactivated(Key::A));
+  }
+
+  #[test]
+  fn test_two_finger_gestures() {
+    let mut input_state = InputState::default();
+    input_state.touch(1, TouchPhase::Start, vec2![100., 100.].into());
+    input_state.touch(1, TouchPhase::Move, vec2![110., 100.].into());
+    assert_eq!(input_state.rel_pan, vec2![0., 0.].into());
+    assert_eq!(input_state.gesture_center(), None);
+
+    // The second finger moves away and around the first one, the middle moves by half as much
+    input_state.touch(2, TouchPhase::Start, vec2![210., 100.].into());
+    assert_eq!(input_state.gesture_center(), Some(vec2![160., 100.].into()));
+    input_state.touch(2, TouchPhase::Move, vec2![110., 300.].into());
+    assert!((input_state.rel_pinch - 1.0).abs() < 1e-12);
+    assert!((input_state.rel_rotation - PI / 2.).abs() < 1e-12);
+    assert_eq!(input_state.rel_pan, vec2![-50., 100.].into());
+
+    // Back where it was, all in the same frame
+    input_state.touch(2, TouchPhase::Move, vec2![210., 100.].into());
+    assert!(input_state.rel_pinch.abs() < 1e-12);
+    assert!(input_state.rel_rotation.abs() < 1e-12);
+    input_state.reset_relative_data();
+    assert_eq!(input_state.rel_pan, vec2![0., 0.].into());
+
+    input_state.touch(1, TouchPhase::End, vec2![110., 100.].into());
+    input_state.touch(2, TouchPhase::Move, vec2![250., 100.].into());
+    assert_eq!(input_state.rel_pan, vec2![0., 0.].into());
+    assert_eq!(input_state.rel_pinch, 0.);
+  }
+
+  #[test]
+  fn test_text_mode_types_characters_instead_of_shortcuts() {
+    let mut input_state = InputState::default();
+    input_state.keyboard.set(Key::LShift, true);
+    input_state.type_text("ignored");
+    assert!(input_state.typed_text.is_empty());
+
+    input_state.set_text_mode(true);
+    assert!(input_state.keyboard.just_deactivated(Key::LShift));
+    input_state.reset_relative_data();
+    input_state.set_key(Key::S, true);
+    input_state.set_key(Key::Backspace, true);
+    input_state.type_text("S");
+    input_state.type_text("ide");
+    assert!(!input_state.keyboard.is_activated(Key::S));
+    assert!(input_state.keyboard.just_activated(Key::Backspace));
+    assert_eq!(input_state.typed_text, "Side");
+
+    // The characters are only there for the frame they are typed in
+    input_state.reset_relative_data();
+    assert!(input_state.typed_text.is_empty());
+    input_state.set_text_mode(false);
+    input_state.set_key(Key::S, true);
+    assert!(input_state.keyboard.just_activated(Key::S));
+  }
+}
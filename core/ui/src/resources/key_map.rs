@@ -0,0 +1,446 @@
+use crate::resources::*;
+use std::collections::HashMap;
+
+/// The actions triggered by a key chord, which the interaction systems look up in the key map
+/// instead of checking the keys themselves
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+  SelectTool,
+  ViewportTool,
+  PointTool,
+  LineTool,
+  CircleTool,
+  PolygonTool,
+  RotateTool,
+  ScaleTool,
+  MirrorTool,
+  RatioTool,
+  TextTool,
+  SliderTool,
+  FreehandTool,
+  MacroTool, // The last defined macro, then the ones before it
+  NextLineType,
+  StraightLineType,
+  RayLineType,
+  SegmentLineType,
+  Cancel, // Aborts the construction going on with the current tool
+  Save,
+  Load,
+  Undo,
+  Redo,
+  ToggleViewportHistory,
+  Copy,
+  Paste,
+  Duplicate,
+  Exit,
+  SelectAll,
+  DeselectAll,
+  RemoveSelected,
+  HideSelected,
+  UnhideAll,
+  HideConstruction,
+  ToggleTrace,
+  ClearTraces,
+  NextColor,
+  NextThickness,
+  NextPointRadius,
+  NextDashPattern,
+  ToggleAnimation,
+  SpeedUpAnimation,
+  SlowDownAnimation,
+  IncreaseUnderlayOpacity,
+  DecreaseUnderlayOpacity,
+  ToggleRuler,
+  ToggleProtractor,
+  ToggleGrid,
+  ToggleGridSnapping,
+  ToggleAxes,
+  ToggleTheme,
+  DefineMacro,
+  CreateMidpoint,
+  CreateIntersections,
+  RedefinePoint,
+  CreatePerpendicular,
+  CreateParallel,
+  CreatePerpendicularBisector,
+  CreateCircumcircle,
+  CreateCompass,
+  ToggleSnapToPoints,
+  ToggleSnapToMidPoints,
+  ToggleSnapToIntersections,
+  ToggleSnapOnLines,
+  ToggleSnapOnCircles,
+  ToggleSnapToGrid,
+  IncreaseSnapRadius,
+  DecreaseSnapRadius,
+  ToggleSpatialHashOverlay,
+}
+
+/// Every action with its name in the config file and its default chords. An action can share its
+/// chord with another one acting on other things, e.g. `Cmd+]` speeds up the selected animations
+/// and makes the selected underlays more opaque
+static BINDINGS: [(Action, &str, &[&str]); 69] = [
+  (Action::SelectTool, "select_tool", &["S"]),
+  (Action::ViewportTool, "viewport_tool", &["V"]),
+  (Action::PointTool, "point_tool", &["P"]),
+  (Action::LineTool, "line_tool", &["L"]),
+  (Action::CircleTool, "circle_tool", &["C"]),
+  (Action::PolygonTool, "polygon_tool", &["G"]),
+  (Action::RotateTool, "rotate_tool", &["R"]),
+  (Action::ScaleTool, "scale_tool", &["K"]),
+  (Action::MirrorTool, "mirror_tool", &["F"]),
+  (Action::RatioTool, "ratio_tool", &["N"]),
+  (Action::TextTool, "text_tool", &["X"]),
+  (Action::SliderTool, "slider_tool", &["Y"]),
+  (Action::FreehandTool, "freehand_tool", &["I"]),
+  (Action::MacroTool, "macro_tool", &["E"]),
+  (Action::NextLineType, "next_line_type", &["Tab"]),
+  (Action::StraightLineType, "straight_line_type", &["1"]),
+  (Action::RayLineType, "ray_line_type", &["2"]),
+  (Action::SegmentLineType, "segment_line_type", &["3"]),
+  (Action::Cancel, "cancel", &["Escape"]),
+  (Action::Save, "save", &["Cmd+S"]),
+  (Action::Load, "load", &["Cmd+Shift+O"]),
+  (Action::Undo, "undo", &["Cmd+Z"]),
+  (Action::Redo, "redo", &["Cmd+Shift+Z"]),
+  (Action::ToggleViewportHistory, "toggle_viewport_history", &["Cmd+Alt+Z"]),
+  (Action::Copy, "copy", &["Cmd+C"]),
+  (Action::Paste, "paste", &["Cmd+V"]),
+  (Action::Duplicate, "duplicate", &["Cmd+Shift+V"]),
+  (Action::Exit, "exit", &["Cmd+Q", "Cmd+W"]),
+  (Action::SelectAll, "select_all", &["Cmd+A"]),
+  (Action::DeselectAll, "deselect_all", &["Cmd+D"]),
+  (Action::RemoveSelected, "remove_selected", &["Delete", "Backspace"]),
+  (Action::HideSelected, "hide_selected", &["Cmd+H"]),
+  (Action::UnhideAll, "unhide_all", &["Cmd+Shift+H"]),
+  (Action::HideConstruction, "hide_construction", &["Cmd+Alt+H"]),
+  (Action::ToggleTrace, "toggle_trace", &["Cmd+T"]),
+  (Action::ClearTraces, "clear_traces", &["Cmd+Shift+T"]),
+  (Action::NextColor, "next_color", &["Cmd+J"]),
+  (Action::NextThickness, "next_thickness", &["Cmd+U"]),
+  (Action::NextPointRadius, "next_point_radius", &["Cmd+Shift+U"]),
+  (Action::NextDashPattern, "next_dash_pattern", &["Cmd+I"]),
+  (Action::ToggleAnimation, "toggle_animation", &["Space"]),
+  (Action::SpeedUpAnimation, "speed_up_animation", &["Cmd+RightBracket"]),
+  (Action::SlowDownAnimation, "slow_down_animation", &["Cmd+LeftBracket"]),
+  (
+    Action::IncreaseUnderlayOpacity,
+    "increase_underlay_opacity",
+    &["Cmd+RightBracket"],
+  ),
+  (
+    Action::DecreaseUnderlayOpacity,
+    "decrease_underlay_opacity",
+    &["Cmd+LeftBracket"],
+  ),
+  (Action::ToggleRuler, "toggle_ruler", &["Cmd+R"]),
+  (Action::ToggleProtractor, "toggle_protractor", &["Cmd+Shift+R"]),
+  (Action::ToggleGrid, "toggle_grid", &["Cmd+Quote"]),
+  (Action::ToggleGridSnapping, "toggle_grid_snapping", &["Cmd+Shift+Quote"]),
+  (Action::ToggleAxes, "toggle_axes", &["Cmd+Semicolon"]),
+  (Action::ToggleTheme, "toggle_theme", &["Cmd+Shift+N"]),
+  (Action::DefineMacro, "define_macro", &["Cmd+Shift+M"]),
+  (Action::CreateMidpoint, "create_midpoint", &["Cmd+M"]),
+  (Action::CreateIntersections, "create_intersections", &["Cmd+E"]),
+  (Action::RedefinePoint, "redefine_point", &["Cmd+F"]),
+  (
+    Action::CreatePerpendicular,
+    "create_perpendicular",
+    &["Cmd+Shift+Backslash"],
+  ),
+  (Action::CreateParallel, "create_parallel", &["Cmd+Shift+Minus"]),
+  (
+    Action::CreatePerpendicularBisector,
+    "create_perpendicular_bisector",
+    &["Cmd+B"],
+  ),
+  (Action::CreateCircumcircle, "create_circumcircle", &["Cmd+O"]),
+  (Action::CreateCompass, "create_compass", &["Cmd+K"]),
+  (Action::ToggleSnapToPoints, "toggle_snap_to_points", &["Cmd+1"]),
+  (Action::ToggleSnapToMidPoints, "toggle_snap_to_mid_points", &["Cmd+2"]),
+  (
+    Action::ToggleSnapToIntersections,
+    "toggle_snap_to_intersections",
+    &["Cmd+3"],
+  ),
+  (Action::ToggleSnapOnLines, "toggle_snap_on_lines", &["Cmd+4"]),
+  (Action::ToggleSnapOnCircles, "toggle_snap_on_circles", &["Cmd+5"]),
+  (Action::ToggleSnapToGrid, "toggle_snap_to_grid", &["Cmd+6"]),
+  (Action::IncreaseSnapRadius, "increase_snap_radius", &["Cmd+Equals"]),
+  (Action::DecreaseSnapRadius, "decrease_snap_radius", &["Cmd+Minus"]),
+  (Action::ToggleSpatialHashOverlay, "toggle_spatial_hash_overlay", &["F3"]),
+];
+
+impl Action {
+  /// The name of the action in the config file, e.g. `create_perpendicular`
+  pub fn name(&self) -> &'static str {
+    BINDINGS.iter().find(|(action, _, _)| action == self).unwrap().1
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    BINDINGS
+      .iter()
+      .find(|(_, action_name, _)| *action_name == name)
+      .map(|(action, _, _)| *action)
+  }
+}
+
+/// A key pressed while holding exactly these modifiers. `Cmd` stands for the command key on macOS
+/// and for control elsewhere
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+  pub key: Key,
+  pub command: bool,
+  pub shift: bool,
+  pub alt: bool,
+}
+
+impl KeyChord {
+  /// Parses chords like `Cmd+Shift+Backslash` or `F3`, the key coming last
+  pub fn parse(text: &str) -> Result<Self, String> {
+    let mut parts = text.split('+').map(str::trim).collect::<Vec<_>>();
+    let key_name = parts.pop().unwrap_or_default();
+    let key = key_named(key_name).ok_or_else(|| format!("Unknown key `{}` in `{}`", key_name, text))?;
+    let mut chord = Self {
+      key,
+      command: false,
+      shift: false,
+      alt: false,
+    };
+    for modifier in parts {
+      match modifier.to_lowercase().as_str() {
+        "cmd" | "command" | "ctrl" | "control" => chord.command = true,
+        "shift" => chord.shift = true,
+        "alt" | "option" => chord.alt = true,
+        _ => return Err(format!("Unknown modifier `{}` in `{}`", modifier, text)),
+      }
+    }
+    Ok(chord)
+  }
+
+  pub fn just_triggered(&self, keyboard: &Keyboard) -> bool {
+    keyboard.just_activated(self.key)
+      && keyboard.is_command_activated() == self.command
+      && keyboard.is_shift_activated() == self.shift
+      && keyboard.is_alt_activated() == self.alt
+  }
+}
+
+/// The key chords of every action, the defaults unless loaded from a config file. The chords are
+/// matched with their exact modifiers, so that `Cmd+H` does not also trigger on `Cmd+Shift+H`
+pub struct KeyMap(HashMap<Action, Vec<KeyChord>>);
+
+impl Default for KeyMap {
+  fn default() -> Self {
+    Self(
+      BINDINGS
+        .iter()
+        .map(|(action, _, chords)| {
+          let chords = chords.iter().map(|chord| KeyChord::parse(chord).unwrap()).collect();
+          (*action, chords)
+        })
+        .collect(),
+    )
+  }
+}
+
+impl KeyMap {
+  /// The default key map with the chords listed in the TOML config replaced, e.g.
+  ///
+  /// ```toml
+  /// create_perpendicular = "Cmd+Shift+P"
+  /// exit = ["Cmd+Q", "Cmd+W"]
+  /// toggle_animation = [] # Unbound
+  /// ```
+  pub fn from_toml(source: &str) -> Result<Self, String> {
+    let table = match source.parse::<toml::Value>() {
+      Ok(toml::Value::Table(table)) => table,
+      Ok(_) => return Err("The key map should be a table".to_string()),
+      Err(err) => return Err(err.to_string()),
+    };
+    let mut key_map = Self::default();
+    for (name, value) in table {
+      let action = Action::from_name(&name).ok_or_else(|| format!("Unknown action `{}`", name))?;
+      let texts = match value {
+        toml::Value::String(text) => vec![text],
+        toml::Value::Array(values) => values
+          .into_iter()
+          .map(|value| match value {
+            toml::Value::String(text) => Ok(text),
+            _ => Err(format!("The chords of `{}` should be strings", name)),
+          })
+          .collect::<Result<Vec<_>, _>>()?,
+        _ => return Err(format!("The chords of `{}` should be a string or a list", name)),
+      };
+      let chords = texts
+        .iter()
+        .map(|text| KeyChord::parse(text))
+        .collect::<Result<Vec<_>, _>>()?;
+      key_map.set(action, chords);
+    }
+    Ok(key_map)
+  }
+
+  pub fn chords(&self, action: Action) -> &[KeyChord] {
+    self.0.get(&action).map_or(&[], |chords| chords.as_slice())
+  }
+
+  pub fn set(&mut self, action: Action, chords: Vec<KeyChord>) {
+    self.0.insert(action, chords);
+  }
+
+  /// Whether one of the chords of the action got pressed this frame
+  pub fn just_triggered(&self, action: Action, input_state: &InputState) -> bool {
+    self
+      .chords(action)
+      .iter()
+      .any(|chord| chord.just_triggered(&input_state.keyboard))
+  }
+}
+
+/// The key with the name, that of the key in the `Key` enum or the character it types, ignoring
+/// the case, e.g. `Backslash`, `\` or `f3`
+fn key_named(name: &str) -> Option<Key> {
+  let key = match name.to_lowercase().as_str() {
+    "a" => Key::A,
+    "b" => Key::B,
+    "c" => Key::C,
+    "d" => Key::D,
+    "e" => Key::E,
+    "f" => Key::F,
+    "g" => Key::G,
+    "h" => Key::H,
+    "i" => Key::I,
+    "j" => Key::J,
+    "k" => Key::K,
+    "l" => Key::L,
+    "m" => Key::M,
+    "n" => Key::N,
+    "o" => Key::O,
+    "p" => Key::P,
+    "q" => Key::Q,
+    "r" => Key::R,
+    "s" => Key::S,
+    "t" => Key::T,
+    "u" => Key::U,
+    "v" => Key::V,
+    "w" => Key::W,
+    "x" => Key::X,
+    "y" => Key::Y,
+    "z" => Key::Z,
+    "0" | "d0" => Key::D0,
+    "1" | "d1" => Key::D1,
+    "2" | "d2" => Key::D2,
+    "3" | "d3" => Key::D3,
+    "4" | "d4" => Key::D4,
+    "5" | "d5" => Key::D5,
+    "6" | "d6" => Key::D6,
+    "7" | "d7" => Key::D7,
+    "8" | "d8" => Key::D8,
+    "9" | "d9" => Key::D9,
+    "f1" => Key::F1,
+    "f2" => Key::F2,
+    "f3" => Key::F3,
+    "f4" => Key::F4,
+    "f5" => Key::F5,
+    "f6" => Key::F6,
+    "f7" => Key::F7,
+    "f8" => Key::F8,
+    "f9" => Key::F9,
+    "f10" => Key::F10,
+    "f11" => Key::F11,
+    "f12" => Key::F12,
+    "tab" => Key::Tab,
+    "space" => Key::Space,
+    "escape" | "esc" => Key::Escape,
+    "return" | "enter" => Key::Return,
+    "backspace" => Key::Backspace,
+    "delete" => Key::Delete,
+    "insert" => Key::Insert,
+    "home" => Key::Home,
+    "end" => Key::End,
+    "pageup" => Key::PageUp,
+    "pagedown" => Key::PageDown,
+    "up" => Key::Up,
+    "down" => Key::Down,
+    "left" => Key::Left,
+    "right" => Key::Right,
+    "minus" | "-" => Key::Minus,
+    "equals" | "=" => Key::Equals,
+    "leftbracket" | "[" => Key::LeftBracket,
+    "rightbracket" | "]" => Key::RightBracket,
+    "backslash" | "\\" => Key::Backslash,
+    "slash" | "/" => Key::Slash,
+    "semicolon" | ";" => Key::Semicolon,
+    "quote" | "'" => Key::Quote,
+    "comma" | "," => Key::Comma,
+    "period" | "." => Key::Period,
+    "backquote" | "`" => Key::Backquote,
+    _ => return None,
+  };
+  Some(key)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn pressing(keys: &[Key]) -> InputState {
+    let mut input_state = InputState::default();
+    for key in keys {
+      input_state.set_key(*key, true);
+    }
+    input_state
+  }
+
+  #[test]
+  fn test_default_chords_match_their_exact_modifiers() {
+    let key_map = KeyMap::default();
+    let cmd = if cfg!(target_os = "macos") {
+      Key::LCommand
+    } else {
+      Key::LCtrl
+    };
+    let perpendicular = pressing(&[cmd, Key::LShift, Key::Backslash]);
+    assert!(key_map.just_triggered(Action::CreatePerpendicular, &perpendicular));
+    let hide = pressing(&[cmd, Key::H]);
+    assert!(key_map.just_triggered(Action::HideSelected, &hide));
+    assert!(!key_map.just_triggered(Action::UnhideAll, &hide));
+    let unhide = pressing(&[cmd, Key::LShift, Key::H]);
+    assert!(!key_map.just_triggered(Action::HideSelected, &unhide));
+    assert!(key_map.just_triggered(Action::UnhideAll, &unhide));
+
+    // Every action has a name of its own
+    for (action, name, _) in BINDINGS.iter() {
+      assert_eq!(Action::from_name(name), Some(*action));
+      assert!(!key_map.chords(*action).is_empty());
+    }
+  }
+
+  #[test]
+  fn test_key_map_from_toml() {
+    let source = "create_perpendicular = \"Ctrl+Alt+p\"\nexit = [\"Cmd+Q\", \"Escape\"]\ntoggle_animation = []\n";
+    let key_map = KeyMap::from_toml(source).unwrap();
+    assert_eq!(
+      key_map.chords(Action::CreatePerpendicular),
+      &[KeyChord {
+        key: Key::P,
+        command: true,
+        shift: false,
+        alt: true,
+      }]
+    );
+    assert_eq!(key_map.chords(Action::Exit).len(), 2);
+    assert!(key_map.chords(Action::ToggleAnimation).is_empty());
+    // The actions left out keep their defaults
+    assert_eq!(key_map.chords(Action::Undo), KeyMap::default().chords(Action::Undo));
+
+    assert!(!key_map.just_triggered(Action::ToggleAnimation, &pressing(&[Key::Space])));
+    assert!(key_map.just_triggered(Action::Exit, &pressing(&[Key::Escape])));
+
+    assert!(KeyMap::from_toml("frobnicate = \"Cmd+F\"").is_err());
+    assert!(KeyMap::from_toml("undo = \"Hyper+Z\"").is_err());
+    assert!(KeyMap::from_toml("undo = \"Cmd+Banana\"").is_err());
+    assert!(KeyMap::from_toml("undo = 3").is_err());
+  }
+}
@@ -0,0 +1,17 @@
+use specs::prelude::*;
+
+/// The measurement overlays shown on top of the sketch: a ruler along the edges of the viewport,
+/// and a protractor dropped at a point to read the angles between the lines through it
+pub struct MeasureOverlays {
+  pub ruler: bool,
+  pub protractor: Option<Entity>, // The point the protractor is centered at
+}
+
+impl Default for MeasureOverlays {
+  fn default() -> Self {
+    Self {
+      ruler: false,
+      protractor: None,
+    }
+  }
+}
@@ -1,19 +1,43 @@
+mod cursor_readout;
 mod default_select_rectangle_style;
 mod delta_time;
 mod exit_state;
+mod freehand_draft;
+mod grid_settings;
 mod input_state;
+mod key_map;
+mod measure_overlays;
+mod ratio_point;
+mod select_lasso;
 mod select_rectangle;
+mod sketch_file_path;
 mod snap_circle;
 mod snap_line;
 mod snap_point;
+mod snap_polygon;
+mod snap_settings;
+mod spatial_hash_overlay;
 mod tool_state;
+mod typing_text;
 
+pub use cursor_readout::*;
 pub use default_select_rectangle_style::*;
 pub use delta_time::*;
 pub use exit_state::*;
+pub use freehand_draft::*;
+pub use grid_settings::*;
 pub use input_state::*;
+pub use key_map::*;
+pub use measure_overlays::*;
+pub use ratio_point::*;
+pub use select_lasso::*;
 pub use select_rectangle::*;
+pub use sketch_file_path::*;
 pub use snap_circle::*;
 pub use snap_line::*;
 pub use snap_point::*;
+pub use snap_polygon::*;
+pub use snap_settings::*;
+pub use spatial_hash_overlay::*;
 pub use tool_state::*;
+pub use typing_text::*;
@@ -0,0 +1,33 @@
+use specs::prelude::*;
+
+/// The point being placed with the ratio tool, dividing the way between the two points picked
+/// first. The ratio is the fraction of the way from the first point
+pub struct RatioPoint {
+  pub maybe_first_point: Option<Entity>,
+  pub maybe_second_point: Option<Entity>,
+  pub ratio: f64,
+}
+
+impl Default for RatioPoint {
+  fn default() -> Self {
+    Self {
+      maybe_first_point: None,
+      maybe_second_point: None,
+      ratio: 0.5,
+    }
+  }
+}
+
+impl RatioPoint {
+  /// Both points are picked, the ratio is adjusted until the point gets placed
+  pub fn get_points(&self) -> Option<(Entity, Entity)> {
+    match (self.maybe_first_point, self.maybe_second_point) {
+      (Some(p1), Some(p2)) => Some((p1, p2)),
+      _ => None,
+    }
+  }
+
+  pub fn clear(&mut self) {
+    *self = Self::default();
+  }
+}
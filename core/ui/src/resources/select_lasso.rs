@@ -0,0 +1,28 @@
+use core_lib::math::{Polygon, Vector2};
+
+/// The cursor path of the ongoing lasso selection, in screen space
+pub struct SelectLasso(pub Vec<Vector2>);
+
+impl Default for SelectLasso {
+  fn default() -> Self {
+    Self(vec![])
+  }
+}
+
+impl SelectLasso {
+  pub fn push(&mut self, p: Vector2) {
+    self.0.push(p);
+  }
+
+  pub fn clear(&mut self) {
+    self.0.clear();
+  }
+
+  pub fn path(&self) -> &[Vector2] {
+    &self.0
+  }
+
+  pub fn polygon(&self) -> Polygon {
+    Polygon::new(self.0.clone())
+  }
+}
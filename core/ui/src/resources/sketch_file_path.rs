@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+/// File the sketch is saved to and loaded from
+pub struct SketchFilePath(pub PathBuf);
+
+impl Default for SketchFilePath {
+  fn default() -> Self {
+    Self(PathBuf::from("sketch.json"))
+  }
+}
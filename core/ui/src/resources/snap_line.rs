@@ -2,12 +2,14 @@ use specs::prelude::*;
 
 pub struct SnapLine {
   pub maybe_first_point: Option<Entity>,
+  pub maybe_relative_angle: Option<(Entity, f64)>, // Reference line, snapped angle relative to it in degrees
 }
 
 impl Default for SnapLine {
   fn default() -> Self {
     Self {
       maybe_first_point: None,
+      maybe_relative_angle: None,
     }
   }
 }
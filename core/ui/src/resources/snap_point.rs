@@ -35,7 +35,11 @@ pub enum SnapPointType {
   SnapOnLine(Entity, f64),                                           // f64 is t
   SnapOnLineLineIntersection(Entity, Entity),                        // Line Line
   SnapOnCircle(Entity, f64),                                         // f64 is theta
+  SnapOnConic(Entity, f64),                                          // f64 is the parameter
   SnapOnCircleLineIntersection(Entity, Entity, CircleIntersectId),   // Circle, Line, type
   SnapOnCircleCircleIntersection(Entity, Entity, CircleIntersectId), // Circle, Circle, type
+  SnapOnArcLineIntersection(Entity, Entity, CircleIntersectId),      // Arc, Line, type
+  SnapOnArcCircleIntersection(Entity, Entity, CircleIntersectId),    // Arc, Circle, type
+  SnapOnGrid(VirtualPosition),                                       // The grid intersection
   NotSnapped,
 }
@@ -0,0 +1,12 @@
+use specs::prelude::*;
+
+/// Vertices clicked so far with the polygon tool, in order
+pub struct SnapPolygon {
+  pub vertices: Vec<Entity>,
+}
+
+impl Default for SnapPolygon {
+  fn default() -> Self {
+    Self { vertices: vec![] }
+  }
+}
@@ -0,0 +1,194 @@
+use core_lib::utilities::ScreenScalar;
+use std::cmp::Ordering;
+
+pub static DEFAULT_SNAP_RADIUS: f64 = 12.0; // Pixel
+pub static MIN_SNAP_RADIUS: f64 = 4.0;
+pub static MAX_SNAP_RADIUS: f64 = 40.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SnapCategory {
+  Point, // Including the endpoints of the segments
+  MidPoint,
+  Intersection,
+  OnLine,
+  OnCircle,
+  Grid,
+}
+
+/// Snap categories ordered from the highest priority to the lowest. The default keeps an existing
+/// point ahead of everything else and the grid behind everything else
+#[derive(Debug, Clone)]
+pub struct SnapPriority(Vec<SnapCategory>);
+
+impl Default for SnapPriority {
+  fn default() -> Self {
+    Self(vec![
+      SnapCategory::Point,
+      SnapCategory::Intersection,
+      SnapCategory::MidPoint,
+      SnapCategory::OnLine,
+      SnapCategory::OnCircle,
+      SnapCategory::Grid,
+    ])
+  }
+}
+
+impl SnapPriority {
+  pub fn new(order: Vec<SnapCategory>) -> Self {
+    Self(order)
+  }
+
+  pub fn order(&self) -> &Vec<SnapCategory> {
+    &self.0
+  }
+
+  /// Categories missing from the order are ranked after all the listed ones
+  pub fn rank(&self, category: SnapCategory) -> usize {
+    self.0.iter().position(|c| *c == category).unwrap_or(self.0.len())
+  }
+
+  /// The candidates are the closest of their category, all within its reach. The distance, relative
+  /// to that reach, decides between the categories ranked the same
+  pub fn choose<T>(&self, candidates: Vec<(SnapCategory, f64, T)>) -> Option<T> {
+    candidates
+      .into_iter()
+      .min_by(|(c1, d1, _), (c2, d2, _)| {
+        (self.rank(*c1), d1)
+          .partial_cmp(&(self.rank(*c2), d2))
+          .unwrap_or(Ordering::Equal)
+      })
+      .map(|(_, _, candidate)| candidate)
+  }
+}
+
+/// How the mouse snaps: which categories it snaps to, how far from them, and which one wins when
+/// several are in reach. Snapping to the grid is off by default
+pub struct SnapSettings {
+  pub priority: SnapPriority,
+  radius: f64, // Pixel, the reach of the points, the other categories scale along
+  disabled: Vec<SnapCategory>,
+}
+
+impl Default for SnapSettings {
+  fn default() -> Self {
+    Self {
+      priority: SnapPriority::default(),
+      radius: DEFAULT_SNAP_RADIUS,
+      disabled: vec![SnapCategory::Grid],
+    }
+  }
+}
+
+impl SnapSettings {
+  pub fn radius(&self) -> f64 {
+    self.radius
+  }
+
+  /// Kept within `MIN_SNAP_RADIUS` and `MAX_SNAP_RADIUS`
+  pub fn set_radius(&mut self, radius: f64) {
+    self.radius = radius.clamp(MIN_SNAP_RADIUS, MAX_SNAP_RADIUS);
+  }
+
+  pub fn is_enabled(&self, category: SnapCategory) -> bool {
+    !self.disabled.contains(&category)
+  }
+
+  pub fn set_enabled(&mut self, category: SnapCategory, enabled: bool) {
+    self.disabled.retain(|c| *c != category);
+    if !enabled {
+      self.disabled.push(category);
+    }
+  }
+
+  pub fn toggle(&mut self, category: SnapCategory) {
+    self.set_enabled(category, !self.is_enabled(category));
+  }
+
+  /// How close the mouse has to get to snap to the category. Lines and circles are thin targets
+  /// that would get in the way of the points with the same reach, intersections get a little more
+  pub fn threshold(&self, category: SnapCategory) -> ScreenScalar {
+    let factor = match category {
+      SnapCategory::Point | SnapCategory::MidPoint => 1.0,
+      SnapCategory::Intersection => 1.25,
+      SnapCategory::OnLine | SnapCategory::OnCircle => 2.0 / 3.0,
+      SnapCategory::Grid => 5.0 / 6.0,
+    };
+    ScreenScalar(self.radius * factor)
+  }
+
+  /// Keeps the candidates of the enabled categories, then chooses according to the priority
+  pub fn choose<T>(&self, candidates: Vec<(SnapCategory, f64, T)>) -> Option<T> {
+    let candidates = candidates
+      .into_iter()
+      .filter(|(category, _, _)| self.is_enabled(*category))
+      .collect();
+    self.priority.choose(candidates)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_snap_priority_default_prefers_point() {
+    let priority = SnapPriority::default();
+    let candidates = vec![
+      (SnapCategory::Intersection, 0.1, "intersection"),
+      (SnapCategory::Point, 0.9, "point"),
+    ];
+    assert_eq!(priority.choose(candidates), Some("point"));
+  }
+
+  #[test]
+  fn test_snap_priority_reordered() {
+    let priority = SnapPriority::new(vec![SnapCategory::Intersection, SnapCategory::Point]);
+    let candidates = vec![
+      (SnapCategory::Point, 0.1, "point"),
+      (SnapCategory::Intersection, 0.9, "intersection"),
+    ];
+    assert_eq!(priority.choose(candidates), Some("intersection"));
+  }
+
+  #[test]
+  fn test_snap_priority_unlisted_category_comes_last() {
+    let priority = SnapPriority::new(vec![SnapCategory::OnCircle]);
+    let candidates = vec![
+      (SnapCategory::Point, 0.1, "point"),
+      (SnapCategory::OnCircle, 0.9, "circle"),
+    ];
+    assert_eq!(priority.choose(candidates), Some("circle"));
+    assert_eq!(priority.choose(Vec::<(SnapCategory, f64, &str)>::new()), None);
+
+    // The unlisted ones are ranked the same, the closest one wins
+    let candidates = vec![
+      (SnapCategory::Point, 0.6, "point"),
+      (SnapCategory::OnLine, 0.2, "line"),
+      (SnapCategory::Grid, 0.4, "grid"),
+    ];
+    assert_eq!(priority.choose(candidates), Some("line"));
+  }
+
+  #[test]
+  fn test_snap_settings_skip_disabled_categories() {
+    let mut snap_settings = SnapSettings::default();
+    let candidates = || {
+      vec![
+        (SnapCategory::OnLine, 0.5, "line"),
+        (SnapCategory::Intersection, 0.5, "intersection"),
+        (SnapCategory::Grid, 0.5, "grid"),
+      ]
+    };
+    assert_eq!(snap_settings.choose(candidates()), Some("intersection"));
+    snap_settings.toggle(SnapCategory::Intersection);
+    assert_eq!(snap_settings.choose(candidates()), Some("line"));
+    snap_settings.set_enabled(SnapCategory::OnLine, false);
+    assert_eq!(snap_settings.choose(candidates()), None);
+    snap_settings.toggle(SnapCategory::Grid);
+    assert_eq!(snap_settings.choose(candidates()), Some("grid"));
+
+    snap_settings.set_radius(100.);
+    assert_eq!(snap_settings.radius(), MAX_SNAP_RADIUS);
+    assert_eq!(snap_settings.threshold(SnapCategory::Intersection), ScreenScalar(50.));
+  }
+}
@@ -0,0 +1,17 @@
+pub struct SpatialHashOverlay(bool);
+
+impl Default for SpatialHashOverlay {
+  fn default() -> Self {
+    Self(false)
+  }
+}
+
+impl SpatialHashOverlay {
+  pub fn is_enabled(&self) -> bool {
+    self.0
+  }
+
+  pub fn toggle(&mut self) {
+    self.0 = !self.0;
+  }
+}
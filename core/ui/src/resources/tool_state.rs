@@ -1,18 +1,27 @@
-use core_lib::math::*;
+use core_lib::{math::*, resources::MacroId};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Tool {
   Select,
   Viewport,
   Point,
   Line(LineType),
   Circle,
+  Polygon,
+  Rotate,
+  Scale,
+  Mirror,
+  Ratio,
+  Text,
+  Slider,
+  Freehand,
+  Macro(MacroId), // Applies the macro to the clicked inputs
 }
 
 impl Tool {
   pub fn need_snap_point(&self) -> bool {
     match self {
-      Tool::Point | Tool::Line(_) | Tool::Circle => true,
+      Tool::Point | Tool::Line(_) | Tool::Circle | Tool::Polygon => true,
       _ => false,
     }
   }
@@ -0,0 +1,24 @@
+use core_lib::utilities::*;
+
+/// The text being typed with the text tool, shown where it will be inserted
+pub struct TypingText(pub Option<ScreenText>);
+
+impl Default for TypingText {
+  fn default() -> Self {
+    Self(None)
+  }
+}
+
+impl TypingText {
+  pub fn set(&mut self, text: ScreenText) {
+    self.0 = Some(text);
+  }
+
+  pub fn clear(&mut self) {
+    self.0 = None;
+  }
+
+  pub fn get(&self) -> Option<&ScreenText> {
+    self.0.as_ref()
+  }
+}
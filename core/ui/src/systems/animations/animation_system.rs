@@ -0,0 +1,142 @@
+use crate::resources::*;
+use core_lib::{
+  components::{markers::*, symbolics::*},
+  events::*,
+  utilities::*,
+};
+use specs::prelude::*;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Moves the playing animated points along their line or circle every frame. The points are
+/// updated the same way as dragging them, and pausing ends the move like a drag ends so that it
+/// can be undone
+pub struct AnimationSystem {
+  start_sym_points: HashMap<Entity, SymbolicPoint>,
+}
+
+impl Default for AnimationSystem {
+  fn default() -> Self {
+    Self {
+      start_sym_points: HashMap::new(),
+    }
+  }
+}
+
+impl<'a> System<'a> for AnimationSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, DeltaTime>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, Animated>,
+  );
+
+  fn run(&mut self, (entities, delta_time, mut command_event_channel, sym_points, mut animateds): Self::SystemData) {
+    let dt = delta_time.get();
+
+    // First end the animations that are not playing anymore
+    let mut ended = vec![];
+    for (ent, start_sym_point) in &self.start_sym_points {
+      let playing = entities.is_alive(*ent) && animateds.get(*ent).is_some_and(|animated| animated.playing);
+      if !playing {
+        ended.push(*ent);
+        if let Some(sym_point) = sym_points.get(*ent) {
+          command_event_channel.single_write(CommandEvent {
+            command: Command::Update(UpdateEvent::UpdatePointEnd(*ent, *start_sym_point, *sym_point)),
+            event_id: None,
+          });
+        }
+      }
+    }
+    for ent in ended {
+      self.start_sym_points.remove(&ent);
+    }
+
+    // Then move the playing ones
+    for (ent, sym_point, animated) in (&entities, &sym_points, &mut animateds).join() {
+      if !animated.playing {
+        continue;
+      }
+      let new_sym_point = match *sym_point {
+        SymbolicPoint::OnLine(line_ent, t) => SymbolicPoint::OnLine(line_ent, VirtualScalar(animated.advance(t.0, dt))),
+        SymbolicPoint::OnCircle(circle_ent, theta) => {
+          SymbolicPoint::OnCircle(circle_ent, animated.advance(theta / (2.0 * PI), dt) * 2.0 * PI)
+        }
+        _ => continue,
+      };
+      self.start_sym_points.entry(ent).or_insert(*sym_point);
+      command_event_channel.single_write(CommandEvent {
+        command: Command::Update(UpdateEvent::UpdatePoint(ent, *sym_point, new_sym_point)),
+        event_id: None,
+      });
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::{components::virtual_shapes::*, math::*, setup_core_lib, test_utils::*};
+
+  fn x_of(world: &World, ent: Entity) -> f64 {
+    world.read_storage::<VirtualPoint>().get(ent).unwrap().0.x
+  }
+
+  #[test]
+  fn test_point_on_line_moves_and_pause_can_be_undone() {
+    let mut world = World::new();
+    world.insert(DeltaTime::fixed(0.5));
+    let mut builder = DispatcherBuilder::new();
+    builder.add(AnimationSystem::default(), "animation_system", &[]);
+    setup_core_lib(&mut builder);
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let insert_point = Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![0., 0.].into())));
+    step(&mut world, &mut dispatcher, insert_point);
+    let p1 = last_inserted::<SymbolicPoint>(&world);
+    let insert_point = Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Free(vec2![4., 0.].into())));
+    step(&mut world, &mut dispatcher, insert_point);
+    let p2 = last_inserted::<SymbolicPoint>(&world);
+    let insert_line = Command::LineInsert(InsertLineEvent::InsertLine(SymbolicLine::Segment(p1, p2)));
+    step(&mut world, &mut dispatcher, insert_line);
+    let line = last_inserted::<SymbolicLine>(&world);
+    let on_line = SymbolicPoint::OnLine(line, VirtualScalar(0.25));
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::PointInsert(InsertPointEvent::InsertPoint(on_line)),
+    );
+    let p = last_inserted::<SymbolicPoint>(&world);
+
+    // Free points can't be animated
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Animation(AnimationEvent::Play(p1)),
+    );
+    assert!(world.read_storage::<Animated>().get(p1).is_none());
+
+    // A quarter of the segment per second, an eighth per frame
+    step(&mut world, &mut dispatcher, Command::Animation(AnimationEvent::Play(p)));
+    assert_eq!(x_of(&world, p), 1.0);
+    dispatcher.dispatch(&world);
+    assert_eq!(x_of(&world, p), 1.5);
+    step(
+      &mut world,
+      &mut dispatcher,
+      Command::Animation(AnimationEvent::Pause(p)),
+    );
+    dispatcher.dispatch(&world);
+    dispatcher.dispatch(&world);
+    assert_eq!(x_of(&world, p), 2.0);
+
+    world
+      .fetch_mut::<HistoryEventChannel>()
+      .single_write(HistoryEvent::Undo);
+    dispatcher.dispatch(&world);
+    dispatcher.dispatch(&world);
+    assert_eq!(x_of(&world, p), 1.0);
+  }
+}
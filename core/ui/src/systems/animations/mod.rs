@@ -0,0 +1,3 @@
+mod animation_system;
+
+pub use animation_system::*;
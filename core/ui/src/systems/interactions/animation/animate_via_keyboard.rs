@@ -0,0 +1,49 @@
+use crate::resources::*;
+use core_lib::{
+  components::{markers::*, symbolics::*},
+  events::*,
+};
+use specs::prelude::*;
+
+/// Space plays or pauses the selected points, `Cmd - ]` and `Cmd - [` make them faster and slower
+#[derive(Default)]
+pub struct AnimateViaKeyboard;
+
+impl<'a> System<'a> for AnimateViaKeyboard {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, Animated>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, input_state, key_map, mut command_event_channel, selecteds, sym_points, animateds): Self::SystemData,
+  ) {
+    if key_map.just_triggered(Action::ToggleAnimation, &input_state) {
+      command_event_channel.single_write(CommandEvent {
+        command: Command::Animation(AnimationEvent::TogglePlaySelected),
+        event_id: None,
+      });
+    }
+
+    let factor = if key_map.just_triggered(Action::SpeedUpAnimation, &input_state) {
+      2.0
+    } else if key_map.just_triggered(Action::SlowDownAnimation, &input_state) {
+      0.5
+    } else {
+      return;
+    };
+    for (ent, _, _) in (&entities, &selecteds, &sym_points).join() {
+      let speed = animateds.get(ent).copied().unwrap_or_default().speed;
+      command_event_channel.single_write(CommandEvent {
+        command: Command::Animation(AnimationEvent::SetSpeed(ent, speed * factor)),
+        event_id: None,
+      });
+    }
+  }
+}
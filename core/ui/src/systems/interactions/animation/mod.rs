@@ -0,0 +1,3 @@
+mod animate_via_keyboard;
+
+pub use animate_via_keyboard::*;
@@ -0,0 +1,28 @@
+use crate::resources::*;
+use core_lib::events::*;
+use specs::prelude::*;
+
+/// Cmd+C copies the selection to the clipboard and Cmd+V pastes it, a bit further every time.
+/// Cmd+Shift+V duplicates the selection without going through the clipboard
+#[derive(Default)]
+pub struct CopyPasteViaKeyboard;
+
+impl<'a> System<'a> for CopyPasteViaKeyboard {
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
+
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    let command = if key_map.just_triggered(Action::Copy, &input_state) {
+      Command::CopySelected
+    } else if key_map.just_triggered(Action::Duplicate, &input_state) {
+      Command::DuplicateSelection
+    } else if key_map.just_triggered(Action::Paste, &input_state) {
+      Command::Paste
+    } else {
+      return;
+    };
+    command_event_channel.single_write(CommandEvent {
+      command,
+      event_id: None,
+    });
+  }
+}
@@ -0,0 +1,3 @@
+mod copy_paste_via_keyboard;
+
+pub use copy_paste_via_keyboard::*;
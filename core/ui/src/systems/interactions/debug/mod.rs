@@ -0,0 +1,3 @@
+mod toggle_spatial_hash_overlay_via_keyboard;
+
+pub use toggle_spatial_hash_overlay_via_keyboard::*;
@@ -0,0 +1,15 @@
+use crate::resources::*;
+use specs::prelude::*;
+
+#[derive(Default)]
+pub struct ToggleSpatialHashOverlayViaKeyboard;
+
+impl<'a> System<'a> for ToggleSpatialHashOverlayViaKeyboard {
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, SpatialHashOverlay>);
+
+  fn run(&mut self, (input_state, key_map, mut overlay): Self::SystemData) {
+    if key_map.just_triggered(Action::ToggleSpatialHashOverlay, &input_state) {
+      overlay.toggle();
+    }
+  }
+}
@@ -5,12 +5,10 @@ use specs::prelude::*;
 pub struct ExitViaKeyboard;
 
 impl<'a> System<'a> for ExitViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, ExitEventChannel>);
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, ExitEventChannel>);
 
-  fn run(&mut self, (input_state, mut exit_event_channel): Self::SystemData) {
-    if input_state.keyboard.is_command_activated()
-      && (input_state.keyboard.just_activated(Key::Q) || input_state.keyboard.just_activated(Key::W))
-    {
+  fn run(&mut self, (input_state, key_map, mut exit_event_channel): Self::SystemData) {
+    if key_map.just_triggered(Action::Exit, &input_state) {
       exit_event_channel.single_write(ExitEvent);
     }
   }
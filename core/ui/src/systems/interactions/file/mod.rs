@@ -0,0 +1,3 @@
+mod save_load_via_keyboard;
+
+pub use save_load_via_keyboard::*;
@@ -0,0 +1,31 @@
+use crate::resources::*;
+use core_lib::events::*;
+use specs::prelude::*;
+
+/// Cmd + S saves the sketch and Cmd + Shift + O loads it back, Cmd + O alone being taken by the
+/// circumcircle
+#[derive(Default)]
+pub struct SaveLoadViaKeyboard;
+
+impl<'a> System<'a> for SaveLoadViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, SketchFilePath>,
+    Write<'a, CommandEventChannel>,
+  );
+
+  fn run(&mut self, (input_state, key_map, sketch_file_path, mut command_event_channel): Self::SystemData) {
+    let command = if key_map.just_triggered(Action::Save, &input_state) {
+      Command::SaveSketch(sketch_file_path.0.clone())
+    } else if key_map.just_triggered(Action::Load, &input_state) {
+      Command::LoadSketch(sketch_file_path.0.clone())
+    } else {
+      return;
+    };
+    command_event_channel.single_write(CommandEvent {
+      command,
+      event_id: None,
+    });
+  }
+}
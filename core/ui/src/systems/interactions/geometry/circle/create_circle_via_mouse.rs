@@ -19,6 +19,7 @@ impl Default for CreateCircleViaMouse {
 impl<'a> System<'a> for CreateCircleViaMouse {
   type SystemData = (
     Read<'a, InputState>,
+    Read<'a, KeyMap>,
     Write<'a, SnapCircle>,
     Read<'a, ToolChangeEventChannel>,
     Write<'a, ActivePointEventChannel>,
@@ -33,12 +34,13 @@ impl<'a> System<'a> for CreateCircleViaMouse {
   fn run(
     &mut self,
     (
-            input_state,
-            mut snap_circle,
-            tool_change_event_channel,
-            mut active_point_event_reader,
-            mut command_event_channel,
-        ): Self::SystemData,
+      input_state,
+      key_map,
+      mut snap_circle,
+      tool_change_event_channel,
+      mut active_point_event_reader,
+      mut command_event_channel,
+    ): Self::SystemData,
   ) {
     if let Some(reader) = &mut self.tool_change_event_reader {
       for ToolChangeEvent(tool) in tool_change_event_channel.read(reader) {
@@ -49,9 +51,7 @@ impl<'a> System<'a> for CreateCircleViaMouse {
             }
           }
           _ => {
-            if let Some(reader) = &mut self.active_point_event_reader {
-              std::mem::drop(reader);
-              self.active_point_event_reader = None;
+            if self.active_point_event_reader.take().is_some() {
               snap_circle.maybe_first_point = None;
             }
           }
@@ -59,7 +59,7 @@ impl<'a> System<'a> for CreateCircleViaMouse {
       }
     }
 
-    if input_state.keyboard.just_activated(Key::Escape) {
+    if key_map.just_triggered(Action::Cancel, &input_state) {
       if snap_circle.maybe_first_point.is_some() {
         snap_circle.maybe_first_point = None;
       }
@@ -0,0 +1,19 @@
+use crate::resources::*;
+use core_lib::events::*;
+use specs::prelude::*;
+
+#[derive(Default)]
+pub struct CreateCircumcircleViaKeyboard;
+
+impl<'a> System<'a> for CreateCircumcircleViaKeyboard {
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
+
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    if key_map.just_triggered(Action::CreateCircumcircle, &input_state) {
+      command_event_channel.single_write(CommandEvent {
+        command: Command::CircleInsert(InsertCircleEvent::InsertCircumcircleFromSelection),
+        event_id: None,
+      });
+    }
+  }
+}
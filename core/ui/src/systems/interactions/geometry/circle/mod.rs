@@ -1,3 +1,7 @@
 mod create_circle_via_mouse;
+mod create_circumcircle_via_keyboard;
+mod create_compass_via_keyboard;
 
 pub use create_circle_via_mouse::*;
+pub use create_circumcircle_via_keyboard::*;
+pub use create_compass_via_keyboard::*;
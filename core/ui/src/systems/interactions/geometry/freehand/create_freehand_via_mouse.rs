@@ -0,0 +1,164 @@
+use crate::{events::*, resources::*};
+use core_lib::{components::freehand::*, events::*, resources::*, utilities::*};
+use specs::prelude::*;
+
+static MIN_SAMPLE_SPACING: ScreenScalar = ScreenScalar(2.0); // Pixel, closer samples only weigh the stroke down
+
+/// With the freehand tool, pressing and dragging draws a stroke following the pen, as wide as it
+/// is pressed. The stroke is added when the pen is lifted
+pub struct CreateFreehandViaMouse {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+}
+
+impl Default for CreateFreehandViaMouse {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for CreateFreehandViaMouse {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, Viewport>,
+    Write<'a, FreehandDraft>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Write<'a, CommandEventChannel>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      input_state,
+      key_map,
+      viewport,
+      mut freehand_draft,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      mut command_event_channel,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is freehand
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Freehand) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            self.mouse_event_reader = None;
+            freehand_draft.stroke = None;
+          }
+        }
+      }
+    }
+
+    if key_map.just_triggered(Action::Cancel, &input_state) {
+      freehand_draft.stroke = None;
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      let pressure = input_state.pressure();
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::MouseDown(position) => {
+            freehand_draft.stroke = Some(FreehandStroke {
+              samples: vec![(position.to_virtual(&*viewport), pressure)],
+            });
+          }
+          MouseEvent::DragMove(_, position) => {
+            if let Some(stroke) = &mut freehand_draft.stroke {
+              add_sample(stroke, *position, pressure, &viewport);
+            }
+          }
+          MouseEvent::MouseUp(position) => {
+            if let Some(mut stroke) = freehand_draft.stroke.take() {
+              // The pen may be reported lifted already, it ends as it was last pressed
+              let last_pressure = stroke.samples.last().map_or(pressure, |(_, pressure)| *pressure);
+              add_sample(&mut stroke, *position, last_pressure, &viewport);
+              command_event_channel.single_write(CommandEvent {
+                command: Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(stroke)),
+                event_id: None,
+              });
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// Adds where the pen is to the stroke, unless it has barely moved since the last sample
+fn add_sample(stroke: &mut FreehandStroke, position: ScreenPosition, pressure: f64, viewport: &Viewport) {
+  let far_enough = match stroke.samples.last() {
+    Some((last, _)) => (last.to_screen(viewport) - position).magnitude() >= MIN_SAMPLE_SPACING,
+    None => true,
+  };
+  if far_enough {
+    stroke.samples.push((position.to_virtual(viewport), pressure));
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  #[test]
+  fn test_draw_a_stroke() {
+    let mut world = World::new();
+    let mut system = CreateFreehandViaMouse::default();
+    System::setup(&mut system, &mut world);
+    let mut command_reader = world.fetch_mut::<CommandEventChannel>().register_reader();
+    world
+      .fetch_mut::<ToolChangeEventChannel>()
+      .single_write(ToolChangeEvent(Tool::Freehand));
+    system.run_now(&world);
+
+    world.fetch_mut::<InputState>().pen_pressure = Some(0.25);
+    let events = vec![
+      MouseEvent::MouseDown(vec2![10., 10.].into()),
+      MouseEvent::DragMove(vec2![0.5, 0.].into(), vec2![10.5, 10.].into()), // Too close, left out
+      MouseEvent::DragMove(vec2![9.5, 0.].into(), vec2![20., 10.].into()),
+      MouseEvent::MouseUp(vec2![30., 10.].into()),
+    ];
+    for event in events {
+      world.fetch_mut::<MouseEventChannel>().single_write(event);
+    }
+    system.run_now(&world);
+
+    let viewport = Viewport::default();
+    let commands = world
+      .fetch::<CommandEventChannel>()
+      .read(&mut command_reader)
+      .map(|event| event.command.clone())
+      .collect::<Vec<_>>();
+    match commands.as_slice() {
+      [Command::FreehandInsert(InsertFreehandEvent::InsertFreehand(stroke))] => {
+        let samples = stroke
+          .samples
+          .iter()
+          .map(|(position, pressure)| (position.to_screen(&viewport), *pressure))
+          .collect::<Vec<_>>();
+        assert_eq!(samples.len(), 3);
+        assert!(((samples[1].0).0 - vec2![20., 10.]).magnitude() < 1e-9);
+        assert!(samples.iter().all(|(_, pressure)| *pressure == 0.25));
+      }
+      _ => panic!("Unexpected commands {:?}", commands),
+    }
+    assert!(world.fetch::<FreehandDraft>().stroke.is_none());
+  }
+}
@@ -0,0 +1,3 @@
+mod create_freehand_via_mouse;
+
+pub use create_freehand_via_mouse::*;
@@ -19,6 +19,7 @@ impl Default for CreateLineViaMouse {
 impl<'a> System<'a> for CreateLineViaMouse {
   type SystemData = (
     Read<'a, InputState>,
+    Read<'a, KeyMap>,
     Read<'a, ToolState>,
     Write<'a, SnapLine>,
     Read<'a, ToolChangeEventChannel>,
@@ -35,6 +36,7 @@ impl<'a> System<'a> for CreateLineViaMouse {
     &mut self,
     (
       input_state,
+      key_map,
       tool_state,
       mut snap_line,
       tool_change_event_channel,
@@ -51,9 +53,7 @@ impl<'a> System<'a> for CreateLineViaMouse {
             }
           }
           _ => {
-            if let Some(reader) = &mut self.active_point_event_reader {
-              std::mem::drop(reader);
-              self.active_point_event_reader = None;
+            if self.active_point_event_reader.take().is_some() {
               snap_line.maybe_first_point = None;
             }
           }
@@ -61,7 +61,7 @@ impl<'a> System<'a> for CreateLineViaMouse {
       }
     }
 
-    if input_state.keyboard.just_activated(Key::Escape) {
+    if key_map.just_triggered(Action::Cancel, &input_state) {
       if snap_line.maybe_first_point.is_some() {
         snap_line.maybe_first_point = None;
       }
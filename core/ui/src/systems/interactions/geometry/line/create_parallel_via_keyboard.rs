@@ -6,13 +6,10 @@ use specs::prelude::*;
 pub struct CreateParallelViaKeyboard;
 
 impl<'a> System<'a> for CreateParallelViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, CommandEventChannel>);
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
 
-  fn run(&mut self, (input_state, mut command_event_channel): Self::SystemData) {
-    let cmd = input_state.keyboard.is_command_activated();
-    let shift = input_state.keyboard.is_shift_activated();
-    let minus = input_state.keyboard.just_activated(Key::Minus);
-    if cmd && shift && minus {
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    if key_map.just_triggered(Action::CreateParallel, &input_state) {
       command_event_channel.single_write(CommandEvent {
         command: Command::LineInsert(InsertLineEvent::InsertParallelFromSelection),
         event_id: None,
@@ -1,7 +1,11 @@
 mod create_line_via_mouse;
 mod create_parallel_via_keyboard;
+mod create_perpendicular_bisector_via_keyboard;
 mod create_perpendicular_via_keyboard;
+mod move_line_via_drag;
 
 pub use create_line_via_mouse::*;
 pub use create_parallel_via_keyboard::*;
+pub use create_perpendicular_bisector_via_keyboard::*;
 pub use create_perpendicular_via_keyboard::*;
+pub use move_line_via_drag::*;
@@ -0,0 +1,248 @@
+use crate::{events::*, resources::*, utilities::hitting_object};
+use core_lib::{
+  components::{markers::*, screen_shapes::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+type Endpoints = [(Entity, VirtualPosition); 2];
+
+pub struct MoveLineViaDrag {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  dragging_line: Option<Endpoints>,
+  start_position: Option<VirtualPosition>,
+}
+
+impl Default for MoveLineViaDrag {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+      dragging_line: None,
+      start_position: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for MoveLineViaDrag {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Read<'a, EntityQuadtree>,
+    Read<'a, Viewport>,
+    Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    Read<'a, LayerManager>,
+    ReadStorage<'a, Layer>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+    self.mouse_event_reader = Some(world.fetch_mut::<MouseEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      input_state,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      entity_quadtree,
+      viewport,
+      mut command_event_channel,
+      mut error_event_channel,
+      layer_manager,
+      layers,
+      sym_points,
+      sym_lines,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+    ): Self::SystemData,
+  ) {
+    // Same as dragging points, only listen to mouse events when the tool state is select
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Select) => {
+            self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+          }
+          _ => self.mouse_event_reader = None,
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::DragBegin(start_position) => {
+            if !input_state.keyboard.is_shift_activated() {
+              if let Some(entity) = hitting_object(
+                *start_position,
+                &entity_quadtree,
+                &viewport,
+                &scrn_points,
+                &sym_points,
+                &scrn_lines,
+                &scrn_circles,
+                SELECT_DIST_THRES,
+              ) {
+                if sym_lines.get(entity).is_some() {
+                  match line_drag_endpoints(entity, &sym_lines, &sym_points) {
+                    Some(endpoints) => {
+                      // Dragging the line moves its endpoints, none of them can be on a locked layer
+                      let [(p1, _), (p2, _)] = endpoints;
+                      match [entity, p1, p2]
+                        .iter()
+                        .find(|ent| layer_manager.is_locked(layers.get(**ent)))
+                      {
+                        Some(locked) => error_event_channel.single_write(ErrorEvent::LayerLocked(*locked)),
+                        None => {
+                          self.dragging_line = Some(endpoints);
+                          self.start_position = Some(start_position.to_virtual(&viewport));
+                          command_event_channel.single_write(CommandEvent {
+                            command: Command::Select(SelectEvent::Select(entity)),
+                            event_id: None,
+                          });
+                        }
+                      }
+                    }
+                    None => error_event_channel.single_write(ErrorEvent::LineNotDraggable(entity)),
+                  }
+                }
+              }
+            }
+          }
+          MouseEvent::DragMove(_, curr_position) => {
+            if let (Some(endpoints), Some(start_position)) = (self.dragging_line, self.start_position) {
+              let delta = curr_position.to_virtual(&viewport) - start_position;
+              for (ent, old_sym_point, new_sym_point) in drag_updates(endpoints, delta, &sym_points) {
+                command_event_channel.single_write(CommandEvent {
+                  command: Command::Update(UpdateEvent::UpdatePoint(ent, old_sym_point, new_sym_point)),
+                  event_id: None,
+                });
+              }
+            }
+          }
+          MouseEvent::DragEnd(curr_position) => {
+            if let (Some(endpoints), Some(start_position)) = (self.dragging_line, self.start_position) {
+              let delta = curr_position.to_virtual(&viewport) - start_position;
+              for (ent, position) in endpoints.iter() {
+                let old_sym_point = SymbolicPoint::Free(*position);
+                let new_sym_point = SymbolicPoint::Free(*position + delta);
+                command_event_channel.single_write(CommandEvent {
+                  command: Command::Update(UpdateEvent::UpdatePointEnd(*ent, old_sym_point, new_sym_point)),
+                  event_id: None,
+                });
+              }
+            }
+            self.dragging_line = None;
+            self.start_position = None;
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// The two defining points of a line together with their positions when the drag begins. Only
+/// lines going through two free points can be dragged by their body
+pub fn line_drag_endpoints<'a>(
+  line_ent: Entity,
+  sym_lines: &ReadStorage<'a, SymbolicLine>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+) -> Option<Endpoints> {
+  let (p1, p2) = match sym_lines.get(line_ent)? {
+    SymbolicLine::Straight(p1, p2) | SymbolicLine::Ray(p1, p2) | SymbolicLine::Segment(p1, p2) => (*p1, *p2),
+    _ => return None,
+  };
+  match (sym_points.get(p1)?, sym_points.get(p2)?) {
+    (SymbolicPoint::Free(pos1), SymbolicPoint::Free(pos2)) => Some([(p1, *pos1), (p2, *pos2)]),
+    _ => None,
+  }
+}
+
+/// Updates moving both of the endpoints by `delta` from where they were when the drag began
+pub fn drag_updates<'a>(
+  endpoints: Endpoints,
+  delta: VirtualPosition,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+) -> Vec<(Entity, SymbolicPoint, SymbolicPoint)> {
+  endpoints
+    .iter()
+    .filter_map(|(ent, position)| {
+      sym_points
+        .get(*ent)
+        .map(|old_sym_point| (*ent, *old_sym_point, SymbolicPoint::Free(*position + delta)))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  fn world_with_segment(p2: SymbolicPoint) -> (World, Entity, Entity, Entity) {
+    let mut world = World::new();
+    world.register::<SymbolicPoint>();
+    world.register::<SymbolicLine>();
+    let p1_ent = world
+      .create_entity()
+      .with(SymbolicPoint::Free(vec2![0., 0.].into()))
+      .build();
+    let p2_ent = world.create_entity().with(p2).build();
+    let line_ent = world
+      .create_entity()
+      .with(SymbolicLine::Segment(p1_ent, p2_ent))
+      .build();
+    (world, p1_ent, p2_ent, line_ent)
+  }
+
+  fn position(sym_point: SymbolicPoint) -> Vector2 {
+    match sym_point {
+      SymbolicPoint::Free(pos) => pos.0,
+      _ => panic!("Expected a free point"),
+    }
+  }
+
+  #[test]
+  fn test_move_segment_body_moves_both_endpoints() {
+    let (world, p1_ent, p2_ent, line_ent) = world_with_segment(SymbolicPoint::Free(vec2![3., 1.].into()));
+    let sym_lines = world.read_storage::<SymbolicLine>();
+    let sym_points = world.read_storage::<SymbolicPoint>();
+    let endpoints = line_drag_endpoints(line_ent, &sym_lines, &sym_points).unwrap();
+
+    let updates = drag_updates(endpoints, vec2![2., -1.].into(), &sym_points);
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0].0, p1_ent);
+    assert_eq!(updates[1].0, p2_ent);
+    for (_, old_sym_point, new_sym_point) in updates {
+      assert_eq!(position(new_sym_point) - position(old_sym_point), vec2![2., -1.]);
+    }
+  }
+
+  #[test]
+  fn test_move_line_body_refuses_constrained_endpoint() {
+    let (world, p1_ent, _, line_ent) = world_with_segment(SymbolicPoint::Fixed(vec2![3., 1.].into()));
+    let sym_lines = world.read_storage::<SymbolicLine>();
+    let sym_points = world.read_storage::<SymbolicPoint>();
+    assert!(line_drag_endpoints(line_ent, &sym_lines, &sym_points).is_none());
+
+    // Points are not lines and can't be dragged as such
+    assert!(line_drag_endpoints(p1_ent, &sym_lines, &sym_points).is_none());
+  }
+}
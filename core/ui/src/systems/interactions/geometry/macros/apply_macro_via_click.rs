@@ -0,0 +1,145 @@
+use crate::{events::*, resources::*, utilities::hitting_object};
+use core_lib::{
+  components::{screen_shapes::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+/// With the tool of a macro, every clicked point, line or circle becomes the next input of the
+/// macro when it is of the expected kind. The macro is applied once it has all its inputs, which
+/// stay selected in the meantime. Press `Escape` to start over
+pub struct ApplyMacroViaClick {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  macro_id: Option<MacroId>,
+  inputs: Vec<Entity>,
+}
+
+impl Default for ApplyMacroViaClick {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+      macro_id: None,
+      inputs: vec![],
+    }
+  }
+}
+
+impl<'a> System<'a> for ApplyMacroViaClick {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Read<'a, EntityQuadtree>,
+    Read<'a, Viewport>,
+    Read<'a, MacroRegistry>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicCircle>,
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      input_state,
+      key_map,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      entity_quadtree,
+      viewport,
+      macro_registry,
+      mut command_event_channel,
+      sym_points,
+      sym_lines,
+      sym_circles,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        self.inputs.clear();
+        match event {
+          ToolChangeEvent(Tool::Macro(id)) => {
+            self.macro_id = Some(*id);
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            self.macro_id = None;
+            self.mouse_event_reader = None;
+          }
+        }
+      }
+    }
+
+    if key_map.just_triggered(Action::Cancel, &input_state) {
+      self.inputs.clear();
+    }
+
+    let input_kinds = match self.macro_id.and_then(|id| macro_registry.get(id)) {
+      Some(construction_macro) => construction_macro.input_kinds(),
+      None => return,
+    };
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        if let MouseEvent::Click(position) = event {
+          let maybe_entity = hitting_object(
+            *position,
+            &entity_quadtree,
+            &viewport,
+            &scrn_points,
+            &sym_points,
+            &scrn_lines,
+            &scrn_circles,
+            SELECT_DIST_THRES,
+          );
+          let entity = match maybe_entity {
+            Some(entity) => entity,
+            None => continue,
+          };
+          let kind = if sym_points.contains(entity) {
+            GeometryKind::Point
+          } else if sym_lines.contains(entity) {
+            GeometryKind::Line
+          } else if sym_circles.contains(entity) {
+            GeometryKind::Circle
+          } else {
+            continue;
+          };
+          if input_kinds.get(self.inputs.len()) != Some(&kind) || self.inputs.contains(&entity) {
+            continue;
+          }
+
+          self.inputs.push(entity);
+          let command = if self.inputs.len() == input_kinds.len() {
+            Command::ApplyMacro(self.macro_id.unwrap(), self.inputs.drain(..).collect())
+          } else {
+            Command::Select(SelectEvent::Select(entity))
+          };
+          command_event_channel.single_write(CommandEvent {
+            command,
+            event_id: None,
+          });
+        }
+      }
+    }
+  }
+}
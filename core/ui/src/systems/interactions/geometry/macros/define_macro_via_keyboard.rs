@@ -0,0 +1,26 @@
+use crate::resources::*;
+use core_lib::{events::*, resources::MacroRegistry};
+use specs::prelude::*;
+
+/// Cmd + Shift + M records the selected construction as a new macro, named after how many there
+/// are, Cmd + M alone being taken by the mid point
+#[derive(Default)]
+pub struct DefineMacroViaKeyboard;
+
+impl<'a> System<'a> for DefineMacroViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, MacroRegistry>,
+    Write<'a, CommandEventChannel>,
+  );
+
+  fn run(&mut self, (input_state, key_map, macro_registry, mut command_event_channel): Self::SystemData) {
+    if key_map.just_triggered(Action::DefineMacro, &input_state) {
+      command_event_channel.single_write(CommandEvent {
+        command: Command::DefineMacroFromSelection(format!("Macro {}", macro_registry.len() + 1)),
+        event_id: None,
+      });
+    }
+  }
+}
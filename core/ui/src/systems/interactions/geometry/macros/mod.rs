@@ -0,0 +1,5 @@
+mod apply_macro_via_click;
+mod define_macro_via_keyboard;
+
+pub use apply_macro_via_click::*;
+pub use define_macro_via_keyboard::*;
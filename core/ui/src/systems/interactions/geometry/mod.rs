@@ -1,7 +1,19 @@
 pub mod circle;
+pub mod freehand;
 pub mod line;
+pub mod macros;
 pub mod point;
+pub mod polygon;
+pub mod slider;
+pub mod text;
+pub mod underlay;
 
+mod reflect_selection_via_click;
 mod remove_selected_via_keyboard;
+mod rotate_selection_via_drag;
+mod scale_selection_via_drag;
 
+pub use reflect_selection_via_click::*;
 pub use remove_selected_via_keyboard::*;
+pub use rotate_selection_via_drag::*;
+pub use scale_selection_via_drag::*;
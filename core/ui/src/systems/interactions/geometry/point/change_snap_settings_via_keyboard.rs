@@ -0,0 +1,36 @@
+use crate::resources::*;
+use specs::prelude::*;
+
+static SNAP_RADIUS_STEP: f64 = 2.0; // Pixel
+
+/// Cmd+1 to Cmd+6 turn snapping to points, midpoints, intersections, lines, circles and the grid on
+/// and off, while Cmd+= and Cmd+- widen and narrow the snap radius
+#[derive(Default)]
+pub struct ChangeSnapSettingsViaKeyboard;
+
+impl<'a> System<'a> for ChangeSnapSettingsViaKeyboard {
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, SnapSettings>);
+
+  fn run(&mut self, (input_state, key_map, mut snap_settings): Self::SystemData) {
+    let categories = [
+      (Action::ToggleSnapToPoints, SnapCategory::Point),
+      (Action::ToggleSnapToMidPoints, SnapCategory::MidPoint),
+      (Action::ToggleSnapToIntersections, SnapCategory::Intersection),
+      (Action::ToggleSnapOnLines, SnapCategory::OnLine),
+      (Action::ToggleSnapOnCircles, SnapCategory::OnCircle),
+      (Action::ToggleSnapToGrid, SnapCategory::Grid),
+    ];
+    for (action, category) in &categories {
+      if key_map.just_triggered(*action, &input_state) {
+        snap_settings.toggle(*category);
+      }
+    }
+    if key_map.just_triggered(Action::IncreaseSnapRadius, &input_state) {
+      let radius = snap_settings.radius() + SNAP_RADIUS_STEP;
+      snap_settings.set_radius(radius);
+    } else if key_map.just_triggered(Action::DecreaseSnapRadius, &input_state) {
+      let radius = snap_settings.radius() - SNAP_RADIUS_STEP;
+      snap_settings.set_radius(radius);
+    }
+  }
+}
@@ -15,6 +15,7 @@ impl Default for ClickOnExistingPoint {
 
 impl<'a> System<'a> for ClickOnExistingPoint {
   type SystemData = (
+    Read<'a, InputState>,
     Read<'a, MaybeSnapPoint>,
     Read<'a, MouseEventChannel>,
     Write<'a, ActivePointEventChannel>,
@@ -25,11 +26,15 @@ impl<'a> System<'a> for ClickOnExistingPoint {
     self.mouse_event_reader = Some(world.fetch_mut::<MouseEventChannel>().register_reader());
   }
 
-  fn run(&mut self, (maybe_snap_point, mouse_event_channel, mut active_point_event_channel): Self::SystemData) {
+  fn run(
+    &mut self,
+    (input_state, maybe_snap_point, mouse_event_channel, mut active_point_event_channel): Self::SystemData,
+  ) {
     if let Some(reader) = &mut self.mouse_event_reader {
       for event in mouse_event_channel.read(reader) {
         match event {
-          MouseEvent::MouseDown(_) => {
+          // With command held a new free point is created instead of reusing the existing one
+          MouseEvent::MouseDown(_) if !input_state.keyboard.is_command_activated() => {
             if let Some(SnapPoint { symbol, .. }) = maybe_snap_point.get() {
               match symbol {
                 SnapPointType::SnapOnPoint(p_ent) => active_point_event_channel.single_write(ActivePointEvent(p_ent)),
@@ -6,13 +6,10 @@ use specs::prelude::*;
 pub struct CreateMidpointViaKeyboard;
 
 impl<'a> System<'a> for CreateMidpointViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, CommandEventChannel>);
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
 
-  fn run(&mut self, (input_state, mut command_event_channel): Self::SystemData) {
-    let cmd = input_state.keyboard.is_command_activated();
-    let no_shift = !input_state.keyboard.is_shift_activated();
-    let m = input_state.keyboard.just_activated(Key::M);
-    if cmd && no_shift && m {
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    if key_map.just_triggered(Action::CreateMidpoint, &input_state) {
       command_event_channel.single_write(CommandEvent {
         command: Command::PointInsert(InsertPointEvent::InsertMidPointFromSelection),
         event_id: None,
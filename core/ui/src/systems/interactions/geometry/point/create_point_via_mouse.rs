@@ -18,6 +18,7 @@ impl Default for CreatePointViaMouse {
 
 impl<'a> System<'a> for CreatePointViaMouse {
   type SystemData = (
+    Read<'a, InputState>,
     Read<'a, MaybeSnapPoint>,
     Read<'a, Viewport>,
     Read<'a, ToolChangeEventChannel>,
@@ -33,6 +34,7 @@ impl<'a> System<'a> for CreatePointViaMouse {
   fn run(
     &mut self,
     (
+      input_state,
       maybe_snap_point,
       viewport,
       tool_change_event_channel,
@@ -46,8 +48,7 @@ impl<'a> System<'a> for CreatePointViaMouse {
           if self.mouse_event_reader.is_none() {
             self.mouse_event_reader = Some(mouse_event_channel.register_reader());
           }
-        } else if let Some(reader) = &mut self.mouse_event_reader {
-          std::mem::drop(reader);
+        } else {
           self.mouse_event_reader = None;
         }
       }
@@ -56,22 +57,13 @@ impl<'a> System<'a> for CreatePointViaMouse {
     if let Some(reader) = &mut self.mouse_event_reader {
       for event in mouse_event_channel.read(reader) {
         match event {
-          MouseEvent::MouseDown(_) => {
-            if let Some(SnapPoint { position, symbol }) = maybe_snap_point.get() {
-              let maybe_sym_point = match symbol {
-                SnapPointType::NotSnapped => Some(SymbolicPoint::Free(position.to_virtual(&*viewport))),
-                SnapPointType::SnapOnLine(l_ent, t) => Some(SymbolicPoint::OnLine(l_ent, t.into())),
-                SnapPointType::SnapOnLineLineIntersection(l1_ent, l2_ent) => {
-                  Some(SymbolicPoint::LineLineIntersect(l1_ent, l2_ent))
-                }
-                SnapPointType::SnapOnCircle(c_ent, theta) => Some(SymbolicPoint::OnCircle(c_ent, theta)),
-                SnapPointType::SnapOnCircleLineIntersection(c_ent, l_ent, id) => {
-                  Some(SymbolicPoint::CircleLineIntersect(c_ent, l_ent, id))
-                }
-                SnapPointType::SnapOnCircleCircleIntersection(c1_ent, c2_ent, id) => {
-                  Some(SymbolicPoint::CircleCircleIntersect(c1_ent, c2_ent, id))
-                }
-                SnapPointType::SnapOnPoint(_) => None,
+          MouseEvent::MouseDown(mouse_position) => {
+            if let Some(snap_point) = maybe_snap_point.get() {
+              // Holding command places a free point right under the cursor, ignoring the snap
+              let maybe_sym_point = if input_state.keyboard.is_command_activated() {
+                Some(SymbolicPoint::Free(mouse_position.to_virtual(&*viewport)))
+              } else {
+                snapped_sym_point(snap_point, &*viewport)
               };
               if let Some(sym_point) = maybe_sym_point {
                 command_event_channel.single_write(CommandEvent {
@@ -87,3 +79,87 @@ impl<'a> System<'a> for CreatePointViaMouse {
     }
   }
 }
+
+/// The point to insert for a snap point. Snapping on an existing point inserts nothing, the
+/// existing point is reused instead
+pub fn snapped_sym_point(SnapPoint { position, symbol }: SnapPoint, viewport: &Viewport) -> Option<SymbolicPoint> {
+  match symbol {
+    SnapPointType::NotSnapped => Some(SymbolicPoint::Free(position.to_virtual(viewport))),
+    SnapPointType::SnapOnLine(l_ent, t) => Some(SymbolicPoint::OnLine(l_ent, t.into())),
+    SnapPointType::SnapOnLineLineIntersection(l1_ent, l2_ent) => Some(SymbolicPoint::LineLineIntersect(l1_ent, l2_ent)),
+    SnapPointType::SnapOnCircle(c_ent, theta) => Some(SymbolicPoint::OnCircle(c_ent, theta)),
+    SnapPointType::SnapOnConic(c_ent, t) => Some(SymbolicPoint::OnConic(c_ent, t)),
+    SnapPointType::SnapOnCircleLineIntersection(c_ent, l_ent, id) => {
+      Some(SymbolicPoint::CircleLineIntersect(c_ent, l_ent, id))
+    }
+    SnapPointType::SnapOnCircleCircleIntersection(c1_ent, c2_ent, id) => {
+      Some(SymbolicPoint::CircleCircleIntersect(c1_ent, c2_ent, id))
+    }
+    SnapPointType::SnapOnArcLineIntersection(a_ent, l_ent, id) => {
+      Some(SymbolicPoint::ArcLineIntersect(a_ent, l_ent, id))
+    }
+    SnapPointType::SnapOnArcCircleIntersection(a_ent, c_ent, id) => {
+      Some(SymbolicPoint::ArcCircleIntersect(a_ent, c_ent, id))
+    }
+    SnapPointType::SnapOnGrid(position) => Some(SymbolicPoint::Free(position)),
+    SnapPointType::SnapOnPoint(_) => None,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::{math::*, utilities::*};
+
+  fn inserted_on_mouse_down(command_held: bool) -> (SymbolicPoint, ScreenPosition, Entity) {
+    let mut world = World::new();
+    let mut system = CreatePointViaMouse::default();
+    System::setup(&mut system, &mut world);
+    let mut reader = world.fetch_mut::<CommandEventChannel>().register_reader();
+
+    let line_ent = world.create_entity().build();
+    let mouse_position: ScreenPosition = vec2![100., 100.].into();
+    world.fetch_mut::<MaybeSnapPoint>().set(SnapPoint {
+      position: vec2![100., 104.].into(),
+      symbol: SnapPointType::SnapOnLine(line_ent, 0.5),
+    });
+    if command_held {
+      let mut input_state = world.fetch_mut::<InputState>();
+      input_state.keyboard.set(Key::LCtrl, true);
+      input_state.keyboard.set(Key::LCommand, true);
+    }
+    world
+      .fetch_mut::<MouseEventChannel>()
+      .single_write(MouseEvent::MouseDown(mouse_position));
+    system.run_now(&world);
+
+    let commands = world
+      .fetch::<CommandEventChannel>()
+      .read(&mut reader)
+      .map(|event| event.command.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(commands.len(), 1);
+    match commands[0] {
+      Command::PointInsert(InsertPointEvent::InsertPoint(sym_point)) => (sym_point, mouse_position, line_ent),
+      _ => panic!("Expected a point insertion"),
+    }
+  }
+
+  #[test]
+  fn test_create_point_uses_snap_point() {
+    let (sym_point, _, line_ent) = inserted_on_mouse_down(false);
+    match sym_point {
+      SymbolicPoint::OnLine(ent, _) => assert_eq!(ent, line_ent),
+      _ => panic!("Expected a point on the snapped line"),
+    }
+  }
+
+  #[test]
+  fn test_create_point_with_command_held_ignores_snap_point() {
+    let (sym_point, mouse_position, _) = inserted_on_mouse_down(true);
+    match sym_point {
+      SymbolicPoint::Free(position) => assert_eq!(position.0, mouse_position.to_virtual(&Viewport::default()).0),
+      _ => panic!("Expected a free point at the cursor"),
+    }
+  }
+}
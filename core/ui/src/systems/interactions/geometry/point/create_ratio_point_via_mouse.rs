@@ -0,0 +1,132 @@
+use crate::{events::*, resources::*, utilities::hitting_object};
+use core_lib::{
+  components::{screen_shapes::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+static RATIO_PER_SCROLL: f64 = 0.05; // Change of the ratio for a scroll of one unit
+
+/// With the ratio tool, clicking on two points starts placing a point dividing the way between
+/// them. Scrolling then adjusts the ratio and a last click places the point
+pub struct CreateRatioPointViaMouse {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+}
+
+impl Default for CreateRatioPointViaMouse {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for CreateRatioPointViaMouse {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Read<'a, EntityQuadtree>,
+    Read<'a, Viewport>,
+    Write<'a, RatioPoint>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      input_state,
+      key_map,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      entity_quadtree,
+      viewport,
+      mut ratio_point,
+      mut command_event_channel,
+      sym_points,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is ratio
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Ratio) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            if self.mouse_event_reader.take().is_some() {
+              ratio_point.clear();
+            }
+          }
+        }
+      }
+    }
+
+    if key_map.just_triggered(Action::Cancel, &input_state) {
+      ratio_point.clear();
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      if ratio_point.get_points().is_some() && input_state.rel_scroll.y != 0.0 {
+        let ratio = ratio_point.ratio + input_state.rel_scroll.y * RATIO_PER_SCROLL;
+        ratio_point.ratio = ratio.max(0.0).min(1.0);
+      }
+
+      for event in mouse_event_channel.read(reader_id) {
+        if let MouseEvent::Click(position) = event {
+          if let Some((p1, p2)) = ratio_point.get_points() {
+            command_event_channel.single_write(CommandEvent {
+              command: Command::PointInsert(InsertPointEvent::InsertPoint(SymbolicPoint::Ratio(
+                p1,
+                p2,
+                ratio_point.ratio,
+              ))),
+              event_id: None,
+            });
+            ratio_point.clear();
+            continue;
+          }
+
+          let maybe_point = hitting_object(
+            *position,
+            &entity_quadtree,
+            &viewport,
+            &scrn_points,
+            &sym_points,
+            &scrn_lines,
+            &scrn_circles,
+            SELECT_DIST_THRES,
+          )
+          .filter(|ent| sym_points.get(*ent).is_some());
+          if let Some(ent) = maybe_point {
+            match ratio_point.maybe_first_point {
+              Some(first_point) if first_point != ent => ratio_point.maybe_second_point = Some(ent),
+              Some(_) => (),
+              None => ratio_point.maybe_first_point = Some(ent),
+            }
+          }
+        }
+      }
+    }
+  }
+}
@@ -1,6 +1,6 @@
 use crate::{events::*, resources::*, utilities::hitting_object};
 use core_lib::{
-  components::{screen_shapes::*, symbolics::*},
+  components::{markers::*, screen_shapes::*, symbolics::*},
   events::*,
   math::*,
   resources::*,
@@ -9,6 +9,7 @@ use core_lib::{
 use specs::prelude::*;
 
 static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+static GLUE_DIST_THRES: ScreenScalar = ScreenScalar(8.0); // Pixel
 
 pub struct MovePointViaDrag {
   tool_change_event_reader: Option<ToolChangeEventReader>,
@@ -34,12 +35,18 @@ impl<'a> System<'a> for MovePointViaDrag {
     Read<'a, ToolChangeEventChannel>,
     Write<'a, MouseEventChannel>,
     Read<'a, SpatialEntityMap>,
+    Read<'a, EntityQuadtree>,
     Read<'a, Viewport>,
+    Read<'a, LayerManager>,
+    Read<'a, DependencyGraph>,
     Write<'a, CommandEventChannel>,
+    Write<'a, ErrorEventChannel>,
+    ReadStorage<'a, Layer>,
     ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, ScreenPoint>,
     ReadStorage<'a, ScreenLine>,
     ReadStorage<'a, ScreenCircle>,
+    ReadStorage<'a, ScreenConic>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -55,12 +62,18 @@ impl<'a> System<'a> for MovePointViaDrag {
       tool_change_event_channel,
       mut mouse_event_channel,
       spatial_entity_map,
+      entity_quadtree,
       viewport,
+      layer_manager,
+      dependency_graph,
       mut command_event_channel,
+      mut error_event_channel,
+      layers,
       sym_points,
       scrn_points,
       scrn_lines,
       scrn_circles,
+      scrn_conics,
     ): Self::SystemData,
   ) {
     // First use tool change to setup mouse event reader.
@@ -72,12 +85,7 @@ impl<'a> System<'a> for MovePointViaDrag {
           ToolChangeEvent(Tool::Select) => {
             self.mouse_event_reader = Some(mouse_event_channel.register_reader());
           }
-          _ => {
-            if let Some(reader_id) = &mut self.mouse_event_reader {
-              drop(reader_id);
-              self.mouse_event_reader = None;
-            }
-          }
+          _ => self.mouse_event_reader = None,
         }
       }
     }
@@ -89,13 +97,17 @@ impl<'a> System<'a> for MovePointViaDrag {
             if !input_state.keyboard.is_shift_activated() {
               if let Some(entity) = hitting_object(
                 *start_position,
-                &spatial_entity_map,
+                &entity_quadtree,
+                &viewport,
                 &scrn_points,
+                &sym_points,
                 &scrn_lines,
                 &scrn_circles,
                 SELECT_DIST_THRES,
               ) {
-                if let Some(sym_point) = sym_points.get(entity) {
+                if sym_points.get(entity).is_some() && layer_manager.is_locked(layers.get(entity)) {
+                  error_event_channel.single_write(ErrorEvent::LayerLocked(entity));
+                } else if let Some(sym_point) = sym_points.get(entity) {
                   self.dragging_point = Some((entity, *sym_point));
                   self.start_position = Some(*start_position);
 
@@ -111,9 +123,14 @@ impl<'a> System<'a> for MovePointViaDrag {
           MouseEvent::DragMove(_, curr_position) => match self.dragging_point {
             Some((ent, _)) => {
               if let Some(old_sym_point) = sym_points.get(ent) {
-                if let Some(new_sym_point) =
-                  get_update(*old_sym_point, *curr_position, &viewport, &scrn_lines, &scrn_circles)
-                {
+                if let Some(new_sym_point) = get_update(
+                  *old_sym_point,
+                  *curr_position,
+                  &viewport,
+                  &scrn_lines,
+                  &scrn_circles,
+                  &scrn_conics,
+                ) {
                   command_event_channel.single_write(CommandEvent {
                     command: Command::Update(UpdateEvent::UpdatePoint(ent, *old_sym_point, new_sym_point)),
                     event_id: None,
@@ -126,9 +143,42 @@ impl<'a> System<'a> for MovePointViaDrag {
           MouseEvent::DragEnd(curr_position) => {
             match self.dragging_point {
               Some((ent, old_sym_point)) => {
-                if let Some(new_sym_point) =
-                  get_update(old_sym_point, *curr_position, &viewport, &scrn_lines, &scrn_circles)
-                {
+                // Holding alt glues a free point onto the curve it is dropped on, as one undo step
+                let maybe_glued_sym_point = match old_sym_point {
+                  SymbolicPoint::Free(_) if input_state.keyboard.is_alt_activated() => glued_sym_point(
+                    ent,
+                    *curr_position,
+                    &spatial_entity_map,
+                    &viewport,
+                    &dependency_graph,
+                    &scrn_lines,
+                    &scrn_circles,
+                    &scrn_conics,
+                  ),
+                  _ => None,
+                };
+                if let Some(glued_sym_point) = maybe_glued_sym_point {
+                  let commands = vec![
+                    Command::BeginTransaction("Reparent".to_string()),
+                    // Solved again at once, so that the point lands right on the curve
+                    Command::Update(UpdateEvent::UpdatePoint(ent, old_sym_point, glued_sym_point)),
+                    Command::Update(UpdateEvent::UpdatePointEnd(ent, old_sym_point, glued_sym_point)),
+                    Command::EndTransaction,
+                  ];
+                  for command in commands {
+                    command_event_channel.single_write(CommandEvent {
+                      command,
+                      event_id: None,
+                    });
+                  }
+                } else if let Some(new_sym_point) = get_update(
+                  old_sym_point,
+                  *curr_position,
+                  &viewport,
+                  &scrn_lines,
+                  &scrn_circles,
+                  &scrn_conics,
+                ) {
                   command_event_channel.single_write(CommandEvent {
                     command: Command::Update(UpdateEvent::UpdatePointEnd(ent, old_sym_point, new_sym_point)),
                     event_id: None,
@@ -152,6 +202,7 @@ fn get_update<'a>(
   viewport: &Viewport,
   scrn_lines: &ReadStorage<'a, ScreenLine>,
   scrn_circles: &ReadStorage<'a, ScreenCircle>,
+  scrn_conics: &ReadStorage<'a, ScreenConic>,
 ) -> Option<SymbolicPoint> {
   match old_sym_point {
     SymbolicPoint::Free(_) => {
@@ -177,6 +228,116 @@ fn get_update<'a>(
         None
       }
     }
+    SymbolicPoint::OnConic(c_ent, _) => {
+      if let Some(conic) = scrn_conics.get(c_ent) {
+        let projected_position = conic.get_closest_point(curr_position);
+        Some(SymbolicPoint::OnConic(c_ent, -conic.parameter_of(projected_position)))
+      } else {
+        None
+      }
+    }
     _ => None,
   }
 }
+
+/// The point glued onto the closest line, circle or conic near `position`, at the parameter of the
+/// position projected onto it. The curves depending on the point itself are left out, they would
+/// make it depend on itself
+fn glued_sym_point<'a>(
+  ent: Entity,
+  position: ScreenPosition,
+  spatial_entity_map: &SpatialEntityMap,
+  viewport: &Viewport,
+  dependency_graph: &DependencyGraph,
+  scrn_lines: &ReadStorage<'a, ScreenLine>,
+  scrn_circles: &ReadStorage<'a, ScreenCircle>,
+  scrn_conics: &ReadStorage<'a, ScreenConic>,
+) -> Option<SymbolicPoint> {
+  let dependents = dependency_graph.get_all_dependents(&ent);
+  let mut closest: Option<(ScreenScalar, SymbolicPoint)> = None;
+  let near = spatial_entity_map.get_entities_near_point(
+    position.to_virtual(viewport).into(),
+    GLUE_DIST_THRES.to_virtual(viewport).into(),
+  );
+  for target in near {
+    if target == ent || dependents.contains(&target) {
+      continue;
+    }
+    let candidate = if let Some(line) = scrn_lines.get(target) {
+      let closest_point = line.get_closest_point(position);
+      let t = line.rel_t_of_point(closest_point);
+      Some((closest_point, SymbolicPoint::OnLine(target, t.into())))
+    } else if let Some(circle) = scrn_circles.get(target) {
+      let projected_position = position.project(*circle);
+      let p_to_cen: Vector2 = (projected_position - circle.center).into();
+      Some((
+        projected_position,
+        SymbolicPoint::OnCircle(target, -p_to_cen.y.atan2(p_to_cen.x)),
+      ))
+    } else if let Some(conic) = scrn_conics.get(target) {
+      let projected_position = conic.get_closest_point(position);
+      Some((
+        projected_position,
+        SymbolicPoint::OnConic(target, -conic.parameter_of(projected_position)),
+      ))
+    } else {
+      None
+    };
+    if let Some((on_curve, sym_point)) = candidate {
+      let dist = (on_curve - position).magnitude();
+      if dist < GLUE_DIST_THRES && closest.is_none_or(|(closest_dist, _)| dist < closest_dist) {
+        closest = Some((dist, sym_point));
+      }
+    }
+  }
+  closest.map(|(_, sym_point)| sym_point)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_glue_onto_the_closest_curve() {
+    let mut world = World::new();
+    world.register::<ScreenLine>();
+    world.register::<ScreenCircle>();
+    world.register::<ScreenConic>();
+    let point = world.create_entity().build();
+    let scrn_line = ScreenLine {
+      from: vec2![0., 100.].into(),
+      to: vec2![200., 100.].into(),
+      line_type: LineType::Segment,
+    };
+    let line = world.create_entity().with(scrn_line).build();
+    let viewport = Viewport::default();
+    let mut spatial_entity_map = SpatialEntityMap::default();
+    spatial_entity_map.insert_line(line, scrn_line.to_virtual(&viewport).into());
+    let mut dependency_graph = DependencyGraph::default();
+
+    let glue = |position: Vector2, dependency_graph: &DependencyGraph| {
+      glued_sym_point(
+        point,
+        position.into(),
+        &spatial_entity_map,
+        &viewport,
+        dependency_graph,
+        &world.read_storage(),
+        &world.read_storage(),
+        &world.read_storage(),
+      )
+    };
+    match glue(vec2![50., 104.], &dependency_graph) {
+      Some(SymbolicPoint::OnLine(ent, t)) => {
+        assert_eq!(ent, line);
+        assert!((Into::<f64>::into(t) - 0.25).abs() < 1e-9);
+      }
+      other => panic!("Expected a point on the line, got {:?}", other),
+    }
+    assert!(glue(vec2![50., 120.], &dependency_graph).is_none());
+
+    // A line through the point cannot carry it
+    dependency_graph.add(&point, &line);
+    assert!(glue(vec2![50., 104.], &dependency_graph).is_none());
+  }
+}
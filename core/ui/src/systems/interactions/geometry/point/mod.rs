@@ -1,13 +1,21 @@
+mod change_snap_settings_via_keyboard;
 mod click_on_existing_point;
+mod create_intersections_via_keyboard;
 mod create_midpoint_via_keyboard;
 mod create_point_via_mouse;
+mod create_ratio_point_via_mouse;
 mod drag_point_via_mouse;
 mod emit_active_point_event;
+mod redefine_point_via_keyboard;
 mod snap_point_via_mouse;
 
+pub use change_snap_settings_via_keyboard::*;
 pub use click_on_existing_point::*;
+pub use create_intersections_via_keyboard::*;
 pub use create_midpoint_via_keyboard::*;
 pub use create_point_via_mouse::*;
+pub use create_ratio_point_via_mouse::*;
 pub use drag_point_via_mouse::*;
 pub use emit_active_point_event::*;
+pub use redefine_point_via_keyboard::*;
 pub use snap_point_via_mouse::*;
@@ -9,10 +9,12 @@ use itertools::Itertools;
 use specs::prelude::*;
 
 // In actual space
-static SNAP_TO_POINT_THRES: ScreenScalar = ScreenScalar(12.0);
-static SNAP_TO_LINE_THRES: ScreenScalar = ScreenScalar(8.0);
-static SNAP_TO_CIRCLE_THRES: ScreenScalar = ScreenScalar(8.0);
-static SNAP_TO_INTERSECTION_THRES: ScreenScalar = ScreenScalar(15.0);
+static REFERENCE_LINE_THRES: ScreenScalar = ScreenScalar(40.0);
+static RELATIVE_ANGLE_THRES: f64 = 3.0; // Degree
+
+// Angles relative to a reference line that a new line snaps to, in degrees. Both sides of the
+// reference line are covered since a line direction is only defined up to 180 degrees
+static RELATIVE_ANGLES: [f64; 9] = [0.0, 30.0, 45.0, 60.0, 90.0, 120.0, 135.0, 150.0, 180.0];
 
 #[derive(Default)]
 pub struct SnapPointViaMouse;
@@ -21,25 +23,38 @@ impl<'a> System<'a> for SnapPointViaMouse {
   type SystemData = (
     Read<'a, InputState>,
     Read<'a, ToolState>,
-    Read<'a, SpatialEntityMap>,
+    Read<'a, SnapSettings>,
+    Read<'a, GridSettings>,
+    Read<'a, Viewport>,
+    Read<'a, EntityQuadtree>,
     Write<'a, MaybeSnapPoint>,
+    Write<'a, SnapLine>,
     ReadStorage<'a, ScreenPoint>,
     ReadStorage<'a, ScreenLine>,
     ReadStorage<'a, ScreenCircle>,
+    ReadStorage<'a, ScreenArc>,
+    ReadStorage<'a, ScreenConic>,
   );
 
   fn run(
     &mut self,
     (
-            input_state,
-            tool_state,
-            spatial_entity_map,
-            mut maybe_snap_point,
-            scrn_points,
-            scrn_lines,
-            scrn_circles,
-        ): Self::SystemData,
+      input_state,
+      tool_state,
+      snap_settings,
+      grid_settings,
+      viewport,
+      entity_quadtree,
+      mut maybe_snap_point,
+      mut snap_line,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+      scrn_arcs,
+      scrn_conics,
+    ): Self::SystemData,
   ) {
+    snap_line.maybe_relative_angle = None;
     if tool_state.need_snap_point() {
       let mouse_pos = input_state.mouse_abs_pos;
 
@@ -49,26 +64,54 @@ impl<'a> System<'a> for SnapPointViaMouse {
         symbol: SnapPointType::NotSnapped,
       });
 
-      // Then get the potential neighbors
-      let neighbor_entities = spatial_entity_map.get_entities_near_point(mouse_pos.into(), SNAP_TO_POINT_THRES.into());
+      // The reach of every category follows the snap radius
+      let snap_to_point_thres = snap_settings.threshold(SnapCategory::Point);
+      let snap_to_mid_point_thres = snap_settings.threshold(SnapCategory::MidPoint);
+      let snap_to_line_thres = snap_settings.threshold(SnapCategory::OnLine);
+      let snap_to_circle_thres = snap_settings.threshold(SnapCategory::OnCircle);
+      let snap_to_intersection_thres = snap_settings.threshold(SnapCategory::Intersection);
+      let snap_to_grid_thres = snap_settings.threshold(SnapCategory::Grid);
+
+      // Then get the potential neighbors within the widest reach, the closest first. The quadtree
+      // is in virtual space
+      let reach = [
+        snap_to_point_thres,
+        snap_to_mid_point_thres,
+        snap_to_line_thres,
+        snap_to_circle_thres,
+        snap_to_intersection_thres,
+      ]
+      .iter()
+      .fold(
+        snap_to_point_thres,
+        |reach, thres| if *thres > reach { *thres } else { reach },
+      );
+      let neighbor_entities = entity_quadtree
+        .entities_within(
+          mouse_pos.to_virtual(&*viewport).into(),
+          reach.to_virtual(&*viewport).into(),
+        )
+        .into_iter()
+        .map(|(entity, _)| entity);
 
       let mut maybe_smallest_dist_to_point: Option<f64> = None;
       let mut maybe_snap_point_on_point = None;
-      let mut is_snapping_to_point = false;
       let mut closest_lines: Vec<(Entity, ScreenLine)> = vec![];
       let mut closest_circles: Vec<(Entity, ScreenCircle)> = vec![];
+      let mut closest_arcs: Vec<(Entity, ScreenArc)> = vec![];
       let mut maybe_smallest_dist_to_line: Option<f64> = None;
       let mut maybe_snap_point_on_line = None;
+      let mut maybe_smallest_dist_to_mid_point: Option<f64> = None;
+      let mut maybe_snap_point_on_mid_point = None;
       let mut maybe_smallest_dist_to_circle: Option<f64> = None;
       let mut maybe_snap_point_on_circle = None;
 
       // Loop through all the neighbor entities
       for entity in neighbor_entities {
         if let Some(p) = scrn_points.get(entity) {
-          let norm_dist = (*p - mouse_pos).magnitude() / SNAP_TO_POINT_THRES;
+          let norm_dist = (*p - mouse_pos).magnitude() / snap_to_point_thres;
           if norm_dist < 1.0 {
             if maybe_smallest_dist_to_point.is_none() || norm_dist < maybe_smallest_dist_to_point.unwrap() {
-              is_snapping_to_point = true;
               maybe_smallest_dist_to_point = Some(norm_dist);
 
               // Set the snap point to snap on point
@@ -82,11 +125,11 @@ impl<'a> System<'a> for SnapPointViaMouse {
           let l = *l;
           let closest_point = l.get_closest_point(mouse_pos);
           let dist = (closest_point - mouse_pos).magnitude();
-          if dist <= SNAP_TO_POINT_THRES {
+          if dist <= snap_to_point_thres {
             closest_lines.push((entity, l));
           }
-          let norm_dist = dist / SNAP_TO_LINE_THRES;
-          if norm_dist < 1.0 && !is_snapping_to_point {
+          let norm_dist = dist / snap_to_line_thres;
+          if norm_dist < 1.0 {
             let t = l.rel_t_of_point(closest_point);
             if maybe_smallest_dist_to_line.is_none() || norm_dist < maybe_smallest_dist_to_line.unwrap() {
               maybe_smallest_dist_to_line = Some(norm_dist);
@@ -98,15 +141,30 @@ impl<'a> System<'a> for SnapPointViaMouse {
               });
             }
           }
+
+          // The middle of a segment stays in the middle as a point on it, half way from its start
+          if l.line_type == LineType::Segment {
+            let mid_point = l.from + (l.to - l.from) * ScreenScalar(0.5);
+            let norm_dist = (mid_point - mouse_pos).magnitude() / snap_to_mid_point_thres;
+            if norm_dist < 1.0 {
+              if maybe_smallest_dist_to_mid_point.is_none() || norm_dist < maybe_smallest_dist_to_mid_point.unwrap() {
+                maybe_smallest_dist_to_mid_point = Some(norm_dist);
+                maybe_snap_point_on_mid_point = Some(SnapPoint {
+                  position: mid_point,
+                  symbol: SnapPointType::SnapOnLine(entity, 0.5),
+                });
+              }
+            }
+          }
         } else if let Some(c) = scrn_circles.get(entity) {
           let c = *c;
           let proj_point = mouse_pos.project(c);
           let dist = (proj_point - mouse_pos).magnitude();
-          if dist <= SNAP_TO_CIRCLE_THRES {
+          if dist <= snap_to_circle_thres {
             closest_circles.push((entity, c));
           }
-          let norm_dist = dist / SNAP_TO_CIRCLE_THRES;
-          if norm_dist < 1.0 && !is_snapping_to_point {
+          let norm_dist = dist / snap_to_circle_thres;
+          if norm_dist < 1.0 {
             let p_to_cen: Vector2 = (proj_point - c.center).into();
             let theta = -p_to_cen.y.atan2(p_to_cen.x);
             if maybe_smallest_dist_to_circle.is_none() || norm_dist < maybe_smallest_dist_to_circle.unwrap() {
@@ -117,19 +175,32 @@ impl<'a> System<'a> for SnapPointViaMouse {
               });
             }
           }
+        } else if let Some(c) = scrn_conics.get(entity) {
+          // Conics compete with the circles, the parameter is flipped back to virtual space
+          let proj_point = c.get_closest_point(mouse_pos);
+          let norm_dist = (proj_point - mouse_pos).magnitude() / snap_to_circle_thres;
+          if norm_dist < 1.0 {
+            if maybe_smallest_dist_to_circle.is_none() || norm_dist < maybe_smallest_dist_to_circle.unwrap() {
+              maybe_smallest_dist_to_circle = Some(norm_dist);
+              maybe_snap_point_on_circle = Some(SnapPoint {
+                position: proj_point,
+                symbol: SnapPointType::SnapOnConic(entity, -c.parameter_of(proj_point)),
+              });
+            }
+          }
+        } else if let Some(a) = scrn_arcs.get(entity) {
+          // Arcs are only used for intersections, there is no point on an arc
+          let a = *a;
+          if (a.get_closest_point(mouse_pos) - mouse_pos).magnitude() <= snap_to_circle_thres {
+            closest_arcs.push((entity, a));
+          }
         }
       }
 
-      // Weight snap on point higher than snap on line
-      if let Some(snap_point) = maybe_snap_point_on_point
-        .or(maybe_snap_point_on_line)
-        .or(maybe_snap_point_on_circle)
-      {
-        maybe_snap_point.set(snap_point)
-      }
-
       // Check if snapping to an intersection
-      if !is_snapping_to_point {
+      let maybe_smallest_dist_to_intersection: Option<f64>;
+      let mut maybe_snap_point_on_intersection = None;
+      {
         let mut maybe_smallest_dist = None;
         let mut has_line_line_itsct = false;
 
@@ -139,13 +210,13 @@ impl<'a> System<'a> for SnapPointViaMouse {
             let l1 = *l1;
             let l2 = *l2;
             if let Some(itsct) = l1.intersect(l2) {
-              let norm_dist = (mouse_pos - itsct).magnitude() / SNAP_TO_INTERSECTION_THRES;
+              let norm_dist = (mouse_pos - itsct).magnitude() / snap_to_intersection_thres;
               if norm_dist < 1.0 {
                 if maybe_smallest_dist.is_none() || norm_dist < maybe_smallest_dist.unwrap() {
                   maybe_smallest_dist = Some(norm_dist);
 
                   // Set the snap point to intersection
-                  maybe_snap_point.set(SnapPoint {
+                  maybe_snap_point_on_intersection = Some(SnapPoint {
                     position: itsct,
                     symbol: SnapPointType::SnapOnLineLineIntersection(*l1_ent, *l2_ent),
                   });
@@ -162,17 +233,44 @@ impl<'a> System<'a> for SnapPointViaMouse {
 
           for ((line_ent, line), (circle_ent, circle)) in closest_lines.iter().cartesian_product(&closest_circles) {
             let ci = line.intersect(*circle);
-            check_circle_intersection(mouse_pos, ci, maybe_smallest_dist.clone(), &mut |m| match m {
-              Some((p, norm_dist, ty)) => {
-                maybe_smallest_dist = Some(norm_dist);
-                maybe_snap_point.set(SnapPoint {
-                  position: p,
-                  symbol: SnapPointType::SnapOnCircleLineIntersection(*circle_ent, *line_ent, ty),
-                });
-                has_circle_line_itsct = true;
-              }
-              None => (),
-            });
+            check_circle_intersection(
+              mouse_pos,
+              snap_to_intersection_thres,
+              ci,
+              maybe_smallest_dist.clone(),
+              &mut |m| match m {
+                Some((p, norm_dist, ty)) => {
+                  maybe_smallest_dist = Some(norm_dist);
+                  maybe_snap_point_on_intersection = Some(SnapPoint {
+                    position: p,
+                    symbol: SnapPointType::SnapOnCircleLineIntersection(*circle_ent, *line_ent, ty),
+                  });
+                  has_circle_line_itsct = true;
+                }
+                None => (),
+              },
+            );
+          }
+
+          for ((line_ent, line), (arc_ent, arc)) in closest_lines.iter().cartesian_product(&closest_arcs) {
+            let ci = arc.intersect(*line);
+            check_circle_intersection(
+              mouse_pos,
+              snap_to_intersection_thres,
+              ci,
+              maybe_smallest_dist.clone(),
+              &mut |m| match m {
+                Some((p, norm_dist, ty)) => {
+                  maybe_smallest_dist = Some(norm_dist);
+                  maybe_snap_point_on_intersection = Some(SnapPoint {
+                    position: p,
+                    symbol: SnapPointType::SnapOnArcLineIntersection(*arc_ent, *line_ent, ty),
+                  });
+                  has_circle_line_itsct = true;
+                }
+                None => (),
+              },
+            );
           }
 
           if !has_circle_line_itsct {
@@ -184,12 +282,13 @@ impl<'a> System<'a> for SnapPointViaMouse {
                 // space answer, we reverse the result
                 check_circle_intersection(
                   mouse_pos,
+                  snap_to_intersection_thres,
                   c1.intersect(*c2).reverse(),
                   maybe_smallest_dist.clone(),
                   &mut |m| match m {
                     Some((p, norm_dist, ty)) => {
                       maybe_smallest_dist = Some(norm_dist);
-                      maybe_snap_point.set(SnapPoint {
+                      maybe_snap_point_on_intersection = Some(SnapPoint {
                         position: p,
                         symbol: SnapPointType::SnapOnCircleCircleIntersection(*c1_ent, *c2_ent, ty),
                       });
@@ -199,6 +298,112 @@ impl<'a> System<'a> for SnapPointViaMouse {
                 );
               }
             }
+
+            // Same as above, the intersection order is reversed between screen and virtual space
+            for ((circle_ent, circle), (arc_ent, arc)) in closest_circles.iter().cartesian_product(&closest_arcs) {
+              check_circle_intersection(
+                mouse_pos,
+                snap_to_intersection_thres,
+                arc.intersect(*circle).reverse(),
+                maybe_smallest_dist.clone(),
+                &mut |m| match m {
+                  Some((p, norm_dist, ty)) => {
+                    maybe_smallest_dist = Some(norm_dist);
+                    maybe_snap_point_on_intersection = Some(SnapPoint {
+                      position: p,
+                      symbol: SnapPointType::SnapOnArcCircleIntersection(*arc_ent, *circle_ent, ty),
+                    });
+                  }
+                  None => (),
+                },
+              );
+            }
+          }
+        }
+        maybe_smallest_dist_to_intersection = maybe_smallest_dist;
+      }
+
+      // The grid intersections are only offered when snapping to the grid is turned on
+      let mut maybe_dist_to_grid: Option<f64> = None;
+      let mut maybe_snap_point_on_grid = None;
+      if snap_settings.is_enabled(SnapCategory::Grid) {
+        let itsct = grid_settings.closest_intersection(mouse_pos.to_virtual(&*viewport), &*viewport);
+        let position = itsct.to_screen(&*viewport);
+        let norm_dist = (position - mouse_pos).magnitude() / snap_to_grid_thres;
+        if norm_dist < 1.0 {
+          maybe_dist_to_grid = Some(norm_dist);
+          maybe_snap_point_on_grid = Some(SnapPoint {
+            position,
+            symbol: SnapPointType::SnapOnGrid(itsct),
+          });
+        }
+      }
+
+      // Pick among the candidates within reach of the enabled categories according to the snap
+      // priority, along with how close they are
+      let candidates = vec![
+        (
+          SnapCategory::Point,
+          maybe_smallest_dist_to_point,
+          maybe_snap_point_on_point,
+        ),
+        (
+          SnapCategory::MidPoint,
+          maybe_smallest_dist_to_mid_point,
+          maybe_snap_point_on_mid_point,
+        ),
+        (
+          SnapCategory::Intersection,
+          maybe_smallest_dist_to_intersection,
+          maybe_snap_point_on_intersection,
+        ),
+        (
+          SnapCategory::OnLine,
+          maybe_smallest_dist_to_line,
+          maybe_snap_point_on_line,
+        ),
+        (
+          SnapCategory::OnCircle,
+          maybe_smallest_dist_to_circle,
+          maybe_snap_point_on_circle,
+        ),
+        (SnapCategory::Grid, maybe_dist_to_grid, maybe_snap_point_on_grid),
+      ];
+      let candidates = candidates
+        .into_iter()
+        .filter_map(|(category, maybe_dist, maybe_candidate)| {
+          maybe_dist
+            .zip(maybe_candidate)
+            .map(|(dist, candidate)| (category, dist, candidate))
+        })
+        .collect();
+      if let Some(snap_point) = snap_settings.choose(candidates) {
+        maybe_snap_point.set(snap_point)
+      } else if let (Tool::Line(_), Some(first_point_ent)) = (tool_state.get(), snap_line.maybe_first_point) {
+        // Not snapped to anything, try to keep a nice angle with a line nearby
+        if let Some(first_point_pos) = scrn_points.get(first_point_ent) {
+          let maybe_snapped = entity_quadtree
+            .entities_within(
+              mouse_pos.to_virtual(&*viewport).into(),
+              REFERENCE_LINE_THRES.to_virtual(&*viewport).into(),
+            )
+            .into_iter()
+            .filter_map(|(entity, _)| scrn_lines.get(entity).map(|l| (entity, *l)))
+            .filter(|(_, l)| (l.get_closest_point(mouse_pos) - mouse_pos).magnitude() <= REFERENCE_LINE_THRES)
+            .filter_map(|(entity, l)| {
+              snap_to_relative_angle(*first_point_pos, mouse_pos, l).map(|snapped| (entity, snapped))
+            })
+            .min_by(|(_, (p1, _)), (_, (p2, _))| {
+              let d1: f64 = (*p1 - mouse_pos).magnitude().into();
+              let d2: f64 = (*p2 - mouse_pos).magnitude().into();
+              d1.partial_cmp(&d2).unwrap()
+            });
+          if let Some((entity, (position, angle))) = maybe_snapped {
+            maybe_snap_point.set(SnapPoint {
+              position,
+              symbol: SnapPointType::NotSnapped,
+            });
+            snap_line.maybe_relative_angle = Some((entity, angle));
           }
         }
       }
@@ -208,8 +413,38 @@ impl<'a> System<'a> for SnapPointViaMouse {
   }
 }
 
+/// Snap `mouse_pos` so that the line from `from` to it keeps one of the nice angles with the
+/// `reference` line. Returns the snapped position and the angle relative to the reference line
+pub fn snap_to_relative_angle(
+  from: ScreenPosition,
+  mouse_pos: ScreenPosition,
+  reference: ScreenLine,
+) -> Option<(ScreenPosition, f64)> {
+  let dir: Vector2 = (mouse_pos - from).into();
+  let ref_dir: Vector2 = (reference.to - reference.from).into();
+  if dir.is_zero() || ref_dir.is_zero() {
+    return None;
+  }
+  let ref_angle = ref_dir.y.atan2(ref_dir.x).to_degrees();
+  let rel_angle = (dir.y.atan2(dir.x).to_degrees() - ref_angle).rem_euclid(180.0);
+  let (offset, snapped_angle) = RELATIVE_ANGLES
+    .iter()
+    .map(|angle| (angle - rel_angle, *angle))
+    .min_by(|(o1, _), (o2, _)| o1.abs().partial_cmp(&o2.abs()).unwrap())?;
+  if offset.abs() > RELATIVE_ANGLE_THRES {
+    return None;
+  }
+
+  // Rotate the mouse direction onto the snapped angle and project the mouse onto it
+  let (sin, cos) = offset.to_radians().sin_cos();
+  let snapped_dir = vec2![dir.x * cos - dir.y * sin, dir.x * sin + dir.y * cos].normalized();
+  let dist = dir.x * snapped_dir.x + dir.y * snapped_dir.y;
+  Some((from + ScreenPosition(snapped_dir * dist), snapped_angle % 180.0))
+}
+
 fn check_circle_intersection<F>(
   mouse_pos: ScreenPosition,
+  threshold: ScreenScalar,
   ci: ScreenCircleIntersect,
   maybe_smallest_dist: Option<f64>,
   callback: &mut F,
@@ -224,7 +459,7 @@ fn check_circle_intersection<F>(
       } else {
         (CircleIntersectId::Second, p2)
       };
-      let norm_dist = (mouse_pos - p).magnitude() / SNAP_TO_INTERSECTION_THRES;
+      let norm_dist = (mouse_pos - p).magnitude() / threshold;
       if norm_dist < 1.0 {
         if maybe_smallest_dist.is_none() || norm_dist < maybe_smallest_dist.unwrap() {
           callback(Some((p, norm_dist, ty)));
@@ -232,7 +467,7 @@ fn check_circle_intersection<F>(
       }
     }
     ScreenCircleIntersect::OnePoint(p) => {
-      let norm_dist = (mouse_pos - p).magnitude() / SNAP_TO_INTERSECTION_THRES;
+      let norm_dist = (mouse_pos - p).magnitude() / threshold;
       if norm_dist < 1.0 {
         if maybe_smallest_dist.is_none() || norm_dist < maybe_smallest_dist.unwrap() {
           callback(Some((p, norm_dist, CircleIntersectId::First)));
@@ -242,3 +477,92 @@ fn check_circle_intersection<F>(
     ScreenCircleIntersect::None => (),
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_snap_to_angle_relative_to_tilted_line() {
+    let deg = std::f64::consts::PI / 180.0;
+    let reference = ScreenLine {
+      from: vec2![0., 0.].into(),
+      to: vec2![100. * (30. * deg).cos(), 100. * (30. * deg).sin()].into(),
+      line_type: LineType::Straight,
+    };
+
+    // Drawing at 76 degrees is snapped to the reference direction + 45 degrees
+    let from: ScreenPosition = vec2![200., 200.].into();
+    let mouse_pos = from + ScreenPosition(vec2![(76. * deg).cos(), (76. * deg).sin()] * 50.);
+    let (snapped, angle) = snap_to_relative_angle(from, mouse_pos, reference).unwrap();
+    assert!((angle - 45.).abs() < 1e-9);
+    let snapped_dir: Vector2 = (snapped - from).into();
+    assert!((snapped_dir.y.atan2(snapped_dir.x) - 75. * deg).abs() < 1e-9);
+    assert!((snapped_dir.magnitude() - 50. * deg.cos()).abs() < 1e-9);
+
+    // Too far away from any of the nice angles
+    let mouse_pos = from + ScreenPosition(vec2![(85. * deg).cos(), (85. * deg).sin()] * 50.);
+    assert!(snap_to_relative_angle(from, mouse_pos, reference).is_none());
+  }
+
+  #[test]
+  fn test_snap_to_grid_only_when_turned_on() {
+    let mut world = World::new();
+    let mut system = SnapPointViaMouse::default();
+    System::setup(&mut system, &mut world);
+    world.fetch_mut::<ToolState>().set(Tool::Point);
+
+    // One virtual unit is 48 pixels in the default viewport, the origin is at the center
+    let near_grid = vec2![480. + 48. + 5., 360. - 4.].into();
+    world.fetch_mut::<InputState>().mouse_abs_pos = near_grid;
+    system.run_now(&world);
+    assert!(matches!(
+      world.fetch::<MaybeSnapPoint>().get().unwrap().symbol,
+      SnapPointType::NotSnapped
+    ));
+
+    world.fetch_mut::<SnapSettings>().toggle(SnapCategory::Grid);
+    system.run_now(&world);
+    let snap_point = world.fetch::<MaybeSnapPoint>().get().unwrap();
+    match snap_point.symbol {
+      SnapPointType::SnapOnGrid(VirtualPosition(p)) => assert_eq!((p.x, p.y), (1.0, 0.0)),
+      symbol => panic!("Expected to snap on the grid, got {:?}", symbol),
+    }
+    assert!((snap_point.position.0.x - 528.).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_snap_priority_between_point_and_grid() {
+    let mut world = World::new();
+    let mut system = SnapPointViaMouse::default();
+    System::setup(&mut system, &mut world);
+    world.fetch_mut::<ToolState>().set(Tool::Point);
+    world.fetch_mut::<SnapSettings>().toggle(SnapCategory::Grid);
+
+    // A point a little right of the grid node at (1, 0), the mouse is in reach of both and closer
+    // to the grid node
+    let point = vec2![1.1, 0.];
+    let ent = world.create_entity().build();
+    let scrn_point = VirtualPosition(point).to_screen(&Viewport::default());
+    world.write_storage::<ScreenPoint>().insert(ent, scrn_point).unwrap();
+    world
+      .fetch_mut::<EntityQuadtree>()
+      .insert(ent, QuadtreeShape::Point(point));
+    world.fetch_mut::<InputState>().mouse_abs_pos = vec2![480. + 48. + 1., 360. - 1.].into();
+
+    system.run_now(&world);
+    let symbol = world.fetch::<MaybeSnapPoint>().get().unwrap().symbol;
+    match symbol {
+      SnapPointType::SnapOnPoint(snapped) => assert_eq!(snapped, ent),
+      symbol => panic!("Expected to snap on the point, got {:?}", symbol),
+    }
+
+    world.fetch_mut::<SnapSettings>().priority = SnapPriority::new(vec![SnapCategory::Grid, SnapCategory::Point]);
+    system.run_now(&world);
+    let symbol = world.fetch::<MaybeSnapPoint>().get().unwrap().symbol;
+    match symbol {
+      SnapPointType::SnapOnGrid(VirtualPosition(p)) => assert_eq!((p.x, p.y), (1.0, 0.0)),
+      symbol => panic!("Expected to snap on the grid, got {:?}", symbol),
+    }
+  }
+}
@@ -0,0 +1,85 @@
+use crate::{events::*, resources::*};
+use core_lib::{components::symbolics::*, events::*};
+use specs::prelude::*;
+
+/// Every clicked point becomes the next vertex, clicking the first vertex again closes the polygon
+pub struct CreatePolygonViaMouse {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  active_point_event_reader: Option<ActivePointEventReader>,
+}
+
+impl Default for CreatePolygonViaMouse {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      active_point_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for CreatePolygonViaMouse {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Write<'a, SnapPolygon>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, ActivePointEventChannel>,
+    Write<'a, CommandEventChannel>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      input_state,
+      key_map,
+      mut snap_polygon,
+      tool_change_event_channel,
+      mut active_point_event_channel,
+      mut command_event_channel,
+    ): Self::SystemData,
+  ) {
+    if let Some(reader) = &mut self.tool_change_event_reader {
+      for ToolChangeEvent(tool) in tool_change_event_channel.read(reader) {
+        match tool {
+          Tool::Polygon => {
+            if self.active_point_event_reader.is_none() {
+              self.active_point_event_reader = Some(active_point_event_channel.register_reader())
+            }
+          }
+          _ => {
+            if self.active_point_event_reader.is_some() {
+              self.active_point_event_reader = None;
+              snap_polygon.vertices.clear();
+            }
+          }
+        }
+      }
+    }
+
+    if key_map.just_triggered(Action::Cancel, &input_state) {
+      snap_polygon.vertices.clear();
+    }
+
+    if let Some(reader) = &mut self.active_point_event_reader {
+      for ActivePointEvent(ent) in active_point_event_channel.read(reader) {
+        if snap_polygon.vertices.first() == Some(ent) {
+          // Clicking the first vertex closes the loop, once there are enough vertices
+          if snap_polygon.vertices.len() >= 3 {
+            let sym_polygon = SymbolicPolygon(snap_polygon.vertices.drain(..).collect());
+            command_event_channel.single_write(CommandEvent {
+              command: Command::PolygonInsert(InsertPolygonEvent::InsertPolygon(sym_polygon)),
+              event_id: None,
+            });
+          }
+        } else if !snap_polygon.vertices.contains(ent) {
+          snap_polygon.vertices.push(*ent);
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,100 @@
+use crate::{events::*, resources::*, utilities::hitting_object};
+use core_lib::{
+  components::{screen_shapes::*, symbolics::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+/// With the mirror tool, clicking on a line reflects the selection across it. The reflections
+/// are dependent copies, following the selection and the mirror when they move
+pub struct ReflectSelectionViaClick {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+}
+
+impl Default for ReflectSelectionViaClick {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for ReflectSelectionViaClick {
+  type SystemData = (
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Read<'a, EntityQuadtree>,
+    Read<'a, Viewport>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      entity_quadtree,
+      viewport,
+      mut command_event_channel,
+      sym_points,
+      sym_lines,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is mirror
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Mirror) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => self.mouse_event_reader = None,
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        if let MouseEvent::Click(position) = event {
+          if let Some(entity) = hitting_object(
+            *position,
+            &entity_quadtree,
+            &viewport,
+            &scrn_points,
+            &sym_points,
+            &scrn_lines,
+            &scrn_circles,
+            SELECT_DIST_THRES,
+          ) {
+            if sym_lines.get(entity).is_some() {
+              command_event_channel.single_write(CommandEvent {
+                command: Command::ReflectSelected { mirror: entity },
+                event_id: None,
+              });
+            }
+          }
+        }
+      }
+    }
+  }
+}
@@ -6,12 +6,14 @@ use specs::prelude::*;
 pub struct RemoveSelectedViaKeyboard;
 
 impl<'a> System<'a> for RemoveSelectedViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, CommandEventChannel>);
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
 
-  fn run(&mut self, (input_state, mut command_event_channel): Self::SystemData) {
-    let delete = input_state.keyboard.just_activated(Key::Delete);
-    let backspace = input_state.keyboard.just_activated(Key::Backspace);
-    if delete || backspace {
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    // Backspace erases the text being typed instead
+    if input_state.text_mode {
+      return;
+    }
+    if key_map.just_triggered(Action::RemoveSelected, &input_state) {
       command_event_channel.single_write(CommandEvent {
         command: Command::Remove(RemoveEvent::RemoveSelected),
         event_id: None,
@@ -0,0 +1,205 @@
+use crate::{events::*, resources::*, utilities::hitting_object};
+use core_lib::{
+  components::{markers::*, screen_shapes::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  resources::*,
+  systems::command_handlers::rotate_point,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+/// With the rotate tool, clicking on a point chooses it as the pivot, then dragging rotates the
+/// selection around it by the angle the cursor sweeps around the pivot
+pub struct RotateSelectionViaDrag {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  pivot: Option<Entity>,
+  pivot_position: Option<VirtualPosition>,
+  start_position: Option<VirtualPosition>,
+  rotating_points: Vec<(Entity, SymbolicPoint)>,
+}
+
+impl Default for RotateSelectionViaDrag {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+      pivot: None,
+      pivot_position: None,
+      start_position: None,
+      rotating_points: vec![],
+    }
+  }
+}
+
+impl<'a> System<'a> for RotateSelectionViaDrag {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Read<'a, EntityQuadtree>,
+    Read<'a, Viewport>,
+    Read<'a, LayerManager>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, Layer>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      input_state,
+      key_map,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      entity_quadtree,
+      viewport,
+      layer_manager,
+      mut command_event_channel,
+      layers,
+      selecteds,
+      sym_points,
+      virt_points,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is rotate
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Rotate) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            self.mouse_event_reader = None;
+            self.pivot = None;
+          }
+        }
+      }
+    }
+
+    if key_map.just_triggered(Action::Cancel, &input_state) {
+      self.pivot = None;
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::Click(position) => {
+            if let Some(entity) = hitting_object(
+              *position,
+              &entity_quadtree,
+              &viewport,
+              &scrn_points,
+              &sym_points,
+              &scrn_lines,
+              &scrn_circles,
+              SELECT_DIST_THRES,
+            ) {
+              if sym_points.get(entity).is_some() {
+                self.pivot = Some(entity);
+              }
+            }
+          }
+          MouseEvent::DragBegin(start_position) => {
+            if let Some(pivot_position) = self.pivot.and_then(|pivot| virt_points.get(pivot)) {
+              self.pivot_position = Some(*pivot_position);
+              self.start_position = Some(start_position.to_virtual(&viewport));
+              self.rotating_points = (&entities, &selecteds, &sym_points, layers.maybe())
+                .join()
+                .filter(|(ent, _, sym_point, layer)| {
+                  // The points on locked layers stay in place
+                  Some(*ent) != self.pivot
+                    && rotate_point(sym_point, *pivot_position, 0.).is_some()
+                    && !layer_manager.is_locked(*layer)
+                })
+                .map(|(ent, _, sym_point, _)| (ent, *sym_point))
+                .collect();
+            }
+          }
+          MouseEvent::DragMove(_, curr_position) => {
+            if let (Some(pivot_position), Some(start_position)) = (self.pivot_position, self.start_position) {
+              let radians = swept_angle(pivot_position, start_position, curr_position.to_virtual(&viewport));
+              for (ent, start_sym_point) in &self.rotating_points {
+                if let (Some(old_sym_point), Some(new_sym_point)) = (
+                  sym_points.get(*ent),
+                  rotate_point(start_sym_point, pivot_position, radians),
+                ) {
+                  command_event_channel.single_write(CommandEvent {
+                    command: Command::Update(UpdateEvent::UpdatePoint(*ent, *old_sym_point, new_sym_point)),
+                    event_id: None,
+                  });
+                }
+              }
+            }
+          }
+          MouseEvent::DragEnd(curr_position) => {
+            if let (Some(pivot_position), Some(start_position)) = (self.pivot_position, self.start_position) {
+              let radians = swept_angle(pivot_position, start_position, curr_position.to_virtual(&viewport));
+              for (ent, start_sym_point) in &self.rotating_points {
+                if let Some(new_sym_point) = rotate_point(start_sym_point, pivot_position, radians) {
+                  command_event_channel.single_write(CommandEvent {
+                    command: Command::Update(UpdateEvent::UpdatePointEnd(*ent, *start_sym_point, new_sym_point)),
+                    event_id: None,
+                  });
+                }
+              }
+            }
+            self.pivot_position = None;
+            self.start_position = None;
+            self.rotating_points.clear();
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// The counter clockwise angle swept around `pivot` when moving from `start` to `curr`
+pub fn swept_angle(pivot: VirtualPosition, start: VirtualPosition, curr: VirtualPosition) -> f64 {
+  let angle = |position: VirtualPosition| {
+    let diff = (position - pivot).0;
+    diff.y.atan2(diff.x)
+  };
+  angle(curr) - angle(start)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+  use std::f64::consts::FRAC_PI_2;
+
+  #[test]
+  fn test_swept_angle_rotates_start_onto_cursor() {
+    let pivot: VirtualPosition = vec2![1., 1.].into();
+    let radians = swept_angle(pivot, vec2![3., 1.].into(), vec2![1., 4.].into());
+    assert!((radians - FRAC_PI_2).abs() < 1e-12);
+
+    let point = SymbolicPoint::Free(vec2![2., 1.].into());
+    match rotate_point(&point, pivot, radians) {
+      Some(SymbolicPoint::Free(position)) => assert!((position.0 - vec2![1., 2.]).magnitude() < 1e-12),
+      _ => panic!("Expected a free point"),
+    }
+  }
+}
@@ -0,0 +1,207 @@
+use crate::{events::*, resources::*, utilities::hitting_object};
+use core_lib::{
+  components::{markers::*, screen_shapes::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  resources::*,
+  systems::command_handlers::scale_point,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+/// With the scale tool, clicking on a point chooses it as the pivot, then dragging scales the
+/// selection by how much farther from the pivot the cursor gets
+pub struct ScaleSelectionViaDrag {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  pivot: Option<Entity>,
+  pivot_position: Option<VirtualPosition>,
+  start_position: Option<VirtualPosition>,
+  scaling_points: Vec<(Entity, SymbolicPoint)>,
+}
+
+impl Default for ScaleSelectionViaDrag {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+      pivot: None,
+      pivot_position: None,
+      start_position: None,
+      scaling_points: vec![],
+    }
+  }
+}
+
+impl<'a> System<'a> for ScaleSelectionViaDrag {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Read<'a, EntityQuadtree>,
+    Read<'a, Viewport>,
+    Read<'a, LayerManager>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, Layer>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      input_state,
+      key_map,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      entity_quadtree,
+      viewport,
+      layer_manager,
+      mut command_event_channel,
+      layers,
+      selecteds,
+      sym_points,
+      virt_points,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is scale
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Scale) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            self.mouse_event_reader = None;
+            self.pivot = None;
+          }
+        }
+      }
+    }
+
+    if key_map.just_triggered(Action::Cancel, &input_state) {
+      self.pivot = None;
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::Click(position) => {
+            if let Some(entity) = hitting_object(
+              *position,
+              &entity_quadtree,
+              &viewport,
+              &scrn_points,
+              &sym_points,
+              &scrn_lines,
+              &scrn_circles,
+              SELECT_DIST_THRES,
+            ) {
+              if sym_points.get(entity).is_some() {
+                self.pivot = Some(entity);
+              }
+            }
+          }
+          MouseEvent::DragBegin(start_position) => {
+            if let Some(pivot_position) = self.pivot.and_then(|pivot| virt_points.get(pivot)) {
+              self.pivot_position = Some(*pivot_position);
+              self.start_position = Some(start_position.to_virtual(&viewport));
+              self.scaling_points = (&entities, &selecteds, &sym_points, layers.maybe())
+                .join()
+                .filter(|(ent, _, sym_point, layer)| {
+                  // The points on locked layers stay in place
+                  Some(*ent) != self.pivot
+                    && scale_point(sym_point, *pivot_position, 1.).is_some()
+                    && !layer_manager.is_locked(*layer)
+                })
+                .map(|(ent, _, sym_point, _)| (ent, *sym_point))
+                .collect();
+            }
+          }
+          MouseEvent::DragMove(_, curr_position) => {
+            if let (Some(pivot_position), Some(start_position)) = (self.pivot_position, self.start_position) {
+              let factor = drag_factor(pivot_position, start_position, curr_position.to_virtual(&viewport));
+              for (ent, start_sym_point) in &self.scaling_points {
+                if let (Some(old_sym_point), Some(new_sym_point)) = (
+                  sym_points.get(*ent),
+                  scale_point(start_sym_point, pivot_position, factor),
+                ) {
+                  command_event_channel.single_write(CommandEvent {
+                    command: Command::Update(UpdateEvent::UpdatePoint(*ent, *old_sym_point, new_sym_point)),
+                    event_id: None,
+                  });
+                }
+              }
+            }
+          }
+          MouseEvent::DragEnd(curr_position) => {
+            if let (Some(pivot_position), Some(start_position)) = (self.pivot_position, self.start_position) {
+              let factor = drag_factor(pivot_position, start_position, curr_position.to_virtual(&viewport));
+              for (ent, start_sym_point) in &self.scaling_points {
+                if let Some(new_sym_point) = scale_point(start_sym_point, pivot_position, factor) {
+                  command_event_channel.single_write(CommandEvent {
+                    command: Command::Update(UpdateEvent::UpdatePointEnd(*ent, *start_sym_point, new_sym_point)),
+                    event_id: None,
+                  });
+                }
+              }
+            }
+            self.pivot_position = None;
+            self.start_position = None;
+            self.scaling_points.clear();
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// The ratio of the distances from `pivot` to `curr` and to `start`. Dragging from right on top
+/// of the pivot can't scale anything, the factor is then kept at 1
+pub fn drag_factor(pivot: VirtualPosition, start: VirtualPosition, curr: VirtualPosition) -> f64 {
+  let start_distance = (start - pivot).0.magnitude();
+  if start_distance == 0.0 {
+    1.0
+  } else {
+    (curr - pivot).0.magnitude() / start_distance
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  #[test]
+  fn test_drag_factor_from_distance_to_pivot() {
+    let pivot: VirtualPosition = vec2![1., 1.].into();
+    let factor = drag_factor(pivot, vec2![2., 1.].into(), vec2![1., 4.].into());
+    assert_eq!(factor, 3.0);
+    assert_eq!(drag_factor(pivot, pivot, vec2![5., 5.].into()), 1.0);
+
+    let point = SymbolicPoint::Fixed(vec2![0., 0.].into());
+    match scale_point(&point, pivot, factor) {
+      Some(SymbolicPoint::Fixed(position)) => assert_eq!(position.0, vec2![-2., -2.]),
+      _ => panic!("Expected a fixed point"),
+    }
+  }
+}
@@ -0,0 +1,67 @@
+use crate::{events::*, resources::*};
+use core_lib::{components::sliders::*, events::*, resources::*};
+use specs::prelude::*;
+
+/// With the slider tool, clicking places a slider going from 0 to 1 with its track starting there
+pub struct CreateSliderViaMouse {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+}
+
+impl Default for CreateSliderViaMouse {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for CreateSliderViaMouse {
+  type SystemData = (
+    Read<'a, Viewport>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Write<'a, CommandEventChannel>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (viewport, tool_change_event_channel, mut mouse_event_channel, mut command_event_channel): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is slider
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Slider) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => self.mouse_event_reader = None,
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        if let MouseEvent::Click(position) = event {
+          let slider = Slider {
+            position: position.to_virtual(&*viewport),
+            min: 0.,
+            max: 1.,
+          };
+          command_event_channel.single_write(CommandEvent {
+            command: Command::SliderInsert(InsertSliderEvent::InsertSlider(slider, SliderValue(0.5))),
+            event_id: None,
+          });
+        }
+      }
+    }
+  }
+}
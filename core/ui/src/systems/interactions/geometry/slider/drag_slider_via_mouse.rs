@@ -0,0 +1,136 @@
+use crate::{events::*, resources::*, utilities::*};
+use core_lib::{
+  components::{markers::*, sliders::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+/// With the select tool, dragging along a slider moves its value under the cursor. The value the
+/// drag began with is kept so that the whole drag is undone at once
+pub struct DragSliderViaMouse {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  dragging_slider: Option<(Entity, f64)>, // Slider entity, value when the drag began
+}
+
+impl Default for DragSliderViaMouse {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+      dragging_slider: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for DragSliderViaMouse {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, Viewport>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, Slider>,
+    ReadStorage<'a, SliderValue>,
+    ReadStorage<'a, Hidden>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+    self.mouse_event_reader = Some(world.fetch_mut::<MouseEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      input_state,
+      viewport,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      mut command_event_channel,
+      sliders,
+      slider_values,
+      hiddens,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is select
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Select) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            self.mouse_event_reader = None;
+            self.dragging_slider = None;
+          }
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::DragBegin(start_position) => {
+            if !input_state.keyboard.is_shift_activated() {
+              if let Some(ent) = hitting_slider(
+                *start_position,
+                &viewport,
+                &entities,
+                &sliders,
+                &hiddens,
+                SELECT_DIST_THRES,
+              ) {
+                if let Some(slider_value) = slider_values.get(ent) {
+                  self.dragging_slider = Some((ent, slider_value.0));
+                  command_event_channel.single_write(CommandEvent {
+                    command: Command::Select(SelectEvent::Select(ent)),
+                    event_id: None,
+                  });
+                }
+              }
+            }
+          }
+          MouseEvent::DragMove(_, curr_position) => {
+            if let Some((ent, _)) = self.dragging_slider {
+              if let (Some(slider), Some(slider_value)) = (sliders.get(ent), slider_values.get(ent)) {
+                command_event_channel.single_write(CommandEvent {
+                  command: Command::Update(UpdateEvent::UpdateSlider(
+                    ent,
+                    slider_value.0,
+                    slider_value_at(slider, *curr_position, &viewport),
+                  )),
+                  event_id: None,
+                });
+              }
+            }
+          }
+          MouseEvent::DragEnd(curr_position) => {
+            if let Some((ent, start_value)) = self.dragging_slider.take() {
+              if let Some(slider) = sliders.get(ent) {
+                command_event_channel.single_write(CommandEvent {
+                  command: Command::Update(UpdateEvent::UpdateSliderEnd(
+                    ent,
+                    start_value,
+                    slider_value_at(slider, *curr_position, &viewport),
+                  )),
+                  event_id: None,
+                });
+              }
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,5 @@
+mod create_slider_via_mouse;
+mod drag_slider_via_mouse;
+
+pub use create_slider_via_mouse::*;
+pub use drag_slider_via_mouse::*;
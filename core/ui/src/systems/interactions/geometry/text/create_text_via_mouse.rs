@@ -0,0 +1,227 @@
+use crate::{events::*, resources::*, utilities::hitting_object};
+use core_lib::{
+  components::{screen_shapes::*, symbolics::*, virtual_shapes::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+/// With the text tool, clicking starts typing a text there. Clicking on a point, a line or a
+/// circle anchors the text to it so that the text follows it. Return inserts the text, as does
+/// clicking somewhere else to start the next one, and Escape drops it
+pub struct CreateTextViaMouse {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  typing: Option<(TextAnchor, ScreenPosition, String)>,
+}
+
+impl Default for CreateTextViaMouse {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+      typing: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for CreateTextViaMouse {
+  type SystemData = (
+    Write<'a, InputState>,
+    Read<'a, Viewport>,
+    Read<'a, EntityQuadtree>,
+    Write<'a, TypingText>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualLine>,
+    ReadStorage<'a, VirtualCircle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      mut input_state,
+      viewport,
+      entity_quadtree,
+      mut typing_text,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      mut command_event_channel,
+      sym_points,
+      scrn_points,
+      scrn_lines,
+      scrn_circles,
+      virt_points,
+      virt_lines,
+      virt_circles,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is text, changing tool drops the text
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Text) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            self.mouse_event_reader = None;
+            if self.typing.take().is_some() {
+              input_state.set_text_mode(false);
+              typing_text.clear();
+            }
+          }
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        if let MouseEvent::Click(position) = event {
+          if let Some((anchor, _, text)) = self.typing.take() {
+            insert_text(anchor, text, &mut command_event_channel);
+          }
+          let virt_position = position.to_virtual(&*viewport);
+          let hit = hitting_object(
+            *position,
+            &entity_quadtree,
+            &viewport,
+            &scrn_points,
+            &sym_points,
+            &scrn_lines,
+            &scrn_circles,
+            SELECT_DIST_THRES,
+          );
+          let anchor = hit
+            .and_then(|ent| {
+              let anchor = if let Some(&point) = virt_points.get(ent) {
+                Some(point)
+              } else if let Some(line) = virt_lines.get(ent) {
+                Some((line.from + line.to) / VirtualScalar(2.0))
+              } else {
+                virt_circles.get(ent).map(|circle| circle.center)
+              };
+              anchor.map(|anchor| TextAnchor::Entity(ent, virt_position - anchor))
+            })
+            .unwrap_or(TextAnchor::Position(virt_position));
+          self.typing = Some((anchor, *position, String::new()));
+          input_state.set_text_mode(true);
+        }
+      }
+    }
+
+    if let Some((anchor, position, mut text)) = self.typing.take() {
+      if input_state.keyboard.just_activated(Key::Escape) {
+        input_state.set_text_mode(false);
+        typing_text.clear();
+      } else if input_state.keyboard.just_activated(Key::Return) || input_state.keyboard.just_activated(Key::Return2) {
+        insert_text(anchor, text, &mut command_event_channel);
+        input_state.set_text_mode(false);
+        typing_text.clear();
+      } else {
+        if input_state.keyboard.just_activated(Key::Backspace) {
+          text.pop();
+        }
+        text.extend(input_state.typed_text.chars().filter(|c| !c.is_control()));
+        typing_text.set(ScreenText {
+          position,
+          text: text.clone(),
+        });
+        self.typing = Some((anchor, position, text));
+      }
+    }
+  }
+}
+
+fn insert_text(anchor: TextAnchor, text: String, command_event_channel: &mut CommandEventChannel) {
+  if !text.trim().is_empty() {
+    command_event_channel.single_write(CommandEvent {
+      command: Command::TextInsert(InsertTextEvent::InsertText(SymbolicText { text, anchor })),
+      event_id: None,
+    });
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  fn frame(world: &mut World, system: &mut CreateTextViaMouse, input: impl FnOnce(&mut InputState)) {
+    {
+      let mut input_state = world.fetch_mut::<InputState>();
+      input_state.reset_relative_data();
+      input(&mut input_state);
+    }
+    system.run_now(world);
+  }
+
+  #[test]
+  fn test_type_text_and_insert_it_with_return() {
+    let mut world = World::new();
+    let mut system = CreateTextViaMouse::default();
+    System::setup(&mut system, &mut world);
+    let mut reader = world.fetch_mut::<CommandEventChannel>().register_reader();
+    world
+      .fetch_mut::<ToolChangeEventChannel>()
+      .single_write(ToolChangeEvent(Tool::Text));
+    system.run_now(&world);
+
+    let position: ScreenPosition = vec2![100., 100.].into();
+    world
+      .fetch_mut::<MouseEventChannel>()
+      .single_write(MouseEvent::Click(position));
+    frame(&mut world, &mut system, |_| ());
+    assert!(world.fetch::<InputState>().text_mode);
+
+    // Shortcut letters are typed, backspace erases the last one
+    frame(&mut world, &mut system, |input_state| {
+      input_state.set_key(Key::X, true);
+      input_state.type_text("Hix");
+    });
+    frame(&mut world, &mut system, |input_state| {
+      input_state.set_key(Key::Backspace, true)
+    });
+    assert!(!world.fetch::<InputState>().keyboard.is_activated(Key::X));
+    assert_eq!(
+      world.fetch::<TypingText>().get().map(|t| t.text.clone()),
+      Some("Hi".to_string())
+    );
+
+    frame(&mut world, &mut system, |input_state| {
+      input_state.set_key(Key::Return, true)
+    });
+    assert!(!world.fetch::<InputState>().text_mode);
+    assert!(world.fetch::<TypingText>().get().is_none());
+    let commands = world
+      .fetch::<CommandEventChannel>()
+      .read(&mut reader)
+      .map(|event| event.command.clone())
+      .collect::<Vec<_>>();
+    match commands.as_slice() {
+      [Command::TextInsert(InsertTextEvent::InsertText(SymbolicText {
+        text,
+        anchor: TextAnchor::Position(at),
+      }))] => {
+        assert_eq!(text, "Hi");
+        assert_eq!(at.0, position.to_virtual(&Viewport::default()).0);
+      }
+      _ => panic!("Expected a text insertion, got {:?}", commands),
+    }
+  }
+}
@@ -0,0 +1,3 @@
+mod create_text_via_mouse;
+
+pub use create_text_via_mouse::*;
@@ -0,0 +1,62 @@
+use crate::resources::*;
+use core_lib::{
+  components::{markers::*, underlays::*},
+  events::*,
+};
+use specs::prelude::*;
+
+static OPACITY_STEP: f64 = 0.1;
+
+/// `Cmd - ]` and `Cmd - [` make the selected underlays more and less opaque, undone in a single
+/// step
+#[derive(Default)]
+pub struct ChangeUnderlayOpacityViaKeyboard;
+
+impl<'a> System<'a> for ChangeUnderlayOpacityViaKeyboard {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, UnderlayPlacement>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, input_state, key_map, mut command_event_channel, selecteds, underlay_placements): Self::SystemData,
+  ) {
+    let step = if key_map.just_triggered(Action::IncreaseUnderlayOpacity, &input_state) {
+      OPACITY_STEP
+    } else if key_map.just_triggered(Action::DecreaseUnderlayOpacity, &input_state) {
+      -OPACITY_STEP
+    } else {
+      return;
+    };
+    let selected = (&entities, &selecteds, &underlay_placements)
+      .join()
+      .map(|(ent, _, placement)| (ent, *placement))
+      .collect::<Vec<_>>();
+    if selected.is_empty() {
+      return;
+    }
+    command_event_channel.single_write(CommandEvent {
+      command: Command::BeginTransaction("Change opacity".to_string()),
+      event_id: None,
+    });
+    for (ent, placement) in selected {
+      command_event_channel.single_write(CommandEvent {
+        command: Command::Update(UpdateEvent::UpdateUnderlayEnd(
+          ent,
+          placement,
+          placement.with_opacity(placement.opacity + step),
+        )),
+        event_id: None,
+      });
+    }
+    command_event_channel.single_write(CommandEvent {
+      command: Command::EndTransaction,
+      event_id: None,
+    });
+  }
+}
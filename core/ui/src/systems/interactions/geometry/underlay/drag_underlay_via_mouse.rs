@@ -0,0 +1,153 @@
+use crate::{events::*, resources::*, utilities::*};
+use core_lib::{
+  components::{markers::*, underlays::*},
+  events::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+
+/// With the select tool, dragging the top left handle of an underlay moves it and dragging the
+/// bottom right one scales it. The placement the drag began with is kept so that the whole drag
+/// is undone at once
+pub struct DragUnderlayViaMouse {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  dragging_underlay: Option<(Entity, UnderlayHandle, UnderlayPlacement)>, // Underlay, handle, placement when the drag began
+}
+
+impl Default for DragUnderlayViaMouse {
+  fn default() -> Self {
+    Self {
+      tool_change_event_reader: None,
+      mouse_event_reader: None,
+      dragging_underlay: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for DragUnderlayViaMouse {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, Viewport>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Write<'a, CommandEventChannel>,
+    ReadStorage<'a, ImageUnderlay>,
+    ReadStorage<'a, UnderlayPlacement>,
+    ReadStorage<'a, Hidden>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+    self.mouse_event_reader = Some(world.fetch_mut::<MouseEventChannel>().register_reader());
+  }
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      input_state,
+      viewport,
+      tool_change_event_channel,
+      mut mouse_event_channel,
+      mut command_event_channel,
+      underlays,
+      underlay_placements,
+      hiddens,
+    ): Self::SystemData,
+  ) {
+    // Only listen to mouse events when the tool state is select
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Select) => {
+            if self.mouse_event_reader.is_none() {
+              self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+            }
+          }
+          _ => {
+            self.mouse_event_reader = None;
+            self.dragging_underlay = None;
+          }
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::DragBegin(start_position) => {
+            if !input_state.keyboard.is_shift_activated() {
+              if let Some((ent, handle)) = hitting_underlay_handle(
+                *start_position,
+                &viewport,
+                &entities,
+                &underlays,
+                &underlay_placements,
+                &hiddens,
+                SELECT_DIST_THRES,
+              ) {
+                if let Some(placement) = underlay_placements.get(ent) {
+                  self.dragging_underlay = Some((ent, handle, *placement));
+                  command_event_channel.single_write(CommandEvent {
+                    command: Command::Select(SelectEvent::Select(ent)),
+                    event_id: None,
+                  });
+                }
+              }
+            }
+          }
+          MouseEvent::DragMove(_, curr_position) => {
+            if let Some((ent, handle, start_placement)) = self.dragging_underlay {
+              if let (Some(underlay), Some(placement)) = (underlays.get(ent), underlay_placements.get(ent)) {
+                command_event_channel.single_write(CommandEvent {
+                  command: Command::Update(UpdateEvent::UpdateUnderlay(
+                    ent,
+                    *placement,
+                    dragged_to(underlay, start_placement, handle, *curr_position, &viewport),
+                  )),
+                  event_id: None,
+                });
+              }
+            }
+          }
+          MouseEvent::DragEnd(curr_position) => {
+            if let Some((ent, handle, start_placement)) = self.dragging_underlay.take() {
+              if let Some(underlay) = underlays.get(ent) {
+                command_event_channel.single_write(CommandEvent {
+                  command: Command::Update(UpdateEvent::UpdateUnderlayEnd(
+                    ent,
+                    start_placement,
+                    dragged_to(underlay, start_placement, handle, *curr_position, &viewport),
+                  )),
+                  event_id: None,
+                });
+              }
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// The placement with the dragged corner under the mouse
+fn dragged_to(
+  underlay: &ImageUnderlay,
+  placement: UnderlayPlacement,
+  handle: UnderlayHandle,
+  position: ScreenPosition,
+  viewport: &Viewport,
+) -> UnderlayPlacement {
+  let position = position.to_virtual(viewport);
+  match handle {
+    UnderlayHandle::Move => placement.moved_by(position - placement.position),
+    UnderlayHandle::Scale => placement.scaled_to(underlay, position),
+  }
+}
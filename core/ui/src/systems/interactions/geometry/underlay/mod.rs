@@ -0,0 +1,5 @@
+mod change_underlay_opacity_via_keyboard;
+mod drag_underlay_via_mouse;
+
+pub use change_underlay_opacity_via_keyboard::*;
+pub use drag_underlay_via_mouse::*;
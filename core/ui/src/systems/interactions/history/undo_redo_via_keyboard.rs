@@ -2,19 +2,53 @@ use crate::resources::*;
 use core_lib::events::*;
 use specs::prelude::*;
 
+/// Cmd+Z undoes, Cmd+Shift+Z redoes and Cmd+Alt+Z turns undoing the viewport changes on and off
 #[derive(Default)]
 pub struct UndoRedoViaKeyboard;
 
 impl<'a> System<'a> for UndoRedoViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, HistoryEventChannel>);
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, HistoryEventChannel>);
 
-  fn run(&mut self, (input_state, mut history_event_channel): Self::SystemData) {
-    let cmd = input_state.keyboard.is_command_activated();
-    let shift = input_state.keyboard.is_shift_activated();
-    let z = input_state.keyboard.just_activated(Key::Z);
-    if cmd && z {
-      let history_event = if shift { HistoryEvent::Redo } else { HistoryEvent::Undo };
-      history_event_channel.single_write(history_event);
+  fn run(&mut self, (input_state, key_map, mut history_event_channel): Self::SystemData) {
+    if key_map.just_triggered(Action::Undo, &input_state) {
+      history_event_channel.single_write(HistoryEvent::Undo);
+    } else if key_map.just_triggered(Action::Redo, &input_state) {
+      history_event_channel.single_write(HistoryEvent::Redo);
+    } else if key_map.just_triggered(Action::ToggleViewportHistory, &input_state) {
+      history_event_channel.single_write(HistoryEvent::ToggleViewportHistory);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_toggle_viewport_history_via_keyboard() {
+    let mut world = World::new();
+    let mut undo_redo = UndoRedoViaKeyboard;
+    System::setup(&mut undo_redo, &mut world);
+    let mut reader = world.fetch_mut::<HistoryEventChannel>().register_reader();
+    let cmd = if cfg!(target_os = "macos") {
+      Key::LCommand
+    } else {
+      Key::LCtrl
+    };
+
+    // Cmd+Alt+Z toggles instead of undoing
+    for key in &[cmd, Key::LAlt, Key::Z] {
+      world.fetch_mut::<InputState>().set_key(*key, true);
+    }
+    undo_redo.run_now(&world);
+    let events = world
+      .fetch::<HistoryEventChannel>()
+      .read(&mut reader)
+      .copied()
+      .collect::<Vec<_>>();
+    match events.as_slice() {
+      [HistoryEvent::ToggleViewportHistory] => (),
+      events => panic!("expected the viewport history to be toggled, got {:?}", events),
     }
   }
 }
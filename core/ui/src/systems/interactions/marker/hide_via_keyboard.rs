@@ -6,21 +6,21 @@ use specs::prelude::*;
 pub struct HideViaKeyboard;
 
 impl<'a> System<'a> for HideViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, CommandEventChannel>);
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
 
-  fn run(&mut self, (input_state, mut command_event_channel): Self::SystemData) {
-    if input_state.keyboard.is_command_activated() {
-      if input_state.keyboard.just_activated(Key::H) {
-        let hide_event = if input_state.keyboard.is_shift_activated() {
-          HideEvent::UnhideAll
-        } else {
-          HideEvent::HideSelected
-        };
-        command_event_channel.single_write(CommandEvent {
-          command: Command::Hide(hide_event),
-          event_id: None,
-        });
-      }
-    }
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    let hide_event = if key_map.just_triggered(Action::HideSelected, &input_state) {
+      HideEvent::HideSelected
+    } else if key_map.just_triggered(Action::UnhideAll, &input_state) {
+      HideEvent::UnhideAll
+    } else if key_map.just_triggered(Action::HideConstruction, &input_state) {
+      HideEvent::HideAncestorsOfSelected
+    } else {
+      return;
+    };
+    command_event_channel.single_write(CommandEvent {
+      command: Command::Hide(hide_event),
+      event_id: None,
+    });
   }
 }
@@ -1,7 +1,11 @@
 mod hide_via_keyboard;
+mod restyle_via_keyboard;
 mod selde_all_via_keyboard;
 mod selde_via_mouse;
+mod trace_via_keyboard;
 
 pub use hide_via_keyboard::*;
+pub use restyle_via_keyboard::*;
 pub use selde_all_via_keyboard::*;
 pub use selde_via_mouse::*;
+pub use trace_via_keyboard::*;
@@ -0,0 +1,51 @@
+use crate::{resources::*, utilities::*};
+use core_lib::{components::styles::*, events::*};
+use specs::prelude::*;
+
+// The default thickness, radius and dash pattern come first so that the first press changes them
+static THICKNESSES: [f64; 4] = [2.0, 3.0, 5.0, 1.0]; // Pixel
+static POINT_RADII: [f64; 4] = [5.0, 7.0, 10.0, 3.0]; // Pixel
+static DASH_PATTERNS: [DashPattern; 4] = [
+  DashPattern::Solid,
+  DashPattern::Dashed,
+  DashPattern::Dotted,
+  DashPattern::DashDot,
+];
+
+/// Cmd+J colors the selection with the next color of the palette, Cmd+U gives it the next
+/// thickness, Cmd+Shift+U gives the selected points the next radius and Cmd+I gives the selected
+/// lines the next dash pattern
+#[derive(Default)]
+pub struct RestyleViaKeyboard {
+  color_index: usize,
+  thickness_index: usize,
+  point_radius_index: usize,
+  dash_pattern_index: usize,
+}
+
+impl<'a> System<'a> for RestyleViaKeyboard {
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
+
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    let style_command = if key_map.just_triggered(Action::NextColor, &input_state) {
+      StyleCommand::SetColor(next(&PALETTE, &mut self.color_index))
+    } else if key_map.just_triggered(Action::NextPointRadius, &input_state) {
+      StyleCommand::SetPointRadius(next(&POINT_RADII, &mut self.point_radius_index))
+    } else if key_map.just_triggered(Action::NextThickness, &input_state) {
+      StyleCommand::SetThickness(next(&THICKNESSES, &mut self.thickness_index))
+    } else if key_map.just_triggered(Action::NextDashPattern, &input_state) {
+      StyleCommand::SetDashPattern(next(&DASH_PATTERNS, &mut self.dash_pattern_index))
+    } else {
+      return;
+    };
+    command_event_channel.single_write(CommandEvent {
+      command: Command::Style(style_command),
+      event_id: None,
+    });
+  }
+}
+
+fn next<T: Copy>(values: &[T], index: &mut usize) -> T {
+  *index = (*index + 1) % values.len();
+  values[*index]
+}
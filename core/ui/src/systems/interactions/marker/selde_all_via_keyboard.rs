@@ -6,23 +6,21 @@ use specs::prelude::*;
 pub struct SeldeAllViaKeyboard;
 
 impl<'a> System<'a> for SeldeAllViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, CommandEventChannel>);
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
 
-  fn run(&mut self, (input_state, mut command_event_channel): Self::SystemData) {
-    if input_state.keyboard.is_command_activated() {
-      let maybe_selde_event = if input_state.keyboard.just_activated(Key::A) {
-        Some(SelectEvent::SelectAll)
-      } else if input_state.keyboard.just_activated(Key::D) {
-        Some(SelectEvent::DeselectAll)
-      } else {
-        None
-      };
-      if let Some(selde_event) = maybe_selde_event {
-        command_event_channel.single_write(CommandEvent {
-          command: Command::Select(selde_event),
-          event_id: None,
-        });
-      }
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    let maybe_selde_event = if key_map.just_triggered(Action::SelectAll, &input_state) {
+      Some(SelectEvent::SelectAll)
+    } else if key_map.just_triggered(Action::DeselectAll, &input_state) {
+      Some(SelectEvent::DeselectAll)
+    } else {
+      None
+    };
+    if let Some(selde_event) = maybe_selde_event {
+      command_event_channel.single_write(CommandEvent {
+        command: Command::Select(selde_event),
+        event_id: None,
+      });
     }
   }
 }
@@ -1,6 +1,10 @@
-use crate::{events::*, resources::*, utilities::hitting_object};
+use crate::{
+  events::*,
+  resources::*,
+  utilities::{hitting_freehand_stroke, hitting_object, hitting_slider, hitting_underlay_handle},
+};
 use core_lib::{
-  components::{markers::*, screen_shapes::*},
+  components::{freehand::*, markers::*, screen_shapes::*, sliders::*, symbolics::*, underlays::*},
   events::*,
   math::*,
   resources::*,
@@ -8,15 +12,16 @@ use core_lib::{
 };
 use specs::prelude::*;
 use std::collections::HashSet;
-use std::mem::drop;
 
 static SELECT_DIST_THRES: ScreenScalar = ScreenScalar(5.0); // Pixel
+static LASSO_SAMPLE_COUNT: usize = 64; // Samples on lines and curves tested against the lasso
 
 pub struct SeldeViaMouse {
   tool_change_reader: Option<ToolChangeEventReader>,
   mouse_event_reader: Option<MouseEventReader>,
   drag_start_position: Option<ScreenPosition>,
   drag_selected_new_entities: HashSet<Entity>,
+  lasso: bool,
 }
 
 impl Default for SeldeViaMouse {
@@ -26,22 +31,35 @@ impl Default for SeldeViaMouse {
       mouse_event_reader: None,
       drag_start_position: None,
       drag_selected_new_entities: HashSet::new(),
+      lasso: false,
     }
   }
 }
 
 impl<'a> System<'a> for SeldeViaMouse {
   type SystemData = (
+    Entities<'a>,
     Read<'a, InputState>,
+    Read<'a, Viewport>,
     Read<'a, ToolChangeEventChannel>,
     Write<'a, MouseEventChannel>,
     Read<'a, SpatialEntityMap>,
+    Read<'a, EntityQuadtree>,
     Write<'a, CommandEventChannel>,
     Write<'a, SelectRectangle>,
+    Write<'a, SelectLasso>,
     ReadStorage<'a, ScreenPoint>,
+    ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, ScreenLine>,
     ReadStorage<'a, ScreenCircle>,
+    ReadStorage<'a, ScreenArc>,
+    ReadStorage<'a, ScreenConic>,
     ReadStorage<'a, Selected>,
+    ReadStorage<'a, Slider>,
+    ReadStorage<'a, ImageUnderlay>,
+    ReadStorage<'a, UnderlayPlacement>,
+    ReadStorage<'a, ScreenFreehandStroke>,
+    ReadStorage<'a, Hidden>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -53,16 +71,28 @@ impl<'a> System<'a> for SeldeViaMouse {
   fn run(
     &mut self,
     (
+      entities,
       input_state,
+      viewport,
       tool_change_event_channel,
       mut mouse_event_channel,
       spatial_entity_map,
+      entity_quadtree,
       mut command_event_channel,
       mut select_rectangle,
+      mut select_lasso,
       scrn_points,
+      sym_points,
       scrn_lines,
       scrn_circles,
+      scrn_arcs,
+      scrn_conics,
       selecteds,
+      sliders,
+      underlays,
+      underlay_placements,
+      scrn_strokes,
+      hiddens,
     ): Self::SystemData,
   ) {
     // First use tool change to setup mouse event reader.
@@ -74,12 +104,7 @@ impl<'a> System<'a> for SeldeViaMouse {
           ToolChangeEvent(Tool::Select) => {
             self.mouse_event_reader = Some(mouse_event_channel.register_reader());
           }
-          _ => {
-            if let Some(reader_id) = &mut self.mouse_event_reader {
-              drop(reader_id);
-              self.mouse_event_reader = None;
-            }
-          }
+          _ => self.mouse_event_reader = None,
         }
       }
     }
@@ -89,15 +114,32 @@ impl<'a> System<'a> for SeldeViaMouse {
       for event in mouse_event_channel.read(reader_id) {
         match event {
           MouseEvent::MouseDown(mouse_pos) => {
-            // Check if hitting something
+            // Check if hitting something, the sliders, the strokes and the underlays are not in the
+            // spatial entity map
             if let Some(entity) = hitting_object(
               *mouse_pos,
-              &*spatial_entity_map,
+              &entity_quadtree,
+              &viewport,
               &scrn_points,
+              &sym_points,
               &scrn_lines,
               &scrn_circles,
               SELECT_DIST_THRES,
-            ) {
+            )
+            .or_else(|| hitting_slider(*mouse_pos, &viewport, &entities, &sliders, &hiddens, SELECT_DIST_THRES))
+            .or_else(|| hitting_freehand_stroke(*mouse_pos, &entities, &scrn_strokes, &hiddens, SELECT_DIST_THRES))
+            .or_else(|| {
+              hitting_underlay_handle(
+                *mouse_pos,
+                &viewport,
+                &entities,
+                &underlays,
+                &underlay_placements,
+                &hiddens,
+                SELECT_DIST_THRES,
+              )
+              .map(|(ent, _)| ent)
+            }) {
               // Check if shift is held
               if input_state.keyboard.is_shift_activated() {
                 // If has shift, select or deselect based on previous state
@@ -135,13 +177,36 @@ impl<'a> System<'a> for SeldeViaMouse {
             // We need the dragging begin from an empty space
             if hitting_object(
               *start_position,
-              &*spatial_entity_map,
+              &entity_quadtree,
+              &viewport,
               &scrn_points,
+              &sym_points,
               &scrn_lines,
               &scrn_circles,
               SELECT_DIST_THRES,
             )
             .is_none()
+              && hitting_slider(
+                *start_position,
+                &viewport,
+                &entities,
+                &sliders,
+                &hiddens,
+                SELECT_DIST_THRES,
+              )
+              .is_none()
+              && hitting_freehand_stroke(*start_position, &entities, &scrn_strokes, &hiddens, SELECT_DIST_THRES)
+                .is_none()
+              && hitting_underlay_handle(
+                *start_position,
+                &viewport,
+                &entities,
+                &underlays,
+                &underlay_placements,
+                &hiddens,
+                SELECT_DIST_THRES,
+              )
+              .is_none()
             {
               // If ther's no shift, clear the selection
               if !input_state.keyboard.is_shift_activated() {
@@ -153,18 +218,35 @@ impl<'a> System<'a> for SeldeViaMouse {
 
               // Setup the drag start position
               self.drag_start_position = Some(*start_position);
+
+              // Holding alt when the drag begins draws a freeform lasso instead of a rectangle
+              self.lasso = input_state.keyboard.is_alt_activated();
+              if self.lasso {
+                select_lasso.push((*start_position).into());
+              }
             }
           }
           MouseEvent::DragMove(_, curr_position) => {
             // Make sure we have start position before we set the dragging
-            if let Some(start_position) = self.drag_start_position {
+            if self.lasso {
+              // The lasso only selects when released
+              select_lasso.push((*curr_position).into());
+            } else if let Some(start_position) = self.drag_start_position {
               // Update the rectangle
               let rect = AABB::two_points(start_position.into(), (*curr_position).into());
               select_rectangle.set(rect);
 
               // Select all the elements intersecting with AABB
-              let mut new_entities =
-                get_entities_in_aabb(rect, &*spatial_entity_map, &scrn_points, &scrn_lines, &scrn_circles);
+              let mut new_entities = get_entities_in_aabb(
+                rect,
+                &*spatial_entity_map,
+                &viewport,
+                &scrn_points,
+                &scrn_lines,
+                &scrn_circles,
+                &scrn_arcs,
+                &scrn_conics,
+              );
               let mut to_remove = vec![];
               for entity in &self.drag_selected_new_entities {
                 if !new_entities.contains(entity) {
@@ -189,7 +271,28 @@ impl<'a> System<'a> for SeldeViaMouse {
               }
             }
           }
-          MouseEvent::DragEnd(_) => {
+          MouseEvent::DragEnd(curr_position) => {
+            if self.lasso {
+              select_lasso.push((*curr_position).into());
+              let polygon = select_lasso.polygon();
+              for entity in get_entities_in_polygon(
+                &polygon,
+                &*spatial_entity_map,
+                &viewport,
+                &scrn_points,
+                &scrn_lines,
+                &scrn_circles,
+                &scrn_arcs,
+                &scrn_conics,
+              ) {
+                command_event_channel.single_write(CommandEvent {
+                  command: Command::Select(SelectEvent::Select(entity)),
+                  event_id: None,
+                });
+              }
+              self.lasso = false;
+              select_lasso.clear();
+            }
             self.drag_start_position = None;
             self.drag_selected_new_entities.clear();
             select_rectangle.clear();
@@ -201,17 +304,28 @@ impl<'a> System<'a> for SeldeViaMouse {
   }
 }
 
+/// The area of virtual space shown in the area of the screen, the y axis going the other way
+fn virtual_aabb(aabb: AABB, viewport: &Viewport) -> AABB {
+  AABB::two_points(
+    ScreenPosition(aabb.min()).to_virtual(viewport).0,
+    ScreenPosition(aabb.max()).to_virtual(viewport).0,
+  )
+}
+
 fn get_entities_in_aabb<'a>(
   aabb: AABB,
   spatial_entity_map: &SpatialEntityMap,
+  viewport: &Viewport,
   scrn_points: &ReadStorage<'a, ScreenPoint>,
   scrn_lines: &ReadStorage<'a, ScreenLine>,
   scrn_circles: &ReadStorage<'a, ScreenCircle>,
+  scrn_arcs: &ReadStorage<'a, ScreenArc>,
+  scrn_conics: &ReadStorage<'a, ScreenConic>,
 ) -> HashSet<Entity> {
   let mut result = HashSet::new();
 
   // Loop through all potential neighbors
-  for entity in spatial_entity_map.get_entities_near_aabb(aabb) {
+  for entity in spatial_entity_map.get_entities_near_aabb(virtual_aabb(aabb, viewport)) {
     if let Some(point) = scrn_points.get(entity) {
       if aabb.contains((*point).into()) {
         result.insert(entity);
@@ -226,6 +340,74 @@ fn get_entities_in_aabb<'a>(
       if circle.intersect(aabb).is_some() {
         result.insert(entity);
       }
+    } else if let Some(arc) = scrn_arcs.get(entity) {
+      let arc: Arc = (*arc).into();
+      if arc.intersect(aabb).is_some() {
+        result.insert(entity);
+      }
+    } else if let Some(conic) = scrn_conics.get(entity) {
+      let ellipse: Ellipse = (*conic).into();
+      if ellipse.intersect(aabb).is_some() {
+        result.insert(entity);
+      }
+    }
+  }
+
+  result
+}
+
+/// Points are selected when inside the polygon, lines and curves when any of their sample points
+/// is inside
+fn get_entities_in_polygon<'a>(
+  polygon: &Polygon,
+  spatial_entity_map: &SpatialEntityMap,
+  viewport: &Viewport,
+  scrn_points: &ReadStorage<'a, ScreenPoint>,
+  scrn_lines: &ReadStorage<'a, ScreenLine>,
+  scrn_circles: &ReadStorage<'a, ScreenCircle>,
+  scrn_arcs: &ReadStorage<'a, ScreenArc>,
+  scrn_conics: &ReadStorage<'a, ScreenConic>,
+) -> HashSet<Entity> {
+  let mut result = HashSet::new();
+  let aabb = polygon.aabb();
+
+  // Loop through all potential neighbors
+  for entity in spatial_entity_map.get_entities_near_aabb(virtual_aabb(aabb, viewport)) {
+    if let Some(point) = scrn_points.get(entity) {
+      if polygon.contains((*point).into()) {
+        result.insert(entity);
+      }
+    } else if let Some(line) = scrn_lines.get(entity) {
+      let line: Line = (*line).into();
+      if let Some((from, to)) = line.intersect(aabb) {
+        if (0..=LASSO_SAMPLE_COUNT)
+          .any(|i| polygon.contains(from + (to - from) * (i as f64 / LASSO_SAMPLE_COUNT as f64)))
+        {
+          result.insert(entity);
+        }
+      }
+    } else if let Some(circle) = scrn_circles.get(entity) {
+      let circle: Circle = (*circle).into();
+      if (0..LASSO_SAMPLE_COUNT).any(|i| {
+        let theta = i as f64 / LASSO_SAMPLE_COUNT as f64 * 2.0 * std::f64::consts::PI;
+        polygon.contains(circle.center + vec2![theta.cos(), theta.sin()] * circle.radius)
+      }) {
+        result.insert(entity);
+      }
+    } else if let Some(arc) = scrn_arcs.get(entity) {
+      let arc: Arc = (*arc).into();
+      if (0..=LASSO_SAMPLE_COUNT)
+        .any(|i| polygon.contains(arc.point_at_angle(arc.start + arc.sweep * i as f64 / LASSO_SAMPLE_COUNT as f64)))
+      {
+        result.insert(entity);
+      }
+    } else if let Some(conic) = scrn_conics.get(entity) {
+      let ellipse: Ellipse = (*conic).into();
+      if (0..LASSO_SAMPLE_COUNT)
+        .any(|i| polygon.contains(ellipse.point_at(i as f64 / LASSO_SAMPLE_COUNT as f64 * 2.0 * std::f64::consts::PI)))
+      {
+        result.insert(entity);
+      }
     }
   }
 
@@ -0,0 +1,24 @@
+use crate::resources::*;
+use core_lib::events::*;
+use specs::prelude::*;
+
+#[derive(Default)]
+pub struct TraceViaKeyboard;
+
+impl<'a> System<'a> for TraceViaKeyboard {
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
+
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    let trace_event = if key_map.just_triggered(Action::ToggleTrace, &input_state) {
+      TraceEvent::ToggleSelected
+    } else if key_map.just_triggered(Action::ClearTraces, &input_state) {
+      TraceEvent::Clear
+    } else {
+      return;
+    };
+    command_event_channel.single_write(CommandEvent {
+      command: Command::Trace(trace_event),
+      event_id: None,
+    });
+  }
+}
@@ -1,6 +1,11 @@
+pub mod animation;
+pub mod clipboard;
+pub mod debug;
 pub mod exit;
+pub mod file;
 pub mod geometry;
 pub mod history;
 pub mod marker;
+pub mod theme;
 pub mod tool;
 pub mod viewport;
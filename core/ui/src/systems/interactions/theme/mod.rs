@@ -0,0 +1,3 @@
+mod toggle_theme_via_keyboard;
+
+pub use toggle_theme_via_keyboard::*;
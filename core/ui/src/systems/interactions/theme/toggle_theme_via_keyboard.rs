@@ -0,0 +1,20 @@
+use crate::resources::*;
+use core_lib::events::*;
+use specs::prelude::*;
+
+/// Cmd+Shift+N switches between the light and the dark themes
+#[derive(Default)]
+pub struct ToggleThemeViaKeyboard;
+
+impl<'a> System<'a> for ToggleThemeViaKeyboard {
+  type SystemData = (Read<'a, InputState>, Read<'a, KeyMap>, Write<'a, CommandEventChannel>);
+
+  fn run(&mut self, (input_state, key_map, mut command_event_channel): Self::SystemData) {
+    if key_map.just_triggered(Action::ToggleTheme, &input_state) {
+      command_event_channel.single_write(CommandEvent {
+        command: Command::ToggleTheme,
+        event_id: None,
+      });
+    }
+  }
+}
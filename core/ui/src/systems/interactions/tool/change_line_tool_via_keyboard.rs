@@ -8,18 +8,21 @@ pub struct ChangeLineToolViaKeyboard;
 impl<'a> System<'a> for ChangeLineToolViaKeyboard {
   type SystemData = (
     Read<'a, InputState>,
+    Read<'a, KeyMap>,
     Read<'a, ToolState>,
     Write<'a, ToolChangeEventChannel>,
   );
 
-  fn run(&mut self, (input_state, tool_state, mut tool_change_event_channel): Self::SystemData) {
+  fn run(&mut self, (input_state, key_map, tool_state, mut tool_change_event_channel): Self::SystemData) {
     match tool_state.get() {
-      Tool::Line(_) => {
-        if input_state.keyboard.just_activated(Key::D1) {
+      Tool::Line(line_type) => {
+        if key_map.just_triggered(Action::NextLineType, &input_state) {
+          tool_change_event_channel.single_write(ToolChangeEvent(Tool::Line(line_type.next())));
+        } else if key_map.just_triggered(Action::StraightLineType, &input_state) {
           tool_change_event_channel.single_write(ToolChangeEvent(Tool::Line(LineType::Straight)));
-        } else if input_state.keyboard.just_activated(Key::D2) {
+        } else if key_map.just_triggered(Action::RayLineType, &input_state) {
           tool_change_event_channel.single_write(ToolChangeEvent(Tool::Line(LineType::Ray)));
-        } else if input_state.keyboard.just_activated(Key::D3) {
+        } else if key_map.just_triggered(Action::SegmentLineType, &input_state) {
           tool_change_event_channel.single_write(ToolChangeEvent(Tool::Line(LineType::Segment)));
         }
       }
@@ -27,3 +30,57 @@ impl<'a> System<'a> for ChangeLineToolViaKeyboard {
     }
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::systems::{interactions::geometry::line::CreateLineViaMouse, state_managers::ToolStateManager};
+  use core_lib::{components::symbolics::*, events::*};
+
+  #[test]
+  fn test_tab_changes_the_type_of_the_next_line() {
+    let mut world = World::new();
+    let mut change_line_tool = ChangeLineToolViaKeyboard;
+    let mut tool_state_manager = ToolStateManager::default();
+    let mut create_line = CreateLineViaMouse::default();
+    System::setup(&mut change_line_tool, &mut world);
+    System::setup(&mut tool_state_manager, &mut world);
+    System::setup(&mut create_line, &mut world);
+    let mut reader = world.fetch_mut::<CommandEventChannel>().register_reader();
+    let mut frame = |world: &mut World| {
+      change_line_tool.run_now(world);
+      tool_state_manager.run_now(world);
+      create_line.run_now(world);
+      world.fetch_mut::<InputState>().reset_relative_data();
+    };
+
+    world
+      .fetch_mut::<ToolChangeEventChannel>()
+      .single_write(ToolChangeEvent(Tool::Line(LineType::Straight)));
+    frame(&mut world);
+    let (p1, p2) = (world.create_entity().build(), world.create_entity().build());
+    for expected in &[SymbolicLine::Segment(p1, p2), SymbolicLine::Ray(p1, p2)] {
+      // Tab is pressed then released
+      world.fetch_mut::<InputState>().keyboard.set(Key::Tab, true);
+      frame(&mut world);
+      world.fetch_mut::<InputState>().keyboard.set(Key::Tab, false);
+      frame(&mut world);
+
+      // The line between the next two points clicked
+      let mut active_point_event_channel = world.fetch_mut::<ActivePointEventChannel>();
+      active_point_event_channel.single_write(ActivePointEvent(p1));
+      active_point_event_channel.single_write(ActivePointEvent(p2));
+      drop(active_point_event_channel);
+      frame(&mut world);
+      let commands = world
+        .fetch::<CommandEventChannel>()
+        .read(&mut reader)
+        .map(|event| event.command.clone())
+        .collect::<Vec<_>>();
+      match commands.as_slice() {
+        [Command::LineInsert(InsertLineEvent::InsertLine(sym_line))] => assert_eq!(sym_line, expected),
+        _ => panic!("Expected a line insertion, got {:?}", commands),
+      }
+    }
+  }
+}
@@ -1,24 +1,54 @@
 use crate::{events::*, resources::*};
-use core_lib::math::*;
+use core_lib::{math::*, resources::MacroRegistry};
 use specs::prelude::*;
 
 #[derive(Default)]
 pub struct ChangeToolViaKeyboard;
 
 impl<'a> System<'a> for ChangeToolViaKeyboard {
-  type SystemData = (Read<'a, InputState>, Write<'a, ToolChangeEventChannel>);
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Read<'a, ToolState>,
+    Read<'a, MacroRegistry>,
+    Write<'a, ToolChangeEventChannel>,
+  );
 
-  fn run(&mut self, (input_state, mut tool_change_event_channel): Self::SystemData) {
-    if input_state.keyboard.just_activated(Key::S) {
-      tool_change_event_channel.single_write(ToolChangeEvent(Tool::Select));
-    } else if input_state.keyboard.just_activated(Key::V) {
-      tool_change_event_channel.single_write(ToolChangeEvent(Tool::Viewport));
-    } else if input_state.keyboard.just_activated(Key::P) {
-      tool_change_event_channel.single_write(ToolChangeEvent(Tool::Point));
-    } else if input_state.keyboard.just_activated(Key::L) {
-      tool_change_event_channel.single_write(ToolChangeEvent(Tool::Line(LineType::Straight)));
-    } else if input_state.keyboard.just_activated(Key::C) {
-      tool_change_event_channel.single_write(ToolChangeEvent(Tool::Circle));
+  fn run(
+    &mut self,
+    (input_state, key_map, tool_state, macro_registry, mut tool_change_event_channel): Self::SystemData,
+  ) {
+    let tools = [
+      (Action::SelectTool, Tool::Select),
+      (Action::ViewportTool, Tool::Viewport),
+      (Action::PointTool, Tool::Point),
+      (Action::LineTool, Tool::Line(LineType::Straight)),
+      (Action::CircleTool, Tool::Circle),
+      (Action::PolygonTool, Tool::Polygon),
+      (Action::RotateTool, Tool::Rotate),
+      (Action::ScaleTool, Tool::Scale),
+      (Action::MirrorTool, Tool::Mirror),
+      (Action::RatioTool, Tool::Ratio),
+      (Action::TextTool, Tool::Text),
+      (Action::SliderTool, Tool::Slider),
+      (Action::FreehandTool, Tool::Freehand),
+    ];
+    if let Some((_, tool)) = tools
+      .iter()
+      .find(|(action, _)| key_map.just_triggered(*action, &input_state))
+    {
+      tool_change_event_channel.single_write(ToolChangeEvent(*tool));
+    } else if key_map.just_triggered(Action::MacroTool, &input_state) {
+      // The last defined macro first, then the ones before it
+      let mut ids = macro_registry.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+      ids.reverse();
+      let next = match tool_state.get() {
+        Tool::Macro(current) => ids.iter().skip_while(|id| **id != current).nth(1),
+        _ => None,
+      };
+      if let Some(id) = next.or_else(|| ids.first()) {
+        tool_change_event_channel.single_write(ToolChangeEvent(Tool::Macro(*id)));
+      }
     }
   }
 }
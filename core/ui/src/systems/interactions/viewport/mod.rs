@@ -1,5 +1,11 @@
 mod move_viewport_via_scroll;
+mod toggle_grid_via_keyboard;
+mod toggle_measure_overlays_via_keyboard;
 mod viewport_drag_tool;
+mod zoom_viewport_via_scroll;
 
 pub use move_viewport_via_scroll::*;
+pub use toggle_grid_via_keyboard::*;
+pub use toggle_measure_overlays_via_keyboard::*;
 pub use viewport_drag_tool::*;
+pub use zoom_viewport_via_scroll::*;
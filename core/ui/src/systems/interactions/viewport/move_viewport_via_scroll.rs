@@ -1,9 +1,11 @@
 use crate::{events::*, resources::*};
-use core_lib::{events::*, math::*};
+use core_lib::{events::*, math::*, resources::*, utilities::*};
 use specs::prelude::*;
 
 static SPEED: f64 = 1.0;
 
+/// Scrolling moves the viewport, except with the viewport tool. Two fingers moving together on a
+/// touch screen drag it along with any tool
 pub struct MoveViewportViaScroll {
   tool_change_event_reader: Option<ToolChangeEventReader>,
   can_scroll: bool,
@@ -23,6 +25,8 @@ impl<'a> System<'a> for MoveViewportViaScroll {
     Read<'a, InputState>,
     Read<'a, DeltaTime>,
     Read<'a, ToolChangeEventChannel>,
+    Read<'a, RatioPoint>,
+    Read<'a, Viewport>,
     Write<'a, ViewportEventChannel>,
   );
 
@@ -33,7 +37,7 @@ impl<'a> System<'a> for MoveViewportViaScroll {
 
   fn run(
     &mut self,
-    (input_state, delta_time, tool_change_event_channel, mut viewport_event_channel): Self::SystemData,
+    (input_state, delta_time, tool_change_event_channel, ratio_point, viewport, mut viewport_event_channel): Self::SystemData,
   ) {
     if let Some(reader) = &mut self.tool_change_event_reader {
       for ToolChangeEvent(tool) in tool_change_event_channel.read(reader) {
@@ -44,7 +48,9 @@ impl<'a> System<'a> for MoveViewportViaScroll {
       }
     }
 
-    if self.can_scroll {
+    // Scrolling while holding Cmd zooms instead, and adjusts the ratio of the point being placed
+    // with the ratio tool
+    if self.can_scroll && !input_state.keyboard.is_command_activated() && ratio_point.get_points().is_none() {
       if !input_state.rel_scroll.is_zero() {
         let raw_movement = input_state.rel_scroll * delta_time.get() * SPEED;
         let movement = if cfg!(target_os = "macos") {
@@ -55,5 +61,12 @@ impl<'a> System<'a> for MoveViewportViaScroll {
         viewport_event_channel.single_write(ViewportEvent::Move(movement));
       }
     }
+
+    // Like dragging with the viewport tool, the sketch follows the fingers
+    let ScreenPosition(pan) = input_state.rel_pan;
+    if !pan.is_zero() {
+      let movement = vec2![-pan.x, pan.y] * viewport.virtual_to_screen_scale();
+      viewport_event_channel.single_write(ViewportEvent::Move(movement));
+    }
   }
 }
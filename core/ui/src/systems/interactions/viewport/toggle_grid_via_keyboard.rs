@@ -0,0 +1,26 @@
+use crate::resources::*;
+use specs::prelude::*;
+
+/// Cmd+' shows the grid, Cmd+Shift+' turns snapping to the grid on and off, and Cmd+; shows
+/// the axes
+#[derive(Default)]
+pub struct ToggleGridViaKeyboard;
+
+impl<'a> System<'a> for ToggleGridViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Write<'a, GridSettings>,
+    Write<'a, SnapSettings>,
+  );
+
+  fn run(&mut self, (input_state, key_map, mut grid_settings, mut snap_settings): Self::SystemData) {
+    if key_map.just_triggered(Action::ToggleGridSnapping, &input_state) {
+      snap_settings.toggle(SnapCategory::Grid);
+    } else if key_map.just_triggered(Action::ToggleGrid, &input_state) {
+      grid_settings.visible = !grid_settings.visible;
+    } else if key_map.just_triggered(Action::ToggleAxes, &input_state) {
+      grid_settings.axes = !grid_settings.axes;
+    }
+  }
+}
@@ -0,0 +1,36 @@
+use crate::resources::*;
+use core_lib::components::{markers::*, symbolics::*};
+use specs::prelude::*;
+
+/// Cmd+R shows the ruler. Cmd+Shift+R drops the protractor at the selected point, or picks it up
+/// when it is already there or when no single point is selected
+#[derive(Default)]
+pub struct ToggleMeasureOverlaysViaKeyboard;
+
+impl<'a> System<'a> for ToggleMeasureOverlaysViaKeyboard {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, KeyMap>,
+    Write<'a, MeasureOverlays>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, Selected>,
+  );
+
+  fn run(&mut self, (entities, input_state, key_map, mut measure_overlays, sym_points, selecteds): Self::SystemData) {
+    if key_map.just_triggered(Action::ToggleProtractor, &input_state) {
+      let mut selected = (&entities, &selecteds).join().map(|(ent, _)| ent);
+      let point = match (selected.next(), selected.next()) {
+        (Some(ent), None) if sym_points.contains(ent) => Some(ent),
+        _ => None,
+      };
+      measure_overlays.protractor = if point == measure_overlays.protractor {
+        None
+      } else {
+        point
+      };
+    } else if key_map.just_triggered(Action::ToggleRuler, &input_state) {
+      measure_overlays.ruler = !measure_overlays.ruler;
+    }
+  }
+}
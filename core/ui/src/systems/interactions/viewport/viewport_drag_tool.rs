@@ -2,8 +2,6 @@ use crate::{events::*, resources::*};
 use core_lib::{events::*, math::*, resources::*};
 use specs::prelude::*;
 
-static SPEED: f64 = 1.0;
-
 pub struct ViewportDragTool {
   tool_change_event_reader: Option<ToolChangeEventReader>,
   mouse_event_reader: Option<MouseEventReader>,
@@ -20,8 +18,6 @@ impl Default for ViewportDragTool {
 
 impl<'a> System<'a> for ViewportDragTool {
   type SystemData = (
-    Read<'a, InputState>,
-    Read<'a, DeltaTime>,
     Read<'a, Viewport>,
     Read<'a, ToolChangeEventChannel>,
     Write<'a, MouseEventChannel>,
@@ -35,14 +31,7 @@ impl<'a> System<'a> for ViewportDragTool {
 
   fn run(
     &mut self,
-    (
-            input_state,
-            delta_time,
-            viewport,
-            tool_change_event_channel,
-            mut mouse_event_channel,
-            mut viewport_event_channel,
-        ): Self::SystemData,
+    (viewport, tool_change_event_channel, mut mouse_event_channel, mut viewport_event_channel): Self::SystemData,
   ) {
     // Register mouse event reader in regards to tool change
     if let Some(reader) = &mut self.tool_change_event_reader {
@@ -53,19 +42,14 @@ impl<'a> System<'a> for ViewportDragTool {
               self.mouse_event_reader = Some(mouse_event_channel.register_reader());
             }
           }
-          _ => {
-            if let Some(reader) = &mut self.mouse_event_reader {
-              std::mem::drop(reader);
-              self.mouse_event_reader = None;
-            }
-          }
+          _ => self.mouse_event_reader = None,
         }
       }
     }
 
-    // Read mouse events. If so then that means we are using viewport tool now
+    // Read mouse events. If so then that means we are using viewport tool now, the scroll zooms
+    // which is handled by `ZoomViewportViaScroll`
     if let Some(reader) = &mut self.mouse_event_reader {
-      // First handle drag event
       for event in mouse_event_channel.read(reader) {
         match event {
           MouseEvent::DragMove(rel_pos, _) => {
@@ -76,16 +60,6 @@ impl<'a> System<'a> for ViewportDragTool {
           _ => (),
         }
       }
-
-      // Then handle scroll. Note that this scale is mouse position dependent
-      if input_state.rel_scroll.y != 0.0 {
-        let m: Vector2 = input_state.mouse_abs_pos.into();
-        let delta = input_state.rel_scroll.y * delta_time.get() * SPEED;
-        let dcx = (m.x - viewport.half_screen_width()) * -delta / viewport.screen_width();
-        let dcy = (viewport.half_screen_height() - m.y) * -delta / viewport.screen_width();
-        viewport_event_channel.single_write(ViewportEvent::Scale(delta));
-        viewport_event_channel.single_write(ViewportEvent::Move(vec2![dcx, dcy]));
-      }
     }
   }
 }
@@ -0,0 +1,86 @@
+use crate::resources::*;
+use core_lib::{events::*, resources::*, utilities::*};
+use specs::prelude::*;
+
+static ZOOM_PER_SCROLL: f64 = 1.1; // Factor of a scroll of one unit
+
+/// Scrolling zooms with the viewport tool or while holding Cmd, pinching on a trackpad always
+/// zooms, as does pinching with two fingers on a touch screen. The zoom is around the cursor, or
+/// around the middle of the fingers, the position under it stays in place
+#[derive(Default)]
+pub struct ZoomViewportViaScroll;
+
+impl<'a> System<'a> for ZoomViewportViaScroll {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, ToolState>,
+    Read<'a, Viewport>,
+    Write<'a, ViewportEventChannel>,
+  );
+
+  fn run(&mut self, (input_state, tool_state, viewport, mut viewport_event_channel): Self::SystemData) {
+    let mut factor = 1.0 + input_state.rel_pinch;
+    let scroll_zooms = match tool_state.get() {
+      Tool::Viewport => true,
+      _ => input_state.keyboard.is_command_activated(),
+    };
+    if scroll_zooms {
+      factor *= ZOOM_PER_SCROLL.powf(-input_state.rel_scroll.y);
+    }
+    if factor > 0.0 && factor != 1.0 {
+      let center = input_state.gesture_center().unwrap_or(input_state.mouse_abs_pos);
+      let VirtualPosition(anchor) = center.to_virtual(&*viewport);
+      viewport_event_channel.single_write(ViewportEvent::Zoom(factor, anchor));
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  #[test]
+  fn test_zoom_around_the_cursor_only_with_the_viewport_tool_or_cmd() {
+    let mut world = World::new();
+    let mut system = ZoomViewportViaScroll::default();
+    System::setup(&mut system, &mut world);
+    let mut reader = world.fetch_mut::<ViewportEventChannel>().register_reader();
+    let zooms = |world: &World, reader: &mut ViewportEventReader| {
+      world
+        .fetch::<ViewportEventChannel>()
+        .read(reader)
+        .filter_map(|event| match event {
+          ViewportEvent::Zoom(factor, anchor) => Some((*factor, *anchor)),
+          _ => None,
+        })
+        .collect::<Vec<_>>()
+    };
+
+    // Scrolling with the point tool pans instead
+    world.fetch_mut::<InputState>().rel_scroll = vec2![0., -1.];
+    world.fetch_mut::<InputState>().mouse_abs_pos = vec2![480. + 48., 360.].into();
+    system.run_now(&world);
+    assert!(zooms(&world, &mut reader).is_empty());
+
+    world.fetch_mut::<ToolState>().set(Tool::Viewport);
+    system.run_now(&world);
+    let zoomed = zooms(&world, &mut reader);
+    assert_eq!(zoomed.len(), 1);
+    assert!((zoomed[0].0 - ZOOM_PER_SCROLL).abs() < 1e-9);
+    assert_eq!((zoomed[0].1.x, zoomed[0].1.y), (1., 0.));
+
+    // Pinching with two fingers zooms around their middle rather than the cursor
+    let mut input_state = world.fetch_mut::<InputState>();
+    input_state.reset_relative_data();
+    input_state.touch(0, TouchPhase::Start, vec2![480. - 96., 360.].into());
+    input_state.touch(1, TouchPhase::Start, vec2![480. - 48., 360.].into());
+    input_state.touch(1, TouchPhase::Move, vec2![480., 360.].into());
+    drop(input_state);
+    system.run_now(&world);
+    let zoomed = zooms(&world, &mut reader);
+    assert_eq!(zoomed.len(), 1);
+    assert!((zoomed[0].0 - 2.).abs() < 1e-9);
+    assert_eq!((zoomed[0].1.x, zoomed[0].1.y), (-1., 0.));
+  }
+}
@@ -0,0 +1,57 @@
+use crate::resources::*;
+use core_lib::{
+  components::{freehand::*, styles::*},
+  resources::*,
+};
+use specs::prelude::*;
+
+/// Previews the stroke being drawn with the freehand tool, before the pen is lifted
+pub struct FreehandDraftRenderer {
+  freehand_draft_entity: Option<Entity>,
+}
+
+impl Default for FreehandDraftRenderer {
+  fn default() -> Self {
+    Self {
+      freehand_draft_entity: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for FreehandDraftRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, FreehandDraft>,
+    Read<'a, DefaultLineStyle>,
+    WriteStorage<'a, ScreenFreehandStroke>,
+    WriteStorage<'a, LineStyle>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, viewport, freehand_draft, default_line_style, mut scrn_strokes, mut line_styles): Self::SystemData,
+  ) {
+    // First make sure we have an entity for rendering the draft
+    let ent = match self.freehand_draft_entity {
+      Some(ent) => ent,
+      None => {
+        let ent = entities.create();
+        self.freehand_draft_entity = Some(ent);
+        ent
+      }
+    };
+
+    // Then we render it when presented
+    if let Some(stroke) = &freehand_draft.stroke {
+      if let Err(err) = scrn_strokes.insert(ent, stroke.clone().to_screen(&*viewport)) {
+        panic!(err)
+      }
+      if let Err(err) = line_styles.insert(ent, default_line_style.get()) {
+        panic!(err)
+      }
+    } else {
+      scrn_strokes.remove(ent);
+    }
+  }
+}
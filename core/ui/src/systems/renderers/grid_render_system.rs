@@ -0,0 +1,142 @@
+use crate::resources::*;
+use core_lib::{
+  components::{markers::*, screen_shapes::*, styles::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Draws the grid lines and the axes with screen only lines marked as background, so that they
+/// go under all the geometry. The lines are only rewritten when they change
+pub struct GridRenderSystem {
+  line_entities: Vec<Entity>,
+  drawn: Option<(Vec<(ScreenLine, bool)>, Color, Color)>,
+}
+
+impl Default for GridRenderSystem {
+  fn default() -> Self {
+    Self {
+      line_entities: vec![],
+      drawn: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for GridRenderSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GridSettings>,
+    Read<'a, Viewport>,
+    Read<'a, Theme>,
+    WriteStorage<'a, ScreenLine>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, Background>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, grid_settings, viewport, theme, mut scrn_lines, mut line_styles, mut backgrounds): Self::SystemData,
+  ) {
+    let drawn = (grid_lines(&grid_settings, &viewport), theme.grid, theme.line);
+    if self.drawn.as_ref() == Some(&drawn) {
+      return;
+    }
+    let (lines, _, _) = &drawn;
+
+    for (i, (line, is_axis)) in lines.iter().enumerate() {
+      // Reuse the line entities created before
+      let ent = if i < self.line_entities.len() {
+        self.line_entities[i]
+      } else {
+        let ent = entities.create();
+        if let Err(err) = backgrounds.insert(ent, Background) {
+          panic!(err)
+        }
+        self.line_entities.push(ent);
+        ent
+      };
+      let style = LineStyle {
+        color: if *is_axis {
+          theme.line.apply_alpha(0.6)
+        } else {
+          theme.grid
+        },
+        width: if *is_axis { 1.5 } else { 1.0 },
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: 1.0,
+      };
+      if let Err(err) = scrn_lines.insert(ent, *line) {
+        panic!(err)
+      }
+      if let Err(err) = line_styles.insert(ent, style) {
+        panic!(err)
+      }
+    }
+
+    // Hide the lines that are not needed anymore
+    for ent in &self.line_entities[lines.len()..] {
+      scrn_lines.remove(*ent);
+    }
+    self.drawn = Some(drawn);
+  }
+}
+
+/// The grid lines in the viewport along with whether each of them is an axis
+fn grid_lines(grid_settings: &GridSettings, viewport: &Viewport) -> Vec<(ScreenLine, bool)> {
+  let mut lines = vec![];
+  let line = |from: Vector2, to: Vector2| ScreenLine {
+    from: VirtualPosition(from).to_screen(viewport),
+    to: VirtualPosition(to).to_screen(viewport),
+    line_type: LineType::Straight,
+  };
+  if grid_settings.visible {
+    let spacing = grid_settings.spacing_in(viewport);
+    let (i_min, i_max) = (
+      (viewport.x_min() / spacing).ceil() as i64,
+      (viewport.x_max() / spacing).floor() as i64,
+    );
+    for i in (i_min..=i_max).filter(|i| *i != 0 || !grid_settings.axes) {
+      let x = i as f64 * spacing;
+      lines.push((line(vec2![x, 0.], vec2![x, 1.]), false));
+    }
+    let (j_min, j_max) = (
+      (viewport.y_min() / spacing).ceil() as i64,
+      (viewport.y_max() / spacing).floor() as i64,
+    );
+    for j in (j_min..=j_max).filter(|j| *j != 0 || !grid_settings.axes) {
+      let y = j as f64 * spacing;
+      lines.push((line(vec2![0., y], vec2![1., y]), false));
+    }
+  }
+  if grid_settings.axes {
+    lines.push((line(vec2![0., 0.], vec2![1., 0.]), true));
+    lines.push((line(vec2![0., 0.], vec2![0., 1.]), true));
+  }
+  lines
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_grid_lines_cover_the_viewport() {
+    let mut grid_settings = GridSettings::default();
+    let viewport = Viewport::default();
+    assert!(grid_lines(&grid_settings, &viewport).is_empty());
+
+    // The default viewport is 20 by 15 units around the origin
+    grid_settings.visible = true;
+    let lines = grid_lines(&grid_settings, &viewport);
+    assert_eq!(lines.len(), 21 + 15);
+    assert!(lines.iter().all(|(_, is_axis)| !is_axis));
+
+    // The axes replace the grid lines through the origin
+    grid_settings.axes = true;
+    let lines = grid_lines(&grid_settings, &viewport);
+    assert_eq!(lines.len(), 21 + 15);
+    assert_eq!(lines.iter().filter(|(_, is_axis)| *is_axis).count(), 2);
+  }
+}
@@ -1,9 +1,33 @@
+mod freehand_draft_renderer;
+mod grid_render_system;
+mod protractor_renderer;
+mod ratio_point_renderer;
+mod ruler_renderer;
+mod select_lasso_renderer;
 mod select_rectangle_renderer;
+mod slider_renderer;
 mod snap_circle_renderer;
 mod snap_line_renderer;
 mod snap_point_renderer;
+mod snap_polygon_renderer;
+mod spatial_hash_overlay_renderer;
+mod trace_renderer;
+mod typing_text_renderer;
+mod underlay_renderer;
 
+pub use freehand_draft_renderer::*;
+pub use grid_render_system::*;
+pub use protractor_renderer::*;
+pub use ratio_point_renderer::*;
+pub use ruler_renderer::*;
+pub use select_lasso_renderer::*;
 pub use select_rectangle_renderer::*;
+pub use slider_renderer::*;
 pub use snap_circle_renderer::*;
 pub use snap_line_renderer::*;
 pub use snap_point_renderer::*;
+pub use snap_polygon_renderer::*;
+pub use spatial_hash_overlay_renderer::*;
+pub use trace_renderer::*;
+pub use typing_text_renderer::*;
+pub use underlay_renderer::*;
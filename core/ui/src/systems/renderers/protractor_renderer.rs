@@ -0,0 +1,250 @@
+use crate::resources::*;
+use core_lib::{
+  components::{markers::*, screen_shapes::*, styles::*, virtual_shapes::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+use std::f64::consts::PI;
+
+static DIAL_RADIUS: f64 = 60.0; // Pixel
+static LABEL_DISTANCE: f64 = 80.0; // Pixel, from the center to the angle labels
+static INCIDENCE_TOLERANCE: f64 = 1e-6;
+
+/// Draws the protractor at the point it was dropped at: a dial with a tick every 10 degrees, and
+/// the angles between the lines through the point, each one labelled along its bisector
+pub struct ProtractorRenderer {
+  dial_entity: Option<Entity>,
+  tick_entities: Vec<Entity>,
+  label_entities: Vec<Entity>,
+}
+
+impl Default for ProtractorRenderer {
+  fn default() -> Self {
+    Self {
+      dial_entity: None,
+      tick_entities: vec![],
+      label_entities: vec![],
+    }
+  }
+}
+
+impl<'a> System<'a> for ProtractorRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, MeasureOverlays>,
+    Read<'a, Viewport>,
+    Read<'a, Theme>,
+    ReadStorage<'a, VirtualPoint>,
+    ReadStorage<'a, VirtualLine>,
+    ReadStorage<'a, Hidden>,
+    WriteStorage<'a, ScreenCircle>,
+    WriteStorage<'a, CircleStyle>,
+    WriteStorage<'a, ScreenLine>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, ScreenText>,
+    WriteStorage<'a, TextStyle>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      measure_overlays,
+      viewport,
+      theme,
+      virt_points,
+      virt_lines,
+      hiddens,
+      mut scrn_circles,
+      mut circle_styles,
+      mut scrn_lines,
+      mut line_styles,
+      mut scrn_texts,
+      mut text_styles,
+    ): Self::SystemData,
+  ) {
+    let dial = *self.dial_entity.get_or_insert_with(|| entities.create());
+    let center = measure_overlays
+      .protractor
+      .filter(|ent| !hiddens.contains(*ent))
+      .and_then(|ent| virt_points.get(ent));
+    let center = match center {
+      Some(center) => *center,
+      None => {
+        // The protractor is put away, or its point is gone
+        scrn_circles.remove(dial);
+        for ent in self.tick_entities.iter().chain(&self.label_entities) {
+          scrn_lines.remove(*ent);
+          scrn_texts.remove(*ent);
+        }
+        return;
+      }
+    };
+
+    let line_style = LineStyle {
+      color: theme.line,
+      width: 1.0,
+      marks: EqualityMarks::default(),
+      dash: DashPattern::Solid,
+      alpha: 0.8,
+    };
+    let scrn_center = center.to_screen(&viewport);
+    let scrn_dial = ScreenCircle {
+      center: scrn_center,
+      radius: ScreenScalar(DIAL_RADIUS),
+    };
+    let dial_style = CircleStyle {
+      fill: theme.overlay.apply_alpha(0.15),
+      border: line_style,
+      alpha: 1.0,
+    };
+    if let Err(err) = scrn_circles.insert(dial, scrn_dial) {
+      panic!(err)
+    }
+    if let Err(err) = circle_styles.insert(dial, dial_style) {
+      panic!(err)
+    }
+
+    // Angles are counterclockwise in the virtual space, where the y axis goes up
+    let on_screen =
+      |angle: f64, distance: f64| scrn_center + ScreenPosition(vec2![angle.cos(), -angle.sin()] * distance);
+    for i in 0..36 {
+      if i >= self.tick_entities.len() {
+        self.tick_entities.push(entities.create());
+      }
+      let angle = (i * 10) as f64 * PI / 180.;
+      let length = if i % 3 == 0 { 12. } else { 6. };
+      let tick = ScreenLine {
+        from: on_screen(angle, DIAL_RADIUS - length),
+        to: on_screen(angle, DIAL_RADIUS),
+        line_type: LineType::Segment,
+      };
+      if let Err(err) = scrn_lines.insert(self.tick_entities[i], tick) {
+        panic!(err)
+      }
+      if let Err(err) = line_styles.insert(self.tick_entities[i], line_style) {
+        panic!(err)
+      }
+    }
+
+    let lines = (&virt_lines, !&hiddens)
+      .join()
+      .map(|(line, _)| *line)
+      .collect::<Vec<_>>();
+    let sectors = sectors(&incident_directions(center.0, &lines));
+    for (i, (bisector, angle)) in sectors.iter().enumerate() {
+      let ent = if i < self.label_entities.len() {
+        self.label_entities[i]
+      } else {
+        let ent = entities.create();
+        self.label_entities.push(ent);
+        ent
+      };
+      let label = ScreenText {
+        // Roughly centered on the bisector, the position being the start of the baseline
+        position: on_screen(*bisector, LABEL_DISTANCE) + ScreenPosition(vec2![-16., 6.]),
+        text: format!("{:.1}°", angle * 180. / PI),
+      };
+      if scrn_texts.get(ent) != Some(&label) {
+        if let Err(err) = scrn_texts.insert(ent, label) {
+          panic!(err)
+        }
+      }
+      if !text_styles.contains(ent) {
+        let style = TextStyle {
+          color: theme.line,
+          font_size: 12.0,
+          alpha: 1.0,
+        };
+        if let Err(err) = text_styles.insert(ent, style) {
+          panic!(err)
+        }
+      }
+    }
+    for ent in &self.label_entities[sectors.len()..] {
+      scrn_texts.remove(*ent);
+    }
+  }
+}
+
+/// The directions, as angles from 0 to 2 PI, in which the lines going through `point` leave it. A
+/// straight line goes both ways, while a segment or a ray only goes the ways it extends to
+fn incident_directions(point: Vector2, lines: &[VirtualLine]) -> Vec<f64> {
+  let mut directions = vec![];
+  for virtual_line in lines {
+    let line: Line = (*virtual_line).into();
+    if line.from_to_length() < INCIDENCE_TOLERANCE
+      || (line.get_closest_point(point) - point).magnitude() > INCIDENCE_TOLERANCE
+    {
+      continue;
+    }
+    let t = line.t_of_point(point);
+    let (forward, backward) = match line.line_type {
+      LineType::Straight => (true, true),
+      LineType::Ray => (true, t > INCIDENCE_TOLERANCE),
+      LineType::Segment => (t < line.from_to_length() - INCIDENCE_TOLERANCE, t > INCIDENCE_TOLERANCE),
+    };
+    let direction = line.direction();
+    let angle = direction.y.atan2(direction.x).rem_euclid(2. * PI);
+    if forward {
+      directions.push(angle);
+    }
+    if backward {
+      directions.push((angle + PI).rem_euclid(2. * PI));
+    }
+  }
+  directions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  directions.dedup_by(|a, b| (*a - *b).abs() < INCIDENCE_TOLERANCE);
+  directions
+}
+
+/// The sectors between consecutive directions going counterclockwise, each one as its bisector
+/// and its angle. There are none with less than two directions
+fn sectors(directions: &[f64]) -> Vec<(f64, f64)> {
+  if directions.len() < 2 {
+    return vec![];
+  }
+  directions
+    .iter()
+    .enumerate()
+    .map(|(i, from)| {
+      let to = directions[(i + 1) % directions.len()];
+      let angle = (to - from).rem_euclid(2. * PI);
+      (from + angle / 2., angle)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn line(from: Vector2, to: Vector2, line_type: LineType) -> VirtualLine {
+    VirtualLine {
+      from: VirtualPosition(from),
+      to: VirtualPosition(to),
+      line_type,
+    }
+  }
+
+  #[test]
+  fn test_angles_between_incident_lines() {
+    let lines = vec![
+      line(vec2![0., 0.], vec2![2., 0.], LineType::Segment), // Ends at the point
+      line(vec2![0., 0.], vec2![1., 1.], LineType::Ray),     // Starts at it
+      line(vec2![-1., 0.], vec2![-1., 1.], LineType::Straight), // Misses it
+      line(vec2![-1., 1.], vec2![1., -1.], LineType::Segment), // Crosses it
+    ];
+    let directions = incident_directions(vec2![0., 0.], &lines);
+    assert_eq!(directions.len(), 4);
+
+    let degrees = sectors(&directions)
+      .iter()
+      .map(|(_, angle)| (angle * 180. / PI).round())
+      .collect::<Vec<_>>();
+    assert_eq!(degrees, vec![45., 90., 180., 45.]);
+    assert!(sectors(&directions[..1]).is_empty());
+  }
+}
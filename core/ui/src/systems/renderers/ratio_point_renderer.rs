@@ -0,0 +1,61 @@
+use crate::resources::*;
+use core_lib::{
+  components::{screen_shapes::*, styles::*},
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Previews the point placed by the ratio tool while its ratio is adjusted
+pub struct RatioPointRenderer {
+  ratio_point_entity: Option<Entity>,
+}
+
+impl Default for RatioPointRenderer {
+  fn default() -> Self {
+    Self {
+      ratio_point_entity: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for RatioPointRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, RatioPoint>,
+    Read<'a, DefaultPointStyle>,
+    WriteStorage<'a, ScreenPoint>,
+    WriteStorage<'a, PointStyle>,
+  );
+
+  fn run(&mut self, (entities, ratio_point, default_point_style, mut scrn_points, mut point_styles): Self::SystemData) {
+    // First make sure we have an entity for rendering the ratio point
+    let ent = match self.ratio_point_entity {
+      Some(ent) => ent,
+      None => {
+        let ent = entities.create();
+        self.ratio_point_entity = Some(ent);
+        ent
+      }
+    };
+
+    // The screen transform keeps the ratios, so the point is placed between the screen points
+    let maybe_position =
+      ratio_point
+        .get_points()
+        .and_then(|(p1, p2)| match (scrn_points.get(p1), scrn_points.get(p2)) {
+          (Some(from), Some(to)) => Some(ScreenPosition(from.0 + (to.0 - from.0) * ratio_point.ratio)),
+          _ => None,
+        });
+    if let Some(position) = maybe_position {
+      if let Err(err) = scrn_points.insert(ent, position) {
+        panic!(err)
+      }
+      if let Err(err) = point_styles.insert(ent, default_point_style.get().apply_alpha(0.6)) {
+        panic!(err)
+      }
+    } else {
+      scrn_points.remove(ent);
+    }
+  }
+}
@@ -0,0 +1,204 @@
+use crate::resources::*;
+use core_lib::{
+  components::{screen_shapes::*, styles::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static MIN_LABEL_SPACING: f64 = 64.0; // Pixel, between two labelled ticks
+static MAJOR_TICK_LENGTH: f64 = 10.0;
+static MINOR_TICK_LENGTH: f64 = 5.0;
+static LABEL_FONT_SIZE: f64 = 12.0;
+
+/// Draws a ruler along the top and the left edges of the viewport, with its ticks at round virtual
+/// coordinates. The ticks and labels are only rewritten when they change
+pub struct RulerRenderer {
+  tick_entities: Vec<Entity>,
+  label_entities: Vec<Entity>,
+  drawn: Option<(Vec<ScreenLine>, Vec<ScreenText>, Color)>,
+}
+
+impl Default for RulerRenderer {
+  fn default() -> Self {
+    Self {
+      tick_entities: vec![],
+      label_entities: vec![],
+      drawn: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for RulerRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, MeasureOverlays>,
+    Read<'a, Viewport>,
+    Read<'a, Theme>,
+    WriteStorage<'a, ScreenLine>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, ScreenText>,
+    WriteStorage<'a, TextStyle>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, measure_overlays, viewport, theme, mut scrn_lines, mut line_styles, mut scrn_texts, mut text_styles): Self::SystemData,
+  ) {
+    let (ticks, labels) = if measure_overlays.ruler {
+      ruler_ticks(&viewport)
+    } else {
+      (vec![], vec![])
+    };
+    let drawn = (ticks, labels, theme.line);
+    if self.drawn.as_ref() == Some(&drawn) {
+      return;
+    }
+    let (ticks, labels, _) = &drawn;
+
+    for (i, tick) in ticks.iter().enumerate() {
+      // Reuse the tick entities created before
+      let ent = if i < self.tick_entities.len() {
+        self.tick_entities[i]
+      } else {
+        let ent = entities.create();
+        self.tick_entities.push(ent);
+        ent
+      };
+      let style = LineStyle {
+        color: theme.line,
+        width: 1.0,
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: 0.8,
+      };
+      if let Err(err) = scrn_lines.insert(ent, *tick) {
+        panic!(err)
+      }
+      if let Err(err) = line_styles.insert(ent, style) {
+        panic!(err)
+      }
+    }
+    for (i, label) in labels.iter().enumerate() {
+      let ent = if i < self.label_entities.len() {
+        self.label_entities[i]
+      } else {
+        let ent = entities.create();
+        self.label_entities.push(ent);
+        ent
+      };
+      let style = TextStyle {
+        color: theme.line,
+        font_size: LABEL_FONT_SIZE,
+        alpha: 0.8,
+      };
+      if let Err(err) = scrn_texts.insert(ent, label.clone()) {
+        panic!(err)
+      }
+      if let Err(err) = text_styles.insert(ent, style) {
+        panic!(err)
+      }
+    }
+
+    // Hide the ticks and labels that are not needed anymore
+    for ent in &self.tick_entities[ticks.len()..] {
+      scrn_lines.remove(*ent);
+    }
+    for ent in &self.label_entities[labels.len()..] {
+      scrn_texts.remove(*ent);
+    }
+    self.drawn = Some(drawn);
+  }
+}
+
+/// The virtual distance between two labelled ticks, a round number of the form 1, 2 or 5 times a
+/// power of ten, along with the number of minor ticks it is split into and the decimals to show
+fn ruler_step(viewport: &Viewport) -> (f64, usize, usize) {
+  let min_step = MIN_LABEL_SPACING * viewport.virtual_to_screen_scale();
+  let exponent = min_step.log10().floor() as i32;
+  let power = 10f64.powi(exponent);
+  let (mantissa, exponent) = match min_step / power {
+    m if m <= 1.0 => (1.0, exponent),
+    m if m <= 2.0 => (2.0, exponent),
+    m if m <= 5.0 => (5.0, exponent),
+    _ => (1.0, exponent + 1),
+  };
+  let step = mantissa * 10f64.powi(exponent);
+  let subdivisions = if mantissa == 2.0 { 4 } else { 5 };
+  (step, subdivisions, (-exponent).max(0) as usize)
+}
+
+/// The ticks of the ruler along the top and the left edges of the viewport, and the labels of the
+/// major ones
+fn ruler_ticks(viewport: &Viewport) -> (Vec<ScreenLine>, Vec<ScreenText>) {
+  let (step, subdivisions, decimals) = ruler_step(viewport);
+  let minor_step = step / subdivisions as f64;
+  let mut ticks = vec![];
+  let mut labels = vec![];
+  let tick = |from: Vector2, to: Vector2| ScreenLine {
+    from: ScreenPosition(from),
+    to: ScreenPosition(to),
+    line_type: LineType::Segment,
+  };
+
+  let (i_min, i_max) = (
+    (viewport.x_min() / minor_step).ceil() as i64,
+    (viewport.x_max() / minor_step).floor() as i64,
+  );
+  for i in i_min..=i_max {
+    let x = i as f64 * minor_step;
+    let screen_x = VirtualPosition(vec2![x, 0.]).to_screen(viewport).0.x;
+    let major = i % subdivisions as i64 == 0;
+    let length = if major { MAJOR_TICK_LENGTH } else { MINOR_TICK_LENGTH };
+    ticks.push(tick(vec2![screen_x, 0.], vec2![screen_x, length]));
+    if major {
+      labels.push(ScreenText {
+        position: ScreenPosition(vec2![screen_x + 2., MAJOR_TICK_LENGTH + LABEL_FONT_SIZE]),
+        text: format!("{:.*}", decimals, x),
+      });
+    }
+  }
+
+  let (j_min, j_max) = (
+    (viewport.y_min() / minor_step).ceil() as i64,
+    (viewport.y_max() / minor_step).floor() as i64,
+  );
+  for j in j_min..=j_max {
+    let y = j as f64 * minor_step;
+    let screen_y = VirtualPosition(vec2![0., y]).to_screen(viewport).0.y;
+    let major = j % subdivisions as i64 == 0;
+    let length = if major { MAJOR_TICK_LENGTH } else { MINOR_TICK_LENGTH };
+    ticks.push(tick(vec2![0., screen_y], vec2![length, screen_y]));
+    if major {
+      labels.push(ScreenText {
+        position: ScreenPosition(vec2![MAJOR_TICK_LENGTH + 2., screen_y + LABEL_FONT_SIZE / 2.]),
+        text: format!("{:.*}", decimals, y),
+      });
+    }
+  }
+  (ticks, labels)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_ruler_ticks_follow_the_zoom() {
+    // The default viewport is 20 by 15 units around the origin, at 48 pixels per unit
+    let mut viewport = Viewport::default();
+    assert_eq!(ruler_step(&viewport), (2., 4, 0));
+    let (ticks, labels) = ruler_ticks(&viewport);
+    assert_eq!(ticks.len(), 41 + 31);
+    assert_eq!(labels.len(), 11 + 7);
+    assert_eq!(labels[0].text, "-10");
+
+    viewport.zoom(100., vec2![0., 0.]);
+    let (step, subdivisions, decimals) = ruler_step(&viewport);
+    assert!((step - 0.02).abs() < 1e-9);
+    assert_eq!((subdivisions, decimals), (4, 2));
+    let (_, labels) = ruler_ticks(&viewport);
+    assert!(labels.iter().any(|label| label.text == "0.02"));
+  }
+}
@@ -0,0 +1,60 @@
+use crate::resources::{DefaultSelectRectangleStyle, SelectLasso};
+use core_lib::{
+  components::{screen_shapes::ScreenLine, styles::LineStyle},
+  math::*,
+  resources::Theme,
+};
+use specs::prelude::*;
+
+pub struct SelectLassoRenderer {
+  segment_entities: Vec<Entity>,
+}
+
+impl Default for SelectLassoRenderer {
+  fn default() -> Self {
+    Self {
+      segment_entities: vec![],
+    }
+  }
+}
+
+impl<'a> System<'a> for SelectLassoRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, SelectLasso>,
+    Read<'a, DefaultSelectRectangleStyle>,
+    Read<'a, Theme>,
+    WriteStorage<'a, ScreenLine>,
+    WriteStorage<'a, LineStyle>,
+  );
+
+  fn run(&mut self, (entities, select_lasso, select_rect_style, theme, mut lines, mut line_styles): Self::SystemData) {
+    let path = select_lasso.path();
+
+    // The path is drawn closed, the last segment goes back to where the drag began
+    let num_segments = if path.len() < 2 { 0 } else { path.len() };
+    for i in 0..num_segments {
+      // Reuse the segment entities created in previous frames
+      let ent = if i < self.segment_entities.len() {
+        self.segment_entities[i]
+      } else {
+        let ent = entities.create();
+        self.segment_entities.push(ent);
+        ent
+      };
+
+      let segment = Line::from_two_points(path[i], path[(i + 1) % path.len()], LineType::Segment);
+      if let Err(err) = lines.insert(ent, segment.into()) {
+        panic!(err)
+      }
+      if let Err(err) = line_styles.insert(ent, select_rect_style.get(&theme).border) {
+        panic!(err)
+      }
+    }
+
+    // Remove the segments not part of the path anymore
+    for ent in &self.segment_entities[num_segments..] {
+      lines.remove(*ent);
+    }
+  }
+}
@@ -1,5 +1,8 @@
 use crate::resources::{DefaultSelectRectangleStyle, SelectRectangle};
-use core_lib::components::{screen_shapes::ScreenRectangle, styles::RectangleStyle};
+use core_lib::{
+  components::{screen_shapes::ScreenRectangle, styles::RectangleStyle},
+  resources::Theme,
+};
 use specs::prelude::*;
 
 pub struct SelectRectangleRenderer {
@@ -19,18 +22,19 @@ impl<'a> System<'a> for SelectRectangleRenderer {
     Entities<'a>,
     Read<'a, SelectRectangle>,
     Read<'a, DefaultSelectRectangleStyle>,
+    Read<'a, Theme>,
     WriteStorage<'a, ScreenRectangle>,
     WriteStorage<'a, RectangleStyle>,
   );
 
-  fn run(&mut self, (entities, select_rect, select_rect_style, mut rects, mut rect_styles): Self::SystemData) {
+  fn run(&mut self, (entities, select_rect, select_rect_style, theme, mut rects, mut rect_styles): Self::SystemData) {
     // Make sure we have the rectangle entity
     let rect_ent = if let Some(ent) = self.drag_rectangle_entity {
       ent
     } else {
       let ent = entities.create();
       self.drag_rectangle_entity = Some(ent);
-      if let Err(err) = rect_styles.insert(ent, select_rect_style.get()) {
+      if let Err(err) = rect_styles.insert(ent, select_rect_style.get(&theme)) {
         panic!(err)
       }
       ent
@@ -0,0 +1,138 @@
+use crate::utilities::*;
+use core_lib::{
+  components::{markers::*, screen_shapes::*, sliders::*, styles::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+static LABEL_OFFSET: Vector2 = Vector2 { x: 0.0, y: -24.0 }; // Pixel, above the left end of the track
+
+/// Draws every visible slider as a track, a handle at its value and a label with its name and
+/// value. The track is dimmed unless the slider is selected
+pub struct SliderRenderer {
+  slider_entities: HashMap<Entity, (Entity, Entity, Entity)>, // Slider to its track, handle and label
+}
+
+impl Default for SliderRenderer {
+  fn default() -> Self {
+    Self {
+      slider_entities: HashMap::new(),
+    }
+  }
+}
+
+impl<'a> System<'a> for SliderRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, Names>,
+    Read<'a, DefaultPointStyle>,
+    Read<'a, DefaultLineStyle>,
+    Read<'a, DefaultTextStyle>,
+    ReadStorage<'a, Slider>,
+    ReadStorage<'a, SliderValue>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, Hidden>,
+    WriteStorage<'a, ScreenPoint>,
+    WriteStorage<'a, PointStyle>,
+    WriteStorage<'a, ScreenLine>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, ScreenText>,
+    WriteStorage<'a, TextStyle>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      viewport,
+      names,
+      default_point_style,
+      default_line_style,
+      default_text_style,
+      sliders,
+      slider_values,
+      selecteds,
+      hiddens,
+      mut scrn_points,
+      mut point_styles,
+      mut scrn_lines,
+      mut line_styles,
+      mut scrn_texts,
+      mut text_styles,
+    ): Self::SystemData,
+  ) {
+    // First drop the rendering entities of the sliders that are gone or hidden
+    let gone = self
+      .slider_entities
+      .keys()
+      .filter(|ent| !sliders.contains(**ent) || hiddens.contains(**ent))
+      .cloned()
+      .collect::<Vec<_>>();
+    for ent in gone {
+      if let Some((track, handle, label)) = self.slider_entities.remove(&ent) {
+        for helper in &[track, handle, label] {
+          if let Err(err) = entities.delete(*helper) {
+            panic!(err)
+          }
+        }
+      }
+    }
+
+    // Then render the others
+    for (ent, slider, slider_value, _) in (&entities, &sliders, &slider_values, !&hiddens).join() {
+      let (track, handle, label) = *self
+        .slider_entities
+        .entry(ent)
+        .or_insert_with(|| (entities.create(), entities.create(), entities.create()));
+
+      let (from, to) = slider_track(slider, &viewport);
+      let scrn_track = ScreenLine {
+        from,
+        to,
+        line_type: LineType::Segment,
+      };
+      let line_style = if selecteds.contains(ent) {
+        default_line_style.get()
+      } else {
+        default_line_style.get().apply_alpha(0.4)
+      };
+      let handle_position = from + (to - from) * ScreenScalar(slider.ratio_of(slider_value.0));
+      let scrn_label = ScreenText {
+        position: from + ScreenPosition(LABEL_OFFSET),
+        text: match names.name_of(ent) {
+          Some(name) => format!("{} = {:.2}", name, slider_value.0),
+          None => format!("{:.2}", slider_value.0),
+        },
+      };
+
+      if let Err(err) = scrn_lines.insert(track, scrn_track) {
+        panic!(err)
+      }
+      if let Err(err) = line_styles.insert(track, line_style) {
+        panic!(err)
+      }
+      if let Err(err) = scrn_points.insert(handle, handle_position) {
+        panic!(err)
+      }
+      if let Err(err) = point_styles.insert(handle, default_point_style.get()) {
+        panic!(err)
+      }
+
+      // Only touch the label when it changes, so that renderers are told about it once
+      if scrn_texts.get(label) != Some(&scrn_label) {
+        if let Err(err) = scrn_texts.insert(label, scrn_label) {
+          panic!(err)
+        }
+      }
+      if !text_styles.contains(label) {
+        if let Err(err) = text_styles.insert(label, default_text_style.get()) {
+          panic!(err)
+        }
+      }
+    }
+  }
+}
@@ -62,7 +62,11 @@ impl<'a> System<'a> for SnapLineRenderer {
           if let Tool::Line(line_type) = tool_state.get() {
             draw = true;
 
-            let line_style = default_line_style.get().apply_alpha(0.6);
+            // The preview is drawn solid when the angle is snapped relative to another line
+            let line_style = match snap_line.maybe_relative_angle {
+              Some(_) => default_line_style.get(),
+              None => default_line_style.get().apply_alpha(0.6),
+            };
             let scrn_line = ScreenLine {
               from: *first_point_pos,
               to: second_point_pos,
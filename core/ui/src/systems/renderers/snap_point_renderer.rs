@@ -22,13 +22,14 @@ impl<'a> System<'a> for SnapPointRenderer {
     Entities<'a>,
     Read<'a, MaybeSnapPoint>,
     Read<'a, DefaultPointStyle>,
+    Read<'a, Theme>,
     WriteStorage<'a, ScreenPoint>,
     WriteStorage<'a, PointStyle>,
   );
 
   fn run(
     &mut self,
-    (entities, maybe_snap_point, default_point_style, mut scrn_points, mut point_styles): Self::SystemData,
+    (entities, maybe_snap_point, default_point_style, theme, mut scrn_points, mut point_styles): Self::SystemData,
   ) {
     // First make sure we have an entity for rendering the snap point
     let ent = match self.snap_point_entity {
@@ -47,8 +48,11 @@ impl<'a> System<'a> for SnapPointRenderer {
         // For not snapped, we want dimmed style
         SnapPointType::NotSnapped => default_point_style.get().apply_alpha(0.6),
 
-        // For snapped, we want it to be bigger than the default
-        _ => default_point_style.get().resize(1.0),
+        // For snapped, we want it to be bigger than the default and in the snap color of the theme
+        _ => PointStyle {
+          color: theme.snap_point,
+          ..default_point_style.get().resize(1.0)
+        },
       };
 
       // Then insert the components
@@ -0,0 +1,77 @@
+use crate::resources::*;
+use core_lib::{
+  components::{screen_shapes::*, styles::*},
+  resources::*,
+};
+use specs::prelude::*;
+
+/// Previews the polygon being drawn, from its vertices so far to the snap point
+pub struct SnapPolygonRenderer {
+  snap_polygon_entity: Option<Entity>,
+}
+
+impl Default for SnapPolygonRenderer {
+  fn default() -> Self {
+    Self {
+      snap_polygon_entity: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for SnapPolygonRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, MaybeSnapPoint>,
+    Read<'a, SnapPolygon>,
+    Read<'a, DefaultPolygonStyle>,
+    ReadStorage<'a, ScreenPoint>,
+    WriteStorage<'a, ScreenPolygon>,
+    WriteStorage<'a, PolygonStyle>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      maybe_snap_point,
+      snap_polygon,
+      default_polygon_style,
+      scrn_points,
+      mut scrn_polygons,
+      mut polygon_styles,
+    ): Self::SystemData,
+  ) {
+    // First make sure we have an entity for rendering the snap polygon
+    let ent = match self.snap_polygon_entity {
+      Some(ent) => ent,
+      None => {
+        let ent = entities.create();
+        self.snap_polygon_entity = Some(ent);
+        ent
+      }
+    };
+
+    // Then we render it when presented
+    let mut vertices = snap_polygon
+      .vertices
+      .iter()
+      .filter_map(|vertex_ent| scrn_points.get(*vertex_ent).copied())
+      .collect::<Vec<_>>();
+    if let Some(SnapPoint { position, .. }) = maybe_snap_point.get() {
+      vertices.push(position);
+    }
+    let draw = !snap_polygon.vertices.is_empty() && vertices.len() >= 2;
+    if draw {
+      let polygon_style = default_polygon_style.get().apply_alpha(0.6);
+      if let Err(err) = scrn_polygons.insert(ent, ScreenPolygon { vertices }) {
+        panic!(err)
+      }
+      if let Err(err) = polygon_styles.insert(ent, polygon_style) {
+        panic!(err)
+      }
+    } else {
+      // If not draw then remove the snap polygon
+      scrn_polygons.remove(ent);
+    }
+  }
+}
@@ -0,0 +1,86 @@
+use crate::resources::*;
+use core_lib::{
+  components::{screen_shapes::ScreenRectangle, styles::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+static MAX_SHADED_COUNT: usize = 8;
+
+pub struct SpatialHashOverlayRenderer {
+  tile_entities: Vec<Entity>,
+}
+
+impl Default for SpatialHashOverlayRenderer {
+  fn default() -> Self {
+    Self { tile_entities: vec![] }
+  }
+}
+
+impl<'a> System<'a> for SpatialHashOverlayRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, SpatialHashOverlay>,
+    Read<'a, SpatialEntityMap>,
+    Read<'a, Viewport>,
+    WriteStorage<'a, ScreenRectangle>,
+    WriteStorage<'a, RectangleStyle>,
+  );
+
+  fn run(&mut self, (entities, overlay, spatial_entity_map, viewport, mut rects, mut rect_styles): Self::SystemData) {
+    let mut num_used = 0;
+
+    if overlay.is_enabled() {
+      // The tiles are in virtual space, only the ones on the screen are drawn
+      let screen = AABB::new(0.0, 0.0, viewport.screen_width(), viewport.screen_height());
+      for (tile, count) in spatial_entity_map.occupied_tiles() {
+        let aabb = AABB::two_points(
+          VirtualPosition(tile.min()).to_screen(&viewport).0,
+          VirtualPosition(tile.max()).to_screen(&viewport).0,
+        );
+        if screen.intersect(aabb).is_none() {
+          continue;
+        }
+
+        // Reuse the tile entities created in previous frames
+        let ent = if num_used < self.tile_entities.len() {
+          self.tile_entities[num_used]
+        } else {
+          let ent = entities.create();
+          self.tile_entities.push(ent);
+          ent
+        };
+        num_used += 1;
+
+        if let Err(err) = rects.insert(ent, aabb) {
+          panic!(err)
+        }
+        if let Err(err) = rect_styles.insert(ent, tile_style(count)) {
+          panic!(err)
+        }
+      }
+    }
+
+    // Hide all the tiles that are not occupied anymore
+    for ent in &self.tile_entities[num_used..] {
+      rects.remove(*ent);
+    }
+  }
+}
+
+fn tile_style(count: usize) -> RectangleStyle {
+  let heat = count.min(MAX_SHADED_COUNT) as f32 / MAX_SHADED_COUNT as f32;
+  RectangleStyle {
+    fill: rgba!(1.0, 0.0, 0.0, 0.05 + 0.4 * heat),
+    border: LineStyle {
+      color: rgba!(1.0, 0.0, 0.0, 0.2),
+      width: 1.0,
+      marks: EqualityMarks::default(),
+      dash: DashPattern::Solid,
+      alpha: 1.0,
+    },
+    alpha: 1.0,
+  }
+}
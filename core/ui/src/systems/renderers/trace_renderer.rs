@@ -0,0 +1,79 @@
+use core_lib::{
+  components::{screen_shapes::ScreenLine, styles::*},
+  math::*,
+  resources::*,
+};
+use specs::prelude::*;
+
+static TRACE_ALPHA: f64 = 0.3;
+
+/// Draws the traces as faint segments of the color of their point
+pub struct TraceRenderer {
+  segment_entities: Vec<Entity>,
+}
+
+impl Default for TraceRenderer {
+  fn default() -> Self {
+    Self {
+      segment_entities: vec![],
+    }
+  }
+}
+
+impl<'a> System<'a> for TraceRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, TraceStore>,
+    ReadStorage<'a, PointStyle>,
+    WriteStorage<'a, ScreenLine>,
+    WriteStorage<'a, LineStyle>,
+  );
+
+  fn run(&mut self, (entities, viewport, trace_store, point_styles, mut lines, mut line_styles): Self::SystemData) {
+    let mut num_used = 0;
+
+    for (ent, polyline) in trace_store.polylines() {
+      let line_style = LineStyle {
+        color: point_styles.get(ent).map_or(Color::black(), |style| style.color),
+        width: 1.0,
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: TRACE_ALPHA,
+      };
+      for segment in polyline.windows(2) {
+        // Reuse the segment entities created in previous frames
+        let seg_ent = if num_used < self.segment_entities.len() {
+          self.segment_entities[num_used]
+        } else {
+          let seg_ent = entities.create();
+          self.segment_entities.push(seg_ent);
+          seg_ent
+        };
+        num_used += 1;
+
+        let from = segment[0].to_screen(&*viewport);
+        let to = segment[1].to_screen(&*viewport);
+        let scrn_line = ScreenLine {
+          from,
+          to,
+          line_type: LineType::Segment,
+        };
+        // Most of the trace stays the same from frame to frame, only update what changed
+        if lines.get(seg_ent) != Some(&scrn_line) {
+          if let Err(err) = lines.insert(seg_ent, scrn_line) {
+            panic!(err)
+          }
+          if let Err(err) = line_styles.insert(seg_ent, line_style) {
+            panic!(err)
+          }
+        }
+      }
+    }
+
+    // Remove the segments not part of any trace anymore
+    for ent in &self.segment_entities[num_used..] {
+      lines.remove(*ent);
+    }
+  }
+}
@@ -0,0 +1,62 @@
+use crate::resources::*;
+use core_lib::{
+  components::{screen_shapes::*, styles::*},
+  resources::*,
+};
+use specs::prelude::*;
+
+/// Shows the text being typed with a caret at its end, dimmed until it gets inserted
+pub struct TypingTextRenderer {
+  typing_text_entity: Option<Entity>,
+}
+
+impl Default for TypingTextRenderer {
+  fn default() -> Self {
+    Self {
+      typing_text_entity: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for TypingTextRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, TypingText>,
+    Read<'a, DefaultTextStyle>,
+    WriteStorage<'a, ScreenText>,
+    WriteStorage<'a, TextStyle>,
+  );
+
+  fn run(&mut self, (entities, typing_text, default_text_style, mut scrn_texts, mut text_styles): Self::SystemData) {
+    // First make sure we have an entity for rendering the text
+    let ent = match self.typing_text_entity {
+      Some(ent) => ent,
+      None => {
+        let ent = entities.create();
+        self.typing_text_entity = Some(ent);
+        ent
+      }
+    };
+
+    match typing_text.get() {
+      Some(typing) => {
+        let mut scrn_text = typing.clone();
+        scrn_text.text.push('|');
+        if scrn_texts.get(ent) != Some(&scrn_text) {
+          if let Err(err) = scrn_texts.insert(ent, scrn_text) {
+            panic!(err)
+          }
+        }
+        if !text_styles.contains(ent) {
+          if let Err(err) = text_styles.insert(ent, default_text_style.get().apply_alpha(0.6)) {
+            panic!(err)
+          }
+        }
+      }
+      None => {
+        scrn_texts.remove(ent);
+        text_styles.remove(ent);
+      }
+    }
+  }
+}
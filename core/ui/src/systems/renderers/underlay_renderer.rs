@@ -0,0 +1,119 @@
+use crate::utilities::*;
+use core_lib::{
+  components::{markers::*, screen_shapes::*, styles::*, underlays::*},
+  math::*,
+  resources::*,
+  utilities::ScreenPosition,
+};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Draws a frame around every visible underlay with a handle on its top left and bottom right
+/// corners, the images themselves are drawn by the apps. The frame is dimmed unless the underlay
+/// is selected
+pub struct UnderlayRenderer {
+  underlay_entities: HashMap<Entity, (Entity, Entity, Entity)>, // Underlay to its frame, move handle and scale handle
+}
+
+impl Default for UnderlayRenderer {
+  fn default() -> Self {
+    Self {
+      underlay_entities: HashMap::new(),
+    }
+  }
+}
+
+impl<'a> System<'a> for UnderlayRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, Theme>,
+    ReadStorage<'a, ImageUnderlay>,
+    ReadStorage<'a, UnderlayPlacement>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, Hidden>,
+    WriteStorage<'a, ScreenRectangle>,
+    WriteStorage<'a, RectangleStyle>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      viewport,
+      theme,
+      underlays,
+      underlay_placements,
+      selecteds,
+      hiddens,
+      mut scrn_rects,
+      mut rect_styles,
+    ): Self::SystemData,
+  ) {
+    // First drop the rendering entities of the underlays that are gone or hidden
+    let gone = self
+      .underlay_entities
+      .keys()
+      .filter(|ent| !underlays.contains(**ent) || hiddens.contains(**ent))
+      .cloned()
+      .collect::<Vec<_>>();
+    for ent in gone {
+      if let Some((frame, move_handle, scale_handle)) = self.underlay_entities.remove(&ent) {
+        for helper in &[frame, move_handle, scale_handle] {
+          if let Err(err) = entities.delete(*helper) {
+            panic!(err)
+          }
+        }
+      }
+    }
+
+    // Then render the others
+    for (ent, underlay, placement, _) in (&entities, &underlays, &underlay_placements, !&hiddens).join() {
+      let (frame, move_handle, scale_handle) = *self
+        .underlay_entities
+        .entry(ent)
+        .or_insert_with(|| (entities.create(), entities.create(), entities.create()));
+
+      let (top_left, bottom_right) = underlay_frame(underlay, placement, &viewport);
+      let color = if selecteds.contains(ent) {
+        theme.selection
+      } else {
+        theme.overlay.apply_alpha(0.4)
+      };
+      let border = LineStyle {
+        color,
+        width: 1.0,
+        marks: EqualityMarks::default(),
+        dash: DashPattern::Solid,
+        alpha: 1.0,
+      };
+      let handle = |corner: ScreenPosition| {
+        let half_size = vec2![UNDERLAY_HANDLE_SIZE.0, UNDERLAY_HANDLE_SIZE.0] / 2.;
+        AABB::two_points(corner.0 - half_size, corner.0 + half_size)
+      };
+
+      let shapes = [
+        (
+          frame,
+          AABB::two_points(top_left.0, bottom_right.0),
+          Color::transparent(),
+        ),
+        (move_handle, handle(top_left), color),
+        (scale_handle, handle(bottom_right), color),
+      ];
+      for (helper, rect, fill) in shapes.iter() {
+        if let Err(err) = scrn_rects.insert(*helper, *rect) {
+          panic!(err)
+        }
+        let style = RectangleStyle {
+          fill: *fill,
+          border,
+          alpha: 1.0,
+        };
+        if let Err(err) = rect_styles.insert(*helper, style) {
+          panic!(err)
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,56 @@
+use crate::{events::*, resources::*};
+use core_lib::resources::*;
+use specs::prelude::*;
+
+/// Updates the cursor readout every frame, the cursor stays still while the viewport moves too.
+/// A status event is only emitted when the text shown changes
+#[derive(Default)]
+pub struct CursorReadoutManager;
+
+impl<'a> System<'a> for CursorReadoutManager {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, Viewport>,
+    Write<'a, CursorReadout>,
+    Write<'a, StatusEventChannel>,
+  );
+
+  fn run(&mut self, (input_state, viewport, mut cursor_readout, mut status_event_channel): Self::SystemData) {
+    let before = cursor_readout.text();
+    cursor_readout.set(input_state.mouse_abs_pos.to_virtual(&*viewport));
+    if let Some(text) = cursor_readout.text() {
+      if Some(&text) != before.as_ref() {
+        status_event_channel.single_write(StatusEvent::CursorPosition(text));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  #[test]
+  fn test_cursor_readout_follows_viewport() {
+    let mut world = World::new();
+    let mut system = CursorReadoutManager::default();
+    System::setup(&mut system, &mut world);
+    let mut reader = world.fetch_mut::<StatusEventChannel>().register_reader();
+    world.insert(Viewport::new(vec2![1., -2.], vec2![20., 15.], vec2![960., 720.]));
+    world.fetch_mut::<CursorReadout>().format.decimals = 3;
+    world.fetch_mut::<InputState>().mouse_abs_pos = vec2![720., 180.].into();
+
+    system.run_now(&world);
+    system.run_now(&world);
+
+    let position = world.fetch::<CursorReadout>().get().unwrap();
+    assert_eq!(position.0, vec2![6., 1.75]);
+    let events = world
+      .fetch::<StatusEventChannel>()
+      .read(&mut reader)
+      .map(|StatusEvent::CursorPosition(text)| text.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(events, vec!["(6.000, 1.750)".to_string()]);
+  }
+}
@@ -0,0 +1,145 @@
+use crate::resources::*;
+use core_lib::{
+  components::{markers::*, screen_shapes::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Marks the curves close enough to the cursor for snapping to use them as hovered, so that they
+/// get highlighted along with the snap point. Lines and circles count when snapping on them or to
+/// the intersections is turned on, arcs only for the intersections
+#[derive(Default)]
+pub struct HoverSystem;
+
+impl<'a> System<'a> for HoverSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, ToolState>,
+    Read<'a, SnapSettings>,
+    Read<'a, SpatialEntityMap>,
+    Read<'a, Viewport>,
+    ReadStorage<'a, ScreenLine>,
+    ReadStorage<'a, ScreenCircle>,
+    ReadStorage<'a, ScreenArc>,
+    ReadStorage<'a, ScreenConic>,
+    ReadStorage<'a, Hidden>,
+    WriteStorage<'a, Hovered>,
+  );
+
+  fn run(
+    &mut self,
+    (
+      entities,
+      input_state,
+      tool_state,
+      snap_settings,
+      spatial_entity_map,
+      viewport,
+      scrn_lines,
+      scrn_circles,
+      scrn_arcs,
+      scrn_conics,
+      hiddens,
+      mut hovereds,
+    ): Self::SystemData,
+  ) {
+    hovereds.clear();
+    if !tool_state.need_snap_point() {
+      return;
+    }
+
+    let mouse_pos = input_state.mouse_abs_pos;
+    let intersections = snap_settings.is_enabled(SnapCategory::Intersection);
+    let reach = |category: SnapCategory| {
+      if snap_settings.is_enabled(category) || intersections {
+        Some(snap_settings.threshold(category))
+      } else {
+        None
+      }
+    };
+    let (line_reach, circle_reach) = (reach(SnapCategory::OnLine), reach(SnapCategory::OnCircle));
+    let arc_reach = if intersections {
+      Some(snap_settings.threshold(SnapCategory::OnCircle))
+    } else {
+      None
+    };
+    let within = |closest: ScreenPosition, reach: Option<ScreenScalar>| match reach {
+      Some(reach) => (closest - mouse_pos).magnitude() <= reach,
+      None => false,
+    };
+
+    // The spatial entity map is in virtual space
+    let neighbor_entities = spatial_entity_map.get_entities_near_point(
+      mouse_pos.to_virtual(&viewport).into(),
+      snap_settings
+        .threshold(SnapCategory::Point)
+        .to_virtual(&viewport)
+        .into(),
+    );
+    for entity in neighbor_entities {
+      if hiddens.contains(entity) || !entities.is_alive(entity) {
+        continue;
+      }
+      let hovered = if let Some(l) = scrn_lines.get(entity) {
+        within(l.get_closest_point(mouse_pos), line_reach)
+      } else if let Some(c) = scrn_circles.get(entity) {
+        within(mouse_pos.project(*c), circle_reach)
+      } else if let Some(c) = scrn_conics.get(entity) {
+        within(c.get_closest_point(mouse_pos), circle_reach)
+      } else if let Some(a) = scrn_arcs.get(entity) {
+        within(a.get_closest_point(mouse_pos), arc_reach)
+      } else {
+        false
+      };
+      if hovered {
+        if let Err(err) = hovereds.insert(entity, Hovered) {
+          panic!(err)
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_hover_curves_near_the_cursor() {
+    let mut world = World::new();
+    let mut system = HoverSystem::default();
+    System::setup(&mut system, &mut world);
+    world.fetch_mut::<ToolState>().set(Tool::Point);
+
+    let scrn_line = ScreenLine {
+      from: vec2![0., 100.].into(),
+      to: vec2![200., 100.].into(),
+      line_type: LineType::Segment,
+    };
+    let line = world.create_entity().with(scrn_line).build();
+    let virt_line = scrn_line.to_virtual(&world.fetch::<Viewport>());
+    world
+      .fetch_mut::<SpatialEntityMap>()
+      .insert_line(line, virt_line.into());
+
+    world.fetch_mut::<InputState>().mouse_abs_pos = vec2![50., 105.].into();
+    system.run_now(&world);
+    assert!(world.read_storage::<Hovered>().contains(line));
+
+    // Cleared as soon as the cursor moves away, or when snapping to the line is off
+    world.fetch_mut::<InputState>().mouse_abs_pos = vec2![50., 150.].into();
+    system.run_now(&world);
+    assert!(!world.read_storage::<Hovered>().contains(line));
+
+    world.fetch_mut::<InputState>().mouse_abs_pos = vec2![50., 105.].into();
+    let mut snap_settings = world.fetch_mut::<SnapSettings>();
+    snap_settings.set_enabled(SnapCategory::OnLine, false);
+    snap_settings.set_enabled(SnapCategory::Intersection, false);
+    drop(snap_settings);
+    system.run_now(&world);
+    assert!(!world.read_storage::<Hovered>().contains(line));
+  }
+}
@@ -1,5 +1,9 @@
+mod cursor_readout_manager;
 mod exit_state_manager;
+mod hover_system;
 mod tool_state_manager;
 
+pub use cursor_readout_manager::*;
 pub use exit_state_manager::*;
+pub use hover_system::*;
 pub use tool_state_manager::*;
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+static MAX_STEPS_PER_ADVANCE: u32 = 8; // Don't try to catch up forever after a long stall
+
+/// Accumulates elapsed time and tells how many fixed steps to run for it
+pub struct FixedTimestep {
+  step: Duration,
+  accumulated: Duration,
+}
+
+impl FixedTimestep {
+  pub fn new(rate: u32) -> Self {
+    Self {
+      step: Duration::from_secs(1) / rate,
+      accumulated: Duration::from_secs(0),
+    }
+  }
+
+  pub fn step(&self) -> Duration {
+    self.step
+  }
+
+  /// Adds `elapsed` to the accumulated time and consumes as many whole steps as possible. The
+  /// remainder is kept for the next advance, except when more than the maximum number of steps
+  /// piled up, in which case the backlog is dropped
+  pub fn advance(&mut self, elapsed: Duration) -> u32 {
+    self.accumulated += elapsed;
+    let mut steps = 0;
+    while self.accumulated >= self.step {
+      self.accumulated -= self.step;
+      steps += 1;
+      if steps == MAX_STEPS_PER_ADVANCE {
+        self.accumulated = Duration::from_secs(0);
+        break;
+      }
+    }
+    steps
+  }
+
+  /// How long to wait until the next step is due
+  pub fn until_next_step(&self) -> Duration {
+    self.step - self.accumulated
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_fixed_timestep_steps_for_elapsed_time() {
+    let mut timestep = FixedTimestep::new(50);
+    assert_eq!(timestep.step(), Duration::from_millis(20));
+
+    // Remainders carry over to the next advance
+    assert_eq!(timestep.advance(Duration::from_millis(70)), 3);
+    assert_eq!(timestep.until_next_step(), Duration::from_millis(10));
+    assert_eq!(timestep.advance(Duration::from_millis(15)), 1);
+    assert_eq!(timestep.until_next_step(), Duration::from_millis(15));
+    assert_eq!(timestep.advance(Duration::from_millis(1)), 0);
+
+    // A long stall is capped and its backlog dropped
+    assert_eq!(timestep.advance(Duration::from_secs(2)), MAX_STEPS_PER_ADVANCE);
+    assert_eq!(timestep.until_next_step(), Duration::from_millis(20));
+  }
+}
@@ -0,0 +1,81 @@
+use core_lib::{
+  components::{freehand::*, markers::*},
+  math::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// The closest visible stroke passing near the mouse. The strokes are not in the spatial entity
+/// map, so they are hit tested apart from the geometries
+pub fn hitting_freehand_stroke<'a>(
+  mouse_pos: ScreenPosition,
+  entities: &Entities<'a>,
+  scrn_strokes: &ReadStorage<'a, ScreenFreehandStroke>,
+  hiddens: &ReadStorage<'a, Hidden>,
+  threshold: ScreenScalar,
+) -> Option<Entity> {
+  (entities, scrn_strokes, !hiddens)
+    .join()
+    .filter_map(|(ent, stroke, _)| distance_to_stroke(stroke, mouse_pos).map(|dist| (ent, dist)))
+    .filter(|(_, dist)| *dist < threshold.0)
+    .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
+    .map(|(ent, _)| ent)
+}
+
+/// Distance to the closest piece of the stroke, a stroke of a single sample being a dot
+fn distance_to_stroke(stroke: &ScreenFreehandStroke, mouse_pos: ScreenPosition) -> Option<f64> {
+  let p = mouse_pos.0;
+  if let [(only, _)] = stroke.samples.as_slice() {
+    return Some((only.0 - p).magnitude());
+  }
+  stroke
+    .samples
+    .windows(2)
+    .map(|pair| {
+      let segment = Line {
+        from: (pair[0].0).0,
+        to: (pair[1].0).0,
+        line_type: LineType::Segment,
+      };
+      (segment.get_closest_point(p) - p).magnitude()
+    })
+    .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_hitting_stroke_along_its_samples() {
+    let mut world = World::new();
+    world.register::<ScreenFreehandStroke>();
+    world.register::<Hidden>();
+    let stroke = ScreenFreehandStroke {
+      samples: vec![
+        (vec2![0., 0.].into(), 1.),
+        (vec2![100., 0.].into(), 1.),
+        (vec2![100., 100.].into(), 1.),
+      ],
+    };
+    let ent = world.create_entity().with(stroke).build();
+    let threshold = ScreenScalar(5.);
+    let hit = |world: &World, mouse_pos: Vector2| {
+      hitting_freehand_stroke(
+        mouse_pos.into(),
+        &world.entities(),
+        &world.read_storage(),
+        &world.read_storage(),
+        threshold,
+      )
+    };
+
+    assert_eq!(hit(&world, vec2![50., 3.]), Some(ent));
+    assert_eq!(hit(&world, vec2![103., 50.]), Some(ent));
+    // Inside the corner the stroke turns around, but away from the ink
+    assert_eq!(hit(&world, vec2![50., 50.]), None);
+
+    world.write_storage::<Hidden>().insert(ent, Hidden).unwrap();
+    assert_eq!(hit(&world, vec2![50., 3.]), None);
+  }
+}
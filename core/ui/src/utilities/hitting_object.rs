@@ -1,44 +1,135 @@
-use core_lib::{components::screen_shapes::*, math::*, resources::*, utilities::*};
+use core_lib::{
+  components::{screen_shapes::*, symbolics::*},
+  resources::*,
+  utilities::*,
+};
 use specs::prelude::*;
 
+/// The geometry under the cursor. Points go first, then the line or circle closest to the cursor,
+/// measured exactly so that clicking between two curves picks the closer one
 pub fn hitting_object<'a>(
   mouse_pos: ScreenPosition,
-  spatial_entity_map: &SpatialEntityMap,
+  entity_quadtree: &EntityQuadtree,
+  viewport: &Viewport,
   scrn_points: &ReadStorage<'a, ScreenPoint>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
   scrn_lines: &ReadStorage<'a, ScreenLine>,
   scrn_circles: &ReadStorage<'a, ScreenCircle>,
   threshold: ScreenScalar,
 ) -> Option<Entity> {
-  // Maybe selected...
-  let mut maybe_selected_point: Option<(Entity, ScreenScalar)> = None;
-  let mut maybe_selected_line: Option<(Entity, ScreenScalar)> = None;
-  let mut maybe_selected_circle: Option<(Entity, ScreenScalar)> = None;
-
-  // Use spatial hash table to get potential neighbors
-  let neighbor_entities = spatial_entity_map.get_entities_near_point(mouse_pos.into(), threshold.into());
-  for entity in neighbor_entities {
-    if let Some(p) = scrn_points.get(entity) {
-      let dist = (*p - mouse_pos).magnitude();
-      if dist < threshold && (maybe_selected_point.is_none() || dist < maybe_selected_point.unwrap().1) {
-        maybe_selected_point = Some((entity, dist));
-      }
-    } else if let Some(l) = scrn_lines.get(entity) {
-      let proj_point = l.get_closest_point(mouse_pos);
-      let dist = (proj_point - mouse_pos).magnitude();
-      if dist < threshold && (maybe_selected_line.is_none() || dist < maybe_selected_line.unwrap().1) {
-        maybe_selected_line = Some((entity, dist));
-      }
-    } else if let Some(c) = scrn_circles.get(entity) {
-      let dist = (mouse_pos - mouse_pos.project(*c)).magnitude();
-      if dist < threshold && (maybe_selected_circle.is_none() || dist < maybe_selected_circle.unwrap().1) {
-        maybe_selected_circle = Some((entity, dist));
-      }
+  // Everything within reach, the closest first. The quadtree is in virtual space
+  let neighbors = entity_quadtree.entities_within(
+    mouse_pos.to_virtual(viewport).into(),
+    threshold.to_virtual(viewport).into(),
+  );
+
+  // Free and fixed points go before the constrained ones, as they are the ones to drag
+  let mut points = neighbors.iter().filter(|(ent, _)| scrn_points.contains(*ent));
+  let constrained = |ent: Entity| matches!(sym_points.get(ent), Some(sym_point) if sym_point.is_constrained());
+  points
+    .clone()
+    .find(|(ent, _)| !constrained(*ent))
+    .or_else(|| points.next())
+    .or_else(|| {
+      neighbors
+        .iter()
+        .find(|(ent, _)| scrn_lines.contains(*ent) || scrn_circles.contains(*ent))
+    })
+    .map(|(ent, _)| *ent)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+
+  #[test]
+  fn test_free_point_before_constrained_point() {
+    let mut world = World::new();
+    world.register::<ScreenPoint>();
+    world.register::<SymbolicPoint>();
+    world.register::<ScreenLine>();
+    world.register::<ScreenCircle>();
+    let free = world
+      .create_entity()
+      .with(SymbolicPoint::Free(vec2![0., 0.].into()))
+      .with(ScreenPosition(vec2![13., 10.]))
+      .build();
+    let mid = world
+      .create_entity()
+      .with(SymbolicPoint::MidPoint(free, free))
+      .with(ScreenPosition(vec2![10., 10.]))
+      .build();
+    let viewport = Viewport::default();
+    let mut entity_quadtree = EntityQuadtree::new();
+    for (ent, position) in [(free, vec2![13., 10.]), (mid, vec2![10., 10.])] {
+      let position = ScreenPosition(position).to_virtual(&viewport);
+      entity_quadtree.insert(ent, QuadtreeShape::Point(position.into()));
     }
+
+    // The constrained point is closer to the cursor, the free one still wins
+    let hit = |world: &World, position: Vector2| {
+      hitting_object(
+        ScreenPosition(position),
+        &entity_quadtree,
+        &viewport,
+        &world.read_storage::<ScreenPoint>(),
+        &world.read_storage::<SymbolicPoint>(),
+        &world.read_storage::<ScreenLine>(),
+        &world.read_storage::<ScreenCircle>(),
+        ScreenScalar(5.0),
+      )
+    };
+    assert_eq!(hit(&world, vec2![10., 10.]), Some(free));
+    assert_eq!(hit(&world, vec2![7., 10.]), Some(mid));
   }
 
-  // Return point in priority to line
-  maybe_selected_point
-    .or(maybe_selected_line)
-    .or(maybe_selected_circle)
-    .map(|(ent, _)| ent)
+  #[test]
+  fn test_closest_curve_between_two() {
+    let mut world = World::new();
+    world.register::<ScreenPoint>();
+    world.register::<SymbolicPoint>();
+    world.register::<ScreenLine>();
+    world.register::<ScreenCircle>();
+    let viewport = Viewport::default();
+    let mut entity_quadtree = EntityQuadtree::new();
+    let scrn_line = |y: f64| ScreenLine {
+      from: vec2![0., y].into(),
+      to: vec2![200., y].into(),
+      line_type: LineType::Segment,
+    };
+    let mut lines = vec![];
+    for y in [100., 106.] {
+      let line = world.create_entity().with(scrn_line(y)).build();
+      entity_quadtree.insert(line, QuadtreeShape::Line(scrn_line(y).to_virtual(&viewport).into()));
+      lines.push(line);
+    }
+    let scrn_circle = ScreenCircle {
+      center: vec2![250., 100.].into(),
+      radius: 50.0.into(),
+    };
+    let circle = world.create_entity().with(scrn_circle).build();
+    entity_quadtree.insert(circle, QuadtreeShape::Circle(scrn_circle.to_virtual(&viewport).into()));
+
+    let hit = |position: Vector2| {
+      hitting_object(
+        ScreenPosition(position),
+        &entity_quadtree,
+        &viewport,
+        &world.read_storage::<ScreenPoint>(),
+        &world.read_storage::<SymbolicPoint>(),
+        &world.read_storage::<ScreenLine>(),
+        &world.read_storage::<ScreenCircle>(),
+        ScreenScalar(5.0),
+      )
+    };
+
+    // Either side of the middle of the two lines picks the closer one
+    assert_eq!(hit(vec2![50., 102.9]), Some(lines[0]));
+    assert_eq!(hit(vec2![50., 103.1]), Some(lines[1]));
+
+    // Where the circle meets the end of a line the circle is closer
+    assert_eq!(hit(vec2![203., 101.]), Some(circle));
+    assert_eq!(hit(vec2![198., 101.]), Some(lines[0]));
+  }
 }
@@ -1,3 +1,13 @@
+mod fixed_timestep;
+mod hitting_freehand_stroke;
 mod hitting_object;
+mod overlay_panel;
+mod slider_track;
+mod underlay_frame;
 
+pub use fixed_timestep::*;
+pub use hitting_freehand_stroke::*;
 pub use hitting_object::*;
+pub use overlay_panel::*;
+pub use slider_track::*;
+pub use underlay_frame::*;
@@ -0,0 +1,211 @@
+use crate::resources::*;
+use core_lib::{components::markers::UnsolvableReason, events::*, math::*, resources::*, utilities::*};
+
+pub static PALETTE: [Color; 6] = [
+  rgb!(0.0, 0.0, 0.0),
+  rgb!(0.85, 0.2, 0.15),
+  rgb!(0.95, 0.55, 0.1),
+  rgb!(0.2, 0.6, 0.25),
+  rgb!(0.15, 0.4, 0.85),
+  rgb!(0.55, 0.25, 0.7),
+];
+static THICKNESS_CHOICES: [f64; 4] = [1.0, 2.0, 3.0, 5.0]; // Pixel
+static TOOLS: [(&str, Tool); 9] = [
+  ("Select", Tool::Select),
+  ("Point", Tool::Point),
+  ("Line", Tool::Line(LineType::Straight)),
+  ("Ray", Tool::Line(LineType::Ray)),
+  ("Segment", Tool::Line(LineType::Segment)),
+  ("Circle", Tool::Circle),
+  ("Polygon", Tool::Polygon),
+  ("Text", Tool::Text),
+  ("Ink", Tool::Freehand),
+];
+
+static MARGIN: f64 = 28.0; // Pixel, from the edges of the window, clear of the ruler
+static SPACING: f64 = 4.0;
+static BUTTON_WIDTH: f64 = 64.0;
+static BUTTON_HEIGHT: f64 = 24.0;
+static INSPECTOR_WIDTH: f64 = 180.0;
+static ROW_HEIGHT: f64 = 18.0;
+
+/// What a click on a widget of the overlay does, sent through the same channels as the keyboard
+/// shortcuts so that every frontend behaves the same
+#[derive(Debug, Clone)]
+pub enum OverlayAction {
+  SetTool(Tool),
+  Command(Command),
+}
+
+/// A widget of the overlay, laid out in screen space. Without an action, it only shows its text
+#[derive(Debug, Clone)]
+pub struct OverlayWidget {
+  pub rect: AABB,
+  pub text: String,
+  pub swatch: Option<Color>, // Shown instead of the text by the color pickers
+  pub active: bool,          // The button of the current tool
+  pub action: Option<OverlayAction>,
+}
+
+impl OverlayWidget {
+  fn label(rect: AABB, text: String) -> Self {
+    Self {
+      rect,
+      text,
+      swatch: None,
+      active: false,
+      action: None,
+    }
+  }
+}
+
+/// The toolbar along the top of the window with the style pickers under it, and the inspector
+/// listing the properties of the selection on the right when there is one. Laid out again every
+/// frame from the tool and the inspection, as an immediate mode UI. It is not built on egui, which
+/// has no backend for the gfx graphics of piston_window, so that any frontend can draw it
+pub fn overlay_widgets(tool: Tool, inspection: &Inspection, viewport: &Viewport) -> Vec<OverlayWidget> {
+  let mut widgets = vec![];
+  let button = |i: usize, row: usize, width: f64| {
+    AABB::new(
+      MARGIN + i as f64 * (width + SPACING),
+      MARGIN + row as f64 * (BUTTON_HEIGHT + SPACING),
+      width,
+      BUTTON_HEIGHT,
+    )
+  };
+
+  for (i, (text, button_tool)) in TOOLS.iter().enumerate() {
+    widgets.push(OverlayWidget {
+      active: *button_tool == tool,
+      action: Some(OverlayAction::SetTool(*button_tool)),
+      ..OverlayWidget::label(button(i, 0, BUTTON_WIDTH), text.to_string())
+    });
+  }
+  for (i, color) in PALETTE.iter().enumerate() {
+    widgets.push(OverlayWidget {
+      swatch: Some(*color),
+      action: Some(OverlayAction::Command(Command::Style(StyleCommand::SetColor(*color)))),
+      ..OverlayWidget::label(button(i, 1, BUTTON_HEIGHT), String::new())
+    });
+  }
+  for (i, thickness) in THICKNESS_CHOICES.iter().enumerate() {
+    // After the swatches on the same row
+    let rect = button(i, 1, BUTTON_HEIGHT * 2.);
+    let offset = PALETTE.len() as f64 * (BUTTON_HEIGHT + SPACING);
+    widgets.push(OverlayWidget {
+      action: Some(OverlayAction::Command(Command::Style(StyleCommand::SetThickness(
+        *thickness,
+      )))),
+      ..OverlayWidget::label(
+        AABB {
+          x: rect.x + offset,
+          ..rect
+        },
+        format!("{}px", thickness),
+      )
+    });
+  }
+
+  if inspection.get().is_empty() {
+    return widgets;
+  }
+  let mut rows = vec![];
+  for inspected in inspection.get() {
+    let kind = format!("{:?}", inspected.kind());
+    rows.push(match &inspected.name {
+      Some(name) => format!("{} {}", kind, name),
+      None => kind,
+    });
+    for (coordinate, value) in &inspected.coordinates {
+      rows.push(format!("  {}: {:.3}", coordinate, value));
+    }
+    match inspected.unsolvable {
+      Some(UnsolvableReason::Degenerate) => rows.push("  undefined".to_string()),
+      Some(UnsolvableReason::UndefinedParent(_)) => rows.push("  undefined, as a parent is".to_string()),
+      None => (),
+    }
+  }
+  // The rows that do not fit in the window are left out
+  let max_rows = ((viewport.screen_height() - 2. * MARGIN) / ROW_HEIGHT).max(0.) as usize;
+  rows.truncate(max_rows);
+  let x = viewport.screen_width() - MARGIN - INSPECTOR_WIDTH;
+  widgets.push(OverlayWidget::label(
+    AABB::new(x, MARGIN, INSPECTOR_WIDTH, rows.len() as f64 * ROW_HEIGHT + SPACING),
+    String::new(),
+  ));
+  for (i, row) in rows.into_iter().enumerate() {
+    let rect = AABB::new(x + SPACING, MARGIN + i as f64 * ROW_HEIGHT, INSPECTOR_WIDTH, ROW_HEIGHT);
+    widgets.push(OverlayWidget::label(rect, row));
+  }
+  widgets
+}
+
+/// Whether the position is over the overlay, the clicks there are not meant for the sketch
+pub fn is_over_overlay(widgets: &[OverlayWidget], position: ScreenPosition) -> bool {
+  widgets.iter().any(|widget| widget.rect.contains(position.0))
+}
+
+/// The action of the widget under the position, if any
+pub fn overlay_action_at(widgets: &[OverlayWidget], position: ScreenPosition) -> Option<&OverlayAction> {
+  widgets
+    .iter()
+    .filter(|widget| widget.rect.contains(position.0))
+    .find_map(|widget| widget.action.as_ref())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::components::symbolics::*;
+  use specs::prelude::*;
+
+  #[test]
+  fn test_overlay_widgets() {
+    let viewport = Viewport::default();
+    let widgets = overlay_widgets(Tool::Circle, &Inspection::default(), &viewport);
+    assert_eq!(widgets.len(), TOOLS.len() + PALETTE.len() + THICKNESS_CHOICES.len());
+    let active = widgets.iter().filter(|widget| widget.active).collect::<Vec<_>>();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].text, "Circle");
+
+    // The second button of the toolbar, then the second swatch under it
+    let center_of =
+      |widget: &OverlayWidget| ScreenPosition(widget.rect.min() + (widget.rect.max() - widget.rect.min()) / 2.);
+    let point_button = center_of(&widgets[1]);
+    assert!(matches!(
+      overlay_action_at(&widgets, point_button),
+      Some(OverlayAction::SetTool(Tool::Point))
+    ));
+    let swatch = center_of(&widgets[TOOLS.len() + 1]);
+    assert!(matches!(
+      overlay_action_at(&widgets, swatch),
+      Some(OverlayAction::Command(Command::Style(StyleCommand::SetColor(color)))) if *color == PALETTE[1]
+    ));
+
+    // Nothing is over the sketch in the middle of the window
+    let middle = ScreenPosition(vec2![viewport.screen_width() / 2., viewport.screen_height() / 2.]);
+    assert!(!is_over_overlay(&widgets, middle));
+    assert!(overlay_action_at(&widgets, middle).is_none());
+
+    // The inspector lists the selected point and its coordinates
+    let mut world = World::new();
+    let mut inspection = Inspection::default();
+    inspection.set(vec![InspectedGeometry {
+      entity: world.create_entity().build(),
+      name: Some("A".to_string()),
+      geometry: Geometry::Point(
+        SymbolicPoint::Free(vec2![1., 2.].into()),
+        DefaultPointStyle::default().get(),
+      ),
+      coordinates: vec![("x", 1.), ("y", 2.)],
+      unsolvable: None,
+    }]);
+    let widgets = overlay_widgets(Tool::Circle, &inspection, &viewport);
+    let rows = widgets
+      .iter()
+      .skip(TOOLS.len() + PALETTE.len() + THICKNESS_CHOICES.len() + 1)
+      .map(|widget| widget.text.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(rows, vec!["Point A", "  x: 1.000", "  y: 2.000"]);
+  }
+}
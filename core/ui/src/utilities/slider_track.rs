@@ -0,0 +1,82 @@
+use core_lib::{
+  components::{markers::*, sliders::*},
+  math::*,
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// The ends of the track of the slider on screen, the track keeps its length whatever the zoom
+pub fn slider_track(slider: &Slider, viewport: &Viewport) -> (ScreenPosition, ScreenPosition) {
+  let from = slider.position.to_screen(viewport);
+  (from, from + ScreenPosition(vec2![SLIDER_TRACK_LENGTH.0, 0.]))
+}
+
+/// The value of the slider under the position, projected on its track
+pub fn slider_value_at(slider: &Slider, position: ScreenPosition, viewport: &Viewport) -> f64 {
+  let (from, _) = slider_track(slider, viewport);
+  slider.value_at((position.0.x - from.0.x) / SLIDER_TRACK_LENGTH.0)
+}
+
+/// The closest visible slider whose track passes near the mouse. The sliders are not in the
+/// spatial entity map, so they are hit tested apart from the geometries
+pub fn hitting_slider<'a>(
+  mouse_pos: ScreenPosition,
+  viewport: &Viewport,
+  entities: &Entities<'a>,
+  sliders: &ReadStorage<'a, Slider>,
+  hiddens: &ReadStorage<'a, Hidden>,
+  threshold: ScreenScalar,
+) -> Option<Entity> {
+  (entities, sliders, !hiddens)
+    .join()
+    .map(|(ent, slider, _)| {
+      let (from, to) = slider_track(slider, viewport);
+      let closest = vec2![mouse_pos.0.x.max(from.0.x).min(to.0.x), from.0.y];
+      (ent, (closest - mouse_pos.0).magnitude())
+    })
+    .filter(|(_, dist)| *dist < threshold.0)
+    .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
+    .map(|(ent, _)| ent)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_hitting_slider_along_its_track() {
+    let mut world = World::new();
+    world.register::<Slider>();
+    world.register::<Hidden>();
+    let viewport = Viewport::new(vec2![0., 0.], vec2![40., 30.], vec2![400., 300.]);
+    let slider = Slider {
+      position: vec2![-10., 0.].into(),
+      min: 0.,
+      max: 4.,
+    };
+    let ent = world.create_entity().with(slider).build();
+
+    // The left end of the track is at (100, 150) on screen
+    let hit = |world: &World, x: f64, y: f64| {
+      hitting_slider(
+        ScreenPosition(vec2![x, y]),
+        &viewport,
+        &world.entities(),
+        &world.read_storage::<Slider>(),
+        &world.read_storage::<Hidden>(),
+        ScreenScalar(5.0),
+      )
+    };
+    assert_eq!(hit(&world, 180., 153.), Some(ent));
+    assert_eq!(hit(&world, 262., 150.), Some(ent));
+    assert_eq!(hit(&world, 180., 160.), None);
+    assert_eq!(hit(&world, 96., 150.), Some(ent));
+    assert_eq!(hit(&world, 90., 150.), None);
+    assert_eq!(slider_value_at(&slider, ScreenPosition(vec2![140., 0.]), &viewport), 1.);
+    assert_eq!(slider_value_at(&slider, ScreenPosition(vec2![300., 0.]), &viewport), 4.);
+
+    world.write_storage::<Hidden>().insert(ent, Hidden).unwrap();
+    assert_eq!(hit(&world, 180., 150.), None);
+  }
+}
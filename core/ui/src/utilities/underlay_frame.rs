@@ -0,0 +1,100 @@
+use core_lib::{
+  components::{markers::*, underlays::*},
+  resources::*,
+  utilities::*,
+};
+use specs::prelude::*;
+
+/// Side of the square handles drawn on the corners of the underlays
+pub static UNDERLAY_HANDLE_SIZE: ScreenScalar = ScreenScalar(8.0); // Pixel
+
+/// The two corners of an underlay that can be dragged
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnderlayHandle {
+  Move,  // Top left corner, moves the whole image
+  Scale, // Bottom right corner, scales the image keeping its aspect ratio
+}
+
+/// The top left and the bottom right corners of the underlay on screen
+pub fn underlay_frame(
+  underlay: &ImageUnderlay,
+  placement: &UnderlayPlacement,
+  viewport: &Viewport,
+) -> (ScreenPosition, ScreenPosition) {
+  (
+    placement.position.to_screen(viewport),
+    placement.bottom_right(underlay).to_screen(viewport),
+  )
+}
+
+/// The visible underlay with a handle under the mouse, and which one. The underlays are not in the
+/// spatial entity map, they are only hit by their handles so that the geometries on top of them
+/// can still be clicked
+pub fn hitting_underlay_handle<'a>(
+  mouse_pos: ScreenPosition,
+  viewport: &Viewport,
+  entities: &Entities<'a>,
+  underlays: &ReadStorage<'a, ImageUnderlay>,
+  underlay_placements: &ReadStorage<'a, UnderlayPlacement>,
+  hiddens: &ReadStorage<'a, Hidden>,
+  threshold: ScreenScalar,
+) -> Option<(Entity, UnderlayHandle)> {
+  (entities, underlays, underlay_placements, !hiddens)
+    .join()
+    .flat_map(|(ent, underlay, placement, _)| {
+      let (top_left, bottom_right) = underlay_frame(underlay, placement, viewport);
+      vec![
+        (ent, UnderlayHandle::Move, top_left),
+        (ent, UnderlayHandle::Scale, bottom_right),
+      ]
+    })
+    .map(|(ent, handle, corner)| {
+      let offset = corner.0 - mouse_pos.0;
+      (ent, handle, offset.x.abs().max(offset.y.abs()))
+    })
+    .filter(|(_, _, dist)| *dist < threshold.0)
+    .min_by(|(_, _, d1), (_, _, d2)| d1.partial_cmp(d2).unwrap())
+    .map(|(ent, handle, _)| (ent, handle))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use core_lib::math::*;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_hitting_the_corners_of_an_underlay() {
+    let mut world = World::new();
+    world.register::<ImageUnderlay>();
+    world.register::<UnderlayPlacement>();
+    world.register::<Hidden>();
+    let viewport = Viewport::new(vec2![0., 0.], vec2![40., 30.], vec2![400., 300.]);
+    let underlay = ImageUnderlay {
+      path: PathBuf::from("diagram.png"),
+      size: vec2![100., 50.],
+    };
+    let placement = UnderlayPlacement::centered_at(&underlay, vec2![0., 0.].into(), 0.1);
+    let ent = world.create_entity().with(underlay).with(placement).build();
+
+    // The image spans from (150, 125) to (250, 175) on screen
+    let hit = |world: &World, x: f64, y: f64| {
+      hitting_underlay_handle(
+        ScreenPosition(vec2![x, y]),
+        &viewport,
+        &world.entities(),
+        &world.read_storage::<ImageUnderlay>(),
+        &world.read_storage::<UnderlayPlacement>(),
+        &world.read_storage::<Hidden>(),
+        ScreenScalar(5.0),
+      )
+    };
+    assert_eq!(hit(&world, 153., 122.), Some((ent, UnderlayHandle::Move)));
+    assert_eq!(hit(&world, 248., 176.), Some((ent, UnderlayHandle::Scale)));
+    assert_eq!(hit(&world, 200., 150.), None);
+    assert_eq!(hit(&world, 250., 125.), None);
+
+    world.write_storage::<Hidden>().insert(ent, Hidden).unwrap();
+    assert_eq!(hit(&world, 150., 125.), None);
+  }
+}